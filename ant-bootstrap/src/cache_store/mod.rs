@@ -48,9 +48,13 @@ impl BootstrapCacheStore {
             })?;
         }
 
+        let data = CacheDataLatest {
+            network_version: crate::get_network_version_for(config.network_id),
+            ..Default::default()
+        };
         let store = Self {
             config: Arc::new(config),
-            data: Arc::new(RwLock::new(CacheDataLatest::default())),
+            data: Arc::new(RwLock::new(data)),
         };
 
         Ok(store)
@@ -104,7 +108,7 @@ impl BootstrapCacheStore {
         // try loading latest first
         match cache_data_v1::CacheData::read_from_file(
             &cfg.cache_dir,
-            &Self::cache_file_name(cfg.local),
+            &Self::cache_file_name_for_network(cfg.local, cfg.network_id),
         ) {
             Ok(mut data) => {
                 while data.peers.len() > cfg.max_peers {
@@ -120,7 +124,7 @@ impl BootstrapCacheStore {
         // Try loading older version
         match cache_data_v0::CacheData::read_from_file(
             &cfg.cache_dir,
-            &Self::cache_file_name(cfg.local),
+            &Self::cache_file_name_for_network(cfg.local, cfg.network_id),
         ) {
             Ok(data) => {
                 warn!("Loaded cache data from older version, upgrading to latest version");
@@ -178,7 +182,7 @@ impl BootstrapCacheStore {
             return Ok(());
         }
 
-        let filename = Self::cache_file_name(self.config.local);
+        let filename = Self::cache_file_name_for_network(self.config.local, self.config.network_id);
 
         self.data
             .write()
@@ -196,13 +200,16 @@ impl BootstrapCacheStore {
 
     /// Returns the name of the cache filename based on the local flag
     pub fn cache_file_name(local: bool) -> String {
+        Self::cache_file_name_for_network(local, ant_protocol::version::get_network_id())
+    }
+
+    /// Returns the name of the cache filename of the given network, based on the local flag
+    pub fn cache_file_name_for_network(local: bool, network_id: u8) -> String {
+        let network_version = crate::get_network_version_for(network_id);
         if local {
-            format!(
-                "bootstrap_cache_local_{}.json",
-                crate::get_network_version()
-            )
+            format!("bootstrap_cache_local_{network_version}.json")
         } else {
-            format!("bootstrap_cache_{}.json", crate::get_network_version())
+            format!("bootstrap_cache_{network_version}.json")
         }
     }
 
@@ -246,7 +253,7 @@ fn duration_with_variance(duration: Duration, variance: u32) -> Duration {
     let variance = duration.as_secs() as f64 * (variance as f64 / 100.0);
 
     let random_adjustment = Duration::from_secs(rand::thread_rng().gen_range(0..variance as u64));
-    if random_adjustment.as_secs() % 2 == 0 {
+    if random_adjustment.as_secs().is_multiple_of(2) {
         duration - random_adjustment
     } else {
         duration + random_adjustment
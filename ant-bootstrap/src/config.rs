@@ -10,6 +10,7 @@ use crate::{
     InitialPeersConfig,
     error::{Error, Result},
 };
+use ant_protocol::version::get_network_id;
 use std::{
     path::{Path, PathBuf},
     time::Duration,
@@ -54,6 +55,8 @@ pub struct BootstrapCacheConfig {
     pub max_addrs_per_peer: usize,
     /// The min time duration until we save the bootstrap cache to disk.
     pub min_cache_save_duration: Duration,
+    /// The network the cached peers belong to. Defaults to the process-wide network ID.
+    pub network_id: u8,
 }
 
 impl TryFrom<&InitialPeersConfig> for BootstrapCacheConfig {
@@ -94,6 +97,7 @@ impl BootstrapCacheConfig {
             min_cache_save_duration: MIN_BOOTSTRAP_CACHE_SAVE_INTERVAL,
             max_cache_save_duration: MAX_BOOTSTRAP_CACHE_SAVE_INTERVAL,
             cache_save_scaling_factor: 2,
+            network_id: get_network_id(),
         }
     }
 
@@ -133,6 +137,12 @@ impl BootstrapCacheConfig {
         self
     }
 
+    /// Sets the network the cached peers belong to
+    pub fn with_network_id(mut self, network_id: u8) -> Self {
+        self.network_id = network_id;
+        self
+    }
+
     /// Sets the flag to disable writing to the cache file
     pub fn with_disable_cache_writing(mut self, disable: bool) -> Self {
         self.disable_cache_writing = disable;
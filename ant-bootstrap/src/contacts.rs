@@ -9,6 +9,7 @@
 use crate::{
     Error, Result, cache_store::CACHE_DATA_VERSION_LATEST, craft_valid_multiaddr_from_str,
 };
+use ant_protocol::version::get_network_id;
 use futures::stream::{self, StreamExt};
 use libp2p::Multiaddr;
 use reqwest::Client;
@@ -50,6 +51,8 @@ pub struct ContactsFetcher {
     request_client: Client,
     /// Ignore PeerId in the multiaddr if not present. This is only useful for fetching nat detection contacts
     ignore_peer_id: bool,
+    /// The network the fetched bootstrap caches must belong to
+    network_id: u8,
 }
 
 impl ContactsFetcher {
@@ -69,6 +72,7 @@ impl ContactsFetcher {
             endpoints,
             request_client,
             ignore_peer_id: false,
+            network_id: get_network_id(),
         })
     }
 
@@ -109,6 +113,12 @@ impl ContactsFetcher {
         self.ignore_peer_id = ignore_peer_id;
     }
 
+    /// Set the network the fetched bootstrap caches must belong to. Defaults to the process-wide
+    /// network ID.
+    pub fn set_network_id(&mut self, network_id: u8) {
+        self.network_id = network_id;
+    }
+
     /// Fetch the list of bootstrap addresses from all configured endpoints
     pub async fn fetch_bootstrap_addresses(&self) -> Result<Vec<Multiaddr>> {
         Ok(self.fetch_addrs().await?.into_iter().collect())
@@ -134,6 +144,7 @@ impl ContactsFetcher {
                         self.request_client.clone(),
                         &endpoint,
                         self.ignore_peer_id,
+                        self.network_id,
                     )
                     .await,
                     endpoint,
@@ -184,6 +195,7 @@ impl ContactsFetcher {
         request_client: Client,
         endpoint: &Url,
         ignore_peer_id: bool,
+        network_id: u8,
     ) -> Result<Vec<Multiaddr>> {
         let mut retries = 0;
 
@@ -199,7 +211,7 @@ impl ContactsFetcher {
                     if response.status().is_success() {
                         let text = response.text().await?;
 
-                        match Self::try_parse_response(&text, ignore_peer_id) {
+                        match Self::try_parse_response(&text, ignore_peer_id, network_id) {
                             Ok(addrs) => break addrs,
                             Err(err) => {
                                 warn!("Failed to parse response with err: {err:?}");
@@ -244,7 +256,11 @@ impl ContactsFetcher {
     }
 
     /// Try to parse a response from an endpoint
-    fn try_parse_response(response: &str, ignore_peer_id: bool) -> Result<Vec<Multiaddr>> {
+    fn try_parse_response(
+        response: &str,
+        ignore_peer_id: bool,
+        network_id: u8,
+    ) -> Result<Vec<Multiaddr>> {
         let cache_data = if let Ok(data) =
             serde_json::from_str::<super::cache_store::cache_data_v1::CacheData>(response)
         {
@@ -263,7 +279,7 @@ impl ContactsFetcher {
                     "Successfully parsed JSON response with {} peers",
                     cache_data.peers.len()
                 );
-                let our_network_version = crate::get_network_version();
+                let our_network_version = crate::get_network_version_for(network_id);
 
                 if cache_data.network_version != our_network_version {
                     warn!(
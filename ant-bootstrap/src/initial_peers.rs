@@ -73,8 +73,18 @@ pub struct InitialPeersConfig {
 }
 
 impl InitialPeersConfig {
-    /// Get bootstrap peers
+    /// Get bootstrap peers of the network set with the process-wide network ID
     pub async fn get_bootstrap_addr(&self, count: Option<usize>) -> Result<Vec<Multiaddr>> {
+        self.get_bootstrap_addr_for_network(count, get_network_id())
+            .await
+    }
+
+    /// Get bootstrap peers of the given network
+    pub async fn get_bootstrap_addr_for_network(
+        &self,
+        count: Option<usize>,
+        network_id: u8,
+    ) -> Result<Vec<Multiaddr>> {
         // If this is the first node, return an empty list
         if self.first {
             info!("First node in network, no initial bootstrap peers");
@@ -121,7 +131,8 @@ impl InitialPeersConfig {
                 .inspect_err(|err| {
                     error!("Failed to create bootstrap cache config: {err}");
                 })
-                .ok();
+                .ok()
+                .map(|cfg| cfg.with_network_id(network_id));
 
             if let Some(cfg) = cfg {
                 if let Ok(data) = BootstrapCacheStore::load_cache_data(&cfg) {
@@ -157,6 +168,7 @@ impl InitialPeersConfig {
                 .map(|url| url.parse::<Url>().map_err(|_| Error::FailedToParseUrl))
                 .collect::<Result<Vec<Url>>>()?;
             let mut contacts_fetcher = ContactsFetcher::with_endpoints(addrs)?;
+            contacts_fetcher.set_network_id(network_id);
             if let Some(count) = count {
                 contacts_fetcher.set_max_addrs(count);
             }
@@ -175,16 +187,18 @@ impl InitialPeersConfig {
             }
         }
 
-        if !self.local && get_network_id() == MAINNET_ID {
+        if !self.local && network_id == MAINNET_ID {
             let mut contacts_fetcher = ContactsFetcher::with_mainnet_endpoints()?;
+            contacts_fetcher.set_network_id(network_id);
             if let Some(count) = count {
                 contacts_fetcher.set_max_addrs(count);
             }
             info!("Fetching bootstrap address from mainnet contacts");
             let addrs = contacts_fetcher.fetch_bootstrap_addresses().await?;
             bootstrap_addresses.extend(addrs);
-        } else if !self.local && get_network_id() == ALPHANET_ID {
+        } else if !self.local && network_id == ALPHANET_ID {
             let mut contacts_fetcher = ContactsFetcher::with_alphanet_endpoints()?;
+            contacts_fetcher.set_network_id(network_id);
             if let Some(count) = count {
                 contacts_fetcher.set_max_addrs(count);
             }
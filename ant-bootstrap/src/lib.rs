@@ -31,7 +31,7 @@ pub mod contacts;
 pub mod error;
 mod initial_peers;
 
-use ant_protocol::version::{get_network_id_str, get_truncate_version_str};
+use ant_protocol::version::{get_network_id, get_truncate_version_str};
 use libp2p::{Multiaddr, PeerId, multiaddr::Protocol};
 use thiserror::Error;
 
@@ -111,5 +111,10 @@ pub fn multiaddr_get_peer_id(addr: &Multiaddr) -> Option<PeerId> {
 }
 
 pub fn get_network_version() -> String {
-    format!("{}_{}", get_network_id_str(), get_truncate_version_str())
+    get_network_version_for(get_network_id())
+}
+
+/// The network version of the given network, used to keep the caches of different networks apart.
+pub fn get_network_version_for(network_id: u8) -> String {
+    format!("{network_id}_{}", get_truncate_version_str())
 }
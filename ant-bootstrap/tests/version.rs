@@ -138,3 +138,52 @@ async fn test_version_specific_file_paths() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_caches_of_different_networks_are_kept_apart() -> Result<()> {
+    let _guard = LogBuilder::init_single_threaded_tokio_test();
+    let temp_dir = TempDir::new()?;
+    let cache_dir = temp_dir.path();
+
+    let mainnet_store = BootstrapCacheStore::new(
+        BootstrapCacheConfig::empty()
+            .with_cache_dir(cache_dir)
+            .with_network_id(1),
+    )?;
+    let testnet_store = BootstrapCacheStore::new(
+        BootstrapCacheConfig::empty()
+            .with_cache_dir(cache_dir)
+            .with_network_id(5),
+    )?;
+    let mainnet_addr: Multiaddr =
+        "/ip4/127.0.0.1/udp/8080/quic-v1/p2p/12D3KooWRBhwfeP2Y4TCx1SM6s9rUoHhR5STiGwxBhgFRcw3UERE"
+            .parse()?;
+    let testnet_addr: Multiaddr =
+        "/ip4/127.0.0.2/udp/8080/quic-v1/p2p/12D3KooWD2aV1f3qkhggzEFaJ24CEFYkSdZF5RKoMLpU6CwExYV5"
+            .parse()?;
+    mainnet_store.add_addr(mainnet_addr.clone()).await;
+    testnet_store.add_addr(testnet_addr.clone()).await;
+    mainnet_store.write().await?;
+    testnet_store.write().await?;
+
+    assert_ne!(
+        BootstrapCacheStore::cache_file_name_for_network(false, 1),
+        BootstrapCacheStore::cache_file_name_for_network(false, 5)
+    );
+    let testnet_data = BootstrapCacheStore::load_cache_data(testnet_store.config())?;
+    assert_eq!(
+        testnet_data.get_all_addrs().cloned().collect::<Vec<_>>(),
+        vec![testnet_addr]
+    );
+    assert_eq!(
+        testnet_data.network_version,
+        ant_bootstrap::get_network_version_for(5)
+    );
+    let mainnet_data = BootstrapCacheStore::load_cache_data(mainnet_store.config())?;
+    assert_eq!(
+        mainnet_data.get_all_addrs().cloned().collect::<Vec<_>>(),
+        vec![mainnet_addr]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,358 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{NetworkContext, connect_to_network_with_config};
+use crate::args::max_fee_per_gas::{MaxFeePerGasParam, get_max_fee_per_gas_from_opt_param};
+use crate::exit_code::{self, ExitCodeError};
+use crate::wallet::load_wallet;
+use autonomi::chunk::DataMapChunk;
+use autonomi::client::GetError;
+use autonomi::client::analyze::{AnalysisError, DataMapContent};
+use autonomi::client::files::archive_private::PrivateArchiveDataMap;
+use autonomi::client::register::{RegisterAddress, RegisterValue};
+use autonomi::client::scratchpad::ScratchpadError;
+use autonomi::data::DataAddress;
+use autonomi::files::PrivateArchive;
+use autonomi::{
+    AttoTokens, Bytes, Client, ClientOperatingStrategy, PublicKey, Scratchpad, ScratchpadAddress,
+    SecretKey, TransactionConfig, Wallet,
+};
+use color_eyre::eyre::{Report, Result, eyre};
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use autonomi::daemon::{DaemonClient, DaemonError, RemoteError};
+
+/// A client connected to the network, either through the daemon or within this process.
+#[allow(clippy::large_enum_variant)]
+pub enum ClientHandle {
+    Local(Client),
+    #[cfg(unix)]
+    Daemon(DaemonClient),
+}
+
+/// Errors of [`ClientHandle::scratchpad_get`], a fork is reported apart so it can be analysed.
+pub enum ScratchpadGetError {
+    Fork(Vec<Scratchpad>),
+    Other(Report),
+}
+
+/// Errors of [`ClientHandle::data_get_public`], data too large to be fetched in memory is
+/// reported apart so it can be downloaded to a file instead.
+pub enum DataGetError {
+    TooLargeForMemory,
+    Other(ExitCodeError),
+}
+
+/// Use the daemon of the network if one is running, otherwise connect within this process.
+///
+/// The daemon pays with its own wallet and max fee per gas, so commands given a max fee per gas
+/// don't allow it.
+pub async fn connect_to_client(
+    network_context: NetworkContext,
+    allow_daemon: bool,
+) -> Result<ClientHandle, ExitCodeError> {
+    connect_to_client_with_config(network_context, Default::default(), allow_daemon).await
+}
+
+/// Same as [`connect_to_client`], the config only applies when connecting within this process.
+pub async fn connect_to_client_with_config(
+    network_context: NetworkContext,
+    operating_strategy: ClientOperatingStrategy,
+    allow_daemon: bool,
+) -> Result<ClientHandle, ExitCodeError> {
+    #[cfg(unix)]
+    if allow_daemon
+        && let Some(daemon) = super::connect_to_daemon(&network_context.network_id).await
+    {
+        eprintln!("Using the client daemon");
+        return Ok(ClientHandle::Daemon(daemon));
+    }
+    #[cfg(not(unix))]
+    let _ = allow_daemon;
+    connect_to_network_with_config(network_context, operating_strategy)
+        .await
+        .map(ClientHandle::Local)
+}
+
+impl ClientHandle {
+    pub async fn file_cost(&mut self, path: &Path) -> Result<AttoTokens> {
+        match self {
+            Self::Local(client) => Ok(client.file_cost(&path.to_path_buf()).await?),
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon.file_cost(path).await?),
+        }
+    }
+
+    /// Returns the rendered analysis, `None` if the address wasn't recognized.
+    pub async fn analyze_address(
+        &mut self,
+        address: &str,
+        verbose: bool,
+    ) -> Result<Option<String>> {
+        match self {
+            Self::Local(client) => match client.analyze_address(address, verbose).await {
+                Ok(analysis) => Ok(Some(analysis.to_string())),
+                Err(AnalysisError::UnrecognizedInput) => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+            #[cfg(unix)]
+            Self::Daemon(daemon) => match daemon.analyze_address(address, verbose).await {
+                Ok(analysis) => Ok(Some(analysis)),
+                Err(DaemonError::Remote(RemoteError::UnrecognizedAddress)) => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+        }
+    }
+
+    /// Find out what a data map points to.
+    pub async fn data_map_content(
+        &mut self,
+        data_map: &DataMapChunk,
+    ) -> Result<DataMapContent, ExitCodeError> {
+        match self {
+            Self::Local(client) => match client.analyze_address(&data_map.to_hex(), true).await {
+                Ok(analysis) => DataMapContent::try_from(analysis).map_err(|other| {
+                    (
+                        eyre!("Unexpected data type found: {other}"),
+                        exit_code::INVALID_INPUT_EXIT_CODE,
+                    )
+                }),
+                Err(err) => {
+                    let exit_code = exit_code::analysis_exit_code(&err);
+                    Err((eyre!(err), exit_code))
+                }
+            },
+            #[cfg(unix)]
+            Self::Daemon(daemon) => daemon.data_map_content(data_map).await.map_err(daemon_err),
+        }
+    }
+
+    pub async fn data_get_public(&mut self, addr: &DataAddress) -> Result<Bytes, DataGetError> {
+        match self {
+            Self::Local(client) => match client.data_get_public(addr).await {
+                Ok(data) => Ok(data),
+                Err(GetError::TooLargeForMemory) => Err(DataGetError::TooLargeForMemory),
+                Err(err) => {
+                    let exit_code = exit_code::get_error_exit_code(&err);
+                    Err(DataGetError::Other((eyre!(err), exit_code)))
+                }
+            },
+            #[cfg(unix)]
+            Self::Daemon(daemon) => match daemon.data_get_public(addr).await {
+                Ok(data) => Ok(data),
+                Err(DaemonError::Remote(RemoteError::TooLargeForMemory)) => {
+                    Err(DataGetError::TooLargeForMemory)
+                }
+                Err(err) => Err(DataGetError::Other(daemon_err(err))),
+            },
+        }
+    }
+
+    pub async fn archive_get(
+        &mut self,
+        addr: &PrivateArchiveDataMap,
+    ) -> Result<PrivateArchive, ExitCodeError> {
+        match self {
+            Self::Local(client) => client.archive_get(addr).await.map_err(|err| {
+                let exit_code = exit_code::get_error_exit_code(&err);
+                (eyre!(err), exit_code)
+            }),
+            #[cfg(unix)]
+            Self::Daemon(daemon) => {
+                let data = daemon.data_get(addr).await.map_err(daemon_err)?;
+                PrivateArchive::from_bytes(data).map_err(|err| {
+                    (
+                        eyre!(err).wrap_err("Failed to deserialize the private archive"),
+                        exit_code::SERIALIZATION_ERROR,
+                    )
+                })
+            }
+        }
+    }
+
+    pub async fn file_download(
+        &mut self,
+        data_map: &DataMapChunk,
+        to_dest: PathBuf,
+    ) -> Result<(), ExitCodeError> {
+        match self {
+            Self::Local(client) => client
+                .file_download(data_map, to_dest)
+                .await
+                .map_err(|err| {
+                    let exit_code = exit_code::get_download_error_exit_code(&err);
+                    (eyre!(err), exit_code)
+                }),
+            #[cfg(unix)]
+            Self::Daemon(daemon) => daemon
+                .file_download(data_map, &to_dest)
+                .await
+                .map_err(daemon_err),
+        }
+    }
+
+    pub async fn file_download_public(
+        &mut self,
+        addr: &DataAddress,
+        to_dest: PathBuf,
+    ) -> Result<(), ExitCodeError> {
+        match self {
+            Self::Local(client) => {
+                client
+                    .file_download_public(addr, to_dest)
+                    .await
+                    .map_err(|err| {
+                        let exit_code = exit_code::get_download_error_exit_code(&err);
+                        (eyre!(err), exit_code)
+                    })
+            }
+            #[cfg(unix)]
+            Self::Daemon(daemon) => daemon
+                .file_download_public(addr, &to_dest)
+                .await
+                .map_err(daemon_err),
+        }
+    }
+
+    pub async fn register_cost(&mut self, owner: &PublicKey) -> Result<AttoTokens> {
+        match self {
+            Self::Local(client) => Ok(client.register_cost(owner).await?),
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon.register_cost(owner).await?),
+        }
+    }
+
+    pub async fn register_get(&mut self, addr: &RegisterAddress) -> Result<RegisterValue> {
+        match self {
+            Self::Local(client) => Ok(client.register_get(addr).await?),
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon.register_get(addr).await?),
+        }
+    }
+
+    pub async fn register_create(
+        &mut self,
+        owner: &SecretKey,
+        value: RegisterValue,
+        max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+    ) -> Result<(AttoTokens, RegisterAddress)> {
+        match self {
+            Self::Local(client) => {
+                let wallet = local_wallet(client, max_fee_per_gas_param)?;
+                Ok(client.register_create(owner, value, wallet.into()).await?)
+            }
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon.register_create(owner, value).await?),
+        }
+    }
+
+    pub async fn register_update(
+        &mut self,
+        owner: &SecretKey,
+        value: RegisterValue,
+        max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+    ) -> Result<AttoTokens> {
+        match self {
+            Self::Local(client) => {
+                let wallet = local_wallet(client, max_fee_per_gas_param)?;
+                Ok(client.register_update(owner, value, wallet.into()).await?)
+            }
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon.register_update(owner, value).await?),
+        }
+    }
+
+    pub async fn scratchpad_cost(&mut self, owner: &PublicKey) -> Result<AttoTokens> {
+        match self {
+            Self::Local(client) => Ok(client.scratchpad_cost(owner).await?),
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon.scratchpad_cost(owner).await?),
+        }
+    }
+
+    pub async fn scratchpad_get(
+        &mut self,
+        addr: &ScratchpadAddress,
+    ) -> Result<Scratchpad, ScratchpadGetError> {
+        match self {
+            Self::Local(client) => match client.scratchpad_get(addr).await {
+                Ok(scratchpad) => Ok(scratchpad),
+                Err(ScratchpadError::Fork(conflicting)) => {
+                    Err(ScratchpadGetError::Fork(conflicting))
+                }
+                Err(err) => Err(ScratchpadGetError::Other(err.into())),
+            },
+            #[cfg(unix)]
+            Self::Daemon(daemon) => match daemon.scratchpad_get(addr).await {
+                Ok(scratchpad) => Ok(scratchpad),
+                Err(DaemonError::Remote(RemoteError::ScratchpadFork(conflicting))) => {
+                    Err(ScratchpadGetError::Fork(conflicting))
+                }
+                Err(err) => Err(ScratchpadGetError::Other(err.into())),
+            },
+        }
+    }
+
+    pub async fn scratchpad_create(
+        &mut self,
+        owner: &SecretKey,
+        content_type: u64,
+        initial_data: &Bytes,
+        max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+    ) -> Result<(AttoTokens, ScratchpadAddress)> {
+        match self {
+            Self::Local(client) => {
+                let wallet = local_wallet(client, max_fee_per_gas_param)?;
+                Ok(client
+                    .scratchpad_create(owner, content_type, initial_data, wallet.into())
+                    .await?)
+            }
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon
+                .scratchpad_create(owner, content_type, initial_data)
+                .await?),
+        }
+    }
+
+    pub async fn scratchpad_update_from(
+        &mut self,
+        current: &Scratchpad,
+        owner: &SecretKey,
+        content_type: u64,
+        data: &Bytes,
+    ) -> Result<Scratchpad> {
+        match self {
+            Self::Local(client) => Ok(client
+                .scratchpad_update_from(current, owner, content_type, data)
+                .await?),
+            #[cfg(unix)]
+            Self::Daemon(daemon) => Ok(daemon
+                .scratchpad_update_from(current, owner, content_type, data)
+                .await?),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn daemon_err(err: DaemonError) -> ExitCodeError {
+    let exit_code = exit_code::daemon_error_exit_code(&err);
+    (eyre!(err), exit_code)
+}
+
+/// The wallet paying for operations done within this process.
+fn local_wallet(
+    client: &Client,
+    max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+) -> Result<Wallet> {
+    let mut wallet = load_wallet(client.evm_network())?;
+    let max_fee_per_gas =
+        get_max_fee_per_gas_from_opt_param(max_fee_per_gas_param, client.evm_network())?;
+    wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
+    Ok(wallet)
+}
@@ -0,0 +1,49 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::opt::NetworkId;
+use autonomi::daemon::DaemonClient;
+use color_eyre::eyre::Result;
+use std::path::PathBuf;
+
+/// The socket of the daemon connected to the network, there can be one daemon per network.
+pub fn daemon_socket_path(network_id: &NetworkId) -> Result<PathBuf> {
+    let dir = crate::access::data_dir::get_client_data_dir_path()?;
+    Ok(dir.join(format!("daemon-{}.sock", network_id.as_u8())))
+}
+
+/// Where a daemon started in the background writes its output.
+pub fn daemon_log_path(network_id: &NetworkId) -> Result<PathBuf> {
+    let dir = crate::access::data_dir::get_client_data_dir_path()?;
+    Ok(dir.join(format!("daemon-{}.log", network_id.as_u8())))
+}
+
+/// Connect to the daemon of the network if one is running.
+pub async fn connect_to_daemon(network_id: &NetworkId) -> Option<DaemonClient> {
+    let socket_path = daemon_socket_path(network_id).ok()?;
+    if !socket_path.exists() {
+        return None;
+    }
+    let mut daemon = match DaemonClient::connect(&socket_path).await {
+        Ok(daemon) => daemon,
+        Err(err) => {
+            info!("No daemon listening on {socket_path:?}: {err}");
+            return None;
+        }
+    };
+    match daemon.status().await {
+        Ok(status) => {
+            info!("Using the client daemon on {socket_path:?}: {status:?}");
+            Some(daemon)
+        }
+        Err(err) => {
+            warn!("The daemon on {socket_path:?} didn't answer: {err}");
+            None
+        }
+    }
+}
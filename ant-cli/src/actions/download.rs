@@ -6,19 +6,23 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::client::{ClientHandle, DataGetError};
 use super::get_progress_bar;
-use crate::exit_code::{self, ExitCodeError, INVALID_INPUT_EXIT_CODE, IO_ERROR};
+use crate::exit_code::{ExitCodeError, INVALID_INPUT_EXIT_CODE, IO_ERROR};
 use autonomi::{
-    Client,
     chunk::DataMapChunk,
-    client::{GetError, analyze::Analysis, files::archive_private::PrivateArchiveDataMap},
+    client::{analyze::DataMapContent, files::archive_private::PrivateArchiveDataMap},
     data::DataAddress,
     files::{PrivateArchive, PublicArchive},
 };
 use color_eyre::{Section, eyre::eyre};
 use std::path::PathBuf;
 
-pub async fn download(addr: &str, dest_path: &str, client: &Client) -> Result<(), ExitCodeError> {
+pub async fn download(
+    addr: &str,
+    dest_path: &str,
+    client: &mut ClientHandle,
+) -> Result<(), ExitCodeError> {
     let try_public_address = DataAddress::from_hex(addr).ok();
     if let Some(public_address) = try_public_address {
         println!("Input supplied was a public address");
@@ -56,15 +60,17 @@ async fn download_private(
     addr: &str,
     private_address: PrivateArchiveDataMap,
     dest_path: &str,
-    client: &Client,
+    client: &mut ClientHandle,
 ) -> Result<(), ExitCodeError> {
-    let archive = client.archive_get(&private_address).await.map_err(|e| {
-        let exit_code = exit_code::get_error_exit_code(&e);
-        (
-            eyre!(e).wrap_err("Failed to fetch Private Archive from address"),
-            exit_code,
-        )
-    })?;
+    let archive = client
+        .archive_get(&private_address)
+        .await
+        .map_err(|(e, exit_code)| {
+            (
+                e.wrap_err("Failed to fetch Private Archive from address"),
+                exit_code,
+            )
+        })?;
 
     download_priv_archive_to_disk(addr, archive, dest_path, client).await
 }
@@ -73,7 +79,7 @@ async fn download_priv_archive_to_disk(
     addr: &str,
     archive: PrivateArchive,
     dest_path: &str,
-    client: &Client,
+    client: &mut ClientHandle,
 ) -> Result<(), ExitCodeError> {
     let progress_bar = get_progress_bar(archive.iter().count() as u64).ok();
     let mut all_errs = vec![];
@@ -88,10 +94,10 @@ async fn download_priv_archive_to_disk(
         let parent = path.parent().unwrap_or_else(|| &here);
         std::fs::create_dir_all(parent).map_err(|err| (err.into(), IO_ERROR))?;
 
-        if let Err(e) = client.file_download(access, path.clone()).await {
+        if let Err((e, exit_code)) = client.file_download(access, path.clone()).await {
             let err = format!("Failed to fetch file {path:?}: {e}");
             all_errs.push(err);
-            last_error = Some(e);
+            last_error = Some(exit_code);
             continue;
         }
 
@@ -104,8 +110,7 @@ async fn download_priv_archive_to_disk(
     }
 
     match last_error {
-        Some(e) => {
-            let exit_code = exit_code::get_download_error_exit_code(&e);
+        Some(exit_code) => {
             let err_no = all_errs.len();
             eprintln!("{err_no} errors while downloading private data with local address: {addr}");
             eprintln!("{all_errs:#?}");
@@ -126,7 +131,7 @@ async fn download_public(
     addr: &str,
     address: DataAddress,
     dest_path: &str,
-    client: &Client,
+    client: &mut ClientHandle,
 ) -> Result<(), ExitCodeError> {
     let path = PathBuf::from(dest_path);
     let here = PathBuf::from(".");
@@ -135,28 +140,20 @@ async fn download_public(
 
     let data = match client.data_get_public(&address).await {
         Ok(data) => data,
-        Err(GetError::TooLargeForMemory) => {
+        Err(DataGetError::TooLargeForMemory) => {
             println!("Detected large file at: {addr}, downloading via streaming");
             info!("Detected large file at: {addr}, downloading via streaming");
             client
                 .file_download_public(&address, path)
                 .await
-                .map_err(|e| {
-                    let exit_code = exit_code::get_download_error_exit_code(&e);
-                    (
-                        eyre!(e).wrap_err("Failed to fetch data from address"),
-                        exit_code,
-                    )
+                .map_err(|(e, exit_code)| {
+                    (e.wrap_err("Failed to fetch data from address"), exit_code)
                 })?;
             println!("Successfully downloaded file at: {addr}");
             return Ok(());
         }
-        Err(e) => {
-            let exit_code = exit_code::get_error_exit_code(&e);
-            return Err((
-                eyre!(e).wrap_err("Failed to fetch data from address"),
-                exit_code,
-            ));
+        Err(DataGetError::Other((e, exit_code))) => {
+            return Err((e.wrap_err("Failed to fetch data from address"), exit_code));
         }
     };
 
@@ -184,7 +181,7 @@ async fn download_pub_archive_to_disk(
     addr: &str,
     archive: PublicArchive,
     dest_path: &str,
-    client: &Client,
+    client: &mut ClientHandle,
 ) -> Result<(), ExitCodeError> {
     let progress_bar = get_progress_bar(archive.iter().count() as u64).ok();
     let mut all_errs = vec![];
@@ -199,10 +196,10 @@ async fn download_pub_archive_to_disk(
         let parent = path.parent().unwrap_or_else(|| &here);
         std::fs::create_dir_all(parent).map_err(|err| (err.into(), IO_ERROR))?;
 
-        if let Err(e) = client.file_download_public(addr, path.clone()).await {
+        if let Err((e, exit_code)) = client.file_download_public(addr, path.clone()).await {
             let err = format!("Failed to fetch file {path:?}: {e}");
             all_errs.push(err);
-            last_error = Some(e);
+            last_error = Some(exit_code);
             continue;
         };
 
@@ -215,8 +212,7 @@ async fn download_pub_archive_to_disk(
     }
 
     match last_error {
-        Some(e) => {
-            let exit_code = exit_code::get_download_error_exit_code(&e);
+        Some(exit_code) => {
             let err_no = all_errs.len();
             eprintln!("{err_no} errors while downloading data at: {addr}");
             eprintln!("{all_errs:#?}");
@@ -236,55 +232,61 @@ async fn download_from_datamap(
     addr: &str,
     datamap: DataMapChunk,
     dest_path: &str,
-    client: &Client,
+    client: &mut ClientHandle,
 ) -> Result<(), ExitCodeError> {
     let datamap_addr = datamap.address();
 
     info!("Analyzing datamap at: {datamap_addr}");
     println!("Analyzing datamap at: {datamap_addr}");
 
-    match client.analyze_address(&datamap.to_hex(), true).await {
-        Ok(Analysis::RawDataMap { data, .. }) => {
-            let path = PathBuf::from(dest_path);
-            let here = PathBuf::from(".");
-            let parent = path.parent().unwrap_or_else(|| &here);
-            std::fs::create_dir_all(parent).map_err(|err| (err.into(), IO_ERROR))?;
-
-            if let Some(data) = data {
-                std::fs::write(path, data).map_err(|err| (err.into(), IO_ERROR))?;
-            } else if let Err(e) = client.file_download(&datamap, path).await {
-                let exit_code = exit_code::get_download_error_exit_code(&e);
-                return Err((
-                    eyre!("Errors while downloading from {datamap_addr:?}"),
-                    exit_code,
-                ));
-            }
-
+    let content = client
+        .data_map_content(&datamap)
+        .await
+        .map_err(|(e, exit_code)| {
+            (
+                e.wrap_err(format!("Failed to fetch file at {datamap_addr}")),
+                exit_code,
+            )
+        })?;
+    match content {
+        DataMapContent::Data(data) => {
+            let path = prepare_file_path(dest_path)?;
+            std::fs::write(path, data).map_err(|err| (err.into(), IO_ERROR))?;
             info!("Successfully downloaded file from datamap at: {datamap_addr}");
             println!("Successfully downloaded file from datamap at: {datamap_addr}");
             Ok(())
         }
-        Ok(Analysis::PublicArchive { archive, .. }) => {
+        DataMapContent::TooLargeForMemory => {
+            let path = prepare_file_path(dest_path)?;
+            client
+                .file_download(&datamap, path)
+                .await
+                .map_err(|(_, exit_code)| {
+                    (
+                        eyre!("Errors while downloading from {datamap_addr:?}"),
+                        exit_code,
+                    )
+                })?;
+            info!("Successfully downloaded file from datamap at: {datamap_addr}");
+            println!("Successfully downloaded file from datamap at: {datamap_addr}");
+            Ok(())
+        }
+        DataMapContent::PublicArchive(archive) => {
             info!("Detected public archive at: {datamap_addr}");
             download_pub_archive_to_disk(addr, archive, dest_path, client).await
         }
-        Ok(Analysis::PrivateArchive(private_archive)) => {
+        DataMapContent::PrivateArchive(private_archive) => {
             info!("Detected private archive at: {datamap_addr}");
             download_priv_archive_to_disk(addr, private_archive, dest_path, client).await
         }
-        Ok(a) => {
-            let err = format!("Unexpected data type found at {datamap_addr}: {a}");
-            Err((
-                eyre!(err).wrap_err("Failed to fetch file from address"),
-                INVALID_INPUT_EXIT_CODE,
-            ))
-        }
-        Err(e) => {
-            let exit_code = exit_code::analysis_exit_code(&e);
-            Err((
-                eyre!(e).wrap_err(format!("Failed to fetch file at {datamap_addr}")),
-                exit_code,
-            ))
-        }
     }
 }
+
+/// The path of the file to write, with its parent directories created.
+fn prepare_file_path(dest_path: &str) -> Result<PathBuf, ExitCodeError> {
+    let path = PathBuf::from(dest_path);
+    let here = PathBuf::from(".");
+    let parent = path.parent().unwrap_or_else(|| &here);
+    std::fs::create_dir_all(parent).map_err(|err| (err.into(), IO_ERROR))?;
+    Ok(path)
+}
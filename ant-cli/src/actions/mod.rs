@@ -6,10 +6,18 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod client;
 mod connect;
+#[cfg(unix)]
+mod daemon;
 mod download;
 mod progress_bar;
 
+pub use client::{
+    ClientHandle, ScratchpadGetError, connect_to_client, connect_to_client_with_config,
+};
 pub use connect::{NetworkContext, connect_to_network, connect_to_network_with_config};
+#[cfg(unix)]
+pub use daemon::{connect_to_daemon, daemon_log_path, daemon_socket_path};
 pub use download::download;
 pub use progress_bar::get_progress_bar;
@@ -7,6 +7,8 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod analyze;
+#[cfg(unix)]
+mod daemon;
 mod file;
 mod pointer;
 mod register;
@@ -63,6 +65,13 @@ pub enum SubCmd {
         command: WalletCmd,
     },
 
+    /// Run a background client daemon that other commands use instead of connecting on their own.
+    #[cfg(unix)]
+    Daemon {
+        #[command(subcommand)]
+        command: DaemonCmd,
+    },
+
     /// Operations related to data analysis.
     Analyze {
         /// The address of the data to analyse.
@@ -134,6 +143,22 @@ pub enum FileCmd {
         #[arg(short, long)]
         verbose: bool,
     },
+
+    /// Check that every chunk of a file or archive is still held by its close group, without
+    /// downloading it.
+    Audit {
+        /// The address of the file or archive to audit.
+        addr: String,
+        /// Upload the weak chunks again, taking them from the chunk cache or the local copy.
+        #[arg(long)]
+        repair: bool,
+        /// Local copy of the file, or of the directory an archive was uploaded from, to take
+        /// the weak chunks from.
+        #[arg(long, requires = "repair")]
+        from: Option<PathBuf>,
+        #[command(flatten)]
+        transaction_opt: TransactionOpt,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -417,6 +442,35 @@ pub enum WalletCmd {
     Balance,
 }
 
+#[cfg(unix)]
+#[derive(Subcommand, Debug)]
+pub enum DaemonCmd {
+    /// Start a daemon connected to the network.
+    ///
+    /// While it runs, commands that it serves (cost, analyze, register and scratchpad operations)
+    /// go through it instead of connecting to the network on their own. It pays for uploads
+    /// with the wallet selected when starting it.
+    Start {
+        /// Start the daemon without a wallet, it won't be able to pay for uploads.
+        #[arg(long)]
+        no_wallet: bool,
+        /// Run the daemon in the foreground instead of in the background.
+        #[arg(long)]
+        foreground: bool,
+        /// Read the wallet private key from stdin, used when starting the daemon in the background.
+        #[arg(long, hide = true)]
+        wallet_from_stdin: bool,
+        #[command(flatten)]
+        transaction_opt: TransactionOpt,
+    },
+
+    /// Show the status of the daemon.
+    Status,
+
+    /// Stop the daemon.
+    Stop,
+}
+
 #[derive(Args, Debug)]
 pub(crate) struct TransactionOpt {
     /// Max fee per gas / gas price bid.
@@ -505,6 +559,27 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
                     Ok(())
                 }
             }
+            FileCmd::Audit {
+                addr,
+                repair,
+                from,
+                transaction_opt,
+            } => {
+                if let Err((err, exit_code)) = file::audit(
+                    &addr,
+                    repair,
+                    from,
+                    network_context,
+                    transaction_opt.max_fee_per_gas,
+                )
+                .await
+                {
+                    eprintln!("{err:?}");
+                    std::process::exit(exit_code);
+                } else {
+                    Ok(())
+                }
+            }
         },
         Some(SubCmd::Register { command }) => match command {
             RegisterCmd::GenerateKey { overwrite } => register::generate_key(overwrite),
@@ -626,6 +701,30 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
             WalletCmd::Export => wallet::export(),
             WalletCmd::Balance => wallet::balance(network_context).await,
         },
+        #[cfg(unix)]
+        Some(SubCmd::Daemon { command }) => match command {
+            DaemonCmd::Start {
+                no_wallet,
+                foreground: false,
+                ..
+            } => daemon::start(network_context, no_wallet).await,
+            DaemonCmd::Start {
+                no_wallet,
+                foreground: true,
+                wallet_from_stdin,
+                transaction_opt,
+            } => {
+                daemon::run(
+                    network_context,
+                    no_wallet,
+                    wallet_from_stdin,
+                    transaction_opt.max_fee_per_gas,
+                )
+                .await
+            }
+            DaemonCmd::Status => daemon::status(network_context).await,
+            DaemonCmd::Stop => daemon::stop(network_context).await,
+        },
         Some(SubCmd::Analyze { addr, verbose }) => {
             analyze::analyze(&addr, verbose, network_context).await
         }
@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::actions::NetworkContext;
-use autonomi::{Multiaddr, RewardsAddress, SecretKey, Wallet, client::analyze::AnalysisError};
+use autonomi::{Multiaddr, RewardsAddress, SecretKey, Wallet};
 use color_eyre::eyre::Result;
 use std::str::FromStr;
 
@@ -22,17 +22,17 @@ pub async fn analyze(addr: &str, verbose: bool, network_context: NetworkContext)
     println_if_verbose!("Analyzing address: {}", addr);
 
     // then connect to network and check data
-    let client = crate::actions::connect_to_network(network_context)
+    let mut client = crate::actions::connect_to_client(network_context, true)
         .await
         .map_err(|(err, _)| err)?;
 
     let analysis = client.analyze_address(addr, verbose).await;
     match analysis {
-        Ok(analysis) => {
+        Ok(Some(analysis)) => {
             println_if_verbose!("Analysis successful");
             println!("{analysis}");
         }
-        Err(AnalysisError::UnrecognizedInput) => {
+        Ok(None) => {
             println!("🚨 Could not identify address type!");
             println_if_verbose!(
                 "Provided string was not recognized as a data address, trying other types..."
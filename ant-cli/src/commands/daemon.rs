@@ -0,0 +1,180 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::actions::{NetworkContext, connect_to_daemon, daemon_log_path, daemon_socket_path};
+use crate::args::max_fee_per_gas::{MaxFeePerGasParam, get_max_fee_per_gas_from_opt_param};
+use crate::wallet::{load_wallet, load_wallet_private_key};
+use autonomi::daemon::DaemonServer;
+use autonomi::{TransactionConfig, Wallet};
+use color_eyre::Section;
+use color_eyre::eyre::{Context, Result, eyre};
+use std::io::{BufRead, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long to wait for a daemon started in the background to connect to the network.
+const DAEMON_START_TIMEOUT: Duration = Duration::from_secs(300);
+const DAEMON_START_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Starts a daemon in the background, running this same command in the foreground.
+///
+/// The wallet is selected here, where a password can be prompted for, and handed over to the
+/// daemon through its stdin.
+pub async fn start(network_context: NetworkContext, no_wallet: bool) -> Result<()> {
+    let network_id = network_context.network_id;
+    if let Some(mut daemon) = connect_to_daemon(&network_id).await {
+        let status = daemon.status().await?;
+        println!(
+            "✅ A daemon is already running for this network (up for {}s)",
+            status.uptime_secs
+        );
+        return Ok(());
+    }
+
+    let private_key = if no_wallet {
+        None
+    } else {
+        Some(
+            load_wallet_private_key()
+                .wrap_err("The daemon needs a wallet to pay for uploads")
+                .with_suggestion(
+                    || "use --no-wallet to start a daemon that can't pay for uploads",
+                )?,
+        )
+    };
+
+    let log_path = daemon_log_path(&network_id)?;
+    let log = std::fs::File::create(&log_path).wrap_err(format!(
+        "Failed to create the daemon log file at {log_path:?}"
+    ))?;
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(std::env::args_os().skip(1))
+        .arg("--foreground")
+        .stdin(Stdio::piped())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        // Keep the daemon running when the terminal it was started from is interrupted.
+        .process_group(0);
+    if private_key.is_some() {
+        command.arg("--wallet-from-stdin");
+    }
+    let mut child = command.spawn().wrap_err("Failed to start the daemon")?;
+    if let (Some(private_key), Some(mut stdin)) = (private_key, child.stdin.take()) {
+        writeln!(stdin, "{private_key}").wrap_err("Failed to hand the wallet to the daemon")?;
+    }
+
+    println!("Starting the daemon, connecting to the network...");
+    let started = Instant::now();
+    while started.elapsed() < DAEMON_START_TIMEOUT {
+        if let Some(status) = child.try_wait()? {
+            return Err(eyre!("The daemon exited with {status}"))
+                .with_suggestion(|| format!("see the daemon output in {}", log_path.display()));
+        }
+        if connect_to_daemon(&network_id).await.is_some() {
+            println!(
+                "✅ Daemon started on {} (pid {})",
+                daemon_socket_path(&network_id)?.display(),
+                child.id()
+            );
+            info!("Daemon started with pid {}", child.id());
+            return Ok(());
+        }
+        tokio::time::sleep(DAEMON_START_POLL_INTERVAL).await;
+    }
+    Err(eyre!(
+        "The daemon didn't connect to the network within {}s",
+        DAEMON_START_TIMEOUT.as_secs()
+    ))
+    .with_suggestion(|| format!("see the daemon output in {}", log_path.display()))
+}
+
+/// Connects to the network and serves the client until the daemon is stopped.
+pub async fn run(
+    network_context: NetworkContext,
+    no_wallet: bool,
+    wallet_from_stdin: bool,
+    max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+) -> Result<()> {
+    let socket_path = daemon_socket_path(&network_context.network_id)?;
+    let client = crate::actions::connect_to_network(network_context)
+        .await
+        .map_err(|(err, _)| err)?;
+
+    let wallet = if wallet_from_stdin {
+        let mut private_key = String::new();
+        std::io::stdin()
+            .lock()
+            .read_line(&mut private_key)
+            .wrap_err("Failed to read the wallet from stdin")?;
+        Some(
+            Wallet::new_from_private_key(client.evm_network().clone(), private_key.trim())
+                .wrap_err("Failed to load the wallet handed to the daemon")?,
+        )
+    } else if no_wallet {
+        None
+    } else {
+        Some(load_wallet(client.evm_network())?)
+    };
+    let wallet = match wallet {
+        Some(mut wallet) => {
+            let max_fee_per_gas =
+                get_max_fee_per_gas_from_opt_param(max_fee_per_gas_param, client.evm_network())?;
+            wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
+            println!("Paying for uploads with wallet {}", wallet.address());
+            Some(wallet)
+        }
+        None => {
+            println!("No wallet, uploads can't be paid for");
+            None
+        }
+    };
+
+    println!("Daemon listening on {}", socket_path.display());
+    DaemonServer::new(client, wallet)
+        .serve(&socket_path)
+        .await
+        .wrap_err("The daemon stopped with an error")?;
+    println!("Daemon stopped");
+    Ok(())
+}
+
+pub async fn status(network_context: NetworkContext) -> Result<()> {
+    let Some(mut daemon) = connect_to_daemon(&network_context.network_id).await else {
+        println!("No daemon is running for this network");
+        return Ok(());
+    };
+    let status = daemon.status().await?;
+    println!("✅ Daemon running");
+    println!(
+        "Socket: {}",
+        daemon_socket_path(&network_context.network_id)?.display()
+    );
+    println!("Version: {}", status.version);
+    println!("Uptime: {}s", status.uptime_secs);
+    println!("EVM network: {}", status.evm_network);
+    match status.wallet {
+        Some(wallet) => println!("Wallet: {wallet}"),
+        None => println!("Wallet: none, uploads can't be paid for"),
+    }
+    Ok(())
+}
+
+pub async fn stop(network_context: NetworkContext) -> Result<()> {
+    let Some(mut daemon) = connect_to_daemon(&network_context.network_id).await else {
+        println!("No daemon is running for this network");
+        return Ok(());
+    };
+    daemon
+        .shutdown()
+        .await
+        .wrap_err("Failed to stop the daemon")?;
+    println!("✅ Daemon stopped");
+    Ok(())
+}
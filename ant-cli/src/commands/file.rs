@@ -7,25 +7,39 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::access::cached_payments;
-use crate::actions::NetworkContext;
+use crate::actions::{ClientHandle, NetworkContext};
 use crate::args::max_fee_per_gas::{MaxFeePerGasParam, get_max_fee_per_gas_from_opt_param};
-use crate::exit_code::{ExitCodeError, FEES_ERROR, IO_ERROR, upload_exit_code};
+use crate::exit_code::{
+    ExitCodeError, FEES_ERROR, INVALID_INPUT_EXIT_CODE, IO_ERROR, UNHEALTHY_DATA_EXIT_CODE,
+    audit_error_exit_code, upload_exit_code,
+};
+#[cfg(unix)]
+use crate::exit_code::{SERIALIZATION_ERROR, daemon_error_exit_code};
 use crate::utils::collect_upload_summary;
 use crate::wallet::load_wallet;
+use autonomi::chunk::DataMapChunk;
 use autonomi::client::PutError;
 use autonomi::client::analyze::Analysis;
 use autonomi::client::payment::PaymentOption;
+use autonomi::client::payment::Receipt;
+use autonomi::data::DataAddress;
 use autonomi::files::UploadError;
+use autonomi::files::audit::AuditTarget;
 use autonomi::networking::{Quorum, RetryStrategy};
 use autonomi::{Client, ClientOperatingStrategy, TransactionConfig};
 use color_eyre::Section;
 use color_eyre::eyre::{Context, Result, eyre};
 use std::path::PathBuf;
 
+#[cfg(unix)]
+use autonomi::AttoTokens;
+#[cfg(unix)]
+use autonomi::daemon::{DaemonClient, DaemonError};
+
 const MAX_ADDRESSES_TO_PRINT: usize = 3;
 
 pub async fn cost(file: &str, network_context: NetworkContext) -> Result<()> {
-    let client = crate::actions::connect_to_network(network_context)
+    let mut client = crate::actions::connect_to_client(network_context, true)
         .await
         .map_err(|(err, _)| err)?;
 
@@ -50,11 +64,100 @@ pub async fn upload(
     max_fee_per_gas_param: Option<MaxFeePerGasParam>,
     retry_failed: u64,
 ) -> Result<(), ExitCodeError> {
-    let config = ClientOperatingStrategy::new();
+    let cached_receipt = cached_payments::load_payment_for_file(file).ok().flatten();
+    // The daemon pays with its own wallet and max fee per gas, and can neither retry failed
+    // chunks on request nor reuse a payment cached by this process
+    let allow_daemon =
+        max_fee_per_gas_param.is_none() && retry_failed == 0 && cached_receipt.is_none();
+    let client = crate::actions::connect_to_client_with_config(
+        network_context,
+        ClientOperatingStrategy::new(),
+        allow_daemon,
+    )
+    .await?;
 
-    let mut client =
-        crate::actions::connect_to_network_with_config(network_context, config).await?;
+    let dir_path = PathBuf::from(file);
+    let name = dir_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(file.to_string());
+    let not_single_file = !dir_path.is_file();
+
+    let (archive_addr, local_addr) = match client {
+        ClientHandle::Local(client) => {
+            upload_in_process(
+                client,
+                file,
+                public,
+                no_archive,
+                max_fee_per_gas_param,
+                retry_failed,
+                cached_receipt,
+            )
+            .await?
+        }
+        #[cfg(unix)]
+        ClientHandle::Daemon(mut daemon) => {
+            upload_through_daemon(&mut daemon, file, public, no_archive).await?
+        }
+    };
+
+    // save archive to local user data
+    if !no_archive && not_single_file {
+        let writer = if public {
+            crate::user_data::write_local_public_file_archive(archive_addr.clone(), &name)
+        } else {
+            crate::user_data::write_local_private_file_archive(
+                archive_addr.clone(),
+                local_addr.clone(),
+                &name,
+            )
+        };
+        writer
+            .wrap_err("Failed to save file to local user data")
+            .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")
+            .map_err(|err| (err, IO_ERROR))?;
+        info!("Saved file to local user data");
+    }
+
+    // save single private files to local user data
+    if !not_single_file && !public {
+        let writer = crate::user_data::write_local_private_file(
+            archive_addr.clone(),
+            local_addr.clone(),
+            &name,
+        );
+        writer
+            .wrap_err("Failed to save private file to local user data")
+            .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")
+            .map_err(|err| (err, IO_ERROR))?;
+        info!("Saved private file to local user data");
+    }
+
+    // save single public files to local user data
+    if !not_single_file && public {
+        let writer = crate::user_data::write_local_public_file(local_addr.to_owned(), &name);
+        writer
+            .wrap_err("Failed to save public file to local user data")
+            .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")
+            .map_err(|err| (err, IO_ERROR))?;
+        info!("Saved public file to local user data");
+    }
 
+    Ok(())
+}
+
+/// Upload with a client connected within this process, reporting what was paid for.
+/// Returns the archive address if any and the address to access the data.
+async fn upload_in_process(
+    mut client: Client,
+    file: &str,
+    public: bool,
+    no_archive: bool,
+    max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+    retry_failed: u64,
+    cached_receipt: Option<Receipt>,
+) -> Result<(String, String), ExitCodeError> {
     // Configure client with retry_failed setting
     if retry_failed != 0 {
         client = client.with_retry_failed(retry_failed);
@@ -70,7 +173,7 @@ pub async fn upload(
             .map_err(|err| (err, FEES_ERROR))?;
     wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
 
-    let payment = if let Ok(Some(receipt)) = cached_payments::load_payment_for_file(file) {
+    let payment = if let Some(receipt) = cached_receipt {
         println!("Using cached payment: no need to re-pay");
         PaymentOption::Receipt(receipt)
     } else {
@@ -86,14 +189,8 @@ pub async fn upload(
         if public { "public" } else { "private" }
     );
 
-    let dir_path = PathBuf::from(file);
-    let name = dir_path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or(file.to_string());
-
     // upload dir
-    let not_single_file = !dir_path.is_file();
+    let dir_path = PathBuf::from(file);
     let (archive_addr, local_addr) =
         match upload_dir(&client, dir_path, public, no_archive, payment).await {
             Ok((a, l)) => (a, l),
@@ -142,49 +239,101 @@ pub async fn upload(
     }
     info!("Summary for upload of file {file} at {local_addr:?}: {summary:?}");
 
-    // save archive to local user data
-    if !no_archive && not_single_file {
-        let writer = if public {
-            crate::user_data::write_local_public_file_archive(archive_addr.clone(), &name)
+    Ok((archive_addr, local_addr))
+}
+
+/// Upload through the client daemon, which pays with its own wallet.
+/// Returns the archive address if any and the address to access the data.
+#[cfg(unix)]
+async fn upload_through_daemon(
+    daemon: &mut DaemonClient,
+    file: &str,
+    public: bool,
+    no_archive: bool,
+) -> Result<(String, String), ExitCodeError> {
+    let failed = |err: DaemonError| {
+        let exit_code = daemon_error_exit_code(&err);
+        (eyre!(err).wrap_err("Failed to upload file"), exit_code)
+    };
+    let archive_failed = |err| {
+        (
+            eyre!("Failed to serialize archive: {err:?}").wrap_err("Failed to upload file"),
+            SERIALIZATION_ERROR,
+        )
+    };
+
+    println!("Uploading data to network through the client daemon...");
+    info!(
+        "Uploading {} file through the client daemon: {file}",
+        if public { "public" } else { "private" }
+    );
+
+    let dir_path = PathBuf::from(file);
+    let is_single_file = dir_path.is_file();
+    let (cost, archive_addr, local_addr) = if public {
+        let (cost, public_archive) = daemon
+            .dir_content_upload_public(&dir_path)
+            .await
+            .map_err(failed)?;
+
+        let mut addrs = vec![];
+        for (file_path, addr, _meta) in public_archive.iter() {
+            println!("  - {file_path:?}: {:?}", addr.to_hex());
+            addrs.push(addr.to_hex());
+        }
+
+        if no_archive || is_single_file {
+            let (archive_addr, local_addr) = no_archive_addresses(addrs);
+            (cost, archive_addr, local_addr)
         } else {
-            crate::user_data::write_local_private_file_archive(
-                archive_addr.clone(),
-                local_addr.clone(),
-                &name,
-            )
-        };
-        writer
-            .wrap_err("Failed to save file to local user data")
-            .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")
-            .map_err(|err| (err, IO_ERROR))?;
-        info!("Saved file to local user data");
-    }
+            let bytes = public_archive.to_bytes().map_err(archive_failed)?;
+            let (archive_cost, addr) = daemon.data_put_public(bytes).await.map_err(failed)?;
+            (add_costs(cost, archive_cost), addr.to_hex(), addr.to_hex())
+        }
+    } else {
+        let (cost, private_archive) = daemon.dir_content_upload(&dir_path).await.map_err(failed)?;
 
-    // save single private files to local user data
-    if !not_single_file && !public {
-        let writer = crate::user_data::write_local_private_file(
-            archive_addr.clone(),
-            local_addr.clone(),
-            &name,
-        );
-        writer
-            .wrap_err("Failed to save private file to local user data")
-            .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")
-            .map_err(|err| (err, IO_ERROR))?;
-        info!("Saved private file to local user data");
-    }
+        let mut addrs = vec![];
+        for (file_path, private_datamap, _meta) in private_archive.iter() {
+            println!("  - {file_path:?}: {:?}", private_datamap.to_hex());
+            addrs.push(private_datamap.to_hex());
+        }
 
-    // save single public files to local user data
-    if !not_single_file && public {
-        let writer = crate::user_data::write_local_public_file(local_addr.to_owned(), &name);
-        writer
-            .wrap_err("Failed to save public file to local user data")
-            .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")
-            .map_err(|err| (err, IO_ERROR))?;
-        info!("Saved public file to local user data");
-    }
+        match private_archive.iter().next() {
+            Some((_, private_datamap, _)) if is_single_file && addrs.len() == 1 => {
+                // For single private files, return both full hex and short address
+                (cost, private_datamap.to_hex(), private_datamap.address())
+            }
+            _ if no_archive || is_single_file => {
+                let (archive_addr, local_addr) = no_archive_addresses(addrs);
+                (cost, archive_addr, local_addr)
+            }
+            _ => {
+                let bytes = private_archive.to_bytes().map_err(archive_failed)?;
+                let (archive_cost, private_datamap) =
+                    daemon.data_put(bytes).await.map_err(failed)?;
+                (
+                    add_costs(cost, archive_cost),
+                    private_datamap.to_hex(),
+                    private_datamap.address(),
+                )
+            }
+        }
+    };
 
-    Ok(())
+    println!("Successfully uploaded: {file}");
+    println!("At address: {local_addr}");
+    println!("Total cost: {cost}");
+    info!("Successfully uploaded: {file} at address: {local_addr} for {cost}");
+    Ok((archive_addr, local_addr))
+}
+
+#[cfg(unix)]
+fn add_costs(cost1: AttoTokens, cost2: AttoTokens) -> AttoTokens {
+    cost1.checked_add(cost2).unwrap_or_else(|| {
+        error!("Total cost overflowed: {cost1:?} + {cost2:?}");
+        cost1
+    })
 }
 
 /// Uploads a file or directory to the network and prints the content and addresses.
@@ -212,11 +361,7 @@ async fn upload_dir(
         }
 
         if no_archive || is_single_file {
-            if addrs.len() > MAX_ADDRESSES_TO_PRINT {
-                Ok(("no-archive".to_string(), "multiple addresses".to_string()))
-            } else {
-                Ok(("no-archive".to_string(), addrs.join(", ")))
-            }
+            Ok(no_archive_addresses(addrs))
         } else {
             let (_, addr) = client
                 .archive_put_public(&public_archive, payment_option.clone())
@@ -235,19 +380,13 @@ async fn upload_dir(
         }
 
         if no_archive || is_single_file {
-            if addrs.len() > MAX_ADDRESSES_TO_PRINT {
-                Ok(("no-archive".to_string(), "multiple addresses".to_string()))
-            } else if is_single_file && addrs.len() == 1 {
+            if is_single_file && addrs.len() == 1 {
                 // For single private files, return both full hex and short address
                 if let Some((_, private_datamap, _)) = private_archive.iter().next() {
-                    Ok((private_datamap.to_hex(), private_datamap.address()))
-                } else {
-                    // This should not happen given the conditions, but handle gracefully
-                    Ok(("no-archive".to_string(), addrs.join(", ")))
+                    return Ok((private_datamap.to_hex(), private_datamap.address()));
                 }
-            } else {
-                Ok(("no-archive".to_string(), addrs.join(", ")))
             }
+            Ok(no_archive_addresses(addrs))
         } else {
             let (_, private_datamap) = client
                 .archive_put(&private_archive, payment_option.clone())
@@ -257,6 +396,15 @@ async fn upload_dir(
     }
 }
 
+/// The addresses to report when the files are uploaded without an archive.
+fn no_archive_addresses(addrs: Vec<String>) -> (String, String) {
+    if addrs.len() > MAX_ADDRESSES_TO_PRINT {
+        ("no-archive".to_string(), "multiple addresses".to_string())
+    } else {
+        ("no-archive".to_string(), addrs.join(", "))
+    }
+}
+
 pub async fn download(
     addr: &str,
     dest_path: &str,
@@ -289,9 +437,14 @@ pub async fn download(
         println!("Chunk caching disabled");
     }
 
-    let client = crate::actions::connect_to_network_with_config(network_context, config).await?;
+    // The daemon fetches with its own quorum, retries and chunk cache, so downloads asking for
+    // others are done within this process
+    let allow_daemon = quorum.is_none() && retries.is_none() && cache_chunks && cache_dir.is_none();
+    let mut client =
+        crate::actions::connect_to_client_with_config(network_context, config, allow_daemon)
+            .await?;
 
-    crate::actions::download(addr, dest_path, &client).await
+    crate::actions::download(addr, dest_path, &mut client).await
 }
 
 pub async fn list(network_context: NetworkContext, verbose: bool) -> Result<(), ExitCodeError> {
@@ -403,3 +556,156 @@ pub async fn list(network_context: NetworkContext, verbose: bool) -> Result<(),
     );
     Ok(())
 }
+
+pub async fn audit(
+    addr: &str,
+    repair: bool,
+    from: Option<PathBuf>,
+    network_context: NetworkContext,
+    max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+) -> Result<(), ExitCodeError> {
+    let client = crate::actions::connect_to_network(network_context).await?;
+    let target = audit_target(addr, &client).await?;
+
+    println!("Auditing {addr}...");
+    info!("Auditing {addr}: {target:?}");
+    let report = client.audit(&target).await;
+
+    for file in report.iter() {
+        let name = match (&file.path, &file.address) {
+            (path, _) if !path.as_os_str().is_empty() => format!("{path:?}"),
+            (_, Some(address)) => address.to_hex(),
+            (_, None) => addr.to_string(),
+        };
+        let weak: Vec<_> = file.weak_chunks().collect();
+        let unknown: Vec<_> = file.unknown_chunks().collect();
+        let status = if file.is_healthy() { "✅" } else { "⚠️ " };
+        println!(
+            "{status} {name}: {} of {} chunks held by their whole close group",
+            file.chunks.len() - weak.len() - unknown.len(),
+            file.chunks.len()
+        );
+        if !file.complete {
+            println!("  - Not all chunks could be listed, the ones that could were audited");
+        }
+        for chunk in weak {
+            println!(
+                "  - {}: weak, {} holders, {} peers didn't answer",
+                chunk.address.to_hex(),
+                chunk.holders,
+                chunk.unanswered
+            );
+        }
+        for chunk in unknown {
+            println!(
+                "  - {}: unknown, {} holders, {} peers didn't answer",
+                chunk.address.to_hex(),
+                chunk.holders,
+                chunk.unanswered
+            );
+        }
+    }
+    println!(
+        "{} of {} chunks are weak, {} could not be fully checked",
+        report.weak_chunk_count(),
+        report.chunk_count(),
+        report.unknown_chunk_count()
+    );
+
+    if report.is_healthy() {
+        println!("All data is healthy");
+        return Ok(());
+    }
+    if report.is_inconclusive() {
+        return Err((
+            eyre!("Data at {addr} could not be fully audited, no weak chunk was found")
+                .with_suggestion(|| "Audit again later, older nodes can't be asked"),
+            UNHEALTHY_DATA_EXIT_CODE,
+        ));
+    }
+    if !repair {
+        return Err((
+            eyre!("Data at {addr} is not fully healthy")
+                .with_suggestion(|| "Use --repair to upload the weak chunks again"),
+            UNHEALTHY_DATA_EXIT_CODE,
+        ));
+    }
+
+    let mut wallet = load_wallet(client.evm_network()).map_err(|err| (err, IO_ERROR))?;
+    let max_fee_per_gas =
+        get_max_fee_per_gas_from_opt_param(max_fee_per_gas_param, client.evm_network())
+            .map_err(|err| (err, FEES_ERROR))?;
+    wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
+
+    println!("Repairing {} weak chunks...", report.weak_chunk_count());
+    let summary = client
+        .audit_repair(&report, from.as_deref(), PaymentOption::Wallet(wallet))
+        .await
+        .map_err(|err| {
+            let exit_code = audit_error_exit_code(&err);
+            (eyre!(err).wrap_err("Failed to repair data"), exit_code)
+        })?;
+
+    println!(
+        "Uploaded {} chunks again for {}",
+        summary.reuploaded, summary.cost
+    );
+    info!(
+        "Repaired {addr}: uploaded {} chunks again for {}, {} unrecoverable",
+        summary.reuploaded,
+        summary.cost,
+        summary.unrecoverable.len()
+    );
+    if !summary.unrecoverable.is_empty() {
+        for chunk in &summary.unrecoverable {
+            println!(
+                "  - {}: not found in the cache nor the local copy",
+                chunk.to_hex()
+            );
+        }
+        return Err((
+            eyre!(
+                "{} weak chunks could not be repaired",
+                summary.unrecoverable.len()
+            )
+            .with_suggestion(|| "Use --from to point at a local copy of the data"),
+            UNHEALTHY_DATA_EXIT_CODE,
+        ));
+    }
+    Ok(())
+}
+
+/// Figure out what an address points to, the same way `file download` does.
+async fn audit_target(addr: &str, client: &Client) -> Result<AuditTarget, ExitCodeError> {
+    if let Ok(address) = DataAddress::from_hex(addr) {
+        let is_local_archive = crate::user_data::get_local_public_file_archives()
+            .is_ok_and(|archives| archives.contains_key(&address));
+        if is_local_archive
+            || matches!(
+                client.analyze_address(addr, false).await,
+                Ok(Analysis::PublicArchive { .. })
+            )
+        {
+            return Ok(AuditTarget::PublicArchive(address));
+        }
+        return Ok(AuditTarget::Public(address));
+    }
+
+    if let Ok(data_map) = crate::user_data::get_local_private_archive_access(addr) {
+        return Ok(AuditTarget::PrivateArchive(data_map));
+    }
+
+    if let Ok(data_map) = crate::user_data::get_local_private_file_access(addr) {
+        return Ok(AuditTarget::Private(data_map));
+    }
+
+    if let Ok(data_map) = DataMapChunk::from_hex(addr) {
+        return Ok(AuditTarget::Private(data_map));
+    }
+
+    Err((
+        eyre!("Failed to parse data address {addr}")
+            .with_suggestion(|| "Try the `file list` command to get addresses you have access to"),
+        INVALID_INPUT_EXIT_CODE,
+    ))
+}
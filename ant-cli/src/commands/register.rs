@@ -9,11 +9,10 @@
 #![allow(deprecated)]
 
 use crate::actions::NetworkContext;
-use crate::args::max_fee_per_gas::{MaxFeePerGasParam, get_max_fee_per_gas_from_opt_param};
-use crate::wallet::load_wallet;
+use crate::args::max_fee_per_gas::MaxFeePerGasParam;
+use autonomi::Client;
 use autonomi::client::register::RegisterAddress;
 use autonomi::client::register::SecretKey as RegisterSecretKey;
-use autonomi::{Client, TransactionConfig};
 use color_eyre::Section;
 use color_eyre::eyre::Context;
 use color_eyre::eyre::Result;
@@ -41,7 +40,7 @@ pub fn generate_key(overwrite: bool) -> Result<()> {
 pub async fn cost(name: &str, network_context: NetworkContext) -> Result<()> {
     let main_registers_key = crate::keys::get_register_signing_key()
         .wrap_err("The register key is required to perform this action")?;
-    let client = crate::actions::connect_to_network(network_context)
+    let mut client = crate::actions::connect_to_client(network_context, true)
         .await
         .map_err(|(err, _)| err)?;
 
@@ -65,15 +64,10 @@ pub async fn create(
 ) -> Result<()> {
     let main_registers_key = crate::keys::get_register_signing_key()
         .wrap_err("The register key is required to perform this action")?;
-    let client = crate::actions::connect_to_network(network_context)
-        .await
-        .map_err(|(err, _)| err)?;
-
-    let mut wallet = load_wallet(client.evm_network())?;
-
-    let max_fee_per_gas =
-        get_max_fee_per_gas_from_opt_param(max_fee_per_gas_param, client.evm_network())?;
-    wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
+    let mut client =
+        crate::actions::connect_to_client(network_context, max_fee_per_gas_param.is_none())
+            .await
+            .map_err(|(err, _)| err)?;
 
     let register_key = Client::register_key_from_name(&main_registers_key, name);
 
@@ -90,7 +84,7 @@ pub async fn create(
     let content = Client::register_value_from_bytes(&value_bytes)?;
 
     let (cost, address) = client
-        .register_create(&register_key, content, wallet.into())
+        .register_create(&register_key, content, max_fee_per_gas_param)
         .await
         .wrap_err("Failed to create register")?;
 
@@ -122,15 +116,10 @@ pub async fn edit(
 ) -> Result<()> {
     let main_registers_key = crate::keys::get_register_signing_key()
         .wrap_err("The register key is required to perform this action")?;
-    let client = crate::actions::connect_to_network(network_context)
-        .await
-        .map_err(|(err, _)| err)?;
-
-    let mut wallet = load_wallet(client.evm_network())?;
-
-    let max_fee_per_gas =
-        get_max_fee_per_gas_from_opt_param(max_fee_per_gas_param, client.evm_network())?;
-    wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
+    let mut client =
+        crate::actions::connect_to_client(network_context, max_fee_per_gas_param.is_none())
+            .await
+            .map_err(|(err, _)| err)?;
 
     let value_bytes = if hex {
         hex::decode(value.trim_start_matches("0x"))
@@ -161,7 +150,7 @@ pub async fn edit(
     info!("Attempting to update register at {address} with new value: {value}");
 
     let cost = client
-        .register_update(&register_key, value_bytes, wallet.into())
+        .register_update(&register_key, value_bytes, max_fee_per_gas_param)
         .await
         .wrap_err(format!("Failed to update register at address: {address}"))?;
 
@@ -186,7 +175,7 @@ pub async fn get(
     hex: bool,
     network_context: NetworkContext,
 ) -> Result<()> {
-    let client = crate::actions::connect_to_network(network_context)
+    let mut client = crate::actions::connect_to_client(network_context, true)
         .await
         .map_err(|(err, _)| err)?;
 
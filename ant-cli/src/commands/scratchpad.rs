@@ -6,13 +6,10 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::actions::NetworkContext;
+use crate::actions::{NetworkContext, ScratchpadGetError};
 use crate::args::max_fee_per_gas::MaxFeePerGasParam;
-use crate::args::max_fee_per_gas::get_max_fee_per_gas_from_opt_param;
-use crate::wallet::load_wallet;
 use autonomi::Bytes;
 use autonomi::ScratchpadAddress;
-use autonomi::TransactionConfig;
 use autonomi::client::data_types::scratchpad::{ScratchpadError, print_fork_analysis};
 use autonomi::client::scratchpad::SecretKey as ScratchpadSecretKey;
 use color_eyre::Section;
@@ -52,7 +49,7 @@ pub fn generate_key(overwrite: bool) -> Result<()> {
 pub async fn cost(name: String, network_context: NetworkContext) -> Result<()> {
     let key = crate::keys::get_scratchpad_signing_key(&name)
         .wrap_err("The scratchpad key is required to perform this action")?;
-    let client = crate::actions::connect_to_network(network_context)
+    let mut client = crate::actions::connect_to_client(network_context, true)
         .await
         .map_err(|(err, _)| err)?;
 
@@ -80,22 +77,21 @@ pub async fn create(
 ) -> Result<()> {
     let scratchpad_key = crate::keys::get_scratchpad_signing_key(&name)
         .wrap_err("The scratchpad key is required to perform this action")?;
-    let client = crate::actions::connect_to_network(context)
+    let mut client = crate::actions::connect_to_client(context, max_fee_per_gas_param.is_none())
         .await
         .map_err(|(err, _)| err)?;
 
-    let mut wallet = load_wallet(client.evm_network())?;
-
-    let max_fee_per_gas =
-        get_max_fee_per_gas_from_opt_param(max_fee_per_gas_param, client.evm_network())?;
-    wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
-
     println!("Creating scratchpad with name: {name}");
     info!("Creating scratchpad with name: {name}");
 
     let bytes = Bytes::from(data);
     let (cost, address) = client
-        .scratchpad_create(&scratchpad_key, Default::default(), &bytes, wallet.into())
+        .scratchpad_create(
+            &scratchpad_key,
+            Default::default(),
+            &bytes,
+            max_fee_per_gas_param,
+        )
         .await
         .wrap_err("Failed to create scratchpad")?;
 
@@ -136,7 +132,7 @@ pub fn share(name: String) -> Result<()> {
 /// * `name` - Name of the scratchpad to load
 /// * `secret_key` - Whether this is an imported secret_key
 pub async fn get(context: NetworkContext, name: String, secret_key: bool, hex: bool) -> Result<()> {
-    let client = crate::actions::connect_to_network(context)
+    let mut client = crate::actions::connect_to_client(context, true)
         .await
         .map_err(|(err, _)| err)?;
 
@@ -154,7 +150,7 @@ pub async fn get(context: NetworkContext, name: String, secret_key: bool, hex: b
 
     let scratchpad = match scratchpad_result {
         Ok(scratchpad) => scratchpad,
-        Err(ScratchpadError::Fork(conflicting_scratchpads)) => {
+        Err(ScratchpadGetError::Fork(conflicting_scratchpads)) => {
             let error =
                 color_eyre::Report::new(ScratchpadError::Fork(conflicting_scratchpads.clone()))
                     .wrap_err("Failed to retrieve scratchpad from network");
@@ -164,9 +160,8 @@ pub async fn get(context: NetworkContext, name: String, secret_key: bool, hex: b
             }
             std::process::exit(1);
         }
-        Err(other_error) => {
-            return Err(color_eyre::Report::new(other_error)
-                .wrap_err("Failed to retrieve scratchpad from network"));
+        Err(ScratchpadGetError::Other(other_error)) => {
+            return Err(other_error.wrap_err("Failed to retrieve scratchpad from network"));
         }
     };
 
@@ -199,7 +194,7 @@ pub async fn edit(
     secret_key: bool,
     data: String,
 ) -> Result<()> {
-    let client = crate::actions::connect_to_network(context)
+    let mut client = crate::actions::connect_to_client(context, true)
         .await
         .map_err(|(err, _)| err)?;
 
@@ -220,7 +215,7 @@ pub async fn edit(
 
     let net_scratchpad = match scratchpad_result {
         Ok(scratchpad) => scratchpad,
-        Err(ScratchpadError::Fork(conflicting_scratchpads)) => {
+        Err(ScratchpadGetError::Fork(conflicting_scratchpads)) => {
             eprintln!("{}", "⚠️  Fork detected, forcing update...".yellow());
             // Continue with the first conflicting scratchpad - scratchpad_update_from will handle the fork.
             conflicting_scratchpads
@@ -228,9 +223,8 @@ pub async fn edit(
                 .ok_or_else(|| eyre!("Fork error contains no conflicting scratchpads"))?
                 .clone()
         }
-        Err(other_error) => {
-            return Err(color_eyre::Report::new(other_error)
-                .wrap_err("Failed to retrieve scratchpad from network"));
+        Err(ScratchpadGetError::Other(other_error)) => {
+            return Err(other_error.wrap_err("Failed to retrieve scratchpad from network"));
         }
     };
     println!(
@@ -11,17 +11,25 @@
 use autonomi::{
     BootstrapError,
     client::{ConnectError, GetError, PutError, analyze::AnalysisError, payment::PayError},
-    files::{DownloadError, UploadError},
+    files::{DownloadError, UploadError, audit::AuditError},
 };
 use color_eyre::eyre::Report;
 
+#[cfg(unix)]
+use autonomi::daemon::{DaemonError, RemoteError};
+
 pub(crate) const INVALID_INPUT_EXIT_CODE: i32 = 6;
-const SERIALIZATION_ERROR: i32 = 11;
+pub(crate) const SERIALIZATION_ERROR: i32 = 11;
 pub const IO_ERROR: i32 = 12;
 const NETWORK_ERROR: i32 = 13;
 const PROTOCOL_ERROR: i32 = 14;
 const SELF_ENCRYPTION_ERROR: i32 = 15;
 pub const FEES_ERROR: i32 = 62;
+/// The client daemon failed to serve a request
+#[cfg(unix)]
+const DAEMON_ERROR: i32 = 70;
+/// Audited data has chunks held by fewer peers than it should
+pub(crate) const UNHEALTHY_DATA_EXIT_CODE: i32 = 38;
 
 pub type ExitCodeError = (Report, i32);
 
@@ -80,6 +88,15 @@ pub(crate) fn analysis_exit_code(err: &AnalysisError) -> i32 {
     }
 }
 
+pub(crate) fn audit_error_exit_code(err: &AuditError) -> i32 {
+    match err {
+        AuditError::IoError(_) => IO_ERROR,
+        AuditError::Encryption(_) => SELF_ENCRYPTION_ERROR,
+        AuditError::PutError(put_error) => put_error_exit_code(put_error),
+        AuditError::GetError(get_error) => get_error_exit_code(get_error),
+    }
+}
+
 pub(crate) fn put_error_exit_code(err: &PutError) -> i32 {
     match err {
         PutError::SelfEncryption(_) => SELF_ENCRYPTION_ERROR,
@@ -118,6 +135,17 @@ pub(crate) fn connect_error_exit_code(err: &ConnectError) -> i32 {
     }
 }
 
+#[cfg(unix)]
+pub(crate) fn daemon_error_exit_code(err: &DaemonError) -> i32 {
+    match err {
+        DaemonError::Io(_) => IO_ERROR,
+        DaemonError::Remote(RemoteError::NoWallet) => FEES_ERROR,
+        DaemonError::Remote(RemoteError::UnrecognizedAddress) => 36,
+        DaemonError::Remote(RemoteError::TooLargeForMemory) => 31,
+        _ => DAEMON_ERROR,
+    }
+}
+
 pub(crate) fn evm_util_error_exit_code(err: &autonomi::EvmUtilError) -> i32 {
     match err {
         autonomi::EvmUtilError::FailedToGetEvmNetwork(_) => 61,
@@ -9,7 +9,9 @@
 use ant_bootstrap::InitialPeersConfig;
 use ant_evm::{EvmNetwork, RewardsAddress};
 use ant_logging::LogFormat;
-use ant_service_management::node::push_arguments_from_initial_peers_config;
+use ant_service_management::{
+    control::ServiceBackend, node::push_arguments_from_initial_peers_config,
+};
 use color_eyre::{Result, eyre::eyre};
 use service_manager::{ServiceInstallCtx, ServiceLabel};
 use std::{
@@ -25,6 +27,15 @@ pub enum PortRange {
     Range(u16, u16),
 }
 
+impl std::fmt::Display for PortRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Single(port) => write!(f, "{port}"),
+            Self::Range(start, end) => write!(f, "{start}-{end}"),
+        }
+    }
+}
+
 impl PortRange {
     pub fn parse(s: &str) -> Result<Self> {
         if let Ok(port) = u16::from_str(s) {
@@ -43,6 +54,14 @@ impl PortRange {
         }
     }
 
+    /// All the ports of the range, in ascending order.
+    pub fn ports(&self) -> Vec<u16> {
+        match self {
+            Self::Single(port) => vec![*port],
+            Self::Range(start, end) => (*start..=*end).collect(),
+        }
+    }
+
     /// Validate the port range against a count to make sure the correct number of ports are provided.
     pub fn validate(&self, count: u16) -> Result<()> {
         match self {
@@ -68,6 +87,46 @@ impl PortRange {
     }
 }
 
+/// Get the rate limit of each node, given the per-node limit and the aggregate limit to be shared
+/// evenly between `count` nodes. The lowest of the two applies.
+pub fn get_per_node_rate_limit(
+    per_node: Option<u64>,
+    aggregate: Option<u64>,
+    count: u16,
+) -> Option<u64> {
+    let shared = aggregate.map(|aggregate| (aggregate / u64::from(count.max(1))).max(1));
+    match (per_node, shared) {
+        (Some(per_node), Some(shared)) => Some(per_node.min(shared)),
+        (per_node, shared) => per_node.or(shared),
+    }
+}
+
+/// Refuse to share an aggregate rate limit with the services added before, unless each of them is
+/// already limited to at most the new per-node `limit`: adding services does not change their
+/// limits, so the fleet could otherwise exceed the aggregate.
+pub fn check_existing_rate_limits<'a>(
+    direction: &str,
+    existing: impl IntoIterator<Item = (&'a str, Option<u64>)>,
+    limit: Option<u64>,
+) -> Result<()> {
+    let Some(limit) = limit else {
+        return Ok(());
+    };
+    let over_limit: Vec<&str> = existing
+        .into_iter()
+        .filter(|(_, rate)| rate.is_none_or(|rate| rate > limit))
+        .map(|(service_name, _)| service_name)
+        .collect();
+    if over_limit.is_empty() {
+        return Ok(());
+    }
+    Err(eyre!(
+        "The total {direction} rate cannot be shared with {}, whose limit is not at most {limit} \
+         KB/s. Lower it first, e.g. with a fleet spec and the apply command.",
+        over_limit.join(", ")
+    ))
+}
+
 #[derive(Debug, PartialEq)]
 pub struct InstallNodeServiceCtxBuilder {
     pub alpha: bool,
@@ -82,7 +141,9 @@ pub struct InstallNodeServiceCtxBuilder {
     pub network_id: Option<u8>,
     pub no_upnp: bool,
     pub max_archived_log_files: Option<usize>,
+    pub max_download_rate: Option<u64>,
     pub max_log_files: Option<usize>,
+    pub max_upload_rate: Option<u64>,
     pub metrics_port: Option<u16>,
     pub node_ip: Option<Ipv4Addr>,
     pub node_port: Option<u16>,
@@ -144,6 +205,14 @@ impl InstallNodeServiceCtxBuilder {
             args.push(OsString::from("--max-log-files"));
             args.push(OsString::from(log_files.to_string()));
         }
+        if let Some(rate) = self.max_download_rate {
+            args.push(OsString::from("--max-download-rate"));
+            args.push(OsString::from(rate.to_string()));
+        }
+        if let Some(rate) = self.max_upload_rate {
+            args.push(OsString::from("--max-upload-rate"));
+            args.push(OsString::from(rate.to_string()));
+        }
 
         args.push(OsString::from("--rewards-address"));
         args.push(OsString::from(self.rewards_address.to_string()));
@@ -185,6 +254,7 @@ pub struct AddNodeServiceOptions {
     pub antnode_src_path: PathBuf,
     pub auto_restart: bool,
     pub auto_set_nat_flags: bool,
+    pub backend: ServiceBackend,
     pub count: Option<u16>,
     pub delete_antnode_src: bool,
     pub enable_metrics_server: bool,
@@ -193,7 +263,11 @@ pub struct AddNodeServiceOptions {
     pub init_peers_config: InitialPeersConfig,
     pub log_format: Option<LogFormat>,
     pub max_archived_log_files: Option<usize>,
+    /// The download rate limit of each node, in kilobytes per second.
+    pub max_download_rate: Option<u64>,
     pub max_log_files: Option<usize>,
+    /// The upload rate limit of each node, in kilobytes per second.
+    pub max_upload_rate: Option<u64>,
     pub metrics_port: Option<PortRange>,
     pub network_id: Option<u8>,
     pub node_ip: Option<Ipv4Addr>,
@@ -239,7 +313,9 @@ mod tests {
             log_dir_path: PathBuf::from("/logs"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             name: "test-node".to_string(),
             network_id: None,
@@ -276,7 +352,9 @@ mod tests {
             log_dir_path: PathBuf::from("/logs"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             name: "test-node".to_string(),
             network_id: None,
@@ -314,7 +392,9 @@ mod tests {
             log_dir_path: PathBuf::from("/logs"),
             log_format: None,
             max_archived_log_files: Some(10),
+            max_download_rate: None,
             max_log_files: Some(10),
+            max_upload_rate: None,
             metrics_port: None,
             name: "test-node".to_string(),
             network_id: Some(5),
@@ -496,4 +576,55 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn per_node_rate_limit_should_share_the_aggregate_limit() {
+        assert_eq!(get_per_node_rate_limit(None, None, 4), None);
+        assert_eq!(get_per_node_rate_limit(Some(500), None, 4), Some(500));
+        assert_eq!(get_per_node_rate_limit(None, Some(1000), 4), Some(250));
+        assert_eq!(get_per_node_rate_limit(Some(200), Some(1000), 4), Some(200));
+        assert_eq!(get_per_node_rate_limit(Some(500), Some(1000), 4), Some(250));
+    }
+
+    #[test]
+    fn existing_rate_limits_should_be_at_most_the_shared_limit() {
+        let existing = [
+            ("antnode1", Some(200)),
+            ("antnode2", None),
+            ("antnode3", Some(300)),
+        ];
+        assert!(check_existing_rate_limits("download", existing, None).is_ok());
+        assert!(check_existing_rate_limits("download", existing[..1].to_vec(), Some(250)).is_ok());
+
+        let err = check_existing_rate_limits("download", existing, Some(250)).unwrap_err();
+        assert!(err.to_string().contains("antnode2, antnode3"));
+    }
+
+    #[test]
+    fn build_should_assign_the_bandwidth_limits_when_provided() {
+        let mut builder = create_default_builder();
+        builder.max_download_rate = Some(2000);
+        builder.max_upload_rate = Some(1000);
+
+        let result = builder.build().unwrap();
+        let args = result
+            .args
+            .iter()
+            .map(|os| os.to_str().unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(
+            args.windows(2)
+                .any(|w| w == ["--max-download-rate", "2000"])
+        );
+        assert!(args.windows(2).any(|w| w == ["--max-upload-rate", "1000"]));
+    }
+
+    #[test]
+    fn port_range_should_display_as_it_is_parsed() {
+        for input in ["12000", "12000-12009"] {
+            let range = PortRange::parse(input).unwrap();
+            assert_eq!(range.to_string(), input);
+        }
+    }
 }
@@ -200,7 +200,9 @@ pub async fn add_node(
             log_dir_path: service_log_dir_path.clone(),
             log_format: options.log_format,
             max_archived_log_files: options.max_archived_log_files,
+            max_download_rate: options.max_download_rate,
             max_log_files: options.max_log_files,
+            max_upload_rate: options.max_upload_rate,
             metrics_port: metrics_free_port,
             name: service_name.clone(),
             network_id: options.network_id,
@@ -232,6 +234,7 @@ pub async fn add_node(
                         alpha: options.alpha,
                         antnode_path: service_antnode_path,
                         auto_restart: options.auto_restart,
+                        backend: options.backend.clone(),
                         connected_peers: None,
                         data_dir_path: service_data_dir_path.clone(),
                         evm_network: options.evm_network.clone(),
@@ -241,7 +244,9 @@ pub async fn add_node(
                         log_dir_path: service_log_dir_path.clone(),
                         log_format: options.log_format,
                         max_archived_log_files: options.max_archived_log_files,
+                        max_download_rate: options.max_download_rate,
                         max_log_files: options.max_log_files,
+                        max_upload_rate: options.max_upload_rate,
                         metrics_port: metrics_free_port,
                         network_id: options.network_id,
                         node_ip: options.node_ip,
@@ -21,7 +21,10 @@ use ant_service_management::{
     DaemonServiceData, NodeRegistryManager, NodeServiceData, ServiceStatus,
 };
 use ant_service_management::{NatDetectionStatus, error::Result as ServiceControlResult};
-use ant_service_management::{control::ServiceControl, node::NODE_SERVICE_DATA_SCHEMA_LATEST};
+use ant_service_management::{
+    control::{ServiceBackend, ServiceControl},
+    node::NODE_SERVICE_DATA_SCHEMA_LATEST,
+};
 use assert_fs::prelude::*;
 use assert_matches::assert_matches;
 use color_eyre::Result;
@@ -119,7 +122,9 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         name: "antnode1".to_string(),
         network_id: None,
@@ -149,6 +154,7 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -156,7 +162,9 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -261,6 +269,7 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -278,7 +287,9 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -318,6 +329,7 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -325,7 +337,9 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -400,6 +414,7 @@ async fn add_genesis_node_should_return_an_error_if_count_is_greater_than_1() ->
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(3),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -407,7 +422,9 @@ async fn add_genesis_node_should_return_an_error_if_count_is_greater_than_1() ->
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -495,7 +512,9 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -545,7 +564,9 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         log_dir_path: node_logs_dir.to_path_buf().join("antnode2"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode2".to_string(),
@@ -595,7 +616,9 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         log_format: None,
         log_dir_path: node_logs_dir.to_path_buf().join("antnode3"),
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode3".to_string(),
@@ -626,6 +649,7 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(3),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -633,7 +657,9 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -778,7 +804,9 @@ async fn add_node_should_update_the_environment_variables_inside_node_registry()
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -808,6 +836,7 @@ async fn add_node_should_update_the_environment_variables_inside_node_registry()
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -815,7 +844,9 @@ async fn add_node_should_update_the_environment_variables_inside_node_registry()
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -896,6 +927,7 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -913,7 +945,9 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -969,7 +1003,9 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
         log_dir_path: node_logs_dir.to_path_buf().join("antnode2"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode2".to_string(),
@@ -1000,6 +1036,7 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -1007,7 +1044,9 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1159,6 +1198,7 @@ async fn add_node_should_create_service_file_with_first_arg() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -1166,7 +1206,9 @@ async fn add_node_should_create_service_file_with_first_arg() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1310,6 +1352,7 @@ async fn add_node_should_create_service_file_with_peers_args() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -1317,7 +1360,9 @@ async fn add_node_should_create_service_file_with_peers_args() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1456,6 +1501,7 @@ async fn add_node_should_create_service_file_with_local_arg() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -1463,7 +1509,9 @@ async fn add_node_should_create_service_file_with_local_arg() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1606,6 +1654,7 @@ async fn add_node_should_create_service_file_with_network_contacts_url_arg() ->
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -1613,7 +1662,9 @@ async fn add_node_should_create_service_file_with_network_contacts_url_arg() ->
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1752,6 +1803,7 @@ async fn add_node_should_create_service_file_with_ignore_cache_arg() -> Result<(
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -1759,7 +1811,9 @@ async fn add_node_should_create_service_file_with_ignore_cache_arg() -> Result<(
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1899,6 +1953,7 @@ async fn add_node_should_create_service_file_with_custom_bootstrap_cache_path()
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -1907,7 +1962,9 @@ async fn add_node_should_create_service_file_with_custom_bootstrap_cache_path()
             init_peers_config: initial_peers_config.clone(),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2040,6 +2097,7 @@ async fn add_node_should_create_service_file_with_network_id() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2047,7 +2105,9 @@ async fn add_node_should_create_service_file_with_network_id() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: Some(5),
             node_ip: None,
@@ -2179,6 +2239,7 @@ async fn add_node_should_use_custom_ip() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2186,7 +2247,9 @@ async fn add_node_should_use_custom_ip() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: Some(custom_ip),
@@ -2277,7 +2340,9 @@ async fn add_node_should_use_custom_ports_for_one_service() -> Result<()> {
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -2308,6 +2373,7 @@ async fn add_node_should_use_custom_ports_for_one_service() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2315,7 +2381,9 @@ async fn add_node_should_use_custom_ports_for_one_service() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2563,6 +2631,7 @@ async fn add_node_should_use_a_custom_port_range() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(3),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2570,7 +2639,9 @@ async fn add_node_should_use_a_custom_port_range() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2630,6 +2701,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -2647,7 +2719,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
             log_format: None,
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2685,6 +2759,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2692,7 +2767,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2747,6 +2824,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -2764,7 +2842,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
             log_format: None,
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2802,6 +2882,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(3),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2809,7 +2890,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2874,6 +2957,7 @@ async fn add_node_should_return_an_error_if_port_and_node_count_do_not_match() -
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(2),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2881,7 +2965,9 @@ async fn add_node_should_return_an_error_if_port_and_node_count_do_not_match() -
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2951,6 +3037,7 @@ async fn add_node_should_return_an_error_if_multiple_services_are_specified_with
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(2),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -2958,7 +3045,9 @@ async fn add_node_should_return_an_error_if_multiple_services_are_specified_with
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3091,6 +3180,7 @@ async fn add_node_should_set_random_ports_if_enable_metrics_server_is_true() ->
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: true,
@@ -3098,7 +3188,9 @@ async fn add_node_should_set_random_ports_if_enable_metrics_server_is_true() ->
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3223,6 +3315,7 @@ async fn add_node_should_set_max_archived_log_files() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(1),
             delete_antnode_src: false,
             enable_metrics_server: false,
@@ -3230,7 +3323,9 @@ async fn add_node_should_set_max_archived_log_files() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: Some(20),
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3356,6 +3451,7 @@ async fn add_node_should_set_max_log_files() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(1),
             delete_antnode_src: false,
             enable_metrics_server: false,
@@ -3363,7 +3459,9 @@ async fn add_node_should_set_max_log_files() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: Some(20),
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3606,6 +3704,7 @@ async fn add_node_should_use_a_custom_port_range_for_metrics_server() -> Result<
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(3),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -3613,7 +3712,9 @@ async fn add_node_should_use_a_custom_port_range_for_metrics_server() -> Result<
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(PortRange::Range(12000, 12002)),
             network_id: None,
             node_ip: None,
@@ -3671,6 +3772,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -3688,7 +3790,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(12000),
             network_id: None,
             node_ip: None,
@@ -3726,6 +3830,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -3733,7 +3838,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(PortRange::Single(12000)),
             network_id: None,
             node_ip: None,
@@ -3789,6 +3896,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -3806,7 +3914,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(12000),
             network_id: None,
             node_ip: None,
@@ -3844,6 +3954,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(3),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -3851,7 +3962,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(PortRange::Range(12000, 12002)),
             network_id: None,
             node_ip: None,
@@ -4077,6 +4190,7 @@ async fn add_node_should_use_a_custom_port_range_for_the_rpc_server() -> Result<
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(3),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -4084,7 +4198,9 @@ async fn add_node_should_use_a_custom_port_range_for_the_rpc_server() -> Result<
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4152,6 +4268,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -4169,7 +4286,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4207,6 +4326,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -4214,7 +4334,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4270,6 +4392,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
         .push_node(NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -4287,7 +4410,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4325,6 +4450,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(2),
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -4332,7 +4458,9 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4421,7 +4549,9 @@ async fn add_node_should_disable_upnp_and_relay_if_nat_status_is_public() -> Res
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -4451,6 +4581,7 @@ async fn add_node_should_disable_upnp_and_relay_if_nat_status_is_public() -> Res
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: true,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -4458,7 +4589,9 @@ async fn add_node_should_disable_upnp_and_relay_if_nat_status_is_public() -> Res
             relay: true,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4545,7 +4678,9 @@ async fn add_node_should_not_set_no_upnp_if_nat_status_is_upnp() -> Result<()> {
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -4575,6 +4710,7 @@ async fn add_node_should_not_set_no_upnp_if_nat_status_is_upnp() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: true,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -4582,7 +4718,9 @@ async fn add_node_should_not_set_no_upnp_if_nat_status_is_upnp() -> Result<()> {
             relay: true,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4668,7 +4806,9 @@ async fn add_node_should_enable_relay_if_nat_status_is_private() -> Result<()> {
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -4698,6 +4838,7 @@ async fn add_node_should_enable_relay_if_nat_status_is_private() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: true,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -4705,7 +4846,9 @@ async fn add_node_should_enable_relay_if_nat_status_is_private() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4792,7 +4935,9 @@ async fn add_node_should_set_relay_and_no_upnp_if_nat_status_is_none_but_auto_se
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -4822,6 +4967,7 @@ async fn add_node_should_set_relay_and_no_upnp_if_nat_status_is_none_but_auto_se
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: true,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -4829,7 +4975,9 @@ async fn add_node_should_set_relay_and_no_upnp_if_nat_status_is_none_but_auto_se
             relay: true,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5052,7 +5200,9 @@ async fn add_node_should_not_delete_the_source_binary_if_path_arg_is_used() -> R
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -5083,6 +5233,7 @@ async fn add_node_should_not_delete_the_source_binary_if_path_arg_is_used() -> R
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(1),
             delete_antnode_src: false,
             enable_metrics_server: false,
@@ -5090,7 +5241,9 @@ async fn add_node_should_not_delete_the_source_binary_if_path_arg_is_used() -> R
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5176,7 +5329,9 @@ async fn add_node_should_apply_the_relay_flag_if_it_is_used() -> Result<()> {
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -5207,6 +5362,7 @@ async fn add_node_should_apply_the_relay_flag_if_it_is_used() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(1),
             delete_antnode_src: false,
             enable_metrics_server: false,
@@ -5214,7 +5370,9 @@ async fn add_node_should_apply_the_relay_flag_if_it_is_used() -> Result<()> {
             relay: true,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5301,7 +5459,9 @@ async fn add_node_should_add_the_node_in_user_mode() -> Result<()> {
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -5332,6 +5492,7 @@ async fn add_node_should_add_the_node_in_user_mode() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(1),
             delete_antnode_src: false,
             enable_metrics_server: false,
@@ -5339,7 +5500,9 @@ async fn add_node_should_add_the_node_in_user_mode() -> Result<()> {
             relay: true,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5422,7 +5585,9 @@ async fn add_node_should_add_the_node_with_no_upnp_flag() -> Result<()> {
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -5453,6 +5618,7 @@ async fn add_node_should_add_the_node_with_no_upnp_flag() -> Result<()> {
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(1),
             delete_antnode_src: false,
             enable_metrics_server: false,
@@ -5460,7 +5626,9 @@ async fn add_node_should_add_the_node_with_no_upnp_flag() -> Result<()> {
             relay: true,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5583,6 +5751,7 @@ async fn add_node_should_auto_restart() -> Result<()> {
             alpha: false,
             auto_restart: true,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -5590,7 +5759,9 @@ async fn add_node_should_auto_restart() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5676,7 +5847,9 @@ async fn add_node_should_add_the_node_with_write_older_cache_files() -> Result<(
         log_dir_path: node_logs_dir.to_path_buf().join("antnode1"),
         log_format: None,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: None,
         network_id: None,
         name: "antnode1".to_string(),
@@ -5707,6 +5880,7 @@ async fn add_node_should_add_the_node_with_write_older_cache_files() -> Result<(
             alpha: false,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: Some(1),
             delete_antnode_src: false,
             enable_metrics_server: false,
@@ -5714,7 +5888,9 @@ async fn add_node_should_add_the_node_with_write_older_cache_files() -> Result<(
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5848,6 +6024,7 @@ async fn add_node_should_create_service_file_with_alpha_arg() -> Result<()> {
             alpha: true,
             auto_restart: false,
             auto_set_nat_flags: false,
+            backend: ServiceBackend::Native,
             count: None,
             delete_antnode_src: true,
             enable_metrics_server: false,
@@ -5855,7 +6032,9 @@ async fn add_node_should_create_service_file_with_alpha_arg() -> Result<()> {
             relay: false,
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
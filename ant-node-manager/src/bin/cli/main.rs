@@ -14,15 +14,32 @@ use ant_evm::RewardsAddress;
 use ant_logging::{LogBuilder, LogFormat};
 use ant_node_manager::{
     DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S, VerbosityLevel,
-    add_services::config::PortRange,
+    add_services::config::{PortRange, check_existing_rate_limits, get_per_node_rate_limit},
     cmd::{self},
     config,
+    rolling_upgrade::{HealthGate, RollingUpgradeStrategy},
+    rpc::set_request_evm_network,
+    rpc_client::DaemonAuth,
 };
-use ant_service_management::NodeRegistryManager;
-use clap::{Parser, Subcommand};
+use ant_service_management::{
+    NodeRegistryManager,
+    antctl_proto::{
+        AddNodeServicesRequest, NodeServicesRequest, ReconfigureNodeServicesRequest,
+        RemoveNodeServicesRequest, UpgradeNodeServicesRequest,
+    },
+    control::{
+        ServiceBackend,
+        container::{DEFAULT_CONTAINER_IMAGE, DEFAULT_CONTAINER_RUNTIME},
+    },
+};
+use clap::{Args, Parser, Subcommand};
 use color_eyre::{Result, eyre::eyre};
 use libp2p::Multiaddr;
-use std::{net::Ipv4Addr, path::PathBuf};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
 use tracing::Level;
 
 const DEFAULT_NODE_COUNT: u16 = 25;
@@ -38,6 +55,40 @@ pub(crate) struct Cmd {
     #[clap(long)]
     pub crate_version: bool,
 
+    /// Manage the services of a remote machine, through the antctld daemon listening at this
+    /// address.
+    ///
+    /// Supported by the add, logs, metrics, reconfigure, remove, start, status, stop and upgrade
+    /// commands.
+    #[clap(long, global = true)]
+    daemon: Option<SocketAddr>,
+
+    /// The PEM CA certificate the certificate of the daemon is verified with. Enables TLS.
+    #[clap(long, global = true, requires = "daemon")]
+    daemon_ca: Option<PathBuf>,
+
+    /// The PEM certificate presented to the daemon, for mutual TLS.
+    #[clap(long, global = true, requires_all = ["daemon_ca", "daemon_key"])]
+    daemon_cert: Option<PathBuf>,
+
+    /// The name the certificate of the daemon is verified against. Defaults to "antctld".
+    #[clap(long, global = true, requires = "daemon_ca")]
+    daemon_domain: Option<String>,
+
+    /// The PEM key of the certificate presented to the daemon.
+    #[clap(long, global = true, requires = "daemon_cert")]
+    daemon_key: Option<PathBuf>,
+
+    /// The shared token sent to the daemon.
+    #[clap(
+        long,
+        global = true,
+        env = "ANTCTL_DAEMON_TOKEN",
+        hide_env_values = true,
+        requires = "daemon"
+    )]
+    daemon_token: Option<String>,
+
     /// Output debug-level logging to stderr.
     #[clap(long, conflicts_with = "trace")]
     debug: bool,
@@ -78,200 +129,156 @@ pub enum SubCmd {
     /// OpenRC service manager, which is used on Alpine, doesn't support user-mode services. Most
     /// distributions, however, use Systemd, which *does* support user-mode services.
     #[clap(name = "add")]
-    Add {
-        /// Set if you want the service to connect to the alpha network.
-        #[clap(long, default_value_t = false)]
-        alpha: bool,
-        /// Set to automatically restart antnode services upon OS reboot.
-        ///
-        /// If not used, any added services will *not* restart automatically when the OS reboots
-        /// and they will need to be explicitly started again.
-        #[clap(long, default_value_t = false)]
-        auto_restart: bool,
-        /// Auto set NAT flags (--no-upnp or --relay) if our NAT status has been obtained by
-        /// running the NAT detection command.
-        ///
-        /// Using the argument will cause an error if the NAT detection command has not already
-        /// ran.
-        ///
-        /// This will override any --no-upnp or --relay options.
-        #[clap(long, default_value_t = false)]
-        auto_set_nat_flags: bool,
-        /// The number of service instances.
-        ///
-        /// If the --first argument is used, the count has to be one, so --count and --first are
-        /// mutually exclusive.
-        #[clap(long, conflicts_with = "first")]
-        count: Option<u16>,
-        /// Provide the path for the data directory for the installed node.
-        ///
-        /// This path is a prefix. Each installed node will have its own directory underneath it.
-        ///
-        /// If not provided, the default location is platform specific:
-        ///  - Linux/macOS (system-wide): /var/antctl/services
-        ///  - Linux/macOS (user-mode): ~/.local/share/autonomi/node
-        ///  - Windows: C:\ProgramData\antnode\services
-        #[clap(long, verbatim_doc_comment)]
-        data_dir_path: Option<PathBuf>,
-        /// Set this flag to enable the metrics server. The ports will be selected at random.
+    Add(Box<AddArgs>),
+    /// Converge the antnode services towards a declarative fleet spec.
+    ///
+    /// The spec is a TOML file describing the node count of each data directory, their port
+    /// ranges, rewards address, EVM network, log settings, environment variables and version.
+    ///
+    /// The changes are printed, as with the 'plan' command, and confirmed before being applied:
+    /// services are removed, reconfigured, upgraded and added. Added services are started.
+    ///
+    /// On Windows, this command must run as the administrative user. On Linux/macOS, run using
+    /// sudo if you defined system-wide services; otherwise, do not run the command elevated.
+    #[clap(name = "apply")]
+    Apply {
+        /// Set to suppress the confirmation prompt.
+        #[clap(long, short = 'y')]
+        auto_approve: bool,
+        /// The max time in seconds to wait for an added or upgraded node to connect to the
+        /// network.
         ///
-        /// If you're passing the compiled antnode via --path, make sure to enable the open-metrics feature
-        /// when compiling.
+        /// Defaults to 300s.
+        #[clap(long, default_value_t = DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S)]
+        connection_timeout: u64,
+        /// The path of the fleet spec.
+        #[clap(long, short = 'f')]
+        file: PathBuf,
+        /// Add services even if the host would run more nodes than recommended for its
+        /// resources.
+        #[clap(long)]
+        ignore_resource_limit: bool,
+    },
+    /// Get node reward balances.
+    #[clap(name = "balance")]
+    Balance {
+        /// Display the balance for a specific service using its peer ID.
         ///
-        /// If you want to specify the ports, use the --metrics-port argument.
+        /// The argument can be used multiple times.
         #[clap(long)]
-        enable_metrics_server: bool,
+        peer_id: Vec<String>,
+        /// Display the balance for a specific service using its name.
+        ///
+        /// The argument can be used multiple times.
+        #[clap(long, conflicts_with = "peer_id")]
+        service_name: Vec<String>,
+    },
+    #[clap(subcommand)]
+    Daemon(DaemonSubCmd),
+    #[clap(subcommand)]
+    Local(LocalSubCmd),
+    /// Print the log of an antnode service.
+    #[clap(name = "logs")]
+    Logs {
+        /// Set this flag to keep printing the lines appended to the log.
+        #[clap(long, short)]
+        follow: bool,
+        /// The number of lines to print from the end of the log.
+        #[clap(long, short = 'n', default_value_t = 50)]
+        lines: usize,
+        /// The name of the service.
+        #[clap(long)]
+        service_name: String,
+    },
+    /// Print a snapshot of the metrics of the running antnode services.
+    ///
+    /// The metrics are obtained through the RPC service of each node.
+    #[clap(name = "metrics")]
+    Metrics {
+        /// Set this flag to output the metrics as a JSON document
+        #[clap(long)]
+        json: bool,
+        /// The name of the service to get the metrics of.
+        ///
+        /// The argument can be used multiple times. All the running services are used if it is
+        /// not.
+        #[clap(long)]
+        service_name: Vec<String>,
+    },
+    #[clap(subcommand)]
+    NatDetection(NatDetectionSubCmd),
+    /// Print the changes 'apply' would make for the services to match a fleet spec.
+    #[clap(name = "plan")]
+    Plan {
+        /// The path of the fleet spec.
+        #[clap(long, short = 'f')]
+        file: PathBuf,
+    },
+    /// Change the settings of existing antnode service(s) in place.
+    ///
+    /// The service definitions are re-installed with the new settings, keeping the data directory
+    /// and peer ID of each node. Only services whose settings change are re-installed, and only
+    /// those that were running are restarted.
+    ///
+    /// If no peer ID(s) or service name(s) are supplied, all services will be reconfigured. Ports
+    /// can only be changed for a single service.
+    ///
+    /// On Windows, this command must run as the administrative user. On Linux/macOS, run using
+    /// sudo if you defined system-wide services; otherwise, do not run the command elevated.
+    #[clap(name = "reconfigure")]
+    Reconfigure {
+        /// Set whether the services should be restarted automatically by the service manager.
+        #[clap(long)]
+        auto_restart: Option<bool>,
         /// Provide environment variables for the antnode service.
         ///
-        /// Useful to set log levels. Variables should be comma separated without spaces.
+        /// Otherwise, the environment variables of the registry are used.
         ///
         /// Example: --env ANT_LOG=all,RUST_LOG=libp2p=debug
         #[clap(name = "env", long, use_value_delimiter = false, value_parser = parse_environment_variables)]
         env_variables: Option<Vec<(String, String)>>,
-        /// Specify what EVM network to use for payments.
-        #[command(subcommand)]
-        evm_network: EvmNetworkCommand,
-        /// Set this flag if UPnP doesn't work, and you are not able to manually port forward.
-        #[clap(long)]
-        relay: bool,
-        /// Provide the path for the log directory for the installed node.
-        ///
-        /// This path is a prefix. Each installed node will have its own directory underneath it.
-        ///
-        /// If not provided, the default location is platform specific:
-        ///  - Linux/macOS (system-wide): /var/log/antnode
-        ///  - Linux/macOS (user-mode): ~/.local/share/autonomi/node/*/logs
-        ///  - Windows: C:\ProgramData\antnode\logs
-        #[clap(long, verbatim_doc_comment)]
-        log_dir_path: Option<PathBuf>,
         /// Specify the logging format for started nodes.
         ///
         /// Valid values are "default" or "json".
-        ///
-        /// If the argument is not used, the default format will be applied.
         #[clap(long, value_parser = LogFormat::parse_from_str, verbatim_doc_comment)]
         log_format: Option<LogFormat>,
-        /// Specify the maximum number of uncompressed log files to store.
-        ///
-        /// After reaching this limit, the older files are archived to save space.
-        /// You can also specify the maximum number of archived log files to keep.
-        #[clap(long, verbatim_doc_comment)]
-        max_log_files: Option<usize>,
         /// Specify the maximum number of archived log files to store.
-        ///
-        /// After reaching this limit, the older archived files are deleted.
-        #[clap(long, verbatim_doc_comment)]
+        #[clap(long)]
         max_archived_log_files: Option<usize>,
-        /// Specify a port for the open metrics server.
-        ///
-        /// If you're passing the compiled antnode via --node-path, make sure to enable the open-metrics feature
-        /// when compiling.
-        ///
-        /// If not set, metrics server will not be started. Use --enable-metrics-server to start
-        /// the metrics server without specifying a port.
-        ///
-        /// If multiple services are being added and this argument is used, you must specify a
-        /// range. For example, '12000-12004'. The length of the range must match the number of
-        /// services, which in this case would be 5. The range must also go from lower to higher.
-        #[clap(long, value_parser = PortRange::parse)]
-        metrics_port: Option<PortRange>,
-        /// Specify the network ID to use for the services. This will allow you to run the node on a different network.
-        ///
-        /// By default, the network ID is set to 1, which represents the mainnet.
-        #[clap(long, verbatim_doc_comment)]
-        network_id: Option<u8>,
-        /// Specify the IP address for the antnode service(s).
-        ///
-        /// If not set, we bind to all the available network interfaces.
+        /// Specify the maximum number of uncompressed log files to store.
         #[clap(long)]
-        node_ip: Option<Ipv4Addr>,
-        /// Specify a port for the antnode service(s).
-        ///
-        /// If not used, ports will be selected at random.
-        ///
-        /// If multiple services are being added and this argument is used, you must specify a
-        /// range. For example, '12000-12004'. The length of the range must match the number of
-        /// services, which in this case would be 5. The range must also go from lower to higher.
-        #[clap(long, value_parser = PortRange::parse)]
-        node_port: Option<PortRange>,
-        /// Provide a path for the antnode binary to be used by the service.
-        ///
-        /// Useful for creating the service using a custom built binary.
+        max_log_files: Option<usize>,
+        /// Specify the port for the metrics server.
         #[clap(long)]
-        path: Option<PathBuf>,
-        #[command(flatten)]
-        peers: InitialPeersConfig,
-        /// Specify the wallet address that will receive the node's earnings.
+        metrics_port: Option<u16>,
+        /// Set whether UPnP should be disabled.
         #[clap(long)]
-        rewards_address: RewardsAddress,
-        /// Specify an Ipv4Addr for the node's RPC server to run on.
-        ///
-        /// Useful if you want to expose the RPC server pubilcly. Ports are assigned automatically.
-        ///
-        /// If not set, the RPC server is run locally.
+        no_upnp: Option<bool>,
+        /// Specify the port the node should listen on.
         #[clap(long)]
-        rpc_address: Option<Ipv4Addr>,
-        /// Specify a port for the RPC service(s).
-        ///
-        /// If not used, ports will be selected at random.
-        ///
-        /// If multiple services are being added and this argument is used, you must specify a
-        /// range. For example, '12000-12004'. The length of the range must match the number of
-        /// services, which in this case would be 5. The range must also go from lower to higher.
-        #[clap(long, value_parser = PortRange::parse)]
-        rpc_port: Option<PortRange>,
-        /// Disables UPnP.
-        ///
-        /// By default, antnode will try to use UPnP if available. Use this flag to disable UPnP.
-        #[clap(long, default_value_t = false)]
-        no_upnp: bool,
-        /// Provide a antnode binary using a URL.
-        ///
-        /// The binary must be inside a zip or gzipped tar archive.
-        ///
-        /// This option can be used to test a antnode binary that has been built from a forked
-        /// branch and uploaded somewhere. A typical use case would be for a developer who launches
-        /// a testnet to test some changes they have on a fork.
-        #[clap(long, conflicts_with = "version")]
-        url: Option<String>,
-        /// The user the service should run as.
-        ///
-        /// If the account does not exist, it will be created.
+        node_port: Option<u16>,
+        /// The peer ID of the service to reconfigure.
         ///
-        /// On Windows this argument will have no effect.
+        /// The argument can be used multiple times to reconfigure many services.
         #[clap(long)]
-        user: Option<String>,
-        /// Provide a specific version of antnode to be installed.
-        ///
-        /// The version number should be in the form X.Y.Z, with no 'v' prefix.
-        ///
-        /// The binary will be downloaded.
+        peer_id: Vec<String>,
+        /// Set whether the nodes should run in relay mode.
         #[clap(long)]
-        version: Option<String>,
-        /// Set this to true if you want the node to write the cache files in the older formats.
-        #[clap(long, default_value_t = false)]
-        write_older_cache_files: bool,
-    },
-    /// Get node reward balances.
-    #[clap(name = "balance")]
-    Balance {
-        /// Display the balance for a specific service using its peer ID.
-        ///
-        /// The argument can be used multiple times.
+        relay: Option<bool>,
+        /// Specify the wallet address that will receive the nodes' earnings.
         #[clap(long)]
-        peer_id: Vec<String>,
-        /// Display the balance for a specific service using its name.
+        rewards_address: Option<RewardsAddress>,
+        /// Specify the port for the RPC service.
+        #[clap(long)]
+        rpc_port: Option<u16>,
+        /// The name of the service to reconfigure.
         ///
-        /// The argument can be used multiple times.
+        /// The argument can be used multiple times to reconfigure many services.
         #[clap(long, conflicts_with = "peer_id")]
         service_name: Vec<String>,
     },
     #[clap(subcommand)]
-    Daemon(DaemonSubCmd),
-    #[clap(subcommand)]
-    Local(LocalSubCmd),
-    #[clap(subcommand)]
-    NatDetection(NatDetectionSubCmd),
+    Registry(RegistrySubCmd),
     /// Remove antnode service(s).
     ///
     /// If no peer ID(s) or service name(s) are supplied, all services will be removed.
@@ -308,6 +315,27 @@ pub enum SubCmd {
         #[clap(long, short)]
         force: bool,
     },
+    /// Print the resources of the host and the maximum number of nodes recommended for it.
+    ///
+    /// The recommendation accounts for the memory, CPU cores and free disk space, along with the
+    /// bandwidth if it is probed. Adding services beyond it requires --ignore-resource-limit.
+    #[clap(name = "resources")]
+    Resources {
+        /// Measure the bandwidth by downloading from this URL for a few seconds.
+        ///
+        /// A large file should be used.
+        #[clap(long)]
+        bandwidth_probe_url: Option<String>,
+        /// The path of the data directory of the nodes, to measure the free space of its drive.
+        ///
+        /// Defaults to the data directories of the existing nodes, one per drive, or to the one
+        /// used by the 'add' command if there are no nodes.
+        #[clap(long)]
+        data_dir_path: Option<PathBuf>,
+        /// Set this flag to output the report as a JSON document
+        #[clap(long)]
+        json: bool,
+    },
     /// Start antnode service(s).
     ///
     /// By default, each node service is started after the previous node has successfully connected to the network or
@@ -398,6 +426,13 @@ pub enum SubCmd {
     /// sudo if you defined system-wide services; otherwise, do not run the command elevated.
     #[clap(name = "upgrade")]
     Upgrade {
+        /// The number of services upgraded together in each stage, after any canaries.
+        #[clap(long, default_value_t = 1)]
+        batch_size: usize,
+        /// The percentage of the services to upgrade first, as canaries, before the remaining
+        /// services are upgraded in batches.
+        #[clap(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+        canary_percentage: u8,
         /// The max time in seconds to wait for a node to connect to the network. If the node does not connect to the
         /// network within this time, the node is considered failed.
         ///
@@ -427,6 +462,17 @@ pub enum SubCmd {
         /// Required if we want to downgrade, or for testing purposes.
         #[clap(long)]
         force: bool,
+        /// Set this flag to gate each stage of the upgrade on the health of the services it
+        /// upgraded.
+        ///
+        /// The health is read from the RPC service of each node: its connected peers, the size of
+        /// its routing table and the records it stores. Services that do not become healthy are
+        /// rolled back to their previous binary and the remaining stages are not upgraded.
+        #[clap(long)]
+        health_check: bool,
+        /// The max time in seconds to wait for an upgraded service to become healthy.
+        #[clap(long, default_value_t = DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S, requires = "health_check")]
+        health_timeout: u64,
         /// An interval applied between upgrading each service.
         ///
         /// Use connection-timeout to scale the interval automatically. This argument is mutually exclusive with the
@@ -435,6 +481,16 @@ pub enum SubCmd {
         /// Units are milliseconds.
         #[clap(long, conflicts_with = "connection_timeout")]
         interval: Option<u64>,
+        /// The minimum number of connected peers for an upgraded service to be healthy.
+        #[clap(long, requires = "health_check")]
+        min_connected_peers: Option<usize>,
+        /// The share of the records stored before the upgrade that an upgraded service must store
+        /// again to be healthy, between 0 and 1.
+        #[clap(long, requires = "health_check", value_parser = parse_ratio)]
+        min_records_ratio: Option<f64>,
+        /// The minimum number of peers in the routing table for an upgraded service to be healthy.
+        #[clap(long, requires = "health_check")]
+        min_routing_table_size: Option<usize>,
         /// Provide a path for the antnode binary to be used by the service.
         ///
         /// Useful for upgrading the service using a custom built binary.
@@ -461,6 +517,230 @@ pub enum SubCmd {
     },
 }
 
+#[derive(Args, Debug)]
+pub struct AddArgs {
+    /// Set if you want the service to connect to the alpha network.
+    #[clap(long, default_value_t = false)]
+    alpha: bool,
+    /// Set to automatically restart antnode services upon OS reboot.
+    ///
+    /// If not used, any added services will *not* restart automatically when the OS reboots
+    /// and they will need to be explicitly started again.
+    #[clap(long, default_value_t = false)]
+    auto_restart: bool,
+    /// Auto set NAT flags (--no-upnp or --relay) if our NAT status has been obtained by
+    /// running the NAT detection command.
+    ///
+    /// Using the argument will cause an error if the NAT detection command has not already
+    /// ran.
+    ///
+    /// This will override any --no-upnp or --relay options.
+    #[clap(long, default_value_t = false)]
+    auto_set_nat_flags: bool,
+    /// How the services are run.
+    ///
+    /// Possible values:
+    ///  - native: as services of the service manager of the operating system.
+    ///  - process: as plain processes launched by antctl, which need no service manager. They
+    ///    are only restarted if they exit while the antctl daemon is running.
+    ///  - container: in OCI containers, using the image and runtime from --container-image and
+    ///    --container-runtime. The containers use the network of the host.
+    #[clap(long, default_value = "native", value_parser = ["native", "process", "container"], verbatim_doc_comment)]
+    backend: String,
+    /// The image of the containers used by the container backend.
+    ///
+    /// The image must be able to run the antnode binary, which is mounted from the host.
+    #[clap(long, default_value = DEFAULT_CONTAINER_IMAGE)]
+    container_image: String,
+    /// The container runtime used by the container backend, e.g., docker or podman.
+    #[clap(long, default_value = DEFAULT_CONTAINER_RUNTIME)]
+    container_runtime: String,
+    /// The number of service instances.
+    ///
+    /// If the --first argument is used, the count has to be one, so --count and --first are
+    /// mutually exclusive.
+    #[clap(long, conflicts_with = "first")]
+    count: Option<u16>,
+    /// Provide the path for the data directory for the installed node.
+    ///
+    /// This path is a prefix. Each installed node will have its own directory underneath it.
+    ///
+    /// If not provided, the default location is platform specific:
+    ///  - Linux/macOS (system-wide): /var/antctl/services
+    ///  - Linux/macOS (user-mode): ~/.local/share/autonomi/node
+    ///  - Windows: C:\ProgramData\antnode\services
+    #[clap(long, verbatim_doc_comment)]
+    data_dir_path: Option<PathBuf>,
+    /// Set this flag to enable the metrics server. The ports will be selected at random.
+    ///
+    /// If you're passing the compiled antnode via --path, make sure to enable the open-metrics feature
+    /// when compiling.
+    ///
+    /// If you want to specify the ports, use the --metrics-port argument.
+    #[clap(long)]
+    enable_metrics_server: bool,
+    /// Provide environment variables for the antnode service.
+    ///
+    /// Useful to set log levels. Variables should be comma separated without spaces.
+    ///
+    /// Example: --env ANT_LOG=all,RUST_LOG=libp2p=debug
+    #[clap(name = "env", long, use_value_delimiter = false, value_parser = parse_environment_variables)]
+    env_variables: Option<Vec<(String, String)>>,
+    /// Specify what EVM network to use for payments.
+    #[command(subcommand)]
+    evm_network: EvmNetworkCommand,
+    /// Add the services even if the host would run more nodes than recommended for its
+    /// resources.
+    ///
+    /// The 'resources' command prints the recommended number of nodes.
+    #[clap(long)]
+    ignore_resource_limit: bool,
+    /// Set this flag if UPnP doesn't work, and you are not able to manually port forward.
+    #[clap(long)]
+    relay: bool,
+    /// Provide the path for the log directory for the installed node.
+    ///
+    /// This path is a prefix. Each installed node will have its own directory underneath it.
+    ///
+    /// If not provided, the default location is platform specific:
+    ///  - Linux/macOS (system-wide): /var/log/antnode
+    ///  - Linux/macOS (user-mode): ~/.local/share/autonomi/node/*/logs
+    ///  - Windows: C:\ProgramData\antnode\logs
+    #[clap(long, verbatim_doc_comment)]
+    log_dir_path: Option<PathBuf>,
+    /// Specify the logging format for started nodes.
+    ///
+    /// Valid values are "default" or "json".
+    ///
+    /// If the argument is not used, the default format will be applied.
+    #[clap(long, value_parser = LogFormat::parse_from_str, verbatim_doc_comment)]
+    log_format: Option<LogFormat>,
+    /// Specify the maximum number of uncompressed log files to store.
+    ///
+    /// After reaching this limit, the older files are archived to save space.
+    /// You can also specify the maximum number of archived log files to keep.
+    #[clap(long, verbatim_doc_comment)]
+    max_log_files: Option<usize>,
+    /// Specify the maximum number of archived log files to store.
+    ///
+    /// After reaching this limit, the older archived files are deleted.
+    #[clap(long, verbatim_doc_comment)]
+    max_archived_log_files: Option<usize>,
+    /// Specify the maximum download rate of each service, in kilobytes per second.
+    ///
+    /// Replication traffic is scaled down to leave room for client traffic within this budget.
+    #[clap(long, verbatim_doc_comment)]
+    max_download_rate: Option<u64>,
+    /// Specify the maximum upload rate of each service, in kilobytes per second.
+    #[clap(long, verbatim_doc_comment)]
+    max_upload_rate: Option<u64>,
+    /// Specify the maximum download rate of all the services together, in kilobytes per
+    /// second.
+    ///
+    /// The rate is shared evenly between these services and the ones added before. The add is
+    /// refused if any service added before is not already limited to its share. If
+    /// --max-download-rate is also used, the lowest of the two applies.
+    #[clap(long, verbatim_doc_comment)]
+    total_download_rate: Option<u64>,
+    /// Specify the maximum upload rate of all the services together, in kilobytes per
+    /// second.
+    ///
+    /// The rate is shared evenly between these services and the ones added before. The add is
+    /// refused if any service added before is not already limited to its share. If
+    /// --max-upload-rate is also used, the lowest of the two applies.
+    #[clap(long, verbatim_doc_comment)]
+    total_upload_rate: Option<u64>,
+    /// Specify a port for the open metrics server.
+    ///
+    /// If you're passing the compiled antnode via --node-path, make sure to enable the open-metrics feature
+    /// when compiling.
+    ///
+    /// If not set, metrics server will not be started. Use --enable-metrics-server to start
+    /// the metrics server without specifying a port.
+    ///
+    /// If multiple services are being added and this argument is used, you must specify a
+    /// range. For example, '12000-12004'. The length of the range must match the number of
+    /// services, which in this case would be 5. The range must also go from lower to higher.
+    #[clap(long, value_parser = PortRange::parse)]
+    metrics_port: Option<PortRange>,
+    /// Specify the network ID to use for the services. This will allow you to run the node on a different network.
+    ///
+    /// By default, the network ID is set to 1, which represents the mainnet.
+    #[clap(long, verbatim_doc_comment)]
+    network_id: Option<u8>,
+    /// Specify the IP address for the antnode service(s).
+    ///
+    /// If not set, we bind to all the available network interfaces.
+    #[clap(long)]
+    node_ip: Option<Ipv4Addr>,
+    /// Specify a port for the antnode service(s).
+    ///
+    /// If not used, ports will be selected at random.
+    ///
+    /// If multiple services are being added and this argument is used, you must specify a
+    /// range. For example, '12000-12004'. The length of the range must match the number of
+    /// services, which in this case would be 5. The range must also go from lower to higher.
+    #[clap(long, value_parser = PortRange::parse)]
+    node_port: Option<PortRange>,
+    /// Provide a path for the antnode binary to be used by the service.
+    ///
+    /// Useful for creating the service using a custom built binary.
+    #[clap(long)]
+    path: Option<PathBuf>,
+    #[command(flatten)]
+    peers: InitialPeersConfig,
+    /// Specify the wallet address that will receive the node's earnings.
+    #[clap(long)]
+    rewards_address: RewardsAddress,
+    /// Specify an Ipv4Addr for the node's RPC server to run on.
+    ///
+    /// Useful if you want to expose the RPC server pubilcly. Ports are assigned automatically.
+    ///
+    /// If not set, the RPC server is run locally.
+    #[clap(long)]
+    rpc_address: Option<Ipv4Addr>,
+    /// Specify a port for the RPC service(s).
+    ///
+    /// If not used, ports will be selected at random.
+    ///
+    /// If multiple services are being added and this argument is used, you must specify a
+    /// range. For example, '12000-12004'. The length of the range must match the number of
+    /// services, which in this case would be 5. The range must also go from lower to higher.
+    #[clap(long, value_parser = PortRange::parse)]
+    rpc_port: Option<PortRange>,
+    /// Disables UPnP.
+    ///
+    /// By default, antnode will try to use UPnP if available. Use this flag to disable UPnP.
+    #[clap(long, default_value_t = false)]
+    no_upnp: bool,
+    /// Provide a antnode binary using a URL.
+    ///
+    /// The binary must be inside a zip or gzipped tar archive.
+    ///
+    /// This option can be used to test a antnode binary that has been built from a forked
+    /// branch and uploaded somewhere. A typical use case would be for a developer who launches
+    /// a testnet to test some changes they have on a fork.
+    #[clap(long, conflicts_with = "version")]
+    url: Option<String>,
+    /// The user the service should run as.
+    ///
+    /// If the account does not exist, it will be created.
+    ///
+    /// On Windows this argument will have no effect.
+    #[clap(long)]
+    user: Option<String>,
+    /// Provide a specific version of antnode to be installed.
+    ///
+    /// The version number should be in the form X.Y.Z, with no 'v' prefix.
+    ///
+    /// The binary will be downloaded.
+    #[clap(long)]
+    version: Option<String>,
+    /// Set this to true if you want the node to write the cache files in the older formats.
+    #[clap(long, default_value_t = false)]
+    write_older_cache_files: bool,
+}
+
 /// Manage the RPC service.
 #[derive(Subcommand, Debug)]
 pub enum DaemonSubCmd {
@@ -780,6 +1060,47 @@ pub enum LocalSubCmd {
     },
 }
 
+/// Manage the history of the node registry.
+///
+/// A snapshot of the registry is kept each time a command changes the configuration of the
+/// services. The most recent 20 snapshots are kept.
+#[derive(Subcommand, Debug)]
+pub enum RegistrySubCmd {
+    /// Print the differences between a snapshot and the current registry.
+    #[clap(name = "diff")]
+    Diff {
+        /// The ID of the snapshot.
+        id: u64,
+        /// Compare with this snapshot rather than the current registry.
+        other_id: Option<u64>,
+    },
+    /// List the snapshots of the registry.
+    #[clap(name = "history")]
+    History {},
+    /// Add the antnode services installed on this host but missing from the registry.
+    ///
+    /// The entries are rebuilt from the service definitions and the data directories of the
+    /// nodes. Services in the registry without a definition are reported, but not removed.
+    ///
+    /// Run using sudo if you defined system-wide services.
+    #[clap(name = "reconcile")]
+    Reconcile {},
+    /// Replace the registry with one of its snapshots.
+    ///
+    /// The services themselves are not changed. The registry being replaced is kept as a
+    /// snapshot, so the restore can be undone.
+    ///
+    /// Run using sudo if you defined system-wide services.
+    #[clap(name = "restore")]
+    Restore {
+        /// Set to suppress the confirmation prompt.
+        #[clap(long, short)]
+        force: bool,
+        /// The ID of the snapshot.
+        id: u64,
+    },
+}
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
     color_eyre::install()?;
@@ -825,50 +1146,80 @@ async fn main() -> Result<()> {
 
     tracing::info!("Executing cmd: {:?}", args.cmd);
 
+    if let Some(daemon_addr) = args.daemon {
+        let auth = DaemonAuth {
+            token: args.daemon_token,
+            ca_cert_path: args.daemon_ca,
+            client_cert_path: args.daemon_cert,
+            client_key_path: args.daemon_key,
+            domain_name: args.daemon_domain,
+        };
+        return run_remote(args.cmd, daemon_addr, &auth, verbosity).await;
+    }
+
     let node_registry = NodeRegistryManager::load(&config::get_node_registry_path()?).await?;
     match args.cmd {
-        Some(SubCmd::Add {
-            alpha,
-            auto_restart,
-            auto_set_nat_flags,
-            count,
-            data_dir_path,
-            enable_metrics_server,
-            env_variables,
-            evm_network,
-            relay,
-            log_dir_path,
-            log_format,
-            max_archived_log_files,
-            max_log_files,
-            metrics_port,
-            network_id,
-            node_ip,
-            node_port,
-            path,
-            peers,
-            rewards_address,
-            rpc_address,
-            rpc_port,
-            url,
-            no_upnp,
-            user,
-            version,
-            write_older_cache_files,
-        }) => {
+        Some(SubCmd::Add(add_args)) => {
+            let AddArgs {
+                alpha,
+                auto_restart,
+                auto_set_nat_flags,
+                backend,
+                container_image,
+                container_runtime,
+                count,
+                data_dir_path,
+                enable_metrics_server,
+                env_variables,
+                evm_network,
+                ignore_resource_limit,
+                relay,
+                log_dir_path,
+                log_format,
+                max_archived_log_files,
+                max_download_rate,
+                max_log_files,
+                max_upload_rate,
+                metrics_port,
+                network_id,
+                node_ip,
+                node_port,
+                path,
+                peers,
+                rewards_address,
+                rpc_address,
+                rpc_port,
+                total_download_rate,
+                total_upload_rate,
+                url,
+                no_upnp,
+                user,
+                version,
+                write_older_cache_files,
+            } = *add_args;
+            let (max_download_rate, max_upload_rate) = get_rate_limits(
+                &cmd::node::get_active_node_rate_limits(&node_registry).await,
+                count.unwrap_or(1),
+                (max_download_rate, total_download_rate),
+                (max_upload_rate, total_upload_rate),
+            )?;
             cmd::node::add(
                 alpha,
                 auto_restart,
                 auto_set_nat_flags,
+                ServiceBackend::parse(&backend, Some(container_image), Some(container_runtime))?,
                 count,
                 data_dir_path,
                 enable_metrics_server,
                 env_variables,
                 Some(evm_network.try_into()?),
+                ignore_resource_limit,
                 log_dir_path,
                 log_format,
                 max_archived_log_files,
+                max_download_rate,
                 max_log_files,
+                max_upload_rate,
                 metrics_port,
                 network_id,
                 node_ip,
@@ -890,6 +1241,22 @@ async fn main() -> Result<()> {
             .await?;
             Ok(())
         }
+        Some(SubCmd::Apply {
+            auto_approve,
+            connection_timeout,
+            file,
+            ignore_resource_limit,
+        }) => {
+            cmd::fleet::apply(
+                auto_approve,
+                connection_timeout,
+                ignore_resource_limit,
+                &file,
+                node_registry,
+                verbosity,
+            )
+            .await
+        }
         Some(SubCmd::Balance {
             peer_id: peer_ids,
             service_name: service_names,
@@ -992,6 +1359,14 @@ async fn main() -> Result<()> {
                 json,
             } => cmd::local::status(details, fail, json).await,
         },
+        Some(SubCmd::Logs {
+            follow,
+            lines,
+            service_name,
+        }) => cmd::node::logs(follow, lines, node_registry, service_name).await,
+        Some(SubCmd::Metrics { json, service_name }) => {
+            cmd::node::metrics(json, node_registry, service_name, verbosity).await
+        }
         Some(SubCmd::NatDetection(NatDetectionSubCmd::Run {
             path,
             servers,
@@ -1001,6 +1376,54 @@ async fn main() -> Result<()> {
             cmd::nat_detection::run_nat_detection(servers, true, path, url, version, verbosity)
                 .await
         }
+        Some(SubCmd::Plan { file }) => cmd::fleet::plan(&file, node_registry, verbosity).await,
+        Some(SubCmd::Reconfigure {
+            auto_restart,
+            env_variables,
+            log_format,
+            max_archived_log_files,
+            max_log_files,
+            metrics_port,
+            no_upnp,
+            node_port,
+            peer_id: peer_ids,
+            relay,
+            rewards_address,
+            rpc_port,
+            service_name: service_names,
+        }) => {
+            cmd::node::reconfigure(
+                auto_restart,
+                env_variables,
+                false,
+                log_format,
+                max_archived_log_files,
+                max_log_files,
+                metrics_port,
+                no_upnp,
+                node_port,
+                node_registry,
+                peer_ids,
+                relay,
+                rewards_address,
+                rpc_port,
+                service_names,
+                verbosity,
+            )
+            .await
+        }
+        Some(SubCmd::Registry(RegistrySubCmd::Diff { id, other_id })) => {
+            cmd::registry::diff(id, other_id, node_registry, verbosity)
+        }
+        Some(SubCmd::Registry(RegistrySubCmd::History {})) => {
+            cmd::registry::history(node_registry, verbosity)
+        }
+        Some(SubCmd::Registry(RegistrySubCmd::Reconcile {})) => {
+            cmd::registry::reconcile(node_registry, verbosity).await
+        }
+        Some(SubCmd::Registry(RegistrySubCmd::Restore { force, id })) => {
+            cmd::registry::restore(force, id, node_registry, verbosity).await
+        }
         Some(SubCmd::Remove {
             keep_directories,
             peer_id: peer_ids,
@@ -1016,6 +1439,20 @@ async fn main() -> Result<()> {
             .await
         }
         Some(SubCmd::Reset { force }) => cmd::node::reset(force, node_registry, verbosity).await,
+        Some(SubCmd::Resources {
+            bandwidth_probe_url,
+            data_dir_path,
+            json,
+        }) => {
+            cmd::node::resources(
+                bandwidth_probe_url,
+                data_dir_path,
+                json,
+                node_registry,
+                verbosity,
+            )
+            .await
+        }
         Some(SubCmd::Start {
             connection_timeout,
             interval,
@@ -1025,6 +1462,7 @@ async fn main() -> Result<()> {
             cmd::node::start(
                 connection_timeout,
                 interval,
+                false,
                 node_registry,
                 peer_ids,
                 service_names,
@@ -1043,10 +1481,17 @@ async fn main() -> Result<()> {
             service_name: service_names,
         }) => cmd::node::stop(interval, node_registry, peer_ids, service_names, verbosity).await,
         Some(SubCmd::Upgrade {
+            batch_size,
+            canary_percentage,
             connection_timeout,
             do_not_start,
             force,
+            health_check,
+            health_timeout,
             interval,
+            min_connected_peers,
+            min_records_ratio,
+            min_routing_table_size,
             path,
             peer_id: peer_ids,
             service_name: service_names,
@@ -1054,16 +1499,33 @@ async fn main() -> Result<()> {
             url,
             version,
         }) => {
+            let health_gate = health_check.then(|| {
+                let default = HealthGate::default();
+                HealthGate {
+                    min_connected_peers: min_connected_peers.unwrap_or(default.min_connected_peers),
+                    min_routing_table_size: min_routing_table_size
+                        .unwrap_or(default.min_routing_table_size),
+                    min_records_ratio: min_records_ratio.unwrap_or(default.min_records_ratio),
+                    timeout: Duration::from_secs(health_timeout),
+                }
+            });
+            let strategy = RollingUpgradeStrategy {
+                canary_percentage,
+                batch_size,
+                health_gate,
+            };
             cmd::node::upgrade(
                 connection_timeout,
                 do_not_start,
                 path,
                 force,
                 interval,
+                false,
                 node_registry,
                 peer_ids,
                 provided_env_variable,
                 service_names,
+                strategy,
                 url,
                 version,
                 verbosity,
@@ -1074,6 +1536,315 @@ async fn main() -> Result<()> {
     }
 }
 
+/// Get the download and upload rate limits of each service to add, given the per-node and the
+/// aggregate limits of each direction. The aggregate limits are shared with the services added
+/// before, which must already be limited to their share.
+fn get_rate_limits(
+    existing: &[(String, Option<u64>, Option<u64>)],
+    count: u16,
+    (max_download_rate, total_download_rate): (Option<u64>, Option<u64>),
+    (max_upload_rate, total_upload_rate): (Option<u64>, Option<u64>),
+) -> Result<(Option<u64>, Option<u64>)> {
+    let node_count = existing.len().saturating_add(usize::from(count));
+    let node_count = u16::try_from(node_count).unwrap_or(u16::MAX);
+    let download_rate = get_per_node_rate_limit(max_download_rate, total_download_rate, node_count);
+    let upload_rate = get_per_node_rate_limit(max_upload_rate, total_upload_rate, node_count);
+    check_existing_rate_limits(
+        "download",
+        existing
+            .iter()
+            .map(|(service_name, rate, _)| (service_name.as_str(), *rate)),
+        total_download_rate.and(download_rate),
+    )?;
+    check_existing_rate_limits(
+        "upload",
+        existing
+            .iter()
+            .map(|(service_name, _, rate)| (service_name.as_str(), *rate)),
+        total_upload_rate.and(upload_rate),
+    )?;
+    Ok((download_rate, upload_rate))
+}
+
+/// Run the command against the daemon of a remote machine.
+async fn run_remote(
+    cmd: Option<SubCmd>,
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    match cmd {
+        Some(SubCmd::Add(add_args)) => {
+            let AddArgs {
+                alpha,
+                auto_restart,
+                auto_set_nat_flags,
+                backend,
+                container_image,
+                container_runtime,
+                count,
+                data_dir_path,
+                enable_metrics_server,
+                env_variables,
+                evm_network,
+                ignore_resource_limit,
+                relay,
+                log_dir_path,
+                log_format,
+                max_archived_log_files,
+                max_download_rate,
+                max_log_files,
+                max_upload_rate,
+                metrics_port,
+                network_id,
+                node_ip,
+                node_port,
+                path,
+                peers,
+                rewards_address,
+                rpc_address,
+                rpc_port,
+                total_download_rate,
+                total_upload_rate,
+                url,
+                no_upnp,
+                user,
+                version,
+                write_older_cache_files,
+            } = *add_args;
+            if path.is_some() || url.is_some() {
+                return Err(eyre!(
+                    "A binary path or URL cannot be used with --daemon; use --version instead"
+                ));
+            }
+            if auto_set_nat_flags {
+                return Err(eyre!("--auto-set-nat-flags cannot be used with --daemon"));
+            }
+            let node_count = count.unwrap_or(1);
+            let existing_rate_limits =
+                if total_download_rate.is_some() || total_upload_rate.is_some() {
+                    cmd::remote::active_node_rate_limits(addr, auth).await?
+                } else {
+                    Vec::new()
+                };
+            let (max_download_rate, max_upload_rate) = get_rate_limits(
+                &existing_rate_limits,
+                node_count,
+                (max_download_rate, total_download_rate),
+                (max_upload_rate, total_upload_rate),
+            )?;
+            let mut request = AddNodeServicesRequest {
+                count: node_count as u32,
+                rewards_address: rewards_address.to_string(),
+                data_dir_path: data_dir_path.map(|path| path.to_string_lossy().to_string()),
+                log_dir_path: log_dir_path.map(|path| path.to_string_lossy().to_string()),
+                node_port: node_port.map(|port| port.to_string()),
+                metrics_port: metrics_port.map(|port| port.to_string()),
+                rpc_port: rpc_port.map(|port| port.to_string()),
+                version,
+                initial_peers: peers.addrs.iter().map(|addr| addr.to_string()).collect(),
+                network_id: network_id.map(u32::from),
+                log_format: log_format.map(|format| format.as_str().to_string()),
+                alpha,
+                auto_restart,
+                enable_metrics_server,
+                no_upnp,
+                relay,
+                user,
+                node_ip: node_ip.map(|ip| ip.to_string()),
+                rpc_address: rpc_address.map(|ip| ip.to_string()),
+                max_archived_log_files: max_archived_log_files.map(|max| max as u64),
+                max_log_files: max_log_files.map(|max| max as u64),
+                max_download_rate,
+                max_upload_rate,
+                write_older_cache_files,
+                ignore_resource_limit,
+                backend: Some(backend),
+                container_image: Some(container_image),
+                container_runtime: Some(container_runtime),
+                env_variables: env_variables
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect(),
+                network_contacts_url: peers.network_contacts_url,
+                first: peers.first,
+                local: peers.local,
+                ignore_cache: peers.ignore_cache,
+                ..Default::default()
+            };
+            set_request_evm_network(&mut request, evm_network.try_into()?);
+            cmd::remote::add(addr, auth, request, verbosity).await?;
+            Ok(())
+        }
+        Some(SubCmd::Logs {
+            follow,
+            lines,
+            service_name,
+        }) => cmd::remote::logs(addr, auth, follow, lines, service_name).await,
+        Some(SubCmd::Metrics { json, service_name }) => {
+            cmd::remote::metrics(addr, auth, json, service_name, verbosity).await
+        }
+        Some(SubCmd::Reconfigure {
+            auto_restart,
+            env_variables,
+            log_format,
+            max_archived_log_files,
+            max_log_files,
+            metrics_port,
+            no_upnp,
+            node_port,
+            peer_id: peer_ids,
+            relay,
+            rewards_address,
+            rpc_port,
+            service_name: service_names,
+        }) => {
+            if env_variables.is_some() {
+                return Err(eyre!("--env cannot be used with --daemon"));
+            }
+            let request = ReconfigureNodeServicesRequest {
+                services: Some(NodeServicesRequest {
+                    service_names,
+                    peer_ids,
+                }),
+                auto_restart,
+                log_format: log_format.map(|format| format.as_str().to_string()),
+                max_archived_log_files: max_archived_log_files.map(|max| max as u64),
+                max_log_files: max_log_files.map(|max| max as u64),
+                metrics_port: metrics_port.map(u32::from),
+                no_upnp,
+                node_port: node_port.map(u32::from),
+                relay,
+                rewards_address: rewards_address.map(|address| address.to_string()),
+                rpc_port: rpc_port.map(u32::from),
+            };
+            cmd::remote::reconfigure(addr, auth, request, verbosity).await
+        }
+        Some(SubCmd::Remove {
+            keep_directories,
+            peer_id: peer_ids,
+            service_name: service_names,
+        }) => {
+            let request = RemoveNodeServicesRequest {
+                services: Some(NodeServicesRequest {
+                    service_names,
+                    peer_ids,
+                }),
+                keep_directories,
+            };
+            cmd::remote::remove(addr, auth, request, verbosity).await
+        }
+        Some(SubCmd::Start {
+            connection_timeout,
+            interval,
+            peer_id: peer_ids,
+            service_name: service_names,
+        }) => {
+            reject_remote_flags(&[
+                (
+                    "--connection-timeout",
+                    connection_timeout != DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S,
+                ),
+                ("--interval", interval.is_some()),
+            ])?;
+            let services = NodeServicesRequest {
+                service_names,
+                peer_ids,
+            };
+            cmd::remote::start(addr, auth, services, verbosity).await
+        }
+        Some(SubCmd::Status {
+            details,
+            fail,
+            json,
+        }) => {
+            reject_remote_flags(&[("--details", details), ("--fail", fail)])?;
+            cmd::remote::status(addr, auth, json).await
+        }
+        Some(SubCmd::Stop {
+            interval,
+            peer_id: peer_ids,
+            service_name: service_names,
+        }) => {
+            reject_remote_flags(&[("--interval", interval.is_some())])?;
+            let services = NodeServicesRequest {
+                service_names,
+                peer_ids,
+            };
+            cmd::remote::stop(addr, auth, services, verbosity).await
+        }
+        Some(SubCmd::Upgrade {
+            batch_size,
+            canary_percentage,
+            connection_timeout,
+            do_not_start,
+            env_variables,
+            force,
+            health_check,
+            health_timeout,
+            interval,
+            min_connected_peers,
+            min_records_ratio,
+            min_routing_table_size,
+            path,
+            peer_id: peer_ids,
+            service_name: service_names,
+            url,
+            version,
+        }) => {
+            reject_remote_flags(&[
+                (
+                    "--connection-timeout",
+                    connection_timeout != DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S,
+                ),
+                ("--do-not-start", do_not_start),
+                ("--env", env_variables.is_some()),
+                (
+                    "--health-timeout",
+                    health_timeout != DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S,
+                ),
+                ("--interval", interval.is_some()),
+                ("--min-connected-peers", min_connected_peers.is_some()),
+                ("--min-records-ratio", min_records_ratio.is_some()),
+                ("--min-routing-table-size", min_routing_table_size.is_some()),
+            ])?;
+            if path.is_some() || url.is_some() {
+                return Err(eyre!(
+                    "A binary path or URL cannot be used with --daemon; use --version instead"
+                ));
+            }
+            let request = UpgradeNodeServicesRequest {
+                services: Some(NodeServicesRequest {
+                    service_names,
+                    peer_ids,
+                }),
+                version,
+                force,
+                canary_percentage: canary_percentage as u32,
+                batch_size: batch_size as u32,
+                health_check,
+            };
+            cmd::remote::upgrade(addr, auth, request, verbosity).await
+        }
+        Some(_) => Err(eyre!("This command is not supported with --daemon")),
+        None => Ok(()),
+    }
+}
+
+/// Refuse the flags that are used but not supported by the daemon, rather than ignoring them.
+fn reject_remote_flags(flags: &[(&str, bool)]) -> Result<()> {
+    let used: Vec<&str> = flags
+        .iter()
+        .filter(|(_, used)| *used)
+        .map(|(flag, _)| *flag)
+        .collect();
+    if used.is_empty() {
+        return Ok(());
+    }
+    Err(eyre!("{} cannot be used with --daemon", used.join(", ")))
+}
+
 fn get_log_builder(level: Level) -> Result<LogBuilder> {
     let logging_targets = vec![
         ("ant_bootstrap".to_string(), level),
@@ -1090,6 +1861,14 @@ fn get_log_builder(level: Level) -> Result<LogBuilder> {
     Ok(log_builder)
 }
 
+fn parse_ratio(ratio: &str) -> Result<f64> {
+    let ratio: f64 = ratio.parse()?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(eyre!("The ratio must be between 0 and 1"));
+    }
+    Ok(ratio)
+}
+
 // Since delimiter is on, we get element of the csv and not the entire csv.
 fn parse_environment_variables(env_var: &str) -> Result<(String, String)> {
     let parts: Vec<&str> = env_var.splitn(2, '=').collect();
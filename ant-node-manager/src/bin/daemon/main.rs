@@ -9,23 +9,64 @@
 #[macro_use]
 extern crate tracing;
 
-use ant_logging::LogBuilder;
-use ant_node_manager::{DAEMON_DEFAULT_PORT, config::get_node_registry_path, rpc};
+use ant_bootstrap::InitialPeersConfig;
+use ant_evm::{EvmNetwork, RewardsAddress};
+use ant_logging::{LogBuilder, LogFormat};
+use ant_node_manager::{
+    DAEMON_DEFAULT_PORT, DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S, VerbosityLevel,
+    add_services::config::PortRange,
+    cmd::node,
+    config::get_node_registry_path,
+    rolling_upgrade::{HealthGate, RollingUpgradeStrategy},
+    rpc,
+    rpc_client::AUTHORIZATION_HEADER,
+    supervisor::{self, OperationLock, SupervisorConfig, SupervisorState},
+};
 use ant_service_management::{
     NodeRegistryManager,
     antctl_proto::{
-        GetStatusRequest, GetStatusResponse, NodeServiceRestartRequest, NodeServiceRestartResponse,
+        AddNodeServicesRequest, AddNodeServicesResponse, GetNodeMetricsRequest,
+        GetNodeMetricsResponse, GetStatusRequest, GetStatusResponse, NodeLogLine,
+        NodeServiceRestartRequest, NodeServiceRestartResponse, NodeServicesRequest,
+        NodeServicesResponse, ReconfigureNodeServicesRequest, RemoveNodeServicesRequest,
+        TailNodeLogsRequest, UpgradeNodeServicesRequest,
         ant_ctl_server::{AntCtl, AntCtlServer},
+        get_node_metrics_response::NodeMetrics,
         get_status_response::Node,
     },
+    control::ServiceBackend,
 };
 use clap::Parser;
-use color_eyre::eyre::{Result, eyre};
+use color_eyre::eyre::{Result, bail, eyre};
+use libp2p::Multiaddr;
 use libp2p_identity::PeerId;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use tonic::{Code, Request, Response, Status, transport::Server};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    num::TryFromIntError,
+    path::PathBuf,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    sync::{Mutex, mpsc},
+    time::MissedTickBehavior,
+};
+use tokio_stream::{Stream, StreamExt, wrappers::ReceiverStream};
+use tonic::{
+    Code, Request, Response, Status,
+    service::Interceptor,
+    transport::{Certificate, Identity, Server, ServerTlsConfig},
+};
 use tracing::Level;
 
+/// The number of log lines sent when none are requested.
+const DEFAULT_LOG_LINES: usize = 50;
+/// The daemon supervises the processes it launches, so they are its children rather than detached
+/// from it.
+const LAUNCH_AS_CHILDREN: bool = true;
+
 #[derive(Parser, Debug)]
 #[command(disable_version_flag = true)]
 struct Args {
@@ -47,9 +88,40 @@ struct Args {
     /// Print version information.
     #[clap(long)]
     version: bool,
+    /// Do not supervise the services.
+    ///
+    /// By default, the running services are health-checked periodically. Those that crash or
+    /// remain unhealthy are restarted, and those that crash-loop are quarantined.
+    #[clap(long, env = "ANTCTLD_NO_SUPERVISION")]
+    no_supervision: bool,
+    /// The interval between the health checks of the services, in seconds.
+    #[clap(long, env = "ANTCTLD_SUPERVISION_INTERVAL", default_value_t = 60)]
+    supervision_interval: u64,
+    /// The size the log directory of a service can reach, in MB, before its archived logs are
+    /// deleted, oldest first.
+    #[clap(long, env = "ANTCTLD_MAX_LOG_DIR_SIZE", default_value_t = 1024)]
+    max_log_dir_size: u64,
+    /// Require clients to send this shared token with each request.
+    ///
+    /// Either a token or a client CA is required to listen on a non-local address.
+    #[clap(long, env = "ANTCTLD_AUTH_TOKEN", hide_env_values = true)]
+    auth_token: Option<String>,
+    /// Serve TLS using this PEM certificate.
+    #[clap(long, env = "ANTCTLD_TLS_CERT", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+    /// The PEM key of the TLS certificate.
+    #[clap(long, env = "ANTCTLD_TLS_KEY", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+    /// Require clients to present a certificate signed by this PEM CA certificate (mutual TLS).
+    #[clap(long, env = "ANTCTLD_TLS_CLIENT_CA", requires = "tls_cert")]
+    tls_client_ca: Option<PathBuf>,
 }
 
-struct AntCtlDaemon {}
+struct AntCtlDaemon {
+    /// Serialises the operations changing the registry, which is loaded for each request, with
+    /// the supervision of the services.
+    operation_lock: Arc<Mutex<()>>,
+}
 
 // Implementing RPC interface for service defined in .proto
 #[tonic::async_trait]
@@ -60,6 +132,17 @@ impl AntCtl for AntCtlDaemon {
     ) -> Result<Response<NodeServiceRestartResponse>, Status> {
         println!("RPC request received {:?}", request.get_ref());
         info!("RPC request received {:?}", request.get_ref());
+        let _guard = self.operation_lock.lock().await;
+        // antctl operates on the services from other processes
+        let node_registry_path = get_node_registry_path().map_err(|err| {
+            Status::new(
+                Code::Internal,
+                format!("Could not obtain node registry path: {err}"),
+            )
+        })?;
+        let _lock = OperationLock::acquire(&node_registry_path)
+            .await
+            .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
         let node_registry = Self::load_node_registry().await.map_err(|err| {
             Status::new(
                 Code::Internal,
@@ -103,12 +186,265 @@ impl AntCtl for AntCtlDaemon {
                 peer_id: node.peer_id.map(|id| id.to_bytes()),
                 status: node.status.clone() as i32,
                 number: node.number as u32,
+                service_name: node.service_name.clone(),
+                max_download_rate: node.max_download_rate,
+                max_upload_rate: node.max_upload_rate,
             });
         }
 
         info!("Node status retrieved, nod len: {:?}", nodes_info.len());
         Ok(Response::new(GetStatusResponse { nodes: nodes_info }))
     }
+
+    async fn add_node_services(
+        &self,
+        request: Request<AddNodeServicesRequest>,
+    ) -> Result<Response<AddNodeServicesResponse>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let request = request.into_inner();
+        let evm_network = rpc::evm_network_from_request(&request)
+            .map_err(|err| Status::new(Code::InvalidArgument, err.to_string()))?;
+        let _guard = self.operation_lock.lock().await;
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        let service_names = Self::add_handler(node_registry, request, evm_network)
+            .await
+            .map_err(|err| Status::new(Code::Internal, format!("Failed to add nodes: {err}")))?;
+        info!("Added node services {service_names:?}");
+        Ok(Response::new(AddNodeServicesResponse { service_names }))
+    }
+
+    async fn start_node_services(
+        &self,
+        request: Request<NodeServicesRequest>,
+    ) -> Result<Response<NodeServicesResponse>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let request = request.into_inner();
+        let _guard = self.operation_lock.lock().await;
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        node::start(
+            DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S,
+            None,
+            LAUNCH_AS_CHILDREN,
+            node_registry,
+            request.peer_ids,
+            request.service_names,
+            VerbosityLevel::Minimal,
+        )
+        .await
+        .map_err(|err| Status::new(Code::Internal, format!("Failed to start nodes: {err}")))?;
+        Ok(Response::new(NodeServicesResponse {}))
+    }
+
+    async fn stop_node_services(
+        &self,
+        request: Request<NodeServicesRequest>,
+    ) -> Result<Response<NodeServicesResponse>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let request = request.into_inner();
+        let _guard = self.operation_lock.lock().await;
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        node::stop(
+            None,
+            node_registry,
+            request.peer_ids,
+            request.service_names,
+            VerbosityLevel::Minimal,
+        )
+        .await
+        .map_err(|err| Status::new(Code::Internal, format!("Failed to stop nodes: {err}")))?;
+        Ok(Response::new(NodeServicesResponse {}))
+    }
+
+    async fn remove_node_services(
+        &self,
+        request: Request<RemoveNodeServicesRequest>,
+    ) -> Result<Response<NodeServicesResponse>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let request = request.into_inner();
+        let services = request.services.unwrap_or_default();
+        let _guard = self.operation_lock.lock().await;
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        node::remove(
+            request.keep_directories,
+            services.peer_ids,
+            node_registry,
+            services.service_names,
+            VerbosityLevel::Minimal,
+        )
+        .await
+        .map_err(|err| Status::new(Code::Internal, format!("Failed to remove nodes: {err}")))?;
+        Ok(Response::new(NodeServicesResponse {}))
+    }
+
+    async fn upgrade_node_services(
+        &self,
+        request: Request<UpgradeNodeServicesRequest>,
+    ) -> Result<Response<NodeServicesResponse>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let request = request.into_inner();
+        let services = request.services.unwrap_or_default();
+        let strategy = RollingUpgradeStrategy {
+            canary_percentage: request.canary_percentage.min(100) as u8,
+            batch_size: (request.batch_size as usize).max(1),
+            health_gate: request.health_check.then(HealthGate::default),
+        };
+        let _guard = self.operation_lock.lock().await;
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        node::upgrade(
+            DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S,
+            false,
+            None,
+            request.force,
+            None,
+            LAUNCH_AS_CHILDREN,
+            node_registry,
+            services.peer_ids,
+            None,
+            services.service_names,
+            strategy,
+            None,
+            request.version,
+            VerbosityLevel::Minimal,
+        )
+        .await
+        .map_err(|err| Status::new(Code::Internal, format!("Failed to upgrade nodes: {err}")))?;
+        Ok(Response::new(NodeServicesResponse {}))
+    }
+
+    async fn reconfigure_node_services(
+        &self,
+        request: Request<ReconfigureNodeServicesRequest>,
+    ) -> Result<Response<NodeServicesResponse>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let request = request.into_inner();
+        let services = request.services.clone().unwrap_or_default();
+        let log_format = request
+            .log_format
+            .as_deref()
+            .map(LogFormat::parse_from_str)
+            .transpose()
+            .map_err(|err| Status::new(Code::InvalidArgument, err.to_string()))?;
+        let rewards_address = request
+            .rewards_address
+            .as_deref()
+            .map(RewardsAddress::from_str)
+            .transpose()
+            .map_err(|err| Status::new(Code::InvalidArgument, err.to_string()))?;
+        let port = |port: Option<u32>| port.map(u16::try_from).transpose();
+        let invalid_port =
+            |err: TryFromIntError| Status::new(Code::InvalidArgument, err.to_string());
+        let (metrics_port, node_port, rpc_port) = (
+            port(request.metrics_port).map_err(invalid_port)?,
+            port(request.node_port).map_err(invalid_port)?,
+            port(request.rpc_port).map_err(invalid_port)?,
+        );
+        let _guard = self.operation_lock.lock().await;
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        node::reconfigure(
+            request.auto_restart,
+            None,
+            LAUNCH_AS_CHILDREN,
+            log_format,
+            request.max_archived_log_files.map(|max| max as usize),
+            request.max_log_files.map(|max| max as usize),
+            metrics_port,
+            request.no_upnp,
+            node_port,
+            node_registry,
+            services.peer_ids,
+            request.relay,
+            rewards_address,
+            rpc_port,
+            services.service_names,
+            VerbosityLevel::Minimal,
+        )
+        .await
+        .map_err(|err| {
+            Status::new(
+                Code::Internal,
+                format!("Failed to reconfigure nodes: {err}"),
+            )
+        })?;
+        Ok(Response::new(NodeServicesResponse {}))
+    }
+
+    type TailNodeLogsStream = Pin<Box<dyn Stream<Item = Result<NodeLogLine, Status>> + Send>>;
+
+    async fn tail_node_logs(
+        &self,
+        request: Request<TailNodeLogsRequest>,
+    ) -> Result<Response<Self::TailNodeLogsStream>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let request = request.into_inner();
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        let mut log_path = None;
+        for node in node_registry.nodes.read().await.iter() {
+            let node = node.read().await;
+            if node.service_name == request.service_name {
+                log_path = Some(rpc::get_node_log_path(&node));
+            }
+        }
+        let log_path = log_path.ok_or_else(|| {
+            Status::new(
+                Code::NotFound,
+                format!("No service named '{}'", request.service_name),
+            )
+        })?;
+        let lines = if request.lines == 0 {
+            DEFAULT_LOG_LINES
+        } else {
+            request.lines as usize
+        };
+        let (lines, offset) = rpc::read_last_log_lines(&log_path, lines)
+            .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+
+        let (sender, receiver) = mpsc::channel(lines.len().max(1));
+        for line in lines {
+            let _ = sender.send(line).await;
+        }
+        if request.follow {
+            tokio::spawn(rpc::follow_log(log_path, offset, sender));
+        }
+        let stream = ReceiverStream::new(receiver)
+            .map(|line| NodeLogLine { line })
+            .map(Ok);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn get_node_metrics(
+        &self,
+        request: Request<GetNodeMetricsRequest>,
+    ) -> Result<Response<GetNodeMetricsResponse>, Status> {
+        info!("RPC request received {:?}", request.get_ref());
+        let node_registry = Self::load_node_registry_for_rpc().await?;
+
+        let metrics = rpc::get_node_metrics(node_registry, &request.get_ref().service_names)
+            .await
+            .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+        let nodes = metrics
+            .into_iter()
+            .map(|metrics| NodeMetrics {
+                service_name: metrics.service_name,
+                peer_id: metrics.peer_id.map(|id| id.to_bytes()),
+                version: metrics.version,
+                pid: metrics.pid,
+                uptime_secs: metrics.uptime_secs,
+                connected_peers: metrics.connected_peers as u64,
+                routing_table_size: metrics.routing_table_size as u64,
+                records_stored: metrics.records_stored as u64,
+                wallet_balance: metrics.wallet_balance,
+                error: metrics.error,
+            })
+            .collect();
+        Ok(Response::new(GetNodeMetricsResponse { nodes }))
+    }
 }
 
 impl AntCtlDaemon {
@@ -121,12 +457,119 @@ impl AntCtlDaemon {
         Ok(node_registry)
     }
 
+    async fn load_node_registry_for_rpc() -> Result<NodeRegistryManager, Status> {
+        Self::load_node_registry().await.map_err(|err| {
+            Status::new(
+                Code::Internal,
+                format!("Failed to load node registry: {err}"),
+            )
+        })
+    }
+
+    async fn add_handler(
+        node_registry: NodeRegistryManager,
+        request: AddNodeServicesRequest,
+        evm_network: Option<EvmNetwork>,
+    ) -> Result<Vec<String>> {
+        let count = u16::try_from(request.count)?;
+        let rewards_address = RewardsAddress::from_str(&request.rewards_address)?;
+        let port = |port: &Option<String>| port.as_deref().map(PortRange::parse).transpose();
+        let log_format = request
+            .log_format
+            .as_deref()
+            .map(LogFormat::parse_from_str)
+            .transpose()?;
+        let network_id = request.network_id.map(u8::try_from).transpose()?;
+        let init_peers_config = InitialPeersConfig {
+            addrs: request
+                .initial_peers
+                .iter()
+                .map(|addr| Multiaddr::from_str(addr))
+                .collect::<Result<_, _>>()?,
+            first: request.first,
+            ignore_cache: request.ignore_cache,
+            local: request.local,
+            network_contacts_url: request.network_contacts_url.clone(),
+            ..Default::default()
+        };
+        let env_variables = if request.env_variables.is_empty() {
+            node_registry.environment_variables.read().await.clone()
+        } else {
+            let mut env_variables = Vec::new();
+            for variable in &request.env_variables {
+                let (key, value) = variable
+                    .split_once('=')
+                    .ok_or_else(|| eyre!("Invalid environment variable: {variable}"))?;
+                env_variables.push((key.to_string(), value.to_string()));
+            }
+            Some(env_variables)
+        };
+        let node_ip = request
+            .node_ip
+            .as_deref()
+            .map(Ipv4Addr::from_str)
+            .transpose()?;
+        let rpc_address = request
+            .rpc_address
+            .as_deref()
+            .map(Ipv4Addr::from_str)
+            .transpose()?;
+        let backend = ServiceBackend::parse(
+            request.backend.as_deref().unwrap_or("native"),
+            request.container_image.clone(),
+            request.container_runtime.clone(),
+        )?;
+
+        node::add(
+            request.alpha,
+            request.auto_restart,
+            false,
+            backend,
+            Some(count),
+            request.data_dir_path.map(PathBuf::from),
+            request.enable_metrics_server,
+            env_variables,
+            evm_network,
+            request.ignore_resource_limit,
+            request.log_dir_path.map(PathBuf::from),
+            log_format,
+            request.max_archived_log_files.map(|max| max as usize),
+            request.max_download_rate,
+            request.max_log_files.map(|max| max as usize),
+            request.max_upload_rate,
+            port(&request.metrics_port)?,
+            network_id,
+            node_ip,
+            port(&request.node_port)?,
+            node_registry.clone(),
+            init_peers_config,
+            request.relay,
+            rewards_address,
+            rpc_address,
+            port(&request.rpc_port)?,
+            None,
+            request.no_upnp,
+            None,
+            request.user,
+            request.version,
+            VerbosityLevel::Minimal,
+            request.write_older_cache_files,
+        )
+        .await
+    }
+
     async fn restart_handler(
         node_registry: NodeRegistryManager,
         peer_id: PeerId,
         retain_peer_id: bool,
     ) -> Result<()> {
-        let res = rpc::restart_node_service(node_registry.clone(), peer_id, retain_peer_id).await;
+        let res = rpc::restart_node_service(
+            LAUNCH_AS_CHILDREN,
+            node_registry.clone(),
+            peer_id,
+            retain_peer_id,
+        )
+        .await;
 
         // make sure to save the state even if the above fn fails.
         node_registry.save().await?;
@@ -168,10 +611,50 @@ async fn main() -> Result<()> {
 
     let _log_handles = get_log_builder()?.initialize()?;
     println!("Starting antctld");
-    let service = AntCtlDaemon {};
+    if !args.address.is_loopback() && args.auth_token.is_none() && args.tls_client_ca.is_none() {
+        error!(
+            "An auth token or a client CA is required to listen on {}",
+            args.address
+        );
+        bail!(
+            "An auth token or a client CA is required to listen on the non-local address {}",
+            args.address
+        );
+    }
+    let operation_lock = Arc::new(Mutex::new(()));
+    if args.no_supervision {
+        info!("The supervision of the services is disabled");
+    } else {
+        let config = SupervisorConfig {
+            check_interval: Duration::from_secs(args.supervision_interval),
+            max_log_dir_bytes: args.max_log_dir_size * 1024 * 1024,
+            ..Default::default()
+        };
+        tokio::spawn(supervise_services(config, Arc::clone(&operation_lock)));
+    }
+    let service = AntCtlDaemon { operation_lock };
+
+    let mut server = Server::builder();
+    if let (Some(cert_path), Some(key_path)) = (&args.tls_cert, &args.tls_key) {
+        let cert = std::fs::read(cert_path)?;
+        let key = std::fs::read(key_path)?;
+        let mut tls_config = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+        if let Some(ca_path) = &args.tls_client_ca {
+            tls_config = tls_config.client_ca_root(Certificate::from_pem(std::fs::read(ca_path)?));
+        }
+        info!(
+            "Serving TLS, with client certificates required: {}",
+            args.tls_client_ca.is_some()
+        );
+        server = server.tls_config(tls_config)?;
+    }
+
+    let check_auth = AuthInterceptor {
+        expected: args.auth_token.map(|token| format!("Bearer {token}")),
+    };
 
-    if let Err(err) = Server::builder()
-        .add_service(AntCtlServer::new(service))
+    if let Err(err) = server
+        .add_service(AntCtlServer::with_interceptor(service, check_auth))
         .serve(SocketAddr::new(IpAddr::V4(args.address), args.port))
         .await
     {
@@ -183,6 +666,67 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Reject the requests without the expected authorization header, if a token is set.
+#[derive(Clone)]
+struct AuthInterceptor {
+    expected: Option<String>,
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(expected) = &self.expected else {
+            return Ok(request);
+        };
+        let provided = request
+            .metadata()
+            .get(AUTHORIZATION_HEADER)
+            .map(|value| value.as_bytes())
+            .unwrap_or_default();
+        if constant_time_eq(provided, expected.as_bytes()) {
+            Ok(request)
+        } else {
+            warn!("Rejected a request with a missing or invalid token");
+            Err(Status::unauthenticated("Missing or invalid token"))
+        }
+    }
+}
+
+/// Periodically check the health of the services, restarting or quarantining those that need it.
+///
+/// The supervisor state is loaded for each pass, because `antctl` resets it when services are
+/// started or stopped by hand.
+async fn supervise_services(config: SupervisorConfig, operation_lock: Arc<Mutex<()>>) {
+    info!("Supervising the services every {:?}", config.check_interval);
+    let mut interval = tokio::time::interval(config.check_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let _guard = operation_lock.lock().await;
+        let result = async {
+            // antctl operates on the services from other processes
+            let node_registry_path = get_node_registry_path()
+                .map_err(|err| eyre!("Could not obtain node registry path: {err:?}"))?;
+            let _lock = OperationLock::acquire(&node_registry_path).await?;
+            let node_registry = AntCtlDaemon::load_node_registry().await?;
+            let state_path = SupervisorState::path(&node_registry.save_path);
+            let mut state = SupervisorState::load(&state_path)?;
+            let result = supervisor::supervise(&node_registry, &mut state, &config).await;
+            // Keep the actions taken before any failure.
+            state.save(&state_path)?;
+            result
+        }
+        .await;
+        if let Err(err) = result {
+            error!("The supervision of the services failed: {err}");
+        }
+    }
+}
+
+/// Compare the tokens without exiting early, so their contents cannot be guessed from timings.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
 fn get_log_builder() -> Result<LogBuilder> {
     let logging_targets = vec![
         ("ant_node_manager".to_string(), Level::TRACE),
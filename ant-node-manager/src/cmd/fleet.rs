@@ -0,0 +1,331 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::node::{self, summarise_any_failed_ops};
+use crate::{
+    ServiceManager, VerbosityLevel,
+    add_services::config::PortRange,
+    config::create_owned_dir,
+    fleet::{FleetAction, FleetChange, FleetPlan, FleetSpec},
+    print_banner, refresh_node_registry,
+    rolling_upgrade::RollingUpgradeStrategy,
+    supervisor::OperationLock,
+};
+use ant_bootstrap::InitialPeersConfig;
+use ant_service_management::{
+    NodeRegistryManager, NodeService, NodeServiceData, control::ServiceController, rpc::RpcClient,
+};
+use color_eyre::Result;
+use std::{collections::HashSet, io::Write, path::Path, sync::Arc};
+use tokio::sync::RwLock;
+
+/// Print the changes required for the services to match the fleet spec, without applying them.
+pub async fn plan(
+    spec_path: &Path,
+    node_registry: NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner("Fleet Plan");
+    }
+    let spec = FleetSpec::from_file(spec_path)?;
+    let plan = get_plan(&spec, &node_registry, verbosity).await?;
+    plan.print(&spec);
+    Ok(())
+}
+
+/// Converge the services towards the fleet spec, by removing, reconfiguring, upgrading and adding
+/// services. The added services are started.
+pub async fn apply(
+    auto_approve: bool,
+    connection_timeout_s: u64,
+    ignore_resource_limit: bool,
+    spec_path: &Path,
+    node_registry: NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner("Apply Fleet Spec");
+    }
+    let spec = FleetSpec::from_file(spec_path)?;
+    let plan = get_plan(&spec, &node_registry, verbosity).await?;
+    plan.print(&spec);
+    if plan.is_empty() {
+        return Ok(());
+    }
+
+    if !auto_approve {
+        println!("Do you wish to apply these changes? [y/n]");
+        std::io::stdout().flush()?;
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if input.trim().to_lowercase() != "y" {
+            println!("Apply aborted");
+            return Ok(());
+        }
+    }
+    info!("Applying fleet spec {spec_path:?}: {plan:?}");
+
+    let mut removals = Vec::new();
+    let mut upgrades = Vec::new();
+    for action in &plan.actions {
+        match action {
+            FleetAction::Remove { service_name } => removals.push(service_name.clone()),
+            FleetAction::Upgrade { service_name, .. } => upgrades.push(service_name.clone()),
+            _ => {}
+        }
+    }
+
+    if !removals.is_empty() {
+        node::stop(
+            None,
+            node_registry.clone(),
+            vec![],
+            removals.clone(),
+            verbosity,
+        )
+        .await?;
+        node::remove(false, vec![], node_registry.clone(), removals, verbosity).await?;
+    }
+
+    let env_variables = spec.env_variables();
+    let upgrading: HashSet<&String> = upgrades.iter().collect();
+    let mut failed_services = Vec::new();
+    {
+        // The daemon's supervision must not overwrite the changes made here, nor the other way
+        // round. The steps run by the other commands take the lock themselves.
+        let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+        node_registry.reload().await?;
+        *node_registry.environment_variables.write().await = env_variables.clone();
+        node_registry.save().await?;
+
+        for action in &plan.actions {
+            let FleetAction::Reconfigure {
+                service_name,
+                changes,
+            } = action
+            else {
+                continue;
+            };
+            let Some(node) = find_node(&node_registry, service_name).await else {
+                continue;
+            };
+            {
+                let mut node = node.write().await;
+                for change in changes {
+                    if *change == FleetChange::EnableMetricsServer && node.metrics_port.is_none() {
+                        node.metrics_port = Some(node.backend.controller().get_available_port()?);
+                    }
+                    change.apply_to(&mut node);
+                }
+                // The directories have to be writable by the user the service now runs as.
+                if changes.iter().any(|change| {
+                    matches!(change, FleetChange::User(_) | FleetChange::LogDirPath(_))
+                }) {
+                    match &node.user {
+                        Some(user) => {
+                            create_owned_dir(node.data_dir_path.clone(), user)?;
+                            create_owned_dir(node.log_dir_path.clone(), user)?;
+                        }
+                        None => std::fs::create_dir_all(&node.log_dir_path)?,
+                    }
+                }
+            }
+            node_registry.save().await?;
+            // The upgrade re-installs the service with the new settings.
+            if upgrading.contains(service_name) {
+                continue;
+            }
+
+            let rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
+            let service = NodeService::new(Arc::clone(&node), Box::new(rpc_client));
+            let mut service_manager =
+                ServiceManager::new(service, node.read().await.backend.controller(), verbosity);
+            let restart = changes.iter().any(FleetChange::needs_restart);
+            if let Err(err) = service_manager
+                .reconfigure(spec.auto_restart, env_variables.clone(), restart)
+                .await
+            {
+                error!("Failed to reconfigure service {service_name}: {err}");
+                failed_services.push((service_name.clone(), err.to_string()));
+            }
+            node_registry.save().await?;
+        }
+    }
+
+    if !upgrades.is_empty() {
+        node::upgrade(
+            connection_timeout_s,
+            false,
+            None,
+            true,
+            None,
+            false,
+            node_registry.clone(),
+            vec![],
+            None,
+            upgrades,
+            RollingUpgradeStrategy::default(),
+            None,
+            spec.version.as_ref().map(|version| version.to_string()),
+            verbosity,
+        )
+        .await?;
+    }
+
+    for action in &plan.actions {
+        let FleetAction::Add {
+            group,
+            count,
+            node_ports,
+            metrics_ports,
+            rpc_ports,
+        } = action
+        else {
+            continue;
+        };
+
+        // Ports are allocated one service at a time, as the free ports might not be contiguous.
+        let batches = if node_ports.is_some() || metrics_ports.is_some() || rpc_ports.is_some() {
+            let port = |ports: &Option<Vec<u16>>, i: usize| {
+                ports
+                    .as_ref()
+                    .and_then(|ports| ports.get(i))
+                    .map(|port| PortRange::Single(*port))
+            };
+            (0..*count as usize)
+                .map(|i| {
+                    (
+                        1,
+                        port(node_ports, i),
+                        port(metrics_ports, i),
+                        port(rpc_ports, i),
+                    )
+                })
+                .collect()
+        } else {
+            vec![(*count, None, None, None)]
+        };
+
+        for (count, node_port, metrics_port, rpc_port) in batches {
+            let added_services = add_services(
+                &spec,
+                *group,
+                count,
+                ignore_resource_limit,
+                node_port,
+                metrics_port,
+                rpc_port,
+                node_registry.clone(),
+                verbosity,
+            )
+            .await?;
+            node::start(
+                connection_timeout_s,
+                None,
+                false,
+                node_registry.clone(),
+                vec![],
+                added_services,
+                verbosity,
+            )
+            .await?;
+        }
+    }
+
+    summarise_any_failed_ops(failed_services, "reconfigure", verbosity)
+}
+
+async fn get_plan(
+    spec: &FleetSpec,
+    node_registry: &NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<FleetPlan> {
+    refresh_node_registry(
+        node_registry.clone(),
+        &ServiceController {},
+        verbosity != VerbosityLevel::Minimal,
+        false,
+        verbosity,
+    )
+    .await?;
+
+    let mut nodes = Vec::new();
+    for node in node_registry.nodes.read().await.iter() {
+        nodes.push(node.read().await.clone());
+    }
+    let env_variables = node_registry.environment_variables.read().await.clone();
+    Ok(FleetPlan::new(spec, &nodes, env_variables.as_ref()))
+}
+
+async fn find_node(
+    node_registry: &NodeRegistryManager,
+    service_name: &str,
+) -> Option<Arc<RwLock<NodeServiceData>>> {
+    for node in node_registry.nodes.read().await.iter() {
+        if node.read().await.service_name == service_name {
+            return Some(Arc::clone(node));
+        }
+    }
+    None
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn add_services(
+    spec: &FleetSpec,
+    group: usize,
+    count: u16,
+    ignore_resource_limit: bool,
+    node_port: Option<PortRange>,
+    metrics_port: Option<PortRange>,
+    rpc_port: Option<PortRange>,
+    node_registry: NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<Vec<String>> {
+    let init_peers_config = InitialPeersConfig {
+        addrs: spec.initial_peers.clone(),
+        ..Default::default()
+    };
+    let backend = node_registry.service_backend().await;
+    node::add(
+        spec.alpha,
+        spec.auto_restart,
+        false,
+        backend,
+        Some(count),
+        Some(spec.groups[group].data_dir_path.clone()),
+        spec.enable_metrics_server,
+        spec.env_variables(),
+        Some(spec.evm_network.clone()),
+        ignore_resource_limit,
+        spec.log.dir_path.clone(),
+        spec.log.format,
+        spec.log.max_archived_log_files,
+        spec.max_download_rate,
+        spec.log.max_log_files,
+        spec.max_upload_rate,
+        metrics_port,
+        spec.network_id,
+        spec.node_ip,
+        node_port,
+        node_registry,
+        init_peers_config,
+        spec.relay,
+        spec.rewards_address,
+        spec.rpc_address,
+        rpc_port,
+        None,
+        spec.no_upnp,
+        None,
+        spec.user.clone(),
+        spec.version.as_ref().map(|version| version.to_string()),
+        verbosity,
+        false,
+    )
+    .await
+}
@@ -7,10 +7,14 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 pub mod daemon;
+pub mod fleet;
 pub mod local;
 pub mod nat_detection;
 pub mod node;
+pub mod registry;
+pub mod remote;
 
+use crate::rpc::NodeMetrics;
 use crate::{
     VerbosityLevel,
     helpers::{download_and_extract_release, get_bin_version},
@@ -97,6 +101,34 @@ pub async fn download_and_get_upgrade_bin_path(
     }
 }
 
+pub fn print_node_metrics(metrics: &[NodeMetrics], json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(metrics)?);
+        return Ok(());
+    }
+    println!(
+        "{:<18} {:<10} {:>8} {:>10} {:>8} {:>14} {:>8}",
+        "Service Name", "Version", "PID", "Uptime (s)", "Peers", "Routing Table", "Records"
+    );
+    for node in metrics {
+        if let Some(err) = &node.error {
+            println!("{:<18} {} {err}", node.service_name, "✕".red());
+            continue;
+        }
+        println!(
+            "{:<18} {:<10} {:>8} {:>10} {:>8} {:>14} {:>8}",
+            node.service_name,
+            node.version,
+            node.pid.map_or("-".to_string(), |pid| pid.to_string()),
+            node.uptime_secs,
+            node.connected_peers,
+            node.routing_table_size,
+            node.records_stored
+        );
+    }
+    Ok(())
+}
+
 pub fn print_upgrade_summary(upgrade_summary: Vec<(String, UpgradeResult)>) {
     println!("Upgrade summary:");
     for (service_name, upgrade_result) in upgrade_summary {
@@ -125,6 +157,13 @@ pub fn print_upgrade_summary(upgrade_summary: Vec<(String, UpgradeResult)>) {
                     service_name
                 );
             }
+            UpgradeResult::RolledBack(previous_version, target_version, reason) => {
+                println!(
+                    "{} {} was rolled back from {target_version} to {previous_version}: {reason}",
+                    "✕".red(),
+                    service_name
+                );
+            }
             UpgradeResult::Error(msg) => {
                 println!("{} {} was not upgraded: {}", "✕".red(), service_name, msg);
             }
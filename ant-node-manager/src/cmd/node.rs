@@ -8,7 +8,7 @@
 
 #![allow(clippy::too_many_arguments)]
 
-use super::{download_and_get_upgrade_bin_path, print_upgrade_summary};
+use super::{download_and_get_upgrade_bin_path, print_node_metrics, print_upgrade_summary, remote};
 use crate::{
     ServiceManager, VerbosityLevel,
     add_services::{
@@ -16,8 +16,15 @@ use crate::{
         config::{AddNodeServiceOptions, PortRange},
     },
     config::{self, is_running_as_root},
-    helpers::{download_and_extract_release, get_bin_version},
-    print_banner, refresh_node_registry, status_report,
+    fleet::FleetChange,
+    helpers::{check_reconfigured_ports, download_and_extract_release, get_bin_version},
+    print_banner, refresh_node_registry,
+    resources::{ExistingNodes, GB, HostResources, MB, ProjectedUsage, probe_bandwidth},
+    rolling_upgrade::{NodeHealth, RollingUpgradeStrategy},
+    rpc,
+    rpc_client::DaemonAuth,
+    status_report,
+    supervisor::{OperationLock, reset_supervision},
 };
 use ant_bootstrap::InitialPeersConfig;
 use ant_evm::{EvmNetwork, RewardsAddress};
@@ -26,7 +33,8 @@ use ant_releases::{AntReleaseRepoActions, ReleaseType};
 use ant_service_management::{
     NodeRegistryManager, NodeService, NodeServiceData, ServiceStateActions, ServiceStatus,
     UpgradeOptions, UpgradeResult,
-    control::{ServiceControl, ServiceController},
+    antctl_proto::NodeServicesRequest,
+    control::{ServiceBackend, ServiceController},
     rpc::RpcClient,
 };
 use color_eyre::{Help, Result, eyre::eyre};
@@ -34,7 +42,13 @@ use colored::Colorize;
 use libp2p_identity::PeerId;
 use semver::Version;
 use std::{
-    cmp::Ordering, io::Write, net::Ipv4Addr, path::PathBuf, str::FromStr, sync::Arc, time::Duration,
+    cmp::Ordering,
+    io::Write,
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
 };
 use tokio::sync::RwLock;
 use tracing::debug;
@@ -44,15 +58,19 @@ pub async fn add(
     alpha: bool,
     auto_restart: bool,
     auto_set_nat_flags: bool,
+    backend: ServiceBackend,
     count: Option<u16>,
     data_dir_path: Option<PathBuf>,
     enable_metrics_server: bool,
     env_variables: Option<Vec<(String, String)>>,
     evm_network: Option<EvmNetwork>,
+    ignore_resource_limit: bool,
     log_dir_path: Option<PathBuf>,
     log_format: Option<LogFormat>,
     max_archived_log_files: Option<usize>,
+    max_download_rate: Option<u64>,
     max_log_files: Option<usize>,
+    max_upload_rate: Option<u64>,
     metrics_port: Option<PortRange>,
     network_id: Option<u8>,
     node_ip: Option<Ipv4Addr>,
@@ -78,7 +96,11 @@ pub async fn add(
         println!("{} service(s) to be added", count.unwrap_or(1));
     }
 
-    let service_manager = ServiceController {};
+    // The daemon's supervision must not overwrite the changes made here, nor the other way round
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.reload().await?;
+
+    let service_manager = backend.controller();
     let service_user = if user_mode {
         None
     } else {
@@ -91,6 +113,28 @@ pub async fn add(
         config::get_service_data_dir_path(data_dir_path, service_user.clone())?;
     let service_log_dir_path =
         config::get_service_log_dir_path(ReleaseType::AntNode, log_dir_path, service_user.clone())?;
+
+    let existing_nodes = measure_existing_nodes(&node_registry, &service_data_dir_path).await?;
+    let recommendation = HostResources::measure(&service_data_dir_path).recommend(&existing_nodes);
+    let total_nodes = existing_nodes.count + count.unwrap_or(1) as usize;
+    // Local networks are for testing and are not expected to fit a production host
+    if !init_peers_config.local && total_nodes > recommendation.max_nodes {
+        let message = format!(
+            "This host would run {total_nodes} nodes, above the {} recommended for its {}",
+            recommendation.max_nodes, recommendation.limited_by
+        );
+        if !ignore_resource_limit {
+            error!("{message}");
+            return Err(eyre!(
+                "{message}. Use --ignore-resource-limit to add the services anyway"
+            ));
+        }
+        warn!("{message}");
+        if verbosity != VerbosityLevel::Minimal {
+            println!("{} {message}", "⚠".yellow());
+        }
+    }
+
     let bootstrap_cache_dir = if let Some(user) = &service_user {
         Some(config::get_bootstrap_cache_owner_path(user)?)
     } else {
@@ -125,6 +169,7 @@ pub async fn add(
         alpha,
         auto_restart,
         auto_set_nat_flags,
+        backend,
         count,
         delete_antnode_src: src_path.is_none(),
         enable_metrics_server,
@@ -133,7 +178,9 @@ pub async fn add(
         relay,
         log_format,
         max_archived_log_files,
+        max_download_rate,
         max_log_files,
+        max_upload_rate,
         metrics_port,
         network_id,
         node_ip,
@@ -154,7 +201,7 @@ pub async fn add(
     };
     info!("Adding node service(s)");
     let added_services_names =
-        add_node(options, node_registry.clone(), &service_manager, verbosity).await?;
+        add_node(options, node_registry.clone(), &*service_manager, verbosity).await?;
 
     node_registry.save().await?;
     debug!("Node registry saved");
@@ -198,6 +245,242 @@ pub async fn balance(
     Ok(())
 }
 
+/// Print the last lines of the log of a service, then optionally the lines appended to it.
+pub async fn logs(
+    follow: bool,
+    lines: usize,
+    node_registry: NodeRegistryManager,
+    service_name: String,
+) -> Result<()> {
+    let services_for_ops =
+        get_services_for_ops(&node_registry, vec![], vec![service_name.clone()]).await?;
+    let log_path = match services_for_ops.first() {
+        Some(node) => rpc::get_node_log_path(&*node.read().await),
+        None => return Err(eyre!("No service named '{service_name}'")),
+    };
+
+    let (last_lines, offset) = rpc::read_last_log_lines(&log_path, lines)?;
+    for line in last_lines {
+        println!("{line}");
+    }
+    if follow {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(100);
+        tokio::spawn(rpc::follow_log(log_path, offset, sender));
+        while let Some(line) = receiver.recv().await {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+/// Print a snapshot of the metrics of the running services, obtained through their RPC service.
+pub async fn metrics(
+    json: bool,
+    node_registry: NodeRegistryManager,
+    service_names: Vec<String>,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal && !json {
+        print_banner("Antnode Metrics");
+    }
+    refresh_node_registry(
+        node_registry.clone(),
+        &ServiceController {},
+        false,
+        false,
+        verbosity,
+    )
+    .await?;
+    let metrics = rpc::get_node_metrics(node_registry, &service_names).await?;
+    print_node_metrics(&metrics, json)
+}
+
+/// Change the settings of existing services in place.
+///
+/// The registry entries are updated and the service definitions re-installed, keeping the data
+/// directory, and so the peer ID, of each service. Only the services with changed settings are
+/// re-installed, and only those that were running are restarted.
+#[allow(clippy::too_many_arguments)]
+pub async fn reconfigure(
+    auto_restart: Option<bool>,
+    env_variables: Option<Vec<(String, String)>>,
+    launch_as_children: bool,
+    log_format: Option<LogFormat>,
+    max_archived_log_files: Option<usize>,
+    max_log_files: Option<usize>,
+    metrics_port: Option<u16>,
+    no_upnp: Option<bool>,
+    node_port: Option<u16>,
+    node_registry: NodeRegistryManager,
+    peer_ids: Vec<String>,
+    relay: Option<bool>,
+    rewards_address: Option<RewardsAddress>,
+    rpc_port: Option<u16>,
+    service_names: Vec<String>,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner("Reconfigure Antnode Services");
+    }
+    info!("Reconfiguring antnode services for: {peer_ids:?}, {service_names:?}");
+
+    // The daemon's supervision must not overwrite the changes made here, nor the other way round
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.reload().await?;
+
+    refresh_node_registry(
+        node_registry.clone(),
+        &ServiceController {},
+        verbosity != VerbosityLevel::Minimal,
+        false,
+        verbosity,
+    )
+    .await?;
+
+    let services_for_ops = get_services_for_ops(&node_registry, peer_ids, service_names).await?;
+    if services_for_ops.is_empty() {
+        info!("Services for ops is empty, no services were eligible for reconfiguration");
+        if verbosity != VerbosityLevel::Minimal {
+            println!("No services were eligible for reconfiguration");
+        }
+        return Ok(());
+    }
+    if (node_port.is_some() || metrics_port.is_some() || rpc_port.is_some())
+        && services_for_ops.len() > 1
+    {
+        error!("Ports can only be changed when reconfiguring a single service");
+        return Err(
+            eyre!("Ports can only be changed when reconfiguring a single service")
+                .suggestion("Use the --service-name or --peer-id argument to select the service"),
+        );
+    }
+
+    let mut failed_services = Vec::new();
+    for node in &services_for_ops {
+        let original = node.read().await.clone();
+        let service_name = original.service_name.clone();
+
+        let mut changes = Vec::new();
+        if let Some(address) = rewards_address
+            && address != original.rewards_address
+        {
+            changes.push(FleetChange::RewardsAddress(address));
+        }
+        if let Some(auto_restart) = auto_restart
+            && auto_restart != original.auto_restart
+        {
+            changes.push(FleetChange::AutoRestart(auto_restart));
+        }
+        if let Some(no_upnp) = no_upnp
+            && no_upnp != original.no_upnp
+        {
+            changes.push(FleetChange::NoUpnp(no_upnp));
+        }
+        if let Some(relay) = relay
+            && relay != original.relay
+        {
+            changes.push(FleetChange::Relay(relay));
+        }
+        if log_format.is_some() && log_format != original.log_format {
+            changes.push(FleetChange::LogFormat(log_format));
+        }
+        if max_log_files.is_some() && max_log_files != original.max_log_files {
+            changes.push(FleetChange::MaxLogFiles(max_log_files));
+        }
+        if max_archived_log_files.is_some()
+            && max_archived_log_files != original.max_archived_log_files
+        {
+            changes.push(FleetChange::MaxArchivedLogFiles(max_archived_log_files));
+        }
+        if node_port.is_some() && node_port != original.node_port {
+            changes.push(FleetChange::NodePort(node_port));
+        }
+        if metrics_port.is_some() && metrics_port != original.metrics_port {
+            changes.push(FleetChange::MetricsPort(metrics_port));
+        }
+        if let Some(port) = rpc_port
+            && port != original.rpc_socket_addr.port()
+        {
+            changes.push(FleetChange::RpcPort(port));
+        }
+        if env_variables.is_some() {
+            changes.push(FleetChange::EnvVariables);
+        }
+
+        if changes.is_empty() {
+            debug!("Service {service_name} does not require any changes");
+            if verbosity != VerbosityLevel::Minimal {
+                println!(
+                    "{} Service {service_name} is already configured",
+                    "✓".green()
+                );
+            }
+            continue;
+        }
+        info!("Reconfiguring service {service_name} with: {changes:?}");
+        if verbosity != VerbosityLevel::Minimal {
+            for change in &changes {
+                println!("  {service_name}: {change}");
+            }
+        }
+
+        let mut reconfigured = original.clone();
+        for change in &changes {
+            change.apply_to(&mut reconfigured);
+        }
+        if let Err(err) =
+            check_reconfigured_ports(&reconfigured, &original, &node_registry.nodes).await
+        {
+            failed_services.push((service_name.clone(), err.to_string()));
+            continue;
+        }
+        *node.write().await = reconfigured;
+
+        // The environment variables are not stored per service, so the ones of the registry are
+        // used unless others are provided.
+        let service_env_variables = if env_variables.is_some() {
+            env_variables.clone()
+        } else {
+            node_registry.environment_variables.read().await.clone()
+        };
+        let auto_restart = node.read().await.auto_restart;
+        let rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
+        let service = NodeService::new(Arc::clone(node), Box::new(rpc_client));
+        let mut service_manager = ServiceManager::new(
+            service,
+            node.read().await.backend.controller_for(launch_as_children),
+            verbosity,
+        );
+        let restart = changes.iter().any(FleetChange::needs_restart);
+        match service_manager
+            .reconfigure(auto_restart, service_env_variables, restart)
+            .await
+        {
+            Ok(()) => {
+                debug!("Reconfigured service {service_name}");
+                // Like when adding services, the environment variables are kept for all of them,
+                // so that upgrades and later reconfigurations use them again.
+                if env_variables.is_some() {
+                    *node_registry.environment_variables.write().await = env_variables.clone();
+                }
+                node_registry.save().await?;
+            }
+            Err(err) => {
+                error!("Failed to reconfigure service {service_name}: {err}");
+                // Restore the previous settings, so the reconfiguration can be retried.
+                let mut node = node.write().await;
+                let (status, pid) = (node.status.clone(), node.pid);
+                *node = original;
+                node.status = status;
+                node.pid = pid;
+                failed_services.push((service_name.clone(), err.to_string()));
+            }
+        }
+    }
+
+    summarise_any_failed_ops(failed_services, "reconfigure", verbosity)
+}
+
 pub async fn remove(
     keep_directories: bool,
     peer_ids: Vec<String>,
@@ -212,6 +495,10 @@ pub async fn remove(
         "Removing antnode services with keep_dirs=({keep_directories}) for: {peer_ids:?}, {service_names:?}"
     );
 
+    // The daemon's supervision must not overwrite the changes made here, nor the other way round
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.reload().await?;
+
     refresh_node_registry(
         node_registry.clone(),
         &ServiceController {},
@@ -237,7 +524,7 @@ pub async fn remove(
         let rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
         let service = NodeService::new(Arc::clone(node), Box::new(rpc_client));
         let mut service_manager =
-            ServiceManager::new(service, Box::new(ServiceController {}), verbosity);
+            ServiceManager::new(service, node.read().await.backend.controller(), verbosity);
         match service_manager.remove(keep_directories).await {
             Ok(()) => {
                 debug!("Removed service {service_name}");
@@ -290,9 +577,170 @@ pub async fn reset(
     Ok(())
 }
 
+/// The download and upload rate limits of the services that have not been removed, by service
+/// name.
+pub async fn get_active_node_rate_limits(
+    node_registry: &NodeRegistryManager,
+) -> Vec<(String, Option<u64>, Option<u64>)> {
+    let mut rate_limits = Vec::new();
+    for node in node_registry.nodes.read().await.iter() {
+        let node = node.read().await;
+        if node.status != ServiceStatus::Removed {
+            rate_limits.push((
+                node.service_name.clone(),
+                node.max_download_rate,
+                node.max_upload_rate,
+            ));
+        }
+    }
+    rate_limits
+}
+
+/// The data directories of the services that have not been removed.
+async fn get_active_node_data_dirs(node_registry: &NodeRegistryManager) -> Vec<PathBuf> {
+    let mut data_dirs = Vec::new();
+    for node in node_registry.nodes.read().await.iter() {
+        let node = node.read().await;
+        if node.status != ServiceStatus::Removed {
+            data_dirs.push(node.data_dir_path.clone());
+        }
+    }
+    data_dirs
+}
+
+/// The nodes on the host, measured against the drive holding `data_dir`. The data of the nodes is
+/// walked on a blocking thread.
+async fn measure_existing_nodes(
+    node_registry: &NodeRegistryManager,
+    data_dir: &Path,
+) -> Result<ExistingNodes> {
+    let data_dirs = get_active_node_data_dirs(node_registry).await;
+    let data_dir = data_dir.to_path_buf();
+    Ok(tokio::task::spawn_blocking(move || ExistingNodes::measure(&data_dirs, &data_dir)).await?)
+}
+
+/// Print the resources of the host, and the number of nodes recommended for it when adding nodes
+/// to each drive.
+///
+/// Without a data directory, the drives are the ones holding the existing nodes, or the one of
+/// the default data directory if there are none.
+pub async fn resources(
+    bandwidth_probe_url: Option<String>,
+    data_dir_path: Option<PathBuf>,
+    json: bool,
+    node_registry: NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    let node_data_dirs = get_active_node_data_dirs(&node_registry).await;
+    let data_dir_paths = match data_dir_path {
+        Some(path) => vec![path],
+        None if node_data_dirs.is_empty() => {
+            vec![config::get_default_service_data_dir_path(
+                is_running_as_root(),
+            )?]
+        }
+        // The services are added underneath the directory given to the add command.
+        None => node_data_dirs
+            .iter()
+            .map(|path| path.parent().unwrap_or(path).to_path_buf())
+            .collect(),
+    };
+
+    let mut bandwidth_bps = None;
+    if let Some(url) = bandwidth_probe_url {
+        if verbosity != VerbosityLevel::Minimal && !json {
+            println!("Probing the bandwidth...");
+        }
+        bandwidth_bps = Some(probe_bandwidth(&url).await?);
+    }
+
+    // One report per drive, for the first data directory found on it.
+    let drives = tokio::task::spawn_blocking(move || {
+        let mut drives: Vec<(PathBuf, HostResources, ExistingNodes)> = Vec::new();
+        for data_dir_path in data_dir_paths {
+            let resources = HostResources {
+                bandwidth_bps,
+                ..HostResources::measure(&data_dir_path)
+            };
+            if drives
+                .iter()
+                .any(|(_, drive, _)| drive.mount_point == resources.mount_point)
+            {
+                continue;
+            }
+            let existing_nodes = ExistingNodes::measure(&node_data_dirs, &data_dir_path);
+            drives.push((data_dir_path, resources, existing_nodes));
+        }
+        drives
+    })
+    .await?;
+
+    if json {
+        let drives: Vec<_> = drives
+            .iter()
+            .map(|(data_dir_path, resources, existing_nodes)| {
+                let recommendation = resources.recommend(existing_nodes);
+                serde_json::json!({
+                    "data_dir_path": data_dir_path,
+                    "resources": resources,
+                    "existing_nodes": existing_nodes,
+                    "projected_usage": ProjectedUsage::for_nodes(recommendation.max_nodes),
+                    "recommendation": recommendation,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&drives)?);
+        return Ok(());
+    }
+
+    let gb = |bytes: u64| format!("{:.1}GB", bytes as f64 / GB as f64);
+    let mbps = |bps: u64| format!("{:.1}MB/s", bps as f64 / MB as f64);
+    print_banner("Host Resources");
+    if let Some((_, resources, existing_nodes)) = drives.first() {
+        println!("Memory: {}", gb(resources.total_memory_b));
+        println!("CPU cores: {}", resources.cpu_cores);
+        println!(
+            "Bandwidth: {}",
+            bandwidth_bps.map_or("not measured".to_string(), mbps)
+        );
+        println!("Existing nodes: {}", existing_nodes.count);
+    }
+    for (data_dir_path, resources, existing_nodes) in &drives {
+        let recommendation = resources.recommend(existing_nodes);
+        let usage = ProjectedUsage::for_nodes(recommendation.max_nodes);
+        println!();
+        println!("Adding nodes under {}:", data_dir_path.to_string_lossy());
+        println!(
+            "  Free disk space: {} on {}, with {} node(s) using {}",
+            gb(resources.available_disk_b),
+            resources
+                .mount_point
+                .as_ref()
+                .map_or("an unknown drive".to_string(), |mount_point| mount_point
+                    .to_string_lossy()
+                    .into_owned()),
+            existing_nodes.on_drive,
+            gb(existing_nodes.drive_usage_b)
+        );
+        println!(
+            "  Recommended maximum: {} nodes, limited by the {}",
+            recommendation.max_nodes, recommendation.limited_by
+        );
+        println!(
+            "  Projected usage: {} of memory, {:.1} CPU cores, {} of disk space and {} of bandwidth",
+            gb(usage.memory_b),
+            usage.cpu_cores,
+            gb(usage.disk_b),
+            mbps(usage.bandwidth_bps)
+        );
+    }
+    Ok(())
+}
+
 pub async fn start(
     connection_timeout_s: u64,
     fixed_interval: Option<u64>,
+    launch_as_children: bool,
     node_registry: NodeRegistryManager,
     peer_ids: Vec<String>,
     service_names: Vec<String>,
@@ -303,6 +751,18 @@ pub async fn start(
     }
     info!("Starting antnode services for: {peer_ids:?}, {service_names:?}");
 
+    // This must happen before the lock is taken, as the daemon takes it to start the services.
+    // The daemon itself launches the processes as its children.
+    let delegated_names = if launch_as_children {
+        Vec::new()
+    } else {
+        delegate_process_starts(&node_registry, &peer_ids, &service_names, verbosity).await?
+    };
+
+    // The daemon's supervision must not overwrite the changes made here, nor the other way round
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.reload().await?;
+
     refresh_node_registry(
         node_registry.clone(),
         &ServiceController {},
@@ -312,8 +772,16 @@ pub async fn start(
     )
     .await?;
 
-    let services_for_ops = get_services_for_ops(&node_registry, peer_ids, service_names).await?;
+    let mut services_for_ops = Vec::new();
+    for node in get_services_for_ops(&node_registry, peer_ids, service_names).await? {
+        if !delegated_names.contains(&node.read().await.service_name) {
+            services_for_ops.push(node);
+        }
+    }
     if services_for_ops.is_empty() {
+        if !delegated_names.is_empty() {
+            return Ok(());
+        }
         info!("No services are eligible to be started");
         // This could be the case if all services are at `Removed` status.
         if verbosity != VerbosityLevel::Minimal {
@@ -322,6 +790,15 @@ pub async fn start(
         return Ok(());
     }
 
+    // Services operated on by hand are no longer the supervisor's to restart or quarantine.
+    let mut supervised_names = Vec::new();
+    for node in &services_for_ops {
+        supervised_names.push(node.read().await.service_name.clone());
+    }
+    if let Err(err) = reset_supervision(&node_registry, &supervised_names) {
+        warn!("Could not reset the supervision of the services: {err}");
+    }
+
     let mut failed_services = Vec::new();
     for node in &services_for_ops {
         let service_name = node.read().await.service_name.clone();
@@ -336,8 +813,11 @@ pub async fn start(
             service
         };
 
-        let mut service_manager =
-            ServiceManager::new(service, Box::new(ServiceController {}), verbosity);
+        let mut service_manager = ServiceManager::new(
+            service,
+            node.read().await.backend.controller_for(launch_as_children),
+            verbosity,
+        );
         if service_manager.service.status().await != ServiceStatus::Running {
             // It would be possible here to check if the service *is* running and then just
             // continue without applying the delay. The reason for not doing so is because when
@@ -364,6 +844,64 @@ pub async fn start(
     summarise_any_failed_ops(failed_services, "start", verbosity)
 }
 
+/// Have the local daemon, if it runs, start the selected services run as plain processes, so they
+/// are launched as its children and supervised by it. The names of those it started are returned.
+///
+/// Should the daemon not be reachable, the services are left to be started by antctl.
+async fn delegate_process_starts(
+    node_registry: &NodeRegistryManager,
+    peer_ids: &[String],
+    service_names: &[String],
+    verbosity: VerbosityLevel,
+) -> Result<Vec<String>> {
+    let endpoint = match &*node_registry.daemon.read().await {
+        Some(daemon) => {
+            let daemon = daemon.read().await;
+            match (&daemon.status, daemon.endpoint) {
+                (ServiceStatus::Running, Some(endpoint)) => endpoint,
+                _ => return Ok(Vec::new()),
+            }
+        }
+        None => return Ok(Vec::new()),
+    };
+
+    let mut process_names = Vec::new();
+    for node in
+        get_services_for_ops(node_registry, peer_ids.to_vec(), service_names.to_vec()).await?
+    {
+        let node = node.read().await;
+        if node.backend == ServiceBackend::Process {
+            process_names.push(node.service_name.clone());
+        }
+    }
+    if process_names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    debug!("Asking the daemon at {endpoint} to start {process_names:?}");
+    let auth = DaemonAuth {
+        token: std::env::var("ANTCTL_DAEMON_TOKEN").ok(),
+        ..Default::default()
+    };
+    let request = NodeServicesRequest {
+        service_names: process_names.clone(),
+        peer_ids: Vec::new(),
+    };
+    match remote::start(endpoint, &auth, request, verbosity).await {
+        Ok(()) => Ok(process_names),
+        Err(err) => {
+            warn!("The daemon at {endpoint} could not start {process_names:?}: {err}");
+            if verbosity != VerbosityLevel::Minimal {
+                println!(
+                    "{} The daemon could not start the services, they will not be supervised",
+                    "⚠".yellow()
+                );
+            }
+            Ok(Vec::new())
+        }
+    }
+}
+
 pub async fn status(
     details: bool,
     fail: bool,
@@ -400,6 +938,10 @@ pub async fn stop(
     }
     info!("Stopping antnode services for: {peer_ids:?}, {service_names:?}");
 
+    // The daemon's supervision must not overwrite the changes made here, nor the other way round
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.reload().await?;
+
     refresh_node_registry(
         node_registry.clone(),
         &ServiceController {},
@@ -419,13 +961,22 @@ pub async fn stop(
         return Ok(());
     }
 
+    // Services operated on by hand are no longer the supervisor's to restart or quarantine.
+    let mut supervised_names = Vec::new();
+    for node in &services_for_ops {
+        supervised_names.push(node.read().await.service_name.clone());
+    }
+    if let Err(err) = reset_supervision(&node_registry, &supervised_names) {
+        warn!("Could not reset the supervision of the services: {err}");
+    }
+
     let mut failed_services = Vec::new();
     for node in services_for_ops.iter() {
         let service_name = node.read().await.service_name.clone();
         let rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
         let service = NodeService::new(Arc::clone(node), Box::new(rpc_client));
         let mut service_manager =
-            ServiceManager::new(service, Box::new(ServiceController {}), verbosity);
+            ServiceManager::new(service, node.read().await.backend.controller(), verbosity);
 
         if service_manager.service.status().await == ServiceStatus::Running
             && let Some(interval) = interval
@@ -454,10 +1005,12 @@ pub async fn upgrade(
     custom_bin_path: Option<PathBuf>,
     force: bool,
     fixed_interval: Option<u64>,
+    launch_as_children: bool,
     node_registry: NodeRegistryManager,
     peer_ids: Vec<String>,
     provided_env_variables: Option<Vec<(String, String)>>,
     service_names: Vec<String>,
+    strategy: RollingUpgradeStrategy,
     url: Option<String>,
     version: Option<String>,
     verbosity: VerbosityLevel,
@@ -483,6 +1036,10 @@ pub async fn upgrade(
     )
     .await?;
 
+    // The daemon's supervision must not overwrite the changes made here, nor the other way round
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.reload().await?;
+
     refresh_node_registry(
         node_registry.clone(),
         &ServiceController {},
@@ -524,67 +1081,184 @@ pub async fn upgrade(
     let services_for_ops = get_services_for_ops(&node_registry, peer_ids, service_names).await?;
     trace!("services_for_ops len: {}", services_for_ops.len());
     let mut upgrade_summary = Vec::new();
+    let stages = strategy.stages(services_for_ops.len());
+    let stage_count = stages.len();
+    let mut halted_at = None;
+
+    for (stage_index, stage) in stages.into_iter().enumerate() {
+        if strategy.health_gate.is_some() && verbosity != VerbosityLevel::Minimal {
+            println!(
+                "Upgrading stage {}/{stage_count} ({} service(s))",
+                stage_index + 1,
+                stage.len()
+            );
+        }
+        // The services upgraded in this stage, with their health before the upgrade and their
+        // index in the summary.
+        let mut upgraded = Vec::new();
 
-    for node in &services_for_ops {
-        let env_variables = if provided_env_variables.is_some() {
-            provided_env_variables.clone()
-        } else {
-            node_registry.environment_variables.read().await.clone()
-        };
-        let options = UpgradeOptions {
-            auto_restart: false,
-            env_variables: env_variables.clone(),
-            force: use_force,
-            start_service: !do_not_start,
-            target_bin_path: upgrade_bin_path.clone(),
-            target_version: target_version.clone(),
-        };
-        let service_name = node.read().await.service_name.clone();
+        for node in &services_for_ops[stage.clone()] {
+            let env_variables = if provided_env_variables.is_some() {
+                provided_env_variables.clone()
+            } else {
+                node_registry.environment_variables.read().await.clone()
+            };
+            let options = UpgradeOptions {
+                auto_restart: false,
+                env_variables: env_variables.clone(),
+                force: use_force,
+                start_service: !do_not_start,
+                target_bin_path: upgrade_bin_path.clone(),
+                target_version: target_version.clone(),
+            };
+            let service_name = node.read().await.service_name.clone();
+
+            let rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
+            let health_before = if strategy.health_gate.is_some()
+                && node.read().await.status == ServiceStatus::Running
+            {
+                NodeHealth::probe(&rpc_client).await.ok()
+            } else {
+                None
+            };
+            let service = NodeService::new(Arc::clone(node), Box::new(rpc_client));
+            // set dynamic startup delay if fixed_interval is not set
+            let service = if fixed_interval.is_none() {
+                service.with_connection_timeout(Duration::from_secs(connection_timeout_s))
+            } else {
+                service
+            };
 
-        let rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
-        let service = NodeService::new(Arc::clone(node), Box::new(rpc_client));
-        // set dynamic startup delay if fixed_interval is not set
-        let service = if fixed_interval.is_none() {
-            service.with_connection_timeout(Duration::from_secs(connection_timeout_s))
-        } else {
-            service
-        };
+            let mut service_manager = ServiceManager::new(
+                service,
+                node.read().await.backend.controller_for(launch_as_children),
+                verbosity,
+            );
 
-        let mut service_manager =
-            ServiceManager::new(service, Box::new(ServiceController {}), verbosity);
-
-        match service_manager.upgrade(options).await {
-            Ok(upgrade_result) => {
-                info!("Service: {service_name} has been upgraded, result: {upgrade_result:?}",);
-                if upgrade_result != UpgradeResult::NotRequired {
-                    // It doesn't seem useful to apply the interval if there was no upgrade
-                    // required for the previous service.
-                    if let Some(interval) = fixed_interval {
-                        debug!("Sleeping for {interval} milliseconds",);
-                        std::thread::sleep(std::time::Duration::from_millis(interval));
+            match service_manager.upgrade(options).await {
+                Ok(upgrade_result) => {
+                    info!("Service: {service_name} has been upgraded, result: {upgrade_result:?}",);
+                    if upgrade_result != UpgradeResult::NotRequired {
+                        // It doesn't seem useful to apply the interval if there was no upgrade
+                        // required for the previous service.
+                        if let Some(interval) = fixed_interval {
+                            debug!("Sleeping for {interval} milliseconds",);
+                            std::thread::sleep(std::time::Duration::from_millis(interval));
+                        }
+                    }
+                    if matches!(
+                        upgrade_result,
+                        UpgradeResult::Upgraded(_, _) | UpgradeResult::Forced(_, _)
+                    ) {
+                        upgraded.push((
+                            Arc::clone(node),
+                            health_before,
+                            env_variables,
+                            upgrade_summary.len(),
+                        ));
                     }
+                    upgrade_summary.push((service_name.clone(), upgrade_result));
+                    node_registry.save().await?;
+                }
+                Err(err) => {
+                    error!("Error upgrading service {service_name}: {err}");
+                    upgrade_summary.push((
+                        service_name.clone(),
+                        UpgradeResult::Error(format!("Error: {err}")),
+                    ));
+                    node_registry.save().await?;
                 }
-                upgrade_summary.push((service_name.clone(), upgrade_result));
-                node_registry.save().await?;
-            }
-            Err(err) => {
-                error!("Error upgrading service {service_name}: {err}");
-                upgrade_summary.push((
-                    service_name.clone(),
-                    UpgradeResult::Error(format!("Error: {err}")),
-                ));
-                node_registry.save().await?;
             }
         }
+
+        let Some(health_gate) = &strategy.health_gate else {
+            continue;
+        };
+        if do_not_start {
+            continue;
+        }
+        // The services of the stage are given the timeout together rather than one after another
+        let mut health_checks = tokio::task::JoinSet::new();
+        for (index, (node, health_before, _, _)) in upgraded.iter().enumerate() {
+            let rpc_socket_addr = node.read().await.rpc_socket_addr;
+            let health_gate = health_gate.clone();
+            let health_before = health_before.clone();
+            health_checks.spawn(async move {
+                let rpc_client = RpcClient::from_socket_addr(rpc_socket_addr);
+                let health = health_gate
+                    .wait_until_healthy(&rpc_client, health_before.as_ref())
+                    .await;
+                (index, health)
+            });
+        }
+        let mut health_results = health_checks.join_all().await;
+        health_results.sort_by_key(|(index, _)| *index);
+
+        let mut stage_healthy = true;
+        for ((node, _, env_variables, summary_index), (_, health)) in
+            upgraded.into_iter().zip(health_results)
+        {
+            let Err(reason) = health else {
+                continue;
+            };
+            stage_healthy = false;
+            let rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
+
+            let (service_name, result) = &upgrade_summary[summary_index];
+            let service_name = service_name.clone();
+            let (UpgradeResult::Upgraded(previous_version, target_version)
+            | UpgradeResult::Forced(previous_version, target_version)) = result.clone()
+            else {
+                continue;
+            };
+            warn!("Service {service_name} is unhealthy after the upgrade: {reason}");
+
+            let service = NodeService::new(Arc::clone(&node), Box::new(rpc_client));
+            let mut service_manager = ServiceManager::new(
+                service,
+                node.read().await.backend.controller_for(launch_as_children),
+                verbosity,
+            );
+            upgrade_summary[summary_index].1 = match service_manager
+                .rollback(&previous_version, false, env_variables)
+                .await
+            {
+                Ok(()) => UpgradeResult::RolledBack(previous_version, target_version, reason),
+                Err(err) => {
+                    error!("Failed to roll back service {service_name}: {err}");
+                    UpgradeResult::Error(format!(
+                        "Unhealthy after the upgrade ({reason}) and the rollback failed: {err}"
+                    ))
+                }
+            };
+            node_registry.save().await?;
+        }
+        if !stage_healthy {
+            halted_at = Some(stage.end);
+            break;
+        }
     }
 
     if verbosity != VerbosityLevel::Minimal {
         print_upgrade_summary(upgrade_summary.clone());
     }
 
+    if let Some(halted_at) = halted_at {
+        let remaining = services_for_ops.len() - halted_at;
+        warn!("The upgrade was halted with {remaining} service(s) not upgraded");
+        if verbosity != VerbosityLevel::Minimal {
+            println!(
+                "{} The upgrade was halted because of unhealthy services; {remaining} service(s) \
+                were not upgraded",
+                "✕".red()
+            );
+        }
+    }
+
     if upgrade_summary.iter().any(|(_, r)| {
         matches!(r, UpgradeResult::Error(_))
             || matches!(r, UpgradeResult::UpgradedButNotStarted(_, _, _))
+            || matches!(r, UpgradeResult::RolledBack(_, _, _))
     }) {
         return Err(eyre!("There was a problem upgrading one or more nodes").suggestion(
             "For any services that were upgraded but did not start, you can attempt to start them \
@@ -607,10 +1281,13 @@ pub async fn maintain_n_running_nodes(
     enable_metrics_server: bool,
     env_variables: Option<Vec<(String, String)>>,
     evm_network: Option<EvmNetwork>,
+    ignore_resource_limit: bool,
     log_dir_path: Option<PathBuf>,
     log_format: Option<LogFormat>,
     max_archived_log_files: Option<usize>,
+    max_download_rate: Option<u64>,
     max_log_files: Option<usize>,
+    max_upload_rate: Option<u64>,
     metrics_port: Option<PortRange>,
     network_id: Option<u8>,
     node_ip: Option<Ipv4Addr>,
@@ -690,6 +1367,7 @@ pub async fn maintain_n_running_nodes(
                 start(
                     connection_timeout_s,
                     start_node_interval,
+                    false,
                     node_registry.clone(),
                     vec![],
                     nodes_to_start,
@@ -712,20 +1390,25 @@ pub async fn maintain_n_running_nodes(
                     None => vec![],
                 };
 
+                let backend = node_registry.service_backend().await;
                 for (i, port) in ports_to_use.into_iter().enumerate() {
                     let added_service = add(
                         alpha,
                         auto_restart,
                         auto_set_nat_flags,
+                        backend.clone(),
                         Some(1),
                         data_dir_path.clone(),
                         enable_metrics_server,
                         env_variables.clone(),
                         evm_network.clone(),
+                        ignore_resource_limit,
                         log_dir_path.clone(),
                         log_format,
                         max_archived_log_files,
+                        max_download_rate,
                         max_log_files,
+                        max_upload_rate,
                         metrics_port.clone(),
                         network_id,
                         node_ip,
@@ -750,6 +1433,7 @@ pub async fn maintain_n_running_nodes(
                         start(
                             connection_timeout_s,
                             start_node_interval,
+                            false,
                             node_registry.clone(),
                             vec![],
                             added_service,
@@ -763,6 +1447,7 @@ pub async fn maintain_n_running_nodes(
                     start(
                         connection_timeout_s,
                         start_node_interval,
+                        false,
                         node_registry.clone(),
                         vec![],
                         inactive_nodes,
@@ -858,7 +1543,7 @@ async fn get_services_for_ops(
     Ok(services)
 }
 
-fn summarise_any_failed_ops(
+pub(crate) fn summarise_any_failed_ops(
     failed_services: Vec<(String, String)>,
     verb: &str,
     verbosity: VerbosityLevel,
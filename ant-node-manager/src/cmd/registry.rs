@@ -0,0 +1,199 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    VerbosityLevel, print_banner,
+    registry::{diff_registries, reconcile as reconcile_registry},
+    supervisor::OperationLock,
+};
+use ant_service_management::{NodeRegistryManager, RegistrySnapshot};
+use color_eyre::{Result, eyre::eyre};
+use serde_json::Value;
+use std::{io::Write, path::Path};
+
+/// Print the snapshots kept in the history of the registry.
+pub fn history(node_registry: NodeRegistryManager, verbosity: VerbosityLevel) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner("Node Registry History");
+    }
+    let snapshots = node_registry.snapshots()?;
+    if snapshots.is_empty() {
+        println!("The registry has no snapshots");
+        return Ok(());
+    }
+
+    println!("{:<15} {:<25} {:>8}", "ID", "Taken", "Services");
+    for snapshot in &snapshots {
+        let services = read_registry(&snapshot.path)
+            .ok()
+            .and_then(|registry| {
+                registry
+                    .get("nodes")
+                    .and_then(Value::as_array)
+                    .map(|nodes| {
+                        nodes
+                            .iter()
+                            .filter(|node| {
+                                node.get("status").and_then(Value::as_str) != Some("Removed")
+                            })
+                            .count()
+                    })
+            })
+            .map_or("-".to_string(), |count| count.to_string());
+        println!(
+            "{:<15} {:<25} {:>8}",
+            snapshot.id,
+            format_snapshot_time(snapshot),
+            services
+        );
+    }
+    if verbosity != VerbosityLevel::Minimal {
+        println!();
+        println!("Use 'antctl registry diff <ID>' to see how a snapshot differs from the registry");
+    }
+    Ok(())
+}
+
+/// Print the differences between a snapshot and either another snapshot or the current registry.
+pub fn diff(
+    id: u64,
+    other_id: Option<u64>,
+    node_registry: NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner("Node Registry Diff");
+    }
+    let old = read_registry(&find_snapshot(&node_registry, id)?.path)?;
+    let (new, new_name) = match other_id {
+        Some(other_id) => (
+            read_registry(&find_snapshot(&node_registry, other_id)?.path)?,
+            format!("snapshot {other_id}"),
+        ),
+        None => (
+            read_registry(&node_registry.save_path)?,
+            "the current registry".to_string(),
+        ),
+    };
+
+    let changes = diff_registries(&old, &new);
+    if changes.is_empty() {
+        println!("No changes from snapshot {id} to {new_name}");
+        return Ok(());
+    }
+    println!("Changes from snapshot {id} to {new_name}:");
+    for change in changes {
+        println!("  {change}");
+    }
+    Ok(())
+}
+
+/// Replace the registry with one of its snapshots.
+///
+/// The services themselves are not changed, so their definitions may no longer match the
+/// restored registry.
+pub async fn restore(
+    force: bool,
+    id: u64,
+    node_registry: NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner("Restore Node Registry");
+    }
+    let snapshot = find_snapshot(&node_registry, id)?;
+
+    let changes = diff_registries(
+        &read_registry(&node_registry.save_path)?,
+        &read_registry(&snapshot.path)?,
+    );
+    if changes.is_empty() {
+        println!("The registry already matches snapshot {id}");
+        return Ok(());
+    }
+    println!("Restoring snapshot {id} makes these changes to the registry:");
+    for change in &changes {
+        println!("  {change}");
+    }
+
+    if !force {
+        println!("The services themselves will not be changed. Do you wish to proceed? [y/n]");
+        std::io::stdout().flush()?;
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if input.trim().to_lowercase() != "y" {
+            println!("Restore aborted");
+            return Ok(());
+        }
+    }
+
+    // The daemon's supervision must not overwrite the restored registry
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.restore(id).await?;
+    info!("Restored the node registry from snapshot {id}");
+    println!("Restored the registry from snapshot {id}");
+    println!("The registry it replaced was kept as a snapshot, so the restore can be undone");
+    Ok(())
+}
+
+/// Add the antnode services installed on the host but missing from the registry.
+pub async fn reconcile(
+    node_registry: NodeRegistryManager,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner("Reconcile Node Registry");
+    }
+    // The daemon's supervision must not overwrite the changes made here, nor the other way round
+    let _lock = OperationLock::acquire(&node_registry.save_path).await?;
+    node_registry.reload().await?;
+    let reconciliation = reconcile_registry(&node_registry).await?;
+
+    for service_name in &reconciliation.added {
+        println!("Added {service_name} to the registry");
+    }
+    for service_name in &reconciliation.missing_definitions {
+        println!("{service_name} is in the registry but has no service definition on this host");
+    }
+    for (service_name, err) in &reconciliation.failed {
+        println!("Failed to read the service definition of {service_name}: {err}");
+    }
+    if reconciliation.added.is_empty() && reconciliation.failed.is_empty() {
+        println!("The registry has every antnode service installed on this host");
+    }
+
+    if !reconciliation.failed.is_empty() {
+        return Err(eyre!(
+            "{} service definition(s) could not be read",
+            reconciliation.failed.len()
+        ));
+    }
+    Ok(())
+}
+
+fn find_snapshot(node_registry: &NodeRegistryManager, id: u64) -> Result<RegistrySnapshot> {
+    node_registry
+        .snapshots()?
+        .into_iter()
+        .find(|snapshot| snapshot.id == id)
+        .ok_or_else(|| eyre!("The registry has no snapshot with the ID {id}"))
+}
+
+fn read_registry(path: &Path) -> Result<Value> {
+    if !path.exists() {
+        return Ok(Value::Null);
+    }
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+fn format_snapshot_time(snapshot: &RegistrySnapshot) -> String {
+    chrono::DateTime::from_timestamp_millis(snapshot.id as i64).map_or("-".to_string(), |time| {
+        time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+    })
+}
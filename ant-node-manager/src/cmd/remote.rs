@@ -0,0 +1,282 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! The commands run against a remote machine, through its `antctld` daemon.
+
+use super::print_node_metrics;
+use crate::{
+    VerbosityLevel, print_banner,
+    rpc::NodeMetrics,
+    rpc_client::{self, DaemonAuth, DaemonClient},
+};
+use ant_service_management::antctl_proto::{
+    AddNodeServicesRequest, GetNodeMetricsRequest, GetStatusRequest, NodeServicesRequest,
+    ReconfigureNodeServicesRequest, RemoveNodeServicesRequest, TailNodeLogsRequest,
+    UpgradeNodeServicesRequest, get_status_response::ServiceStatus,
+};
+use color_eyre::{Result, eyre::eyre};
+use colored::Colorize;
+use libp2p_identity::PeerId;
+use std::net::SocketAddr;
+use tonic::{Request, Status};
+
+/// Add services on the remote machine. They are not started.
+pub async fn add(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    request: AddNodeServicesRequest,
+    verbosity: VerbosityLevel,
+) -> Result<Vec<String>> {
+    if verbosity != VerbosityLevel::Minimal {
+        print_banner(&format!("Add Antnode Services on {addr}"));
+    }
+    let mut client = connect(addr, auth).await?;
+    let response = client
+        .add_node_services(Request::new(request))
+        .await
+        .map_err(to_error)?
+        .into_inner();
+    if verbosity != VerbosityLevel::Minimal {
+        for service_name in &response.service_names {
+            println!("{} Added {service_name}", "✓".green());
+        }
+        println!("[!] Note: newly added services have not been started");
+    }
+    Ok(response.service_names)
+}
+
+pub async fn start(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    services: NodeServicesRequest,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    let mut client = connect(addr, auth).await?;
+    client
+        .start_node_services(Request::new(services))
+        .await
+        .map_err(to_error)?;
+    print_done("started", addr, verbosity);
+    Ok(())
+}
+
+pub async fn stop(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    services: NodeServicesRequest,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    let mut client = connect(addr, auth).await?;
+    client
+        .stop_node_services(Request::new(services))
+        .await
+        .map_err(to_error)?;
+    print_done("stopped", addr, verbosity);
+    Ok(())
+}
+
+pub async fn remove(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    request: RemoveNodeServicesRequest,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    let mut client = connect(addr, auth).await?;
+    client
+        .remove_node_services(Request::new(request))
+        .await
+        .map_err(to_error)?;
+    print_done("removed", addr, verbosity);
+    Ok(())
+}
+
+pub async fn upgrade(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    request: UpgradeNodeServicesRequest,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal {
+        println!("Upgrading the services on {addr}, which can take a while...");
+    }
+    let mut client = connect(addr, auth).await?;
+    client
+        .upgrade_node_services(Request::new(request))
+        .await
+        .map_err(to_error)?;
+    print_done("upgraded", addr, verbosity);
+    Ok(())
+}
+
+pub async fn reconfigure(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    request: ReconfigureNodeServicesRequest,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    let mut client = connect(addr, auth).await?;
+    client
+        .reconfigure_node_services(Request::new(request))
+        .await
+        .map_err(to_error)?;
+    print_done("reconfigured", addr, verbosity);
+    Ok(())
+}
+
+/// The download and upload rate limits of the services on the remote machine that have not been
+/// removed, by service name.
+pub async fn active_node_rate_limits(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+) -> Result<Vec<(String, Option<u64>, Option<u64>)>> {
+    let mut client = connect(addr, auth).await?;
+    let response = client
+        .get_status(Request::new(GetStatusRequest {}))
+        .await
+        .map_err(to_error)?
+        .into_inner();
+    Ok(response
+        .nodes
+        .into_iter()
+        .filter(|node| ServiceStatus::from_i32(node.status) != Some(ServiceStatus::Removed))
+        .map(|node| {
+            (
+                node.service_name,
+                node.max_download_rate,
+                node.max_upload_rate,
+            )
+        })
+        .collect())
+}
+
+pub async fn status(addr: SocketAddr, auth: &DaemonAuth, json: bool) -> Result<()> {
+    let mut client = connect(addr, auth).await?;
+    let response = client
+        .get_status(Request::new(GetStatusRequest {}))
+        .await
+        .map_err(to_error)?
+        .into_inner();
+
+    let nodes: Vec<(String, String, String)> = response
+        .nodes
+        .iter()
+        .map(|node| {
+            let peer_id = node
+                .peer_id
+                .as_ref()
+                .and_then(|bytes| PeerId::from_bytes(bytes).ok())
+                .map_or("-".to_string(), |id| id.to_string());
+            let status = ServiceStatus::from_i32(node.status)
+                .map_or("UNKNOWN".to_string(), |status| {
+                    format!("{status:?}").to_uppercase()
+                });
+            (node.service_name.clone(), peer_id, status)
+        })
+        .collect();
+
+    if json {
+        let nodes: Vec<serde_json::Value> = nodes
+            .into_iter()
+            .map(|(service_name, peer_id, status)| {
+                serde_json::json!({
+                    "service_name": service_name,
+                    "peer_id": peer_id,
+                    "status": status,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&nodes)?);
+        return Ok(());
+    }
+
+    print_banner(&format!("Antnode Services on {addr}"));
+    println!("{:<18} {:<52} {:<7}", "Service Name", "Peer ID", "Status");
+    for (service_name, peer_id, status) in nodes {
+        println!("{service_name:<18} {peer_id:<52} {status:<7}");
+    }
+    Ok(())
+}
+
+/// Print the last lines of the log of a remote service, then optionally the lines appended to it.
+pub async fn logs(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    follow: bool,
+    lines: usize,
+    service_name: String,
+) -> Result<()> {
+    let mut client = connect(addr, auth).await?;
+    let mut stream = client
+        .tail_node_logs(Request::new(TailNodeLogsRequest {
+            service_name,
+            lines: lines as u32,
+            follow,
+        }))
+        .await
+        .map_err(to_error)?
+        .into_inner();
+    while let Some(line) = stream.message().await.map_err(to_error)? {
+        println!("{}", line.line);
+    }
+    Ok(())
+}
+
+pub async fn metrics(
+    addr: SocketAddr,
+    auth: &DaemonAuth,
+    json: bool,
+    service_names: Vec<String>,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if verbosity != VerbosityLevel::Minimal && !json {
+        print_banner(&format!("Antnode Metrics on {addr}"));
+    }
+    let mut client = connect(addr, auth).await?;
+    let response = client
+        .get_node_metrics(Request::new(GetNodeMetricsRequest { service_names }))
+        .await
+        .map_err(to_error)?
+        .into_inner();
+    let metrics: Vec<NodeMetrics> = response
+        .nodes
+        .into_iter()
+        .map(|node| NodeMetrics {
+            service_name: node.service_name,
+            peer_id: node
+                .peer_id
+                .and_then(|bytes| PeerId::from_bytes(&bytes).ok()),
+            version: node.version,
+            pid: node.pid,
+            uptime_secs: node.uptime_secs,
+            connected_peers: node.connected_peers as usize,
+            routing_table_size: node.routing_table_size as usize,
+            records_stored: node.records_stored as usize,
+            wallet_balance: node.wallet_balance,
+            error: node.error,
+        })
+        .collect();
+    print_node_metrics(&metrics, json)
+}
+
+async fn connect(addr: SocketAddr, auth: &DaemonAuth) -> Result<DaemonClient> {
+    rpc_client::connect(addr, auth).await.map_err(|err| {
+        error!("Could not connect to the daemon at {addr}: {err}");
+        eyre!("Could not connect to the daemon at {addr}: {err}")
+    })
+}
+
+fn to_error(status: Status) -> color_eyre::eyre::Error {
+    error!("The daemon returned an error: {status:?}");
+    eyre!("The daemon returned an error: {}", status.message())
+}
+
+fn print_done(operation: &str, addr: SocketAddr, verbosity: VerbosityLevel) {
+    if verbosity != VerbosityLevel::Minimal {
+        println!("{} The services on {addr} were {operation}", "✓".green());
+    }
+}
@@ -122,15 +122,9 @@ pub fn get_service_data_dir_path(
             p
         }
         None => {
-            if owner.is_some() {
-                let path = PathBuf::from("/var/antctl/services");
-                debug!("Using default path for service data dir: {path:?}");
-                path
-            } else {
-                let path = get_user_antnode_data_dir()?;
-                debug!("Using user mode service data dir: {path:?}");
-                path
-            }
+            let path = get_default_service_data_dir_path(owner.is_some())?;
+            debug!("Using default path for service data dir: {path:?}");
+            path
         }
     };
     if let Some(owner) = owner {
@@ -150,7 +144,7 @@ pub fn get_service_data_dir_path(
             p
         }
         None => {
-            let path = PathBuf::from("C:\\ProgramData\\antctl\\data");
+            let path = get_default_service_data_dir_path(true)?;
             debug!("Using default path for service data dir: {path:?}");
             path
         }
@@ -159,6 +153,22 @@ pub fn get_service_data_dir_path(
     Ok(path)
 }
 
+/// The data directory of the services when none is specified, which is not created. The
+/// system-wide services and the user-mode ones have a different default.
+#[cfg(unix)]
+pub fn get_default_service_data_dir_path(system_wide: bool) -> Result<PathBuf> {
+    if system_wide {
+        Ok(PathBuf::from("/var/antctl/services"))
+    } else {
+        get_user_antnode_data_dir()
+    }
+}
+
+#[cfg(windows)]
+pub fn get_default_service_data_dir_path(_system_wide: bool) -> Result<PathBuf> {
+    Ok(PathBuf::from("C:\\ProgramData\\antctl\\data"))
+}
+
 /// Get the bootstrap cache owner path
 #[cfg(unix)]
 pub fn get_bootstrap_cache_owner_path(owner: &str) -> Result<PathBuf> {
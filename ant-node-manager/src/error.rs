@@ -23,6 +23,8 @@ pub enum Error {
     PidNotFoundAfterStarting,
     #[error("The PID of the process was not set.")]
     PidNotSet,
+    #[error("There is no previous binary at {0:?} to roll back to")]
+    PreviousBinaryNotFound(std::path::PathBuf),
     #[error(transparent)]
     SemverError(#[from] semver::Error),
     #[error("Unable to remove a running service {0:?}, stop this service first before removing")]
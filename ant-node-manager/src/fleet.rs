@@ -0,0 +1,892 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Declarative description of a fleet of antnode services, and the planning of the changes
+//! required to converge the services in the node registry towards it.
+//!
+//! A fleet spec is a TOML document, for example:
+//!
+//! ```toml
+//! rewards_address = "0x03B770D9cD32077cC0bF330c13C114a87643B124"
+//! evm_network = "ArbitrumOne"
+//! version = "0.4.4"
+//!
+//! [log]
+//! format = "Json"
+//! max_log_files = 10
+//!
+//! [env]
+//! ANT_LOG = "all"
+//!
+//! [[groups]]
+//! data_dir_path = "/mnt/disk1/antnode"
+//! count = 10
+//! node_port = "12000-12009"
+//!
+//! [[groups]]
+//! data_dir_path = "/mnt/disk2/antnode"
+//! count = 5
+//! ```
+
+use crate::add_services::config::PortRange;
+use ant_evm::{EvmNetwork, RewardsAddress};
+use ant_logging::LogFormat;
+use ant_service_management::{NodeServiceData, ServiceStatus};
+use color_eyre::{Result, eyre::eyre};
+use libp2p::Multiaddr;
+use semver::Version;
+use serde::{Deserialize, Deserializer};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+};
+
+/// The desired state of all the antnode services managed on this machine.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FleetSpec {
+    /// The wallet address that will receive the earnings of every node.
+    pub rewards_address: RewardsAddress,
+    #[serde(default)]
+    pub evm_network: EvmNetwork,
+    /// Pin the antnode version of every service. The latest version is used for new services
+    /// if not set, and existing services are left on their version.
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: Option<Version>,
+    #[serde(default)]
+    pub alpha: bool,
+    #[serde(default)]
+    pub auto_restart: bool,
+    #[serde(default)]
+    pub network_id: Option<u8>,
+    #[serde(default)]
+    pub no_upnp: bool,
+    #[serde(default)]
+    pub relay: bool,
+    #[serde(default)]
+    pub node_ip: Option<Ipv4Addr>,
+    #[serde(default)]
+    pub rpc_address: Option<Ipv4Addr>,
+    #[serde(default)]
+    pub enable_metrics_server: bool,
+    /// The download rate limit of each service, in kilobytes per second.
+    #[serde(default)]
+    pub max_download_rate: Option<u64>,
+    /// The upload rate limit of each service, in kilobytes per second.
+    #[serde(default)]
+    pub max_upload_rate: Option<u64>,
+    /// The user the services run as, for system-wide services.
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub initial_peers: Vec<Multiaddr>,
+    #[serde(default)]
+    pub log: LogSpec,
+    /// Environment variables shared by all the services.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// The services of each data directory or drive.
+    pub groups: Vec<NodeGroupSpec>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogSpec {
+    #[serde(default)]
+    pub dir_path: Option<PathBuf>,
+    #[serde(default)]
+    pub format: Option<LogFormat>,
+    #[serde(default)]
+    pub max_log_files: Option<usize>,
+    #[serde(default)]
+    pub max_archived_log_files: Option<usize>,
+}
+
+/// A number of services sharing a data directory prefix.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeGroupSpec {
+    /// Each service of the group gets its own directory underneath this one.
+    pub data_dir_path: PathBuf,
+    pub count: u16,
+    /// The ports new services of the group are allocated from. Ports of existing services are
+    /// left unchanged, only the services without a metrics port get one from its range.
+    #[serde(default, deserialize_with = "deserialize_port_range")]
+    pub node_port: Option<PortRange>,
+    #[serde(default, deserialize_with = "deserialize_port_range")]
+    pub metrics_port: Option<PortRange>,
+    #[serde(default, deserialize_with = "deserialize_port_range")]
+    pub rpc_port: Option<PortRange>,
+}
+
+impl FleetSpec {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| eyre!("Failed to read the fleet spec at {path:?}: {err}"))?;
+        Self::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let spec: Self =
+            toml::from_str(contents).map_err(|err| eyre!("Invalid fleet spec: {err}"))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut data_dirs = HashSet::new();
+        let mut allocated_ports: HashMap<u16, (&str, &PathBuf)> = HashMap::new();
+        for group in &self.groups {
+            if !data_dirs.insert(&group.data_dir_path) {
+                return Err(eyre!(
+                    "The data directory {:?} is used by more than one group",
+                    group.data_dir_path
+                ));
+            }
+            for (name, range) in [
+                ("node", &group.node_port),
+                ("metrics", &group.metrics_port),
+                ("rpc", &group.rpc_port),
+            ] {
+                let Some(range) = range else {
+                    continue;
+                };
+                if range.ports().len() < group.count as usize {
+                    return Err(eyre!(
+                        "The {name} port range of {:?} has fewer ports than its {} services",
+                        group.data_dir_path,
+                        group.count
+                    ));
+                }
+                for port in range.ports() {
+                    if let Some((other_name, other_dir)) =
+                        allocated_ports.insert(port, (name, &group.data_dir_path))
+                    {
+                        return Err(eyre!(
+                            "The {name} port range of {:?} overlaps the {other_name} port range of {other_dir:?}",
+                            group.data_dir_path
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The environment variables as stored in the node registry.
+    pub fn env_variables(&self) -> Option<Vec<(String, String)>> {
+        if self.env.is_empty() {
+            None
+        } else {
+            Some(
+                self.env
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            )
+        }
+    }
+
+    fn group_of(&self, node: &NodeServiceData) -> Option<usize> {
+        let parent = node.data_dir_path.parent()?;
+        self.groups
+            .iter()
+            .position(|group| group.data_dir_path == parent)
+    }
+}
+
+fn deserialize_version<'de, D>(deserializer: D) -> Result<Option<Version>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let version: Option<String> = Option::deserialize(deserializer)?;
+    version
+        .map(|version| Version::parse(&version).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn deserialize_port_range<'de, D>(deserializer: D) -> Result<Option<PortRange>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Ports {
+        Single(u16),
+        Range(String),
+    }
+
+    match Option::<Ports>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Ports::Single(port)) => Ok(Some(PortRange::Single(port))),
+        Some(Ports::Range(range)) => PortRange::parse(&range)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// A single step towards the desired fleet.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FleetAction {
+    /// Add services to a group. The ports are only set if the group specifies a range.
+    Add {
+        group: usize,
+        count: u16,
+        node_ports: Option<Vec<u16>>,
+        metrics_ports: Option<Vec<u16>>,
+        rpc_ports: Option<Vec<u16>>,
+    },
+    Remove {
+        service_name: String,
+    },
+    /// Re-install the service with the changed settings.
+    Reconfigure {
+        service_name: String,
+        changes: Vec<FleetChange>,
+    },
+    Upgrade {
+        service_name: String,
+        from: String,
+        to: Version,
+    },
+}
+
+/// A setting of an existing service that differs from the spec, or that is changed by
+/// `antctl reconfigure`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FleetChange {
+    RewardsAddress(RewardsAddress),
+    EvmNetwork(EvmNetwork),
+    AutoRestart(bool),
+    NetworkId(Option<u8>),
+    NoUpnp(bool),
+    Relay(bool),
+    LogFormat(Option<LogFormat>),
+    MaxLogFiles(Option<usize>),
+    MaxArchivedLogFiles(Option<usize>),
+    MaxDownloadRate(Option<u64>),
+    MaxUploadRate(Option<u64>),
+    NodePort(Option<u16>),
+    MetricsPort(Option<u16>),
+    RpcPort(u16),
+    /// Enable the metrics server on a port that is free when the change is applied.
+    EnableMetricsServer,
+    NodeIp(Option<Ipv4Addr>),
+    RpcAddress(Ipv4Addr),
+    User(Option<String>),
+    InitialPeers(Vec<Multiaddr>),
+    LogDirPath(PathBuf),
+    Alpha(bool),
+    EnvVariables,
+}
+
+impl FleetChange {
+    /// Whether a running service has to be restarted for the change to apply. Only the restart
+    /// policy is not among the settings the node is launched with.
+    pub fn needs_restart(&self) -> bool {
+        !matches!(self, Self::AutoRestart(_))
+    }
+
+    /// Write the change to the registry entry of the service.
+    pub fn apply_to(&self, node: &mut NodeServiceData) {
+        match self {
+            Self::RewardsAddress(address) => node.rewards_address = *address,
+            Self::EvmNetwork(network) => node.evm_network = network.clone(),
+            Self::AutoRestart(auto_restart) => node.auto_restart = *auto_restart,
+            Self::NetworkId(network_id) => node.network_id = *network_id,
+            Self::NoUpnp(no_upnp) => node.no_upnp = *no_upnp,
+            Self::Relay(relay) => node.relay = *relay,
+            Self::LogFormat(format) => node.log_format = *format,
+            Self::MaxLogFiles(max) => node.max_log_files = *max,
+            Self::MaxArchivedLogFiles(max) => node.max_archived_log_files = *max,
+            Self::MaxDownloadRate(rate) => node.max_download_rate = *rate,
+            Self::MaxUploadRate(rate) => node.max_upload_rate = *rate,
+            Self::NodePort(port) => node.node_port = *port,
+            Self::MetricsPort(port) => node.metrics_port = *port,
+            Self::RpcPort(port) => node.rpc_socket_addr.set_port(*port),
+            // The port is picked by the caller, which can check it is free on the machine.
+            Self::EnableMetricsServer => {}
+            Self::NodeIp(ip) => node.node_ip = *ip,
+            Self::RpcAddress(ip) => node.rpc_socket_addr.set_ip(IpAddr::V4(*ip)),
+            Self::User(user) => node.user = user.clone(),
+            Self::InitialPeers(addrs) => node.initial_peers_config.addrs = addrs.clone(),
+            Self::LogDirPath(path) => node.log_dir_path = path.clone(),
+            Self::Alpha(alpha) => node.alpha = *alpha,
+            // Shared by all the services, the registry is updated once.
+            Self::EnvVariables => {}
+        }
+    }
+}
+
+impl fmt::Display for FleetChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RewardsAddress(address) => write!(f, "rewards address -> {address}"),
+            Self::EvmNetwork(network) => write!(f, "EVM network -> {network}"),
+            Self::AutoRestart(auto_restart) => write!(f, "auto restart -> {auto_restart}"),
+            Self::NetworkId(network_id) => write!(f, "network ID -> {network_id:?}"),
+            Self::NoUpnp(no_upnp) => write!(f, "no UPnP -> {no_upnp}"),
+            Self::Relay(relay) => write!(f, "relay -> {relay}"),
+            Self::LogFormat(format) => write!(
+                f,
+                "log format -> {}",
+                format.map_or("default", |format| format.as_str())
+            ),
+            Self::MaxLogFiles(max) => write!(f, "max log files -> {max:?}"),
+            Self::MaxArchivedLogFiles(max) => write!(f, "max archived log files -> {max:?}"),
+            Self::MaxDownloadRate(rate) => write!(f, "max download rate -> {rate:?}"),
+            Self::MaxUploadRate(rate) => write!(f, "max upload rate -> {rate:?}"),
+            Self::NodePort(port) => write!(f, "node port -> {port:?}"),
+            Self::MetricsPort(port) => write!(f, "metrics port -> {port:?}"),
+            Self::RpcPort(port) => write!(f, "RPC port -> {port}"),
+            Self::EnableMetricsServer => write!(f, "metrics server -> enabled"),
+            Self::NodeIp(ip) => write!(f, "node IP -> {ip:?}"),
+            Self::RpcAddress(ip) => write!(f, "RPC address -> {ip}"),
+            Self::User(user) => write!(f, "user -> {user:?}"),
+            Self::InitialPeers(addrs) => write!(
+                f,
+                "initial peers -> [{}]",
+                addrs
+                    .iter()
+                    .map(|addr| addr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::LogDirPath(path) => write!(f, "log directory -> {path:?}"),
+            Self::Alpha(alpha) => write!(f, "alpha -> {alpha}"),
+            Self::EnvVariables => write!(f, "environment variables"),
+        }
+    }
+}
+
+/// The actions converging the registry towards a spec, in the order they are applied.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FleetPlan {
+    pub actions: Vec<FleetAction>,
+}
+
+impl FleetPlan {
+    /// Compare the services of the registry, and its environment variables, against the spec.
+    pub fn new(
+        spec: &FleetSpec,
+        nodes: &[NodeServiceData],
+        env_variables: Option<&Vec<(String, String)>>,
+    ) -> Self {
+        let mut removals = Vec::new();
+        let mut reconfigurations = Vec::new();
+        let mut upgrades = Vec::new();
+        let mut additions = Vec::new();
+
+        let mut groups: Vec<Vec<&NodeServiceData>> = vec![Vec::new(); spec.groups.len()];
+        for node in nodes
+            .iter()
+            .filter(|node| node.status != ServiceStatus::Removed)
+        {
+            match spec.group_of(node) {
+                Some(group) => groups[group].push(node),
+                None => removals.push(FleetAction::Remove {
+                    service_name: node.service_name.clone(),
+                }),
+            }
+        }
+
+        // New services can't take the ports of any service left in place, whichever its group.
+        let mut used_ports: HashSet<u16> = nodes
+            .iter()
+            .filter(|node| node.status != ServiceStatus::Removed)
+            .flat_map(|node| {
+                [
+                    node.node_port,
+                    node.metrics_port,
+                    Some(node.rpc_socket_addr.port()),
+                ]
+            })
+            .flatten()
+            .collect();
+
+        let env_changed = spec.env_variables().as_ref() != env_variables;
+        for (index, (group, mut members)) in spec.groups.iter().zip(groups).enumerate() {
+            let target = group.count as usize;
+            if members.len() > target {
+                // Keep the running services and, among them, the oldest ones.
+                members.sort_by_key(|node| (node.status != ServiceStatus::Running, node.number));
+                for node in members.drain(target..) {
+                    removals.push(FleetAction::Remove {
+                        service_name: node.service_name.clone(),
+                    });
+                }
+            }
+
+            // The existing services pick their metrics ports first, the new ones get the rest.
+            let missing = target.saturating_sub(members.len());
+            for node in members {
+                let changes = Self::changes(spec, group, node, env_changed, &mut used_ports);
+                if !changes.is_empty() {
+                    reconfigurations.push(FleetAction::Reconfigure {
+                        service_name: node.service_name.clone(),
+                        changes,
+                    });
+                }
+                if let Some(version) = &spec.version
+                    && Version::parse(&node.version).ok().as_ref() != Some(version)
+                {
+                    upgrades.push(FleetAction::Upgrade {
+                        service_name: node.service_name.clone(),
+                        from: node.version.clone(),
+                        to: version.clone(),
+                    });
+                }
+            }
+
+            if missing > 0 {
+                let count = missing as u16;
+                let free_ports = |range: &Option<PortRange>| {
+                    range.as_ref().map(|range| {
+                        range
+                            .ports()
+                            .into_iter()
+                            .filter(|port| !used_ports.contains(port))
+                            .take(count as usize)
+                            .collect::<Vec<_>>()
+                    })
+                };
+                additions.push(FleetAction::Add {
+                    group: index,
+                    count,
+                    node_ports: free_ports(&group.node_port),
+                    metrics_ports: free_ports(&group.metrics_port),
+                    rpc_ports: free_ports(&group.rpc_port),
+                });
+            }
+        }
+
+        let mut actions = removals;
+        actions.extend(reconfigurations);
+        actions.extend(upgrades);
+        actions.extend(additions);
+        Self { actions }
+    }
+
+    /// The settings of a member of `group` that differ from the spec. A metrics port taken from
+    /// the range of the group is added to `used_ports`.
+    fn changes(
+        spec: &FleetSpec,
+        group: &NodeGroupSpec,
+        node: &NodeServiceData,
+        env_changed: bool,
+        used_ports: &mut HashSet<u16>,
+    ) -> Vec<FleetChange> {
+        let mut changes = Vec::new();
+        if node.rewards_address != spec.rewards_address {
+            changes.push(FleetChange::RewardsAddress(spec.rewards_address));
+        }
+        if node.evm_network != spec.evm_network {
+            changes.push(FleetChange::EvmNetwork(spec.evm_network.clone()));
+        }
+        if node.auto_restart != spec.auto_restart {
+            changes.push(FleetChange::AutoRestart(spec.auto_restart));
+        }
+        if node.network_id != spec.network_id {
+            changes.push(FleetChange::NetworkId(spec.network_id));
+        }
+        if node.no_upnp != spec.no_upnp {
+            changes.push(FleetChange::NoUpnp(spec.no_upnp));
+        }
+        if node.relay != spec.relay {
+            changes.push(FleetChange::Relay(spec.relay));
+        }
+        if node.log_format != spec.log.format {
+            changes.push(FleetChange::LogFormat(spec.log.format));
+        }
+        if node.max_log_files != spec.log.max_log_files {
+            changes.push(FleetChange::MaxLogFiles(spec.log.max_log_files));
+        }
+        if node.max_archived_log_files != spec.log.max_archived_log_files {
+            changes.push(FleetChange::MaxArchivedLogFiles(
+                spec.log.max_archived_log_files,
+            ));
+        }
+        if node.max_download_rate != spec.max_download_rate {
+            changes.push(FleetChange::MaxDownloadRate(spec.max_download_rate));
+        }
+        if node.max_upload_rate != spec.max_upload_rate {
+            changes.push(FleetChange::MaxUploadRate(spec.max_upload_rate));
+        }
+        if node.alpha != spec.alpha {
+            changes.push(FleetChange::Alpha(spec.alpha));
+        }
+        if node.node_ip != spec.node_ip {
+            changes.push(FleetChange::NodeIp(spec.node_ip));
+        }
+        let rpc_address = spec.rpc_address.unwrap_or(Ipv4Addr::LOCALHOST);
+        if node.rpc_socket_addr.ip() != IpAddr::V4(rpc_address) {
+            changes.push(FleetChange::RpcAddress(rpc_address));
+        }
+        // The metrics server runs whenever the service has a metrics port.
+        let metrics_enabled = spec.enable_metrics_server || group.metrics_port.is_some();
+        match (node.metrics_port, metrics_enabled) {
+            (Some(_), false) => changes.push(FleetChange::MetricsPort(None)),
+            (None, true) => {
+                let free_port = group.metrics_port.as_ref().and_then(|range| {
+                    range
+                        .ports()
+                        .into_iter()
+                        .find(|port| !used_ports.contains(port))
+                });
+                match free_port {
+                    Some(port) => {
+                        used_ports.insert(port);
+                        changes.push(FleetChange::MetricsPort(Some(port)));
+                    }
+                    None => changes.push(FleetChange::EnableMetricsServer),
+                }
+            }
+            _ => {}
+        }
+        if node.user != spec.user {
+            changes.push(FleetChange::User(spec.user.clone()));
+        }
+        if node.initial_peers_config.addrs != spec.initial_peers {
+            changes.push(FleetChange::InitialPeers(spec.initial_peers.clone()));
+        }
+        // Without a directory in the spec, each service keeps the default one it was added with.
+        if let Some(dir_path) = &spec.log.dir_path {
+            let log_dir_path = dir_path.join(&node.service_name);
+            if node.log_dir_path != log_dir_path {
+                changes.push(FleetChange::LogDirPath(log_dir_path));
+            }
+        }
+        if env_changed {
+            changes.push(FleetChange::EnvVariables);
+        }
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Print the plan as a diff against the current fleet.
+    pub fn print(&self, spec: &FleetSpec) {
+        if self.is_empty() {
+            println!("No changes. The services match the fleet spec.");
+            return;
+        }
+
+        for action in &self.actions {
+            match action {
+                FleetAction::Add {
+                    group,
+                    count,
+                    node_ports,
+                    ..
+                } => {
+                    let ports = node_ports
+                        .as_ref()
+                        .map(|ports| format!(" on ports {ports:?}"))
+                        .unwrap_or_default();
+                    println!(
+                        "+ add {count} service(s) under {:?}{ports}",
+                        spec.groups[*group].data_dir_path
+                    );
+                }
+                FleetAction::Remove { service_name } => println!("- remove {service_name}"),
+                FleetAction::Reconfigure {
+                    service_name,
+                    changes,
+                } => {
+                    println!("~ reconfigure {service_name}:");
+                    for change in changes {
+                        println!("    {change}");
+                    }
+                }
+                FleetAction::Upgrade {
+                    service_name,
+                    from,
+                    to,
+                } => println!("^ upgrade {service_name}: {from} -> {to}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{self, REWARDS_ADDRESS};
+
+    fn spec(extra: &str) -> FleetSpec {
+        FleetSpec::parse(&format!(
+            r#"
+rewards_address = "{REWARDS_ADDRESS}"
+version = "0.4.4"
+{extra}
+
+[[groups]]
+data_dir_path = "/mnt/disk1"
+count = 2
+node_port = "12000-12002"
+
+[[groups]]
+data_dir_path = "/mnt/disk2"
+count = 1
+"#
+        ))
+        .unwrap()
+    }
+
+    /// A service of the group using `data_dir`, on the ports the spec gives its position.
+    fn node(number: u16, data_dir: &str, status: ServiceStatus) -> NodeServiceData {
+        let mut node = test_utils::node(number);
+        node.data_dir_path = PathBuf::from(data_dir).join(&node.service_name);
+        node.antnode_path = node.data_dir_path.join("antnode");
+        node.node_port = Some(12000 + number - 1);
+        node.status = status;
+        node
+    }
+
+    #[test]
+    fn matching_fleet_should_have_an_empty_plan() {
+        let spec = spec("");
+        let nodes = vec![
+            node(1, "/mnt/disk1", ServiceStatus::Running),
+            node(2, "/mnt/disk1", ServiceStatus::Running),
+            node(3, "/mnt/disk2", ServiceStatus::Stopped),
+            node(4, "/mnt/disk3", ServiceStatus::Removed),
+        ];
+        assert!(FleetPlan::new(&spec, &nodes, None).is_empty());
+    }
+
+    #[test]
+    fn missing_services_should_be_added_on_free_ports() {
+        let spec = spec("");
+        let nodes = vec![node(2, "/mnt/disk1", ServiceStatus::Running)];
+        let plan = FleetPlan::new(&spec, &nodes, None);
+        assert_eq!(
+            plan.actions,
+            vec![
+                FleetAction::Add {
+                    group: 0,
+                    count: 1,
+                    node_ports: Some(vec![12000]),
+                    metrics_ports: None,
+                    rpc_ports: None,
+                },
+                FleetAction::Add {
+                    group: 1,
+                    count: 1,
+                    node_ports: None,
+                    metrics_ports: None,
+                    rpc_ports: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn new_services_should_not_take_the_ports_of_other_groups() {
+        let spec = spec("");
+        // Added to the second group before its ports were managed by the spec
+        let mut other = node(3, "/mnt/disk2", ServiceStatus::Running);
+        other.node_port = Some(12000);
+        let nodes = vec![node(2, "/mnt/disk1", ServiceStatus::Running), other];
+        let plan = FleetPlan::new(&spec, &nodes, None);
+        assert_eq!(
+            plan.actions,
+            vec![FleetAction::Add {
+                group: 0,
+                count: 1,
+                node_ports: Some(vec![12002]),
+                metrics_ports: None,
+                rpc_ports: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn overlapping_port_ranges_should_be_rejected() {
+        let groups = |second_group: &str| {
+            format!(
+                r#"rewards_address = "{REWARDS_ADDRESS}"
+
+[[groups]]
+data_dir_path = "/mnt/disk1"
+count = 2
+node_port = "12000-12002"
+metrics_port = "13000-13002"
+
+[[groups]]
+data_dir_path = "/mnt/disk2"
+count = 2
+{second_group}
+"#
+            )
+        };
+        assert!(FleetSpec::parse(&groups("node_port = \"12003-12004\"")).is_ok());
+        assert!(FleetSpec::parse(&groups("node_port = \"12002-12003\"")).is_err());
+        assert!(FleetSpec::parse(&groups("rpc_port = \"13001-13003\"")).is_err());
+        assert!(
+            FleetSpec::parse(&groups(
+                "node_port = \"14000-14001\"\nrpc_port = \"14001-14002\""
+            ))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn surplus_and_unknown_services_should_be_removed() {
+        let spec = spec("");
+        let nodes = vec![
+            node(1, "/mnt/disk1", ServiceStatus::Running),
+            node(2, "/mnt/disk1", ServiceStatus::Stopped),
+            node(3, "/mnt/disk1", ServiceStatus::Running),
+            node(4, "/mnt/disk2", ServiceStatus::Running),
+            node(5, "/mnt/disk3", ServiceStatus::Running),
+        ];
+        let plan = FleetPlan::new(&spec, &nodes, None);
+        assert_eq!(
+            plan.actions,
+            vec![
+                FleetAction::Remove {
+                    service_name: "antnode5".to_string()
+                },
+                FleetAction::Remove {
+                    service_name: "antnode2".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_settings_and_version_should_be_planned() {
+        let spec = spec("relay = true\n[env]\nANT_LOG = \"all\"");
+        let mut old = node(1, "/mnt/disk1", ServiceStatus::Running);
+        old.version = "0.4.3".to_string();
+        let nodes = vec![
+            old,
+            node(2, "/mnt/disk1", ServiceStatus::Running),
+            node(3, "/mnt/disk2", ServiceStatus::Running),
+        ];
+        let env = vec![("ANT_LOG".to_string(), "all".to_string())];
+        let plan = FleetPlan::new(&spec, &nodes, Some(&env));
+        assert_eq!(plan.actions.len(), 4);
+        assert_eq!(
+            plan.actions[0],
+            FleetAction::Reconfigure {
+                service_name: "antnode1".to_string(),
+                changes: vec![FleetChange::Relay(true)],
+            }
+        );
+        assert_eq!(
+            plan.actions[3],
+            FleetAction::Upgrade {
+                service_name: "antnode1".to_string(),
+                from: "0.4.3".to_string(),
+                to: Version::new(0, 4, 4),
+            }
+        );
+
+        let plan = FleetPlan::new(&spec, &nodes, None);
+        assert!(plan.actions.iter().all(|action| matches!(
+            action,
+            FleetAction::Reconfigure { changes, .. } if changes.contains(&FleetChange::EnvVariables)
+        ) || matches!(
+            action,
+            FleetAction::Upgrade { .. }
+        )));
+    }
+
+    #[test]
+    fn changed_addresses_user_and_directories_should_be_planned() {
+        let spec = spec(
+            r#"alpha = true
+node_ip = "10.0.0.1"
+rpc_address = "0.0.0.0"
+enable_metrics_server = true
+user = "ant"
+initial_peers = ["/ip4/1.2.3.4/udp/12000/quic-v1"]
+
+[log]
+dir_path = "/mnt/logs""#,
+        );
+        let nodes = vec![node(1, "/mnt/disk1", ServiceStatus::Running)];
+        let plan = FleetPlan::new(&spec, &nodes, None);
+        assert_eq!(
+            plan.actions[0],
+            FleetAction::Reconfigure {
+                service_name: "antnode1".to_string(),
+                changes: vec![
+                    FleetChange::Alpha(true),
+                    FleetChange::NodeIp(Some(Ipv4Addr::new(10, 0, 0, 1))),
+                    FleetChange::RpcAddress(Ipv4Addr::UNSPECIFIED),
+                    FleetChange::EnableMetricsServer,
+                    FleetChange::User(Some("ant".to_string())),
+                    FleetChange::InitialPeers(vec![
+                        "/ip4/1.2.3.4/udp/12000/quic-v1".parse().unwrap()
+                    ]),
+                    FleetChange::LogDirPath(PathBuf::from("/mnt/logs/antnode1")),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn metrics_ports_should_be_taken_from_the_range_of_the_group() {
+        let spec = FleetSpec::parse(&format!(
+            r#"rewards_address = "{REWARDS_ADDRESS}"
+version = "0.4.4"
+
+[[groups]]
+data_dir_path = "/mnt/disk1"
+count = 2
+node_port = "12000-12002"
+metrics_port = "14000-14002"
+"#
+        ))
+        .unwrap();
+        let mut with_metrics = node(1, "/mnt/disk1", ServiceStatus::Running);
+        with_metrics.metrics_port = Some(14000);
+        let nodes = vec![with_metrics, node(2, "/mnt/disk1", ServiceStatus::Running)];
+        let plan = FleetPlan::new(&spec, &nodes, None);
+        assert_eq!(
+            plan.actions,
+            vec![FleetAction::Reconfigure {
+                service_name: "antnode2".to_string(),
+                changes: vec![FleetChange::MetricsPort(Some(14001))],
+            }]
+        );
+
+        let spec = self::spec("");
+        let mut with_metrics = node(1, "/mnt/disk1", ServiceStatus::Running);
+        with_metrics.metrics_port = Some(13000);
+        let plan = FleetPlan::new(&spec, &[with_metrics], None);
+        assert!(plan.actions.contains(&FleetAction::Reconfigure {
+            service_name: "antnode1".to_string(),
+            changes: vec![FleetChange::MetricsPort(None)],
+        }));
+    }
+
+    #[test]
+    fn invalid_specs_should_be_rejected() {
+        let too_few_ports = format!(
+            "rewards_address = \"{REWARDS_ADDRESS}\"\n[[groups]]\ndata_dir_path = \"/a\"\ncount = 3\nnode_port = \"12000-12001\""
+        );
+        assert!(FleetSpec::parse(&too_few_ports).is_err());
+
+        let duplicate_dir = format!(
+            "rewards_address = \"{REWARDS_ADDRESS}\"\n[[groups]]\ndata_dir_path = \"/a\"\ncount = 1\n[[groups]]\ndata_dir_path = \"/a\"\ncount = 1"
+        );
+        assert!(FleetSpec::parse(&duplicate_dir).is_err());
+
+        let unknown_field =
+            format!("rewards_address = \"{REWARDS_ADDRESS}\"\ncolour = \"red\"\ngroups = []");
+        assert!(FleetSpec::parse(&unknown_field).is_err());
+    }
+}
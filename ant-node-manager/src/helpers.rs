@@ -424,3 +424,93 @@ pub async fn check_port_availability(
     }
     Ok(())
 }
+
+/// Make sure the ports of a reconfigured node are distinct, and that the ones it didn't use before
+/// are not used by any other node.
+pub async fn check_reconfigured_ports(
+    reconfigured: &NodeServiceData,
+    original: &NodeServiceData,
+    nodes: &Arc<RwLock<Vec<Arc<RwLock<NodeServiceData>>>>>,
+) -> Result<()> {
+    let ports = |node: &NodeServiceData| {
+        [
+            node.node_port,
+            node.metrics_port,
+            Some(node.rpc_socket_addr.port()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<u16>>()
+    };
+
+    let node_ports = ports(reconfigured);
+    for (i, port) in node_ports.iter().enumerate() {
+        if node_ports[i + 1..].contains(port) {
+            error!(
+                "Port {port} is used more than once by {}",
+                reconfigured.service_name
+            );
+            bail!(
+                "Port {port} is used more than once by {}",
+                reconfigured.service_name
+            );
+        }
+    }
+    let original_ports = ports(original);
+    for port in node_ports
+        .iter()
+        .filter(|port| !original_ports.contains(port))
+    {
+        check_port_availability(&PortRange::Single(*port), nodes).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::node;
+
+    #[tokio::test]
+    async fn reconfigured_ports_should_not_conflict_with_other_services() {
+        let original = node(1);
+        let nodes = Arc::new(RwLock::new(vec![
+            Arc::new(RwLock::new(original.clone())),
+            Arc::new(RwLock::new(node(2))),
+        ]));
+
+        let mut reconfigured = original.clone();
+        reconfigured.node_port = Some(12100);
+        reconfigured.rpc_socket_addr.set_port(13100);
+        assert!(
+            check_reconfigured_ports(&reconfigured, &original, &nodes)
+                .await
+                .is_ok()
+        );
+
+        let mut swapped = original.clone();
+        swapped.node_port = Some(13001);
+        swapped.rpc_socket_addr.set_port(12001);
+        assert!(
+            check_reconfigured_ports(&swapped, &original, &nodes)
+                .await
+                .is_ok()
+        );
+
+        let mut taken = original.clone();
+        taken.node_port = Some(12002);
+        assert!(
+            check_reconfigured_ports(&taken, &original, &nodes)
+                .await
+                .is_err()
+        );
+
+        let mut duplicated = original.clone();
+        duplicated.metrics_port = Some(13001);
+        assert!(
+            check_reconfigured_ports(&duplicated, &original, &nodes)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -13,10 +13,17 @@ pub mod add_services;
 pub mod cmd;
 pub mod config;
 pub mod error;
+pub mod fleet;
 pub mod helpers;
 pub mod local;
+pub mod registry;
+pub mod resources;
+pub mod rolling_upgrade;
 pub mod rpc;
 pub mod rpc_client;
+pub mod supervisor;
+#[cfg(test)]
+mod test_utils;
 
 pub const DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S: u64 = 300;
 
@@ -39,6 +46,7 @@ impl From<u8> for VerbosityLevel {
 }
 
 use crate::error::{Error, Result};
+use crate::supervisor::SupervisorState;
 use ant_service_management::NodeRegistryManager;
 use ant_service_management::rpc::RpcActions;
 use ant_service_management::{
@@ -49,13 +57,19 @@ use colored::Colorize;
 use indicatif::ProgressBar;
 use indicatif::ProgressStyle;
 use semver::Version;
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::debug;
 
 pub const DAEMON_DEFAULT_PORT: u16 = 12500;
 pub const DAEMON_SERVICE_NAME: &str = "antctld";
 
 const RPC_START_UP_DELAY_MS: u64 = 3000;
+/// The number of the most recent supervisor actions shown for each service in the status details.
+const STATUS_AUDIT_ENTRIES: usize = 10;
 
 pub struct ServiceManager<T: ServiceStateActions + Send> {
     pub service: T,
@@ -191,8 +205,16 @@ impl<T: ServiceStateActions + Send> ServiceManager<T> {
                     if self.verbosity != VerbosityLevel::Minimal {
                         println!("Attempting to stop {service_name}...");
                     }
-                    self.service_control
-                        .stop(&service_name, self.service.is_user_mode().await)?;
+                    let user_mode = self.service.is_user_mode().await;
+                    self.service_control.stop(&service_name, user_mode)?;
+                    let start = Instant::now();
+                    while !self.service_control.poll_stopped(
+                        &service_name,
+                        user_mode,
+                        start.elapsed(),
+                    )? {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                    }
                     if self.verbosity != VerbosityLevel::Minimal {
                         println!(
                             "{} Service {service_name} with PID {} was stopped",
@@ -301,6 +323,117 @@ impl<T: ServiceStateActions + Send> ServiceManager<T> {
         Ok(())
     }
 
+    /// Re-install the service definition from the current service data, e.g., after its settings
+    /// were changed in the registry. The binary is kept.
+    ///
+    /// A running service is only restarted when `restart` is set, because the settings it was
+    /// launched with changed, or when its definition can't be replaced while it runs.
+    pub async fn reconfigure(
+        &mut self,
+        auto_restart: bool,
+        env_variables: Option<Vec<(String, String)>>,
+        restart: bool,
+    ) -> Result<()> {
+        let service_name = self.service.name().await;
+        info!("Reconfiguring the {service_name} service");
+        let was_running = self.service.status().await == ServiceStatus::Running;
+        if was_running && !restart {
+            let options = UpgradeOptions {
+                auto_restart,
+                env_variables: env_variables.clone(),
+                force: true,
+                start_service: true,
+                target_bin_path: self.service.bin_path().await,
+                target_version: Version::parse(&self.service.version().await)?,
+            };
+            let install_ctx = self.service.build_upgrade_install_context(options).await?;
+            if self
+                .service_control
+                .reinstall_running(install_ctx, self.service.is_user_mode().await)?
+            {
+                if self.verbosity != VerbosityLevel::Minimal {
+                    println!(
+                        "{} Service {service_name} was reconfigured without a restart",
+                        "✓".green()
+                    );
+                }
+                return Ok(());
+            }
+            debug!("The definition of {service_name} can't be replaced while it runs");
+        }
+        self.stop().await?;
+
+        let options = UpgradeOptions {
+            auto_restart,
+            env_variables,
+            force: true,
+            start_service: was_running,
+            target_bin_path: self.service.bin_path().await,
+            target_version: Version::parse(&self.service.version().await)?,
+        };
+        self.reinstall(options).await?;
+
+        if was_running {
+            self.start().await?;
+        }
+        if self.verbosity != VerbosityLevel::Minimal {
+            println!("{} Service {service_name} was reconfigured", "✓".green());
+        }
+        Ok(())
+    }
+
+    /// Restore the binary the service ran before its last upgrade, and restart it.
+    ///
+    /// The previous binary is kept alongside the service binary by `upgrade`.
+    pub async fn rollback(
+        &mut self,
+        previous_version: &str,
+        auto_restart: bool,
+        env_variables: Option<Vec<(String, String)>>,
+    ) -> Result<()> {
+        let service_name = self.service.name().await;
+        let bin_path = self.service.bin_path().await;
+        let previous_bin_path = get_previous_bin_path(&bin_path);
+        if !previous_bin_path.exists() {
+            error!("No previous binary for {service_name} at {previous_bin_path:?}");
+            return Err(Error::PreviousBinaryNotFound(previous_bin_path));
+        }
+        info!("Rolling back the {service_name} service to {previous_version}");
+
+        self.stop().await?;
+        std::fs::copy(&previous_bin_path, &bin_path)?;
+        let options = UpgradeOptions {
+            auto_restart,
+            env_variables,
+            force: true,
+            start_service: true,
+            target_bin_path: previous_bin_path,
+            target_version: Version::parse(previous_version)?,
+        };
+        self.reinstall(options).await?;
+        self.service.set_version(previous_version).await;
+        self.start().await?;
+
+        if self.verbosity != VerbosityLevel::Minimal {
+            println!(
+                "{} Service {service_name} was rolled back to {previous_version}",
+                "✓".green()
+            );
+        }
+        Ok(())
+    }
+
+    async fn reinstall(&mut self, options: UpgradeOptions) -> Result<()> {
+        let service_name = self.service.name().await;
+        self.service_control
+            .uninstall(&service_name, self.service.is_user_mode().await)?;
+        self.service_control.install(
+            self.service.build_upgrade_install_context(options).await?,
+            self.service.is_user_mode().await,
+        )?;
+        Ok(())
+    }
+
     pub async fn upgrade(&mut self, options: UpgradeOptions) -> Result<UpgradeResult> {
         let current_version = Version::parse(&self.service.version().await)?;
         if !options.force
@@ -316,21 +449,14 @@ impl<T: ServiceStateActions + Send> ServiceManager<T> {
 
         debug!("Stopping the service and copying the binary");
         self.stop().await?;
-        std::fs::copy(
-            options.clone().target_bin_path,
-            self.service.bin_path().await,
-        )?;
+        // Keep the current binary alongside, to be able to roll back the upgrade.
+        let bin_path = self.service.bin_path().await;
+        if bin_path.exists() {
+            std::fs::copy(&bin_path, get_previous_bin_path(&bin_path))?;
+        }
+        std::fs::copy(options.clone().target_bin_path, &bin_path)?;
 
-        self.service_control.uninstall(
-            &self.service.name().await,
-            self.service.is_user_mode().await,
-        )?;
-        self.service_control.install(
-            self.service
-                .build_upgrade_install_context(options.clone())
-                .await?,
-            self.service.is_user_mode().await,
-        )?;
+        self.reinstall(options.clone()).await?;
 
         if options.start_service {
             match self.start().await {
@@ -366,6 +492,13 @@ impl<T: ServiceStateActions + Send> ServiceManager<T> {
     }
 }
 
+/// The path the binary of a service is kept at when it gets upgraded.
+pub fn get_previous_bin_path(bin_path: &Path) -> PathBuf {
+    let mut file_name = bin_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".previous");
+    bin_path.with_file_name(file_name)
+}
+
 pub async fn status_report(
     node_registry: &NodeRegistryManager,
     service_control: &dyn ServiceControl,
@@ -387,6 +520,13 @@ pub async fn status_report(
         let json = serde_json::to_string_pretty(&node_registry.to_status_summary().await)?;
         println!("{json}");
     } else if detailed_view {
+        let supervisor_state = SupervisorState::load(&SupervisorState::path(
+            &node_registry.save_path,
+        ))
+        .unwrap_or_else(|err| {
+            warn!("Could not load the supervisor state: {err}");
+            SupervisorState::default()
+        });
         for node in node_registry.nodes.read().await.iter() {
             let node = node.read().await;
             print_banner(&format!(
@@ -416,6 +556,7 @@ pub async fn status_report(
             println!("Data path: {}", node.data_dir_path.to_string_lossy());
             println!("Log path: {}", node.log_dir_path.to_string_lossy());
             println!("Bin path: {}", node.antnode_path.to_string_lossy());
+            println!("Backend: {}", node.backend);
             println!(
                 "Connected peers: {}",
                 node.connected_peers
@@ -428,6 +569,21 @@ pub async fn status_report(
                     .map_or("-".to_string(), |b| b.to_string())
             );
             println!("Rewards address: {}", node.rewards_address);
+            if let Some(supervision) = supervisor_state.nodes.get(&node.service_name) {
+                if let Some(reason) = &supervision.quarantined {
+                    println!("Quarantined: {reason}");
+                }
+                if !supervision.audit_log.is_empty() {
+                    println!("Supervisor actions:");
+                    let skipped = supervision
+                        .audit_log
+                        .len()
+                        .saturating_sub(STATUS_AUDIT_ENTRIES);
+                    for entry in supervision.audit_log.iter().skip(skipped) {
+                        println!("  {entry}");
+                    }
+                }
+            }
             println!();
         }
 
@@ -662,6 +818,7 @@ mod tests {
     use ant_logging::LogFormat;
     use ant_service_management::{
         UpgradeOptions, UpgradeResult,
+        control::ServiceBackend,
         error::{Error as ServiceControlError, Result as ServiceControlResult},
         node::{NODE_SERVICE_DATA_SCHEMA_LATEST, NodeService, NodeServiceData},
         rpc::{NetworkInfo, NodeInfo, RecordAddress, RpcActions},
@@ -691,6 +848,7 @@ mod tests {
             async fn node_info(&self) -> ServiceControlResult<NodeInfo>;
             async fn network_info(&self) -> ServiceControlResult<NetworkInfo>;
             async fn record_addresses(&self) -> ServiceControlResult<Vec<RecordAddress>>;
+            async fn routing_table_size(&self) -> ServiceControlResult<usize>;
             async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> ServiceControlResult<()>;
             async fn node_stop(&self, delay_millis: u64) -> ServiceControlResult<()>;
             async fn node_update(&self, delay_millis: u64) -> ServiceControlResult<()>;
@@ -709,6 +867,7 @@ mod tests {
             fn start(&self, service_name: &str, user_mode: bool) -> ServiceControlResult<()>;
             fn stop(&self, service_name: &str, user_mode: bool) -> ServiceControlResult<()>;
             fn uninstall(&self, service_name: &str, user_mode: bool) -> ServiceControlResult<()>;
+            fn reinstall_running(&self, install_ctx: ServiceInstallCtx, user_mode: bool) -> ServiceControlResult<bool>;
             fn wait(&self, delay: u64);
         }
     }
@@ -760,6 +919,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -777,7 +937,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -875,6 +1037,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -892,7 +1055,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -955,6 +1120,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -972,7 +1138,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1075,6 +1243,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1092,7 +1261,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1168,6 +1339,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1185,7 +1357,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1273,6 +1447,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1290,7 +1465,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1377,6 +1554,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1394,7 +1572,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1451,6 +1631,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1468,7 +1649,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1515,6 +1698,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1532,7 +1716,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1579,6 +1765,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1596,7 +1783,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1646,6 +1835,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1663,7 +1853,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1724,6 +1916,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1741,7 +1934,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1865,6 +2060,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1882,7 +2078,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -1947,6 +2145,181 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn rollback_should_restore_the_previous_binary_and_version() -> Result<()> {
+        let previous_version = "0.1.0";
+        let target_version = "0.2.0";
+
+        let tmp_data_dir = assert_fs::TempDir::new()?;
+        let current_install_dir = tmp_data_dir.child("antnode_install");
+        current_install_dir.create_dir_all()?;
+
+        let current_node_bin = current_install_dir.child("antnode");
+        current_node_bin.write_binary(b"upgraded antnode binary")?;
+        let previous_node_bin = current_install_dir.child("antnode.previous");
+        previous_node_bin.write_binary(b"previous antnode binary")?;
+
+        let mut mock_service_control = MockServiceControl::new();
+        let mut mock_rpc_client = MockRpcClient::new();
+
+        mock_service_control
+            .expect_get_process_pid()
+            .with(eq(current_node_bin.to_path_buf().clone()))
+            .times(1)
+            .returning(|_| Ok(1000));
+        mock_service_control
+            .expect_stop()
+            .with(eq("antnode1"), eq(false))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_service_control
+            .expect_uninstall()
+            .with(eq("antnode1"), eq(false))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_service_control
+            .expect_install()
+            .with(always(), always())
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_service_control
+            .expect_start()
+            .with(eq("antnode1"), eq(false))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_service_control
+            .expect_wait()
+            .with(eq(3000))
+            .times(1)
+            .returning(|_| ());
+        mock_service_control
+            .expect_get_process_pid()
+            .with(eq(current_node_bin.to_path_buf().clone()))
+            .times(1)
+            .returning(|_| Ok(2000));
+
+        mock_rpc_client.expect_node_info().times(1).returning(|| {
+            Ok(NodeInfo {
+                pid: 2000,
+                peer_id: PeerId::from_str("12D3KooWS2tpXGGTmg2AHFiDh57yPQnat49YHnyqoggzXZWpqkCR")?,
+                data_path: PathBuf::from("/var/antctl/services/antnode1"),
+                log_path: PathBuf::from("/var/log/antnode/antnode1"),
+                version: previous_version.to_string(),
+                uptime: std::time::Duration::from_secs(1), // the service was just started
+                wallet_balance: 0,
+            })
+        });
+        mock_rpc_client
+            .expect_network_info()
+            .times(1)
+            .returning(|| {
+                Ok(NetworkInfo {
+                    connected_peers: Vec::new(),
+                    listeners: Vec::new(),
+                })
+            });
+
+        let service_data = NodeServiceData {
+            alpha: false,
+            auto_restart: false,
+            backend: ServiceBackend::Native,
+            connected_peers: None,
+            data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
+            evm_network: EvmNetwork::Custom(CustomNetwork {
+                rpc_url_http: "http://localhost:8545".parse()?,
+                payment_token_address: RewardsAddress::from_str(
+                    "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                )?,
+                data_payments_address: RewardsAddress::from_str(
+                    "0x8464135c8F25Da09e49BC8782676a84730C318bC",
+                )?,
+            }),
+            relay: false,
+            initial_peers_config: InitialPeersConfig::default(),
+            listen_addr: None,
+            log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
+            log_format: None,
+            max_archived_log_files: None,
+            max_download_rate: None,
+            max_log_files: None,
+            max_upload_rate: None,
+            metrics_port: None,
+            network_id: None,
+            node_ip: None,
+            node_port: None,
+            number: 1,
+            peer_id: Some(PeerId::from_str(
+                "12D3KooWS2tpXGGTmg2AHFiDh57yPQnat49YHnyqoggzXZWpqkCR",
+            )?),
+            pid: Some(1000),
+            rewards_address: RewardsAddress::from_str(
+                "0x03B770D9cD32077cC0bF330c13C114a87643B124",
+            )?,
+            reward_balance: Some(AttoTokens::zero()),
+            rpc_socket_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8081),
+            antnode_path: current_node_bin.to_path_buf(),
+            schema_version: NODE_SERVICE_DATA_SCHEMA_LATEST,
+            service_name: "antnode1".to_string(),
+            status: ServiceStatus::Running,
+            no_upnp: false,
+            user: Some("ant".to_string()),
+            user_mode: false,
+            version: target_version.to_string(),
+            write_older_cache_files: false,
+        };
+        let service_data = Arc::new(RwLock::new(service_data));
+        let service = NodeService::new(Arc::clone(&service_data), Box::new(mock_rpc_client));
+        let mut service_manager = ServiceManager::new(
+            service,
+            Box::new(mock_service_control),
+            VerbosityLevel::Normal,
+        );
+
+        service_manager
+            .rollback(previous_version, false, None)
+            .await?;
+
+        assert_eq!(
+            std::fs::read(current_node_bin.path())?,
+            b"previous antnode binary"
+        );
+        let service_data = service_data.read().await;
+        assert_eq!(service_data.pid, Some(2000));
+        assert_eq!(service_data.version, previous_version);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reconfigure_should_not_restart_a_service_whose_launch_settings_are_unchanged()
+    -> Result<()> {
+        let mut mock_service_control = MockServiceControl::new();
+        mock_service_control
+            .expect_reinstall_running()
+            .withf(|install_ctx, user_mode| {
+                install_ctx.label.to_qualified_name() == "antnode1" && *user_mode
+            })
+            .times(1)
+            .returning(|_, _| Ok(true));
+
+        let mut service_data = crate::test_utils::node(1);
+        service_data.pid = Some(1000);
+        let service_data = Arc::new(RwLock::new(service_data));
+        let service = NodeService::new(Arc::clone(&service_data), Box::new(MockRpcClient::new()));
+        let mut service_manager = ServiceManager::new(
+            service,
+            Box::new(mock_service_control),
+            VerbosityLevel::Normal,
+        );
+
+        service_manager.reconfigure(true, None, false).await?;
+
+        let service_data = service_data.read().await;
+        assert_eq!(service_data.status, ServiceStatus::Running);
+        assert_eq!(service_data.pid, Some(1000));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn upgrade_should_not_be_required_if_target_is_less_than_current_version() -> Result<()> {
         let current_version = "0.2.0";
@@ -1967,6 +2340,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -1984,7 +2358,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2116,6 +2492,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -2133,7 +2510,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2275,6 +2654,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -2292,7 +2672,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2426,6 +2808,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -2443,7 +2826,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2582,6 +2967,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -2599,7 +2985,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2771,6 +3159,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -2787,7 +3176,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -2947,6 +3338,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -2966,7 +3358,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3123,6 +3517,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -3132,7 +3527,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: Some(5),
             node_ip: None,
@@ -3289,6 +3686,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -3305,7 +3703,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3463,6 +3863,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -3482,7 +3883,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3642,6 +4045,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -3658,7 +4062,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3816,6 +4222,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -3834,7 +4241,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -3996,6 +4405,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -4005,7 +4415,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4163,6 +4575,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -4172,7 +4585,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: Some(LogFormat::Json),
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4330,6 +4745,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -4339,7 +4755,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4497,6 +4915,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -4506,7 +4925,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             number: 1,
@@ -4664,6 +5085,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -4673,7 +5095,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             number: 1,
@@ -4831,6 +5255,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             relay: false,
@@ -4839,7 +5264,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: Some(20),
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -4998,6 +5425,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             relay: false,
@@ -5006,7 +5434,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: Some(20),
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5165,6 +5595,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -5174,7 +5605,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(12000),
             network_id: None,
             node_ip: None,
@@ -5332,6 +5765,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -5341,7 +5775,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(12000),
             network_id: None,
             node_ip: None,
@@ -5500,6 +5936,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: true,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -5509,7 +5946,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5671,6 +6110,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: true,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -5688,7 +6128,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -5851,6 +6293,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: true,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -5868,7 +6311,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -6028,6 +6473,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -6037,7 +6483,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -6196,6 +6644,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -6204,7 +6653,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: Some(12000),
             network_id: None,
             node_ip: None,
@@ -6276,6 +6727,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: data_dir.to_path_buf(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -6293,7 +6745,9 @@ mod tests {
             log_dir_path: log_dir.to_path_buf(),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -6346,6 +6800,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -6363,7 +6818,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -6433,6 +6890,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -6450,7 +6908,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -6515,6 +6975,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: data_dir.to_path_buf(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -6532,7 +6993,9 @@ mod tests {
             log_dir_path: log_dir.to_path_buf(),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -6593,6 +7056,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: false,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: data_dir.to_path_buf(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
@@ -6610,7 +7074,9 @@ mod tests {
             log_dir_path: log_dir.to_path_buf(),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -6757,6 +7223,7 @@ mod tests {
         let service_data = NodeServiceData {
             alpha: true,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::ArbitrumOne,
@@ -6773,7 +7240,9 @@ mod tests {
             log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
             log_format: None,
             max_archived_log_files: None,
+            max_download_rate: None,
             max_log_files: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
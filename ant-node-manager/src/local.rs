@@ -18,7 +18,7 @@ use ant_service_management::NodeRegistryManager;
 use ant_service_management::node::NODE_SERVICE_DATA_SCHEMA_LATEST;
 use ant_service_management::{
     NodeServiceData, ServiceStatus,
-    control::ServiceControl,
+    control::{ServiceBackend, ServiceControl},
     rpc::{RpcActions, RpcClient},
 };
 use color_eyre::eyre::OptionExt;
@@ -418,6 +418,7 @@ pub async fn run_node(
         alpha: false,
         antnode_path: launcher.get_antnode_path(),
         auto_restart: false,
+        backend: ServiceBackend::Native,
         connected_peers,
         data_dir_path: node_info.data_path,
         evm_network: run_options.evm_network,
@@ -434,7 +435,9 @@ pub async fn run_node(
         log_dir_path: node_info.log_path,
         log_format: run_options.log_format,
         max_archived_log_files: None,
+        max_download_rate: None,
         max_log_files: None,
+        max_upload_rate: None,
         metrics_port: run_options.metrics_port,
         network_id: None,
         node_ip: None,
@@ -540,6 +543,7 @@ mod tests {
             async fn node_info(&self) -> RpcResult<NodeInfo>;
             async fn network_info(&self) -> RpcResult<NetworkInfo>;
             async fn record_addresses(&self) -> RpcResult<Vec<RecordAddress>>;
+            async fn routing_table_size(&self) -> RpcResult<usize>;
             async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> RpcResult<()>;
             async fn node_stop(&self, delay_millis: u64) -> RpcResult<()>;
             async fn node_update(&self, delay_millis: u64) -> RpcResult<()>;
@@ -0,0 +1,997 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Comparing versions of the node registry, and rebuilding its entries from the antnode service
+//! definitions installed on the host and the data directories of the nodes they point to.
+
+use crate::helpers::get_bin_version;
+use ant_bootstrap::InitialPeersConfig;
+use ant_evm::{CustomNetwork, EvmNetwork};
+use ant_logging::LogFormat;
+use ant_service_management::{
+    NodeRegistryManager, NodeServiceData, ServiceStatus, control::ServiceBackend,
+    node::NODE_SERVICE_DATA_SCHEMA_LATEST, registry::VOLATILE_NODE_FIELDS,
+};
+use color_eyre::{Result, eyre::eyre};
+use libp2p::PeerId;
+use libp2p_identity::Keypair;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A difference between two versions of the registry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistryChange {
+    ServiceAdded(String),
+    ServiceRemoved(String),
+    /// A field of a service, with its old and new values.
+    ServiceChanged {
+        service_name: String,
+        field: String,
+        old: Value,
+        new: Value,
+    },
+    /// A field of the registry itself, such as its environment variables.
+    SettingChanged {
+        field: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl fmt::Display for RegistryChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryChange::ServiceAdded(service_name) => write!(f, "+ {service_name}"),
+            RegistryChange::ServiceRemoved(service_name) => write!(f, "- {service_name}"),
+            RegistryChange::ServiceChanged {
+                service_name,
+                field,
+                old,
+                new,
+            } => write!(f, "~ {service_name}: {field}: {old} -> {new}"),
+            RegistryChange::SettingChanged { field, old, new } => {
+                write!(f, "~ {field}: {old} -> {new}")
+            }
+        }
+    }
+}
+
+/// The differences between two versions of the registry, given as their JSON documents.
+///
+/// The fields of a node that change as it runs are not compared. A service whose status became
+/// `Removed` is reported as removed.
+pub fn diff_registries(old: &Value, new: &Value) -> Vec<RegistryChange> {
+    let mut changes = Vec::new();
+
+    let settings = |registry: &Value| -> BTreeMap<String, Value> {
+        registry
+            .as_object()
+            .map(|registry| {
+                registry
+                    .iter()
+                    .filter(|(field, _)| *field != "nodes" && *field != "save_path")
+                    .map(|(field, value)| (field.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let (old_settings, new_settings) = (settings(old), settings(new));
+    let fields = old_settings
+        .keys()
+        .chain(new_settings.keys())
+        .collect::<BTreeSet<_>>();
+    for field in fields {
+        let old = old_settings.get(field).cloned().unwrap_or(Value::Null);
+        let new = new_settings.get(field).cloned().unwrap_or(Value::Null);
+        if old != new {
+            changes.push(RegistryChange::SettingChanged {
+                field: field.clone(),
+                old,
+                new,
+            });
+        }
+    }
+
+    let services = |registry: &Value| -> BTreeMap<String, serde_json::Map<String, Value>> {
+        registry
+            .get("nodes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|node| {
+                let mut node = node.as_object()?.clone();
+                if node.get("status").and_then(Value::as_str) == Some("Removed") {
+                    return None;
+                }
+                for field in VOLATILE_NODE_FIELDS {
+                    node.remove(field);
+                }
+                node.remove("status");
+                let service_name = node.get("service_name")?.as_str()?.to_string();
+                Some((service_name, node))
+            })
+            .collect()
+    };
+    let (old_services, new_services) = (services(old), services(new));
+    for (service_name, old_node) in &old_services {
+        let Some(new_node) = new_services.get(service_name) else {
+            changes.push(RegistryChange::ServiceRemoved(service_name.clone()));
+            continue;
+        };
+        let fields = old_node
+            .keys()
+            .chain(new_node.keys())
+            .collect::<BTreeSet<_>>();
+        for field in fields {
+            let old = old_node.get(field).cloned().unwrap_or(Value::Null);
+            let new = new_node.get(field).cloned().unwrap_or(Value::Null);
+            if old != new {
+                changes.push(RegistryChange::ServiceChanged {
+                    service_name: service_name.clone(),
+                    field: field.clone(),
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+    for service_name in new_services.keys() {
+        if !old_services.contains_key(service_name) {
+            changes.push(RegistryChange::ServiceAdded(service_name.clone()));
+        }
+    }
+    changes
+}
+
+/// An antnode service definition, as installed by the service manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServiceDefinition {
+    pub service_name: String,
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub autostart: bool,
+    /// The environment variables the service is started with.
+    pub environment: Vec<(String, String)>,
+    pub user: Option<String>,
+    pub user_mode: bool,
+}
+
+/// The result of reconciling the registry with the host.
+#[derive(Debug, Default)]
+pub struct Reconciliation {
+    /// The services added to the registry.
+    pub added: Vec<String>,
+    /// The service definitions that could not be read, with the reason.
+    pub failed: Vec<(String, String)>,
+    /// The services in the registry without a definition on the host.
+    pub missing_definitions: Vec<String>,
+}
+
+/// Add the antnode services installed on the host but missing from the registry.
+///
+/// Existing entries are left as they are; a service whose definition has gone is only reported.
+pub async fn reconcile(node_registry: &NodeRegistryManager) -> Result<Reconciliation> {
+    let definitions = find_service_definitions()?;
+    let mut reconciliation = Reconciliation::default();
+
+    let mut registered = Vec::new();
+    for node in node_registry.nodes.read().await.iter() {
+        let node = node.read().await;
+        if node.status != ServiceStatus::Removed {
+            registered.push(node.service_name.clone());
+        }
+    }
+
+    for service_name in &registered {
+        if !definitions
+            .iter()
+            .any(|definition| &definition.service_name == service_name)
+        {
+            reconciliation
+                .missing_definitions
+                .push(service_name.clone());
+        }
+    }
+
+    for definition in definitions {
+        if registered.contains(&definition.service_name) {
+            continue;
+        }
+        match node_service_data_from_definition(&definition) {
+            Ok(node) => {
+                info!(
+                    "Adding {} to the registry from its service definition",
+                    definition.service_name
+                );
+                // The services share the environment variables of the registry.
+                if !definition.environment.is_empty() {
+                    let mut env_variables = node_registry.environment_variables.write().await;
+                    if env_variables.is_none() {
+                        *env_variables = Some(definition.environment.clone());
+                    }
+                }
+                // A removed entry is replaced by the service that was re-installed.
+                {
+                    let mut nodes = node_registry.nodes.write().await;
+                    let mut retained = Vec::new();
+                    for existing in nodes.drain(..) {
+                        if existing.read().await.service_name != definition.service_name {
+                            retained.push(existing);
+                        }
+                    }
+                    *nodes = retained;
+                }
+                node_registry.push_node(node).await;
+                reconciliation.added.push(definition.service_name);
+            }
+            Err(err) => {
+                error!(
+                    "Failed to read the service definition of {}: {err}",
+                    definition.service_name
+                );
+                reconciliation
+                    .failed
+                    .push((definition.service_name, err.to_string()));
+            }
+        }
+    }
+
+    if !reconciliation.added.is_empty() {
+        node_registry.save().await?;
+    }
+    Ok(reconciliation)
+}
+
+/// Build the registry entry of a service from its definition and the data directory of its node.
+pub fn node_service_data_from_definition(
+    definition: &ServiceDefinition,
+) -> Result<NodeServiceData> {
+    let mut node = parse_node_args(
+        &definition.service_name,
+        &definition.program,
+        &definition.args,
+    )?;
+    node.auto_restart = definition.autostart;
+    node.user = definition.user.clone();
+    node.user_mode = definition.user_mode;
+    node.version = get_bin_version(&node.antnode_path).unwrap_or_else(|err| {
+        warn!(
+            "Could not obtain the version of {:?}: {err}",
+            node.antnode_path
+        );
+        "unknown".to_string()
+    });
+    node.peer_id = read_peer_id(&node.data_dir_path);
+    Ok(node)
+}
+
+/// The peer ID of a node, from the key kept in its data directory.
+pub fn read_peer_id(data_dir_path: &Path) -> Option<PeerId> {
+    let bytes = std::fs::read(data_dir_path.join("secret-key")).ok()?;
+    let keypair = Keypair::ed25519_from_bytes(bytes)
+        .inspect_err(|err| warn!("Invalid secret key in {data_dir_path:?}: {err}"))
+        .ok()?;
+    Some(PeerId::from(keypair.public()))
+}
+
+/// Parse the arguments of an antnode service back into its registry entry.
+///
+/// This is the inverse of the arguments produced when the service is installed. The fields that
+/// cannot be obtained from the arguments are left with their defaults, and the service is
+/// considered stopped.
+pub fn parse_node_args(
+    service_name: &str,
+    program: &Path,
+    args: &[String],
+) -> Result<NodeServiceData> {
+    let number = service_name
+        .strip_prefix("antnode")
+        .and_then(|number| number.parse().ok())
+        .ok_or_else(|| eyre!("{service_name} is not the name of an antnode service"))?;
+
+    let mut alpha = false;
+    let mut data_dir_path = None;
+    let mut evm_network = None;
+    let mut initial_peers_config = InitialPeersConfig::default();
+    let mut log_dir_path = None;
+    let mut log_format = None;
+    let mut max_archived_log_files = None;
+    let mut max_download_rate = None;
+    let mut max_log_files = None;
+    let mut max_upload_rate = None;
+    let mut metrics_port = None;
+    let mut network_id = None;
+    let mut no_upnp = false;
+    let mut node_ip = None;
+    let mut node_port = None;
+    let mut relay = false;
+    let mut rewards_address = None;
+    let mut rpc_socket_addr = None;
+    let mut write_older_cache_files = false;
+    let mut rpc_url = None;
+    let mut payment_token_address = None;
+    let mut data_payments_address = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre!("The {arg} argument of {service_name} has no value"))
+        };
+        match arg.as_str() {
+            "--alpha" => alpha = true,
+            "--bootstrap-cache-dir" => {
+                initial_peers_config.bootstrap_cache_dir = Some(PathBuf::from(value()?))
+            }
+            "--data-payments-address" => data_payments_address = Some(value()?.clone()),
+            "--first" => initial_peers_config.first = true,
+            "--ignore-cache" => initial_peers_config.ignore_cache = true,
+            "--ip" => node_ip = Some(parse_value(arg, value()?)?),
+            "--local" => initial_peers_config.local = true,
+            "--log-format" => {
+                log_format = Some(
+                    LogFormat::parse_from_str(value()?)
+                        .map_err(|err| eyre!("Invalid {arg} argument: {err}"))?,
+                )
+            }
+            "--log-output-dest" => log_dir_path = Some(PathBuf::from(value()?)),
+            "--max-archived-log-files" => {
+                max_archived_log_files = Some(parse_value(arg, value()?)?)
+            }
+            "--max-download-rate" => max_download_rate = Some(parse_value(arg, value()?)?),
+            "--max-log-files" => max_log_files = Some(parse_value(arg, value()?)?),
+            "--max-upload-rate" => max_upload_rate = Some(parse_value(arg, value()?)?),
+            "--metrics-server-port" => metrics_port = Some(parse_value(arg, value()?)?),
+            "--network-contacts-url" => {
+                initial_peers_config.network_contacts_url =
+                    value()?.split(',').map(str::to_string).collect()
+            }
+            "--network-id" => network_id = Some(parse_value(arg, value()?)?),
+            "--no-upnp" => no_upnp = true,
+            "--payment-token-address" => payment_token_address = Some(value()?.clone()),
+            "--peer" => {
+                initial_peers_config.addrs = value()?
+                    .split(',')
+                    .map(|addr| parse_value(arg, addr))
+                    .collect::<Result<_>>()?
+            }
+            "--port" => node_port = Some(parse_value(arg, value()?)?),
+            "--relay" => relay = true,
+            "--rewards-address" => rewards_address = Some(parse_value(arg, value()?)?),
+            "--root-dir" => data_dir_path = Some(PathBuf::from(value()?)),
+            "--rpc" => rpc_socket_addr = Some(parse_value::<SocketAddr>(arg, value()?)?),
+            "--rpc-url" => rpc_url = Some(value()?.clone()),
+            "--write-older-cache-files" => write_older_cache_files = true,
+            "evm-custom" => evm_network = Some(None),
+            network if network.starts_with("evm-") => {
+                evm_network = Some(Some(
+                    EvmNetwork::from_str(network)
+                        .map_err(|_| eyre!("Unknown EVM network {network}"))?,
+                ))
+            }
+            _ => warn!("Ignoring the unknown argument {arg} of {service_name}"),
+        }
+    }
+
+    let evm_network = match evm_network {
+        Some(Some(network)) => network,
+        Some(None) => {
+            let missing =
+                |name: &str| eyre!("The custom EVM network of {service_name} has no {name}");
+            EvmNetwork::Custom(CustomNetwork {
+                rpc_url_http: parse_value(
+                    "--rpc-url",
+                    &rpc_url.ok_or_else(|| missing("--rpc-url"))?,
+                )?,
+                payment_token_address: parse_value(
+                    "--payment-token-address",
+                    &payment_token_address.ok_or_else(|| missing("--payment-token-address"))?,
+                )?,
+                data_payments_address: parse_value(
+                    "--data-payments-address",
+                    &data_payments_address.ok_or_else(|| missing("--data-payments-address"))?,
+                )?,
+            })
+        }
+        None => return Err(eyre!("The arguments of {service_name} have no EVM network")),
+    };
+    let required = |name: &str| eyre!("The arguments of {service_name} have no {name}");
+
+    Ok(NodeServiceData {
+        alpha,
+        antnode_path: program.to_path_buf(),
+        auto_restart: false,
+        backend: ServiceBackend::Native,
+        connected_peers: None,
+        data_dir_path: data_dir_path.ok_or_else(|| required("--root-dir"))?,
+        evm_network,
+        initial_peers_config,
+        listen_addr: None,
+        log_dir_path: log_dir_path.ok_or_else(|| required("--log-output-dest"))?,
+        log_format,
+        max_archived_log_files,
+        max_download_rate,
+        max_log_files,
+        max_upload_rate,
+        metrics_port,
+        network_id,
+        no_upnp,
+        node_ip,
+        node_port,
+        number,
+        peer_id: None,
+        pid: None,
+        relay,
+        reward_balance: None,
+        rewards_address: rewards_address.ok_or_else(|| required("--rewards-address"))?,
+        rpc_socket_addr: rpc_socket_addr.ok_or_else(|| required("--rpc"))?,
+        schema_version: NODE_SERVICE_DATA_SCHEMA_LATEST,
+        service_name: service_name.to_string(),
+        status: ServiceStatus::Stopped,
+        user: None,
+        user_mode: false,
+        version: String::new(),
+        write_older_cache_files,
+    })
+}
+
+fn parse_value<T: FromStr>(arg: &str, value: &str) -> Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|err| eyre!("Invalid value '{value}' for {arg}: {err}"))
+}
+
+/// Find the antnode service definitions installed system-wide and for the current user.
+#[cfg(target_os = "linux")]
+pub fn find_service_definitions() -> Result<Vec<ServiceDefinition>> {
+    let mut dirs = vec![(PathBuf::from("/etc/systemd/system"), false)];
+    if let Some(config_dir) = dirs_next::config_dir() {
+        dirs.push((config_dir.join("systemd").join("user"), true));
+    }
+    let mut definitions = Vec::new();
+    for (dir, user_mode) in dirs {
+        for (service_name, path) in find_antnode_files(&dir, "service")? {
+            let contents = std::fs::read_to_string(&path)?;
+            match parse_systemd_unit(&service_name, &contents, user_mode) {
+                Some(definition) => definitions.push(definition),
+                None => warn!("The service definition {path:?} has no ExecStart"),
+            }
+        }
+    }
+    Ok(definitions)
+}
+
+/// Find the antnode service definitions installed system-wide and for the current user.
+#[cfg(target_os = "macos")]
+pub fn find_service_definitions() -> Result<Vec<ServiceDefinition>> {
+    let mut dirs = vec![(PathBuf::from("/Library/LaunchDaemons"), false)];
+    if let Some(home_dir) = dirs_next::home_dir() {
+        dirs.push((home_dir.join("Library").join("LaunchAgents"), true));
+    }
+    let mut definitions = Vec::new();
+    for (dir, user_mode) in dirs {
+        for (service_name, path) in find_antnode_files(&dir, "plist")? {
+            let contents = std::fs::read_to_string(&path)?;
+            match parse_launchd_plist(&service_name, &contents, user_mode) {
+                Some(definition) => definitions.push(definition),
+                None => warn!("The service definition {path:?} has no ProgramArguments"),
+            }
+        }
+    }
+    Ok(definitions)
+}
+
+/// Find the antnode service definitions installed system-wide and for the current user.
+#[cfg(windows)]
+pub fn find_service_definitions() -> Result<Vec<ServiceDefinition>> {
+    Err(eyre!(
+        "Reading the service definitions is not supported on Windows"
+    ))
+}
+
+/// The files named after antnode services in `dir`, with the given extension.
+#[cfg(unix)]
+fn find_antnode_files(dir: &Path, extension: &str) -> Result<Vec<(String, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+            continue;
+        }
+        let Some(service_name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if service_name
+            .strip_prefix("antnode")
+            .is_some_and(|number| number.parse::<u16>().is_ok())
+        {
+            files.push((service_name.to_string(), path.clone()));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Parse a systemd unit written by the service manager.
+pub fn parse_systemd_unit(
+    service_name: &str,
+    contents: &str,
+    user_mode: bool,
+) -> Option<ServiceDefinition> {
+    let mut exec_start = None;
+    let mut environment = Vec::new();
+    let mut user = None;
+    let mut autostart = false;
+    for line in contents.lines().map(str::trim) {
+        if let Some(command) = line.strip_prefix("ExecStart=") {
+            exec_start = Some(command);
+        } else if let Some(assignments) = line.strip_prefix("Environment=") {
+            let assignments = split_systemd_words(assignments);
+            // An empty assignment resets the variables set before it.
+            if assignments.is_empty() {
+                environment.clear();
+            }
+            for assignment in assignments {
+                if let Some((name, value)) = assignment.split_once('=') {
+                    environment.retain(|(existing, _): &(String, String)| existing != name);
+                    environment.push((name.to_string(), value.to_string()));
+                }
+            }
+        } else if let Some(name) = line.strip_prefix("User=") {
+            user = Some(name.to_string());
+        } else if line.starts_with("WantedBy=") {
+            autostart = true;
+        }
+    }
+    let mut command = split_systemd_words(exec_start?).into_iter();
+    let program = PathBuf::from(command.next()?);
+    Some(ServiceDefinition {
+        service_name: service_name.to_string(),
+        program,
+        args: command.collect(),
+        autostart,
+        environment,
+        user,
+        user_mode,
+    })
+}
+
+/// Split a systemd command line or list of assignments into words the way systemd does: words
+/// are separated by whitespace, quotes keep words together and a backslash escapes the next
+/// character.
+fn split_systemd_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+                in_word = true;
+            }
+            (c, Some(open)) if c == open => quote = None,
+            (c, Some(_)) => word.push(c),
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (c, None) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (c, None) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Parse a launchd property list written by the service manager.
+pub fn parse_launchd_plist(
+    service_name: &str,
+    contents: &str,
+    user_mode: bool,
+) -> Option<ServiceDefinition> {
+    let value_after = |key: &str| {
+        let start = contents.find(&format!("<key>{key}</key>"))? + key.len() + 11;
+        Some(contents[start..].trim_start())
+    };
+    let strings = |xml: &str| {
+        let mut strings = Vec::new();
+        let mut rest = xml;
+        while let Some(start) = rest.find("<string>") {
+            let end = rest[start..].find("</string>")? + start;
+            strings.push(unescape_xml(&rest[start + 8..end]));
+            rest = &rest[end + 9..];
+        }
+        Some(strings)
+    };
+
+    let arguments = value_after("ProgramArguments")?;
+    let arguments = &arguments[..arguments.find("</array>")?];
+    let mut args = strings(arguments)?.into_iter();
+    let program = PathBuf::from(args.next()?);
+    let user = value_after("UserName")
+        .and_then(|value| strings(&value[..value.find("</string>")? + 9]))
+        .and_then(|names| names.into_iter().next());
+    let autostart = value_after("RunAtLoad").is_some_and(|value| value.starts_with("<true/>"));
+    let environment = value_after("EnvironmentVariables")
+        .map(|value| plist_string_dict(&value[..value.find("</dict>").unwrap_or(0)]))
+        .unwrap_or_default();
+    Some(ServiceDefinition {
+        service_name: service_name.to_string(),
+        program,
+        args: args.collect(),
+        autostart,
+        environment,
+        user,
+        user_mode,
+    })
+}
+
+/// The keys of a property list dictionary, each with the string value that follows it.
+fn plist_string_dict(xml: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut rest = xml;
+    while let (Some(key_start), Some(key_end)) = (rest.find("<key>"), rest.find("</key>"))
+        && key_start + 5 <= key_end
+    {
+        let name = unescape_xml(&rest[key_start + 5..key_end]);
+        rest = &rest[key_end + 6..];
+        let (Some(start), Some(end)) = (rest.find("<string>"), rest.find("</string>")) else {
+            break;
+        };
+        if start + 8 > end {
+            break;
+        }
+        entries.push((name, unescape_xml(&rest[start + 8..end])));
+        rest = &rest[end + 9..];
+    }
+    entries
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::add_services::config::InstallNodeServiceCtxBuilder;
+    use ant_evm::RewardsAddress;
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        sync::Arc,
+    };
+
+    fn builder() -> InstallNodeServiceCtxBuilder {
+        InstallNodeServiceCtxBuilder {
+            alpha: true,
+            antnode_path: PathBuf::from("/var/antctl/services/antnode3/antnode"),
+            autostart: true,
+            data_dir_path: PathBuf::from("/var/antctl/services/antnode3"),
+            env_variables: None,
+            evm_network: EvmNetwork::Custom(CustomNetwork::new(
+                "http://localhost:8545",
+                "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                "0x8464135c8F25Da09e49BC8782676a84730C318bC",
+            )),
+            log_dir_path: PathBuf::from("/var/log/antnode/antnode3"),
+            log_format: Some(LogFormat::Json),
+            max_archived_log_files: Some(5),
+            max_download_rate: Some(1000),
+            max_log_files: Some(10),
+            max_upload_rate: None,
+            metrics_port: Some(13003),
+            name: "antnode3".to_string(),
+            network_id: Some(2),
+            no_upnp: true,
+            node_ip: Some(Ipv4Addr::new(192, 168, 1, 10)),
+            node_port: Some(12003),
+            init_peers_config: InitialPeersConfig {
+                first: false,
+                addrs: vec![
+                    "/ip4/127.0.0.1/udp/8080/quic-v1/p2p/12D3KooWRBhwfeP2Y4TCx1SM6s9rUoHhR5STiGwxBhgFRcw3UERE"
+                        .parse()
+                        .unwrap(),
+                ],
+                network_contacts_url: vec!["http://localhost/contacts".to_string()],
+                local: false,
+                ignore_cache: true,
+                bootstrap_cache_dir: None,
+            },
+            rewards_address: RewardsAddress::from_str("0x03B770D9cD32077cC0bF330c13C114a87643B124")
+                .unwrap(),
+            relay: true,
+            rpc_socket_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 15003),
+            service_user: Some("ant".to_string()),
+            write_older_cache_files: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn saving_a_changed_registry_should_keep_a_snapshot_that_can_be_restored() {
+        let tmp_dir = assert_fs::TempDir::new().unwrap();
+        let node_registry = NodeRegistryManager::empty(tmp_dir.path().join("node_registry.json"));
+        node_registry.save().await.unwrap();
+        assert!(node_registry.snapshots().unwrap().is_empty());
+
+        *node_registry.environment_variables.write().await =
+            Some(vec![("ANT_LOG".to_string(), "all".to_string())]);
+        node_registry.save().await.unwrap();
+        // Saving an unchanged registry does not produce a snapshot.
+        node_registry.save().await.unwrap();
+        let snapshots = node_registry.snapshots().unwrap();
+        assert_eq!(snapshots.len(), 1);
+
+        node_registry.restore(snapshots[0].id).await.unwrap();
+        assert!(node_registry.environment_variables.read().await.is_none());
+        assert_eq!(node_registry.snapshots().unwrap().len(), 2);
+
+        for i in 0..30 {
+            *node_registry.environment_variables.write().await =
+                Some(vec![("ANT_LOG".to_string(), i.to_string())]);
+            node_registry.save().await.unwrap();
+        }
+        assert_eq!(
+            node_registry.snapshots().unwrap().len(),
+            ant_service_management::registry::REGISTRY_HISTORY_SIZE
+        );
+    }
+
+    #[tokio::test]
+    async fn reloading_should_pick_up_the_changes_saved_by_others() {
+        let tmp_dir = assert_fs::TempDir::new().unwrap();
+        let path = tmp_dir.path().join("node_registry.json");
+        let node_registry = NodeRegistryManager::empty(path.clone());
+        *node_registry.environment_variables.write().await =
+            Some(vec![("ANT_LOG".to_string(), "all".to_string())]);
+        // Nothing to reload before the registry is saved.
+        node_registry.reload().await.unwrap();
+        assert!(node_registry.environment_variables.read().await.is_some());
+
+        let other = NodeRegistryManager::empty(path);
+        other.save().await.unwrap();
+        node_registry.reload().await.unwrap();
+        assert!(node_registry.environment_variables.read().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn reloading_should_update_the_nodes_held_by_callers() {
+        let tmp_dir = assert_fs::TempDir::new().unwrap();
+        let path = tmp_dir.path().join("node_registry.json");
+        let node_registry = NodeRegistryManager::empty(path.clone());
+        node_registry.push_node(crate::test_utils::node(1)).await;
+        node_registry.push_node(crate::test_utils::node(2)).await;
+        node_registry.save().await.unwrap();
+        let held = Arc::clone(&node_registry.nodes.read().await[0]);
+
+        let other = NodeRegistryManager::load(&path).await.unwrap();
+        other.nodes.read().await[0].write().await.status = ServiceStatus::Stopped;
+        other.nodes.write().await.remove(1);
+        other.push_node(crate::test_utils::node(3)).await;
+        other.save().await.unwrap();
+
+        node_registry.reload().await.unwrap();
+        assert_eq!(held.read().await.status, ServiceStatus::Stopped);
+        let nodes = node_registry.nodes.read().await;
+        assert!(Arc::ptr_eq(&nodes[0], &held));
+        let mut service_names = Vec::new();
+        for node in nodes.iter() {
+            service_names.push(node.read().await.service_name.clone());
+        }
+        assert_eq!(service_names, vec!["antnode1", "antnode3"]);
+    }
+
+    #[test]
+    fn diff_registries_should_report_the_changed_services_and_settings() {
+        let old = serde_json::json!({
+            "environment_variables": null,
+            "nodes": [
+                { "service_name": "antnode1", "max_log_files": 10, "pid": 100, "status": "Running" },
+                { "service_name": "antnode2", "max_log_files": 10, "pid": 101, "status": "Running" },
+            ],
+        });
+        let new = serde_json::json!({
+            "environment_variables": [["ANT_LOG", "all"]],
+            "nodes": [
+                { "service_name": "antnode1", "max_log_files": 20, "pid": 200, "status": "Stopped" },
+                { "service_name": "antnode2", "max_log_files": 10, "pid": 101, "status": "Removed" },
+                { "service_name": "antnode3", "max_log_files": 10, "pid": null, "status": "Added" },
+            ],
+        });
+
+        assert_eq!(
+            diff_registries(&old, &new),
+            vec![
+                RegistryChange::SettingChanged {
+                    field: "environment_variables".to_string(),
+                    old: Value::Null,
+                    new: serde_json::json!([["ANT_LOG", "all"]]),
+                },
+                RegistryChange::ServiceChanged {
+                    service_name: "antnode1".to_string(),
+                    field: "max_log_files".to_string(),
+                    old: serde_json::json!(10),
+                    new: serde_json::json!(20),
+                },
+                RegistryChange::ServiceRemoved("antnode2".to_string()),
+                RegistryChange::ServiceAdded("antnode3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_node_args_should_invert_the_installed_arguments() {
+        let builder = builder();
+        let ctx = builder.build().unwrap();
+        let args = ctx
+            .args
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+
+        let node = parse_node_args("antnode3", &ctx.program, &args).unwrap();
+
+        let builder = self::builder();
+        assert_eq!(node.number, 3);
+        assert!(node.alpha);
+        assert_eq!(node.antnode_path, builder.antnode_path);
+        assert_eq!(node.data_dir_path, builder.data_dir_path);
+        assert_eq!(node.evm_network, builder.evm_network);
+        assert_eq!(node.initial_peers_config, builder.init_peers_config);
+        assert_eq!(node.log_dir_path, builder.log_dir_path);
+        assert_eq!(node.log_format, builder.log_format);
+        assert_eq!(node.max_archived_log_files, Some(5));
+        assert_eq!(node.max_download_rate, Some(1000));
+        assert_eq!(node.max_log_files, Some(10));
+        assert_eq!(node.max_upload_rate, None);
+        assert_eq!(node.metrics_port, Some(13003));
+        assert_eq!(node.network_id, Some(2));
+        assert!(node.no_upnp);
+        assert_eq!(node.node_ip, builder.node_ip);
+        assert_eq!(node.node_port, Some(12003));
+        assert!(node.relay);
+        assert_eq!(node.rewards_address, builder.rewards_address);
+        assert_eq!(node.rpc_socket_addr, builder.rpc_socket_addr);
+        assert!(node.write_older_cache_files);
+        assert_eq!(node.status, ServiceStatus::Stopped);
+    }
+
+    #[test]
+    fn parse_systemd_unit_should_read_the_command_and_user() {
+        let unit = "[Unit]\n\
+            Description=antnode3\n\
+            [Service]\n\
+            ExecStart=/var/antctl/services/antnode3/antnode --rpc 127.0.0.1:15003 --root-dir /var/antctl/services/antnode3\n\
+            User=ant\n\
+            [Install]\n\
+            WantedBy=multi-user.target";
+
+        let definition = parse_systemd_unit("antnode3", unit, false).unwrap();
+
+        assert_eq!(
+            definition,
+            ServiceDefinition {
+                service_name: "antnode3".to_string(),
+                program: PathBuf::from("/var/antctl/services/antnode3/antnode"),
+                args: vec![
+                    "--rpc".to_string(),
+                    "127.0.0.1:15003".to_string(),
+                    "--root-dir".to_string(),
+                    "/var/antctl/services/antnode3".to_string(),
+                ],
+                autostart: true,
+                environment: vec![],
+                user: Some("ant".to_string()),
+                user_mode: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_systemd_unit_should_handle_quotes_and_the_environment() {
+        let unit = "[Service]\n\
+            Environment=\"ANT_LOG=all\" RUST_BACKTRACE=1\n\
+            Environment=\"NOTE=two words\"\n\
+            ExecStart=/usr/bin/antnode --root-dir \"/var/ant nodes/antnode1\" --log-output-dest '/var/log/ant'\n";
+
+        let definition = parse_systemd_unit("antnode1", unit, true).unwrap();
+
+        assert_eq!(
+            definition.args,
+            vec![
+                "--root-dir".to_string(),
+                "/var/ant nodes/antnode1".to_string(),
+                "--log-output-dest".to_string(),
+                "/var/log/ant".to_string(),
+            ]
+        );
+        assert_eq!(
+            definition.environment,
+            vec![
+                ("ANT_LOG".to_string(), "all".to_string()),
+                ("RUST_BACKTRACE".to_string(), "1".to_string()),
+                ("NOTE".to_string(), "two words".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_launchd_plist_should_read_the_program_arguments_and_user() {
+        let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Label</key>
+	<string>antnode1</string>
+	<key>ProgramArguments</key>
+	<array>
+		<string>/usr/local/bin/antnode</string>
+		<string>--network-contacts-url</string>
+		<string>http://localhost/contacts?a=1&amp;b=2</string>
+	</array>
+	<key>UserName</key>
+	<string>ant</string>
+	<key>EnvironmentVariables</key>
+	<dict>
+		<key>ANT_LOG</key>
+		<string>all</string>
+	</dict>
+	<key>RunAtLoad</key>
+	<false/>
+</dict>
+</plist>"#;
+
+        let definition = parse_launchd_plist("antnode1", plist, true).unwrap();
+
+        assert_eq!(
+            definition,
+            ServiceDefinition {
+                service_name: "antnode1".to_string(),
+                program: PathBuf::from("/usr/local/bin/antnode"),
+                args: vec![
+                    "--network-contacts-url".to_string(),
+                    "http://localhost/contacts?a=1&b=2".to_string(),
+                ],
+                autostart: false,
+                environment: vec![("ANT_LOG".to_string(), "all".to_string())],
+                user: Some("ant".to_string()),
+                user_mode: true,
+            }
+        );
+    }
+}
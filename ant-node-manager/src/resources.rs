@@ -0,0 +1,373 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Planning the number of nodes a host can run, from its memory, CPU cores, free disk space and,
+//! optionally, its bandwidth.
+
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use sysinfo::{Disk, Disks, System};
+
+pub const MB: u64 = 1000 * 1000;
+pub const GB: u64 = MB * 1000;
+
+/// The disk space a node can grow to use.
+pub const DISK_PER_NODE_B: u64 = 35 * GB;
+/// The memory a node uses once its record store is full.
+pub const MEMORY_PER_NODE_B: u64 = 500 * MB;
+/// The memory left to the rest of the system.
+pub const RESERVED_MEMORY_B: u64 = GB;
+/// The share of a CPU core a node uses.
+pub const CPU_CORES_PER_NODE: f64 = 0.25;
+/// The bandwidth a node uses, in bytes per second.
+pub const BANDWIDTH_PER_NODE_BPS: u64 = 250 * 1000;
+
+/// How long the bandwidth probe downloads for, at most.
+const BANDWIDTH_PROBE_DURATION: Duration = Duration::from_secs(10);
+
+/// The resources of the host that limit the number of nodes it can run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct HostResources {
+    pub total_memory_b: u64,
+    pub cpu_cores: usize,
+    /// The free space on the drive holding the data of the nodes.
+    pub available_disk_b: u64,
+    /// Where that drive is mounted, if it is known.
+    pub mount_point: Option<PathBuf>,
+    /// Only known when measured with a probe.
+    pub bandwidth_bps: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Resource {
+    Memory,
+    Cpu,
+    Disk,
+    Bandwidth,
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let resource = match self {
+            Resource::Memory => "memory",
+            Resource::Cpu => "CPU cores",
+            Resource::Disk => "disk space",
+            Resource::Bandwidth => "bandwidth",
+        };
+        write!(f, "{resource}")
+    }
+}
+
+/// The nodes already on the host, and their use of the drive the new nodes are added to.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExistingNodes {
+    pub count: usize,
+    /// The nodes with their data on the same drive as the new ones.
+    pub on_drive: usize,
+    /// The disk space used by the data of the nodes on that drive.
+    pub drive_usage_b: u64,
+}
+
+impl ExistingNodes {
+    /// Measure the nodes with these data directories against the drive holding `data_dir`. Only
+    /// the directories on that drive are walked, which can take a while for large record stores.
+    pub fn measure(node_data_dirs: &[PathBuf], data_dir: &Path) -> Self {
+        let disks = Disks::new_with_refreshed_list();
+        let drive = mount_point_of(&disks, data_dir);
+        let mut existing = Self {
+            count: node_data_dirs.len(),
+            ..Default::default()
+        };
+        for node_data_dir in node_data_dirs {
+            if mount_point_of(&disks, node_data_dir) == drive {
+                existing.on_drive += 1;
+                existing.drive_usage_b += get_dir_disk_usage_b(node_data_dir);
+            }
+        }
+        existing
+    }
+}
+
+/// The maximum number of nodes recommended for the host, including the existing ones.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Recommendation {
+    pub max_nodes: usize,
+    pub limited_by: Resource,
+}
+
+/// The resources a number of nodes are expected to use.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProjectedUsage {
+    pub memory_b: u64,
+    pub cpu_cores: f64,
+    pub disk_b: u64,
+    pub bandwidth_bps: u64,
+}
+
+impl ProjectedUsage {
+    pub fn for_nodes(count: usize) -> Self {
+        Self {
+            memory_b: count as u64 * MEMORY_PER_NODE_B,
+            cpu_cores: count as f64 * CPU_CORES_PER_NODE,
+            disk_b: count as u64 * DISK_PER_NODE_B,
+            bandwidth_bps: count as u64 * BANDWIDTH_PER_NODE_BPS,
+        }
+    }
+}
+
+impl HostResources {
+    /// Measure the resources of the host, with the free space of the drive holding `data_dir`.
+    pub fn measure(data_dir: &Path) -> Self {
+        let mut system = System::new();
+        system.refresh_memory();
+        let cpu_cores = std::thread::available_parallelism().map_or(1, |cores| cores.get());
+        Self {
+            total_memory_b: system.total_memory(),
+            cpu_cores,
+            available_disk_b: get_available_disk_space_b(data_dir),
+            mount_point: get_mount_point(data_dir),
+            bandwidth_bps: None,
+        }
+    }
+
+    /// Recommend the maximum number of nodes, including the existing ones, when the new nodes are
+    /// added to the measured drive. The disk space the existing nodes on that drive use is not in
+    /// its free space, but they can grow until they each use [`DISK_PER_NODE_B`]. The nodes on
+    /// other drives don't take any of its space.
+    pub fn recommend(&self, existing: &ExistingNodes) -> Recommendation {
+        let on_other_drives = existing.count.saturating_sub(existing.on_drive);
+        let mut limits = vec![
+            (
+                (self.total_memory_b.saturating_sub(RESERVED_MEMORY_B) / MEMORY_PER_NODE_B)
+                    as usize,
+                Resource::Memory,
+            ),
+            (
+                (self.cpu_cores as f64 / CPU_CORES_PER_NODE) as usize,
+                Resource::Cpu,
+            ),
+            (
+                on_other_drives
+                    + (self.available_disk_b.saturating_add(existing.drive_usage_b)
+                        / DISK_PER_NODE_B) as usize,
+                Resource::Disk,
+            ),
+        ];
+        if let Some(bandwidth_bps) = self.bandwidth_bps {
+            limits.push((
+                (bandwidth_bps / BANDWIDTH_PER_NODE_BPS) as usize,
+                Resource::Bandwidth,
+            ));
+        }
+
+        let (max_nodes, limited_by) = limits
+            .into_iter()
+            .min_by_key(|(max_nodes, _)| *max_nodes)
+            .unwrap_or((0, Resource::Memory));
+        Recommendation {
+            max_nodes,
+            limited_by,
+        }
+    }
+}
+
+/// The free space on the drive holding `path`, which does not have to exist yet.
+pub fn get_available_disk_space_b(path: &Path) -> u64 {
+    let disks = Disks::new_with_refreshed_list();
+    disk_of(&disks, path).map_or(0, |disk| disk.available_space())
+}
+
+/// Where the drive holding `path` is mounted, `path` does not have to exist yet.
+pub fn get_mount_point(path: &Path) -> Option<PathBuf> {
+    let disks = Disks::new_with_refreshed_list();
+    mount_point_of(&disks, path)
+}
+
+fn mount_point_of(disks: &Disks, path: &Path) -> Option<PathBuf> {
+    disk_of(disks, path).map(|disk| disk.mount_point().to_path_buf())
+}
+
+fn disk_of<'a>(disks: &'a Disks, path: &Path) -> Option<&'a Disk> {
+    let path = path
+        .ancestors()
+        .find_map(|ancestor| ancestor.canonicalize().ok())
+        .unwrap_or_else(|| path.to_path_buf());
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+}
+
+/// The disk space used by the files under `path`, 0 if it does not exist.
+pub fn get_dir_disk_usage_b(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => get_dir_disk_usage_b(&entry.path()),
+            Ok(file_type) if file_type.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Measure the bandwidth of the host by downloading from `url`, in bytes per second.
+///
+/// The download stops after a few seconds, so a large file can be used.
+pub async fn probe_bandwidth(url: &str) -> Result<u64> {
+    info!("Probing the bandwidth with {url}");
+    let start = Instant::now();
+    let mut response = reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| eyre!("The bandwidth probe failed: {err}"))?;
+    let mut downloaded = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|err| eyre!("The bandwidth probe failed: {err}"))?
+    {
+        downloaded += chunk.len() as u64;
+        if start.elapsed() >= BANDWIDTH_PROBE_DURATION {
+            break;
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    if downloaded == 0 || elapsed == 0.0 {
+        return Err(eyre!("The bandwidth probe did not download anything"));
+    }
+    let bandwidth_bps = (downloaded as f64 / elapsed) as u64;
+    debug!("Downloaded {downloaded} bytes in {elapsed:.1}s: {bandwidth_bps} B/s");
+    Ok(bandwidth_bps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> HostResources {
+        HostResources {
+            total_memory_b: 16 * GB,
+            cpu_cores: 8,
+            available_disk_b: 1000 * GB,
+            mount_point: None,
+            bandwidth_bps: None,
+        }
+    }
+
+    /// Nodes all on the drive the new ones are added to.
+    fn on_drive(count: usize, drive_usage_b: u64) -> ExistingNodes {
+        ExistingNodes {
+            count,
+            on_drive: count,
+            drive_usage_b,
+        }
+    }
+
+    #[test]
+    fn recommendation_should_be_limited_by_the_scarcest_resource() {
+        // 30 nodes for the memory, 32 for the CPU and 28 for the disk.
+        assert_eq!(
+            host().recommend(&ExistingNodes::default()),
+            Recommendation {
+                max_nodes: 28,
+                limited_by: Resource::Disk
+            }
+        );
+        // The disk space already used by the existing nodes is theirs to grow into.
+        assert_eq!(
+            host().recommend(&on_drive(3, 100 * GB)),
+            Recommendation {
+                max_nodes: 30,
+                limited_by: Resource::Memory
+            }
+        );
+
+        let host = HostResources {
+            bandwidth_bps: Some(2_500_000),
+            ..host()
+        };
+        assert_eq!(
+            host.recommend(&ExistingNodes::default()),
+            Recommendation {
+                max_nodes: 10,
+                limited_by: Resource::Bandwidth
+            }
+        );
+    }
+
+    #[test]
+    fn existing_nodes_should_only_count_their_actual_disk_usage() {
+        let host = HostResources {
+            available_disk_b: 70 * GB,
+            ..host()
+        };
+        assert_eq!(host.recommend(&ExistingNodes::default()).max_nodes, 2);
+        // The 35GB used by the existing nodes is counted once, not as 35GB per node.
+        assert_eq!(host.recommend(&on_drive(2, 35 * GB)).max_nodes, 3);
+    }
+
+    #[test]
+    fn nodes_on_other_drives_should_not_take_the_space_of_the_drive() {
+        let host = HostResources {
+            available_disk_b: 70 * GB,
+            ..host()
+        };
+        // The 4 nodes on another drive are added to the 2 the free space of this one fits.
+        let existing = ExistingNodes {
+            count: 4,
+            on_drive: 0,
+            drive_usage_b: 0,
+        };
+        assert_eq!(host.recommend(&existing).max_nodes, 6);
+        // A node on this drive counts once, along with the space its data uses.
+        let existing = ExistingNodes {
+            count: 5,
+            on_drive: 1,
+            drive_usage_b: 10 * GB,
+        };
+        assert_eq!(host.recommend(&existing).max_nodes, 6);
+    }
+
+    #[test]
+    fn existing_nodes_should_only_walk_the_directories_on_the_drive() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let node_dir = dir.path().join("antnode1");
+        std::fs::create_dir_all(&node_dir).unwrap();
+        std::fs::write(node_dir.join("antnode.log"), [0u8; 100]).unwrap();
+        let existing = ExistingNodes::measure(std::slice::from_ref(&node_dir), dir.path());
+        assert_eq!(existing, on_drive(1, 100));
+    }
+
+    #[test]
+    fn dir_disk_usage_should_sum_nested_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("record_store")).unwrap();
+        std::fs::write(dir.path().join("antnode.log"), [0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("record_store").join("record"), [0u8; 50]).unwrap();
+        assert_eq!(get_dir_disk_usage_b(dir.path()), 150);
+        assert_eq!(get_dir_disk_usage_b(&dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn small_host_should_not_be_recommended_any_node() {
+        let host = HostResources {
+            total_memory_b: GB,
+            ..host()
+        };
+        assert_eq!(host.recommend(&ExistingNodes::default()).max_nodes, 0);
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Upgrading services in stages, each stage gated on the health of the services it upgraded.
+
+use crate::DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S;
+use ant_protocol::CLOSE_GROUP_SIZE;
+use ant_service_management::{error::Result as ServiceResult, rpc::RpcActions};
+use std::{ops::Range, time::Duration};
+
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How the services are split into stages when they get upgraded.
+///
+/// The default upgrades the services one at a time, without checking their health.
+#[derive(Clone, Debug)]
+pub struct RollingUpgradeStrategy {
+    /// The percentage of the services upgraded first, on their own, as canaries.
+    pub canary_percentage: u8,
+    /// The number of services upgraded in each stage after the canaries.
+    pub batch_size: usize,
+    /// If set, each stage must be healthy before moving on to the next. The unhealthy services
+    /// are rolled back and the upgrade is halted.
+    pub health_gate: Option<HealthGate>,
+}
+
+impl Default for RollingUpgradeStrategy {
+    fn default() -> Self {
+        Self {
+            canary_percentage: 0,
+            batch_size: 1,
+            health_gate: None,
+        }
+    }
+}
+
+impl RollingUpgradeStrategy {
+    /// Split `count` services into the consecutive stages they are upgraded in.
+    pub fn stages(&self, count: usize) -> Vec<Range<usize>> {
+        let mut stages = Vec::new();
+        let canaries = if self.canary_percentage == 0 || count == 0 {
+            0
+        } else {
+            (count * self.canary_percentage.min(100) as usize).div_ceil(100)
+        };
+        if canaries > 0 {
+            stages.push(0..canaries);
+        }
+
+        let batch_size = self.batch_size.max(1);
+        let mut start = canaries;
+        while start < count {
+            let end = (start + batch_size).min(count);
+            stages.push(start..end);
+            start = end;
+        }
+        stages
+    }
+}
+
+/// The thresholds an upgraded service must reach to be considered healthy.
+#[derive(Clone, Debug)]
+pub struct HealthGate {
+    pub min_connected_peers: usize,
+    pub min_routing_table_size: usize,
+    /// The share of the records held before the upgrade that the service must hold again,
+    /// between 0 and 1.
+    pub min_records_ratio: f64,
+    /// How long the service has to become healthy once upgraded.
+    pub timeout: Duration,
+}
+
+impl Default for HealthGate {
+    fn default() -> Self {
+        Self {
+            min_connected_peers: CLOSE_GROUP_SIZE,
+            min_routing_table_size: CLOSE_GROUP_SIZE * 4,
+            min_records_ratio: 0.9,
+            timeout: Duration::from_secs(DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S),
+        }
+    }
+}
+
+/// The health of a running service, as reported through its RPC service.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeHealth {
+    pub connected_peers: usize,
+    pub routing_table_size: usize,
+    pub records_stored: usize,
+}
+
+impl NodeHealth {
+    pub async fn probe(rpc_client: &dyn RpcActions) -> ServiceResult<Self> {
+        let connected_peers = rpc_client.network_info().await?.connected_peers.len();
+        let routing_table_size = rpc_client.routing_table_size().await?;
+        let records_stored = rpc_client.record_addresses().await?.len();
+        Ok(Self {
+            connected_peers,
+            routing_table_size,
+            records_stored,
+        })
+    }
+}
+
+impl HealthGate {
+    /// Returns why the service is not healthy, compared to its health before the upgrade.
+    pub fn check(&self, before: Option<&NodeHealth>, health: &NodeHealth) -> Option<String> {
+        if health.connected_peers < self.min_connected_peers {
+            return Some(format!(
+                "{} connected peers, expected at least {}",
+                health.connected_peers, self.min_connected_peers
+            ));
+        }
+        if health.routing_table_size < self.min_routing_table_size {
+            return Some(format!(
+                "{} peers in the routing table, expected at least {}",
+                health.routing_table_size, self.min_routing_table_size
+            ));
+        }
+        if let Some(before) = before {
+            let min_records = (before.records_stored as f64 * self.min_records_ratio) as usize;
+            if health.records_stored < min_records {
+                return Some(format!(
+                    "{} records stored, expected at least {min_records}",
+                    health.records_stored
+                ));
+            }
+        }
+        None
+    }
+
+    /// Poll the health of the service until it passes the gate. On timeout, the reason of the
+    /// last failed check is returned.
+    pub async fn wait_until_healthy(
+        &self,
+        rpc_client: &dyn RpcActions,
+        before: Option<&NodeHealth>,
+    ) -> Result<NodeHealth, String> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        loop {
+            let reason = match NodeHealth::probe(rpc_client).await {
+                Ok(health) => match self.check(before, &health) {
+                    None => return Ok(health),
+                    Some(reason) => reason,
+                },
+                Err(err) => format!("health could not be obtained: {err}"),
+            };
+            debug!("The service is not healthy yet: {reason}");
+            if tokio::time::Instant::now() + HEALTH_POLL_INTERVAL > deadline {
+                return Err(reason);
+            }
+            tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_strategy_should_upgrade_one_service_at_a_time() {
+        let stages = RollingUpgradeStrategy::default().stages(3);
+        assert_eq!(stages, vec![0..1, 1..2, 2..3]);
+        assert!(RollingUpgradeStrategy::default().stages(0).is_empty());
+    }
+
+    #[test]
+    fn canaries_should_be_upgraded_before_the_batches() {
+        let strategy = RollingUpgradeStrategy {
+            canary_percentage: 10,
+            batch_size: 4,
+            health_gate: None,
+        };
+        assert_eq!(strategy.stages(11), vec![0..2, 2..6, 6..10, 10..11]);
+        // At least one canary, however few the services.
+        assert_eq!(strategy.stages(3), vec![0..1, 1..3]);
+    }
+
+    #[test]
+    fn health_gate_should_check_every_threshold() {
+        let gate = HealthGate {
+            min_connected_peers: 5,
+            min_routing_table_size: 20,
+            min_records_ratio: 0.9,
+            timeout: Duration::from_secs(1),
+        };
+        let before = NodeHealth {
+            connected_peers: 40,
+            routing_table_size: 100,
+            records_stored: 100,
+        };
+        let healthy = NodeHealth {
+            connected_peers: 6,
+            routing_table_size: 25,
+            records_stored: 90,
+        };
+        assert_eq!(gate.check(Some(&before), &healthy), None);
+
+        let few_peers = NodeHealth {
+            connected_peers: 4,
+            ..healthy.clone()
+        };
+        assert!(gate.check(Some(&before), &few_peers).is_some());
+        let small_routing_table = NodeHealth {
+            routing_table_size: 19,
+            ..healthy.clone()
+        };
+        assert!(gate.check(Some(&before), &small_routing_table).is_some());
+        let lost_records = NodeHealth {
+            records_stored: 89,
+            ..healthy.clone()
+        };
+        assert!(gate.check(Some(&before), &lost_records).is_some());
+        assert_eq!(gate.check(None, &lost_records), None);
+    }
+}
@@ -8,22 +8,36 @@
 
 use crate::{
     ServiceManager, VerbosityLevel, add_services::config::InstallNodeServiceCtxBuilder,
-    config::create_owned_dir,
+    config::create_owned_dir, rolling_upgrade::NodeHealth,
 };
+use ant_evm::{CustomNetwork, EvmAddress, EvmNetwork};
 use ant_service_management::{
     NodeRegistryManager, NodeService, NodeServiceData, ServiceStatus,
-    control::{ServiceControl, ServiceController},
+    antctl_proto::{AddNodeServicesRequest, CustomEvmNetwork},
     node::NODE_SERVICE_DATA_SCHEMA_LATEST,
-    rpc::RpcClient,
+    rpc::{RpcActions, RpcClient},
 };
 use color_eyre::{
     Result,
     eyre::{OptionExt, eyre},
 };
 use libp2p::PeerId;
-use std::sync::Arc;
+use serde::Serialize;
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+const LOG_FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+/// The size of the blocks in which the tail of a log file is read.
+const LOG_TAIL_BLOCK_SIZE: u64 = 64 * 1024;
 
 pub async fn restart_node_service(
+    launch_as_children: bool,
     node_registry: NodeRegistryManager,
     peer_id: PeerId,
     retain_peer_id: bool,
@@ -43,17 +57,18 @@ pub async fn restart_node_service(
         eyre!("Could not find the provided PeerId: {peer_id:?}")
     })?;
 
+    let backend = current_node.read().await.backend.clone();
     let rpc_client = RpcClient::from_socket_addr(current_node.read().await.rpc_socket_addr);
     let service = NodeService::new(Arc::clone(&current_node), Box::new(rpc_client));
     let mut service_manager = ServiceManager::new(
         service,
-        Box::new(ServiceController {}),
+        backend.controller_for(launch_as_children),
         VerbosityLevel::Normal,
     );
     service_manager.stop().await?;
     let service_name = current_node.read().await.service_name.clone();
 
-    let service_control = ServiceController {};
+    let service_control = backend.controller_for(launch_as_children);
     if retain_peer_id {
         debug!("Retaining the peer id: {peer_id:?} for the node: {service_name:?}");
         // reuse the same port and root dir to retain peer id.
@@ -75,7 +90,9 @@ pub async fn restart_node_service(
             log_dir_path: current_node_clone.log_dir_path.clone(),
             log_format: current_node_clone.log_format,
             max_archived_log_files: current_node_clone.max_archived_log_files,
+            max_download_rate: current_node_clone.max_download_rate,
             max_log_files: current_node_clone.max_log_files,
+            max_upload_rate: current_node_clone.max_upload_rate,
             metrics_port: None,
             name: current_node_clone.service_name.clone(),
             network_id: current_node_clone.network_id,
@@ -192,7 +209,9 @@ pub async fn restart_node_service(
             log_format: current_node_clone.log_format,
             name: new_service_name.clone(),
             max_archived_log_files: current_node_clone.max_archived_log_files,
+            max_download_rate: current_node_clone.max_download_rate,
             max_log_files: current_node_clone.max_log_files,
+            max_upload_rate: current_node_clone.max_upload_rate,
             metrics_port: None,
             network_id: current_node_clone.network_id,
             node_ip: current_node_clone.node_ip,
@@ -213,6 +232,7 @@ pub async fn restart_node_service(
             alpha: current_node_clone.alpha,
             antnode_path,
             auto_restart: current_node_clone.auto_restart,
+            backend: backend.clone(),
             connected_peers: None,
             data_dir_path,
             evm_network: current_node_clone.evm_network,
@@ -222,7 +242,9 @@ pub async fn restart_node_service(
             log_dir_path,
             log_format: current_node_clone.log_format,
             max_archived_log_files: current_node_clone.max_archived_log_files,
+            max_download_rate: current_node_clone.max_download_rate,
             max_log_files: current_node_clone.max_log_files,
+            max_upload_rate: current_node_clone.max_upload_rate,
             metrics_port: None,
             network_id: current_node_clone.network_id,
             node_ip: current_node_clone.node_ip,
@@ -247,7 +269,7 @@ pub async fn restart_node_service(
         let service = NodeService::new(Arc::clone(&current_node), Box::new(rpc_client));
         let mut service_manager = ServiceManager::new(
             service,
-            Box::new(ServiceController {}),
+            backend.controller_for(launch_as_children),
             VerbosityLevel::Normal,
         );
         service_manager.start().await?;
@@ -258,3 +280,278 @@ pub async fn restart_node_service(
 
     Ok(())
 }
+
+/// A snapshot of the metrics of a running node, obtained through its RPC service.
+///
+/// When the node could not be reached, only the service name, peer ID and error are set.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct NodeMetrics {
+    pub service_name: String,
+    #[serde(serialize_with = "NodeServiceData::serialize_peer_id")]
+    pub peer_id: Option<PeerId>,
+    pub version: String,
+    pub pid: Option<u32>,
+    pub uptime_secs: u64,
+    pub connected_peers: usize,
+    pub routing_table_size: usize,
+    pub records_stored: usize,
+    pub wallet_balance: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Get the metrics of the running services, or of the named ones if any are provided.
+///
+/// A node that cannot be reached does not fail the others, its error is reported in its metrics.
+pub async fn get_node_metrics(
+    node_registry: NodeRegistryManager,
+    service_names: &[String],
+) -> Result<Vec<NodeMetrics>> {
+    let mut metrics = Vec::new();
+    for node in node_registry.nodes.read().await.iter() {
+        let node = node.read().await;
+        if !service_names.is_empty() && !service_names.contains(&node.service_name) {
+            continue;
+        }
+        if node.status != ServiceStatus::Running {
+            debug!(
+                "Skipping metrics for {}, it is not running",
+                node.service_name
+            );
+            continue;
+        }
+
+        let rpc_client = RpcClient::from_socket_addr(node.rpc_socket_addr);
+        let node_metrics = match rpc_client.node_info().await {
+            Ok(node_info) => match NodeHealth::probe(&rpc_client).await {
+                Ok(health) => NodeMetrics {
+                    service_name: node.service_name.clone(),
+                    peer_id: node.peer_id,
+                    version: node_info.version,
+                    pid: Some(node_info.pid),
+                    uptime_secs: node_info.uptime.as_secs(),
+                    connected_peers: health.connected_peers,
+                    routing_table_size: health.routing_table_size,
+                    records_stored: health.records_stored,
+                    wallet_balance: node_info.wallet_balance,
+                    error: None,
+                },
+                Err(err) => NodeMetrics {
+                    service_name: node.service_name.clone(),
+                    peer_id: node.peer_id,
+                    error: Some(format!("Could not obtain the health: {err}")),
+                    ..Default::default()
+                },
+            },
+            Err(err) => NodeMetrics {
+                service_name: node.service_name.clone(),
+                peer_id: node.peer_id,
+                error: Some(format!("Could not obtain the node info: {err}")),
+                ..Default::default()
+            },
+        };
+        if let Some(err) = &node_metrics.error {
+            warn!("Failed to get the metrics of {}: {err}", node.service_name);
+        }
+        metrics.push(node_metrics);
+    }
+
+    for name in service_names {
+        if !metrics.iter().any(|metrics| &metrics.service_name == name) {
+            warn!("No metrics were obtained for {name}");
+        }
+    }
+    Ok(metrics)
+}
+
+/// The path of the current log file of a node.
+pub fn get_node_log_path(node: &NodeServiceData) -> PathBuf {
+    node.log_dir_path.join("antnode.log")
+}
+
+/// Read the last `count` lines of a log file, returning them along with the length of the file.
+///
+/// The file is read backwards by blocks, so that only its tail is loaded.
+pub fn read_last_log_lines(log_path: &Path, count: usize) -> Result<(Vec<String>, u64)> {
+    let read_tail = || -> std::io::Result<(Vec<u8>, u64, bool)> {
+        let mut file = std::fs::File::open(log_path)?;
+        let len = file.metadata()?.len();
+        let mut tail = Vec::new();
+        let mut newlines = 0;
+        let mut pos = len;
+        // One more newline than lines makes sure the first line kept is complete
+        while pos > 0 && newlines <= count {
+            let block_len = pos.min(LOG_TAIL_BLOCK_SIZE);
+            pos -= block_len;
+            let mut block = vec![0; block_len as usize];
+            file.seek(SeekFrom::Start(pos))?;
+            file.read_exact(&mut block)?;
+            newlines += block.iter().filter(|byte| **byte == b'\n').count();
+            block.extend_from_slice(&tail);
+            tail = block;
+        }
+        Ok((tail, len, pos == 0))
+    };
+    let (mut tail, offset, from_start) =
+        read_tail().map_err(|err| eyre!("Could not read the log file at {log_path:?}: {err}"))?;
+    if !from_start {
+        let first_newline = tail.iter().position(|byte| *byte == b'\n').unwrap_or(0);
+        let _ = tail.drain(..=first_newline);
+    }
+
+    let tail = String::from_utf8_lossy(&tail);
+    let lines: Vec<&str> = tail.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    let lines = lines[start..].iter().map(|line| line.to_string()).collect();
+    Ok((lines, offset))
+}
+
+/// Send the lines appended to a log file after `offset`, until the receiver is dropped.
+///
+/// When the file is rotated, the lines of the new file are sent from its start.
+pub async fn follow_log(log_path: PathBuf, mut offset: u64, sender: mpsc::Sender<String>) {
+    let mut partial_line = String::new();
+    loop {
+        tokio::time::sleep(LOG_FOLLOW_INTERVAL).await;
+        if sender.is_closed() {
+            debug!("Stopped following {log_path:?}");
+            return;
+        }
+        let Ok(len) = std::fs::metadata(&log_path).map(|metadata| metadata.len()) else {
+            continue;
+        };
+        if len < offset {
+            debug!("The log file {log_path:?} was rotated");
+            offset = 0;
+            partial_line.clear();
+        }
+        if len == offset {
+            continue;
+        }
+
+        let Ok(appended) = read_log_from(&log_path, offset) else {
+            continue;
+        };
+        offset += appended.len() as u64;
+        partial_line.push_str(&String::from_utf8_lossy(&appended));
+        while let Some(index) = partial_line.find('\n') {
+            let line: String = partial_line.drain(..=index).collect();
+            if sender.send(line.trim_end().to_string()).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Read the bytes of a log file from `offset` to its end.
+fn read_log_from(log_path: &Path, offset: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(log_path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut appended = Vec::new();
+    file.read_to_end(&mut appended)?;
+    Ok(appended)
+}
+
+/// Get the network of an `AddNodeServicesRequest`, the default one if none is set.
+///
+/// The request comes from a remote client, so a malformed custom network is an error rather than
+/// a panic.
+pub fn evm_network_from_request(request: &AddNodeServicesRequest) -> Result<Option<EvmNetwork>> {
+    if let Some(custom) = &request.custom_evm_network {
+        let rpc_url_http = custom
+            .rpc_url
+            .parse()
+            .map_err(|err| eyre!("Invalid RPC URL {}: {err}", custom.rpc_url))?;
+        let payment_token_address =
+            EvmAddress::from_str(&custom.payment_token_address).map_err(|err| {
+                eyre!(
+                    "Invalid payment token address {}: {err}",
+                    custom.payment_token_address
+                )
+            })?;
+        let data_payments_address =
+            EvmAddress::from_str(&custom.data_payments_address).map_err(|err| {
+                eyre!(
+                    "Invalid data payments address {}: {err}",
+                    custom.data_payments_address
+                )
+            })?;
+        return Ok(Some(EvmNetwork::Custom(CustomNetwork {
+            rpc_url_http,
+            payment_token_address,
+            data_payments_address,
+        })));
+    }
+    request
+        .evm_network
+        .as_ref()
+        .map(|network| {
+            EvmNetwork::from_str(network).map_err(|_| eyre!("Unknown EVM network: {network}"))
+        })
+        .transpose()
+}
+
+/// Set the network of an `AddNodeServicesRequest`.
+pub fn set_request_evm_network(request: &mut AddNodeServicesRequest, network: EvmNetwork) {
+    match network {
+        EvmNetwork::Custom(custom) => {
+            request.custom_evm_network = Some(CustomEvmNetwork {
+                rpc_url: custom.rpc_url_http.to_string(),
+                payment_token_address: custom.payment_token_address.to_string(),
+                data_payments_address: custom.data_payments_address.to_string(),
+            });
+        }
+        network => request.evm_network = Some(network.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_log_lines_should_be_read_from_the_tail() -> Result<()> {
+        let dir = assert_fs::TempDir::new()?;
+        let log_path = dir.path().join("antnode.log");
+        let content: String = (0..10_000).map(|index| format!("line {index}\n")).collect();
+        std::fs::write(&log_path, &content)?;
+
+        let (lines, offset) = read_last_log_lines(&log_path, 3)?;
+        assert_eq!(lines, ["line 9997", "line 9998", "line 9999"]);
+        assert_eq!(offset, content.len() as u64);
+
+        let (lines, _) = read_last_log_lines(&log_path, 20_000)?;
+        assert_eq!(lines.len(), 10_000);
+        assert_eq!(lines[0], "line 0");
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_custom_evm_network_should_be_an_error() {
+        let request = |rpc_url: &str, address: &str| AddNodeServicesRequest {
+            custom_evm_network: Some(CustomEvmNetwork {
+                rpc_url: rpc_url.to_string(),
+                payment_token_address: address.to_string(),
+                data_payments_address: address.to_string(),
+            }),
+            ..Default::default()
+        };
+        let address = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+
+        assert!(evm_network_from_request(&request("not a url", address)).is_err());
+        assert!(evm_network_from_request(&request("http://localhost:8545", "0x123")).is_err());
+        assert!(matches!(
+            evm_network_from_request(&request("http://localhost:8545", address)),
+            Ok(Some(EvmNetwork::Custom(_)))
+        ));
+    }
+
+    #[test]
+    fn log_should_be_read_from_the_offset() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let log_path = dir.path().join("antnode.log");
+        std::fs::write(&log_path, "first\nsecond\n").unwrap();
+        assert_eq!(read_log_from(&log_path, 6).unwrap(), b"second\n");
+        assert!(read_log_from(&log_path, 13).unwrap().is_empty());
+    }
+}
@@ -4,26 +4,115 @@ use color_eyre::eyre::bail;
 use color_eyre::{Result, eyre::eyre};
 use libp2p_identity::PeerId;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
-use tonic::Request;
-use tonic::transport::Channel;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::{Interceptor, interceptor::InterceptedService};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::{Request, Status};
+
+/// The metadata key holding the shared token, as `Bearer <token>`.
+pub const AUTHORIZATION_HEADER: &str = "authorization";
+
+/// The name the daemon certificate is verified against, unless another is provided.
+pub const DEFAULT_DAEMON_DOMAIN_NAME: &str = "antctld";
+
+pub type DaemonClient = AntCtlClient<InterceptedService<Channel, AuthInterceptor>>;
+
+/// How the client authenticates with the daemon, and verifies it.
+#[derive(Clone, Debug, Default)]
+pub struct DaemonAuth {
+    /// The shared token sent with each request.
+    pub token: Option<String>,
+    /// The CA certificate the daemon certificate is verified with. Setting it enables TLS.
+    pub ca_cert_path: Option<PathBuf>,
+    /// The certificate and key presented to the daemon, for mutual TLS.
+    pub client_cert_path: Option<PathBuf>,
+    pub client_key_path: Option<PathBuf>,
+    /// The name the daemon certificate is verified against.
+    pub domain_name: Option<String>,
+}
+
+/// Adds the shared token, if any, to every request sent to the daemon.
+#[derive(Clone, Debug)]
+pub struct AuthInterceptor {
+    token: Option<MetadataValue<Ascii>>,
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(token) = &self.token {
+            request
+                .metadata_mut()
+                .insert(AUTHORIZATION_HEADER, token.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// Connect to the daemon at the given address, without retrying.
+pub async fn connect(addr: SocketAddr, auth: &DaemonAuth) -> Result<DaemonClient> {
+    let token = auth
+        .token
+        .as_ref()
+        .map(|token| {
+            MetadataValue::from_str(&format!("Bearer {token}"))
+                .map_err(|_| eyre!("The daemon token contains invalid characters"))
+        })
+        .transpose()?;
+
+    let mut endpoint = Endpoint::from_shared(format!("https://{addr}"))?;
+    if let Some(ca_cert_path) = &auth.ca_cert_path {
+        let ca_cert = std::fs::read(ca_cert_path)
+            .map_err(|err| eyre!("Could not read the CA certificate at {ca_cert_path:?}: {err}"))?;
+        let mut tls_config = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(ca_cert))
+            .domain_name(
+                auth.domain_name
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_DAEMON_DOMAIN_NAME.to_string()),
+            );
+        match (&auth.client_cert_path, &auth.client_key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                let cert = std::fs::read(cert_path).map_err(|err| {
+                    eyre!("Could not read the client certificate at {cert_path:?}: {err}")
+                })?;
+                let key = std::fs::read(key_path)
+                    .map_err(|err| eyre!("Could not read the client key at {key_path:?}: {err}"))?;
+                tls_config = tls_config.identity(Identity::from_pem(cert, key));
+            }
+            (None, None) => {}
+            _ => bail!("Both the client certificate and key are required for mutual TLS"),
+        }
+        endpoint = endpoint.tls_config(tls_config)?;
+    } else if auth.client_cert_path.is_some() || auth.client_key_path.is_some() {
+        bail!("The CA certificate of the daemon is required to use TLS");
+    }
+
+    let channel = endpoint.connect().await?;
+    Ok(AntCtlClient::with_interceptor(
+        channel,
+        AuthInterceptor { token },
+    ))
+}
 
 struct DaemonRpcClient {
     addr: SocketAddr,
-    rpc: AntCtlClient<Channel>,
+    rpc: DaemonClient,
 }
 
 pub async fn restart_node(
     peer_ids: Vec<String>,
     rpc_server_address: SocketAddr,
     retain_peer_id: bool,
+    auth: &DaemonAuth,
 ) -> Result<()> {
     for peer_id in peer_ids {
         debug!("Sending NodeServiceRestartRequest to {peer_id:?} at {rpc_server_address:?}");
         let str_bytes = PeerId::from_str(&peer_id)?.to_bytes();
 
-        let mut daemon_client = get_rpc_client(rpc_server_address).await?;
+        let mut daemon_client = get_rpc_client(rpc_server_address, auth).await?;
 
         let _response = daemon_client
             .rpc
@@ -44,11 +133,11 @@ pub async fn restart_node(
     Ok(())
 }
 
-async fn get_rpc_client(socket_addr: SocketAddr) -> Result<DaemonRpcClient> {
+async fn get_rpc_client(socket_addr: SocketAddr, auth: &DaemonAuth) -> Result<DaemonRpcClient> {
     let endpoint = format!("https://{socket_addr}");
     let mut attempts = 0;
     loop {
-        if let Ok(rpc_client) = AntCtlClient::connect(endpoint.clone()).await {
+        if let Ok(rpc_client) = connect(socket_addr, auth).await {
             let rpc_client = DaemonRpcClient {
                 addr: socket_addr,
                 rpc: rpc_client,
@@ -0,0 +1,706 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Supervision of the node services by the daemon.
+//!
+//! The running services are periodically checked through their RPC service. Those that crashed,
+//! or stayed unhealthy for longer than a grace period, are restarted with an exponential backoff.
+//! A service restarted too many times within a window is crash-looping: it is stopped and
+//! quarantined until it is started again by hand. Every action is recorded in an audit log, kept
+//! with the rest of the supervision state next to the node registry.
+
+use crate::{ServiceManager, VerbosityLevel, refresh_node_registry};
+use ant_service_management::{
+    NodeRegistryManager, NodeService, NodeServiceData, ServiceStatus,
+    control::ServiceController,
+    rpc::{RpcActions, RpcClient},
+};
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const SUPERVISOR_STATE_FILE_NAME: &str = "supervisor.json";
+/// Locked while the services are operated on, by a supervision pass of the daemon or by `antctl`.
+pub const OPERATION_LOCK_FILE_NAME: &str = "operations.lock";
+/// The number of audit log entries kept for each service.
+const MAX_AUDIT_ENTRIES: usize = 50;
+/// The log files that are never deleted when the logs of a service are rotated.
+const PROTECTED_LOG_FILES: [&str; 2] = ["antnode.log", "critical_failure.log"];
+
+#[derive(Clone, Debug)]
+pub struct SupervisorConfig {
+    pub check_interval: Duration,
+    /// How long a running service can be unhealthy before it gets restarted.
+    pub unhealthy_grace_period: Duration,
+    /// How long the process of a service can be gone before it gets restarted. A service stopped
+    /// by an operation that did not go through the operation lock is then left to it.
+    pub crashed_grace_period: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// A service restarted this many times within the crash loop window is quarantined.
+    pub crash_loop_restarts: usize,
+    pub crash_loop_window: Duration,
+    /// The archived logs of a service are deleted, oldest first, beyond this total size.
+    pub max_log_dir_bytes: u64,
+    pub rpc_timeout: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(60),
+            unhealthy_grace_period: Duration::from_secs(5 * 60),
+            crashed_grace_period: Duration::from_secs(60),
+            initial_backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(30 * 60),
+            crash_loop_restarts: 5,
+            crash_loop_window: Duration::from_secs(60 * 60),
+            max_log_dir_bytes: 1024 * 1024 * 1024,
+            rpc_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// The outcome of a health check.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Healthy,
+    /// The process of the service is no longer running.
+    Crashed,
+    Unhealthy(String),
+}
+
+/// What the supervisor has to do with a service, following a health check.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision {
+    Nothing,
+    Restart(String),
+    Quarantine(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SupervisorAction {
+    Restarted,
+    RestartFailed,
+    Quarantined,
+    Released,
+    LogsRotated,
+}
+
+impl fmt::Display for SupervisorAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            SupervisorAction::Restarted => "restarted",
+            SupervisorAction::RestartFailed => "restart failed",
+            SupervisorAction::Quarantined => "quarantined",
+            SupervisorAction::Released => "released",
+            SupervisorAction::LogsRotated => "logs rotated",
+        };
+        write!(f, "{action}")
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub action: SupervisorAction,
+    pub detail: String,
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = chrono::DateTime::from_timestamp(self.timestamp as i64, 0)
+            .map_or("-".to_string(), |time| {
+                time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+            });
+        write!(f, "[{time}] {}: {}", self.action, self.detail)
+    }
+}
+
+/// The supervision state of a single service.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NodeSupervision {
+    pub unhealthy_since: Option<u64>,
+    /// When the service was restarted, within the crash loop window.
+    pub restarts: Vec<u64>,
+    pub backoff_secs: u64,
+    pub next_restart_at: Option<u64>,
+    /// Why the service was quarantined, if it is.
+    pub quarantined: Option<String>,
+    pub audit_log: VecDeque<AuditEntry>,
+}
+
+impl NodeSupervision {
+    pub fn evaluate(&mut self, verdict: &Verdict, now: u64, config: &SupervisorConfig) -> Decision {
+        if self.quarantined.is_some() {
+            return Decision::Nothing;
+        }
+        let window = config.crash_loop_window.as_secs();
+        self.restarts
+            .retain(|restart| now.saturating_sub(*restart) < window);
+
+        let reason = match verdict {
+            Verdict::Healthy => {
+                self.unhealthy_since = None;
+                if self.restarts.is_empty() {
+                    self.backoff_secs = 0;
+                    self.next_restart_at = None;
+                }
+                return Decision::Nothing;
+            }
+            Verdict::Crashed => "the process is not running".to_string(),
+            Verdict::Unhealthy(reason) => reason.clone(),
+        };
+
+        let unhealthy_since = *self.unhealthy_since.get_or_insert(now);
+        let grace_period = if *verdict == Verdict::Crashed {
+            config.crashed_grace_period
+        } else {
+            config.unhealthy_grace_period
+        };
+        if now.saturating_sub(unhealthy_since) < grace_period.as_secs() {
+            return Decision::Nothing;
+        }
+        if self.next_restart_at.is_some_and(|at| now < at) {
+            return Decision::Nothing;
+        }
+        if self.restarts.len() >= config.crash_loop_restarts {
+            return Decision::Quarantine(format!(
+                "restarted {} times within {}s, last because {reason}",
+                self.restarts.len(),
+                window
+            ));
+        }
+        Decision::Restart(reason)
+    }
+
+    /// Record a restart attempt, doubling the delay before the next one.
+    pub fn on_restart(&mut self, now: u64, succeeded: bool, config: &SupervisorConfig) {
+        self.restarts.push(now);
+        self.backoff_secs = if self.backoff_secs == 0 {
+            config.initial_backoff.as_secs()
+        } else {
+            (self.backoff_secs * 2).min(config.max_backoff.as_secs())
+        };
+        self.next_restart_at = Some(now + self.backoff_secs);
+        if succeeded {
+            self.unhealthy_since = None;
+        }
+    }
+
+    pub fn record(&mut self, action: SupervisorAction, detail: String, now: u64) {
+        info!("Supervisor action {action}: {detail}");
+        self.audit_log.push_back(AuditEntry {
+            timestamp: now,
+            action,
+            detail,
+        });
+        while self.audit_log.len() > MAX_AUDIT_ENTRIES {
+            self.audit_log.pop_front();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SupervisorState {
+    pub nodes: BTreeMap<String, NodeSupervision>,
+}
+
+impl SupervisorState {
+    /// The state is kept in the same directory as the node registry.
+    pub fn path(node_registry_path: &Path) -> PathBuf {
+        node_registry_path.with_file_name(SUPERVISOR_STATE_FILE_NAME)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|err| eyre!("Could not parse the supervisor state at {path:?}: {err}"))
+    }
+
+    /// Write to a temporary file that replaces the state, so that it is never left partially
+    /// written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)?;
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".tmp");
+        let tmp_path = path.with_file_name(file_name);
+        std::fs::write(&tmp_path, json)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Hand the services back to the user, who started or stopped them by hand: they are released
+    /// from quarantine and their pending restarts are cancelled.
+    pub fn reset(&mut self, service_names: &[String], now: u64) {
+        for service_name in service_names {
+            let Some(supervision) = self.nodes.get_mut(service_name) else {
+                continue;
+            };
+            if let Some(reason) = supervision.quarantined.take() {
+                supervision.record(
+                    SupervisorAction::Released,
+                    format!("{service_name} was released from quarantine ({reason})"),
+                    now,
+                );
+            }
+            supervision.unhealthy_since = None;
+            supervision.restarts.clear();
+            supervision.backoff_secs = 0;
+            supervision.next_restart_at = None;
+        }
+    }
+}
+
+/// An exclusive lock on the services, across processes.
+///
+/// Supervision passes and the `antctl` commands changing the services load the node registry and
+/// supervisor state, and save them whole when they are done. Holding the lock for the whole
+/// operation keeps them from overwriting each other's changes, e.g. restarting a service the user
+/// just stopped, or one an upgrade is replacing.
+#[derive(Debug)]
+pub struct OperationLock {
+    _file: Option<std::fs::File>,
+}
+
+impl OperationLock {
+    /// Wait for the lock kept next to the node registry.
+    ///
+    /// Users without elevated privileges cannot create the lock file for system-wide services,
+    /// they then operate without it.
+    pub async fn acquire(node_registry_path: &Path) -> Result<Self> {
+        let path = node_registry_path.with_file_name(OPERATION_LOCK_FILE_NAME);
+        let file = match std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+        {
+            Ok(file) => file,
+            Err(err) => {
+                warn!("Could not open the operation lock at {path:?}, operating without it: {err}");
+                return Ok(Self { _file: None });
+            }
+        };
+        debug!("Waiting for the operation lock at {path:?}");
+        let file = tokio::task::spawn_blocking(move || file.lock().map(|()| file))
+            .await
+            .map_err(|err| eyre!("Could not wait for the operation lock: {err}"))?
+            .map_err(|err| eyre!("Could not take the operation lock at {path:?}: {err}"))?;
+        Ok(Self { _file: Some(file) })
+    }
+}
+
+/// Reset the supervision of services the user operated on, if the daemon supervises them.
+pub fn reset_supervision(
+    node_registry: &NodeRegistryManager,
+    service_names: &[String],
+) -> Result<()> {
+    let path = SupervisorState::path(&node_registry.save_path);
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut state = SupervisorState::load(&path)?;
+    state.reset(service_names, unix_now());
+    state.save(&path)
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Delete the archived logs in `log_dir`, oldest first, until the directory is within `max_bytes`.
+///
+/// Returns the number of files deleted.
+pub fn rotate_logs(log_dir: &Path, max_bytes: u64) -> Result<usize> {
+    let Ok(entries) = std::fs::read_dir(log_dir) else {
+        return Ok(0);
+    };
+    let mut total_bytes = 0;
+    let mut archived = Vec::new();
+    for entry in entries.flatten() {
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        total_bytes += metadata.len();
+        let protected = PROTECTED_LOG_FILES
+            .iter()
+            .any(|name| entry.file_name() == *name);
+        if !protected {
+            archived.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+    }
+
+    archived.sort();
+    let mut deleted = 0;
+    for (_, len, path) in archived {
+        if total_bytes <= max_bytes {
+            break;
+        }
+        debug!("Deleting the archived log {path:?}");
+        std::fs::remove_file(&path)?;
+        total_bytes -= len;
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+/// Check the health of a service, after the registry was refreshed.
+///
+/// The supervision pass holds the [`OperationLock`], so the service is not in the middle of an
+/// operation of `antctl`. A service that is not running is only restarted once it stayed that way
+/// for the grace period, see [`NodeSupervision::evaluate`].
+pub async fn check_node(
+    node: &NodeServiceData,
+    rpc_client: &dyn RpcActions,
+    config: &SupervisorConfig,
+) -> Verdict {
+    if node.status != ServiceStatus::Running {
+        return Verdict::Crashed;
+    }
+    match tokio::time::timeout(config.rpc_timeout, rpc_client.network_info()).await {
+        Ok(Ok(info)) if info.connected_peers.is_empty() => {
+            Verdict::Unhealthy("it has no connected peers".to_string())
+        }
+        Ok(Ok(_)) => Verdict::Healthy,
+        Ok(Err(err)) => Verdict::Unhealthy(format!("its RPC service failed: {err}")),
+        Err(_) => Verdict::Unhealthy("its RPC service is not responding".to_string()),
+    }
+}
+
+/// Check every supervised service once, restarting or quarantining those that need it.
+///
+/// The services supervised are those that were running, along with those the supervisor failed
+/// to restart.
+pub async fn supervise(
+    node_registry: &NodeRegistryManager,
+    state: &mut SupervisorState,
+    config: &SupervisorConfig,
+) -> Result<()> {
+    let mut supervised = HashSet::new();
+    for node in node_registry.nodes.read().await.iter() {
+        let node = node.read().await;
+        let pending_restart = state
+            .nodes
+            .get(&node.service_name)
+            .is_some_and(|supervision| {
+                supervision.unhealthy_since.is_some() && supervision.quarantined.is_none()
+            });
+        if node.status == ServiceStatus::Running
+            || (pending_restart && node.status != ServiceStatus::Removed)
+        {
+            supervised.insert(node.service_name.clone());
+        }
+    }
+
+    refresh_node_registry(
+        node_registry.clone(),
+        &ServiceController {},
+        false,
+        false,
+        VerbosityLevel::Minimal,
+    )
+    .await?;
+
+    let now = unix_now();
+    let mut service_names = HashSet::new();
+    for node in node_registry.nodes.read().await.iter() {
+        let service_name = node.read().await.service_name.clone();
+        service_names.insert(service_name.clone());
+        let supervision = state.nodes.entry(service_name.clone()).or_default();
+
+        if node.read().await.status != ServiceStatus::Removed {
+            let log_dir = node.read().await.log_dir_path.clone();
+            match rotate_logs(&log_dir, config.max_log_dir_bytes) {
+                Ok(0) => {}
+                Ok(deleted) => supervision.record(
+                    SupervisorAction::LogsRotated,
+                    format!("deleted {deleted} archived log file(s) of {service_name}"),
+                    now,
+                ),
+                Err(err) => warn!("Could not rotate the logs of {service_name}: {err}"),
+            }
+        }
+
+        if !supervised.contains(&service_name) {
+            continue;
+        }
+
+        let mut rpc_client = RpcClient::from_socket_addr(node.read().await.rpc_socket_addr);
+        rpc_client.set_max_attempts(1);
+        let verdict = check_node(&*node.read().await, &rpc_client, config).await;
+        let decision = supervision.evaluate(&verdict, now, config);
+        if decision == Decision::Nothing {
+            continue;
+        }
+
+        let service = NodeService::new(Arc::clone(node), Box::new(rpc_client));
+        // The processes the daemon restarts are its children, like those it starts.
+        let mut service_manager = ServiceManager::new(
+            service,
+            node.read().await.backend.controller_for(true),
+            VerbosityLevel::Minimal,
+        );
+        match decision {
+            Decision::Restart(reason) => {
+                info!("Restarting {service_name}, because {reason}");
+                let result = match service_manager.stop().await {
+                    Ok(()) => service_manager.start().await,
+                    Err(err) => Err(err),
+                };
+                supervision.on_restart(now, result.is_ok(), config);
+                match result {
+                    Ok(()) => supervision.record(
+                        SupervisorAction::Restarted,
+                        format!("{service_name} was restarted because {reason}"),
+                        now,
+                    ),
+                    Err(err) => supervision.record(
+                        SupervisorAction::RestartFailed,
+                        format!("{service_name} could not be restarted: {err}"),
+                        now,
+                    ),
+                }
+            }
+            Decision::Quarantine(reason) => {
+                warn!("Quarantining {service_name}, which was {reason}");
+                if let Err(err) = service_manager.stop().await {
+                    error!("Could not stop the quarantined {service_name}: {err}");
+                }
+                supervision.record(
+                    SupervisorAction::Quarantined,
+                    format!("{service_name} was {reason}"),
+                    now,
+                );
+                supervision.quarantined = Some(reason);
+            }
+            Decision::Nothing => {}
+        }
+    }
+
+    state
+        .nodes
+        .retain(|service_name, _| service_names.contains(service_name));
+    node_registry.save().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SupervisorConfig {
+        SupervisorConfig {
+            unhealthy_grace_period: Duration::from_secs(300),
+            crashed_grace_period: Duration::ZERO,
+            initial_backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(100),
+            crash_loop_restarts: 3,
+            crash_loop_window: Duration::from_secs(3600),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn operation_lock_should_be_exclusive() {
+        let tmp_dir = assert_fs::TempDir::new().unwrap();
+        let node_registry_path = tmp_dir.path().join("node_registry.json");
+
+        let lock = OperationLock::acquire(&node_registry_path).await.unwrap();
+        let waiting = tokio::spawn({
+            let node_registry_path = node_registry_path.clone();
+            async move { OperationLock::acquire(&node_registry_path).await }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!waiting.is_finished());
+
+        drop(lock);
+        tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .expect("the lock should be taken once released")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn state_should_be_saved_without_leaving_a_temporary_file() {
+        let tmp_dir = assert_fs::TempDir::new().unwrap();
+        let path = tmp_dir.path().join(SUPERVISOR_STATE_FILE_NAME);
+        let mut state = SupervisorState::default();
+        state
+            .nodes
+            .insert("antnode1".to_string(), NodeSupervision::default());
+
+        state.save(&path).unwrap();
+        assert!(
+            SupervisorState::load(&path)
+                .unwrap()
+                .nodes
+                .contains_key("antnode1")
+        );
+        assert_eq!(std::fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn unhealthy_node_should_only_be_restarted_after_the_grace_period() {
+        let config = config();
+        let mut supervision = NodeSupervision::default();
+        let unhealthy = Verdict::Unhealthy("it has no connected peers".to_string());
+
+        assert_eq!(
+            supervision.evaluate(&unhealthy, 1000, &config),
+            Decision::Nothing
+        );
+        assert_eq!(
+            supervision.evaluate(&unhealthy, 1299, &config),
+            Decision::Nothing
+        );
+        assert_eq!(
+            supervision.evaluate(&unhealthy, 1300, &config),
+            Decision::Restart("it has no connected peers".to_string())
+        );
+
+        // Recovering resets the grace period.
+        assert_eq!(
+            supervision.evaluate(&Verdict::Healthy, 1301, &config),
+            Decision::Nothing
+        );
+        assert_eq!(
+            supervision.evaluate(&unhealthy, 1302, &config),
+            Decision::Nothing
+        );
+    }
+
+    #[test]
+    fn crashed_node_should_only_be_restarted_after_its_grace_period() {
+        let config = SupervisorConfig {
+            crashed_grace_period: Duration::from_secs(60),
+            ..config()
+        };
+        let mut supervision = NodeSupervision::default();
+
+        // E.g. stopped by an upgrade, which starts it again shortly
+        assert_eq!(
+            supervision.evaluate(&Verdict::Crashed, 1000, &config),
+            Decision::Nothing
+        );
+        assert_eq!(
+            supervision.evaluate(&Verdict::Healthy, 1030, &config),
+            Decision::Nothing
+        );
+
+        assert_eq!(
+            supervision.evaluate(&Verdict::Crashed, 2000, &config),
+            Decision::Nothing
+        );
+        assert_eq!(
+            supervision.evaluate(&Verdict::Crashed, 2060, &config),
+            Decision::Restart("the process is not running".to_string())
+        );
+    }
+
+    #[test]
+    fn restarts_should_back_off_exponentially() {
+        let config = config();
+        let mut supervision = NodeSupervision::default();
+
+        assert!(matches!(
+            supervision.evaluate(&Verdict::Crashed, 0, &config),
+            Decision::Restart(_)
+        ));
+        supervision.on_restart(0, true, &config);
+        assert_eq!(supervision.next_restart_at, Some(30));
+
+        assert_eq!(
+            supervision.evaluate(&Verdict::Crashed, 29, &config),
+            Decision::Nothing
+        );
+        assert!(matches!(
+            supervision.evaluate(&Verdict::Crashed, 30, &config),
+            Decision::Restart(_)
+        ));
+        supervision.on_restart(30, false, &config);
+        assert_eq!(supervision.backoff_secs, 60);
+        supervision.on_restart(90, false, &config);
+        // Capped by the maximum backoff.
+        assert_eq!(supervision.backoff_secs, 100);
+    }
+
+    #[test]
+    fn crash_looping_node_should_be_quarantined() {
+        let config = config();
+        let mut supervision = NodeSupervision::default();
+        let mut now = 0;
+        for _ in 0..config.crash_loop_restarts {
+            assert!(matches!(
+                supervision.evaluate(&Verdict::Crashed, now, &config),
+                Decision::Restart(_)
+            ));
+            supervision.on_restart(now, true, &config);
+            now = supervision.next_restart_at.unwrap();
+        }
+
+        assert!(matches!(
+            supervision.evaluate(&Verdict::Crashed, now, &config),
+            Decision::Quarantine(_)
+        ));
+        supervision.quarantined = Some("crash looping".to_string());
+        assert_eq!(
+            supervision.evaluate(&Verdict::Crashed, now + 10_000, &config),
+            Decision::Nothing
+        );
+
+        let mut state = SupervisorState::default();
+        state.nodes.insert("antnode1".to_string(), supervision);
+        state.reset(&["antnode1".to_string()], now);
+        let supervision = &state.nodes["antnode1"];
+        assert!(supervision.quarantined.is_none());
+        assert!(supervision.restarts.is_empty());
+        assert_eq!(
+            supervision.audit_log.back().map(|entry| &entry.action),
+            Some(&SupervisorAction::Released)
+        );
+    }
+
+    #[test]
+    fn rotate_logs_should_delete_the_oldest_archived_logs() -> Result<()> {
+        let log_dir = assert_fs::TempDir::new()?;
+        let write = |name: &str, len: usize| {
+            std::fs::write(log_dir.path().join(name), vec![b'x'; len])?;
+            // Distinct modification times.
+            std::thread::sleep(Duration::from_millis(20));
+            Ok::<_, std::io::Error>(())
+        };
+        write("antnode.log.20250101T000000", 100)?;
+        write("antnode.log.20250102T000000", 100)?;
+        write("antnode.log.20250103T000000", 100)?;
+        write("antnode.log", 100)?;
+
+        assert_eq!(rotate_logs(log_dir.path(), 250)?, 2);
+        assert!(!log_dir.path().join("antnode.log.20250101T000000").exists());
+        assert!(!log_dir.path().join("antnode.log.20250102T000000").exists());
+        assert!(log_dir.path().join("antnode.log.20250103T000000").exists());
+        assert!(log_dir.path().join("antnode.log").exists());
+
+        assert_eq!(rotate_logs(log_dir.path(), 250)?, 0);
+        Ok(())
+    }
+}
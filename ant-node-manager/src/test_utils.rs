@@ -0,0 +1,62 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Fixtures shared by the unit tests.
+
+use ant_bootstrap::InitialPeersConfig;
+use ant_evm::{EvmNetwork, RewardsAddress};
+use ant_service_management::{
+    NodeServiceData, ServiceStatus, control::ServiceBackend, node::NODE_SERVICE_DATA_SCHEMA_LATEST,
+};
+use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+
+pub const REWARDS_ADDRESS: &str = "0x03B770D9cD32077cC0bF330c13C114a87643B124";
+
+/// A running node service in user mode, with its directories under the default paths and its
+/// node and RPC ports derived from its number.
+pub fn node(number: u16) -> NodeServiceData {
+    let service_name = format!("antnode{number}");
+    NodeServiceData {
+        alpha: false,
+        antnode_path: PathBuf::from("/var/antctl/services")
+            .join(&service_name)
+            .join("antnode"),
+        auto_restart: false,
+        backend: ServiceBackend::Native,
+        connected_peers: None,
+        data_dir_path: PathBuf::from("/var/antctl/services").join(&service_name),
+        evm_network: EvmNetwork::ArbitrumOne,
+        initial_peers_config: InitialPeersConfig::default(),
+        listen_addr: None,
+        log_dir_path: PathBuf::from("/var/log/antnode").join(&service_name),
+        log_format: None,
+        max_archived_log_files: None,
+        max_download_rate: None,
+        max_log_files: None,
+        max_upload_rate: None,
+        metrics_port: None,
+        network_id: None,
+        node_ip: None,
+        node_port: Some(12000 + number),
+        no_upnp: false,
+        number,
+        peer_id: None,
+        pid: None,
+        relay: false,
+        rewards_address: RewardsAddress::from_str(REWARDS_ADDRESS).unwrap(),
+        reward_balance: None,
+        rpc_socket_addr: SocketAddr::from(([127, 0, 0, 1], 13000 + number)),
+        schema_version: NODE_SERVICE_DATA_SCHEMA_LATEST,
+        service_name,
+        status: ServiceStatus::Running,
+        user: None,
+        user_mode: true,
+        version: "0.4.4".to_string(),
+        write_older_cache_files: false,
+    }
+}
@@ -9,10 +9,14 @@
 
 use ant_logging::{Level, LogBuilder};
 use ant_node::NodeEvent;
-use ant_protocol::antnode_proto::{NodeEventsRequest, ant_node_client::AntNodeClient};
+use ant_protocol::antnode_proto::{
+    ChallengePeerRequest, NodeEventsRequest, StorageChallengeStatsRequest,
+    ant_node_client::AntNodeClient,
+};
 use ant_service_management::rpc::{RpcActions, RpcClient};
 use clap::Parser;
 use color_eyre::eyre::Result;
+use libp2p::PeerId;
 use std::{net::SocketAddr, time::Duration};
 use tokio_stream::StreamExt;
 use tonic::Request;
@@ -51,7 +55,24 @@ enum Cmd {
     /// Start listening for node events.
     /// Note this blocks the app and it will print events as they are broadcasted by the node
     #[clap(name = "events")]
-    Events,
+    Events {
+        /// Only listen for the given kinds of events, e.g. `--kind ChunkStored --kind PeerShunned`.
+        ///
+        /// All events are printed if not provided.
+        #[clap(long = "kind")]
+        kinds: Vec<String>,
+    },
+    /// Retrieve the storage challenge results of the peers challenged by the node
+    #[clap(name = "challenges")]
+    Challenges,
+    /// Challenge a peer to prove it holds a record, which the node must hold as well
+    #[clap(name = "challenge")]
+    Challenge {
+        /// The PeerId of the peer to challenge
+        peer_id: PeerId,
+        /// The hex encoded key of the record, as listed by the node's record addresses
+        key: String,
+    },
     /// Restart the node after the specified delay
     #[clap(name = "restart")]
     Restart {
@@ -125,7 +146,9 @@ async fn main() -> Result<()> {
     match opt.cmd {
         Cmd::Info => node_info(addr).await,
         Cmd::Netinfo => network_info(addr).await,
-        Cmd::Events => node_events(addr).await,
+        Cmd::Events { kinds } => node_events(addr, kinds).await,
+        Cmd::Challenges => storage_challenge_stats(addr).await,
+        Cmd::Challenge { peer_id, key } => challenge_peer(addr, peer_id, key).await,
         Cmd::Restart {
             delay_millis,
             retain_peer_id,
@@ -174,11 +197,11 @@ pub async fn network_info(addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
-pub async fn node_events(addr: SocketAddr) -> Result<()> {
+pub async fn node_events(addr: SocketAddr, event_kinds: Vec<String>) -> Result<()> {
     let endpoint = format!("https://{addr}");
     let mut client = AntNodeClient::connect(endpoint).await?;
     let response = client
-        .node_events(Request::new(NodeEventsRequest {}))
+        .node_events(Request::new(NodeEventsRequest { event_kinds }))
         .await?;
 
     println!("Listening to node events... (press Ctrl+C to exit)");
@@ -196,6 +219,42 @@ pub async fn node_events(addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
+pub async fn storage_challenge_stats(addr: SocketAddr) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let mut client = AntNodeClient::connect(endpoint).await?;
+    let response = client
+        .storage_challenge_stats(Request::new(StorageChallengeStatsRequest {}))
+        .await?;
+
+    println!("Storage challenge results of the challenged peers:");
+    for stats in response.into_inner().peers {
+        let peer_id = PeerId::from_bytes(&stats.peer_id)?;
+        println!(
+            "Peer: {peer_id} passed: {}, failed: {}, unresponsive: {}, last outcome: {}",
+            stats.passed, stats.failed, stats.unresponsive, stats.last_outcome
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn challenge_peer(addr: SocketAddr, peer_id: PeerId, key: String) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let mut client = AntNodeClient::connect(endpoint).await?;
+    let response = client
+        .challenge_peer(Request::new(ChallengePeerRequest {
+            peer_id: peer_id.to_bytes(),
+            key: hex::decode(key)?,
+        }))
+        .await?;
+
+    println!(
+        "Peer {peer_id} storage challenge outcome: {}",
+        response.into_inner().outcome
+    );
+    Ok(())
+}
+
 pub async fn record_addresses(addr: SocketAddr) -> Result<()> {
     let endpoint = format!("https://{addr}");
     let client = RpcClient::new(&endpoint);
@@ -24,12 +24,12 @@ use ant_evm::{EvmNetwork, RewardsAddress, get_evm_network};
 use ant_logging::metrics::init_metrics;
 use ant_logging::{Level, LogFormat, LogOutputDest, ReloadHandle};
 use ant_node::utils::{get_antnode_root_dir, get_root_dir_and_keypair};
-use ant_node::{Marker, NodeBuilder, NodeEvent, NodeEventsReceiver};
+use ant_node::{BandwidthLimits, Marker, NodeBuilder, NodeEvent, NodeEventsReceiver};
 use ant_protocol::{
     node_rpc::{NodeCtrl, StopResult},
     version,
 };
-use clap::{Parser, command};
+use clap::Parser;
 use color_eyre::{Result, eyre::eyre};
 use const_hex::traits::FromHex;
 use libp2p::PeerId;
@@ -80,6 +80,17 @@ pub fn parse_log_output(val: &str) -> Result<LogOutputDestArg> {
 #[command(disable_version_flag = true)]
 #[clap(name = "antnode cli", version = env!("CARGO_PKG_VERSION"))]
 struct Opt {
+    /// Specify the path to a static allow/deny list of peers and IP ranges.
+    ///
+    /// Each line is either `allow <target>` or `deny <target>`, where the target is a PeerId, an IP
+    /// or an IP range in CIDR notation. Deny entries always take precedence. Once any allow entry is
+    /// present, only the matching peers are accepted.
+    ///
+    /// The file must exist if provided. Otherwise, the `access_list` file within the node's data
+    /// directory is used, if present.
+    #[clap(long, verbatim_doc_comment)]
+    access_list: Option<PathBuf>,
+
     /// Set to connect to the alpha network.
     #[clap(long)]
     alpha: bool,
@@ -151,6 +162,20 @@ struct Opt {
     #[clap(long, verbatim_doc_comment)]
     max_archived_log_files: Option<usize>,
 
+    /// Specify the maximum download rate of the node, in kilobytes per second.
+    ///
+    /// Replication fetches are scaled down to leave room for the client traffic within this budget.
+    ///
+    /// If not provided, the download rate is unlimited.
+    #[clap(long, verbatim_doc_comment)]
+    max_download_rate: Option<u64>,
+
+    /// Specify the maximum upload rate of the node, in kilobytes per second.
+    ///
+    /// If not provided, the upload rate is unlimited.
+    #[clap(long, verbatim_doc_comment)]
+    max_upload_rate: Option<u64>,
+
     #[cfg(feature = "open-metrics")]
     /// Specify the port for the OpenMetrics server.
     ///
@@ -333,9 +358,15 @@ fn main() -> Result<()> {
             root_dir,
         );
         node_builder.local(opt.peers.local);
+        node_builder.network_context(version::NetworkContext::new(network_id));
         node_builder.no_upnp(opt.no_upnp);
         node_builder.bootstrap_cache(bootstrap_cache);
         node_builder.relay_client(opt.relay);
+        node_builder.access_list(opt.access_list);
+        node_builder.bandwidth_limits(BandwidthLimits {
+            upload: opt.max_upload_rate.map(|rate| rate.saturating_mul(1000)),
+            download: opt.max_download_rate.map(|rate| rate.saturating_mul(1000)),
+        });
         #[cfg(feature = "open-metrics")]
         let mut node_builder = node_builder;
         // if enable flag is provided or only if the port is specified then enable the server by setting Some()
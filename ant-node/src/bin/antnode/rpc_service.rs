@@ -7,23 +7,27 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use ant_logging::ReloadHandle;
-use ant_node::RunningNode;
+use ant_node::{NodeEventFilter, RunningNode};
+use ant_protocol::NetworkAddress;
 use ant_protocol::antnode_proto::{
-    KBucketsRequest, KBucketsResponse, NetworkInfoRequest, NetworkInfoResponse, NodeEvent,
-    NodeEventsRequest, NodeInfoRequest, NodeInfoResponse, RecordAddressesRequest,
-    RecordAddressesResponse, RestartRequest, RestartResponse, StopRequest, StopResponse,
-    UpdateLogLevelRequest, UpdateLogLevelResponse, UpdateRequest, UpdateResponse,
+    ChallengePeerRequest, ChallengePeerResponse, KBucketsRequest, KBucketsResponse,
+    NetworkInfoRequest, NetworkInfoResponse, NodeEvent, NodeEventsRequest, NodeInfoRequest,
+    NodeInfoResponse, RecordAddressesRequest, RecordAddressesResponse, RestartRequest,
+    RestartResponse, StopRequest, StopResponse, StorageChallengeStatsRequest,
+    StorageChallengeStatsResponse, UpdateLogLevelRequest, UpdateLogLevelResponse, UpdateRequest,
+    UpdateResponse,
     ant_node_server::{AntNode, AntNodeServer},
-    k_buckets_response,
+    k_buckets_response, storage_challenge_stats_response,
 };
 use ant_protocol::node_rpc::{NodeCtrl, StopResult};
 use eyre::{ErrReport, Result};
+use libp2p::{PeerId, kad::RecordKey};
 use std::{
     collections::HashMap,
     env,
     net::SocketAddr,
     process,
-    time::{Duration, Instant},
+    time::{Duration, Instant, UNIX_EPOCH},
 };
 use tokio::sync::mpsc::{self, Sender};
 use tokio_stream::wrappers::ReceiverStream;
@@ -111,9 +115,15 @@ impl AntNode for SafeNodeRpcService {
             request.get_ref()
         );
 
+        let filter = NodeEventFilter::from_names(&request.get_ref().event_kinds)
+            .map_err(|err| Status::new(Code::InvalidArgument, err.to_string()))?;
+
         let (client_tx, client_rx) = mpsc::channel(4);
 
-        let mut events_rx = self.running_node.node_events_channel().subscribe();
+        let mut events_rx = self
+            .running_node
+            .node_events_channel()
+            .subscribe_filtered(filter);
         let _handle = tokio::spawn(async move {
             while let Ok(event) = events_rx.recv().await {
                 let event_bytes = match event.to_bytes() {
@@ -193,6 +203,72 @@ impl AntNode for SafeNodeRpcService {
         Ok(Response::new(KBucketsResponse { kbuckets }))
     }
 
+    async fn storage_challenge_stats(
+        &self,
+        request: Request<StorageChallengeStatsRequest>,
+    ) -> Result<Response<StorageChallengeStatsResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let peers = match self.running_node.get_storage_challenge_stats().await {
+            Ok(stats) => stats
+                .into_iter()
+                .map(
+                    |(peer_id, stats)| storage_challenge_stats_response::PeerStats {
+                        peer_id: peer_id.to_bytes(),
+                        passed: stats.passed,
+                        failed: stats.failed,
+                        unresponsive: stats.unresponsive,
+                        last_outcome: stats
+                            .last_outcome
+                            .map(|outcome| outcome.to_string())
+                            .unwrap_or_default(),
+                        last_challenged_secs: stats
+                            .last_challenged
+                            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                            .map(|duration| duration.as_secs())
+                            .unwrap_or_default(),
+                    },
+                )
+                .collect(),
+            Err(err) => {
+                return Err(Status::internal(format!(
+                    "Failed to get storage challenge stats: {err:?}"
+                )));
+            }
+        };
+
+        Ok(Response::new(StorageChallengeStatsResponse { peers }))
+    }
+
+    async fn challenge_peer(
+        &self,
+        request: Request<ChallengePeerRequest>,
+    ) -> Result<Response<ChallengePeerResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let request = request.into_inner();
+        let peer_id = PeerId::from_bytes(&request.peer_id)
+            .map_err(|err| Status::invalid_argument(format!("Invalid peer id: {err:?}")))?;
+        let key = NetworkAddress::from(&RecordKey::new(&request.key));
+
+        match self.running_node.challenge_peer(peer_id, key).await {
+            Ok(outcome) => Ok(Response::new(ChallengePeerResponse {
+                outcome: outcome.to_string(),
+            })),
+            Err(err) => Err(Status::failed_precondition(format!(
+                "Failed to challenge peer {peer_id:?}: {err:?}"
+            ))),
+        }
+    }
+
     async fn stop(&self, request: Request<StopRequest>) -> Result<Response<StopResponse>, Status> {
         debug!(
             "RPC request received at {}: {:?}",
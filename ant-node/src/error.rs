@@ -94,9 +94,18 @@ pub enum Error {
     #[error("Failed to parse NodeEvent")]
     NodeEventParsingFailed,
 
+    #[error("Unknown NodeEvent kind: {0}")]
+    InvalidNodeEventKind(String),
+
     #[error("Failed to obtain node's current port")]
     FailedToGetNodePort,
 
+    #[error("Cannot challenge a peer for the record {0:?} that is not held locally")]
+    ChallengeTargetNotHeld(ant_protocol::NetworkAddress),
+
+    #[error("Cannot challenge the peer {0:?} that is not in the routing table")]
+    ChallengePeerUnknown(PeerId),
+
     // ---------- Quote Errors
     #[error("The content of the payment quote is invalid")]
     InvalidQuoteContent,
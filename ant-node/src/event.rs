@@ -9,9 +9,14 @@
 use crate::error::{Error, Result};
 
 use ant_evm::AttoTokens;
-use ant_protocol::{NetworkAddress, storage::ChunkAddress};
+use ant_protocol::{
+    NetworkAddress,
+    storage::{ChunkAddress, GraphEntryAddress, PointerAddress, ScratchpadAddress},
+};
 use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast;
+use std::{collections::HashSet, str::FromStr};
+use strum::{Display, EnumDiscriminants, EnumIter, EnumString};
+use tokio::sync::broadcast::{self, error::RecvError};
 
 const NODE_EVENT_CHANNEL_SIZE: usize = 500;
 
@@ -35,6 +40,14 @@ impl NodeEventsChannel {
         self.0.subscribe()
     }
 
+    /// Returns a new receiver that only yields the events matching the provided filter.
+    pub fn subscribe_filtered(&self, filter: NodeEventFilter) -> FilteredNodeEventsReceiver {
+        FilteredNodeEventsReceiver {
+            receiver: self.0.subscribe(),
+            filter,
+        }
+    }
+
     // Broadcast a new event, meant to be a helper only used by the ant-node's internals.
     pub(crate) fn broadcast(&self, event: NodeEvent) {
         let event_string = format!("{event:?}");
@@ -52,12 +65,54 @@ impl NodeEventsChannel {
 }
 
 /// Type of events broadcasted by the node to the public API.
-#[derive(Clone, Serialize, custom_debug::Debug, Deserialize)]
+#[derive(Clone, Serialize, custom_debug::Debug, Deserialize, EnumDiscriminants)]
+#[strum_discriminants(
+    name(NodeEventKind),
+    derive(Hash, Display, EnumString, EnumIter, Serialize, Deserialize)
+)]
 pub enum NodeEvent {
     /// The node has been connected to the network
     ConnectedToNetwork,
     /// A Chunk has been stored in local storage
     ChunkStored(ChunkAddress),
+    /// A Scratchpad has been stored in local storage
+    ScratchpadStored(ScratchpadAddress),
+    /// A Pointer has been stored in local storage
+    PointerStored(PointerAddress),
+    /// A GraphEntry has been stored in local storage
+    GraphEntryStored(GraphEntryAddress),
+    /// A record has been fetched from a holder during replication and stored locally
+    ReplicatedRecordFetched {
+        key: NetworkAddress,
+        holder: NetworkAddress,
+    },
+    /// Failed to fetch a record from a holder during replication
+    ReplicationFetchFailed {
+        key: NetworkAddress,
+        holder: NetworkAddress,
+        reason: String,
+    },
+    /// Records have been pruned from local storage to free up space or as out of range
+    RecordsPruned(Vec<NetworkAddress>),
+    /// The payment attached to a record could not be verified
+    PaymentVerificationFailed { key: NetworkAddress, reason: String },
+    /// A store quote has been issued to a client
+    QuoteIssued {
+        key: NetworkAddress,
+        /// DataTypes as represented as its `index`
+        data_type: u32,
+        data_size: usize,
+    },
+    /// A peer has been considered as bad by us and is being shunned
+    PeerShunned {
+        peer: NetworkAddress,
+        reason: String,
+    },
+    /// We have been considered as bad by a peer
+    ShunnedByPeer {
+        peer: NetworkAddress,
+        reason: String,
+    },
     /// A new reward was received
     RewardReceived(AttoTokens, NetworkAddress),
     /// One of the sub event channel closed and unrecoverable.
@@ -76,4 +131,103 @@ impl NodeEvent {
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
         rmp_serde::from_slice(bytes).map_err(|_| Error::NodeEventParsingFailed)
     }
+
+    /// Returns the kind of this event, used for filtering subscriptions
+    pub fn kind(&self) -> NodeEventKind {
+        NodeEventKind::from(self)
+    }
+}
+
+/// Set of `NodeEventKind`s a subscriber is interested in.
+/// An empty filter lets every event through.
+#[derive(Clone, Debug, Default)]
+pub struct NodeEventFilter(HashSet<NodeEventKind>);
+
+impl NodeEventFilter {
+    /// Creates a filter that only matches the provided kinds.
+    /// Providing no kinds matches every event.
+    pub fn new(kinds: impl IntoIterator<Item = NodeEventKind>) -> Self {
+        Self(kinds.into_iter().collect())
+    }
+
+    /// Parses a filter from the names of the event kinds, e.g. `["ChunkStored", "PeerShunned"]`
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Result<Self> {
+        let kinds = names
+            .iter()
+            .map(|name| {
+                NodeEventKind::from_str(name.as_ref())
+                    .map_err(|_| Error::InvalidNodeEventKind(name.as_ref().to_string()))
+            })
+            .collect::<Result<HashSet<_>>>()?;
+        Ok(Self(kinds))
+    }
+
+    /// Returns true if the event passes the filter
+    pub fn matches(&self, event: &NodeEvent) -> bool {
+        self.0.is_empty() || self.0.contains(&event.kind())
+    }
+}
+
+/// Receiver which skips over the events that do not match its `NodeEventFilter`.
+pub struct FilteredNodeEventsReceiver {
+    receiver: NodeEventsReceiver,
+    filter: NodeEventFilter,
+}
+
+impl FilteredNodeEventsReceiver {
+    /// Receives the next event matching the filter.
+    pub async fn recv(&mut self) -> std::result::Result<NodeEvent, RecvError> {
+        loop {
+            let event = self.receiver.recv().await?;
+            if self.filter.matches(&event) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_protocol::storage::ChunkAddress;
+    use xor_name::XorName;
+
+    #[test]
+    fn filter_from_names_should_match_only_listed_kinds() -> eyre::Result<()> {
+        let filter = NodeEventFilter::from_names(&["ChunkStored", "PeerShunned"])?;
+
+        let chunk_stored = NodeEvent::ChunkStored(ChunkAddress::new(XorName::default()));
+        assert!(filter.matches(&chunk_stored));
+        assert!(!filter.matches(&NodeEvent::ConnectedToNetwork));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_filter_should_match_all_events() {
+        let filter = NodeEventFilter::default();
+        assert!(filter.matches(&NodeEvent::ConnectedToNetwork));
+        assert!(filter.matches(&NodeEvent::RecordsPruned(vec![])));
+    }
+
+    #[test]
+    fn filter_from_unknown_name_should_fail() {
+        let result = NodeEventFilter::from_names(&["NotAnEvent"]);
+        assert!(matches!(result, Err(Error::InvalidNodeEventKind(name)) if name == "NotAnEvent"));
+    }
+
+    #[tokio::test]
+    async fn filtered_receiver_should_skip_unmatched_events() -> eyre::Result<()> {
+        let channel = NodeEventsChannel::default();
+        let mut receiver =
+            channel.subscribe_filtered(NodeEventFilter::new([NodeEventKind::RecordsPruned]));
+
+        channel.broadcast(NodeEvent::ConnectedToNetwork);
+        channel.broadcast(NodeEvent::RecordsPruned(vec![]));
+
+        assert!(matches!(
+            receiver.recv().await?,
+            NodeEvent::RecordsPruned(keys) if keys.is_empty()
+        ));
+        Ok(())
+    }
 }
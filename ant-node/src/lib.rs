@@ -46,7 +46,10 @@ pub mod utils;
 
 pub use self::{
     error::{Error, PutValidationError},
-    event::{NodeEvent, NodeEventsChannel, NodeEventsReceiver},
+    event::{
+        FilteredNodeEventsReceiver, NodeEvent, NodeEventFilter, NodeEventKind, NodeEventsChannel,
+        NodeEventsReceiver,
+    },
     log_markers::Marker,
     networking::sort_peers_by_key,
     node::{NodeBuilder, PERIODIC_REPLICATION_INTERVAL_MAX_S},
@@ -55,12 +58,14 @@ pub use self::{
 use crate::error::Result;
 
 use crate::networking::Network;
-pub use crate::networking::SwarmLocalState;
+pub use crate::networking::{
+    BandwidthLimits, ChallengeOutcome, PeerChallengeStats, SwarmLocalState,
+};
 use ant_evm::RewardsAddress;
 use ant_protocol::{NetworkAddress, get_port_from_multiaddr};
 use libp2p::{Multiaddr, PeerId};
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::PathBuf,
 };
 use tokio::sync::watch;
@@ -154,6 +159,23 @@ impl RunningNode {
         Ok(kbuckets)
     }
 
+    /// Returns the storage challenge results of the peers this node has challenged.
+    pub async fn get_storage_challenge_stats(&self) -> Result<HashMap<PeerId, PeerChallengeStats>> {
+        let stats = self.network.get_challenge_stats().await?;
+        Ok(stats)
+    }
+
+    /// Challenges a peer to prove it holds the record at `key`, which this node must hold as well.
+    /// The peer must be in the routing table. Unlike the periodic storage challenges, the outcome
+    /// is only reported and does not count towards shunning the peer.
+    pub async fn challenge_peer(
+        &self,
+        peer_id: PeerId,
+        key: NetworkAddress,
+    ) -> Result<ChallengeOutcome> {
+        node::Node::challenge_peer(&self.network, peer_id, key).await
+    }
+
     /// Returns the node's reward address
     pub fn reward_address(&self) -> &RewardsAddress {
         &self.rewards_address
@@ -0,0 +1,408 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Upload and download rate limiting of the node's traffic.
+//!
+//! Every substream opened over the main transport shares the same token buckets. Once a bucket is
+//! exhausted, reads (resp. writes) are paused until enough tokens have been refilled. Pausing the
+//! reads makes the QUIC flow control push back on the sender.
+//!
+//! The substreams opened by the node itself, mostly replication fetches, are also subject to
+//! buckets of their own holding a share of the limits, so that the substreams opened by peers,
+//! which carry the client traffic, can't be starved by them.
+
+#[cfg(feature = "open-metrics")]
+use crate::networking::MetricsRegistries;
+use futures::{AsyncRead, AsyncWrite, future::FutureExt};
+use libp2p::core::muxing::{StreamMuxer, StreamMuxerEvent};
+#[cfg(feature = "open-metrics")]
+use prometheus_client::metrics::counter::Counter;
+#[cfg(feature = "open-metrics")]
+use std::sync::atomic::AtomicU64;
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, ready},
+    time::{Duration, Instant},
+};
+use tokio::time::Sleep;
+
+/// The bursts allowed above the rate, as a duration of traffic at the full rate.
+const BURST_DURATION: Duration = Duration::from_secs(1);
+
+/// The shortest pause applied to a throttled stream, to avoid waking up too often.
+const MIN_THROTTLE_DELAY: Duration = Duration::from_millis(10);
+
+/// The link is considered congested if the traffic has been throttled within this period.
+const CONGESTION_WINDOW: Duration = Duration::from_secs(10);
+
+/// The share of the limits that the substreams opened by the node can use.
+/// The rest is left to the client GETs/PUTs and quotes, which are opened by peers.
+const OUTBOUND_BANDWIDTH_SHARE_PERCENT: u64 = 70;
+
+/// Upload and download rate limits of a node, in bytes per second. `None` means unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BandwidthLimits {
+    /// The max rate of the outgoing traffic.
+    pub upload: Option<u64>,
+    /// The max rate of the incoming traffic.
+    pub download: Option<u64>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    /// Can go negative, in which case the traffic is paused until it's refilled above zero.
+    tokens: f64,
+    last_refill: Instant,
+    last_throttled: Option<Instant>,
+}
+
+/// A token bucket refilled at `rate` bytes per second.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    state: Mutex<BucketState>,
+    #[cfg(feature = "open-metrics")]
+    throttled_seconds: Counter<f64, AtomicU64>,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        let rate = rate.max(1) as f64;
+        let capacity = rate * BURST_DURATION.as_secs_f64();
+        Self {
+            rate,
+            capacity,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+                last_throttled: None,
+            }),
+            #[cfg(feature = "open-metrics")]
+            throttled_seconds: Counter::default(),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        // The state is always left consistent, hence it's fine to recover from a poisoned lock.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns how long the traffic has to be paused for, `None` if it can flow now.
+    fn delay(&self) -> Option<Duration> {
+        let mut state = self.state();
+        let now = Instant::now();
+        let refill = now.duration_since(state.last_refill).as_secs_f64() * self.rate;
+        state.tokens = (state.tokens + refill).min(self.capacity);
+        state.last_refill = now;
+
+        if state.tokens > 0.0 {
+            return None;
+        }
+
+        state.last_throttled = Some(now);
+        let delay = Duration::from_secs_f64(-state.tokens / self.rate).max(MIN_THROTTLE_DELAY);
+        #[cfg(feature = "open-metrics")]
+        let _ = self.throttled_seconds.inc_by(delay.as_secs_f64());
+        Some(delay)
+    }
+
+    fn consume(&self, bytes: usize) {
+        self.state().tokens -= bytes as f64;
+    }
+
+    fn throttled_within(&self, period: Duration) -> bool {
+        self.state()
+            .last_throttled
+            .is_some_and(|at| at.elapsed() < period)
+    }
+}
+
+/// Shared upload and download limits, applied to every stream of the wrapped muxers.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BandwidthLimiter {
+    limits: BandwidthLimits,
+    upload: Option<Arc<TokenBucket>>,
+    download: Option<Arc<TokenBucket>>,
+    /// Only applied to the substreams opened by the node.
+    outbound_upload: Option<Arc<TokenBucket>>,
+    outbound_download: Option<Arc<TokenBucket>>,
+}
+
+impl BandwidthLimiter {
+    pub(crate) fn new(limits: BandwidthLimits) -> Self {
+        if limits != BandwidthLimits::default() {
+            info!("Limiting the bandwidth of the node to {limits:?} bytes per second");
+        }
+        let bucket = |rate: Option<u64>| rate.map(|rate| Arc::new(TokenBucket::new(rate)));
+        Self {
+            limits,
+            upload: bucket(limits.upload),
+            download: bucket(limits.download),
+            outbound_upload: bucket(limits.upload.map(outbound_share)),
+            outbound_download: bucket(limits.download.map(outbound_share)),
+        }
+    }
+
+    /// The download budget of the substreams opened by the node in bytes per second, `None` if
+    /// unlimited.
+    pub(crate) fn outbound_download_budget(&self) -> Option<u64> {
+        self.limits.download.map(outbound_share)
+    }
+
+    /// Whether any of the traffic has been throttled recently.
+    pub(crate) fn is_congested(&self) -> bool {
+        [
+            &self.upload,
+            &self.download,
+            &self.outbound_upload,
+            &self.outbound_download,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|bucket| bucket.throttled_within(CONGESTION_WINDOW))
+    }
+
+    /// Wrap the muxer so that all of its streams are subject to the limits.
+    pub(crate) fn wrap<M>(&self, muxer: M) -> ThrottledMuxer<M> {
+        ThrottledMuxer {
+            inner: muxer,
+            limiter: self.clone(),
+        }
+    }
+
+    #[cfg(feature = "open-metrics")]
+    pub(crate) fn register_metrics(&self, registries: &mut MetricsRegistries) {
+        let sub_registry = registries
+            .standard_metrics
+            .sub_registry_with_prefix("ant_networking");
+        if let Some(bucket) = &self.upload {
+            sub_registry.register(
+                "upload_throttled_seconds",
+                "The time the outgoing traffic has been paused for, due to the upload limit",
+                bucket.throttled_seconds.clone(),
+            );
+        }
+        if let Some(bucket) = &self.download {
+            sub_registry.register(
+                "download_throttled_seconds",
+                "The time the incoming traffic has been paused for, due to the download limit",
+                bucket.throttled_seconds.clone(),
+            );
+        }
+    }
+}
+
+/// Wraps a [`StreamMuxer`], applying the [`BandwidthLimiter`] to all of its streams.
+#[pin_project::pin_project]
+pub(crate) struct ThrottledMuxer<M> {
+    #[pin]
+    inner: M,
+    limiter: BandwidthLimiter,
+}
+
+impl<M: StreamMuxer> StreamMuxer for ThrottledMuxer<M> {
+    type Substream = ThrottledStream<M::Substream>;
+    type Error = M::Error;
+
+    fn poll_inbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.project();
+        let inner = ready!(this.inner.poll_inbound(cx)?);
+        Poll::Ready(Ok(ThrottledStream::new(inner, this.limiter, false)))
+    }
+
+    fn poll_outbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.project();
+        let inner = ready!(this.inner.poll_outbound(cx)?);
+        Poll::Ready(Ok(ThrottledStream::new(inner, this.limiter, true)))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        self.project().inner.poll(cx)
+    }
+}
+
+/// The share of a limit that the substreams opened by the node can use.
+fn outbound_share(rate: u64) -> u64 {
+    (rate * OUTBOUND_BANDWIDTH_SHARE_PERCENT / 100).max(1)
+}
+
+/// A stream whose reads and writes are paused while any of the respective buckets is exhausted.
+#[pin_project::pin_project]
+pub(crate) struct ThrottledStream<S> {
+    #[pin]
+    inner: S,
+    upload: Vec<Arc<TokenBucket>>,
+    download: Vec<Arc<TokenBucket>>,
+    read_delay: Option<Pin<Box<Sleep>>>,
+    write_delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> ThrottledStream<S> {
+    fn new(inner: S, limiter: &BandwidthLimiter, outbound: bool) -> Self {
+        let buckets = |shared: &Option<Arc<TokenBucket>>, own: &Option<Arc<TokenBucket>>| {
+            shared
+                .iter()
+                .chain(own.iter().filter(|_| outbound))
+                .cloned()
+                .collect()
+        };
+        Self {
+            inner,
+            upload: buckets(&limiter.upload, &limiter.outbound_upload),
+            download: buckets(&limiter.download, &limiter.outbound_download),
+            read_delay: None,
+            write_delay: None,
+        }
+    }
+}
+
+/// Resolves once all the buckets allow the traffic to flow again.
+fn poll_budget(
+    buckets: &[Arc<TokenBucket>],
+    delay: &mut Option<Pin<Box<Sleep>>>,
+    cx: &mut Context<'_>,
+) -> Poll<()> {
+    for bucket in buckets {
+        loop {
+            if let Some(sleep) = delay.as_mut() {
+                ready!(sleep.poll_unpin(cx));
+                *delay = None;
+            }
+            match bucket.delay() {
+                None => break,
+                Some(duration) => *delay = Some(Box::pin(tokio::time::sleep(duration))),
+            }
+        }
+    }
+    Poll::Ready(())
+}
+
+impl<S: AsyncRead> AsyncRead for ThrottledStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        ready!(poll_budget(this.download, this.read_delay, cx));
+        let num_bytes = ready!(this.inner.poll_read(cx, buf))?;
+        for bucket in this.download.iter() {
+            bucket.consume(num_bytes);
+        }
+        Poll::Ready(Ok(num_bytes))
+    }
+}
+
+impl<S: AsyncWrite> AsyncWrite for ThrottledStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        ready!(poll_budget(this.upload, this.write_delay, cx));
+        let num_bytes = ready!(this.inner.poll_write(cx, buf))?;
+        for bucket in this.upload.iter() {
+            bucket.consume(num_bytes);
+        }
+        Poll::Ready(Ok(num_bytes))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_should_throttle_once_exhausted() {
+        let bucket = TokenBucket::new(1000);
+        assert_eq!(bucket.delay(), None);
+        assert!(!bucket.throttled_within(CONGESTION_WINDOW));
+
+        bucket.consume(1500);
+        let delay = bucket.delay().expect("the bucket should be exhausted");
+        assert!(delay >= Duration::from_millis(400) && delay <= Duration::from_millis(500));
+        assert!(bucket.throttled_within(CONGESTION_WINDOW));
+    }
+
+    #[test]
+    fn bucket_should_be_refilled_over_time() {
+        let bucket = TokenBucket::new(1000);
+        bucket.consume(1010);
+        assert!(bucket.delay().is_some());
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(bucket.delay(), None);
+    }
+
+    #[test]
+    fn unlimited_limiter_should_never_be_congested() {
+        let limiter = BandwidthLimiter::new(BandwidthLimits::default());
+        assert!(limiter.upload.is_none() && limiter.download.is_none());
+        assert!(!limiter.is_congested());
+        assert_eq!(limiter.outbound_download_budget(), None);
+    }
+
+    #[test]
+    fn outbound_streams_should_leave_room_for_inbound_ones() {
+        let limiter = BandwidthLimiter::new(BandwidthLimits {
+            upload: None,
+            download: Some(1000),
+        });
+        assert_eq!(limiter.outbound_download_budget(), Some(700));
+
+        let inbound = ThrottledStream::new((), &limiter, false);
+        let outbound = ThrottledStream::new((), &limiter, true);
+        assert!(inbound.upload.is_empty() && outbound.upload.is_empty());
+        assert_eq!(inbound.download.len(), 1);
+        assert_eq!(outbound.download.len(), 2);
+
+        // The outbound streams exhaust their own share, leaving the rest to the inbound ones.
+        for bucket in &outbound.download {
+            bucket.consume(750);
+        }
+        assert!(
+            outbound
+                .download
+                .iter()
+                .any(|bucket| bucket.delay().is_some())
+        );
+        assert!(
+            inbound
+                .download
+                .iter()
+                .all(|bucket| bucket.delay().is_none())
+        );
+    }
+}
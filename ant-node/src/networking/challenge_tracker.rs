@@ -0,0 +1,117 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Book keeping of the storage challenges carried out against our neighbours.
+
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::SystemTime};
+
+/// Upper bound of the peers tracked, the least recently challenged ones are dropped beyond it.
+const MAX_TRACKED_PEERS: usize = 1000;
+
+/// The verdict of challenging a peer to prove it holds the records it's responsible for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, strum::Display)]
+pub enum ChallengeOutcome {
+    /// The peer answered in time with correct proofs.
+    Passed,
+    /// The peer answered with false proofs, too few proofs or too late.
+    Failed,
+    /// The peer didn't answer the challenge.
+    Unresponsive,
+}
+
+/// The storage challenge results of a peer, since the node started.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeerChallengeStats {
+    pub passed: u64,
+    pub failed: u64,
+    pub unresponsive: u64,
+    pub last_outcome: Option<ChallengeOutcome>,
+    pub last_challenged: Option<SystemTime>,
+}
+
+impl PeerChallengeStats {
+    /// The ratio of challenges passed, `None` if the peer has never been challenged.
+    pub fn pass_rate(&self) -> Option<f64> {
+        let total = self.passed + self.failed + self.unresponsive;
+        (total > 0).then(|| self.passed as f64 / total as f64)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ChallengeTracker {
+    peers: HashMap<PeerId, PeerChallengeStats>,
+}
+
+impl ChallengeTracker {
+    pub(crate) fn record(&mut self, peer_id: PeerId, outcome: ChallengeOutcome) {
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= MAX_TRACKED_PEERS {
+            let least_recent = self
+                .peers
+                .iter()
+                .min_by_key(|(_, stats)| stats.last_challenged)
+                .map(|(peer_id, _)| *peer_id);
+            if let Some(least_recent) = least_recent {
+                let _ = self.peers.remove(&least_recent);
+            }
+        }
+
+        let stats = self.peers.entry(peer_id).or_default();
+        match outcome {
+            ChallengeOutcome::Passed => stats.passed += 1,
+            ChallengeOutcome::Failed => stats.failed += 1,
+            ChallengeOutcome::Unresponsive => stats.unresponsive += 1,
+        }
+        stats.last_outcome = Some(outcome);
+        stats.last_challenged = Some(SystemTime::now());
+    }
+
+    pub(crate) fn stats(&self) -> &HashMap<PeerId, PeerChallengeStats> {
+        &self.peers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcomes_should_be_accumulated_per_peer() {
+        let mut tracker = ChallengeTracker::default();
+        let peer_id = PeerId::random();
+        tracker.record(peer_id, ChallengeOutcome::Passed);
+        tracker.record(peer_id, ChallengeOutcome::Passed);
+        tracker.record(peer_id, ChallengeOutcome::Failed);
+        tracker.record(peer_id, ChallengeOutcome::Unresponsive);
+
+        let stats = &tracker.stats()[&peer_id];
+        assert_eq!((stats.passed, stats.failed, stats.unresponsive), (2, 1, 1));
+        assert_eq!(stats.last_outcome, Some(ChallengeOutcome::Unresponsive));
+        assert_eq!(stats.pass_rate(), Some(0.5));
+        assert_eq!(PeerChallengeStats::default().pass_rate(), None);
+    }
+
+    #[test]
+    fn least_recently_challenged_peer_should_be_dropped_when_full() {
+        let mut tracker = ChallengeTracker::default();
+        let oldest = PeerId::random();
+        tracker.record(oldest, ChallengeOutcome::Passed);
+        if let Some(stats) = tracker.peers.get_mut(&oldest) {
+            stats.last_challenged = Some(SystemTime::UNIX_EPOCH);
+        }
+        for _ in 1..MAX_TRACKED_PEERS {
+            tracker.record(PeerId::random(), ChallengeOutcome::Passed);
+        }
+        assert!(tracker.stats().contains_key(&oldest));
+
+        tracker.record(PeerId::random(), ChallengeOutcome::Failed);
+        assert_eq!(tracker.stats().len(), MAX_TRACKED_PEERS);
+        assert!(!tracker.stats().contains_key(&oldest));
+    }
+}
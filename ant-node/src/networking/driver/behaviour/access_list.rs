@@ -0,0 +1,368 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! A static allow/deny list of `PeerId`s and IP ranges, enforced at connection establishment.
+//!
+//! The list is read from a plain text file, one entry per line:
+//!
+//! ```text
+//! # Comments and empty lines are ignored
+//! deny 12D3KooWRBhwfeP2Y4TCx1SM6s9rUoHhR5STiGwxBhgFRcw3UERE
+//! deny 203.0.113.0/24
+//! allow 198.51.100.7
+//! ```
+//!
+//! Deny entries always take precedence. Once any allow entry is present, only the connections
+//! whose `PeerId` or remote IP matches an allow entry are accepted.
+
+use crate::networking::{NetworkError, error::Result, multiaddr_get_ip};
+use ipnet::IpNet;
+use libp2p::{
+    Multiaddr, PeerId,
+    core::{Endpoint, transport::PortUse},
+    swarm::{
+        ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler, THandlerInEvent,
+        THandlerOutEvent, ToSwarm, dummy,
+    },
+};
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    fmt, fs,
+    net::IpAddr,
+    path::Path,
+    str::FromStr,
+    task::{Context, Poll},
+};
+
+/// The `PeerId`s and IP ranges that are explicitly allowed or denied.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct AccessList {
+    allowed_peers: HashSet<PeerId>,
+    denied_peers: HashSet<PeerId>,
+    allowed_ips: Vec<IpNet>,
+    denied_ips: Vec<IpNet>,
+}
+
+impl AccessList {
+    /// Read the access list from the file. A missing file results in an empty list if it is
+    /// `optional`, and in an error otherwise.
+    pub(crate) fn from_file(path: &Path, optional: bool) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                let access_list =
+                    Self::from_str(&content).map_err(|reason| NetworkError::InvalidAccessList {
+                        path: path.to_path_buf(),
+                        reason,
+                    })?;
+                info!("Loaded access list from {path:?}: {access_list}");
+                Ok(access_list)
+            }
+            Err(err) if optional && err.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(err) => Err(NetworkError::Io(err)),
+        }
+    }
+
+    fn has_allow_entries(&self) -> bool {
+        !self.allowed_peers.is_empty() || !self.allowed_ips.is_empty()
+    }
+
+    fn is_ip_denied(&self, ip: &IpAddr) -> bool {
+        self.denied_ips.iter().any(|net| net.contains(ip))
+    }
+
+    /// Check the remote address before the `PeerId` is known.
+    /// Only the deny entries can be enforced at this stage.
+    fn check_addr(&self, addr: &Multiaddr) -> std::result::Result<(), AccessDenied> {
+        match multiaddr_get_ip(addr) {
+            Some(ip) if self.is_ip_denied(&ip) => Err(AccessDenied::DeniedIp(ip)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check an established connection against all the entries.
+    fn check(&self, peer: &PeerId, addr: &Multiaddr) -> std::result::Result<(), AccessDenied> {
+        if self.denied_peers.contains(peer) {
+            return Err(AccessDenied::DeniedPeer(*peer));
+        }
+        let ip = multiaddr_get_ip(addr);
+        if let Some(ip) = ip
+            && self.is_ip_denied(&ip)
+        {
+            return Err(AccessDenied::DeniedIp(ip));
+        }
+
+        if !self.has_allow_entries() || self.allowed_peers.contains(peer) {
+            return Ok(());
+        }
+        match ip {
+            Some(ip) if self.allowed_ips.iter().any(|net| net.contains(&ip)) => Ok(()),
+            _ => Err(AccessDenied::NotAllowed(*peer)),
+        }
+    }
+}
+
+impl FromStr for AccessList {
+    type Err = String;
+
+    fn from_str(content: &str) -> std::result::Result<Self, Self::Err> {
+        let mut access_list = Self::default();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let (Some(action), Some(target), None) = (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(format!(
+                    "line {}: expected `allow <target>` or `deny <target>`",
+                    index + 1
+                ));
+            };
+            let is_allow = match action {
+                "allow" => true,
+                "deny" => false,
+                other => return Err(format!("line {}: unknown action {other:?}", index + 1)),
+            };
+
+            if let Ok(peer_id) = PeerId::from_str(target) {
+                let peers = if is_allow {
+                    &mut access_list.allowed_peers
+                } else {
+                    &mut access_list.denied_peers
+                };
+                let _ = peers.insert(peer_id);
+                continue;
+            }
+
+            let net = IpNet::from_str(target)
+                .or_else(|_| IpAddr::from_str(target).map(IpNet::from))
+                .map_err(|_| {
+                    format!(
+                        "line {}: {target:?} is neither a PeerId, an IP nor an IP range",
+                        index + 1
+                    )
+                })?;
+            if is_allow {
+                access_list.allowed_ips.push(net);
+            } else {
+                access_list.denied_ips.push(net);
+            }
+        }
+
+        Ok(access_list)
+    }
+}
+
+impl fmt::Display for AccessList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allowed peers, {} denied peers, {} allowed IP ranges, {} denied IP ranges",
+            self.allowed_peers.len(),
+            self.denied_peers.len(),
+            self.allowed_ips.len(),
+            self.denied_ips.len()
+        )
+    }
+}
+
+/// The reason a connection has been [`denied`](ConnectionDenied) by the access list.
+#[derive(Debug)]
+pub(crate) enum AccessDenied {
+    DeniedPeer(PeerId),
+    DeniedIp(IpAddr),
+    NotAllowed(PeerId),
+}
+
+impl fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessDenied::DeniedPeer(peer) => write!(f, "peer {peer} is in the deny list"),
+            AccessDenied::DeniedIp(ip) => write!(f, "ip {ip} is in the deny list"),
+            AccessDenied::NotAllowed(peer) => write!(f, "peer {peer} is not in the allow list"),
+        }
+    }
+}
+
+impl std::error::Error for AccessDenied {}
+
+/// Behaviour enforcing the `AccessList` on every connection.
+#[derive(Default)]
+pub(crate) struct Behaviour {
+    access_list: AccessList,
+}
+
+impl Behaviour {
+    pub(crate) fn new(access_list: AccessList) -> Self {
+        Self { access_list }
+    }
+
+    fn enforce(
+        &self,
+        peer: &PeerId,
+        addr: &Multiaddr,
+    ) -> std::result::Result<THandler<Self>, ConnectionDenied> {
+        self.access_list.check(peer, addr).map_err(|reason| {
+            debug!("Denying connection with {peer:?} at {addr:?}: {reason}");
+            ConnectionDenied::new(reason)
+        })?;
+        Ok(dummy::ConnectionHandler)
+    }
+}
+
+impl NetworkBehaviour for Behaviour {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Infallible;
+
+    fn handle_pending_inbound_connection(
+        &mut self,
+        _: ConnectionId,
+        _: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> std::result::Result<(), ConnectionDenied> {
+        self.access_list
+            .check_addr(remote_addr)
+            .map_err(ConnectionDenied::new)
+    }
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: PeerId,
+        _: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> std::result::Result<THandler<Self>, ConnectionDenied> {
+        self.enforce(&peer, remote_addr)
+    }
+
+    fn handle_pending_outbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: Option<PeerId>,
+        _: &[Multiaddr],
+        _: Endpoint,
+    ) -> std::result::Result<Vec<Multiaddr>, ConnectionDenied> {
+        if let Some(peer) = peer
+            && self.access_list.denied_peers.contains(&peer)
+        {
+            return Err(ConnectionDenied::new(AccessDenied::DeniedPeer(peer)));
+        }
+        Ok(vec![])
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: PeerId,
+        addr: &Multiaddr,
+        _: Endpoint,
+        _: PortUse,
+    ) -> std::result::Result<THandler<Self>, ConnectionDenied> {
+        self.enforce(&peer, addr)
+    }
+
+    fn on_swarm_event(&mut self, _event: FromSwarm) {}
+
+    fn on_connection_handler_event(
+        &mut self,
+        _id: PeerId,
+        _: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn poll(&mut self, _: &mut Context<'_>) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(ip: &str) -> Multiaddr {
+        Multiaddr::from_str(&format!("/ip4/{ip}/udp/1234/quic-v1")).expect("valid multiaddr")
+    }
+
+    #[test]
+    fn empty_access_list_should_allow_everything() {
+        let access_list = AccessList::default();
+        assert!(
+            access_list
+                .check(&PeerId::random(), &addr("1.2.3.4"))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn missing_file_should_only_be_accepted_if_optional() {
+        let path = Path::new("/nonexistent/access_list");
+        assert!(AccessList::from_file(path, true).is_ok());
+        assert!(AccessList::from_file(path, false).is_err());
+    }
+
+    #[test]
+    fn deny_entries_should_deny_peers_and_ip_ranges() -> std::result::Result<(), String> {
+        let denied_peer = PeerId::random();
+        let access_list =
+            AccessList::from_str(&format!("# comment\ndeny {denied_peer}\ndeny 10.0.0.0/8\n"))?;
+
+        assert!(matches!(
+            access_list.check(&denied_peer, &addr("1.2.3.4")),
+            Err(AccessDenied::DeniedPeer(_))
+        ));
+        assert!(matches!(
+            access_list.check_addr(&addr("10.1.2.3")),
+            Err(AccessDenied::DeniedIp(_))
+        ));
+        assert!(
+            access_list
+                .check(&PeerId::random(), &addr("1.2.3.4"))
+                .is_ok()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn allow_entries_should_restrict_to_the_allowed() -> std::result::Result<(), String> {
+        let allowed_peer = PeerId::random();
+        let access_list = AccessList::from_str(&format!(
+            "allow {allowed_peer}\nallow 192.168.1.0/24\ndeny 192.168.1.66"
+        ))?;
+
+        assert!(access_list.check(&allowed_peer, &addr("1.2.3.4")).is_ok());
+        assert!(
+            access_list
+                .check(&PeerId::random(), &addr("192.168.1.10"))
+                .is_ok()
+        );
+        assert!(matches!(
+            access_list.check(&PeerId::random(), &addr("1.2.3.4")),
+            Err(AccessDenied::NotAllowed(_))
+        ));
+        assert!(matches!(
+            access_list.check(&PeerId::random(), &addr("192.168.1.66")),
+            Err(AccessDenied::DeniedIp(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_entries_should_be_rejected() {
+        assert!(AccessList::from_str("block 1.2.3.4").is_err());
+        assert!(AccessList::from_str("deny not-a-target").is_err());
+        assert!(AccessList::from_str("deny 1.2.3.4 extra").is_err());
+    }
+}
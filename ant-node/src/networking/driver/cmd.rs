@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::networking::{
-    Addresses, CLOSE_GROUP_SIZE, NetworkEvent, NodeIssue, SwarmLocalState,
+    Addresses, CLOSE_GROUP_SIZE, ChallengeOutcome, NetworkEvent, NodeIssue, SwarmLocalState,
     driver::{PendingGetClosestType, SwarmDriver, event::MsgResponder},
     error::{NetworkError, Result},
     interface::{LocalSwarmCmd, NetworkSwarmCmd, TerminateNodeReason},
@@ -477,9 +477,13 @@ impl SwarmDriver {
                     .store_mut()
                     .cleanup_irrelevant_records();
             }
-            LocalSwarmCmd::NotifyPeerScores { peer_scores } => {
-                cmd_string = "NotifyPeerScores";
-                self.replication_fetcher.add_peer_scores(peer_scores);
+            LocalSwarmCmd::NotifyChallengeOutcomes { outcomes } => {
+                cmd_string = "NotifyChallengeOutcomes";
+                self.record_challenge_outcomes(outcomes);
+            }
+            LocalSwarmCmd::GetChallengeStats { sender } => {
+                cmd_string = "GetChallengeStats";
+                let _ = sender.send(self.challenge_tracker.stats().clone());
             }
             LocalSwarmCmd::AddFreshReplicateRecords { holder, keys } => {
                 cmd_string = "AddFreshReplicateRecords";
@@ -512,6 +516,24 @@ impl SwarmDriver {
         Ok(())
     }
 
+    /// Every challenged peer gets its outcome tracked and scored for the replication. Peers failing
+    /// the challenge, or not answering it, are reported as having failed a chunk proof check.
+    fn record_challenge_outcomes(&mut self, outcomes: Vec<(PeerId, ChallengeOutcome)>) {
+        let mut peer_scores = Vec::with_capacity(outcomes.len());
+        for (peer_id, outcome) in outcomes {
+            self.challenge_tracker.record(peer_id, outcome);
+            #[cfg(feature = "open-metrics")]
+            if let Some(metrics_recorder) = &self.metrics_recorder {
+                metrics_recorder.record_challenge_outcome(outcome);
+            }
+            if outcome != ChallengeOutcome::Passed {
+                self.record_node_issue(peer_id, NodeIssue::FailedChunkProofCheck);
+            }
+            peer_scores.push((peer_id, outcome == ChallengeOutcome::Passed));
+        }
+        self.replication_fetcher.add_peer_scores(peer_scores);
+    }
+
     fn record_node_version(&mut self, peer_id: PeerId, version: String) {
         let _ = self.peers_version.insert(peer_id, version);
     }
@@ -603,6 +625,12 @@ impl SwarmDriver {
                 warn!(
                     "Peer {peer_id:?} is considered as bad due to {bad_behaviour:?}. Informing the peer and adding to blocklist."
                 );
+                self.peer_reputation
+                    .record_shunned(peer_id, bad_behaviour.to_string());
+                self.send_event(NetworkEvent::PeerConsideredAsBad {
+                    bad_peer: peer_id,
+                    bad_behaviour: bad_behaviour.to_string(),
+                });
                 // response handling
                 let (tx, rx) = oneshot::channel();
                 let local_swarm_cmd_sender = self.local_cmd_sender.clone();
@@ -11,7 +11,6 @@ use crate::networking::{
     network::connection_action_logging,
     relay_manager::{RelayManager, is_a_relayed_peer},
 };
-use ant_protocol::version::IDENTIFY_PROTOCOL_STR;
 use itertools::Itertools;
 use libp2p::Multiaddr;
 use libp2p::identify::Info;
@@ -98,11 +97,11 @@ impl SwarmDriver {
             return;
         };
 
-        let our_identify_protocol = IDENTIFY_PROTOCOL_STR.read().expect("IDENTIFY_PROTOCOL_STR has been locked to write. A call to set_network_id performed. This should not happen.").to_string();
+        let our_identify_protocol = self.identify_protocol.clone();
 
         if info.protocol_version != our_identify_protocol {
             warn!(
-                "identify: {peer_id:?} does not have the same protocol. Our IDENTIFY_PROTOCOL_STR: {our_identify_protocol:?}. Their protocol version: {:?}",
+                "identify: {peer_id:?} does not have the same protocol. Our identify protocol: {our_identify_protocol:?}. Their protocol version: {:?}",
                 info.protocol_version
             );
 
@@ -391,6 +390,15 @@ fn does_the_peer_support_dnd(info: &Info) -> bool {
 /// RelayManager::craft_relay_address for relayed addr. This is for non-relayed addr.
 fn craft_valid_multiaddr_without_p2p(addr: &Multiaddr) -> Option<Multiaddr> {
     let mut new_multiaddr = Multiaddr::empty();
+    // The simulated nodes are reached over the memory transport.
+    #[cfg(feature = "simulation")]
+    if let Some(port) = addr.iter().find_map(|p| match p {
+        Protocol::Memory(port) => Some(port),
+        _ => None,
+    }) {
+        new_multiaddr.push(Protocol::Memory(port));
+        return Some(new_multiaddr);
+    }
     let ip = addr.iter().find_map(|p| match p {
         Protocol::Ip4(addr) => Some(addr),
         _ => None,
@@ -72,6 +72,12 @@ impl SwarmDriver {
                             ant_protocol::messages::Query::GetVersion(..) => {
                                 "Request::Query::GetVersion"
                             }
+                            ant_protocol::messages::Query::GetReplicatedRecords { .. } => {
+                                "Request::Query::GetReplicatedRecords"
+                            }
+                            ant_protocol::messages::Query::CheckRecordsExist { .. } => {
+                                "Request::Query::CheckRecordsExist"
+                            }
                         },
                     };
                     connection_action_logging(
@@ -143,6 +149,12 @@ impl SwarmDriver {
                                 self.record_metrics(Marker::FlaggedAsBadNode {
                                     flagged_by: &detected_by,
                                 });
+                                self.peer_reputation
+                                    .record_shunned_by(detected_by, bad_behaviour.clone());
+                                self.send_event(NetworkEvent::FlaggedAsBadNode {
+                                    flagged_by: detected_by,
+                                    bad_behaviour,
+                                });
                             } else {
                                 error!(
                                     "Received a bad_peer notification from {detected_by:?}, targeting {bad_peer:?}, which is not us."
@@ -202,6 +214,12 @@ impl SwarmDriver {
                             ant_protocol::messages::QueryResponse::GetVersion { .. } => {
                                 "Response::Query::GetVersion".to_string()
                             }
+                            ant_protocol::messages::QueryResponse::GetReplicatedRecords {
+                                ..
+                            } => "Response::Query::GetReplicatedRecords".to_string(),
+                            ant_protocol::messages::QueryResponse::CheckRecordsExist { .. } => {
+                                "Response::Query::CheckRecordsExist".to_string()
+                            }
                         },
                     };
                     connection_action_logging(
@@ -17,7 +17,7 @@ use network_discovery::{NETWORK_DISCOVER_INTERVAL, NetworkDiscovery};
 #[cfg(feature = "open-metrics")]
 use crate::networking::metrics::NetworkMetricsRecorder;
 use crate::networking::{
-    Addresses, CLOSE_GROUP_SIZE, NodeIssue, NodeRecordStore,
+    Addresses, BandwidthLimiter, CLOSE_GROUP_SIZE, ChallengeTracker, NodeIssue, NodeRecordStore,
     bootstrap::{INITIAL_BOOTSTRAP_CHECK_INTERVAL, InitialBootstrap, InitialBootstrapTrigger},
     circular_vec::CircularVec,
     driver::kad::U256,
@@ -26,13 +26,14 @@ use crate::networking::{
     log_markers::Marker,
     relay_manager::RelayManager,
     replication_fetcher::ReplicationFetcher,
+    reputation::{PeerReputation, REPUTATION_FLUSH_INTERVAL},
 };
 use ant_bootstrap::BootstrapCacheStore;
 use ant_evm::PaymentQuote;
 use ant_protocol::messages::ConnectionInfo;
 use ant_protocol::{
     NetworkAddress,
-    messages::{Request, Response},
+    messages::{MessageCodec, Response},
 };
 use futures::StreamExt;
 use libp2p::{
@@ -51,6 +52,7 @@ use libp2p::{
 use std::collections::{BTreeMap, HashMap, HashSet, btree_map::Entry};
 use std::time::Instant;
 use tokio::sync::{mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
 use tokio::time::{Duration, Interval, interval};
 use tracing::warn;
 
@@ -70,6 +72,9 @@ pub(crate) const RELAY_MANAGER_RESERVATION_INTERVAL: Duration = Duration::from_s
 /// Interval over which we check if we could dial any peer in the dial queue.
 const DIAL_QUEUE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
 
+/// Interval over which the replication fetches are adapted to the available bandwidth.
+const BANDWIDTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
 /// The ways in which the Get Closest queries are used.
 pub(crate) enum PendingGetClosestType {
     /// The network discovery method is present at the networking layer
@@ -92,6 +97,7 @@ impl From<std::convert::Infallible> for NodeEvent {
 #[derive(NetworkBehaviour)]
 #[behaviour(to_swarm = "NodeEvent")]
 pub(super) struct NodeBehaviour {
+    pub(super) access_list: behaviour::access_list::Behaviour,
     pub(super) blocklist:
         libp2p::allow_block_list::Behaviour<libp2p::allow_block_list::BlockedPeers>,
     pub(super) do_not_disturb: behaviour::do_not_disturb::Behaviour,
@@ -100,7 +106,7 @@ pub(super) struct NodeBehaviour {
     pub(super) relay_client: libp2p::relay::client::Behaviour,
     pub(super) relay_server: Toggle<libp2p::relay::Behaviour>,
     pub(super) kademlia: kad::Behaviour<NodeRecordStore>,
-    pub(super) request_response: request_response::cbor::Behaviour<Request, Response>,
+    pub(super) request_response: request_response::Behaviour<MessageCodec>,
 }
 
 pub(crate) struct SwarmDriver {
@@ -108,6 +114,8 @@ pub(crate) struct SwarmDriver {
     pub(crate) self_peer_id: PeerId,
     /// When true, we don't filter our local addresses
     pub(crate) local: bool,
+    /// The identify protocol of our network. Peers announcing any other are blocked.
+    pub(crate) identify_protocol: String,
     pub(crate) is_relay_client: bool,
     #[cfg(feature = "open-metrics")]
     pub(crate) close_group: Vec<PeerId>,
@@ -122,6 +130,8 @@ pub(crate) struct SwarmDriver {
     pub(crate) connected_relay_clients: HashSet<PeerId>,
     /// The peers that are closer to our PeerId. Includes self.
     pub(crate) replication_fetcher: ReplicationFetcher,
+    /// The storage challenge results of the peers we've challenged.
+    pub(crate) challenge_tracker: ChallengeTracker,
     #[cfg(feature = "open-metrics")]
     pub(crate) metrics_recorder: Option<NetworkMetricsRecorder>,
 
@@ -152,6 +162,10 @@ pub(crate) struct SwarmDriver {
     pub(crate) handled_times: usize,
     pub(crate) hard_disk_write_error: usize,
     pub(crate) bad_nodes: BadNodes,
+    /// Persisted reputation knowledge of peers, complementing `bad_nodes`.
+    pub(crate) peer_reputation: PeerReputation,
+    /// Upload and download limits shared with the transport.
+    pub(crate) bandwidth_limiter: BandwidthLimiter,
     pub(crate) quotes_history: BTreeMap<PeerId, PaymentQuote>,
     pub(crate) replication_targets: BTreeMap<PeerId, Instant>,
     /// when was the last replication event
@@ -179,6 +193,10 @@ impl SwarmDriver {
             Some(interval(INITIAL_BOOTSTRAP_CHECK_INTERVAL));
         let mut dial_queue_check_interval = interval(DIAL_QUEUE_CHECK_INTERVAL);
         let _ = dial_queue_check_interval.tick().await; // first tick completes immediately
+        let mut reputation_flush_interval = interval(REPUTATION_FLUSH_INTERVAL);
+        let _ = reputation_flush_interval.tick().await; // first tick completes immediately
+        let mut bandwidth_check_interval = interval(BANDWIDTH_CHECK_INTERVAL);
+        let _ = bandwidth_check_interval.tick().await; // first tick completes immediately
 
         if let Some(cache) = &self.bootstrap_cache {
             // start the periodic cache sync and flush task
@@ -221,6 +239,9 @@ impl SwarmDriver {
                 result = shutdown_rx.changed() => {
                     if result.is_ok() && *shutdown_rx.borrow() || result.is_err() {
                         info!("Shutdown signal received or sender dropped. Exiting swarm driver loop.");
+                        if let Err(err) = self.flush_peer_reputation().await {
+                            error!("Failed to flush the peer reputation: {err:?}");
+                        }
                         break;
                     }
                 },
@@ -346,10 +367,35 @@ impl SwarmDriver {
                         relay_manager.try_connecting_to_relay(&mut self.swarm, &self.bad_nodes)
                     }
                 },
+                _ = reputation_flush_interval.tick() => {
+                    for peer_id in self.peer_reputation.decay() {
+                        info!("Shunning of {peer_id:?} has decayed, giving it another chance.");
+                        let _ = self.swarm.behaviour_mut().blocklist.unblock_peer(peer_id);
+                        let _ = self.bad_nodes.remove(&peer_id);
+                    }
+                    let _handle = self.flush_peer_reputation();
+                },
+                _ = bandwidth_check_interval.tick() => {
+                    #[cfg_attr(not(feature = "open-metrics"), allow(unused_variables))]
+                    let max_parallel_fetch = self.replication_fetcher.adapt_to_bandwidth(
+                        self.bandwidth_limiter.outbound_download_budget(),
+                        self.bandwidth_limiter.is_congested(),
+                    );
+                    #[cfg(feature = "open-metrics")]
+                    if let Some(metrics_recorder) = &self.metrics_recorder {
+                        let _ = metrics_recorder.max_parallel_replication_fetches.set(max_parallel_fetch as i64);
+                    }
+                },
             }
         }
     }
 
+    /// Persist the reputation knowledge of peers to disk, off the driver loop.
+    fn flush_peer_reputation(&self) -> JoinHandle<()> {
+        self.peer_reputation
+            .flush(&self.bad_nodes, self.replication_fetcher.peer_scores())
+    }
+
     // --------------------------------------------
     // ---------- Crate helpers -------------------
     // --------------------------------------------
@@ -482,7 +528,7 @@ impl SwarmDriver {
                 })
                 .collect();
 
-            stats.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by count in descending order
+            stats.sort_by_key(|s| std::cmp::Reverse(s.1)); // Sort by count in descending order
 
             trace!("SwarmDriver Handling Statistics: {:?}", stats);
             // now we've logged, lets clear the stats from the btreemap
@@ -116,6 +116,9 @@ pub enum NetworkError {
 
     #[error("Error setting up behaviour: {0}")]
     BehaviourErr(String),
+
+    #[error("Invalid access list at {path:?}: {reason}")]
+    InvalidAccessList { path: PathBuf, reason: String },
 }
 
 /// Return a list of error strings for the DialError type
@@ -21,11 +21,12 @@ use libp2p::{
     core::Multiaddr,
     kad::{KBucketDistance as Distance, Record, RecordKey},
 };
+use serde::{Deserialize, Serialize};
 use tokio::sync::oneshot;
 
-use crate::networking::Addresses;
+use crate::networking::{Addresses, ChallengeOutcome, PeerChallengeStats};
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub(crate) enum NodeIssue {
     /// Some connections might be considered to be critical and should be tracked.
     ConnectionIssue,
@@ -135,9 +136,13 @@ pub(crate) enum LocalSwarmCmd {
     TriggerIntervalReplication,
     /// Triggers unrelevant record cleanup
     TriggerIrrelevantRecordCleanup,
-    /// Send peer scores (collected from storage challenge) to replication_fetcher
-    NotifyPeerScores {
-        peer_scores: Vec<(PeerId, bool)>,
+    /// Record the outcomes of a storage challenge, feeding the peer scores and the bad nodes
+    NotifyChallengeOutcomes {
+        outcomes: Vec<(PeerId, ChallengeOutcome)>,
+    },
+    /// Get the storage challenge results of the challenged peers
+    GetChallengeStats {
+        sender: oneshot::Sender<HashMap<PeerId, PeerChallengeStats>>,
     },
     /// Add fresh replicate records into replication_fetcher
     AddFreshReplicateRecords {
@@ -262,8 +267,11 @@ impl Debug for LocalSwarmCmd {
             LocalSwarmCmd::TriggerIrrelevantRecordCleanup => {
                 write!(f, "LocalSwarmCmd::TriggerUnrelevantRecordCleanup")
             }
-            LocalSwarmCmd::NotifyPeerScores { peer_scores } => {
-                write!(f, "LocalSwarmCmd::NotifyPeerScores({peer_scores:?})")
+            LocalSwarmCmd::NotifyChallengeOutcomes { outcomes } => {
+                write!(f, "LocalSwarmCmd::NotifyChallengeOutcomes({outcomes:?})")
+            }
+            LocalSwarmCmd::GetChallengeStats { .. } => {
+                write!(f, "LocalSwarmCmd::GetChallengeStats")
             }
             LocalSwarmCmd::AddFreshReplicateRecords { holder, keys } => {
                 write!(
@@ -67,6 +67,18 @@ pub(crate) enum NetworkEvent {
     },
     /// Peers of picked bucket for version query.
     PeersForVersionQuery(Vec<(PeerId, Addresses)>),
+    /// Records that have been pruned from the local storage.
+    RecordsPruned(Vec<NetworkAddress>),
+    /// A peer has been considered as bad by us, due to the bad behaviour.
+    PeerConsideredAsBad {
+        bad_peer: PeerId,
+        bad_behaviour: String,
+    },
+    /// We have been considered as bad by a peer, due to the bad behaviour.
+    FlaggedAsBadNode {
+        flagged_by: PeerId,
+        bad_behaviour: String,
+    },
 }
 
 /// Terminate node for the following reason
@@ -151,6 +163,27 @@ impl std::fmt::Debug for NetworkEvent {
                         .collect::<Vec<&PeerId>>()
                 )
             }
+            NetworkEvent::RecordsPruned(keys) => {
+                write!(f, "NetworkEvent::RecordsPruned({} keys)", keys.len())
+            }
+            NetworkEvent::PeerConsideredAsBad {
+                bad_peer,
+                bad_behaviour,
+            } => {
+                write!(
+                    f,
+                    "NetworkEvent::PeerConsideredAsBad({bad_peer:?}, {bad_behaviour:?})"
+                )
+            }
+            NetworkEvent::FlaggedAsBadNode {
+                flagged_by,
+                bad_behaviour,
+            } => {
+                write!(
+                    f,
+                    "NetworkEvent::FlaggedAsBadNode({flagged_by:?}, {bad_behaviour:?})"
+                )
+            }
         }
     }
 }
@@ -12,8 +12,8 @@ mod relay_client;
 pub(super) mod service;
 mod upnp;
 
-use crate::networking::MetricsRegistries;
 use crate::networking::log_markers::Marker;
+use crate::networking::{ChallengeOutcome, MetricsRegistries};
 use bad_node::{BadNodeMetrics, BadNodeMetricsMsg, TimeFrame};
 use libp2p::{
     PeerId,
@@ -38,6 +38,11 @@ pub(crate) struct VersionLabels {
     version: String,
 }
 
+#[derive(Clone, Hash, PartialEq, Eq, Debug, EncodeLabelSet)]
+pub(crate) struct ChallengeOutcomeLabels {
+    outcome: String,
+}
+
 /// The shared recorders that are used to record metrics.
 pub(crate) struct NetworkMetricsRecorder {
     // Records libp2p related metrics
@@ -56,8 +61,10 @@ pub(crate) struct NetworkMetricsRecorder {
     pub(crate) peers_in_routing_table: Gauge,
     pub(crate) relay_peers_in_routing_table: Gauge,
     pub(crate) records_stored: Gauge,
+    pub(crate) max_parallel_replication_fetches: Gauge,
     pub(crate) relay_reservation_health: Gauge<f64, AtomicU64>,
     pub(crate) node_versions: Family<VersionLabels, Gauge>,
+    storage_challenge_outcomes: Family<ChallengeOutcomeLabels, Counter>,
 
     // quoting metrics
     relevant_records: Gauge,
@@ -98,6 +105,12 @@ impl NetworkMetricsRecorder {
             "The number of records stored locally",
             records_stored.clone(),
         );
+        let max_parallel_replication_fetches = Gauge::default();
+        sub_registry.register(
+            "max_parallel_replication_fetches",
+            "The current limit of parallel replication fetches, adapted to the available bandwidth",
+            max_parallel_replication_fetches.clone(),
+        );
         let relay_reservation_health = Gauge::<f64, AtomicU64>::default();
         sub_registry.register(
             "relay_reservation_health",
@@ -225,6 +238,13 @@ impl NetworkMetricsRecorder {
             live_time.clone(),
         );
 
+        let storage_challenge_outcomes = Family::default();
+        sub_registry.register(
+            "storage_challenge_outcomes",
+            "The outcomes of the storage challenges carried out against our neighbours",
+            storage_challenge_outcomes.clone(),
+        );
+
         let shunned_by_close_group = Gauge::default();
         sub_registry.register(
             "shunned_by_close_group",
@@ -262,6 +282,7 @@ impl NetworkMetricsRecorder {
             relay_client_events,
 
             records_stored,
+            max_parallel_replication_fetches,
             estimated_network_size,
             relay_peers_percentage,
             connected_peers,
@@ -275,6 +296,7 @@ impl NetworkMetricsRecorder {
             received_payment_count,
             live_time,
             node_versions,
+            storage_challenge_outcomes,
 
             bad_peers_count,
             shunned_count_across_time_frames,
@@ -377,6 +399,15 @@ impl NetworkMetricsRecorder {
         });
     }
 
+    pub(crate) fn record_challenge_outcome(&self, outcome: ChallengeOutcome) {
+        let _ = self
+            .storage_challenge_outcomes
+            .get_or_create(&ChallengeOutcomeLabels {
+                outcome: outcome.to_string(),
+            })
+            .inc();
+    }
+
     pub(crate) fn update_node_versions(&self, versions: &HashMap<PeerId, String>) {
         // First, count occurrences of each version
         let mut version_counts: HashMap<String, u64> = HashMap::new();
@@ -9,7 +9,9 @@
 #![allow(clippy::large_enum_variant)]
 #![allow(clippy::result_large_err)]
 
+mod bandwidth;
 mod bootstrap;
+mod challenge_tracker;
 mod circular_vec;
 mod driver;
 mod error;
@@ -22,16 +24,27 @@ mod network;
 mod record_store;
 mod relay_manager;
 mod replication_fetcher;
+mod reputation;
+#[cfg(feature = "simulation")]
+mod simulation;
 mod transport;
 
 // re-export arch dependent deps for use in the crate, or above
-pub use self::interface::SwarmLocalState;
+#[cfg(feature = "simulation")]
+pub use self::simulation::NetworkConditions;
 pub(crate) use self::{
+    bandwidth::BandwidthLimiter,
+    challenge_tracker::ChallengeTracker,
     error::NetworkError,
     interface::{NetworkEvent, NodeIssue},
     network::{Network, NetworkConfig},
     record_store::NodeRecordStore,
 };
+pub use self::{
+    bandwidth::BandwidthLimits,
+    challenge_tracker::{ChallengeOutcome, PeerChallengeStats},
+    interface::SwarmLocalState,
+};
 
 #[cfg(feature = "open-metrics")]
 pub(crate) use metrics::service::MetricsRegistries;
@@ -74,7 +87,7 @@ pub fn sort_peers_by_key<T>(
     }
 
     // Sort the vector of tuples by the distance.
-    peer_distances.sort_by(|a, b| a.2.cmp(&b.2));
+    peer_distances.sort_by_key(|a| a.2);
 
     // Collect the sorted peers into a new vector.
     let sorted_peers: Vec<(PeerId, Addresses)> = peer_distances
@@ -9,15 +9,18 @@
 use ant_protocol::constants::{KAD_STREAM_PROTOCOL_ID, MAX_PACKET_SIZE, REPLICATION_FACTOR};
 
 use crate::networking::{
-    CLOSE_GROUP_SIZE, NetworkEvent,
+    BandwidthLimiter, BandwidthLimits, CLOSE_GROUP_SIZE, NetworkEvent,
     bootstrap::{InitialBootstrap, InitialBootstrapTrigger},
     circular_vec::CircularVec,
-    driver::{NodeBehaviour, SwarmDriver, network_discovery::NetworkDiscovery},
+    driver::{
+        NodeBehaviour, SwarmDriver, behaviour::access_list, network_discovery::NetworkDiscovery,
+    },
     error::{NetworkError, Result},
     external_address::ExternalAddressManager,
     record_store::{NodeRecordStore, NodeRecordStoreConfig},
     relay_manager::RelayManager,
     replication_fetcher::ReplicationFetcher,
+    reputation::PeerReputation,
     transport,
 };
 #[cfg(feature = "open-metrics")]
@@ -27,8 +30,8 @@ use crate::networking::{
 use ant_bootstrap::BootstrapCacheStore;
 use ant_protocol::{
     NetworkAddress, PrettyPrintKBucketKey,
-    messages::{Request, Response},
-    version::{IDENTIFY_PROTOCOL_STR, REQ_RESPONSE_VERSION_STR, get_network_id_str},
+    messages::{CompressionMetrics, MessageCodec},
+    version::NetworkContext,
 };
 use futures::future::Either;
 use libp2p::Transport as _;
@@ -37,9 +40,7 @@ use libp2p::{
     identity::Keypair,
     kad,
     multiaddr::Protocol,
-    request_response::{
-        self, Config as RequestResponseConfig, ProtocolSupport, cbor::codec::Codec as CborCodec,
-    },
+    request_response::{self, Config as RequestResponseConfig, ProtocolSupport},
     swarm::{StreamProtocol, Swarm},
 };
 use libp2p::{core::muxing::StreamMuxerBox, relay};
@@ -70,6 +71,9 @@ const NETWORKING_CHANNEL_SIZE: usize = 10_000;
 /// Time before a Kad query times out if no response is received
 const KAD_QUERY_TIMEOUT_S: Duration = Duration::from_secs(10);
 
+/// Name of the access list file looked up in the root dir, if no custom path is provided.
+const DEFAULT_ACCESS_LIST_FILENAME: &str = "access_list";
+
 #[derive(Debug)]
 pub(crate) struct NetworkConfig {
     pub keypair: Keypair,
@@ -82,6 +86,15 @@ pub(crate) struct NetworkConfig {
     pub no_upnp: bool,
     pub relay_client: bool,
     pub custom_request_timeout: Option<Duration>,
+    /// Path to the static allow/deny list of peers and IP ranges.
+    /// Defaults to the `access_list` file within the root dir.
+    pub access_list_path: Option<PathBuf>,
+    pub bandwidth_limits: BandwidthLimits,
+    /// The network the node takes part in.
+    pub network_context: NetworkContext,
+    /// Run over the in-memory transport of the network simulator instead of QUIC.
+    #[cfg(feature = "simulation")]
+    pub simulated_network: Option<crate::networking::NetworkConditions>,
     #[cfg(feature = "open-metrics")]
     pub metrics_registries: MetricsRegistries,
     #[cfg(feature = "open-metrics")]
@@ -140,7 +153,7 @@ pub(super) fn init_driver(
         check_and_wipe_storage_dir_if_necessary(
             config.root_dir.clone(),
             storage_dir_path.clone(),
-            get_network_id_str(),
+            config.network_context.network_id().to_string(),
         )?;
 
         // Configures the disk_store to store records under the provided path and increase the max record size
@@ -168,11 +181,36 @@ pub(super) fn init_driver(
         }
     };
 
+    // Only the default file may be missing, so a mistyped path does not disable the list
+    let access_list = match &config.access_list_path {
+        Some(path) => access_list::AccessList::from_file(path, false)?,
+        None => access_list::AccessList::from_file(
+            &config.root_dir.join(DEFAULT_ACCESS_LIST_FILENAME),
+            true,
+        )?,
+    };
+
     // Listen on the provided address
     let listen_socket_addr = config.listen_addr;
+    #[cfg(feature = "simulation")]
+    let is_simulated = config.simulated_network.is_some();
+
+    let (events_receiver, mut swarm_driver) = init_swarm_driver(
+        kad_cfg,
+        store_cfg,
+        ProtocolSupport::Full,
+        access_list,
+        config,
+    );
 
-    let (events_receiver, mut swarm_driver) =
-        init_swarm_driver(kad_cfg, store_cfg, ProtocolSupport::Full, config);
+    // Simulated nodes listen on a random free memory port
+    #[cfg(feature = "simulation")]
+    if is_simulated {
+        swarm_driver
+            .listen_on(Multiaddr::empty().with(Protocol::Memory(0)))
+            .expect("Multiaddr should be supported by our configured transports");
+        return Ok((swarm_driver, events_receiver));
+    }
 
     // Listen on QUIC
     let addr_quic = Multiaddr::from(listen_socket_addr.ip())
@@ -190,12 +228,10 @@ fn init_swarm_driver(
     kad_cfg: kad::Config,
     record_store_cfg: NodeRecordStoreConfig,
     req_res_protocol: ProtocolSupport,
+    access_list: access_list::AccessList,
     config: NetworkConfig,
 ) -> (mpsc::Receiver<NetworkEvent>, SwarmDriver) {
-    let identify_protocol_str = IDENTIFY_PROTOCOL_STR
-        .read()
-        .expect("Failed to obtain read lock for IDENTIFY_PROTOCOL_STR")
-        .clone();
+    let identify_protocol_str = config.network_context.identify_protocol();
 
     let peer_id = PeerId::from(config.keypair.public());
     // vdash metric (if modified please notify at https://github.com/happybeing/vdash/issues):
@@ -212,10 +248,24 @@ fn init_swarm_driver(
     let mut metrics_registries = config.metrics_registries;
 
     // ==== Transport ====
+    let bandwidth_limiter = BandwidthLimiter::new(config.bandwidth_limits);
     #[cfg(feature = "open-metrics")]
-    let main_transport = transport::build_transport(&config.keypair, &mut metrics_registries);
+    let main_transport = transport::build_transport(
+        &config.keypair,
+        bandwidth_limiter.clone(),
+        &mut metrics_registries,
+    );
     #[cfg(not(feature = "open-metrics"))]
-    let main_transport = transport::build_transport(&config.keypair);
+    let main_transport = transport::build_transport(&config.keypair, bandwidth_limiter.clone());
+    #[cfg(feature = "simulation")]
+    let main_transport = match config.simulated_network.clone() {
+        Some(conditions) => crate::networking::simulation::build_simulated_transport(
+            &config.keypair,
+            conditions,
+            bandwidth_limiter.clone(),
+        ),
+        None => main_transport,
+    };
     let transport = if !config.local {
         debug!("Preventing non-global dials");
         // Wrap upper in a transport that prevents dialing local addresses.
@@ -242,9 +292,16 @@ fn init_swarm_driver(
         })
         .boxed();
 
+    let compression_metrics = CompressionMetrics::default();
     #[cfg(feature = "open-metrics")]
     let metrics_recorder = if let Some(port) = config.metrics_server_port {
         let metrics_recorder = NetworkMetricsRecorder::new(&mut metrics_registries);
+        bandwidth_limiter.register_metrics(&mut metrics_registries);
+        compression_metrics.register(
+            metrics_registries
+                .standard_metrics
+                .sub_registry_with_prefix("ant_networking"),
+        );
         let metadata_sub_reg = metrics_registries
             .metadata
             .sub_registry_with_prefix("ant_networking");
@@ -276,20 +333,30 @@ fn init_swarm_driver(
                 .custom_request_timeout
                 .unwrap_or(REQUEST_TIMEOUT_DEFAULT_S),
         );
-        let req_res_version_str = REQ_RESPONSE_VERSION_STR
-            .read()
-            .expect("Failed to obtain read lock for REQ_RESPONSE_VERSION_STR")
-            .clone();
-        info!("Building request response with {req_res_version_str:?}");
-        let codec = CborCodec::<Request, Response>::default()
-            .set_request_size_maximum(2 * MAX_PACKET_SIZE as u64);
+        let req_res_version_str = config.network_context.req_response_protocol();
+        let compressed_req_res_version_str =
+            config.network_context.compressed_req_response_protocol();
+        info!(
+            "Building request response with {compressed_req_res_version_str:?} and {req_res_version_str:?}"
+        );
+        let codec = MessageCodec::default()
+            .set_request_size_maximum(2 * MAX_PACKET_SIZE as u64)
+            .with_metrics(compression_metrics);
+        // The compressed protocol comes first so that it is preferred by peers supporting it
         request_response::Behaviour::with_codec(
             codec,
-            [(
-                StreamProtocol::try_from_owned(req_res_version_str)
-                    .expect("StreamProtocol should start with a /"),
-                req_res_protocol,
-            )],
+            [
+                (
+                    StreamProtocol::try_from_owned(compressed_req_res_version_str)
+                        .expect("StreamProtocol should start with a /"),
+                    req_res_protocol.clone(),
+                ),
+                (
+                    StreamProtocol::try_from_owned(req_res_version_str)
+                        .expect("StreamProtocol should start with a /"),
+                    req_res_protocol,
+                ),
+            ],
             cfg,
         )
     };
@@ -317,8 +384,9 @@ fn init_swarm_driver(
         kad::Behaviour::with_config(peer_id, store, kad_cfg)
     };
 
-    let agent_version =
-        ant_protocol::version::construct_node_user_agent(env!("CARGO_PKG_VERSION").to_string());
+    let agent_version = config
+        .network_context
+        .node_user_agent(env!("CARGO_PKG_VERSION").to_string());
 
     // Identify Behaviour
     info!(
@@ -357,8 +425,16 @@ fn init_swarm_driver(
     }
     .into();
 
+    // Restore the reputation knowledge from the previous run, keep shunning known bad peers.
+    let (peer_reputation, bad_nodes, peer_scores) = PeerReputation::load(&config.root_dir);
+    let mut blocklist = libp2p::allow_block_list::Behaviour::default();
+    for peer_id in peer_reputation.shunned_peers() {
+        let _ = blocklist.block_peer(*peer_id);
+    }
+
     let behaviour = NodeBehaviour {
-        blocklist: libp2p::allow_block_list::Behaviour::default(),
+        access_list: access_list::Behaviour::new(access_list),
+        blocklist,
         do_not_disturb: crate::networking::driver::behaviour::do_not_disturb::Behaviour::default(),
         // `Relay client Behaviour` is enabled for all nodes. This is required for normal nodes to connect to relay
         // clients.
@@ -375,7 +451,8 @@ fn init_swarm_driver(
 
     let swarm = Swarm::new(transport, behaviour, peer_id, swarm_config);
 
-    let replication_fetcher = ReplicationFetcher::new(peer_id, network_event_sender.clone());
+    let mut replication_fetcher = ReplicationFetcher::new(peer_id, network_event_sender.clone());
+    replication_fetcher.restore_peer_scores(peer_scores);
 
     // Enable relay manager to allow the node to act as a relay client and connect via relay servers to the network
     let relay_manager = if config.relay_client {
@@ -405,6 +482,7 @@ fn init_swarm_driver(
         swarm,
         self_peer_id: peer_id,
         local: config.local,
+        identify_protocol: config.network_context.identify_protocol(),
         is_relay_client: config.relay_client,
         #[cfg(feature = "open-metrics")]
         close_group: Vec::with_capacity(CLOSE_GROUP_SIZE),
@@ -416,6 +494,7 @@ fn init_swarm_driver(
         connected_relay_clients: Default::default(),
         external_address_manager,
         replication_fetcher,
+        challenge_tracker: Default::default(),
         #[cfg(feature = "open-metrics")]
         metrics_recorder,
         // kept here to ensure we can push messages to the channel
@@ -436,7 +515,9 @@ fn init_swarm_driver(
         handling_statistics: Default::default(),
         handled_times: 0,
         hard_disk_write_error: 0,
-        bad_nodes: Default::default(),
+        bad_nodes,
+        peer_reputation,
+        bandwidth_limiter,
         quotes_history: Default::default(),
         replication_targets: Default::default(),
         last_replication: None,
@@ -22,7 +22,9 @@ use tokio::sync::{mpsc, oneshot};
 use super::driver::event::MsgResponder;
 use super::error::{NetworkError, Result};
 use super::interface::{LocalSwarmCmd, NetworkSwarmCmd};
-use super::{Addresses, NetworkEvent, NodeIssue, SwarmLocalState};
+use super::{
+    Addresses, ChallengeOutcome, NetworkEvent, NodeIssue, PeerChallengeStats, SwarmLocalState,
+};
 
 mod init;
 
@@ -300,8 +302,17 @@ impl Network {
         self.send_local_swarm_cmd(LocalSwarmCmd::TriggerIrrelevantRecordCleanup)
     }
 
-    pub(crate) fn notify_peer_scores(&self, peer_scores: Vec<(PeerId, bool)>) {
-        self.send_local_swarm_cmd(LocalSwarmCmd::NotifyPeerScores { peer_scores })
+    pub(crate) fn notify_challenge_outcomes(&self, outcomes: Vec<(PeerId, ChallengeOutcome)>) {
+        self.send_local_swarm_cmd(LocalSwarmCmd::NotifyChallengeOutcomes { outcomes })
+    }
+
+    /// Returns the storage challenge results of the challenged peers.
+    pub(crate) async fn get_challenge_stats(&self) -> Result<HashMap<PeerId, PeerChallengeStats>> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_local_swarm_cmd(LocalSwarmCmd::GetChallengeStats { sender });
+        receiver
+            .await
+            .map_err(|_e| NetworkError::InternalMsgChannelDropped)
     }
 
     pub(crate) fn notify_node_version(&self, peer: PeerId, version: String) {
@@ -554,6 +554,7 @@ impl NodeRecordStore {
                 PrettyPrintRecordKey::from(&farthest_record)
             );
             self.remove(&farthest_record);
+            self.notify_records_pruned(vec![NetworkAddress::from(&farthest_record)]);
         }
 
         Ok(())
@@ -587,14 +588,31 @@ impl NodeRecordStore {
         let keys_to_remove_len = keys_to_remove.len();
 
         // Remove collected keys
+        let mut pruned_keys = Vec::with_capacity(keys_to_remove_len);
         for key in keys_to_remove {
             self.remove(&key);
+            pruned_keys.push(NetworkAddress::from(&key));
         }
 
         info!(
             "Cleaned up {} unrelevant records, among the original {accumulated_records} accumulated_records",
             keys_to_remove_len
         );
+
+        if !pruned_keys.is_empty() {
+            self.notify_records_pruned(pruned_keys);
+        }
+    }
+
+    /// Notify the upper layers about the records removed from the local storage.
+    fn notify_records_pruned(&self, keys: Vec<NetworkAddress>) {
+        let event_sender = self.network_event_sender.clone();
+        // push the event off thread so as to be non-blocking
+        let _handle = spawn(async move {
+            if let Err(error) = event_sender.send(NetworkEvent::RecordsPruned(keys)).await {
+                error!("SwarmDriver failed to send event: {}", error);
+            }
+        });
     }
 }
 
@@ -1514,8 +1532,7 @@ mod tests {
         // now all failed records should be farther than the farthest stored record
         let mut sorted_stored_data = stored_data_at_end.iter().collect_vec();
 
-        sorted_stored_data
-            .sort_by(|(a, _), (b, _)| self_address.distance(a).cmp(&self_address.distance(b)));
+        sorted_stored_data.sort_by_key(|(a, _)| self_address.distance(a));
 
         // next assert that all records stored are closer than the next closest of the failed records
         if let Some((most_distant_data, _)) = sorted_stored_data.last() {
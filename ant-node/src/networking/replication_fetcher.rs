@@ -10,7 +10,7 @@
 use crate::networking::interface::NetworkEvent;
 use ant_protocol::{
     NetworkAddress, PrettyPrintRecordKey,
-    constants::CLOSE_GROUP_SIZE,
+    constants::{CLOSE_GROUP_SIZE, MAX_RECORD_SIZE},
     storage::{DataTypes, ValidationType},
 };
 use libp2p::{
@@ -22,6 +22,9 @@ use std::time::Instant;
 use tokio::spawn;
 use tokio::{sync::mpsc, time::Duration};
 
+/// Storage challenge scores of peers, along with when they were last seen.
+pub(crate) type PeerScores = HashMap<PeerId, (VecDeque<bool>, Instant)>;
+
 // Max parallel fetches that can be undertaken at the same time.
 const MAX_PARALLEL_FETCH: usize = 5;
 
@@ -45,6 +48,8 @@ pub(crate) struct ReplicationFetcher {
     to_be_fetched: HashMap<(RecordKey, ValidationType, PeerId), ReplicationTimeout>,
     // Avoid fetching same chunk from different nodes AND carry out too many parallel tasks.
     on_going_fetches: HashMap<(RecordKey, ValidationType), (PeerId, ReplicationTimeout)>,
+    // Current limit of parallel fetches, adapted to the available bandwidth.
+    max_parallel_fetch: usize,
     event_sender: mpsc::Sender<NetworkEvent>,
     /// Distance range that the incoming key shall be fetched
     distance_range: Option<Distance>,
@@ -54,7 +59,7 @@ pub(crate) struct ReplicationFetcher {
     farthest_acceptable_distance: Option<Distance>,
     /// Scoring of peers collected from storage_challenge.
     /// To be a trustworthy replication source, the peer must has two latest scoring both healthy.
-    peers_scores: HashMap<PeerId, (VecDeque<bool>, Instant)>,
+    peers_scores: PeerScores,
     /// During startup, when the knowledge of peers scoring hasn't been built up,
     /// only records got `majority` of replicated in copies shall be trusted.
     /// This is the temp container to accumulate those intitial replicated in records.
@@ -68,6 +73,7 @@ impl ReplicationFetcher {
             self_peer_id,
             to_be_fetched: HashMap::new(),
             on_going_fetches: HashMap::new(),
+            max_parallel_fetch: MAX_PARALLEL_FETCH,
             event_sender,
             distance_range: None,
             farthest_acceptable_distance: None,
@@ -206,13 +212,13 @@ impl ReplicationFetcher {
 
     // Returns the set of keys that has to be fetched from the peer/network.
     // Target must not be under-fetching
-    // and no more than MAX_PARALLEL_FETCH fetches to be undertaken at the same time.
+    // and no more than `max_parallel_fetch` fetches to be undertaken at the same time.
     pub(crate) fn next_keys_to_fetch(&mut self) -> Vec<(PeerId, RecordKey)> {
         self.prune_expired_keys_and_slow_nodes();
 
         debug!("Next to fetch....");
 
-        if self.on_going_fetches.len() >= MAX_PARALLEL_FETCH {
+        if self.on_going_fetches.len() >= self.max_parallel_fetch {
             warn!(
                 "Replication Fetcher doesn't have free fetch capacity. Currently has {} entries in queue.",
                 self.to_be_fetched.len()
@@ -231,7 +237,9 @@ impl ReplicationFetcher {
         );
 
         // Pre-allocate vectors with known capacity
-        let remaining_capacity = MAX_PARALLEL_FETCH - self.on_going_fetches.len();
+        let remaining_capacity = self
+            .max_parallel_fetch
+            .saturating_sub(self.on_going_fetches.len());
         let mut data_to_fetch = Vec::with_capacity(remaining_capacity);
 
         // Sort to_be_fetched by key closeness to our PeerId
@@ -249,7 +257,7 @@ impl ReplicationFetcher {
             // Already carried out expiration pruning above.
             // Hence here only need to check whether is ongoing fetching.
             // Also avoid fetching same record from different nodes.
-            if self.on_going_fetches.len() < MAX_PARALLEL_FETCH
+            if self.on_going_fetches.len() < self.max_parallel_fetch
                 && !self
                     .on_going_fetches
                     .contains_key(&(key.clone(), t.clone()))
@@ -262,7 +270,7 @@ impl ReplicationFetcher {
             }
 
             // break out the loop early if we can do no more now
-            if self.on_going_fetches.len() >= MAX_PARALLEL_FETCH {
+            if self.on_going_fetches.len() >= self.max_parallel_fetch {
                 break;
             }
         }
@@ -290,6 +298,49 @@ impl ReplicationFetcher {
             .collect()
     }
 
+    /// Adapt the number of parallel fetches to the download budget of the streams opened by the
+    /// node, which the limiter already keeps to a share of the node's budget.
+    /// Backs off while the traffic is being throttled, to leave room for the client traffic,
+    /// and recovers gradually once it's not. Returns the new limit.
+    pub(crate) fn adapt_to_bandwidth(
+        &mut self,
+        download_budget: Option<u64>,
+        congested: bool,
+    ) -> usize {
+        // The bandwidth a single fetch of the largest record needs to complete in time.
+        let bandwidth_per_fetch = MAX_RECORD_SIZE as u64 / FETCH_TIMEOUT.as_secs();
+        let cap = download_budget.map_or(MAX_PARALLEL_FETCH, |budget| {
+            (budget / bandwidth_per_fetch).clamp(1, MAX_PARALLEL_FETCH as u64) as usize
+        });
+
+        let limit = if congested {
+            self.max_parallel_fetch / 2
+        } else {
+            self.max_parallel_fetch + 1
+        };
+        let limit = limit.clamp(1, cap);
+        if limit != self.max_parallel_fetch {
+            debug!(
+                "Adapting the parallel replication fetches from {} to {limit}, congested: {congested}",
+                self.max_parallel_fetch
+            );
+            self.max_parallel_fetch = limit;
+        }
+        limit
+    }
+
+    /// Restore the peers' scores persisted from the previous run.
+    pub(crate) fn restore_peer_scores(&mut self, scores: PeerScores) {
+        self.peers_scores = scores;
+        if self.had_enough_scoring_knowledge() {
+            self.initial_replicates.clear();
+        }
+    }
+
+    pub(crate) fn peer_scores(&self) -> &PeerScores {
+        &self.peers_scores
+    }
+
     // Record peers' healthy status after the storage chanllenge.
     pub(crate) fn add_peer_scores(&mut self, scores: Vec<(PeerId, bool)>) {
         for (peer_id, is_healthy) in scores {
@@ -615,6 +666,29 @@ mod tests {
     };
     use tokio::{sync::mpsc, time::sleep};
 
+    #[test]
+    fn parallel_fetches_should_adapt_to_bandwidth() {
+        let (event_sender, _event_receiver) = mpsc::channel(4);
+        let mut replication_fetcher = ReplicationFetcher::new(PeerId::random(), event_sender);
+
+        // Unlimited and not throttled, the default limit applies.
+        assert_eq!(
+            replication_fetcher.adapt_to_bandwidth(None, false),
+            MAX_PARALLEL_FETCH
+        );
+
+        // Being throttled halves the limit, down to a single fetch.
+        assert_eq!(replication_fetcher.adapt_to_bandwidth(None, true), 2);
+        assert_eq!(replication_fetcher.adapt_to_bandwidth(None, true), 1);
+        assert_eq!(replication_fetcher.adapt_to_bandwidth(None, true), 1);
+
+        // Recovers gradually, but never above what the budget can sustain.
+        let budget = Some(420_000);
+        assert_eq!(replication_fetcher.adapt_to_bandwidth(budget, false), 2);
+        assert_eq!(replication_fetcher.adapt_to_bandwidth(budget, false), 2);
+        assert_eq!(replication_fetcher.adapt_to_bandwidth(None, false), 3);
+    }
+
     #[tokio::test]
     async fn verify_max_parallel_fetches() -> Result<()> {
         //random peer_id
@@ -0,0 +1,333 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::networking::{NodeIssue, driver::BadNodes, replication_fetcher::PeerScores};
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::task::JoinHandle;
+
+const PEER_REPUTATION_FILENAME: &str = "peer_reputation";
+
+/// Interval to flush the reputation knowledge to disk and to decay the outdated entries.
+pub(crate) const REPUTATION_FLUSH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// A peer we have shunned is given another chance after this period.
+const SHUN_REPORT_DECAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Storage challenge scores older than this are no longer trusted on restart.
+/// This is twice the max storage challenge interval.
+const PEER_SCORE_DECAY: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// Issues older than this are dropped on restart, in line with the in-memory tracking.
+const NODE_ISSUE_DECAY: Duration = Duration::from_secs(300);
+
+/// The reason a peer has been shunned and when it happened.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ShunReport {
+    pub(crate) reason: String,
+    pub(crate) timestamp: SystemTime,
+}
+
+impl ShunReport {
+    fn new(reason: String) -> Self {
+        Self {
+            reason,
+            timestamp: SystemTime::now(),
+        }
+    }
+
+    fn is_decayed(&self) -> bool {
+        is_older_than(self.timestamp, SHUN_REPORT_DECAY)
+    }
+}
+
+/// The tracked issues of a peer, with the time they were reported.
+type IssuesSnapshot = Vec<(NodeIssue, SystemTime)>;
+
+/// The on-disk representation of the reputation knowledge.
+/// `PeerId`s are stored as bytes, `Instant`s are converted to `SystemTime`s.
+#[derive(Default, Serialize, Deserialize)]
+struct ReputationSnapshot {
+    issues: Vec<(Vec<u8>, IssuesSnapshot)>,
+    shunned: Vec<(Vec<u8>, ShunReport)>,
+    shunned_by: Vec<(Vec<u8>, ShunReport)>,
+    scores: Vec<(Vec<u8>, Vec<bool>, SystemTime)>,
+}
+
+/// Reputation knowledge of the peers which is persisted in the node's root dir, so that a
+/// restarted node keeps distrusting known misbehaving peers and keeps its scoring knowledge.
+#[derive(Debug)]
+pub(crate) struct PeerReputation {
+    file_path: PathBuf,
+    /// Held while writing the file, so that a flush never overlaps the previous one.
+    write_lock: Arc<Mutex<()>>,
+    /// Peers that have been considered as bad by us.
+    shunned: BTreeMap<PeerId, ShunReport>,
+    /// Peers that have considered us as bad.
+    shunned_by: BTreeMap<PeerId, ShunReport>,
+}
+
+impl PeerReputation {
+    /// Load the reputation knowledge from the root dir, dropping the decayed entries.
+    /// Returns the tracked issues and the storage challenge scores to be restored as well.
+    pub(crate) fn load(root_dir: &Path) -> (Self, BadNodes, PeerScores) {
+        let file_path = root_dir.join(PEER_REPUTATION_FILENAME);
+
+        let snapshot: ReputationSnapshot = match fs::File::open(&file_path) {
+            Ok(file) => rmp_serde::from_read(&file).unwrap_or_else(|err| {
+                warn!("Failed to parse the peer reputation file {file_path:?}: {err:?}");
+                ReputationSnapshot::default()
+            }),
+            Err(_) => ReputationSnapshot::default(),
+        };
+
+        let shunned: BTreeMap<_, _> = parse_peers(snapshot.shunned)
+            .filter(|(_, report)| !report.is_decayed())
+            .collect();
+        let shunned_by = parse_peers(snapshot.shunned_by)
+            .filter(|(_, report)| !report.is_decayed())
+            .collect();
+
+        let mut bad_nodes = BadNodes::new();
+        for (peer_id, issues) in parse_peers(snapshot.issues) {
+            let issues: Vec<_> = issues
+                .into_iter()
+                .filter(|(_, timestamp)| !is_older_than(*timestamp, NODE_ISSUE_DECAY))
+                .filter_map(|(issue, timestamp)| Some((issue, to_instant(timestamp)?)))
+                .collect();
+            if !issues.is_empty() {
+                let _ = bad_nodes.insert(peer_id, (issues, false));
+            }
+        }
+        for peer_id in shunned.keys() {
+            bad_nodes.entry(*peer_id).or_default().1 = true;
+        }
+
+        let scores = snapshot
+            .scores
+            .into_iter()
+            .filter(|(_, _, last_seen)| !is_older_than(*last_seen, PEER_SCORE_DECAY))
+            .filter_map(|(peer_id, scores, last_seen)| {
+                let peer_id = PeerId::from_bytes(&peer_id).ok()?;
+                Some((peer_id, (VecDeque::from(scores), to_instant(last_seen)?)))
+            })
+            .collect();
+
+        if !shunned.is_empty() {
+            info!(
+                "Restored {} shunned peers from the peer reputation file",
+                shunned.len()
+            );
+        }
+
+        let reputation = Self {
+            file_path,
+            write_lock: Arc::default(),
+            shunned,
+            shunned_by,
+        };
+        (reputation, bad_nodes, scores)
+    }
+
+    /// Peers that are currently shunned by us.
+    pub(crate) fn shunned_peers(&self) -> impl Iterator<Item = &PeerId> {
+        self.shunned.keys()
+    }
+
+    /// Record that we have considered the peer as bad.
+    pub(crate) fn record_shunned(&mut self, peer_id: PeerId, reason: String) {
+        let _ = self.shunned.insert(peer_id, ShunReport::new(reason));
+    }
+
+    /// Record that the peer has considered us as bad.
+    pub(crate) fn record_shunned_by(&mut self, peer_id: PeerId, reason: String) {
+        let _ = self.shunned_by.insert(peer_id, ShunReport::new(reason));
+    }
+
+    /// Remove the decayed shun reports, returns the peers that shall no longer be shunned.
+    pub(crate) fn decay(&mut self) -> Vec<PeerId> {
+        self.shunned_by.retain(|_, report| !report.is_decayed());
+
+        let decayed: Vec<PeerId> = self
+            .shunned
+            .iter()
+            .filter(|(_, report)| report.is_decayed())
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+        for peer_id in &decayed {
+            let _ = self.shunned.remove(peer_id);
+        }
+        decayed
+    }
+
+    /// Write the reputation knowledge to disk, off the calling task.
+    /// The file is written to a temp file first and then renamed, to avoid corrupting it.
+    pub(crate) fn flush(&self, bad_nodes: &BadNodes, scores: &PeerScores) -> JoinHandle<()> {
+        let snapshot = ReputationSnapshot {
+            issues: bad_nodes
+                .iter()
+                .filter(|(_, (issues, _))| !issues.is_empty())
+                .map(|(peer_id, (issues, _))| {
+                    let issues = issues
+                        .iter()
+                        .map(|(issue, instant)| (issue.clone(), to_system_time(*instant)))
+                        .collect();
+                    (peer_id.to_bytes(), issues)
+                })
+                .collect(),
+            shunned: to_bytes_keyed(&self.shunned),
+            shunned_by: to_bytes_keyed(&self.shunned_by),
+            scores: scores
+                .iter()
+                .map(|(peer_id, (scores, last_seen))| {
+                    (
+                        peer_id.to_bytes(),
+                        scores.iter().copied().collect(),
+                        to_system_time(*last_seen),
+                    )
+                })
+                .collect(),
+        };
+
+        let file_path = self.file_path.clone();
+        let write_lock = Arc::clone(&self.write_lock);
+        tokio::task::spawn_blocking(move || {
+            let bytes = match rmp_serde::to_vec(&snapshot) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    error!("Failed to serialize the peer reputation: {err:?}");
+                    return;
+                }
+            };
+
+            let _guard = write_lock.lock().unwrap_or_else(|err| err.into_inner());
+            let tmp_path = file_path.with_extension("tmp");
+            if let Err(err) =
+                fs::write(&tmp_path, bytes).and_then(|_| fs::rename(&tmp_path, &file_path))
+            {
+                error!("Failed to write the peer reputation file {file_path:?}: {err:?}");
+            }
+        })
+    }
+}
+
+fn parse_peers<T>(entries: Vec<(Vec<u8>, T)>) -> impl Iterator<Item = (PeerId, T)> {
+    entries
+        .into_iter()
+        .filter_map(|(peer_id, value)| Some((PeerId::from_bytes(&peer_id).ok()?, value)))
+}
+
+fn to_bytes_keyed<T: Clone>(map: &BTreeMap<PeerId, T>) -> Vec<(Vec<u8>, T)> {
+    map.iter()
+        .map(|(peer_id, value)| (peer_id.to_bytes(), value.clone()))
+        .collect()
+}
+
+fn is_older_than(timestamp: SystemTime, period: Duration) -> bool {
+    SystemTime::now()
+        .duration_since(timestamp)
+        .map(|age| age > period)
+        .unwrap_or(false)
+}
+
+fn to_system_time(instant: Instant) -> SystemTime {
+    SystemTime::now()
+        .checked_sub(instant.elapsed())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn to_instant(timestamp: SystemTime) -> Option<Instant> {
+    let age = SystemTime::now()
+        .duration_since(timestamp)
+        .unwrap_or_default();
+    Instant::now().checked_sub(age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn reputation_should_survive_a_restart() -> Result<()> {
+        let root_dir = tempfile::tempdir()?;
+        let shunned_peer = PeerId::random();
+        let shunned_by_peer = PeerId::random();
+        let scored_peer = PeerId::random();
+
+        let (mut reputation, _, _) = PeerReputation::load(root_dir.path());
+        reputation.record_shunned(shunned_peer, "ReplicationFailure".to_string());
+        reputation.record_shunned_by(shunned_by_peer, "BadQuoting".to_string());
+
+        let mut bad_nodes = BadNodes::new();
+        let _ = bad_nodes.insert(
+            shunned_peer,
+            (vec![(NodeIssue::ReplicationFailure, Instant::now())], true),
+        );
+        let mut scores = PeerScores::new();
+        let _ = scores.insert(
+            scored_peer,
+            (VecDeque::from(vec![true, false]), Instant::now()),
+        );
+        reputation.flush(&bad_nodes, &scores).await?;
+
+        let (restored, restored_bad_nodes, restored_scores) = PeerReputation::load(root_dir.path());
+
+        assert_eq!(
+            restored.shunned_peers().collect::<Vec<_>>(),
+            vec![&shunned_peer]
+        );
+        assert_eq!(
+            restored
+                .shunned_by
+                .get(&shunned_by_peer)
+                .map(|r| r.reason.as_str()),
+            Some("BadQuoting")
+        );
+        let (issues, is_bad) = restored_bad_nodes
+            .get(&shunned_peer)
+            .ok_or_else(|| eyre::eyre!("bad node not restored"))?;
+        assert!(*is_bad);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            restored_scores.get(&scored_peer).map(|(s, _)| s.clone()),
+            Some(VecDeque::from(vec![true, false]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decayed_shun_reports_should_be_removed() {
+        let root_dir = PathBuf::from("unused");
+        let peer_id = PeerId::random();
+        let mut reputation = PeerReputation {
+            file_path: root_dir,
+            write_lock: Arc::default(),
+            shunned: BTreeMap::new(),
+            shunned_by: BTreeMap::new(),
+        };
+        let _ = reputation.shunned.insert(
+            peer_id,
+            ShunReport {
+                reason: "BadQuoting".to_string(),
+                timestamp: SystemTime::now() - SHUN_REPORT_DECAY - Duration::from_secs(1),
+            },
+        );
+        reputation.record_shunned(PeerId::random(), "BadQuoting".to_string());
+
+        assert_eq!(reputation.decay(), vec![peer_id]);
+        assert_eq!(reputation.shunned.len(), 1);
+    }
+}
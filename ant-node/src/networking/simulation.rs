@@ -0,0 +1,518 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! In-process transport used by the network simulator.
+//!
+//! Nodes are connected through the libp2p memory transport, with every connection going through a
+//! fault injecting muxer driven by the shared [`NetworkConditions`]:
+//! - latency: each substream waits for the configured latency before sending its first bytes.
+//! - packet loss: an outbound substream is dropped silently with the configured probability;
+//!   whatever is written to it is swallowed and it never yields any data. Every request opens a
+//!   substream of its own, which its response travels back on, so the configured rate is the rate
+//!   of requests lost along with their response. The bytes within a substream are never lost, as
+//!   losing a single frame would corrupt the stream rather than lose a message.
+//! - partitions: peers of different groups can neither connect nor exchange data. Established
+//!   connections are torn down on their next activity.
+
+use crate::networking::BandwidthLimiter;
+use futures::{
+    AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+    future::{BoxFuture, FutureExt, Ready},
+    stream::{FuturesUnordered, StreamExt},
+};
+use libp2p::{
+    Multiaddr, PeerId, Transport,
+    core::{
+        muxing::{StreamMuxer, StreamMuxerBox, StreamMuxerEvent},
+        transport::{
+            self, DialOpts, ListenerId, MemoryTransport, TransportError, TransportEvent,
+            memory::Channel,
+        },
+        upgrade,
+    },
+    identity::Keypair,
+    multiaddr::Protocol,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::{
+    collections::HashMap,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+use tokio::time::Sleep;
+
+#[derive(Debug)]
+struct ConditionsState {
+    latency: Duration,
+    packet_loss: f64,
+    /// The partition group of each peer. Peers without a group form a group of their own.
+    groups: HashMap<PeerId, usize>,
+    rng: StdRng,
+}
+
+/// The conditions of the links between the simulated nodes, shared by all of them.
+///
+/// Cloning it returns a handle to the same conditions, hence any change is applied to the whole
+/// simulated network at once.
+#[derive(Clone, Debug)]
+pub struct NetworkConditions {
+    state: Arc<Mutex<ConditionsState>>,
+}
+
+impl NetworkConditions {
+    /// Creates perfect network conditions. The seed drives the packet loss, so that runs can be
+    /// replayed deterministically.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ConditionsState {
+                latency: Duration::ZERO,
+                packet_loss: 0.0,
+                groups: HashMap::new(),
+                rng: StdRng::seed_from_u64(seed),
+            })),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, ConditionsState> {
+        // The state is always left consistent, hence it's fine to recover from a poisoned lock.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Sets the one way latency applied to every message.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// Sets the probability, between `0.0` and `1.0`, for a request to be lost. The response to a
+    /// request that made it through is not lost.
+    pub fn set_packet_loss(&self, probability: f64) {
+        self.state().packet_loss = probability.clamp(0.0, 1.0);
+    }
+
+    /// Splits the network in the provided groups. Peers can only reach the peers of their group,
+    /// the peers left out of all the groups can only reach each other.
+    pub fn partition(&self, groups: &[Vec<PeerId>]) {
+        let mut state = self.state();
+        state.groups.clear();
+        for (index, group) in groups.iter().enumerate() {
+            for peer_id in group {
+                let _ = state.groups.insert(*peer_id, index);
+            }
+        }
+    }
+
+    /// Cuts the peer off from the rest of the network.
+    pub fn isolate(&self, peer_id: PeerId) {
+        let mut state = self.state();
+        let group = state.groups.values().max().map_or(0, |max| max + 1);
+        let _ = state.groups.insert(peer_id, group);
+    }
+
+    /// Removes all the partitions.
+    pub fn heal(&self) {
+        self.state().groups.clear();
+    }
+
+    /// Whether the two peers are able to communicate.
+    pub fn is_reachable(&self, peer_a: &PeerId, peer_b: &PeerId) -> bool {
+        let state = self.state();
+        state.groups.get(peer_a) == state.groups.get(peer_b)
+    }
+
+    fn latency(&self) -> Duration {
+        self.state().latency
+    }
+
+    fn roll_loss(&self) -> bool {
+        let mut state = self.state();
+        let packet_loss = state.packet_loss;
+        packet_loss > 0.0 && state.rng.gen_bool(packet_loss)
+    }
+}
+
+/// Build the memory transport of a simulated node, subject to the provided conditions.
+pub(crate) fn build_simulated_transport(
+    keypair: &Keypair,
+    conditions: NetworkConditions,
+    bandwidth_limiter: BandwidthLimiter,
+) -> transport::Boxed<(PeerId, StreamMuxerBox)> {
+    let local_peer_id = keypair.public().to_peer_id();
+    SimulatedTransport::default()
+        .upgrade(upgrade::Version::V1Lazy)
+        .authenticate(
+            libp2p::noise::Config::new(keypair)
+                .expect("Signing libp2p-noise static DH keypair failed."),
+        )
+        .multiplex(libp2p::yamux::Config::default())
+        .and_then(move |(peer_id, muxer), _| {
+            let result = if conditions.is_reachable(&local_peer_id, &peer_id) {
+                let muxer = FaultyMuxer {
+                    inner: StreamMuxerBox::new(bandwidth_limiter.wrap(muxer)),
+                    local_peer_id,
+                    remote_peer_id: peer_id,
+                    conditions: conditions.clone(),
+                };
+                Ok((peer_id, StreamMuxerBox::new(muxer)))
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::ConnectionRefused,
+                    format!("{peer_id:?} is partitioned from {local_peer_id:?}"),
+                ))
+            };
+            futures::future::ready(result)
+        })
+        .boxed()
+}
+
+/// The memory transport hands out a random port to each dial, which doesn't allow the listener to
+/// dial back. The dialer hence sends its listen port first, which is reported as the address of
+/// the incoming connection, like QUIC does by reusing the listen port.
+#[derive(Default)]
+struct SimulatedTransport {
+    inner: MemoryTransport,
+    listen_port: u64,
+    pending_incoming: FuturesUnordered<BoxFuture<'static, PendingIncoming>>,
+}
+
+struct PendingIncoming {
+    listener_id: ListenerId,
+    local_addr: Multiaddr,
+    result: io::Result<(Channel<Vec<u8>>, u64)>,
+}
+
+fn memory_port(addr: &Multiaddr) -> Option<u64> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Memory(port) => Some(port),
+        _ => None,
+    })
+}
+
+impl Transport for SimulatedTransport {
+    type Output = Channel<Vec<u8>>;
+    type Error = io::Error;
+    type ListenerUpgrade = Ready<io::Result<Self::Output>>;
+    type Dial = BoxFuture<'static, io::Result<Self::Output>>;
+
+    fn listen_on(
+        &mut self,
+        id: ListenerId,
+        addr: Multiaddr,
+    ) -> Result<(), TransportError<Self::Error>> {
+        self.inner
+            .listen_on(id, addr)
+            .map_err(|err| err.map(io::Error::other))
+    }
+
+    fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.inner.remove_listener(id)
+    }
+
+    fn dial(
+        &mut self,
+        addr: Multiaddr,
+        opts: DialOpts,
+    ) -> Result<Self::Dial, TransportError<Self::Error>> {
+        let dial = self
+            .inner
+            .dial(addr, opts)
+            .map_err(|err| err.map(io::Error::other))?;
+        let listen_port = self.listen_port;
+        Ok(async move {
+            let mut channel = dial.await.map_err(io::Error::other)?;
+            channel.write_all(&listen_port.to_be_bytes()).await?;
+            Ok(channel)
+        }
+        .boxed())
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+        loop {
+            if let Poll::Ready(Some(incoming)) = self.pending_incoming.poll_next_unpin(cx) {
+                match incoming.result {
+                    Ok((channel, port)) => {
+                        return Poll::Ready(TransportEvent::Incoming {
+                            listener_id: incoming.listener_id,
+                            upgrade: futures::future::ready(Ok(channel)),
+                            local_addr: incoming.local_addr,
+                            send_back_addr: Multiaddr::empty().with(Protocol::Memory(port)),
+                        });
+                    }
+                    Err(err) => {
+                        debug!("Simulated incoming connection failed: {err:?}");
+                        continue;
+                    }
+                }
+            }
+
+            let event = ready!(Pin::new(&mut self.inner).poll(cx));
+            match event {
+                TransportEvent::Incoming {
+                    listener_id,
+                    upgrade,
+                    local_addr,
+                    ..
+                } => {
+                    self.pending_incoming.push(
+                        async move {
+                            let result = async {
+                                let mut channel = upgrade.await.map_err(io::Error::other)?;
+                                let mut port = [0u8; 8];
+                                channel.read_exact(&mut port).await?;
+                                Ok((channel, u64::from_be_bytes(port)))
+                            }
+                            .await;
+                            PendingIncoming {
+                                listener_id,
+                                local_addr,
+                                result,
+                            }
+                        }
+                        .boxed(),
+                    );
+                }
+                TransportEvent::NewAddress {
+                    listener_id,
+                    listen_addr,
+                } => {
+                    if let Some(port) = memory_port(&listen_addr) {
+                        self.listen_port = port;
+                    }
+                    return Poll::Ready(TransportEvent::NewAddress {
+                        listener_id,
+                        listen_addr,
+                    });
+                }
+                TransportEvent::AddressExpired {
+                    listener_id,
+                    listen_addr,
+                } => {
+                    return Poll::Ready(TransportEvent::AddressExpired {
+                        listener_id,
+                        listen_addr,
+                    });
+                }
+                TransportEvent::ListenerClosed {
+                    listener_id,
+                    reason,
+                } => {
+                    return Poll::Ready(TransportEvent::ListenerClosed {
+                        listener_id,
+                        reason: reason.map_err(io::Error::other),
+                    });
+                }
+                TransportEvent::ListenerError { listener_id, error } => {
+                    return Poll::Ready(TransportEvent::ListenerError {
+                        listener_id,
+                        error: io::Error::other(error),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Wraps the muxer of a connection, applying the [`NetworkConditions`] to all of its streams.
+#[pin_project::pin_project]
+struct FaultyMuxer {
+    #[pin]
+    inner: StreamMuxerBox,
+    local_peer_id: PeerId,
+    remote_peer_id: PeerId,
+    conditions: NetworkConditions,
+}
+
+impl FaultyMuxer {
+    fn check_reachable(&self) -> io::Result<()> {
+        if self
+            .conditions
+            .is_reachable(&self.local_peer_id, &self.remote_peer_id)
+        {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                format!("{:?} has been partitioned", self.remote_peer_id),
+            ))
+        }
+    }
+
+    /// Only the outbound end of a substream rolls for its loss, otherwise both ends would and a
+    /// request would be lost at about twice the configured rate.
+    fn new_stream<S>(&self, inner: S, outbound: bool) -> FaultyStream<S> {
+        FaultyStream {
+            inner,
+            local_peer_id: self.local_peer_id,
+            remote_peer_id: self.remote_peer_id,
+            conditions: self.conditions.clone(),
+            is_lost: outbound && self.conditions.roll_loss(),
+            latency: Some(self.conditions.latency()).filter(|latency| !latency.is_zero()),
+            delay: None,
+        }
+    }
+}
+
+impl StreamMuxer for FaultyMuxer {
+    type Substream = FaultyStream<<StreamMuxerBox as StreamMuxer>::Substream>;
+    type Error = io::Error;
+
+    fn poll_inbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        self.check_reachable()?;
+        let inner = ready!(self.as_mut().project().inner.poll_inbound(cx)?);
+        Poll::Ready(Ok(self.new_stream(inner, false)))
+    }
+
+    fn poll_outbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        self.check_reachable()?;
+        let inner = ready!(self.as_mut().project().inner.poll_outbound(cx)?);
+        Poll::Ready(Ok(self.new_stream(inner, true)))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        self.check_reachable()?;
+        self.project().inner.poll(cx)
+    }
+}
+
+/// A stream delayed by the latency, which might be lost or cut by a partition.
+#[pin_project::pin_project]
+struct FaultyStream<S> {
+    #[pin]
+    inner: S,
+    local_peer_id: PeerId,
+    remote_peer_id: PeerId,
+    conditions: NetworkConditions,
+    is_lost: bool,
+    /// The latency still to be applied before the first write.
+    latency: Option<Duration>,
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+fn partitioned_error(remote_peer_id: &PeerId) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        format!("{remote_peer_id:?} has been partitioned"),
+    )
+}
+
+impl<S: AsyncRead> AsyncRead for FaultyStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        if !this
+            .conditions
+            .is_reachable(this.local_peer_id, this.remote_peer_id)
+        {
+            return Poll::Ready(Err(partitioned_error(this.remote_peer_id)));
+        }
+        if *this.is_lost {
+            // Nothing ever makes it through, the reader gives up on its own timeout.
+            return Poll::Pending;
+        }
+        this.inner.poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite> AsyncWrite for FaultyStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        if !this
+            .conditions
+            .is_reachable(this.local_peer_id, this.remote_peer_id)
+        {
+            return Poll::Ready(Err(partitioned_error(this.remote_peer_id)));
+        }
+        if let Some(latency) = this.latency.take() {
+            *this.delay = Some(Box::pin(tokio::time::sleep(latency)));
+        }
+        if let Some(delay) = this.delay.as_mut() {
+            ready!(delay.poll_unpin(cx));
+            *this.delay = None;
+        }
+        if *this.is_lost {
+            return Poll::Ready(Ok(buf.len()));
+        }
+        this.inner.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+        if *this.is_lost {
+            return Poll::Ready(Ok(()));
+        }
+        this.inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partitions_should_only_allow_traffic_within_groups() {
+        let conditions = NetworkConditions::new(0);
+        let [a, b, c] = [PeerId::random(), PeerId::random(), PeerId::random()];
+        assert!(conditions.is_reachable(&a, &b));
+
+        conditions.partition(&[vec![a, b]]);
+        assert!(conditions.is_reachable(&a, &b));
+        assert!(!conditions.is_reachable(&a, &c));
+
+        conditions.isolate(b);
+        assert!(!conditions.is_reachable(&a, &b));
+        assert!(!conditions.is_reachable(&b, &c));
+
+        conditions.heal();
+        assert!(conditions.is_reachable(&a, &c) && conditions.is_reachable(&b, &c));
+    }
+
+    #[test]
+    fn packet_loss_should_be_deterministic_for_a_seed() {
+        let rolls = |seed| {
+            let conditions = NetworkConditions::new(seed);
+            conditions.set_packet_loss(0.5);
+            (0..64).map(|_| conditions.roll_loss()).collect::<Vec<_>>()
+        };
+        assert_eq!(rolls(7), rolls(7));
+        assert!(rolls(7).iter().any(|lost| *lost) && rolls(7).iter().any(|lost| !*lost));
+
+        let conditions = NetworkConditions::new(7);
+        assert!((0..64).all(|_| !conditions.roll_loss()));
+    }
+}
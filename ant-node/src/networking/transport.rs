@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::networking::BandwidthLimiter;
 #[cfg(feature = "open-metrics")]
 use crate::networking::MetricsRegistries;
 use libp2p::{
@@ -18,9 +19,11 @@ const MAX_STREAM_DATA_ENV_STR: &str = "ANT_MAX_STREAM_DATA";
 
 pub(crate) fn build_transport(
     keypair: &Keypair,
+    bandwidth_limiter: BandwidthLimiter,
     #[cfg(feature = "open-metrics")] registries: &mut MetricsRegistries,
 ) -> transport::Boxed<(PeerId, StreamMuxerBox)> {
-    let trans = generate_quic_transport(keypair);
+    let trans = generate_quic_transport(keypair)
+        .map(move |(peer_id, muxer), _| (peer_id, bandwidth_limiter.wrap(muxer)));
     #[cfg(feature = "open-metrics")]
     let trans = libp2p::metrics::BandwidthTransport::new(trans, &mut registries.standard_metrics);
 
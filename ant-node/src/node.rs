@@ -7,22 +7,34 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
-    Marker, NodeEvent, error::Result, event::NodeEventsChannel, quote::quotes_verification,
+    Marker, NodeEvent,
+    error::{Error, Result},
+    event::NodeEventsChannel,
+    quote::quotes_verification,
 };
 #[cfg(feature = "open-metrics")]
 use crate::metrics::NodeMetricsRecorder;
 #[cfg(feature = "open-metrics")]
 use crate::networking::MetricsRegistries;
-use crate::networking::{Addresses, Network, NetworkConfig, NetworkError, NetworkEvent, NodeIssue};
+#[cfg(feature = "simulation")]
+use crate::networking::NetworkConditions;
+use crate::networking::{
+    Addresses, BandwidthLimits, ChallengeOutcome, Network, NetworkConfig, NetworkError,
+    NetworkEvent, NodeIssue,
+};
+#[cfg(feature = "simulation")]
+use crate::spawn::simulation::SimulatedEvm;
 use crate::{PutValidationError, RunningNode};
 use ant_bootstrap::BootstrapCacheStore;
 use ant_evm::EvmNetwork;
 use ant_evm::RewardsAddress;
 use ant_protocol::{
     CLOSE_GROUP_SIZE, NetworkAddress, PrettyPrintRecordKey,
+    constants::{MAX_KEYS_PER_BATCH_QUERY, MAX_PACKET_SIZE},
     error::Error as ProtocolError,
     messages::{ChunkProof, CmdResponse, Nonce, Query, QueryResponse, Request, Response},
     storage::ValidationType,
+    version::NetworkContext,
 };
 use bytes::Bytes;
 use itertools::Itertools;
@@ -81,7 +93,9 @@ const TIME_STEP: usize = 20;
 
 /// Helper to build and run a Node
 pub struct NodeBuilder {
+    access_list_path: Option<PathBuf>,
     addr: SocketAddr,
+    bandwidth_limits: BandwidthLimits,
     bootstrap_cache: Option<BootstrapCacheStore>,
     evm_address: RewardsAddress,
     evm_network: EvmNetwork,
@@ -91,9 +105,12 @@ pub struct NodeBuilder {
     #[cfg(feature = "open-metrics")]
     /// Set to Some to enable the metrics server
     metrics_server_port: Option<u16>,
+    network_context: Option<NetworkContext>,
     no_upnp: bool,
     relay_client: bool,
     root_dir: PathBuf,
+    #[cfg(feature = "simulation")]
+    simulation: Option<(NetworkConditions, SimulatedEvm)>,
 }
 
 impl NodeBuilder {
@@ -108,7 +125,9 @@ impl NodeBuilder {
         root_dir: PathBuf,
     ) -> Self {
         Self {
+            access_list_path: None,
             addr,
+            bandwidth_limits: BandwidthLimits::default(),
             bootstrap_cache: None,
             evm_address,
             evm_network,
@@ -117,9 +136,12 @@ impl NodeBuilder {
             local: false,
             #[cfg(feature = "open-metrics")]
             metrics_server_port: None,
+            network_context: None,
             no_upnp: false,
             relay_client: false,
             root_dir,
+            #[cfg(feature = "simulation")]
+            simulation: None,
         }
     }
 
@@ -144,11 +166,36 @@ impl NodeBuilder {
         self.relay_client = relay_client;
     }
 
+    /// Set the network the node takes part in. Defaults to the network of the process-wide network
+    /// ID if not set.
+    pub fn network_context(&mut self, network_context: NetworkContext) {
+        self.network_context = Some(network_context);
+    }
+
     /// Set the flag to disable UPnP for the node
     pub fn no_upnp(&mut self, no_upnp: bool) {
         self.no_upnp = no_upnp;
     }
 
+    /// Set the path to the static allow/deny list of peers and IP ranges.
+    /// Defaults to the `access_list` file within the root dir if not set.
+    pub fn access_list(&mut self, path: Option<PathBuf>) {
+        self.access_list_path = path;
+    }
+
+    /// Set the upload and download rate limits of the node. Unlimited by default.
+    pub fn bandwidth_limits(&mut self, limits: BandwidthLimits) {
+        self.bandwidth_limits = limits;
+    }
+
+    #[cfg(feature = "simulation")]
+    /// Run the node within the network simulator, over the memory transport and paying through
+    /// the stubbed EVM.
+    pub(crate) fn simulation(&mut self, conditions: NetworkConditions, evm: SimulatedEvm) {
+        self.local = true;
+        self.simulation = Some((conditions, evm));
+    }
+
     /// Asynchronously runs a new node instance, setting up the swarm driver,
     /// creating a data storage, and handling network events. Returns the
     /// created `RunningNode` which contains a `NodeEventsChannel` for listening
@@ -189,6 +236,14 @@ impl NodeBuilder {
             no_upnp: self.no_upnp,
             relay_client: self.relay_client,
             custom_request_timeout: None,
+            access_list_path: self.access_list_path,
+            bandwidth_limits: self.bandwidth_limits,
+            network_context: self.network_context.unwrap_or_else(NetworkContext::current),
+            #[cfg(feature = "simulation")]
+            simulated_network: self
+                .simulation
+                .as_ref()
+                .map(|(conditions, _)| conditions.clone()),
             #[cfg(feature = "open-metrics")]
             metrics_registries,
             #[cfg(feature = "open-metrics")]
@@ -205,6 +260,8 @@ impl NodeBuilder {
             #[cfg(feature = "open-metrics")]
             metrics_recorder,
             evm_network: self.evm_network,
+            #[cfg(feature = "simulation")]
+            simulated_evm: self.simulation.map(|(_, evm)| evm),
         };
         let node = Node {
             inner: Arc::new(node),
@@ -241,6 +298,8 @@ struct NodeInner {
     metrics_recorder: Option<NodeMetricsRecorder>,
     reward_address: RewardsAddress,
     evm_network: EvmNetwork,
+    #[cfg(feature = "simulation")]
+    simulated_evm: Option<SimulatedEvm>,
 }
 
 impl Node {
@@ -270,6 +329,12 @@ impl Node {
         &self.inner.evm_network
     }
 
+    #[cfg(feature = "simulation")]
+    /// Returns the stubbed EVM, if the node runs within the network simulator
+    pub(crate) fn simulated_evm(&self) -> Option<&SimulatedEvm> {
+        self.inner.simulated_evm.as_ref()
+    }
+
     /// Spawns a task to process for `NetworkEvents`.
     /// Returns both tasks as JoinHandle<()>.
     fn run(
@@ -572,6 +637,31 @@ impl Node {
                     Self::query_peers_version(network, peers).await;
                 });
             }
+            NetworkEvent::RecordsPruned(keys) => {
+                event_header = "RecordsPruned";
+                self.events_channel()
+                    .broadcast(NodeEvent::RecordsPruned(keys));
+            }
+            NetworkEvent::PeerConsideredAsBad {
+                bad_peer,
+                bad_behaviour,
+            } => {
+                event_header = "PeerConsideredAsBad";
+                self.events_channel().broadcast(NodeEvent::PeerShunned {
+                    peer: NetworkAddress::from(bad_peer),
+                    reason: bad_behaviour,
+                });
+            }
+            NetworkEvent::FlaggedAsBadNode {
+                flagged_by,
+                bad_behaviour,
+            } => {
+                event_header = "FlaggedAsBadNode";
+                self.events_channel().broadcast(NodeEvent::ShunnedByPeer {
+                    peer: NetworkAddress::from(flagged_by),
+                    reason: bad_behaviour,
+                });
+            }
         }
 
         trace!(
@@ -644,13 +734,21 @@ impl Node {
                                 storage_proofs,
                             }
                         } else {
+                            let quote = Self::create_quote_for_storecost(
+                                network,
+                                &key,
+                                &quoting_metrics,
+                                &payment_address,
+                            );
+                            if quote.is_ok() {
+                                node.events_channel().broadcast(NodeEvent::QuoteIssued {
+                                    key: key.clone(),
+                                    data_type,
+                                    data_size,
+                                });
+                            }
                             QueryResponse::GetStoreQuote {
-                                quote: Self::create_quote_for_storecost(
-                                    network,
-                                    &key,
-                                    &quoting_metrics,
-                                    &payment_address,
-                                ),
+                                quote,
                                 peer_address: NetworkAddress::from(self_id),
                                 storage_proofs,
                             }
@@ -755,10 +853,91 @@ impl Node {
                     record_addr: address,
                 }
             }
+            Query::GetReplicatedRecords { holder: _, keys } => {
+                debug!("Got GetReplicatedRecords for {} keys", keys.len());
+                Self::respond_get_replicated_records(network, keys).await
+            }
+            Query::CheckRecordsExist { holder: _, keys } => {
+                debug!("Got CheckRecordsExist for {} keys", keys.len());
+                let mut results = vec![];
+                for key in keys.into_iter().take(MAX_KEYS_PER_BATCH_QUERY) {
+                    let exists = match key.as_record_key() {
+                        Some(record_key) => network
+                            .is_record_key_present_locally(&record_key)
+                            .await
+                            .unwrap_or(false),
+                        None => false,
+                    };
+                    results.push((key, exists));
+                }
+
+                QueryResponse::CheckRecordsExist {
+                    holder: NetworkAddress::from(network.peer_id()),
+                    results,
+                }
+            }
         };
         Response::Query(resp)
     }
 
+    /// Packs as many of the requested records as fit in `MAX_PACKET_SIZE`, in the requested order.
+    /// The keys left over are returned as `remaining` for the requester to ask for again.
+    async fn respond_get_replicated_records(
+        network: &Network,
+        keys: Vec<NetworkAddress>,
+    ) -> QueryResponse {
+        let (records, remaining) = Self::pack_records(keys, |key| async move {
+            let record_key = key.as_record_key()?;
+            network
+                .get_local_record(&record_key)
+                .await
+                .ok()
+                .flatten()
+                .map(|record| record.value)
+        })
+        .await;
+
+        QueryResponse::GetReplicatedRecords {
+            holder: NetworkAddress::from(network.peer_id()),
+            records,
+            remaining,
+        }
+    }
+
+    /// Packs the records `get_record` finds for the keys, until they fill `MAX_PACKET_SIZE` or
+    /// `MAX_KEYS_PER_BATCH_QUERY` keys are handled. Returns the packed records, `None` for the
+    /// ones not held, along with the keys left over.
+    async fn pack_records<F, Fut>(
+        keys: Vec<NetworkAddress>,
+        mut get_record: F,
+    ) -> (Vec<(NetworkAddress, Option<Bytes>)>, Vec<NetworkAddress>)
+    where
+        F: FnMut(NetworkAddress) -> Fut,
+        Fut: Future<Output = Option<Vec<u8>>>,
+    {
+        let mut records = vec![];
+        let mut remaining = vec![];
+        let mut packed_size = 0;
+
+        let mut keys = keys.into_iter();
+        for key in keys.by_ref().take(MAX_KEYS_PER_BATCH_QUERY) {
+            match get_record(key.clone()).await {
+                Some(value) => {
+                    // A single record always fits, as records are capped at `MAX_RECORD_SIZE`.
+                    if !records.is_empty() && packed_size + value.len() > MAX_PACKET_SIZE {
+                        remaining.push(key);
+                        break;
+                    }
+                    packed_size += value.len();
+                    records.push((key, Some(Bytes::from(value))));
+                }
+                None => records.push((key, None)),
+            }
+        }
+        remaining.extend(keys);
+        (records, remaining)
+    }
+
     async fn respond_get_closest_peers(
         network: &Network,
         target: NetworkAddress,
@@ -985,27 +1164,19 @@ impl Node {
             });
         }
 
-        let mut peer_scores = vec![];
+        let mut outcomes = vec![];
         while let Some(res) = tasks.join_next().await {
             match res {
-                Ok((peer_id, score)) => {
-                    let is_healthy = score > MIN_ACCEPTABLE_HEALTHY_SCORE;
-                    if !is_healthy {
-                        info!(
-                            "Peer {peer_id:?} failed storage challenge with low score {score}/{MIN_ACCEPTABLE_HEALTHY_SCORE}."
-                        );
-                        // TODO: shall the challenge failure immediately triggers the node to be removed?
-                        network.record_node_issues(peer_id, NodeIssue::FailedChunkProofCheck);
-                    }
-                    peer_scores.push((peer_id, is_healthy));
-                }
+                Ok((peer_id, outcome)) => outcomes.push((peer_id, outcome)),
                 Err(e) => {
                     info!("StorageChallenge task completed with error {e:?}");
                 }
             }
         }
-        if !peer_scores.is_empty() {
-            network.notify_peer_scores(peer_scores);
+        // The failed and unresponsive peers are reported as bad by the driver,
+        // together with the scoring of the replication fetcher.
+        if !outcomes.is_empty() {
+            network.notify_challenge_outcomes(outcomes);
         }
 
         info!(
@@ -1014,6 +1185,42 @@ impl Node {
         );
     }
 
+    /// Challenge a single peer to prove it holds the record of `key` and the ones around it.
+    /// We must hold the record ourselves to be able to verify the proof, and the peer must be in
+    /// our routing table. The outcome is only reported, it is not held against the peer.
+    pub(crate) async fn challenge_peer(
+        network: &Network,
+        peer_id: PeerId,
+        key: NetworkAddress,
+    ) -> Result<ChallengeOutcome> {
+        let Some(record) = network.get_local_record(&key.to_record_key()).await? else {
+            return Err(Error::ChallengeTargetNotHeld(key));
+        };
+        let nonce: Nonce = thread_rng().r#gen::<u64>();
+        let expected_proofs = HashMap::from([(key.clone(), ChunkProof::new(&record.value, nonce))]);
+        let request = Request::Query(Query::GetChunkExistenceProof {
+            key,
+            nonce,
+            difficulty: 1,
+        });
+
+        let addresses = network
+            .get_local_peers_with_multiaddr()
+            .await?
+            .into_iter()
+            .find(|(peer, _)| *peer == peer_id)
+            .map(|(_, addrs)| Addresses(addrs))
+            .ok_or(Error::ChallengePeerUnknown(peer_id))?;
+        let outcome = scoring_peer(
+            network.clone(),
+            (peer_id, addresses),
+            request,
+            expected_proofs,
+        )
+        .await;
+        Ok(outcome)
+    }
+
     /// Query peers' versions and update local knowledge.
     async fn query_peers_version(network: Network, peers: Vec<(PeerId, Addresses)>) {
         // To avoid choking, carry out the queries one by one
@@ -1056,7 +1263,7 @@ async fn scoring_peer(
     peer: (PeerId, Addresses),
     request: Request,
     expected_proofs: HashMap<NetworkAddress, ChunkProof>,
-) -> usize {
+) -> ChallengeOutcome {
     let peer_id = peer.0;
     let start = Instant::now();
     let responses = network
@@ -1068,7 +1275,7 @@ async fn scoring_peer(
     {
         if answers.is_empty() {
             info!("Peer {peer_id:?} didn't answer the ChunkProofChallenge.");
-            return 0;
+            return ChallengeOutcome::Failed;
         }
         let elapsed = start.elapsed();
 
@@ -1084,10 +1291,21 @@ async fn scoring_peer(
             "Received {} answers from peer {peer_id:?} after {elapsed:?}, score it as {score}.",
             answers.len()
         );
-        score
+        outcome_from_score(score)
     } else {
         info!("Peer {peer_id:?} doesn't reply the ChunkProofChallenge, or replied with error.");
-        0
+        ChallengeOutcome::Unresponsive
+    }
+}
+
+fn outcome_from_score(score: usize) -> ChallengeOutcome {
+    if score > MIN_ACCEPTABLE_HEALTHY_SCORE {
+        ChallengeOutcome::Passed
+    } else {
+        info!(
+            "Peer failed storage challenge with low score {score}/{MIN_ACCEPTABLE_HEALTHY_SCORE}."
+        );
+        ChallengeOutcome::Failed
     }
 }
 
@@ -1155,6 +1373,60 @@ mod tests {
     use super::*;
     use std::str::FromStr;
 
+    fn chunk_addr() -> NetworkAddress {
+        NetworkAddress::from(ant_protocol::storage::ChunkAddress::new(
+            xor_name::XorName::random(&mut rand::thread_rng()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn packed_records_should_fit_in_a_packet() {
+        let record_size = MAX_PACKET_SIZE / 3 + 1;
+        let keys: Vec<_> = (0..5).map(|_| chunk_addr()).collect();
+        let missing = keys[1].clone();
+
+        let (records, remaining) = Node::pack_records(keys.clone(), |key| {
+            let found = key != missing;
+            async move { found.then(|| vec![0; record_size]) }
+        })
+        .await;
+
+        // Two records fit, the third one and the keys after it are left over
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1], (missing, None));
+        assert!(
+            records
+                .iter()
+                .filter_map(|(_, value)| value.as_ref())
+                .all(|value| value.len() == record_size)
+        );
+        assert_eq!(remaining, keys[3..]);
+    }
+
+    #[tokio::test]
+    async fn a_single_large_record_should_be_packed() {
+        let keys: Vec<_> = (0..2).map(|_| chunk_addr()).collect();
+
+        let (records, remaining) =
+            Node::pack_records(keys.clone(), |_| async { Some(vec![0; MAX_PACKET_SIZE]) }).await;
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(remaining, keys[1..]);
+    }
+
+    #[tokio::test]
+    async fn keys_beyond_the_batch_limit_should_remain() {
+        let keys: Vec<_> = (0..MAX_KEYS_PER_BATCH_QUERY + 2)
+            .map(|_| chunk_addr())
+            .collect();
+
+        let (records, remaining) = Node::pack_records(keys.clone(), |_| async { None }).await;
+
+        assert_eq!(records.len(), MAX_KEYS_PER_BATCH_QUERY);
+        assert!(records.iter().all(|(_, value)| value.is_none()));
+        assert_eq!(remaining, keys[MAX_KEYS_PER_BATCH_QUERY..]);
+    }
+
     #[test]
     fn test_no_local_peers() {
         let local_peers: Vec<(PeerId, Vec<Multiaddr>)> = vec![];
@@ -1249,4 +1521,17 @@ mod tests {
 
         assert_eq!(expected_result, result);
     }
+
+    #[test]
+    fn test_challenge_outcome_from_score() {
+        assert_eq!(outcome_from_score(0), ChallengeOutcome::Failed);
+        assert_eq!(
+            outcome_from_score(MIN_ACCEPTABLE_HEALTHY_SCORE),
+            ChallengeOutcome::Failed
+        );
+        assert_eq!(
+            outcome_from_score(MIN_ACCEPTABLE_HEALTHY_SCORE + 1),
+            ChallengeOutcome::Passed
+        );
+    }
 }
@@ -9,9 +9,9 @@
 use std::collections::BTreeSet;
 
 use crate::error::PutValidationError;
-use crate::{Marker, Result, node::Node};
-use ant_evm::ProofOfPayment;
+use crate::{Marker, NodeEvent, Result, node::Node};
 use ant_evm::payment_vault::verify_data_payment;
+use ant_evm::{Amount, AttoTokens, ProofOfPayment, QuoteHash, QuotingMetrics, RewardsAddress};
 use ant_protocol::storage::GraphEntry;
 use ant_protocol::{
     NetworkAddress, PrettyPrintRecordKey,
@@ -529,9 +529,8 @@ impl Node {
 
         self.record_metrics(Marker::ValidChunkRecordPutFromNetwork(&pretty_key));
 
-        // TODO: currently ignored, re-enable once start to handle
-        // self.events_channel()
-        //     .broadcast(crate::NodeEvent::ChunkStored(chunk_addr));
+        self.events_channel()
+            .broadcast(NodeEvent::ChunkStored(*chunk.address()));
 
         Ok(())
     }
@@ -624,6 +623,8 @@ impl Node {
         let pretty_key = PrettyPrintRecordKey::from(&scratchpad_key);
 
         self.record_metrics(Marker::ValidScratchpadRecordPutFromNetwork(&pretty_key));
+        self.events_channel()
+            .broadcast(NodeEvent::ScratchpadStored(*addr));
 
         // Client changed to upload to ALL payees, hence no longer need this.
         // May need again once client change back to upload to just one to save traffic.
@@ -696,7 +697,7 @@ impl Node {
         // add local GraphEntries to the validated GraphEntries, turn to Vec
         let local_entries = self.get_local_graphentries(addr).await?;
         let existing_entry = local_entries.len();
-        validated_entries.extend(local_entries.into_iter());
+        validated_entries.extend(local_entries);
         let validated_entries: Vec<GraphEntry> = validated_entries.into_iter().collect();
 
         // No need to write to disk if nothing new.
@@ -731,15 +732,35 @@ impl Node {
         }
 
         self.record_metrics(Marker::ValidGraphEntryRecordPutFromNetwork(&pretty_key));
+        self.events_channel()
+            .broadcast(NodeEvent::GraphEntryStored(addr));
         Ok(())
     }
 
     /// Perform validations on the provided `Record`.
+    /// Broadcasts a `NodeEvent::PaymentVerificationFailed` if the payment is not valid.
     pub(crate) async fn payment_for_us_exists_and_is_still_valid(
         &self,
         address: &NetworkAddress,
         data_type: DataTypes,
         payment: ProofOfPayment,
+    ) -> Result<(), PutValidationError> {
+        self.verify_payment_for_us(address, data_type, payment)
+            .await
+            .inspect_err(|err| {
+                self.events_channel()
+                    .broadcast(NodeEvent::PaymentVerificationFailed {
+                        key: address.clone(),
+                        reason: err.to_string(),
+                    });
+            })
+    }
+
+    async fn verify_payment_for_us(
+        &self,
+        address: &NetworkAddress,
+        data_type: DataTypes,
+        payment: ProofOfPayment,
     ) -> Result<(), PutValidationError> {
         let key = address.to_record_key();
         let pretty_key = PrettyPrintRecordKey::from(&key).into_owned();
@@ -801,12 +822,9 @@ impl Node {
         // check if payment is valid on chain
         let payments_to_verify = payment.digest();
         let owned_payment_quotes: Vec<_> = own_quotes.iter().map(|quote| quote.hash()).collect();
-        let reward_amount = match verify_data_payment(
-            self.evm_network(),
-            owned_payment_quotes.clone(),
-            payments_to_verify.clone(),
-        )
-        .await
+        let reward_amount = match self
+            .verify_data_payment_on_chain(owned_payment_quotes.clone(), payments_to_verify.clone())
+            .await
         {
             Ok(amount) => amount,
             Err(e) => {
@@ -816,7 +834,7 @@ impl Node {
                     RETRY_PAYMENT_VERIFICATION_WAIT_TIME_SECS,
                 ))
                 .await;
-                verify_data_payment(self.evm_network(), owned_payment_quotes, payments_to_verify)
+                self.verify_data_payment_on_chain(owned_payment_quotes, payments_to_verify)
                     .await
                     .inspect_err(|e| {
                         warn!("Failed to verify record payment on the second attempt: {e}");
@@ -845,12 +863,10 @@ impl Node {
                     .set(new_value);
             }
 
-            // TODO: currently ignored, re-enable once going to handle this.
-            // self.events_channel()
-            //     .broadcast(crate::NodeEvent::RewardReceived(
-            //         AttoTokens::from(reward_amount),
-            //         address.clone(),
-            //     ));
+            self.events_channel().broadcast(NodeEvent::RewardReceived(
+                AttoTokens::from(reward_amount),
+                address.clone(),
+            ));
 
             // vdash metric (if modified please notify at https://github.com/happybeing/vdash/issues):
             info!(
@@ -871,6 +887,19 @@ impl Node {
         Ok(())
     }
 
+    /// Returns the amount paid to the owned quotes, once the payment has been verified on chain.
+    async fn verify_data_payment_on_chain(
+        &self,
+        owned_quote_hashes: Vec<QuoteHash>,
+        payment: Vec<(QuoteHash, QuotingMetrics, RewardsAddress)>,
+    ) -> Result<Amount, ant_evm::payment_vault::error::Error> {
+        #[cfg(feature = "simulation")]
+        if let Some(evm) = self.simulated_evm() {
+            return evm.verify_data_payment(owned_quote_hashes, payment);
+        }
+        verify_data_payment(self.evm_network(), owned_quote_hashes, payment).await
+    }
+
     /// Get the local GraphEntries for the provided `GraphEntryAddress`
     /// This only fetches the GraphEntries from the local store and does not perform any network operations.
     async fn get_local_graphentries(
@@ -999,6 +1028,8 @@ impl Node {
         };
         self.network()
             .put_local_record(record.clone(), is_client_put);
+        self.events_channel()
+            .broadcast(NodeEvent::PointerStored(pointer.address()));
 
         // Client changed to upload to ALL payees, hence no longer need this.
         // May need again once client change back to upload to just one to save traffic.
@@ -1,5 +1,5 @@
 use crate::{
-    NodeBuilder, RunningNode,
+    FilteredNodeEventsReceiver, NodeBuilder, NodeEvent, NodeEventFilter, RunningNode,
     spawn::{
         network_spawner::{NetworkSpawner, RunningNetwork},
         node_spawner::NodeSpawner,
@@ -286,6 +286,64 @@ impl PyAntNode {
             .ok_or_else(|| PyValueError::new_err("Invalid path encoding"))?
             .to_string())
     }
+
+    /// Subscribe to the events broadcasted by the node.
+    /// Only the events of the given kinds are received, or all of them if none is given.
+    #[pyo3(signature = (kinds = vec![]))]
+    fn subscribe_events(self_: PyRef<Self>, kinds: Vec<String>) -> PyResult<PyNodeEventsReceiver> {
+        let filter = NodeEventFilter::from_names(&kinds)
+            .map_err(|e| PyValueError::new_err(format!("Invalid event kind: {e}")))?;
+
+        let node_guard = self_
+            .node
+            .try_read()
+            .map_err(|_| PyRuntimeError::new_err("Failed to acquire node lock"))?;
+
+        let receiver = node_guard.node_events_channel().subscribe_filtered(filter);
+        Ok(PyNodeEventsReceiver(Arc::new(Mutex::new(receiver))))
+    }
+}
+
+/// Python wrapper for a `NodeEvent`
+#[pyclass(name = "NodeEvent")]
+pub struct PyNodeEvent(NodeEvent);
+
+#[pymethods]
+impl PyNodeEvent {
+    /// The kind of the event, e.g. "ChunkStored"
+    #[getter]
+    fn kind(&self) -> String {
+        self.0.kind().to_string()
+    }
+
+    /// A human readable description of the event and its content
+    #[getter]
+    fn details(&self) -> String {
+        format!("{:?}", self.0)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("NodeEvent({:?})", self.0)
+    }
+}
+
+/// Receiver of the events broadcasted by a node
+#[pyclass(name = "NodeEventsReceiver")]
+pub struct PyNodeEventsReceiver(Arc<Mutex<FilteredNodeEventsReceiver>>);
+
+#[pymethods]
+impl PyNodeEventsReceiver {
+    /// Wait for the next event
+    fn recv<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let receiver = Arc::clone(&self.0);
+        future_into_py(py, async move {
+            let event =
+                receiver.lock().await.recv().await.map_err(|e| {
+                    PyRuntimeError::new_err(format!("Failed to receive event: {e}"))
+                })?;
+            Ok(PyNodeEvent(event))
+        })
+    }
 }
 
 #[pyclass(name = "RunningNetwork")]
@@ -575,6 +633,8 @@ impl PyNetwork {
 #[pyo3(name = "_antnode")]
 fn init_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyAntNode>()?;
+    m.add_class::<PyNodeEvent>()?;
+    m.add_class::<PyNodeEventsReceiver>()?;
     m.add_class::<PyNodeSpawner>()?;
     m.add_class::<PyNetworkSpawner>()?;
     m.add_class::<PyRunningNetwork>()?;
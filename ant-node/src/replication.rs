@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::networking::Network;
-use crate::{error::Result, node::Node};
+use crate::{NodeEvent, error::Result, node::Node};
 use ant_evm::ProofOfPayment;
 use ant_protocol::{
     NetworkAddress, PrettyPrintRecordKey,
@@ -60,12 +60,13 @@ impl Node {
                     )) => match result {
                         Ok((_holder, record_content)) => {
                             debug!("Fecthed record {pretty_key:?} from holder {holder:?}");
-                            Record::new(key, record_content.to_vec())
+                            Record::new(key.clone(), record_content.to_vec())
                         }
                         Err(err) => {
                             info!(
                                 "Failed fetch record {pretty_key:?} from holder {holder:?}, with error {err:?}"
                             );
+                            node.notify_replication_fetch_failed(&key, holder, format!("{err:?}"));
                             return;
                         }
                     },
@@ -73,12 +74,18 @@ impl Node {
                         info!(
                             "Cannot fetch record {pretty_key:?} from holder {holder:?}, with response {other:?}"
                         );
+                        node.notify_replication_fetch_failed(
+                            &key,
+                            holder,
+                            format!("Unexpected response {other:?}"),
+                        );
                         return;
                     }
                     Err(err) => {
                         info!(
                             "Failed to send request to fetch record {pretty_key:?} from holder {holder:?}, with error {err:?}"
                         );
+                        node.notify_replication_fetch_failed(&key, holder, format!("{err:?}"));
                         return;
                     }
                 };
@@ -87,16 +94,32 @@ impl Node {
                     error!(
                         "During store replication fetched {pretty_key:?} from holder {holder:?}, got error {err:?}"
                     );
+                    node.notify_replication_fetch_failed(&key, holder, format!("{err:?}"));
                 } else {
                     debug!(
                         "Completed storing Replication Record {pretty_key:?} from holder {holder:?}."
                     );
+                    node.events_channel()
+                        .broadcast(NodeEvent::ReplicatedRecordFetched {
+                            key: NetworkAddress::from(&key),
+                            holder: NetworkAddress::from(holder),
+                        });
                 }
             });
         }
         Ok(())
     }
 
+    /// Broadcast that fetching a record from a holder during replication failed.
+    fn notify_replication_fetch_failed(&self, key: &RecordKey, holder: PeerId, reason: String) {
+        self.events_channel()
+            .broadcast(NodeEvent::ReplicationFetchFailed {
+                key: NetworkAddress::from(key),
+                holder: NetworkAddress::from(holder),
+                reason,
+            });
+    }
+
     // Client changed to upload to ALL payees, hence no longer need this.
     // May need again once client change back to upload to just one to save traffic.
     //
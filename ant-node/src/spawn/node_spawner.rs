@@ -9,6 +9,7 @@
 use crate::utils::get_root_dir_and_keypair;
 use crate::{NodeBuilder, RunningNode};
 pub use ant_evm::{EvmNetwork, RewardsAddress};
+use ant_protocol::version::NetworkContext;
 pub use libp2p::Multiaddr;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
@@ -27,6 +28,8 @@ pub struct NodeSpawner {
     local: bool,
     /// A boolean indicating whether UPnP should be disabled.
     no_upnp: bool,
+    /// The network the node takes part in. Defaults to the process-wide network ID.
+    network_id: Option<u8>,
     /// An optional `PathBuf` representing the root directory for the node.
     root_dir: Option<PathBuf>,
 }
@@ -41,6 +44,7 @@ impl NodeSpawner {
             initial_peers: vec![],
             local: false,
             no_upnp: false,
+            network_id: None,
             root_dir: None,
         }
     }
@@ -105,6 +109,16 @@ impl NodeSpawner {
         self
     }
 
+    /// Set the network the node takes part in.
+    ///
+    /// # Arguments
+    ///
+    /// * `network_id` - The ID of the network, used in the protocol strings of the node.
+    pub fn with_network_id(mut self, network_id: u8) -> Self {
+        self.network_id = Some(network_id);
+        self
+    }
+
     /// Set the root directory for the node.
     ///
     /// # Arguments
@@ -128,6 +142,7 @@ impl NodeSpawner {
             self.initial_peers,
             self.local,
             self.no_upnp,
+            self.network_id,
             &self.root_dir,
         )
         .await
@@ -140,6 +155,7 @@ impl Default for NodeSpawner {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn spawn_node(
     socket_addr: SocketAddr,
     evm_network: EvmNetwork,
@@ -147,6 +163,7 @@ async fn spawn_node(
     initial_peers: Vec<Multiaddr>,
     local: bool,
     no_upnp: bool,
+    network_id: Option<u8>,
     root_dir: &Option<PathBuf>,
 ) -> eyre::Result<RunningNode> {
     let (root_dir, keypair) = get_root_dir_and_keypair(root_dir)?;
@@ -161,6 +178,9 @@ async fn spawn_node(
     );
     node_builder.local(local);
     node_builder.no_upnp(no_upnp);
+    if let Some(network_id) = network_id {
+        node_builder.network_context(NetworkContext::new(network_id));
+    }
 
     let running_node = node_builder.build_and_run()?;
 
@@ -0,0 +1,660 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! In-process network simulator, for exercising churn and replication deterministically.
+//!
+//! All the nodes of a [`SimulatedNetwork`] run within the current process, connected over the
+//! libp2p memory transport. The links between them are driven by the shared [`NetworkConditions`]
+//! (latency, packet loss and partitions), nodes can be crashed and restarted at will, and payments
+//! are settled against a [`SimulatedEvm`] ledger instead of a blockchain.
+//!
+//! The nodes run their periodic tasks on the tokio timers. Running the simulation within a paused
+//! runtime (e.g. `#[tokio::test(start_paused = true)]`) allows fast forwarding them with
+//! [`SimulatedNetwork::advance_clock`].
+
+pub use crate::networking::NetworkConditions;
+use crate::networking::{Addresses, Network};
+use crate::{NodeBuilder, RunningNode};
+use ant_evm::payment_vault::error::Error as PaymentError;
+use ant_evm::{
+    Amount, EncodedPeerId, EvmNetwork, PaymentQuote, ProofOfPayment, QuoteHash, QuotingMetrics,
+    RewardsAddress,
+};
+use ant_protocol::{
+    CLOSE_GROUP_SIZE, NetworkAddress,
+    messages::{Query, QueryResponse, Request, Response},
+    storage::{
+        Chunk, ChunkAddress, DataTypes, RecordKind, try_deserialize_record, try_serialize_record,
+    },
+};
+use libp2p::{Multiaddr, PeerId, identity::Keypair, kad::Record};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+/// The price paid by the simulated clients for each quote.
+const SIMULATED_QUOTE_PRICE: u64 = 1_000;
+
+/// Stub of the payment vault contract, recording the payments in memory.
+#[derive(Clone, Debug, Default)]
+pub struct SimulatedEvm {
+    payments: Arc<Mutex<HashMap<QuoteHash, (RewardsAddress, Amount)>>>,
+}
+
+impl SimulatedEvm {
+    fn payments(&self) -> MutexGuard<'_, HashMap<QuoteHash, (RewardsAddress, Amount)>> {
+        // The ledger is always left consistent, hence it's fine to recover from a poisoned lock.
+        self.payments
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Pays `amount` for each of the quotes, to the rewards address of the quote.
+    pub fn pay(
+        &self,
+        quotes: impl IntoIterator<Item = (QuoteHash, RewardsAddress)>,
+        amount: Amount,
+    ) {
+        let mut payments = self.payments();
+        for (quote_hash, rewards_address) in quotes {
+            let _ = payments.insert(quote_hash, (rewards_address, amount));
+        }
+    }
+
+    /// The total amount paid to the rewards address.
+    pub fn balance_of(&self, rewards_address: &RewardsAddress) -> Amount {
+        self.payments()
+            .values()
+            .filter(|(address, _)| address == rewards_address)
+            .fold(Amount::ZERO, |total, (_, amount)| total + *amount)
+    }
+
+    /// Mirrors `payment_vault::verify_data_payment`: fails if any of the quotes hasn't been paid to
+    /// its rewards address, otherwise returns the amount paid to the owned quotes.
+    pub(crate) fn verify_data_payment(
+        &self,
+        owned_quote_hashes: Vec<QuoteHash>,
+        payment: Vec<(QuoteHash, QuotingMetrics, RewardsAddress)>,
+    ) -> Result<Amount, PaymentError> {
+        let payments = self.payments();
+        let mut amount = Amount::ZERO;
+        for (quote_hash, _, rewards_address) in payment {
+            match payments.get(&quote_hash) {
+                Some((paid_address, paid)) if *paid_address == rewards_address => {
+                    if owned_quote_hashes.contains(&quote_hash) {
+                        amount += *paid;
+                    }
+                }
+                _ => return Err(PaymentError::PaymentInvalid),
+            }
+        }
+        Ok(amount)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkSimulator {
+    /// Drives the identities of the nodes and the packet loss.
+    seed: u64,
+    /// Optional root directory to store the data of the nodes.
+    root_dir: Option<PathBuf>,
+    /// Number of nodes to spawn in the network.
+    size: usize,
+}
+
+impl NetworkSimulator {
+    /// Creates a new `NetworkSimulator` with default configurations.
+    ///
+    /// Default values:
+    /// - `seed`: `0`
+    /// - `root_dir`: `None`, a fresh directory within the temp dir is used, and removed along with
+    ///   the network
+    /// - `size`: `10`
+    pub fn new() -> Self {
+        Self {
+            seed: 0,
+            root_dir: None,
+            size: 10,
+        }
+    }
+
+    /// Sets the seed of the simulation. The same seed yields the same peer ids, rewards addresses
+    /// and packet loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the random generators.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the root directory for the nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_dir` - An optional file path where nodes will store their data.
+    pub fn with_root_dir(mut self, root_dir: Option<PathBuf>) -> Self {
+        self.root_dir = root_dir;
+        self
+    }
+
+    /// Specifies the number of nodes to spawn in the network.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The number of nodes to create. Default is 10.
+    pub fn with_size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Spawns the simulated network, with perfect network conditions.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to a `SimulatedNetwork` once all the nodes are listening.
+    pub async fn spawn(self) -> eyre::Result<SimulatedNetwork> {
+        let (root_dir, temp_dir) = match self.root_dir {
+            Some(root_dir) => (root_dir, None),
+            None => {
+                let temp_dir = tempfile::Builder::new()
+                    .prefix("ant-node-simulation-")
+                    .tempdir()?;
+                (temp_dir.path().to_path_buf(), Some(temp_dir))
+            }
+        };
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let mut network = SimulatedNetwork {
+            conditions: NetworkConditions::new(self.seed),
+            evm: SimulatedEvm::default(),
+            nodes: Vec::with_capacity(self.size),
+            _temp_dir: temp_dir,
+        };
+        for index in 0..self.size {
+            let keypair = Keypair::ed25519_from_bytes(rng.r#gen::<[u8; 32]>())?;
+            network.nodes.push(SimulatedNode {
+                peer_id: keypair.public().to_peer_id(),
+                keypair,
+                rewards_address: RewardsAddress::new(rng.r#gen::<[u8; 20]>()),
+                root_dir: root_dir.join(format!("node-{index}")),
+                running: None,
+            });
+            network.restart(index).await?;
+        }
+
+        Ok(network)
+    }
+}
+
+impl Default for NetworkSimulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct SimulatedNode {
+    keypair: Keypair,
+    peer_id: PeerId,
+    rewards_address: RewardsAddress,
+    root_dir: PathBuf,
+    /// `None` while the node is crashed.
+    running: Option<RunningNode>,
+}
+
+pub struct SimulatedNetwork {
+    conditions: NetworkConditions,
+    evm: SimulatedEvm,
+    nodes: Vec<SimulatedNode>,
+    /// The data of the nodes when no root directory was provided, removed once they are dropped.
+    _temp_dir: Option<tempfile::TempDir>,
+}
+
+impl SimulatedNetwork {
+    /// Returns the number of nodes, including the crashed ones.
+    pub fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the conditions of the links between the nodes.
+    pub fn conditions(&self) -> &NetworkConditions {
+        &self.conditions
+    }
+
+    /// Returns the ledger the payments are settled against.
+    pub fn evm(&self) -> &SimulatedEvm {
+        &self.evm
+    }
+
+    /// Returns the `PeerId` of the node, which is kept across restarts.
+    pub fn peer_id(&self, index: usize) -> Option<PeerId> {
+        self.nodes.get(index).map(|node| node.peer_id)
+    }
+
+    /// Returns the rewards address of the node.
+    pub fn rewards_address(&self, index: usize) -> Option<RewardsAddress> {
+        self.nodes.get(index).map(|node| node.rewards_address)
+    }
+
+    /// Returns the node, `None` if it's crashed.
+    pub fn running_node(&self, index: usize) -> Option<&RunningNode> {
+        self.nodes.get(index)?.running.as_ref()
+    }
+
+    /// Splits the network in the provided groups of node indexes.
+    /// See [`NetworkConditions::partition`].
+    pub fn partition(&self, groups: &[Vec<usize>]) {
+        let groups: Vec<Vec<PeerId>> = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .filter_map(|index| self.peer_id(*index))
+                    .collect()
+            })
+            .collect();
+        self.conditions.partition(&groups);
+    }
+
+    /// Cuts the node off from the rest of the network.
+    pub fn isolate(&self, index: usize) {
+        if let Some(peer_id) = self.peer_id(index) {
+            self.conditions.isolate(peer_id);
+        }
+    }
+
+    /// Removes all the partitions.
+    pub fn heal(&self) {
+        self.conditions.heal();
+    }
+
+    /// Stops the node abruptly. Its data is kept on disk for a later restart.
+    pub fn crash(&mut self, index: usize) {
+        if let Some(running) = self
+            .nodes
+            .get_mut(index)
+            .and_then(|node| node.running.take())
+        {
+            info!("Simulation: crashing node #{index} {:?}", running.peer_id());
+            running.shutdown();
+        }
+    }
+
+    /// Starts the node again, with the same identity and data. It bootstraps off the running
+    /// nodes.
+    pub async fn restart(&mut self, index: usize) -> eyre::Result<()> {
+        let mut initial_peers: Vec<Multiaddr> = vec![];
+        for node in self.nodes.iter().filter_map(|node| node.running.as_ref()) {
+            if let Ok(listen_addrs) = node.get_listen_addrs_with_peer_id().await {
+                initial_peers.extend(listen_addrs);
+            }
+        }
+
+        let node = self
+            .nodes
+            .get_mut(index)
+            .ok_or_else(|| eyre::eyre!("No simulated node #{index}"))?;
+        if node.running.is_some() {
+            eyre::bail!("Simulated node #{index} is already running");
+        }
+        std::fs::create_dir_all(&node.root_dir)?;
+
+        let mut node_builder = NodeBuilder::new(
+            node.keypair.clone(),
+            initial_peers,
+            node.rewards_address,
+            EvmNetwork::default(),
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0),
+            node.root_dir.clone(),
+        );
+        node_builder.no_upnp(true);
+        node_builder.simulation(self.conditions.clone(), self.evm.clone());
+        let running_node = node_builder.build_and_run()?;
+
+        // Wait till the node is listening
+        let mut retries: u8 = 0;
+        loop {
+            if let Ok(listen_addrs) = running_node.get_listen_addrs().await
+                && !listen_addrs.is_empty()
+            {
+                info!(
+                    "Simulation: node #{index} {:?} listening on {listen_addrs:?}",
+                    node.peer_id
+                );
+                break;
+            }
+            if retries >= 10 {
+                running_node.shutdown();
+                eyre::bail!("Simulated node #{index} failed to listen");
+            }
+            retries += 1;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        node.running = Some(running_node);
+        Ok(())
+    }
+
+    /// Fast forwards the clock of the nodes.
+    ///
+    /// The simulation must run within a current thread runtime with the time paused, see
+    /// `tokio::time::pause`. Only the tokio timers are accelerated, i.e. the periodic tasks of the
+    /// nodes and the simulated latency, not the libp2p internal timeouts.
+    pub async fn advance_clock(&self, duration: Duration) {
+        tokio::time::advance(duration).await;
+    }
+
+    /// Returns the indexes of the running nodes holding the record.
+    pub async fn holders(&self, address: &NetworkAddress) -> Vec<usize> {
+        let key = address.to_record_key();
+        let mut holders = vec![];
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let Some(running) = &node.running
+                && let Ok(addresses) = running.get_all_record_addresses().await
+                && addresses.iter().any(|held| held.to_record_key() == key)
+            {
+                holders.push(index);
+            }
+        }
+        holders
+    }
+
+    /// Returns a client reaching the network through the node, which acts as its gateway.
+    pub fn client(&self, gateway: usize) -> eyre::Result<SimulatedClient> {
+        let running = self
+            .running_node(gateway)
+            .ok_or_else(|| eyre::eyre!("Simulated node #{gateway} is not running"))?;
+        Ok(SimulatedClient {
+            network: running.network.clone(),
+            evm: self.evm.clone(),
+        })
+    }
+
+    /// Shutdown all running nodes.
+    pub fn shutdown(self) {
+        for running in self.nodes.into_iter().filter_map(|node| node.running) {
+            running.shutdown();
+        }
+    }
+}
+
+/// A client uploading and downloading through a gateway node, paying with the [`SimulatedEvm`].
+///
+/// This is not an `autonomi::Client`: that one dials the nodes over its own transport and pays on
+/// an actual EVM network, neither of which can reach the simulation. The requests it sends are
+/// the same, but the client side logic (retries, quorum, self-encryption...) is not exercised.
+#[derive(Clone)]
+pub struct SimulatedClient {
+    network: Network,
+    evm: SimulatedEvm,
+}
+
+impl SimulatedClient {
+    /// The closest peers to the address, the gateway excluded.
+    async fn closest_peers(
+        &self,
+        address: &NetworkAddress,
+    ) -> eyre::Result<Vec<(PeerId, Addresses)>> {
+        let self_peer_id = self.network.peer_id();
+        let mut peers = self.network.get_closest_peers(address).await?;
+        peers.retain(|(peer_id, _)| *peer_id != self_peer_id);
+        peers.sort_by_key(|(peer_id, _)| NetworkAddress::from(*peer_id).distance(address));
+        peers.truncate(CLOSE_GROUP_SIZE);
+        Ok(peers)
+    }
+
+    /// Quotes, pays and uploads the chunk to its close group.
+    /// Succeeds once the majority of the close group has stored it.
+    pub async fn put_chunk(&self, chunk: &Chunk) -> eyre::Result<()> {
+        let address = chunk.network_address();
+        let mut quotes: Vec<(PeerId, Addresses, PaymentQuote)> = vec![];
+        for (peer_id, addrs) in self.closest_peers(&address).await? {
+            let request = Request::Query(Query::GetStoreQuote {
+                key: address.clone(),
+                data_type: DataTypes::Chunk.get_index(),
+                data_size: chunk.size(),
+                nonce: None,
+                difficulty: 0,
+            });
+            match self
+                .network
+                .send_request(request, peer_id, addrs.clone())
+                .await
+            {
+                Ok((
+                    Response::Query(QueryResponse::GetStoreQuote {
+                        quote: Ok(quote), ..
+                    }),
+                    _,
+                )) => quotes.push((peer_id, addrs, quote)),
+                Ok((response, _)) => {
+                    warn!("Simulation: unexpected quote response from {peer_id:?}: {response:?}")
+                }
+                Err(err) => warn!("Simulation: failed to get a quote from {peer_id:?}: {err:?}"),
+            }
+        }
+
+        let majority = CLOSE_GROUP_SIZE / 2 + 1;
+        if quotes.len() < majority {
+            eyre::bail!("Only got {} quotes for {address:?}", quotes.len());
+        }
+
+        self.evm.pay(
+            quotes
+                .iter()
+                .map(|(_, _, quote)| (quote.hash(), quote.rewards_address)),
+            Amount::from(SIMULATED_QUOTE_PRICE),
+        );
+        let payment = ProofOfPayment {
+            peer_quotes: quotes
+                .iter()
+                .map(|(peer_id, _, quote)| (EncodedPeerId::from(*peer_id), quote.clone()))
+                .collect(),
+        };
+        let serialized_record = try_serialize_record(
+            &(payment, chunk.clone()),
+            RecordKind::DataWithPayment(DataTypes::Chunk),
+        )?
+        .to_vec();
+
+        let mut stored = 0;
+        for (peer_id, addrs, _) in quotes {
+            let request = Request::Query(Query::PutRecord {
+                holder: NetworkAddress::from(peer_id),
+                serialized_record: serialized_record.clone(),
+                address: address.clone(),
+            });
+            match self.network.send_request(request, peer_id, addrs).await {
+                Ok((Response::Query(QueryResponse::PutRecord { result: Ok(()), .. }), _)) => {
+                    stored += 1;
+                }
+                Ok((response, _)) => {
+                    warn!("Simulation: {peer_id:?} failed to store {address:?}: {response:?}")
+                }
+                Err(err) => {
+                    warn!("Simulation: failed to store {address:?} to {peer_id:?}: {err:?}")
+                }
+            }
+        }
+
+        if stored < majority {
+            eyre::bail!("Only {stored} nodes stored {address:?}");
+        }
+        Ok(())
+    }
+
+    /// Fetches the chunk from the gateway, or else from the close group.
+    pub async fn get_chunk(&self, address: &ChunkAddress) -> eyre::Result<Chunk> {
+        let address = NetworkAddress::from(*address);
+        let key = address.to_record_key();
+        if let Ok(Some(record)) = self.network.get_local_record(&key).await {
+            return Ok(try_deserialize_record(&record)?);
+        }
+
+        for (peer_id, addrs) in self.closest_peers(&address).await? {
+            let request = Request::Query(Query::GetReplicatedRecord {
+                requester: NetworkAddress::from(self.network.peer_id()),
+                key: NetworkAddress::from(&key),
+            });
+            match self.network.send_request(request, peer_id, addrs).await {
+                Ok((Response::Query(QueryResponse::GetReplicatedRecord(Ok((_, value)))), _)) => {
+                    let record = Record::new(key.clone(), value.to_vec());
+                    match try_deserialize_record::<Chunk>(&record) {
+                        Ok(chunk) if chunk.network_address() == address => return Ok(chunk),
+                        other => {
+                            warn!("Simulation: {peer_id:?} returned an invalid chunk: {other:?}")
+                        }
+                    }
+                }
+                Ok((response, _)) => {
+                    debug!("Simulation: {peer_id:?} doesn't hold {address:?}: {response:?}")
+                }
+                Err(err) => {
+                    warn!("Simulation: failed to fetch {address:?} from {peer_id:?}: {err:?}")
+                }
+            }
+        }
+
+        eyre::bail!("Failed to fetch {address:?}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    fn metrics() -> QuotingMetrics {
+        QuotingMetrics {
+            data_size: 0,
+            data_type: 0,
+            close_records_stored: 0,
+            records_per_type: vec![],
+            max_records: 0,
+            received_payment_count: 0,
+            live_time: 0,
+            network_density: None,
+            network_size: None,
+        }
+    }
+
+    #[test]
+    fn simulated_evm_should_only_accept_paid_quotes() {
+        let evm = SimulatedEvm::default();
+        let [owned, other, unpaid] = [[1u8; 32], [2; 32], [3; 32]].map(QuoteHash::from);
+        let [our_address, their_address] = [[1u8; 20], [2; 20]].map(RewardsAddress::new);
+        evm.pay(
+            [(owned, our_address), (other, their_address)],
+            Amount::from(10u64),
+        );
+
+        let payment = vec![
+            (owned, metrics(), our_address),
+            (other, metrics(), their_address),
+        ];
+        assert_eq!(
+            evm.verify_data_payment(vec![owned], payment.clone()).ok(),
+            Some(Amount::from(10u64))
+        );
+        assert_eq!(evm.balance_of(&our_address), Amount::from(10u64));
+
+        let mut with_unpaid = payment.clone();
+        with_unpaid.push((unpaid, metrics(), our_address));
+        assert!(evm.verify_data_payment(vec![owned], with_unpaid).is_err());
+
+        let mut wrong_payee = payment;
+        wrong_payee[1].2 = our_address;
+        assert!(evm.verify_data_payment(vec![owned], wrong_payee).is_err());
+    }
+
+    async fn wait_for_holders(network: &SimulatedNetwork, address: &NetworkAddress, min: usize) {
+        for _ in 0..30 {
+            if network.holders(address).await.len() >= min {
+                return;
+            }
+            network.advance_clock(Duration::from_secs(1)).await;
+        }
+        panic!("{address:?} isn't held by {min} nodes");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn chunk_should_survive_churn_and_partitions() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let mut network = NetworkSimulator::new()
+            .with_seed(42)
+            .with_size(12)
+            .with_root_dir(Some(root_dir.path().to_path_buf()))
+            .spawn()
+            .await
+            .unwrap();
+        // Let the nodes fill up their RT
+        network.advance_clock(Duration::from_secs(5)).await;
+
+        let client = network.client(0).unwrap();
+        let chunk = Chunk::new(Bytes::from_static(b"simulated chunk"));
+        let address = chunk.network_address();
+        client.put_chunk(&chunk).await.unwrap();
+        wait_for_holders(&network, &address, CLOSE_GROUP_SIZE / 2 + 1).await;
+
+        // The holders got paid through the stubbed EVM
+        let holders = network.holders(&address).await;
+        assert!(holders.iter().any(|index| {
+            network
+                .rewards_address(*index)
+                .is_some_and(|address| !network.evm().balance_of(&address).is_zero())
+        }));
+
+        // Crash a holder, the chunk is still retrievable and the holder gets it back on restart
+        let crashed = holders[0];
+        network.crash(crashed);
+        assert!(network.running_node(crashed).is_none());
+        let fetched = client.get_chunk(chunk.address()).await.unwrap();
+        assert_eq!(fetched, chunk);
+        network.restart(crashed).await.unwrap();
+        assert!(network.holders(&address).await.contains(&crashed));
+
+        // An isolated gateway can't reach the network until the partition heals
+        network.isolate(0);
+        let other_chunk = Chunk::new(Bytes::from_static(b"another simulated chunk"));
+        assert!(client.put_chunk(&other_chunk).await.is_err());
+        network.heal();
+        network.advance_clock(Duration::from_secs(2)).await;
+        client.put_chunk(&other_chunk).await.unwrap();
+
+        network.shutdown();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn latency_should_run_on_the_accelerated_clock() {
+        let network = NetworkSimulator::new()
+            .with_seed(7)
+            .with_size(8)
+            .spawn()
+            .await
+            .unwrap();
+        network.advance_clock(Duration::from_secs(60)).await;
+
+        network.conditions().set_latency(Duration::from_millis(500));
+        let started = tokio::time::Instant::now();
+        let real_started = std::time::Instant::now();
+        let chunk = Chunk::new(Bytes::from_static(b"delayed chunk"));
+        network.client(0).unwrap().put_chunk(&chunk).await.unwrap();
+
+        // Each quote and store round trip is delayed, without the test waiting for it for real
+        assert!(started.elapsed() >= Duration::from_secs(2));
+        assert!(real_started.elapsed() < started.elapsed());
+
+        network.shutdown();
+    }
+}
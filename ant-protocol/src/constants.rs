@@ -18,6 +18,10 @@ pub const MAX_PACKET_SIZE: usize = 1024 * 1024 * 5;
 /// The size has been set to 5 for improved performance.
 pub const CLOSE_GROUP_SIZE: usize = 5;
 
+/// The maximum number of keys a node accepts in a single batched query
+/// (`GetReplicatedRecords` or `CheckRecordsExist`). Keys above it are left unprocessed.
+pub const MAX_KEYS_PER_BATCH_QUERY: usize = 256;
+
 /// The protocol ID for the Kademlia stream
 pub const KAD_STREAM_PROTOCOL_ID: &str = "/autonomi/kad/1.0.0";
 
@@ -9,6 +9,7 @@
 //! Data messages and their possible responses.
 mod chunk_proof;
 mod cmd;
+mod codec;
 mod connection_info;
 mod node_id;
 mod query;
@@ -17,6 +18,7 @@ mod response;
 pub use self::{
     chunk_proof::{ChunkProof, Nonce},
     cmd::Cmd,
+    codec::{CompressionMetrics, MessageCodec},
     connection_info::ConnectionInfo,
     node_id::NodeId,
     query::Query,
@@ -0,0 +1,371 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{Request, Response};
+use crate::version::COMPRESSED_PROTOCOL_SUFFIX;
+use async_trait::async_trait;
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, io::Cursor};
+use libp2p::{
+    StreamProtocol,
+    request_response::{Codec, cbor::codec::Codec as CborCodec},
+};
+use prometheus_client::{metrics::counter::Counter, registry::Registry};
+use std::io::{self, Read};
+
+/// zstd compression level, favouring speed as nodes handle a lot of messages.
+const COMPRESSION_LEVEL: i32 = 1;
+/// Messages smaller than this aren't worth compressing.
+const MIN_COMPRESSION_SIZE: usize = 256;
+/// Larger messages are first compressed on a sample of this size, to skip encrypted data cheaply.
+const COMPRESSION_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Flag byte prefixing every message sent on the compressed protocol.
+const FLAG_UNCOMPRESSED: u8 = 0;
+const FLAG_ZSTD: u8 = 1;
+
+/// Metrics on the compression of the messages we send.
+#[derive(Clone, Debug, Default)]
+pub struct CompressionMetrics {
+    /// Number of messages sent compressed.
+    pub compressed_messages: Counter,
+    /// Bytes saved by compressing the messages we sent.
+    pub bytes_saved: Counter,
+}
+
+impl CompressionMetrics {
+    /// Register the metrics within the provided registry.
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "compressed_messages",
+            "Number of request/response messages sent compressed",
+            self.compressed_messages.clone(),
+        );
+        registry.register(
+            "compression_bytes_saved",
+            "Number of bytes saved by compressing the request/response messages we sent",
+            self.bytes_saved.clone(),
+        );
+    }
+}
+
+/// Codec for [`Request`]s and [`Response`]s.
+///
+/// Messages are CBOR encoded, as they always have been. On protocols ending with
+/// [`COMPRESSED_PROTOCOL_SUFFIX`], which peers only negotiate when both support it, messages are
+/// also compressed with zstd whenever that makes them smaller.
+#[derive(Clone)]
+pub struct MessageCodec {
+    inner: CborCodec<Request, Response>,
+    request_size_maximum: u64,
+    response_size_maximum: u64,
+    metrics: CompressionMetrics,
+}
+
+impl Default for MessageCodec {
+    fn default() -> Self {
+        // Same limits as the CBOR codec defaults
+        Self {
+            inner: CborCodec::default(),
+            request_size_maximum: 1024 * 1024,
+            response_size_maximum: 10 * 1024 * 1024,
+            metrics: CompressionMetrics::default(),
+        }
+    }
+}
+
+impl MessageCodec {
+    /// Sets the limit for request size in bytes.
+    pub fn set_request_size_maximum(mut self, request_size_maximum: u64) -> Self {
+        self.inner = self.inner.set_request_size_maximum(request_size_maximum);
+        self.request_size_maximum = request_size_maximum;
+        self
+    }
+
+    /// Sets the limit for response size in bytes.
+    pub fn set_response_size_maximum(mut self, response_size_maximum: u64) -> Self {
+        self.inner = self.inner.set_response_size_maximum(response_size_maximum);
+        self.response_size_maximum = response_size_maximum;
+        self
+    }
+
+    /// Sets the metrics the codec reports compression to.
+    pub fn with_metrics(mut self, metrics: CompressionMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Compresses an encoded message if it is worth it, prefixing it with the matching flag.
+    fn compress(&self, data: Vec<u8>) -> Vec<u8> {
+        let compressed = try_compress(&data);
+        match compressed {
+            Some(compressed) => {
+                self.metrics.compressed_messages.inc();
+                self.metrics
+                    .bytes_saved
+                    .inc_by((data.len() - compressed.len()) as u64);
+                let mut framed = Vec::with_capacity(compressed.len() + 1);
+                framed.push(FLAG_ZSTD);
+                framed.extend(compressed);
+                framed
+            }
+            None => {
+                let mut framed = Vec::with_capacity(data.len() + 1);
+                framed.push(FLAG_UNCOMPRESSED);
+                framed.extend(data);
+                framed
+            }
+        }
+    }
+}
+
+fn is_compressed_protocol(protocol: &StreamProtocol) -> bool {
+    protocol.as_ref().ends_with(COMPRESSED_PROTOCOL_SUFFIX)
+}
+
+/// Returns the zstd compressed data, or `None` if compressing doesn't make it smaller.
+fn try_compress(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < MIN_COMPRESSION_SIZE {
+        return None;
+    }
+
+    // Encrypted data doesn't compress, find out on a sample before compressing it all
+    if data.len() > COMPRESSION_SAMPLE_SIZE {
+        let sample = &data[..COMPRESSION_SAMPLE_SIZE];
+        let compressed_sample = zstd::bulk::compress(sample, COMPRESSION_LEVEL).ok()?;
+        if compressed_sample.len() * 10 > sample.len() * 9 {
+            return None;
+        }
+    }
+
+    let compressed = zstd::bulk::compress(data, COMPRESSION_LEVEL).ok()?;
+    (compressed.len() < data.len()).then_some(compressed)
+}
+
+/// Strips the flag byte off a message read on the compressed protocol and decompresses it,
+/// refusing to decompress more than `size_maximum` bytes.
+fn decompress(framed: Vec<u8>, size_maximum: u64) -> io::Result<Vec<u8>> {
+    match framed.split_first() {
+        Some((&FLAG_UNCOMPRESSED, data)) => Ok(data.to_vec()),
+        Some((&FLAG_ZSTD, data)) => {
+            let mut decompressed = Vec::new();
+            zstd::stream::read::Decoder::new(data)?
+                .take(size_maximum + 1)
+                .read_to_end(&mut decompressed)?;
+            if decompressed.len() as u64 > size_maximum {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Decompressed message exceeds the maximum size",
+                ));
+            }
+            Ok(decompressed)
+        }
+        Some((flag, _)) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unknown compression flag {flag}"),
+        )),
+        None => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Empty message",
+        )),
+    }
+}
+
+#[async_trait]
+impl Codec for MessageCodec {
+    type Protocol = StreamProtocol;
+    type Request = Request;
+    type Response = Response;
+
+    async fn read_request<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        io: &mut T,
+    ) -> io::Result<Request>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        if !is_compressed_protocol(protocol) {
+            return self.inner.read_request(protocol, io).await;
+        }
+
+        let mut framed = Vec::new();
+        io.take(self.request_size_maximum + 1)
+            .read_to_end(&mut framed)
+            .await?;
+        let data = decompress(framed, self.request_size_maximum)?;
+        self.inner
+            .read_request(protocol, &mut Cursor::new(data))
+            .await
+    }
+
+    async fn read_response<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        io: &mut T,
+    ) -> io::Result<Response>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        if !is_compressed_protocol(protocol) {
+            return self.inner.read_response(protocol, io).await;
+        }
+
+        let mut framed = Vec::new();
+        io.take(self.response_size_maximum + 1)
+            .read_to_end(&mut framed)
+            .await?;
+        let data = decompress(framed, self.response_size_maximum)?;
+        self.inner
+            .read_response(protocol, &mut Cursor::new(data))
+            .await
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        io: &mut T,
+        req: Request,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        if !is_compressed_protocol(protocol) {
+            return self.inner.write_request(protocol, io, req).await;
+        }
+
+        let mut encoded = Cursor::new(Vec::new());
+        self.inner
+            .write_request(protocol, &mut encoded, req)
+            .await?;
+        io.write_all(&self.compress(encoded.into_inner())).await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        io: &mut T,
+        res: Response,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        if !is_compressed_protocol(protocol) {
+            return self.inner.write_response(protocol, io, res).await;
+        }
+
+        let mut encoded = Cursor::new(Vec::new());
+        self.inner
+            .write_response(protocol, &mut encoded, res)
+            .await?;
+        io.write_all(&self.compress(encoded.into_inner())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        NetworkAddress,
+        messages::{Cmd, Query},
+    };
+    use futures::executor::block_on;
+    use libp2p::PeerId;
+
+    fn plain_protocol() -> StreamProtocol {
+        StreamProtocol::new("/ant/test/1")
+    }
+
+    fn compressed_protocol() -> StreamProtocol {
+        StreamProtocol::try_from_owned(format!("/ant/test/1{COMPRESSED_PROTOCOL_SUFFIX}"))
+            .expect("valid protocol")
+    }
+
+    fn replicate_request() -> Request {
+        let holder = NetworkAddress::from(PeerId::random());
+        let keys = (0..200)
+            .map(|_| {
+                (
+                    NetworkAddress::from(PeerId::random()),
+                    crate::storage::ValidationType::Chunk,
+                )
+            })
+            .collect();
+        Request::Cmd(Cmd::Replicate { holder, keys })
+    }
+
+    async fn round_trip(
+        codec: &mut MessageCodec,
+        protocol: &StreamProtocol,
+        req: Request,
+    ) -> usize {
+        let mut wire = Cursor::new(Vec::new());
+        codec
+            .write_request(protocol, &mut wire, req.clone())
+            .await
+            .expect("write request");
+        let wire = wire.into_inner();
+        let read = codec
+            .read_request(protocol, &mut Cursor::new(wire.clone()))
+            .await
+            .expect("read request");
+        assert_eq!(read, req);
+        wire.len()
+    }
+
+    #[test]
+    fn compressed_protocol_round_trips_and_saves_bytes() {
+        let metrics = CompressionMetrics::default();
+        let mut codec = MessageCodec::default().with_metrics(metrics.clone());
+        let req = replicate_request();
+
+        let plain_len = block_on(round_trip(&mut codec, &plain_protocol(), req.clone()));
+        assert_eq!(metrics.compressed_messages.get(), 0);
+
+        let compressed_len = block_on(round_trip(&mut codec, &compressed_protocol(), req));
+        assert!(compressed_len < plain_len);
+        assert_eq!(metrics.compressed_messages.get(), 1);
+        assert_eq!(
+            metrics.bytes_saved.get(),
+            (plain_len - compressed_len + 1) as u64
+        );
+    }
+
+    #[test]
+    fn small_messages_are_sent_uncompressed() {
+        let metrics = CompressionMetrics::default();
+        let mut codec = MessageCodec::default().with_metrics(metrics.clone());
+        let req = Request::Query(Query::GetVersion(NetworkAddress::from(PeerId::random())));
+
+        let plain_len = block_on(round_trip(&mut codec, &plain_protocol(), req.clone()));
+        let framed_len = block_on(round_trip(&mut codec, &compressed_protocol(), req));
+
+        assert_eq!(framed_len, plain_len + 1);
+        assert_eq!(metrics.compressed_messages.get(), 0);
+    }
+
+    #[test]
+    fn incompressible_data_is_not_compressed() {
+        let data: Vec<u8> = (0..4 * COMPRESSION_SAMPLE_SIZE)
+            .map(|_| rand::random::<u8>())
+            .collect();
+        assert!(try_compress(&data).is_none());
+    }
+
+    #[test]
+    fn decompression_is_bounded() {
+        let data = vec![0u8; 1024 * 1024];
+        let mut framed = vec![FLAG_ZSTD];
+        framed.extend(zstd::bulk::compress(&data, COMPRESSION_LEVEL).expect("compress"));
+
+        assert!(decompress(framed.clone(), 1024).is_err());
+        assert_eq!(
+            decompress(framed, data.len() as u64).expect("decompress"),
+            data
+        );
+    }
+}
@@ -84,6 +84,30 @@ pub enum Query {
         /// Address of the record.
         address: NetworkAddress,
     },
+    /// Retrieve several records from a specific peer in one go.
+    ///
+    /// The peer fills its [`GetReplicatedRecords`] response up to [`MAX_PACKET_SIZE`],
+    /// and hands back the keys it didn't get to, so the requester can ask for them again.
+    ///
+    /// [`GetReplicatedRecords`]: super::QueryResponse::GetReplicatedRecords
+    /// [`MAX_PACKET_SIZE`]: crate::constants::MAX_PACKET_SIZE
+    GetReplicatedRecords {
+        /// Peer being queried.
+        holder: NetworkAddress,
+        /// Keys of the records to be fetched
+        keys: Vec<NetworkAddress>,
+    },
+    /// Check whether a specific peer holds each of the given records.
+    ///
+    /// This should eventually lead to a [`CheckRecordsExist`] response.
+    ///
+    /// [`CheckRecordsExist`]: super::QueryResponse::CheckRecordsExist
+    CheckRecordsExist {
+        /// Peer being queried.
+        holder: NetworkAddress,
+        /// Keys of the records to be checked
+        keys: Vec<NetworkAddress>,
+    },
 }
 
 impl Query {
@@ -97,7 +121,9 @@ impl Query {
             | Query::GetReplicatedRecord { key, .. }
             | Query::GetChunkExistenceProof { key, .. }
             | Query::GetClosestPeers { key, .. } => key.clone(),
-            Query::PutRecord { holder, .. } => holder.clone(),
+            Query::PutRecord { holder, .. }
+            | Query::GetReplicatedRecords { holder, .. }
+            | Query::CheckRecordsExist { holder, .. } => holder.clone(),
         }
     }
 }
@@ -160,6 +186,20 @@ impl std::fmt::Display for Query {
                     serialized_record.len()
                 )
             }
+            Query::GetReplicatedRecords { holder, keys } => {
+                write!(
+                    f,
+                    "Query::GetReplicatedRecords({holder:?} {} keys)",
+                    keys.len()
+                )
+            }
+            Query::CheckRecordsExist { holder, keys } => {
+                write!(
+                    f,
+                    "Query::CheckRecordsExist({holder:?} {} keys)",
+                    keys.len()
+                )
+            }
         }
     }
 }
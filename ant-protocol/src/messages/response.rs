@@ -87,6 +87,26 @@ pub enum QueryResponse {
         /// Correspondent Record Address
         record_addr: NetworkAddress,
     },
+    /// Response to [`GetReplicatedRecords`]
+    ///
+    /// [`GetReplicatedRecords`]: crate::messages::Query::GetReplicatedRecords
+    GetReplicatedRecords {
+        /// Node's Peer Address
+        holder: NetworkAddress,
+        /// The records found, `None` for the keys the node doesn't hold.
+        records: Vec<(NetworkAddress, Option<Bytes>)>,
+        /// Keys left out to keep the response under `MAX_PACKET_SIZE`, to be asked for again.
+        remaining: Vec<NetworkAddress>,
+    },
+    /// Response to [`CheckRecordsExist`]
+    ///
+    /// [`CheckRecordsExist`]: crate::messages::Query::CheckRecordsExist
+    CheckRecordsExist {
+        /// Node's Peer Address
+        holder: NetworkAddress,
+        /// Whether the node holds the record, for each of the queried keys.
+        results: Vec<(NetworkAddress, bool)>,
+    },
 }
 
 // Debug implementation for QueryResponse, to avoid printing Vec<u8>
@@ -152,6 +172,27 @@ impl Debug for QueryResponse {
                     "PutRecord(Record {record_addr:?} uploaded to {peer_address:?} with result {result:?})",
                 )
             }
+            QueryResponse::GetReplicatedRecords {
+                holder,
+                records,
+                remaining,
+            } => {
+                let found = records.iter().filter(|(_, data)| data.is_some()).count();
+                write!(
+                    f,
+                    "GetReplicatedRecords(holder: {holder:?}, found {found}/{} records, {} remaining)",
+                    records.len(),
+                    remaining.len()
+                )
+            }
+            QueryResponse::CheckRecordsExist { holder, results } => {
+                let found = results.iter().filter(|(_, exists)| *exists).count();
+                write!(
+                    f,
+                    "CheckRecordsExist(holder: {holder:?}, {found}/{} records exist)",
+                    results.len()
+                )
+            }
         }
     }
 }
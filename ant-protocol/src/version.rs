@@ -22,6 +22,87 @@ pub const ALPHANET_ID: u8 = 2;
 /// - Used in protocol strings and user-agent identifiers
 pub static NETWORK_ID: LazyLock<RwLock<u8>> = LazyLock::new(|| RwLock::new(1));
 
+/// Suffix of the req/response protocol on which messages are compressed.
+///
+/// Peers supporting compression offer this protocol ahead of the plain one, so compression is
+/// only used when both ends support it, while older peers keep talking the plain protocol.
+pub const COMPRESSED_PROTOCOL_SUFFIX: &str = "/zstd";
+
+/// The network a client or node takes part in, and the identifiers derived from it.
+///
+/// Each client or node holds its own context, so peers of different networks can run side by side
+/// in one process. The process-wide [`NETWORK_ID`] only provides the default context, for the
+/// binaries which are configured through [`set_network_id`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NetworkContext {
+    network_id: u8,
+}
+
+impl NetworkContext {
+    pub fn new(network_id: u8) -> Self {
+        Self { network_id }
+    }
+
+    /// The context of the process-wide [`NETWORK_ID`].
+    pub fn current() -> Self {
+        Self::new(get_network_id())
+    }
+
+    pub fn network_id(&self) -> u8 {
+        self.network_id
+    }
+
+    /// The req/response protocol version, see [`REQ_RESPONSE_VERSION_STR`].
+    pub fn req_response_protocol(&self) -> String {
+        format!(
+            "/ant/{ant_protocol_version}/{network_id}",
+            ant_protocol_version = get_truncate_version_str(),
+            network_id = self.network_id
+        )
+    }
+
+    /// The req/response protocol with negotiated compression, see [`COMPRESSED_PROTOCOL_SUFFIX`].
+    pub fn compressed_req_response_protocol(&self) -> String {
+        format!(
+            "{}{COMPRESSED_PROTOCOL_SUFFIX}",
+            self.req_response_protocol()
+        )
+    }
+
+    /// The identify protocol version, see [`IDENTIFY_PROTOCOL_STR`].
+    pub fn identify_protocol(&self) -> String {
+        format!(
+            "ant/{ant_protocol_version}/{network_id}",
+            ant_protocol_version = get_truncate_version_str(),
+            network_id = self.network_id
+        )
+    }
+
+    /// The node user agent, see [`construct_node_user_agent`].
+    pub fn node_user_agent(&self, node_version: String) -> String {
+        format!(
+            "ant/node/{ant_protocol_version}/{node_version}/{network_id}",
+            ant_protocol_version = get_truncate_version_str(),
+            network_id = self.network_id,
+        )
+    }
+
+    /// The client user agent, see [`construct_client_user_agent`].
+    pub fn client_user_agent(&self, client_version: String) -> String {
+        format!(
+            "ant/client/{ant_protocol_version}/{client_version}/{network_id}",
+            ant_protocol_version = get_truncate_version_str(),
+            network_id = self.network_id,
+        )
+    }
+}
+
+impl Default for NetworkContext {
+    fn default() -> Self {
+        Self::new(MAINNET_ID)
+    }
+}
+
 /// Node user-agent identifier for peer recognition and routing table management.
 ///
 /// ## Purpose
@@ -35,11 +116,7 @@ pub static NETWORK_ID: LazyLock<RwLock<u8>> = LazyLock::new(|| RwLock::new(1));
 /// - Other nodes recognize this as a fellow routing participant
 /// - Peers with this identifier are added to routing tables (RT)
 pub fn construct_node_user_agent(node_version: String) -> String {
-    format!(
-        "ant/node/{ant_protocol_version}/{node_version}/{network_id}",
-        ant_protocol_version = get_truncate_version_str(),
-        network_id = get_network_id(),
-    )
+    NetworkContext::current().node_user_agent(node_version)
 }
 
 /// Client user-agent identifier for peer recognition and routing exclusion.
@@ -55,11 +132,7 @@ pub fn construct_node_user_agent(node_version: String) -> String {
 /// - Nodes search for "client" in this identifier and they are **excluded** from routing tables (RT)
 /// - Treated as network consumers rather than routing participants
 pub fn construct_client_user_agent(client_version: String) -> String {
-    format!(
-        "ant/client/{ant_protocol_version}/{client_version}/{network_id}",
-        ant_protocol_version = get_truncate_version_str(),
-        network_id = get_network_id(),
-    )
+    NetworkContext::current().client_user_agent(client_version)
 }
 
 /// The req/response protocol version
@@ -76,13 +149,8 @@ pub fn construct_client_user_agent(client_version: String) -> String {
 ///   - Different versions cannot communicate
 ///   - Different network IDs are isolated
 ///   - Connection remains open for other protocols
-pub static REQ_RESPONSE_VERSION_STR: LazyLock<RwLock<String>> = LazyLock::new(|| {
-    RwLock::new(format!(
-        "/ant/{ant_protocol_version}/{network_id}",
-        ant_protocol_version = get_truncate_version_str(),
-        network_id = get_network_id()
-    ))
-});
+pub static REQ_RESPONSE_VERSION_STR: LazyLock<RwLock<String>> =
+    LazyLock::new(|| RwLock::new(NetworkContext::current().req_response_protocol()));
 
 /// Identify protocol version string for peer compatibility verification.
 ///
@@ -99,19 +167,15 @@ pub static REQ_RESPONSE_VERSION_STR: LazyLock<RwLock<String>> = LazyLock::new(||
 ///   - Considered incompatible
 ///   - Added to the blocklist
 ///   - Protected against cross-network contamination
-pub static IDENTIFY_PROTOCOL_STR: LazyLock<RwLock<String>> = LazyLock::new(|| {
-    RwLock::new(format!(
-        "ant/{ant_protocol_version}/{network_id}",
-        ant_protocol_version = get_truncate_version_str(),
-        network_id = get_network_id(),
-    ))
-});
+pub static IDENTIFY_PROTOCOL_STR: LazyLock<RwLock<String>> =
+    LazyLock::new(|| RwLock::new(NetworkContext::current().identify_protocol()));
 
 /// Update the NETWORK_ID.
 ///
 /// Other version strings will reference this value. The default is 1, representing the mainnet.
 ///
-/// This function should be used sparingly, ideally before the node or client is started.
+/// This function should be used sparingly, ideally before the node or client is started. Clients and
+/// nodes given their own [`NetworkContext`] are not affected by it.
 ///
 /// Each of the version strings need to be explicitly updated here. There are scenarios where they
 /// could be read before this function is called, in which case they will have the old value for
@@ -129,14 +193,14 @@ pub fn set_network_id(id: u8) {
         let mut req_response = REQ_RESPONSE_VERSION_STR
             .write()
             .expect("Failed to obtain write lock for REQ_RESPONSE_VERSION_STR");
-        *req_response = format!("/ant/{}/{}", get_truncate_version_str(), id);
+        *req_response = NetworkContext::new(id).req_response_protocol();
     }
 
     {
         let mut identify_protocol = IDENTIFY_PROTOCOL_STR
             .write()
             .expect("Failed to obtain write lock for IDENTIFY_PROTOCOL_STR");
-        *identify_protocol = format!("ant/{}/{}", get_truncate_version_str(), id);
+        *identify_protocol = NetworkContext::new(id).identify_protocol();
     }
 
     info!("Network id set to: {id} and all protocol strings updated");
@@ -210,4 +274,28 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn network_contexts_are_independent_of_the_global_network_id() {
+        let mainnet = NetworkContext::default();
+        let testnet = NetworkContext::new(7);
+        let version = get_truncate_version_str();
+
+        assert_eq!(mainnet.network_id(), MAINNET_ID);
+        assert_eq!(testnet.req_response_protocol(), format!("/ant/{version}/7"));
+        assert_eq!(
+            testnet.compressed_req_response_protocol(),
+            format!("/ant/{version}/7/zstd")
+        );
+        assert_eq!(testnet.identify_protocol(), format!("ant/{version}/7"));
+        assert_eq!(
+            testnet.client_user_agent("1.0.0".to_string()),
+            format!("ant/client/{version}/1.0.0/7")
+        );
+        assert_eq!(
+            testnet.node_user_agent("1.0.0".to_string()),
+            format!("ant/node/{version}/1.0.0/7")
+        );
+        assert_ne!(mainnet.identify_protocol(), testnet.identify_protocol());
+    }
 }
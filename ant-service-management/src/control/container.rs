@@ -0,0 +1,288 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{ServiceControl, ServiceController};
+use crate::error::{Error, Result};
+use service_manager::ServiceInstallCtx;
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+pub const DEFAULT_CONTAINER_IMAGE: &str = "debian:bookworm-slim";
+pub const DEFAULT_CONTAINER_RUNTIME: &str = "docker";
+
+/// The arguments of antnode whose values are directories the container needs access to.
+const MOUNTED_DIR_ARGS: [&str; 3] = ["--bootstrap-cache-dir", "--log-output-dest", "--root-dir"];
+/// How long the container is given to stop, in seconds, before its process is killed.
+const STOP_TIMEOUT_SECS: u32 = 30;
+const STOP_TIMEOUT: Duration = Duration::from_secs(STOP_TIMEOUT_SECS as u64);
+
+/// Runs each service in an OCI container, through a runtime with a Docker compatible command line,
+/// such as docker or podman.
+///
+/// The container uses the network of the host, and has the binary, data and log directories of
+/// the node mounted at the same paths as on the host. The ports of the node and the process seen
+/// from the host are then the same as for the other services. The containers are not restarted by
+/// the runtime; the daemon supervises them.
+pub struct ContainerController {
+    pub image: String,
+    pub runtime: String,
+}
+
+impl ContainerController {
+    fn run(&self, args: Vec<OsString>) -> Result<String> {
+        debug!("Running {} {args:?}", self.runtime);
+        let output = Command::new(&self.runtime)
+            .args(&args)
+            .output()
+            .map_err(|err| {
+                error!("Failed to run {}: {err:?}", self.runtime);
+                Error::ContainerRuntimeError(format!("could not run {}: {err}", self.runtime))
+            })?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            error!("{} {args:?} failed: {stderr}", self.runtime);
+            return Err(Error::ContainerRuntimeError(stderr));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Send a signal to the process of a container, which might have exited already.
+    fn kill(&self, service_name: &str, signal: &str) -> Result<()> {
+        match self.run(vec![
+            "kill".into(),
+            "--signal".into(),
+            signal.into(),
+            service_name.into(),
+        ]) {
+            Ok(_) => Ok(()),
+            Err(Error::ContainerRuntimeError(err))
+                if err.to_lowercase().contains("not running") =>
+            {
+                debug!("The container of {service_name} is not running");
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The arguments to create the container of a service.
+    pub fn create_args(&self, install_ctx: &ServiceInstallCtx) -> Result<Vec<OsString>> {
+        let mut args: Vec<OsString> = vec![
+            "create".into(),
+            "--name".into(),
+            install_ctx.label.to_qualified_name().into(),
+            "--network".into(),
+            "host".into(),
+            "--init".into(),
+            "--stop-timeout".into(),
+            STOP_TIMEOUT_SECS.to_string().into(),
+        ];
+        if let Some(username) = &install_ctx.username {
+            args.push("--user".into());
+            args.push(user_ids(username)?.into());
+        }
+        for (key, value) in install_ctx.environment.iter().flatten() {
+            args.push("--env".into());
+            args.push(format!("{key}={value}").into());
+        }
+
+        let mut mounts = Vec::new();
+        let mut ctx_args = install_ctx.args.iter();
+        while let Some(arg) = ctx_args.next() {
+            if MOUNTED_DIR_ARGS.iter().any(|mounted| arg == *mounted)
+                && let Some(dir) = ctx_args.next()
+            {
+                mounts.push((PathBuf::from(dir), false));
+            }
+        }
+        // The binary is normally in the data directory, which is already mounted.
+        if let Some(bin_dir) = install_ctx.program.parent()
+            && !mounts.iter().any(|(dir, _)| dir == bin_dir)
+        {
+            mounts.push((bin_dir.to_path_buf(), true));
+        }
+        for (dir, read_only) in mounts {
+            // The runtime would create a missing directory as root.
+            std::fs::create_dir_all(&dir)?;
+            let mut mount = OsString::from(dir.as_os_str());
+            mount.push(":");
+            mount.push(dir.as_os_str());
+            if read_only {
+                mount.push(":ro");
+            }
+            args.push("--volume".into());
+            args.push(mount);
+        }
+        if let Some(working_directory) = &install_ctx.working_directory {
+            args.push("--workdir".into());
+            args.push(working_directory.into());
+        }
+
+        args.push(self.image.clone().into());
+        args.push(install_ctx.program.clone().into());
+        args.extend(install_ctx.args.iter().cloned());
+        Ok(args)
+    }
+}
+
+impl ServiceControl for ContainerController {
+    fn create_service_user(&self, username: &str) -> Result<()> {
+        ServiceController {}.create_service_user(username)
+    }
+
+    fn get_available_port(&self) -> Result<u16> {
+        ServiceController {}.get_available_port()
+    }
+
+    fn get_process_pid(&self, bin_path: &Path) -> Result<u32> {
+        ServiceController {}.get_process_pid(bin_path)
+    }
+
+    fn install(&self, install_ctx: ServiceInstallCtx, _user_mode: bool) -> Result<()> {
+        debug!("Creating a container for the service: {install_ctx:?}");
+        let args = self.create_args(&install_ctx)?;
+        self.run(args)?;
+        Ok(())
+    }
+
+    fn start(&self, service_name: &str, _user_mode: bool) -> Result<()> {
+        debug!("Starting the container of {service_name}");
+        self.run(vec!["start".into(), service_name.into()])?;
+        Ok(())
+    }
+
+    /// Ask the process of the container to exit, see [`ServiceControl::poll_stopped`] for waiting
+    /// on it. The runtime's own stop command would block until the container exits.
+    fn stop(&self, service_name: &str, _user_mode: bool) -> Result<()> {
+        debug!("Stopping the container of {service_name}");
+        self.kill(service_name, "SIGTERM")
+    }
+
+    fn poll_stopped(
+        &self,
+        service_name: &str,
+        _user_mode: bool,
+        elapsed: Duration,
+    ) -> Result<bool> {
+        let running = self.run(vec![
+            "inspect".into(),
+            "--format".into(),
+            "{{.State.Running}}".into(),
+            service_name.into(),
+        ])?;
+        if running != "true" {
+            return Ok(true);
+        }
+
+        if elapsed >= STOP_TIMEOUT {
+            warn!("The container of {service_name} did not stop in time, killing it");
+            self.kill(service_name, "SIGKILL")?;
+        }
+        Ok(false)
+    }
+
+    fn uninstall(&self, service_name: &str, _user_mode: bool) -> Result<()> {
+        debug!("Removing the container of {service_name}");
+        match self.run(vec!["rm".into(), "--force".into(), service_name.into()]) {
+            Ok(_) => Ok(()),
+            Err(Error::ContainerRuntimeError(err)) if err.to_lowercase().contains("no such") => {
+                error!("The container of {service_name} might have been removed manually");
+                Err(Error::ServiceRemovedManually(service_name.to_string()))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn wait(&self, delay: u64) {
+        ServiceController {}.wait(delay)
+    }
+}
+
+/// The numeric user and group IDs of the service user, since it does not exist in the image.
+#[cfg(unix)]
+fn user_ids(username: &str) -> Result<String> {
+    let id = |flag: &str| -> Result<String> {
+        let output = Command::new("id").arg(flag).arg(username).output()?;
+        if !output.status.success() {
+            return Err(Error::ServiceUserNotFound(username.to_string()));
+        }
+        Ok(std::str::from_utf8(&output.stdout)?.trim().to_string())
+    };
+    Ok(format!("{}:{}", id("-u")?, id("-g")?))
+}
+
+#[cfg(windows)]
+fn user_ids(username: &str) -> Result<String> {
+    Ok(username.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use service_manager::ServiceLabel;
+
+    #[test]
+    fn create_args_should_mount_the_node_directories_at_their_host_paths() {
+        let tmp_dir = TempDir::new().unwrap();
+        let bin_dir = tmp_dir.join("antnode1");
+        let log_dir = tmp_dir.join("logs");
+        let controller = ContainerController {
+            image: DEFAULT_CONTAINER_IMAGE.to_string(),
+            runtime: DEFAULT_CONTAINER_RUNTIME.to_string(),
+        };
+        let ctx = ServiceInstallCtx {
+            label: "antnode1".parse::<ServiceLabel>().unwrap(),
+            program: bin_dir.join("antnode"),
+            args: vec![
+                "--rpc".into(),
+                "127.0.0.1:12001".into(),
+                "--root-dir".into(),
+                bin_dir.clone().into(),
+                "--log-output-dest".into(),
+                log_dir.clone().into(),
+            ],
+            contents: None,
+            username: None,
+            working_directory: None,
+            environment: Some(vec![("ANT_LOG".to_string(), "all".to_string())]),
+            autostart: false,
+            disable_restart_on_failure: true,
+        };
+
+        let args = controller.create_args(&ctx).unwrap();
+
+        let mount = |dir: &Path, suffix: &str| {
+            OsString::from(format!("{}:{}{suffix}", dir.display(), dir.display()))
+        };
+        let mut expected: Vec<OsString> = vec![
+            "create".into(),
+            "--name".into(),
+            "antnode1".into(),
+            "--network".into(),
+            "host".into(),
+            "--init".into(),
+            "--stop-timeout".into(),
+            "30".into(),
+            "--env".into(),
+            "ANT_LOG=all".into(),
+            "--volume".into(),
+            mount(&bin_dir, ""),
+            "--volume".into(),
+            mount(&log_dir, ""),
+            DEFAULT_CONTAINER_IMAGE.into(),
+            bin_dir.join("antnode").into(),
+        ];
+        expected.extend(ctx.args.iter().cloned());
+        assert_eq!(args, expected);
+    }
+}
@@ -6,14 +6,21 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+pub mod container;
+pub mod process;
+
+use self::{container::ContainerController, process::ProcessController};
 use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
 use service_manager::{
     ServiceInstallCtx, ServiceLabel, ServiceLevel, ServiceManager, ServiceStartCtx, ServiceStopCtx,
     ServiceUninstallCtx,
 };
 use std::{
+    fmt,
     net::{SocketAddr, TcpListener},
     path::Path,
+    time::Duration,
 };
 use sysinfo::System;
 
@@ -31,10 +38,83 @@ pub trait ServiceControl: Sync {
     fn get_process_pid(&self, path: &Path) -> Result<u32>;
     fn start(&self, service_name: &str, user_mode: bool) -> Result<()>;
     fn stop(&self, service_name: &str, user_mode: bool) -> Result<()>;
+    /// Whether a service asked to stop, `elapsed` ago, has stopped. Services of the service manager
+    /// have stopped by the time `stop` returns.
+    fn poll_stopped(
+        &self,
+        _service_name: &str,
+        _user_mode: bool,
+        _elapsed: Duration,
+    ) -> Result<bool> {
+        Ok(true)
+    }
     fn uninstall(&self, service_name: &str, user_mode: bool) -> Result<()>;
+    /// Replace the definition of a running service without stopping it, for changes that don't
+    /// affect how it was launched. Returns `false` when that is not possible, and nothing changed.
+    fn reinstall_running(&self, _install_ctx: ServiceInstallCtx, _user_mode: bool) -> Result<bool> {
+        Ok(false)
+    }
     fn wait(&self, delay: u64);
 }
 
+/// How the services of nodes are run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ServiceBackend {
+    /// As services of the service manager of the operating system.
+    #[default]
+    Native,
+    /// As plain processes, which need no privileges to install.
+    Process,
+    /// In OCI containers, driven through a container runtime such as docker or podman.
+    Container { image: String, runtime: String },
+}
+
+impl ServiceBackend {
+    /// Parse the name of a backend, with the image and runtime used by the container backend.
+    pub fn parse(name: &str, image: Option<String>, runtime: Option<String>) -> Result<Self> {
+        match name {
+            "native" => Ok(ServiceBackend::Native),
+            "process" => Ok(ServiceBackend::Process),
+            "container" => Ok(ServiceBackend::Container {
+                image: image.unwrap_or_else(|| container::DEFAULT_CONTAINER_IMAGE.to_string()),
+                runtime: runtime
+                    .unwrap_or_else(|| container::DEFAULT_CONTAINER_RUNTIME.to_string()),
+            }),
+            _ => Err(Error::InvalidServiceBackend(name.to_string())),
+        }
+    }
+
+    /// The implementation of `ServiceControl` for the services run by this backend.
+    pub fn controller(&self) -> Box<dyn ServiceControl + Send> {
+        self.controller_for(false)
+    }
+
+    /// Like [`Self::controller`], with the processes of the process backend launched as children
+    /// of the current process if `launch_as_children` is set, rather than detached from it.
+    pub fn controller_for(&self, launch_as_children: bool) -> Box<dyn ServiceControl + Send> {
+        match self {
+            ServiceBackend::Native => Box::new(ServiceController {}),
+            ServiceBackend::Process => Box::new(ProcessController { launch_as_children }),
+            ServiceBackend::Container { image, runtime } => Box::new(ContainerController {
+                image: image.clone(),
+                runtime: runtime.clone(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ServiceBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceBackend::Native => write!(f, "native"),
+            ServiceBackend::Process => write!(f, "process"),
+            ServiceBackend::Container { image, runtime } => {
+                write!(f, "container ({runtime}, {image})")
+            }
+        }
+    }
+}
+
 pub struct ServiceController {}
 
 impl ServiceControl for ServiceController {
@@ -319,6 +399,36 @@ impl ServiceControl for ServiceController {
         }
     }
 
+    /// systemd keeps running the service when its unit is replaced, and picks the new unit up
+    /// once it is reloaded.
+    #[cfg(target_os = "linux")]
+    fn reinstall_running(&self, install_ctx: ServiceInstallCtx, user_mode: bool) -> Result<bool> {
+        use std::process::Command;
+
+        let service_name = install_ctx.label.to_qualified_name();
+        debug!("Replacing the unit of the running service: {service_name}");
+        self.uninstall(&service_name, user_mode)?;
+        self.install(install_ctx, user_mode)?;
+
+        let mut command = Command::new("systemctl");
+        if user_mode {
+            command.arg("--user");
+        }
+        let output = command
+            .arg("daemon-reload")
+            .output()
+            .inspect_err(|err| error!("Failed to execute systemctl daemon-reload: {err:?}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            error!("Failed to reload the systemd units: {stderr}");
+            return Err(std::io::Error::other(format!(
+                "Could not reload the systemd units: {stderr}"
+            ))
+            .into());
+        }
+        Ok(true)
+    }
+
     /// Provide a delay for the service to start or stop.
     ///
     /// This is wrapped mainly just for unit testing.
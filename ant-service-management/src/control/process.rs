@@ -0,0 +1,278 @@
+// Copyright (C) 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{ServiceControl, ServiceController};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use service_manager::ServiceInstallCtx;
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Duration,
+};
+use sysinfo::{Pid, Signal, System};
+
+/// How long a process is given to exit after being asked to, before it is killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What is needed to launch the process of a service, in place of a service definition.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProcessDefinition {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub environment: Option<Vec<(String, String)>>,
+    pub username: Option<String>,
+    pub working_directory: Option<PathBuf>,
+}
+
+impl From<ServiceInstallCtx> for ProcessDefinition {
+    fn from(ctx: ServiceInstallCtx) -> Self {
+        Self {
+            program: ctx.program,
+            args: ctx
+                .args
+                .into_iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect(),
+            environment: ctx.environment,
+            username: ctx.username,
+            working_directory: ctx.working_directory,
+        }
+    }
+}
+
+/// Runs services as plain processes, for hosts where services cannot be installed.
+///
+/// The processes are not restarted by the operating system. When the daemon runs, the processes
+/// are launched by it as its children, and it restarts those that exit just as it does for the
+/// other services. Otherwise antctl launches them detached from itself.
+pub struct ProcessController {
+    /// Launch the processes as children of the current process, rather than detached from it. The
+    /// daemon does so, as the processes it launches are its own to supervise.
+    pub launch_as_children: bool,
+}
+
+impl ProcessController {
+    /// The directory holding the definitions of the processes, and their PIDs.
+    pub fn definitions_dir_path(user_mode: bool) -> Result<PathBuf> {
+        let path = if user_mode {
+            dirs_next::data_dir()
+                .ok_or(Error::UserDataDirectoryNotObtainable)?
+                .join("autonomi")
+                .join("node")
+                .join("processes")
+        } else if cfg!(windows) {
+            PathBuf::from("C:\\ProgramData\\antctl\\processes")
+        } else {
+            PathBuf::from("/var/antctl/processes")
+        };
+        std::fs::create_dir_all(&path)?;
+        Ok(path)
+    }
+
+    pub fn load_definition(service_name: &str, user_mode: bool) -> Result<ProcessDefinition> {
+        let path = Self::definitions_dir_path(user_mode)?.join(format!("{service_name}.json"));
+        if !path.exists() {
+            error!("The process definition {path:?} does not exist");
+            return Err(Error::ServiceRemovedManually(service_name.to_string()));
+        }
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// The PID of the running process of the service, if any.
+    fn running_pid(service_name: &str, user_mode: bool) -> Result<Option<Pid>> {
+        let pid_path = Self::definitions_dir_path(user_mode)?.join(format!("{service_name}.pid"));
+        let Ok(pid) = std::fs::read_to_string(&pid_path) else {
+            return Ok(None);
+        };
+        let pid = Pid::from_u32(pid.trim().parse()?);
+        let definition = Self::load_definition(service_name, user_mode)?;
+
+        // The PID could have been reused by another process since it was recorded.
+        let mut system = System::new();
+        system.refresh_process(pid);
+        let running = system
+            .process(pid)
+            .and_then(|process| process.exe())
+            .is_some_and(|exe| exe == definition.program);
+        Ok(running.then_some(pid))
+    }
+}
+
+impl ServiceControl for ProcessController {
+    fn create_service_user(&self, username: &str) -> Result<()> {
+        ServiceController {}.create_service_user(username)
+    }
+
+    fn get_available_port(&self) -> Result<u16> {
+        ServiceController {}.get_available_port()
+    }
+
+    fn get_process_pid(&self, bin_path: &Path) -> Result<u32> {
+        ServiceController {}.get_process_pid(bin_path)
+    }
+
+    fn install(&self, install_ctx: ServiceInstallCtx, user_mode: bool) -> Result<()> {
+        let service_name = install_ctx.label.to_qualified_name();
+        let path = Self::definitions_dir_path(user_mode)?.join(format!("{service_name}.json"));
+        debug!("Writing the process definition of {service_name} to {path:?}");
+        let definition = ProcessDefinition::from(install_ctx);
+        std::fs::write(path, serde_json::to_string_pretty(&definition)?)?;
+        Ok(())
+    }
+
+    fn start(&self, service_name: &str, user_mode: bool) -> Result<()> {
+        debug!("Starting the process of {service_name}");
+        if let Some(pid) = Self::running_pid(service_name, user_mode)? {
+            debug!("The process of {service_name} is already running with PID {pid}");
+            return Ok(());
+        }
+
+        let definition = Self::load_definition(service_name, user_mode)?;
+        let dir_path = Self::definitions_dir_path(user_mode)?;
+        let stderr = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir_path.join(format!("{service_name}.stderr.log")))?;
+
+        let mut command = Command::new(&definition.program);
+        command
+            .args(&definition.args)
+            .envs(definition.environment.clone().unwrap_or_default())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(stderr);
+        if let Some(working_directory) = &definition.working_directory {
+            command.current_dir(working_directory);
+        }
+        if !self.launch_as_children {
+            detach(&mut command);
+        }
+        #[cfg(unix)]
+        if let Some(username) = &definition.username {
+            run_as(&mut command, username)?;
+        }
+
+        let mut child = command
+            .spawn()
+            .inspect_err(|err| error!("Failed to launch the process of {service_name}: {err:?}"))?;
+        let pid = child.id();
+        std::fs::write(
+            dir_path.join(format!("{service_name}.pid")),
+            pid.to_string(),
+        )?;
+        info!("Launched the process of {service_name} with PID {pid}");
+
+        // The exit status is collected so the process does not linger after it exits, for as long
+        // as the current process keeps running.
+        let service_name = service_name.to_string();
+        std::thread::spawn(move || {
+            if let Ok(status) = child.wait() {
+                info!("The process of {service_name} exited with {status}");
+            }
+        });
+        Ok(())
+    }
+
+    /// Ask the process to exit, see [`ServiceControl::poll_stopped`] for waiting on it.
+    fn stop(&self, service_name: &str, user_mode: bool) -> Result<()> {
+        debug!("Stopping the process of {service_name}");
+        let Some(pid) = Self::running_pid(service_name, user_mode)? else {
+            debug!("The process of {service_name} is not running");
+            return Ok(());
+        };
+
+        let mut system = System::new();
+        system.refresh_process(pid);
+        if let Some(process) = system.process(pid)
+            && process.kill_with(Signal::Term).is_none()
+        {
+            // The platform cannot ask the process to exit.
+            process.kill();
+        }
+        Ok(())
+    }
+
+    fn poll_stopped(&self, service_name: &str, user_mode: bool, elapsed: Duration) -> Result<bool> {
+        let Some(pid) = Self::running_pid(service_name, user_mode)? else {
+            let pid_path =
+                Self::definitions_dir_path(user_mode)?.join(format!("{service_name}.pid"));
+            if pid_path.exists() {
+                std::fs::remove_file(pid_path)?;
+            }
+            return Ok(true);
+        };
+
+        if elapsed >= STOP_TIMEOUT {
+            warn!("The process of {service_name} did not exit in time, killing it");
+            let mut system = System::new();
+            system.refresh_process(pid);
+            if let Some(process) = system.process(pid) {
+                process.kill();
+            }
+        }
+        Ok(false)
+    }
+
+    fn uninstall(&self, service_name: &str, user_mode: bool) -> Result<()> {
+        debug!("Removing the process definition of {service_name}");
+        let dir_path = Self::definitions_dir_path(user_mode)?;
+        let definition_path = dir_path.join(format!("{service_name}.json"));
+        if !definition_path.exists() {
+            error!("The process definition {definition_path:?} does not exist");
+            return Err(Error::ServiceRemovedManually(service_name.to_string()));
+        }
+        std::fs::remove_file(definition_path)?;
+        for extension in ["pid", "stderr.log"] {
+            let path = dir_path.join(format!("{service_name}.{extension}"));
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn wait(&self, delay: u64) {
+        ServiceController {}.wait(delay)
+    }
+}
+
+/// Launch the process in its own process group, so it outlives antctl.
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    command.process_group(0);
+}
+
+/// Launch the process detached from the console of antctl, so it outlives it.
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+    command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+}
+
+/// Launch the process as the service user.
+#[cfg(unix)]
+fn run_as(command: &mut Command, username: &str) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let id = |flag: &str| -> Result<u32> {
+        let output = Command::new("id").arg(flag).arg(username).output()?;
+        if !output.status.success() {
+            return Err(Error::ServiceUserNotFound(username.to_string()));
+        }
+        Ok(std::str::from_utf8(&output.stdout)?.trim().parse()?)
+    };
+    command.uid(id("-u")?).gid(id("-g")?);
+    Ok(())
+}
@@ -19,6 +19,10 @@ pub enum Error {
     AddrParseError(#[from] std::net::AddrParseError),
     #[error("The endpoint for the daemon has not been set")]
     DaemonEndpointNotSet,
+    #[error("The container runtime failed: {0}")]
+    ContainerRuntimeError(String),
+    #[error("'{0}' is not a service backend; use one of native, process or container")]
+    InvalidServiceBackend(String),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
@@ -33,6 +37,8 @@ pub enum Error {
     PeerIdParseError(#[from] libp2p_identity::ParseError),
     #[error("Could not connect to RPC endpoint '{0}'")]
     RpcConnectionError(String),
+    #[error("Could not obtain the k-buckets through RPC: {0}")]
+    RpcKBucketsError(String),
     #[error("Could not obtain node info through RPC: {0}")]
     RpcNodeInfoError(String),
     #[error("Could not obtain network info through RPC: {0}")]
@@ -45,12 +51,16 @@ pub enum Error {
     RpcNodeUpdateError(String),
     #[error("Could not obtain record addresses through RPC: {0}")]
     RpcRecordAddressError(String),
+    #[error("The service user '{0}' could not be found")]
+    ServiceUserNotFound(String),
     #[error("Could not find process at '{0}'")]
     ServiceProcessNotFound(String),
     #[error("The service '{0}' does not exists and cannot be removed.")]
     ServiceDoesNotExists(String),
     #[error("The user may have removed the '{0}' service outwith the node manager")]
     ServiceRemovedManually(String),
+    #[error("The registry has no snapshot with the ID {0}")]
+    RegistrySnapshotNotFound(u64),
     #[error("Failed to create service user account")]
     ServiceUserAccountCreationFailed,
     #[error("Could not obtain user's data directory")]
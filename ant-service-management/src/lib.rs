@@ -35,7 +35,9 @@ use service_manager::ServiceInstallCtx;
 pub use daemon::{DaemonService, DaemonServiceData};
 pub use error::{Error, Result};
 pub use node::{NodeService, NodeServiceData};
-pub use registry::{NodeRegistryManager, StatusSummary, get_local_node_registry_path};
+pub use registry::{
+    NodeRegistryManager, RegistrySnapshot, StatusSummary, get_local_node_registry_path,
+};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ServiceStatus {
@@ -62,6 +64,8 @@ pub enum UpgradeResult {
     NotRequired,
     Upgraded(String, String),
     UpgradedButNotStarted(String, String, String),
+    /// The upgraded service was unhealthy, so it was rolled back to its previous binary.
+    RolledBack(String, String, String),
     Error(String),
 }
 
@@ -113,6 +113,14 @@ impl ServiceStateActions for NodeService {
             args.push(OsString::from("--max-log-files"));
             args.push(OsString::from(max_log_files.to_string()));
         }
+        if let Some(max_download_rate) = service_data.max_download_rate {
+            args.push(OsString::from("--max-download-rate"));
+            args.push(OsString::from(max_download_rate.to_string()));
+        }
+        if let Some(max_upload_rate) = service_data.max_upload_rate {
+            args.push(OsString::from("--max-upload-rate"));
+            args.push(OsString::from(max_upload_rate.to_string()));
+        }
 
         args.push(OsString::from("--rewards-address"));
         args.push(OsString::from(service_data.rewards_address.to_string()));
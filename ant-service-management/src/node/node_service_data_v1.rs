@@ -9,6 +9,7 @@
 use super::NodeServiceData;
 use crate::{
     ServiceStatus,
+    control::ServiceBackend,
     node::node_service_data_v2::{NODE_SERVICE_DATA_SCHEMA_V2, NodeServiceDataV2},
 };
 use ant_bootstrap::InitialPeersConfig;
@@ -85,6 +86,7 @@ impl From<NodeServiceDataV1> for NodeServiceDataV2 {
             alpha: false, //  Default value for upgraded instances
             antnode_path: v1.antnode_path,
             auto_restart: v1.auto_restart,
+            backend: ServiceBackend::Native,
             connected_peers: v1.connected_peers,
             data_dir_path: v1.data_dir_path,
             evm_network: v1.evm_network,
@@ -94,6 +96,8 @@ impl From<NodeServiceDataV1> for NodeServiceDataV2 {
             log_format: v1.log_format,
             max_archived_log_files: v1.max_archived_log_files,
             max_log_files: v1.max_log_files,
+            max_download_rate: None,
+            max_upload_rate: None,
             metrics_port: v1.metrics_port,
             network_id: v1.network_id,
             node_ip: v1.node_ip,
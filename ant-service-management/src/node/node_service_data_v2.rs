@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::NodeServiceData;
-use crate::{ServiceStatus, error::Result};
+use crate::{ServiceStatus, control::ServiceBackend, error::Result};
 use ant_bootstrap::InitialPeersConfig;
 use ant_evm::{AttoTokens, EvmNetwork, RewardsAddress};
 use ant_logging::LogFormat;
@@ -34,6 +34,8 @@ pub struct NodeServiceDataV2 {
     pub antnode_path: PathBuf,
     #[serde(default)]
     pub auto_restart: bool,
+    #[serde(default)]
+    pub backend: ServiceBackend,
     #[serde(serialize_with = "NodeServiceData::serialize_connected_peers")]
     pub connected_peers: Option<Vec<PeerId>>,
     pub data_dir_path: PathBuf,
@@ -45,6 +47,12 @@ pub struct NodeServiceDataV2 {
     pub log_format: Option<LogFormat>,
     pub max_archived_log_files: Option<usize>,
     pub max_log_files: Option<usize>,
+    /// The download rate limit, in kilobytes per second.
+    #[serde(default)]
+    pub max_download_rate: Option<u64>,
+    /// The upload rate limit, in kilobytes per second.
+    #[serde(default)]
+    pub max_upload_rate: Option<u64>,
     #[serde(default)]
     pub metrics_port: Option<u16>,
     pub network_id: Option<u8>,
@@ -87,6 +95,8 @@ impl NodeServiceDataV2 {
             antnode_path: PathBuf,
             #[serde(default)]
             auto_restart: bool,
+            #[serde(default)]
+            backend: ServiceBackend,
             #[serde(deserialize_with = "NodeServiceData::deserialize_connected_peers")]
             connected_peers: Option<Vec<PeerId>>,
             data_dir_path: PathBuf,
@@ -99,6 +109,10 @@ impl NodeServiceDataV2 {
             max_archived_log_files: Option<usize>,
             max_log_files: Option<usize>,
             #[serde(default)]
+            max_download_rate: Option<u64>,
+            #[serde(default)]
+            max_upload_rate: Option<u64>,
+            #[serde(default)]
             metrics_port: Option<u16>,
             network_id: Option<u8>,
             #[serde(default)]
@@ -132,6 +146,7 @@ impl NodeServiceDataV2 {
             schema_version: helper.schema_version,
             antnode_path: helper.antnode_path,
             auto_restart: helper.auto_restart,
+            backend: helper.backend,
             connected_peers: helper.connected_peers,
             data_dir_path: helper.data_dir_path,
             evm_network: helper.evm_network,
@@ -141,6 +156,8 @@ impl NodeServiceDataV2 {
             log_format: helper.log_format,
             max_archived_log_files: helper.max_archived_log_files,
             max_log_files: helper.max_log_files,
+            max_download_rate: helper.max_download_rate,
+            max_upload_rate: helper.max_upload_rate,
             metrics_port: helper.metrics_port,
             network_id: helper.network_id,
             node_ip: helper.node_ip,
@@ -169,6 +186,7 @@ mod tests {
     use super::super::node_service_data::NodeServiceData;
     use crate::{
         ServiceStatus,
+        control::ServiceBackend,
         node::{
             NODE_SERVICE_DATA_SCHEMA_LATEST,
             node_service_data_v2::{NODE_SERVICE_DATA_SCHEMA_V2, NodeServiceDataV2},
@@ -198,6 +216,7 @@ mod tests {
             no_upnp: false,
             relay: true,
             auto_restart: false,
+            backend: ServiceBackend::Native,
             connected_peers: None,
             evm_network: EvmNetwork::ArbitrumSepoliaTest,
             initial_peers_config: InitialPeersConfig {
@@ -212,6 +231,8 @@ mod tests {
             log_format: None,
             max_archived_log_files: None,
             max_log_files: None,
+            max_download_rate: None,
+            max_upload_rate: None,
             metrics_port: None,
             network_id: None,
             node_ip: None,
@@ -7,7 +7,9 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::error::{Error, Result};
-use crate::{DaemonServiceData, NatDetectionStatus, NodeServiceData};
+use crate::{
+    DaemonServiceData, NatDetectionStatus, NodeServiceData, ServiceStatus, control::ServiceBackend,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::{
@@ -16,6 +18,21 @@ use std::{
 };
 use tokio::sync::RwLock;
 
+/// The number of snapshots kept in the history of the registry.
+pub const REGISTRY_HISTORY_SIZE: usize = 20;
+/// The fields of a node that change as it runs, rather than through a command. Changes to them
+/// do not produce a snapshot.
+pub const VOLATILE_NODE_FIELDS: [&str; 4] =
+    ["connected_peers", "listen_addr", "pid", "reward_balance"];
+
+/// A snapshot of the registry, taken before it was overwritten.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegistrySnapshot {
+    /// The time the snapshot was taken, in milliseconds since the Unix epoch.
+    pub id: u64,
+    pub path: PathBuf,
+}
+
 /// Used to manage the NodeRegistry data and allows us to share the data across multiple threads.
 ///
 /// Can be cloned freely.
@@ -74,12 +91,94 @@ impl NodeRegistryManager {
     }
 
     /// Saves the current state of the node registry to the specified path.
+    ///
+    /// The file is replaced atomically. If the configuration of the services changed, the
+    /// previous registry is kept as a snapshot in its history.
     pub async fn save(&self) -> Result<()> {
         let registry = self.to_registry().await;
         registry.save()?;
         Ok(())
     }
 
+    /// The directory holding the snapshots of the registry.
+    pub fn history_dir_path(&self) -> PathBuf {
+        history_dir_path(&self.save_path)
+    }
+
+    /// The snapshots of the registry, from the oldest to the most recent.
+    pub fn snapshots(&self) -> Result<Vec<RegistrySnapshot>> {
+        list_snapshots(&self.save_path)
+    }
+
+    /// Loads a snapshot of the registry. It is saved to the path of this registry.
+    pub fn load_snapshot(&self, id: u64) -> Result<Self> {
+        let snapshot = self
+            .snapshots()?
+            .into_iter()
+            .find(|snapshot| snapshot.id == id)
+            .ok_or(Error::RegistrySnapshotNotFound(id))?;
+        let mut registry = NodeRegistry::load(&snapshot.path)?;
+        registry.save_path = self.save_path.clone();
+        Ok(NodeRegistryManager::from(registry))
+    }
+
+    /// Replaces the contents of the registry with a snapshot, and saves it. The registry being
+    /// replaced is itself kept as a snapshot, so a restore can be undone.
+    pub async fn restore(&self, id: u64) -> Result<()> {
+        let snapshot = self.load_snapshot(id)?;
+        self.replace_with(snapshot).await;
+        self.save().await
+    }
+
+    /// Reads the registry from its path again, to pick up the changes saved by other processes
+    /// since it was loaded. Nothing changes if it was never saved.
+    pub async fn reload(&self) -> Result<()> {
+        if !self.save_path.exists() {
+            return Ok(());
+        }
+        let registry = NodeRegistryManager::load(&self.save_path).await?;
+        self.replace_with(registry).await;
+        Ok(())
+    }
+
+    /// The entries are updated in place, so the nodes and daemon callers already hold see the
+    /// new contents. Nodes that are no longer in `other` are dropped from the registry.
+    async fn replace_with(&self, other: NodeRegistryManager) {
+        {
+            let mut daemon = self.daemon.write().await;
+            match (daemon.as_ref(), other.daemon.read().await.as_ref()) {
+                (Some(current), Some(new)) => {
+                    *current.write().await = new.read().await.clone();
+                }
+                (_, new) => *daemon = new.cloned(),
+            }
+        }
+        *self.environment_variables.write().await =
+            other.environment_variables.read().await.clone();
+        *self.nat_status.write().await = other.nat_status.read().await.clone();
+
+        let mut nodes = self.nodes.write().await;
+        let mut updated = Vec::new();
+        for new in other.nodes.read().await.iter() {
+            let new_data = new.read().await.clone();
+            let mut current = None;
+            for node in nodes.iter() {
+                if node.read().await.service_name == new_data.service_name {
+                    current = Some(Arc::clone(node));
+                    break;
+                }
+            }
+            match current {
+                Some(current) => {
+                    *current.write().await = new_data;
+                    updated.push(current);
+                }
+                None => updated.push(Arc::clone(new)),
+            }
+        }
+        *nodes = updated;
+    }
+
     /// Converts the current state of the `NodeRegistryManager` to a `NodeRegistry`.
     async fn to_registry(&self) -> NodeRegistry {
         let nodes = self.get_node_service_data().await;
@@ -124,6 +223,18 @@ impl NodeRegistryManager {
         }
         node_services
     }
+
+    /// The backend new services are run by: the one of the most recently added service that was
+    /// not removed, or the default for a registry without any.
+    pub async fn service_backend(&self) -> ServiceBackend {
+        for node in self.nodes.read().await.iter().rev() {
+            let node = node.read().await;
+            if node.status != ServiceStatus::Removed {
+                return node.backend.clone();
+            }
+        }
+        ServiceBackend::default()
+    }
 }
 
 /// The struct that is written to the fs.
@@ -156,11 +267,80 @@ impl NodeRegistry {
         }
 
         let json = serde_json::to_string(self)?;
-        let mut file = std::fs::File::create(self.save_path.clone())
+        if let Ok(previous) = std::fs::read_to_string(path)
+            && Self::configuration_changed(&previous, &json)
+        {
+            // Losing the history should not prevent the registry from being saved.
+            if let Err(err) = self.snapshot(&previous) {
+                warn!("Failed to keep a snapshot of the node registry: {err:?}");
+            }
+        }
+
+        // Write to a temporary file that replaces the registry, so that it is never left
+        // partially written.
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".tmp");
+        let tmp_path = path.with_file_name(file_name);
+        let mut file = std::fs::File::create(&tmp_path)
             .inspect_err(|err| error!("Error creating node registry file: {err:?}"))?;
         file.write_all(json.as_bytes())
             .inspect_err(|err| error!("Error writing to node registry: {err:?}"))?;
+        file.sync_all()?;
+        // The registry can be made writable by every user, which has to be retained.
+        if let Ok(metadata) = std::fs::metadata(path) {
+            std::fs::set_permissions(&tmp_path, metadata.permissions())?;
+        }
+        std::fs::rename(&tmp_path, path)
+            .inspect_err(|err| error!("Error replacing the node registry: {err:?}"))?;
+
+        Ok(())
+    }
+
+    /// Whether the registries differ in more than the volatile fields of their nodes.
+    fn configuration_changed(previous: &str, current: &str) -> bool {
+        let configuration = |json: &str| {
+            let mut value: serde_json::Value = serde_json::from_str(json).ok()?;
+            for node in value.get_mut("nodes")?.as_array_mut()? {
+                let node = node.as_object_mut()?;
+                for field in VOLATILE_NODE_FIELDS {
+                    node.remove(field);
+                }
+                // Only the removal of a node is a change of configuration; it is otherwise
+                // started and stopped.
+                if node.get("status").and_then(|status| status.as_str()) != Some("Removed") {
+                    node.remove("status");
+                }
+            }
+            Some(value)
+        };
+        match (configuration(previous), configuration(current)) {
+            (Some(previous), Some(current)) => previous != current,
+            // A registry that cannot be parsed is not worth keeping.
+            (None, _) => false,
+            (Some(_), None) => true,
+        }
+    }
+
+    /// Keep the registry as a snapshot, deleting the oldest snapshots beyond the history size.
+    fn snapshot(&self, contents: &str) -> Result<()> {
+        let history_dir = history_dir_path(&self.save_path);
+        std::fs::create_dir_all(&history_dir)?;
+        let mut id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis() as u64);
+        let snapshots = list_snapshots(&self.save_path)?;
+        if let Some(last) = snapshots.last() {
+            id = id.max(last.id + 1);
+        }
+        let snapshot_path = history_dir.join(snapshot_file_name(&self.save_path, id));
+        debug!("Keeping the previous node registry as {snapshot_path:?}");
+        std::fs::write(&snapshot_path, contents)?;
 
+        let excess = (snapshots.len() + 1).saturating_sub(REGISTRY_HISTORY_SIZE);
+        for snapshot in snapshots.into_iter().take(excess) {
+            debug!("Deleting the registry snapshot {:?}", snapshot.path);
+            std::fs::remove_file(&snapshot.path)?;
+        }
         Ok(())
     }
 
@@ -213,6 +393,48 @@ impl NodeRegistry {
     }
 }
 
+fn history_dir_path(registry_path: &Path) -> PathBuf {
+    let stem = registry_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    registry_path.with_file_name(format!("{stem}_history"))
+}
+
+fn snapshot_file_name(registry_path: &Path, id: u64) -> String {
+    let stem = registry_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    format!("{stem}.{id}.json")
+}
+
+fn list_snapshots(registry_path: &Path) -> Result<Vec<RegistrySnapshot>> {
+    let history_dir = history_dir_path(registry_path);
+    if !history_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let stem = registry_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let mut snapshots = Vec::new();
+    for entry in std::fs::read_dir(&history_dir)? {
+        let path = entry?.path();
+        let id = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&format!("{stem}.")))
+            .and_then(|name| name.strip_suffix(".json"))
+            .and_then(|id| id.parse().ok());
+        if let Some(id) = id {
+            snapshots.push(RegistrySnapshot { id, path });
+        }
+    }
+    snapshots.sort_by_key(|snapshot| snapshot.id);
+    Ok(snapshots)
+}
+
 pub fn get_local_node_registry_path() -> Result<PathBuf> {
     let path = dirs_next::data_dir()
         .ok_or_else(|| {
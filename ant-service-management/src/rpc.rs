@@ -10,8 +10,9 @@ use crate::error::{Error, Result};
 use ant_protocol::{
     CLOSE_GROUP_SIZE,
     antnode_proto::{
-        NetworkInfoRequest, NodeInfoRequest, RecordAddressesRequest, RestartRequest, StopRequest,
-        UpdateLogLevelRequest, UpdateRequest, ant_node_client::AntNodeClient,
+        KBucketsRequest, NetworkInfoRequest, NodeInfoRequest, RecordAddressesRequest,
+        RestartRequest, StopRequest, UpdateLogLevelRequest, UpdateRequest,
+        ant_node_client::AntNodeClient,
     },
 };
 use async_trait::async_trait;
@@ -48,6 +49,7 @@ pub trait RpcActions: Sync {
     async fn node_info(&self) -> Result<NodeInfo>;
     async fn network_info(&self) -> Result<NetworkInfo>;
     async fn record_addresses(&self) -> Result<Vec<RecordAddress>>;
+    async fn routing_table_size(&self) -> Result<usize>;
     async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> Result<()>;
     async fn node_stop(&self, delay_millis: u64) -> Result<()>;
     async fn node_update(&self, delay_millis: u64) -> Result<()>;
@@ -188,6 +190,24 @@ impl RpcActions for RpcClient {
         Ok(record_addresses)
     }
 
+    async fn routing_table_size(&self) -> Result<usize> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .k_buckets(Request::new(KBucketsRequest {}))
+            .await
+            .map_err(|e| {
+                error!("Could not obtain the k-buckets through RPC: {e:?}");
+                Error::RpcKBucketsError(e.to_string())
+            })?;
+        let size = response
+            .get_ref()
+            .kbuckets
+            .values()
+            .map(|bucket| bucket.peers.len())
+            .sum();
+        Ok(size)
+    }
+
     async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> Result<()> {
         let mut client = self.connect_with_retry().await?;
         let _response = client
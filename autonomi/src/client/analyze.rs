@@ -8,6 +8,7 @@
 
 use ant_protocol::storage::{PointerTarget, ScratchpadAddress};
 use self_encryption::{ChunkInfo, DataMap};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     Bytes, Client, PublicKey,
@@ -68,6 +69,39 @@ pub enum Analysis {
     PrivateArchive(PrivateArchive),
 }
 
+/// What a data map points to, the part of an [`Analysis`] needed to download it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DataMapContent {
+    PublicArchive(PublicArchive),
+    PrivateArchive(PrivateArchive),
+    /// The content of a file.
+    Data(Bytes),
+    /// A file too large to be fetched in memory, which has to be downloaded to disk.
+    TooLargeForMemory,
+}
+
+impl TryFrom<Analysis> for DataMapContent {
+    type Error = Analysis;
+
+    /// Fails with the analysis of anything other than a data map.
+    fn try_from(analysis: Analysis) -> Result<Self, Analysis> {
+        match analysis {
+            Analysis::RawDataMap {
+                data: Some(data), ..
+            }
+            | Analysis::DataMap {
+                data: Some(data), ..
+            } => Ok(Self::Data(data)),
+            Analysis::RawDataMap { data: None, .. } | Analysis::DataMap { data: None, .. } => {
+                Ok(Self::TooLargeForMemory)
+            }
+            Analysis::PublicArchive { archive, .. } => Ok(Self::PublicArchive(archive)),
+            Analysis::PrivateArchive(archive) => Ok(Self::PrivateArchive(archive)),
+            other => Err(other),
+        }
+    }
+}
+
 impl std::fmt::Display for Analysis {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
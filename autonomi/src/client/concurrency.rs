@@ -0,0 +1,255 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::config::Concurrency;
+use crate::client::{GetError, PutError};
+use crate::networking::NetworkError;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Weight of the latest operation in the latency and error rate averages.
+const EWMA_ALPHA: f64 = 0.1;
+/// An operation taking more than this many times the average latency is a sign of congestion.
+const LATENCY_TOLERANCE: f64 = 2.0;
+/// Concurrency is only raised while the error rate stays below this.
+const MAX_HEALTHY_ERROR_RATE: f64 = 0.1;
+/// Factor applied to the concurrency on a timeout.
+const BACKOFF_FACTOR: f64 = 0.5;
+
+/// How an operation run under a [`ConcurrencyController`] went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TaskOutcome {
+    Success,
+    /// The operation timed out, the network or the peers are likely overloaded.
+    Timeout,
+    /// The operation failed for another reason.
+    Failure,
+}
+
+impl TaskOutcome {
+    pub(crate) fn of_network_error(err: &NetworkError) -> Self {
+        if err.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Failure
+        }
+    }
+
+    pub(crate) fn of_put_error(err: &PutError) -> Self {
+        match err {
+            PutError::Network { network_error, .. } => Self::of_network_error(network_error),
+            _ => Self::Failure,
+        }
+    }
+
+    pub(crate) fn of_get_error(err: &GetError) -> Self {
+        match err {
+            GetError::Network(network_error) => Self::of_network_error(network_error),
+            _ => Self::Failure,
+        }
+    }
+}
+
+/// Adapts the number of operations run at once to how they go, AIMD-style:
+/// additive increase while latency and error rate are healthy, multiplicative decrease on
+/// timeouts.
+#[derive(Debug)]
+pub(crate) struct ConcurrencyController {
+    min: f64,
+    max: f64,
+    adaptive: bool,
+    state: Mutex<ControllerState>,
+}
+
+#[derive(Debug)]
+struct ControllerState {
+    limit: f64,
+    /// Average latency of the successful operations, in seconds.
+    latency: Option<f64>,
+    error_rate: f64,
+    last_backoff: Option<Instant>,
+}
+
+impl ConcurrencyController {
+    pub(crate) fn new(concurrency: Concurrency) -> Self {
+        let (initial, min, max, adaptive) = match concurrency {
+            Concurrency::Fixed(n) => (n.get(), n.get(), n.get(), false),
+            Concurrency::Adaptive { initial, min, max } => {
+                let max = max.get().max(min.get());
+                (initial.get().clamp(min.get(), max), min.get(), max, true)
+            }
+        };
+        Self {
+            min: min as f64,
+            max: max as f64,
+            adaptive,
+            state: Mutex::new(ControllerState {
+                limit: initial as f64,
+                latency: None,
+                error_rate: 0.0,
+                last_backoff: None,
+            }),
+        }
+    }
+
+    /// The number of operations to run at once.
+    pub(crate) fn limit(&self) -> usize {
+        self.lock_state().limit as usize
+    }
+
+    /// Adapt the limit to an operation that took `elapsed`.
+    pub(crate) fn record(&self, outcome: TaskOutcome, elapsed: Duration) {
+        if !self.adaptive {
+            return;
+        }
+        let elapsed = elapsed.as_secs_f64();
+        let mut state = self.lock_state();
+        let previous_limit = state.limit as usize;
+
+        let error = if outcome == TaskOutcome::Success {
+            0.0
+        } else {
+            1.0
+        };
+        state.error_rate = ewma(state.error_rate, error);
+
+        match outcome {
+            TaskOutcome::Success => {
+                let timely = state
+                    .latency
+                    .is_none_or(|latency| elapsed <= latency * LATENCY_TOLERANCE);
+                if timely && state.error_rate < MAX_HEALTHY_ERROR_RATE {
+                    // One more operation per round of `limit` operations
+                    state.limit = (state.limit + 1.0 / state.limit).min(self.max);
+                }
+                state.latency = Some(state.latency.map_or(elapsed, |l| ewma(l, elapsed)));
+            }
+            TaskOutcome::Timeout => {
+                // The operations running at once time out together, only back off once for them.
+                // Until an operation succeeds, a round lasts as long as it took this one to time out.
+                let round = state.latency.unwrap_or(elapsed);
+                let recently_backed_off = state
+                    .last_backoff
+                    .is_some_and(|at| at.elapsed().as_secs_f64() < round);
+                if !recently_backed_off {
+                    state.limit = (state.limit * BACKOFF_FACTOR).max(self.min);
+                    state.last_backoff = Some(Instant::now());
+                }
+            }
+            TaskOutcome::Failure => {}
+        }
+
+        let limit = state.limit as usize;
+        if limit != previous_limit {
+            debug!(
+                "Concurrency changed from {previous_limit} to {limit} (latency: {:?}s, error rate: {:.2})",
+                state.latency, state.error_rate
+            );
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, ControllerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn ewma(average: f64, value: f64) -> f64 {
+    average + EWMA_ALPHA * (value - average)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZero;
+
+    fn adaptive(initial: usize, min: usize, max: usize) -> ConcurrencyController {
+        ConcurrencyController::new(Concurrency::Adaptive {
+            initial: NonZero::new(initial).unwrap(),
+            min: NonZero::new(min).unwrap(),
+            max: NonZero::new(max).unwrap(),
+        })
+    }
+
+    #[test]
+    fn healthy_operations_raise_concurrency_up_to_max() {
+        let controller = adaptive(1, 1, 8);
+        for _ in 0..10 {
+            controller.record(TaskOutcome::Success, Duration::from_millis(100));
+        }
+        assert!(controller.limit() > 2);
+
+        for _ in 0..1000 {
+            controller.record(TaskOutcome::Success, Duration::from_millis(100));
+        }
+        assert_eq!(controller.limit(), 8);
+    }
+
+    #[test]
+    fn timeouts_halve_concurrency_once_per_round() {
+        let controller = adaptive(16, 2, 16);
+        controller.record(TaskOutcome::Success, Duration::from_secs(10));
+        controller.record(TaskOutcome::Timeout, Duration::from_secs(10));
+        assert_eq!(controller.limit(), 8);
+
+        // The other operations of the round timing out don't back off again
+        controller.record(TaskOutcome::Timeout, Duration::from_secs(10));
+        assert_eq!(controller.limit(), 8);
+    }
+
+    #[test]
+    fn timeouts_before_any_success_keep_backing_off_to_min() {
+        let controller = adaptive(16, 2, 16);
+        controller.record(TaskOutcome::Timeout, Duration::ZERO);
+        assert_eq!(controller.limit(), 8);
+        controller.record(TaskOutcome::Timeout, Duration::ZERO);
+        assert_eq!(controller.limit(), 4);
+        for _ in 0..3 {
+            controller.record(TaskOutcome::Timeout, Duration::ZERO);
+        }
+        assert_eq!(controller.limit(), 2);
+
+        // The other operations of a round that took a while to time out don't back off again
+        let controller = adaptive(16, 2, 16);
+        controller.record(TaskOutcome::Timeout, Duration::from_secs(30));
+        controller.record(TaskOutcome::Timeout, Duration::from_secs(30));
+        assert_eq!(controller.limit(), 8);
+    }
+
+    #[test]
+    fn concurrency_is_not_raised_while_slow_or_failing() {
+        let controller = adaptive(4, 1, 16);
+        controller.record(TaskOutcome::Success, Duration::from_millis(100));
+        let limit = controller.limit();
+
+        for _ in 0..3 {
+            controller.record(TaskOutcome::Success, Duration::from_secs(1));
+        }
+        assert_eq!(controller.limit(), limit);
+
+        let controller = adaptive(4, 1, 16);
+        for _ in 0..5 {
+            controller.record(TaskOutcome::Failure, Duration::from_millis(100));
+        }
+        for _ in 0..4 {
+            controller.record(TaskOutcome::Success, Duration::from_millis(100));
+        }
+        assert_eq!(controller.limit(), 4);
+    }
+
+    #[test]
+    fn fixed_concurrency_never_changes() {
+        let controller = ConcurrencyController::new(Concurrency::Fixed(NonZero::new(3).unwrap()));
+        controller.record(TaskOutcome::Timeout, Duration::from_secs(1));
+        for _ in 0..100 {
+            controller.record(TaskOutcome::Success, Duration::from_millis(10));
+        }
+        assert_eq!(controller.limit(), 3);
+    }
+}
@@ -7,34 +7,18 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::networking::{Quorum, RetryStrategy, Strategy};
+use crate::self_encryption::{Compression, EncryptionOptions, Resilience};
 pub use ant_bootstrap::{BootstrapCacheConfig, InitialPeersConfig, error::Error as BootstrapError};
 use ant_evm::EvmNetwork;
 use evmlib::contract::payment_vault::MAX_TRANSFERS_PER_TRANSACTION;
 use std::{num::NonZero, sync::LazyLock};
 
-/// Number of chunks to upload in parallel.
-///
-/// Can be overridden by the `CHUNK_UPLOAD_BATCH_SIZE` environment variable.
-pub(crate) static CHUNK_UPLOAD_BATCH_SIZE: LazyLock<usize> = LazyLock::new(|| {
-    let batch_size = std::env::var("CHUNK_UPLOAD_BATCH_SIZE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(1);
-    info!("Chunk upload batch size: {}", batch_size);
-    batch_size
-});
-
 /// Number of chunks to download in parallel.
 ///
 /// Can be overridden by the `CHUNK_DOWNLOAD_BATCH_SIZE` environment variable.
-pub static CHUNK_DOWNLOAD_BATCH_SIZE: LazyLock<usize> = LazyLock::new(|| {
-    let batch_size = std::env::var("CHUNK_DOWNLOAD_BATCH_SIZE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(1);
-    info!("Chunk download batch size: {}", batch_size);
-    batch_size
-});
+#[deprecated(note = "Use `ClientOperatingStrategy::chunk_downloads` instead")]
+pub static CHUNK_DOWNLOAD_BATCH_SIZE: LazyLock<usize> =
+    LazyLock::new(|| BATCH_SIZES_FROM_ENV.chunk_downloads.map_or(1, NonZero::get));
 
 /// Maximum number of chunks that we allow to download from a datamap in memory.
 /// This affects the maximum size of data downloaded with APIs such as [`crate::Client::data_get`]
@@ -52,14 +36,9 @@ pub static MAX_IN_MEMORY_DOWNLOAD_SIZE: LazyLock<usize> = LazyLock::new(|| {
 /// Number of files to upload in parallel.
 ///
 /// Can be overridden by the `FILE_UPLOAD_BATCH_SIZE` environment variable.
-pub static FILE_UPLOAD_BATCH_SIZE: LazyLock<usize> = LazyLock::new(|| {
-    let batch_size = std::env::var("FILE_UPLOAD_BATCH_SIZE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(1);
-    info!("File upload batch size: {}", batch_size);
-    batch_size
-});
+#[deprecated(note = "Use `ClientOperatingStrategy::file_uploads` instead")]
+pub static FILE_UPLOAD_BATCH_SIZE: LazyLock<usize> =
+    LazyLock::new(|| BATCH_SIZES_FROM_ENV.file_uploads.map_or(1, NonZero::get));
 
 /// Number of files to encrypt in parallel.
 ///
@@ -94,19 +73,56 @@ pub static IN_MEMORY_ENCRYPTION_MAX_SIZE: LazyLock<usize> = LazyLock::new(|| {
     max_size
 });
 
-/// Number of batch size of an entire quote-pay-upload flow to process.
-/// Suggested to be multiples of `MAX_TRANSFERS_PER_TRANSACTION  / 3` (records-payouts-per-transaction).
-///
-/// Can be overridden by the `UPLOAD_FLOW_BATCH_SIZE` environment variable.
-pub(crate) static UPLOAD_FLOW_BATCH_SIZE: LazyLock<usize> = LazyLock::new(|| {
-    let batch_size = std::env::var("UPLOAD_FLOW_BATCH_SIZE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(MAX_TRANSFERS_PER_TRANSACTION / 3);
-    info!("Upload flow batch size: {}", batch_size);
-    batch_size
+/// Batch sizes set by environment variables, from before the batch sizes were part of
+/// [`ClientOperatingStrategy`]. They are read once, as the default strategy is built often.
+static BATCH_SIZES_FROM_ENV: LazyLock<BatchSizesFromEnv> = LazyLock::new(|| BatchSizesFromEnv {
+    chunk_uploads: batch_size_from_env("CHUNK_UPLOAD_BATCH_SIZE"),
+    chunk_downloads: batch_size_from_env("CHUNK_DOWNLOAD_BATCH_SIZE"),
+    file_uploads: batch_size_from_env("FILE_UPLOAD_BATCH_SIZE"),
+    upload_flow: batch_size_from_env("UPLOAD_FLOW_BATCH_SIZE"),
 });
 
+struct BatchSizesFromEnv {
+    chunk_uploads: Option<NonZero<usize>>,
+    chunk_downloads: Option<NonZero<usize>>,
+    file_uploads: Option<NonZero<usize>>,
+    upload_flow: Option<NonZero<usize>>,
+}
+
+/// Batch size set by the given environment variable.
+fn batch_size_from_env(var: &str) -> Option<NonZero<usize>> {
+    let batch_size = std::env::var(var).ok()?.parse().ok()?;
+    info!("{var} is set, using a fixed batch size of {batch_size}");
+    Some(batch_size)
+}
+
+/// How many operations of a kind the client runs at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Concurrency {
+    /// Always run this many operations at once.
+    Fixed(NonZero<usize>),
+    /// Start with `initial` operations at once and adapt between `min` and `max`.
+    ///
+    /// One more operation is run at once for every round of operations completing in a timely
+    /// manner without many errors, and half as many after a timeout.
+    Adaptive {
+        initial: NonZero<usize>,
+        min: NonZero<usize>,
+        max: NonZero<usize>,
+    },
+}
+
+impl Concurrency {
+    /// Adapt from a single operation at once up to `max`.
+    pub fn adaptive(max: NonZero<usize>) -> Self {
+        Self::Adaptive {
+            initial: NonZero::<usize>::MIN,
+            min: NonZero::<usize>::MIN,
+            max,
+        }
+    }
+}
+
 /// Configuration for the [`crate::Client`] which can be provided through: [`crate::Client::init_with_config`].
 #[derive(Debug, Clone, Default)]
 pub struct ClientConfig {
@@ -122,6 +138,9 @@ pub struct ClientConfig {
 
     /// The network ID to use for the client.
     /// This is used to differentiate between different networks.
+    ///
+    /// The ID only applies to this client, so clients of different networks can coexist in one
+    /// process. Defaults to the process-wide network ID, see [`crate::version::set_network_id`].
     pub network_id: Option<u8>,
 
     /// Strategy for data operations by the client.
@@ -131,6 +150,10 @@ pub struct ClientConfig {
 /// Strategy configuration for data operations by the client.
 ///
 /// Default values are used for each type of data, but you can override them here.
+///
+/// The `CHUNK_UPLOAD_BATCH_SIZE`, `CHUNK_DOWNLOAD_BATCH_SIZE`, `FILE_UPLOAD_BATCH_SIZE` and
+/// `UPLOAD_FLOW_BATCH_SIZE` environment variables are still honoured by the defaults, the chunk
+/// ones fixing the concurrency to the given number of chunks.
 #[derive(Debug, Clone)]
 pub struct ClientOperatingStrategy {
     pub chunks: Strategy,
@@ -141,12 +164,38 @@ pub struct ClientOperatingStrategy {
     pub chunk_cache_enabled: bool,
     /// Custom chunk cache directory (if None, uses default)
     pub chunk_cache_dir: Option<std::path::PathBuf>,
+    /// Compress data before self-encrypting it on upload, reducing the number of chunks to pay
+    /// for when the data compresses well (text, JSON, logs...). Disabled by default.
+    /// Compressed data is decompressed on download whatever this is set to.
+    pub compression: Option<Compression>,
+    /// Add parity chunks to uploaded data, so that lost chunks can be reconstructed from the
+    /// others on download, or uploaded again with [`crate::Client::data_repair`].
+    /// Disabled by default.
+    pub resilience: Option<Resilience>,
+    /// Number of chunks uploaded at once.
+    pub chunk_uploads: Concurrency,
+    /// Number of chunks downloaded at once, also used for the batched queries to the peers
+    /// holding them.
+    pub chunk_downloads: Concurrency,
+    /// Number of vault files uploaded at once.
+    pub file_uploads: NonZero<usize>,
+    /// Number of chunks to go through a quote-pay-upload flow at once.
+    /// Suggested to be multiples of `MAX_TRANSFERS_PER_TRANSACTION / 3` (records-payouts-per-transaction).
+    pub upload_flow_batch_size: NonZero<usize>,
 }
 
 impl ClientOperatingStrategy {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// How uploaded data gets encoded around its self-encryption.
+    pub(crate) fn encryption_options(&self) -> EncryptionOptions {
+        EncryptionOptions {
+            compression: self.compression,
+            resilience: self.resilience,
+        }
+    }
 }
 
 /// The default configuration for the client.
@@ -187,6 +236,23 @@ impl Default for ClientOperatingStrategy {
             },
             chunk_cache_enabled: true,
             chunk_cache_dir: None,
+            compression: None,
+            resilience: None,
+            chunk_uploads: BATCH_SIZES_FROM_ENV.chunk_uploads.map_or(
+                Concurrency::adaptive(NonZero::new(16).expect("16 is non 0")),
+                Concurrency::Fixed,
+            ),
+            chunk_downloads: BATCH_SIZES_FROM_ENV.chunk_downloads.map_or(
+                Concurrency::adaptive(NonZero::new(32).expect("32 is non 0")),
+                Concurrency::Fixed,
+            ),
+            file_uploads: BATCH_SIZES_FROM_ENV
+                .file_uploads
+                .unwrap_or(NonZero::<usize>::MIN),
+            upload_flow_batch_size: BATCH_SIZES_FROM_ENV.upload_flow.unwrap_or(
+                NonZero::new(MAX_TRANSFERS_PER_TRANSACTION / 3)
+                    .expect("MAX_TRANSFERS_PER_TRANSACTION is at least 3"),
+            ),
         }
     }
 }
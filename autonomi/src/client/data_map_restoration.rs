@@ -8,13 +8,73 @@
 
 use crate::ChunkAddress;
 use crate::client::data_types::chunk::DataMapChunk;
+use crate::client::high_level::data::resilience::StripeRecovery;
 use crate::client::{Client, GetError};
-use crate::self_encryption::DataMapLevel;
+use crate::self_encryption::{
+    CompressedFrames, DataMapLevel, EncryptionOptions, ParityRecord, ParityShards, WrappedDataMap,
+};
 use bytes::Bytes;
 use eyre::Result;
 use self_encryption::{ChunkInfo, DataMap, get_root_data_map};
+use std::collections::HashSet;
+use std::sync::Arc;
 use xor_name::XorName;
 
+/// A datamap restored from its chunk, along with what's needed to read the data it points to.
+pub(crate) struct RestoredDataMap {
+    pub data_map: DataMap,
+    /// Set if the data was compressed before being encrypted
+    pub compression: Option<CompressedFrames>,
+    /// Set if parity chunks were added to the data, to reconstruct lost chunks
+    pub parity: Option<Arc<ParityShards>>,
+    /// Chunks holding the upper levels of the datamap and the parity record if it was too large
+    /// for the datamap chunk, fetched to restore them
+    pub level_chunks: Vec<ChunkAddress>,
+}
+
+impl RestoredDataMap {
+    fn plain(data_map: DataMap, level_chunks: Vec<ChunkAddress>) -> Self {
+        Self {
+            data_map,
+            compression: None,
+            parity: None,
+            level_chunks,
+        }
+    }
+
+    /// Addresses of all the chunks of the data: the levels of its datamap, its content and its
+    /// parity chunks, without duplicates.
+    pub(crate) fn chunk_addresses(&self) -> Vec<ChunkAddress> {
+        let content = self
+            .data_map
+            .infos()
+            .into_iter()
+            .map(|info| ChunkAddress::new(info.dst_hash));
+        let parity = self
+            .parity
+            .iter()
+            .flat_map(|parity| parity.shard_names())
+            .map(ChunkAddress::new);
+
+        let mut seen = HashSet::new();
+        self.level_chunks
+            .iter()
+            .copied()
+            .chain(content)
+            .chain(parity)
+            .filter(|addr| seen.insert(*addr))
+            .collect()
+    }
+
+    /// The options the data was encrypted with, to get the same chunks out of it again.
+    pub(crate) fn encryption_options(&self) -> EncryptionOptions {
+        EncryptionOptions {
+            compression: self.compression.as_ref().map(|frames| frames.compression()),
+            resilience: self.parity.as_ref().and_then(|parity| parity.resilience()),
+        }
+    }
+}
+
 impl Client {
     /// Restore a complete datamap from a DataMapChunk, handling both old and new formats
     /// This function properly handles recursive DataMapLevel schemes by traversing all levels
@@ -27,32 +87,66 @@ impl Client {
     ///   * compose the real datamap of the file, from that 3 datamap_chunks
     ///   * repeat the above two steps if the recovered data_map contains chiled
     ///   * fetch the leftover content chunks to compose the file
+    ///
+    /// Data that was compressed or got parity chunks on upload has a wrapped datamap, recording
+    /// what's needed to read it back, see [`RestoredDataMap`].
     pub(crate) async fn restore_data_map_from_chunk(
         &self,
         data_map_chunk: &DataMapChunk,
-    ) -> Result<DataMap, GetError> {
+    ) -> Result<RestoredDataMap, GetError> {
         let mut data_map_bytes = data_map_chunk.0.value().clone();
 
+        if let Some(wrapped) = WrappedDataMap::from_bytes(&data_map_bytes) {
+            let (data_map, compression, parity) =
+                wrapped.map_err(GetError::InvalidDataMap)?.into_parts();
+            info!("Restoring from wrapped data_map:\n{data_map:?}");
+            let (parity, mut level_chunks) = match parity {
+                Some(ParityRecord::Inline(parity)) => (Some(parity), vec![]),
+                Some(ParityRecord::Chunked(parity_map)) => {
+                    let (parity, parity_chunks) = self.fetch_parity(&parity_map).await?;
+                    (Some(parity), parity_chunks)
+                }
+                None => (None, vec![]),
+            };
+            let parity = parity.map(Arc::new);
+            let (file_data_map, file_level_chunks) =
+                self.fetch_new_data_map(&data_map, parity.clone())?;
+            level_chunks.extend(file_level_chunks);
+            return Ok(RestoredDataMap {
+                data_map: file_data_map,
+                compression,
+                parity,
+                level_chunks,
+            });
+        }
+
         // In case the input is the new data_map root, restore it further,
         // before going into the further recursive.
         if let Ok(data_map) = rmp_serde::from_slice::<DataMap>(&data_map_bytes) {
             info!("Restoring from new root data_map:\n{data_map:?}");
-            let file_data_map = self.fetch_new_data_map(&data_map)?;
+            let (file_data_map, level_chunks) = self.fetch_new_data_map(&data_map, None)?;
 
             info!("Fetched file data_map of new version: \n{file_data_map:?}");
-            return Ok(file_data_map);
+            return Ok(RestoredDataMap::plain(file_data_map, level_chunks));
         }
 
+        let mut level_chunks = vec![];
         loop {
             // The data_map_bytes could be an Archive, we shall return earlier for that case
             match Self::deserialize_data_map(&data_map_bytes) {
                 Ok(mut data_map) => {
                     info!("Restoring from data_map:\n{data_map:?}");
                     if !data_map.is_child() {
-                        return Ok(data_map);
+                        return Ok(RestoredDataMap::plain(data_map, level_chunks));
                     }
                     data_map.child = None;
-                    data_map_bytes = self.fetch_from_data_map(&data_map).await?;
+                    level_chunks.extend(
+                        data_map
+                            .infos()
+                            .iter()
+                            .map(|info| ChunkAddress::new(info.dst_hash)),
+                    );
+                    data_map_bytes = self.fetch_from_data_map(&data_map, None).await?;
                 }
                 Err(e) => {
                     info!("Failed to deserialize data_map_bytes: {e:?}");
@@ -62,9 +156,33 @@ impl Client {
         }
     }
 
+    /// Fetch a parity record stored in chunks of its own, returning it along with the addresses
+    /// of these chunks.
+    async fn fetch_parity(
+        &self,
+        parity_map: &DataMap,
+    ) -> Result<(ParityShards, Vec<ChunkAddress>), GetError> {
+        let (root_map, mut parity_chunks) = self.fetch_new_data_map(parity_map, None)?;
+        let bytes = self.fetch_from_data_map(&root_map, None).await?;
+        parity_chunks.extend(
+            root_map
+                .infos()
+                .iter()
+                .map(|info| ChunkAddress::new(info.dst_hash)),
+        );
+        self.cleanup_cached_chunks(&parity_chunks);
+        let parity = rmp_serde::from_slice(&bytes).map_err(GetError::InvalidDataMap)?;
+        Ok((parity, parity_chunks))
+    }
+
     /// Fetch the file data_map from the root one using lazy evaluation.
     /// Chunks are only fetched from the network when actually needed by get_root_data_map.
-    fn fetch_new_data_map(&self, data_map: &DataMap) -> Result<DataMap, GetError> {
+    /// Returns the file data_map along with the addresses of the chunks fetched to get it.
+    fn fetch_new_data_map(
+        &self,
+        data_map: &DataMap,
+        parity: Option<Arc<ParityShards>>,
+    ) -> Result<(DataMap, Vec<ChunkAddress>), GetError> {
         let total_chunks = data_map.infos().len();
         #[cfg(feature = "loud")]
         println!("Using lazy chunk fetching for {total_chunks} of datamap {data_map:?}");
@@ -72,13 +190,20 @@ impl Client {
 
         // Create a closure that fetches chunks on-demand
         let client = self.clone();
+        let recovery = parity.map(StripeRecovery::new);
+        let mut fetched = vec![];
+        let fetched_chunks = &mut fetched;
         let mut chunk_fetcher = move |xor_name: XorName| -> Result<Bytes, self_encryption::Error> {
             let chunk_addr = ChunkAddress::new(xor_name);
+            fetched_chunks.push(chunk_addr);
 
             // Use tokio::task::spawn_blocking to handle the async operation in a sync context
             let fetch_result = tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current()
-                    .block_on(async { client.chunk_get(&chunk_addr).await })
+                tokio::runtime::Handle::current().block_on(async {
+                    client
+                        .chunk_get_or_recover(&chunk_addr, recovery.as_ref())
+                        .await
+                })
             });
 
             match fetch_result {
@@ -113,7 +238,7 @@ impl Client {
         println!("Successfully processed datamap with lazy chunk fetching");
         debug!("Successfully processed datamap with lazy chunk fetching");
 
-        Ok(result_data_map)
+        Ok((result_data_map, fetched))
     }
 
     /// Deserialize datamap from bytes, handling both old and new formats
@@ -9,16 +9,17 @@
 use crate::client::chunk_cache::{
     default_cache_dir, delete_chunks, is_chunk_cached, load_chunk, store_chunk,
 };
-use crate::client::config::{CHUNK_DOWNLOAD_BATCH_SIZE, CHUNK_UPLOAD_BATCH_SIZE};
-use crate::networking::PeerInfo;
+use crate::client::high_level::data::resilience::StripeRecovery;
+use crate::networking::{NetworkError, PeerInfo, Quorum};
 use crate::{
     Client,
     client::{
         ChunkBatchUploadState, GetError, PutError,
+        concurrency::TaskOutcome,
         payment::{PaymentOption, Receipt},
         quote::CostError,
     },
-    utils::process_tasks_with_max_concurrency,
+    utils::process_tasks_with_adaptive_concurrency,
 };
 use ant_evm::{Amount, AttoTokens, ClientProofOfPayment};
 pub use ant_protocol::storage::{Chunk, ChunkAddress};
@@ -76,6 +77,23 @@ impl std::fmt::Debug for DataMapChunk {
     }
 }
 
+fn chunk_from_record(record: &Record) -> Result<Chunk, GetError> {
+    let header = RecordHeader::from_record(record)?;
+
+    if RecordHeader::is_record_of_type_chunk(record)? {
+        let chunk: Chunk = try_deserialize_record(record)?;
+        Ok(chunk)
+    } else {
+        error!(
+            "Record kind mismatch: expected Chunk, got {:?}",
+            header.kind
+        );
+        Err(GetError::RecordKindMismatch(RecordKind::DataOnly(
+            DataTypes::Chunk,
+        )))
+    }
+}
+
 fn hash_to_short_string(input: &str) -> String {
     let mut hasher = DefaultHasher::new();
     input.hash(&mut hasher);
@@ -98,22 +116,35 @@ impl Client {
         }
     }
 
-    fn try_load_chunk_from_cache(&self, addr: &ChunkAddress) -> Result<Option<Chunk>, GetError> {
+    pub(crate) fn try_load_chunk_from_cache(
+        &self,
+        addr: &ChunkAddress,
+    ) -> Result<Option<Chunk>, GetError> {
         if !self.config.chunk_cache_enabled {
             return Ok(None);
         }
 
         let cache_dir = self.get_chunk_cache_dir()?;
-        if is_chunk_cached(cache_dir.clone(), addr)
+        let cached_chunk = if is_chunk_cached(cache_dir.clone(), addr)
             && let Ok(Some(cached_chunk)) = load_chunk(cache_dir, addr)
         {
             debug!("Loaded chunk from cache: {addr:?}");
-            return Ok(Some(cached_chunk));
+            Some(cached_chunk)
+        } else {
+            None
+        };
+        #[cfg(feature = "open-metrics")]
+        if let Some(metrics_recorder) = &self.network.metrics_recorder {
+            metrics_recorder.record_chunk_cache_lookup(cached_chunk.is_some());
         }
-        Ok(None)
+        Ok(cached_chunk)
     }
 
-    fn try_cache_chunk(&self, addr: &ChunkAddress, chunk: &Chunk) -> Result<(), GetError> {
+    pub(crate) fn try_cache_chunk(
+        &self,
+        addr: &ChunkAddress,
+        chunk: &Chunk,
+    ) -> Result<(), GetError> {
         if self.config.chunk_cache_enabled {
             let cache_dir = self.get_chunk_cache_dir()?;
             if let Err(e) = store_chunk(cache_dir, addr, chunk) {
@@ -142,6 +173,20 @@ impl Client {
         let key = NetworkAddress::from(*addr);
         debug!("Fetching chunk from network at: {key:?}");
 
+        // Chunks are self-verifying, a single holder is enough, ask the fastest ones directly
+        if self.config.chunks.get_quorum == Quorum::One
+            && let Some(record) = self
+                .network
+                .get_record_from_fastest_holders(key.clone(), &self.config.chunks)
+                .await
+        {
+            match chunk_from_record(&record) {
+                Ok(chunk) if chunk.address() == addr => return Ok(chunk),
+                Ok(_) => warn!("Fastest holders returned a different chunk for {addr:?}"),
+                Err(err) => warn!("Fastest holders returned an invalid chunk for {addr:?}: {err}"),
+            }
+        }
+
         let record = self
             .network
             .get_record_with_retries(key, &self.config.chunks)
@@ -149,20 +194,7 @@ impl Client {
             .inspect_err(|err| error!("Error fetching chunk: {err:?}"))?
             .ok_or(GetError::RecordNotFound)?;
 
-        let header = RecordHeader::from_record(&record)?;
-
-        if RecordHeader::is_record_of_type_chunk(&record)? {
-            let chunk: Chunk = try_deserialize_record(&record)?;
-            Ok(chunk)
-        } else {
-            error!(
-                "Record kind mismatch: expected Chunk, got {:?}",
-                header.kind
-            );
-            Err(GetError::RecordKindMismatch(RecordKind::DataOnly(
-                DataTypes::Chunk,
-            )))
-        }
+        chunk_from_record(&record)
     }
 
     /// Get a chunk from the network.
@@ -178,6 +210,108 @@ impl Client {
         Ok(chunk)
     }
 
+    /// Get several chunks, asking each close group peer for all the chunks it holds in one go.
+    /// Returns the chunks found in the cache or fetched that way. Chunks that are missing, e.g.
+    /// because their peers are old nodes without batched queries, are meant to be fetched with
+    /// [`Client::chunk_get`].
+    pub(crate) async fn chunks_get_batched(
+        &self,
+        addrs: &[ChunkAddress],
+    ) -> HashMap<ChunkAddress, Chunk> {
+        let mut chunks = HashMap::new();
+        let mut to_fetch = HashMap::new();
+        for addr in addrs {
+            match self.try_load_chunk_from_cache(addr) {
+                Ok(Some(chunk)) => {
+                    chunks.insert(*addr, chunk);
+                }
+                _ => {
+                    to_fetch.insert(NetworkAddress::from(*addr), *addr);
+                }
+            }
+        }
+
+        // Looking up the peers of a single chunk costs as much as fetching it
+        if to_fetch.len() < 2 {
+            return chunks;
+        }
+
+        debug!("Fetching {} chunks in batches", to_fetch.len());
+        let records = self
+            .network
+            .get_records_batched(
+                to_fetch.keys().cloned().collect(),
+                &self.config.chunks,
+                &self.chunk_download_concurrency,
+            )
+            .await;
+
+        for (key, record) in records {
+            let Some(addr) = to_fetch.get(&key) else {
+                continue;
+            };
+            // The chunk comes from a single peer, so make sure it matches its address
+            match chunk_from_record(&record) {
+                Ok(chunk) if chunk.address() == addr => {
+                    if let Err(err) = self.try_cache_chunk(addr, &chunk) {
+                        warn!("Failed to cache chunk {addr:?}: {err}");
+                    }
+                    chunks.insert(*addr, chunk);
+                }
+                Ok(_) => warn!("Batched get returned a chunk not matching its address {addr:?}"),
+                Err(err) => warn!("Batched get returned an invalid chunk at {addr:?}: {err}"),
+            }
+        }
+        debug!(
+            "Fetched {} of {} chunks in batches",
+            chunks.len(),
+            addrs.len()
+        );
+        chunks
+    }
+
+    /// Check whether several chunks exist on the network.
+    /// Each close group peer is asked about all the chunks it should hold at once, the chunks
+    /// it doesn't confirm are then checked individually.
+    pub async fn chunks_check_existence(
+        &self,
+        addrs: &[ChunkAddress],
+    ) -> Result<HashMap<ChunkAddress, bool>, GetError> {
+        let keys: Vec<_> = addrs
+            .iter()
+            .map(|addr| NetworkAddress::from(*addr))
+            .collect();
+        debug!("Checking existence of {} chunks", keys.len());
+
+        let existing = self
+            .network
+            .check_records_existence_batched(keys, &self.chunk_download_concurrency)
+            .await;
+
+        let mut results = HashMap::new();
+        for addr in addrs {
+            let key = NetworkAddress::from(*addr);
+            let exists = if existing.contains(&key) {
+                true
+            } else {
+                match self
+                    .network
+                    .get_record(key, self.config.chunks.verification_quorum)
+                    .await
+                {
+                    Ok(record) => record.is_some(),
+                    Err(NetworkError::SplitRecord(..)) => true,
+                    Err(err) => {
+                        error!("Error checking chunk existence at {addr:?}: {err:?}");
+                        return Err(GetError::Network(err));
+                    }
+                }
+            };
+            results.insert(*addr, exists);
+        }
+        Ok(results)
+    }
+
     /// Manually upload a chunk to the network.
     /// It is recommended to use the [`Client::data_put`] method instead to upload data.
     pub async fn chunk_put(
@@ -373,8 +507,15 @@ impl Client {
                 res
             });
         }
-        let uploads =
-            process_tasks_with_max_concurrency(upload_tasks, *CHUNK_UPLOAD_BATCH_SIZE).await;
+        let uploads = process_tasks_with_adaptive_concurrency(
+            upload_tasks,
+            &self.chunk_upload_concurrency,
+            |res| match res {
+                Ok(_) => TaskOutcome::Success,
+                Err((_, err)) => TaskOutcome::of_put_error(err),
+            },
+        )
+        .await;
 
         // return errors
         if uploads.iter().any(|res| res.is_err()) {
@@ -445,7 +586,12 @@ impl Client {
     }
 
     /// Fetch and decrypt all chunks in the datamap.
-    pub(crate) async fn fetch_from_data_map(&self, data_map: &DataMap) -> Result<Bytes, GetError> {
+    /// Chunks that can't be fetched are recovered from the parity chunks of the data, if any.
+    pub(crate) async fn fetch_from_data_map(
+        &self,
+        data_map: &DataMap,
+        recovery: Option<&StripeRecovery>,
+    ) -> Result<Bytes, GetError> {
         let total_chunks = data_map.infos().len();
         #[cfg(feature = "loud")]
         println!("Fetching {total_chunks} encrypted data chunks from network.");
@@ -457,17 +603,26 @@ impl Client {
             .iter()
             .map(|info| ChunkAddress::new(info.dst_hash))
             .collect();
+        let prefetched = self.chunks_get_batched(&chunk_addrs).await;
 
+        let mut encrypted_chunks = vec![];
         for (i, info) in data_map.infos().into_iter().enumerate() {
-            download_tasks.push(async move {
-                let idx = i + 1;
-                let chunk_addr = ChunkAddress::new(info.dst_hash);
+            let idx = i + 1;
+            let chunk_addr = ChunkAddress::new(info.dst_hash);
+            if let Some(chunk) = prefetched.get(&chunk_addr).cloned() {
+                info!("Fetched chunk {idx}/{total_chunks}({chunk_addr:?}) in a batch");
+                encrypted_chunks.push(EncryptedChunk {
+                    content: chunk.value,
+                });
+                continue;
+            }
 
+            download_tasks.push(async move {
                 #[cfg(feature = "loud")]
                 println!("Fetching chunk {idx}/{total_chunks} ...");
                 info!("Fetching chunk {idx}/{total_chunks}({chunk_addr:?})");
 
-                match self.chunk_get(&chunk_addr).await {
+                match self.chunk_get_or_recover(&chunk_addr, recovery).await {
                     Ok(chunk) => {
                         #[cfg(feature = "loud")]
                         println!("Fetching chunk {idx}/{total_chunks} [DONE]");
@@ -487,11 +642,18 @@ impl Client {
                 }
             });
         }
-        let encrypted_chunks =
-            process_tasks_with_max_concurrency(download_tasks, *CHUNK_DOWNLOAD_BATCH_SIZE)
-                .await
-                .into_iter()
-                .collect::<Result<Vec<EncryptedChunk>, GetError>>()?;
+        let fetched = process_tasks_with_adaptive_concurrency(
+            download_tasks,
+            &self.chunk_download_concurrency,
+            |res| match res {
+                Ok(_) => TaskOutcome::Success,
+                Err(err) => TaskOutcome::of_get_error(err),
+            },
+        )
+        .await;
+        for res in fetched {
+            encrypted_chunks.push(res?);
+        }
         #[cfg(feature = "loud")]
         println!("Successfully fetched all {total_chunks} encrypted chunks");
         debug!("Successfully fetched all {total_chunks} encrypted chunks");
@@ -313,6 +313,7 @@ impl Client {
     /// The content type is used to identify the type of data stored in the scratchpad, the choice is up to the caller.
     ///
     /// Returns the cost and the address of the scratchpad.
+    #[tracing::instrument(skip_all)]
     pub async fn scratchpad_create(
         &self,
         owner: &SecretKey,
@@ -335,6 +336,7 @@ impl Client {
     /// The scratchpad needs to be created first with [`Client::scratchpad_create`].
     /// This operation is free as the scratchpad was already paid for at creation.
     /// Only the latest version of the scratchpad is kept on the Network, previous versions will be overwritten and unrecoverable.
+    #[tracing::instrument(skip_all)]
     pub async fn scratchpad_update(
         &self,
         owner: &SecretKey,
@@ -7,7 +7,6 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::Client;
-use crate::client::config::UPLOAD_FLOW_BATCH_SIZE;
 use crate::client::payment::PayError::EvmWalletError;
 use crate::client::payment::PaymentOption;
 use crate::client::payment::Receipt;
@@ -143,7 +142,8 @@ impl Client {
 
         loop {
             // Get next batch if current_batch has space and file has more chunks
-            if let Some(next_batch) = file.next_batch(*UPLOAD_FLOW_BATCH_SIZE - current_batch.len())
+            if let Some(next_batch) =
+                file.next_batch(self.config.upload_flow_batch_size.get() - current_batch.len())
             {
                 // prepare batch
                 let next_batch_len = next_batch.len();
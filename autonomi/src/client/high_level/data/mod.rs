@@ -14,6 +14,8 @@ use xor_name::XorName;
 pub mod private;
 /// Public data on the network, readable by anyone with the DataAddr
 pub mod public;
+/// Recovering and repairing data uploaded with parity chunks
+pub mod resilience;
 
 mod helpers;
 mod stream;
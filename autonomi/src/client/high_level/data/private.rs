@@ -10,6 +10,8 @@ use std::time::Instant;
 
 use crate::AttoTokens;
 use crate::Client;
+use crate::client::data_map_restoration::RestoredDataMap;
+use crate::client::high_level::data::resilience::StripeRecovery;
 use crate::client::payment::PaymentOption;
 use crate::client::{GetError, PutError};
 use crate::self_encryption::EncryptionStream;
@@ -34,13 +36,19 @@ impl Client {
     /// # Ok(())
     /// # }
     /// ```
+    #[tracing::instrument(skip_all)]
     pub async fn data_get(&self, data_map: &DataMapChunk) -> Result<Bytes, GetError> {
         info!(
             "Fetching private data from datamap {:?}",
             data_map.0.address()
         );
 
-        let mut datamap = self.restore_data_map_from_chunk(data_map).await?;
+        let RestoredDataMap {
+            data_map: mut datamap,
+            compression,
+            parity,
+            ..
+        } = self.restore_data_map_from_chunk(data_map).await?;
         let chunk_count = datamap.infos().len();
 
         if chunk_count > *crate::client::config::MAX_IN_MEMORY_DOWNLOAD_SIZE {
@@ -48,7 +56,13 @@ impl Client {
         }
 
         datamap.child = None;
-        let data = self.fetch_from_data_map(&datamap).await?;
+        let recovery = parity.map(StripeRecovery::new);
+        let mut data = self
+            .fetch_from_data_map(&datamap, recovery.as_ref())
+            .await?;
+        if let Some(frames) = compression {
+            data = frames.decompress_all(&data).map_err(GetError::Decryption)?;
+        }
         debug!(
             "Successfully fetched private data ({} chunks) in-memory",
             chunk_count
@@ -81,15 +95,15 @@ impl Client {
             data_map.0.address()
         );
 
-        let datamap = self.restore_data_map_from_chunk(data_map).await?;
-        let chunk_count = datamap.infos().len();
+        let restored = self.restore_data_map_from_chunk(data_map).await?;
+        let chunk_count = restored.data_map.infos().len();
 
         debug!(
             "Starting streaming fetch of private data ({} chunks)",
             chunk_count
         );
 
-        DataStream::new(self.clone(), datamap)
+        DataStream::new(self.clone(), restored)
     }
 
     /// Upload a piece of private data to the network. This data will be self-encrypted.
@@ -112,6 +126,7 @@ impl Client {
     /// # Ok(())
     /// # }
     /// ```
+    #[tracing::instrument(skip_all, fields(size = data.len()))]
     pub async fn data_put(
         &self,
         data: Bytes,
@@ -119,7 +134,8 @@ impl Client {
     ) -> Result<(AttoTokens, DataMapChunk), PutError> {
         let now = Instant::now();
 
-        let (chunk_stream, data_map_chunk) = EncryptionStream::new_in_memory(data, false)?;
+        let (chunk_stream, data_map_chunk) =
+            EncryptionStream::new_in_memory(data, false, self.config.encryption_options())?;
         debug!("Encryption took: {:.2?}", now.elapsed());
 
         // Note within the `pay_and_upload`, UploadSummary will be sent to client via event_channel.
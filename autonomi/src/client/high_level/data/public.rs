@@ -18,7 +18,7 @@ use crate::self_encryption::EncryptionStream;
 use crate::{
     Client,
     chunk::{ChunkAddress, DataMapChunk},
-    self_encryption::encrypt,
+    self_encryption::encrypt_with_options,
 };
 use ant_evm::{Amount, AttoTokens};
 use xor_name::XorName;
@@ -28,6 +28,7 @@ use super::DataAddress;
 impl Client {
     /// Fetch a blob of public data from the network. In-memory only - fails for large files.
     /// Use file_download_public for large files that need streaming.
+    #[tracing::instrument(skip_all, fields(addr = ?addr))]
     pub async fn data_get_public(&self, addr: &DataAddress) -> Result<Bytes, GetError> {
         info!("Fetching public data from Data Address: {addr:?}");
         let datamap_chunk =
@@ -65,12 +66,14 @@ impl Client {
     /// Upload a piece of data to the network. This data is publicly accessible.
     ///
     /// Returns the Data Address at which the data was stored.
+    #[tracing::instrument(skip_all, fields(size = data.len()))]
     pub async fn data_put_public(
         &self,
         data: Bytes,
         payment_option: PaymentOption,
     ) -> Result<(AttoTokens, DataAddress), PutError> {
-        let (chunk_stream, data_map_chunk) = EncryptionStream::new_in_memory(data, true)?;
+        let (chunk_stream, data_map_chunk) =
+            EncryptionStream::new_in_memory(data, true, self.config.encryption_options())?;
 
         let data_map_addr = *data_map_chunk.0.address();
         info!("Uploading datamap chunk to the network at: {data_map_addr:?}");
@@ -84,6 +87,8 @@ impl Client {
     }
 
     /// Get the estimated cost of storing a piece of data.
+    /// The data is encoded as the client is configured to, so compression and parity chunks are
+    /// accounted for.
     pub async fn data_cost(&self, data: Bytes) -> Result<AttoTokens, CostError> {
         let content_addrs = self.get_content_addrs(data)?;
         self.get_cost_estimation(content_addrs).await
@@ -95,7 +100,8 @@ impl Client {
         data: Bytes,
     ) -> Result<Vec<(XorName, usize)>, CostError> {
         let now = Instant::now();
-        let (data_map_chunks, chunks) = encrypt(data)?;
+        let (data_map_chunks, chunks) =
+            encrypt_with_options(data, self.config.encryption_options())?;
 
         debug!("Encryption took: {:.2?}", now.elapsed());
 
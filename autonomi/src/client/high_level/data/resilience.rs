@@ -0,0 +1,248 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::AttoTokens;
+use crate::Client;
+use crate::client::data_types::chunk::{Chunk, ChunkAddress, DataMapChunk};
+use crate::client::payment::PaymentOption;
+use crate::client::{GetError, PutError};
+use crate::self_encryption::{EncryptionStream, ParityShards};
+use bytes::Bytes;
+use futures::future::join_all;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+use xor_name::XorName;
+
+/// Errors that can occur when repairing data.
+#[derive(Debug, thiserror::Error)]
+pub enum RepairError {
+    #[error("The data was uploaded without parity chunks, it can't be repaired")]
+    NoParity,
+    #[error("Failed to reconstruct the lost chunks: {0}")]
+    GetError(#[from] GetError),
+    #[error("Failed to upload the reconstructed chunks: {0}")]
+    PutError(#[from] PutError),
+}
+
+/// The shards of a stripe that couldn't be fetched, once it's rebuilt.
+type RebuiltStripe = Arc<OnceCell<HashMap<XorName, Bytes>>>;
+
+/// The parity chunks of some data along with the chunks rebuilt out of them while downloading
+/// it, so that each stripe is only fetched and rebuilt once.
+pub(crate) struct StripeRecovery {
+    parity: Arc<ParityShards>,
+    /// Chunks that couldn't be fetched in each stripe rebuilt so far
+    rebuilt: Mutex<HashMap<usize, RebuiltStripe>>,
+}
+
+impl StripeRecovery {
+    pub(crate) fn new(parity: Arc<ParityShards>) -> Self {
+        Self {
+            parity,
+            rebuilt: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn stripe(&self, stripe: usize) -> RebuiltStripe {
+        let mut rebuilt = self
+            .rebuilt
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::clone(rebuilt.entry(stripe).or_default())
+    }
+}
+
+impl Client {
+    /// Get a chunk of some data, reconstructing it out of the other chunks of its stripe if it
+    /// can't be fetched and the data has parity chunks.
+    pub(crate) async fn chunk_get_or_recover(
+        &self,
+        addr: &ChunkAddress,
+        recovery: Option<&StripeRecovery>,
+    ) -> Result<Chunk, GetError> {
+        let Some(recovery) = recovery else {
+            return self.chunk_get(addr).await;
+        };
+        match self.chunk_get(addr).await {
+            Ok(chunk) if chunk.address() == addr => Ok(chunk),
+            Ok(_) => {
+                warn!("Chunk fetched at {addr:?} doesn't match its address, recovering it");
+                self.recover_chunk(addr, recovery).await
+            }
+            Err(err) => {
+                warn!("Failed to fetch chunk {addr:?}, recovering it: {err}");
+                self.recover_chunk(addr, recovery)
+                    .await
+                    .map_err(|recover_err| {
+                        error!("Failed to recover chunk {addr:?}: {recover_err}");
+                        err
+                    })
+            }
+        }
+    }
+
+    /// Reconstruct a chunk out of the other chunks of its stripe, reusing the stripe if it was
+    /// already rebuilt during the download.
+    async fn recover_chunk(
+        &self,
+        addr: &ChunkAddress,
+        recovery: &StripeRecovery,
+    ) -> Result<Chunk, GetError> {
+        let parity = &recovery.parity;
+        let stripe = parity.stripe_of(addr.xorname()).ok_or_else(|| {
+            GetError::Decryption(crate::self_encryption::Error::ErasureCoding(format!(
+                "Chunk {addr:?} isn't covered by the parity of the data"
+            )))
+        })?;
+        let rebuilt = recovery
+            .stripe(stripe)
+            .get_or_try_init(|| self.rebuild_stripe(parity, stripe, addr.xorname()))
+            .await?
+            .get(addr.xorname())
+            .cloned();
+        let shard = match rebuilt {
+            Some(shard) => shard,
+            // The chunk could still be fetched when the stripe was rebuilt, it's always left out
+            // of the stripe when rebuilding it again for it
+            None => self
+                .rebuild_stripe(parity, stripe, addr.xorname())
+                .await?
+                .remove(addr.xorname())
+                .ok_or_else(|| {
+                    GetError::Decryption(crate::self_encryption::Error::ErasureCoding(format!(
+                        "Chunk {addr:?} wasn't rebuilt with its stripe"
+                    )))
+                })?,
+        };
+
+        let chunk = Chunk::new(shard);
+        info!("Recovered chunk {addr:?} out of stripe {stripe}");
+        self.try_cache_chunk(addr, &chunk)?;
+        Ok(chunk)
+    }
+
+    /// Rebuild a stripe out of the shards that can be fetched, leaving out the lost one.
+    /// Returns the shards that couldn't be fetched.
+    async fn rebuild_stripe(
+        &self,
+        parity: &ParityShards,
+        stripe: usize,
+        lost: &XorName,
+    ) -> Result<HashMap<XorName, Bytes>, GetError> {
+        let names = parity.stripe_shards(stripe);
+        let shards = self
+            .fetch_stripe_shards(&names, &HashSet::from([*lost]))
+            .await;
+        let missing: Vec<bool> = shards.iter().map(Option::is_none).collect();
+        let rebuilt = parity
+            .reconstruct(stripe, shards)
+            .map_err(GetError::Decryption)?;
+        info!(
+            "Rebuilt {} shards of stripe {stripe}",
+            missing.iter().filter(|missing| **missing).count()
+        );
+
+        Ok(names
+            .into_iter()
+            .zip(rebuilt)
+            .zip(missing)
+            .filter(|(_, missing)| *missing)
+            .map(|(shard, _)| shard)
+            .collect())
+    }
+
+    /// Fetch the shards of a stripe, leaving out the given ones and the ones that can't be
+    /// fetched or don't match their address.
+    async fn fetch_stripe_shards(
+        &self,
+        names: &[XorName],
+        skip: &HashSet<XorName>,
+    ) -> Vec<Option<Bytes>> {
+        join_all(names.iter().map(|name| async move {
+            if skip.contains(name) {
+                return None;
+            }
+            let addr = ChunkAddress::new(*name);
+            match self.chunk_get(&addr).await {
+                Ok(chunk) if chunk.address() == &addr => Some(chunk.value),
+                Ok(_) => {
+                    warn!("Chunk fetched at {addr:?} doesn't match its address");
+                    None
+                }
+                Err(err) => {
+                    debug!("Shard {addr:?} can't be fetched: {err}");
+                    None
+                }
+            }
+        }))
+        .await
+    }
+
+    /// Upload again the chunks of some data that can no longer be found on the network,
+    /// reconstructing them out of the parity chunks added when the data was uploaded with
+    /// [`crate::client::config::ClientOperatingStrategy::resilience`].
+    ///
+    /// Only the reconstructed chunks are paid for. Returns the cost and the number of chunks
+    /// uploaded again.
+    pub async fn data_repair(
+        &self,
+        data_map: &DataMapChunk,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, usize), RepairError> {
+        let restored = self.restore_data_map_from_chunk(data_map).await?;
+        let Some(parity) = restored.parity else {
+            return Err(RepairError::NoParity);
+        };
+
+        let addrs: Vec<ChunkAddress> = parity.shard_names().map(ChunkAddress::new).collect();
+        let existence = self.chunks_check_existence(&addrs).await?;
+        let missing: HashSet<_> = existence
+            .into_iter()
+            .filter(|(_, exists)| !exists)
+            .map(|(addr, _)| *addr.xorname())
+            .collect();
+        if missing.is_empty() {
+            info!("All {} chunks of the data are on the network", addrs.len());
+            return Ok((AttoTokens::zero(), 0));
+        }
+        info!(
+            "{} of the {} chunks of the data are missing, reconstructing them",
+            missing.len(),
+            addrs.len()
+        );
+
+        let mut lost = vec![];
+        for stripe in 0..parity.stripe_count() {
+            let names = parity.stripe_shards(stripe);
+            if !names.iter().any(|name| missing.contains(name)) {
+                continue;
+            }
+            let shards = self.fetch_stripe_shards(&names, &missing).await;
+            let rebuilt = parity
+                .reconstruct(stripe, shards)
+                .map_err(GetError::Decryption)?;
+            lost.extend(
+                names
+                    .iter()
+                    .zip(rebuilt)
+                    .filter(|(name, _)| missing.contains(*name))
+                    .map(|(_, shard)| Chunk::new(shard)),
+            );
+        }
+
+        let count = lost.len();
+        let mut stream = EncryptionStream::new_from_chunks(lost, data_map.clone());
+        let cost = self
+            .pay_and_upload(payment_option, std::slice::from_mut(&mut stream))
+            .await?;
+        self.cleanup_cached_chunks(&addrs);
+        info!("Uploaded {count} reconstructed chunks again");
+        Ok((cost, count))
+    }
+}
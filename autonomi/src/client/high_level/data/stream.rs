@@ -12,17 +12,31 @@ use xor_name::XorName;
 use crate::Bytes;
 use crate::Client;
 use crate::client::GetError;
+use crate::client::data_map_restoration::RestoredDataMap;
+use crate::client::high_level::data::resilience::StripeRecovery;
+use crate::self_encryption::{CompressedFrames, MAX_CHUNK_SIZE};
 
 type ChunkFetcher =
     Box<dyn Fn(&[(usize, XorName)]) -> self_encryption::Result<Vec<(usize, Bytes)>> + Send + Sync>;
 
 pub struct DataStream {
     streaming_decrypt: self_encryption::DecryptionStream<ChunkFetcher>,
+    /// Set if the data was compressed before being encrypted
+    compression: Option<CompressedFrames>,
+    /// Next compressed frame to decompress when iterating
+    next_frame: usize,
 }
 
 impl DataStream {
-    pub(crate) fn new(client: Client, datamap: self_encryption::DataMap) -> Result<Self, GetError> {
+    pub(crate) fn new(client: Client, restored: RestoredDataMap) -> Result<Self, GetError> {
+        let RestoredDataMap {
+            data_map: datamap,
+            compression,
+            parity,
+            ..
+        } = restored;
         let client_clone = client.clone();
+        let recovery = parity.map(StripeRecovery::new);
 
         // Create the chunk fetcher function that the streaming decrypt will use
         let chunk_fetcher: ChunkFetcher = Box::new(move |chunk_names: &[(usize, XorName)]| -> self_encryption::Result<Vec<(usize, Bytes)>> {
@@ -35,7 +49,11 @@ impl DataStream {
             tokio::task::block_in_place(|| {
                 tokio::runtime::Handle::current().block_on(async {
                     client_clone
-                        .fetch_chunks_parallel(&chunk_addresses, chunk_names.len())
+                        .fetch_chunks_parallel(
+                            &chunk_addresses,
+                            chunk_names.len(),
+                            recovery.as_ref(),
+                        )
                         .await
                 })
             })
@@ -45,18 +63,33 @@ impl DataStream {
         let streaming_decrypt = self_encryption::streaming_decrypt(&datamap, chunk_fetcher)
             .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))?;
 
-        Ok(Self { streaming_decrypt })
+        Ok(Self::with_decryption(streaming_decrypt, compression))
+    }
+
+    fn with_decryption(
+        streaming_decrypt: self_encryption::DecryptionStream<ChunkFetcher>,
+        compression: Option<CompressedFrames>,
+    ) -> Self {
+        Self {
+            streaming_decrypt,
+            compression,
+            next_frame: 0,
+        }
     }
 
     /// Returns the original data size
     pub fn data_size(&self) -> usize {
-        self.streaming_decrypt.file_size()
+        match &self.compression {
+            Some(frames) => frames.original_size(),
+            None => self.streaming_decrypt.file_size(),
+        }
     }
 
     /// Decrypts and returns a specific byte range from the encrypted data.
     ///
     /// This method provides random access to any portion of the encrypted file
     /// without requiring sequential iteration through all preceding chunks.
+    /// For compressed data, only the compressed frames overlapping the range are fetched.
     ///
     /// # Arguments
     ///
@@ -67,13 +100,27 @@ impl DataStream {
     ///
     /// * `Result<Bytes>` - The decrypted range of data or an error if chunks are missing/corrupted
     pub fn get_range(&self, start: usize, len: usize) -> Result<Bytes, GetError> {
-        self.streaming_decrypt
-            .get_range(start, len)
-            .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))
+        let Some(frames) = &self.compression else {
+            return self.streaming_decrypt.get_range(start, len).map_err(|e| {
+                GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e))
+            });
+        };
+
+        let frame_range = frames.frames_in_range(start, len);
+        if frame_range.is_empty() {
+            return Ok(Bytes::new());
+        }
+        let data = self.decompress_frames(frames, frame_range.clone())?;
+        let offset = start - frames.frame_start(frame_range.start);
+        let len = len.min(data.len() - offset);
+        Ok(data.slice(offset..offset + len))
     }
 
     /// Convenience method to get a range using Range syntax.
     pub fn range(&self, range: std::ops::Range<usize>) -> Result<Bytes, GetError> {
+        if self.compression.is_some() {
+            return self.get_range(range.start, range.end.saturating_sub(range.start));
+        }
         self.streaming_decrypt
             .range(range)
             .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))
@@ -81,6 +128,9 @@ impl DataStream {
 
     /// Convenience method to get a range from a starting position to the end of the file.
     pub fn range_from(&self, start: usize) -> Result<Bytes, GetError> {
+        if self.compression.is_some() {
+            return self.get_range(start, self.data_size().saturating_sub(start));
+        }
         self.streaming_decrypt
             .range_from(start)
             .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))
@@ -88,6 +138,9 @@ impl DataStream {
 
     /// Convenience method to get a range from the beginning of the file to an end position.
     pub fn range_to(&self, end: usize) -> Result<Bytes, GetError> {
+        if self.compression.is_some() {
+            return self.get_range(0, end);
+        }
         self.streaming_decrypt
             .range_to(end)
             .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))
@@ -95,6 +148,9 @@ impl DataStream {
 
     /// Convenience method to get the entire file content.
     pub fn range_full(&self) -> Result<Bytes, GetError> {
+        if self.compression.is_some() {
+            return self.get_range(0, self.data_size());
+        }
         self.streaming_decrypt
             .range_full()
             .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))
@@ -102,16 +158,45 @@ impl DataStream {
 
     /// Convenience method to get an inclusive range.
     pub fn range_inclusive(&self, start: usize, end: usize) -> Result<Bytes, GetError> {
+        if self.compression.is_some() {
+            return self.get_range(start, (end + 1).saturating_sub(start));
+        }
         self.streaming_decrypt
             .range_inclusive(start, end)
             .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))
     }
+
+    /// Fetch and decompress the given compressed frames.
+    fn decompress_frames(
+        &self,
+        frames: &CompressedFrames,
+        frame_range: std::ops::Range<usize>,
+    ) -> Result<Bytes, GetError> {
+        let compressed_range = frames.compressed_range(frame_range.clone());
+        let compressed = self
+            .streaming_decrypt
+            .get_range(compressed_range.start, compressed_range.len())
+            .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))?;
+        frames
+            .decompress(&compressed, frame_range)
+            .map_err(GetError::Decryption)
+    }
 }
 
 impl Iterator for DataStream {
     type Item = Result<Bytes, GetError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(frames) = &self.compression {
+            // Decompress about a chunk worth of compressed frames at a time
+            let frame_range = frames.frames_up_to_size(self.next_frame, MAX_CHUNK_SIZE);
+            if frame_range.is_empty() {
+                return None;
+            }
+            self.next_frame = frame_range.end;
+            return Some(self.decompress_frames(frames, frame_range));
+        }
+
         // Get the next chunk from the streaming decrypt iterator
         match self.streaming_decrypt.next() {
             Some(Ok(chunk_bytes)) => {
@@ -181,7 +266,7 @@ mod tests {
                 .expect("Failed to create streaming decrypt");
 
         // Create DataStream with our mocked streaming_decrypt
-        let data_stream = DataStream { streaming_decrypt };
+        let data_stream = DataStream::with_decryption(streaming_decrypt, None);
 
         // Test data_size method
         assert_eq!(data_stream.data_size(), test_data.len());
@@ -258,7 +343,7 @@ mod tests {
                 .expect("Failed to create streaming decrypt");
 
         // Create DataStream with our mocked streaming_decrypt
-        let data_stream = DataStream { streaming_decrypt };
+        let data_stream = DataStream::with_decryption(streaming_decrypt, None);
 
         // Test range beyond file size
         let beyond_range = data_stream.get_range(10000, 1000).unwrap();
@@ -369,4 +454,75 @@ mod tests {
             "data_get and data_stream results don't match"
         );
     }
+
+    #[tokio::test]
+    async fn test_data_stream_compressed_range_access() {
+        use crate::self_encryption::{Compression, WrappedDataMap};
+        use std::collections::HashMap;
+
+        // Compressible data spanning several compressed frames
+        let test_data = crate::Bytes::from(
+            b"2025-01-01T00:00:00Z INFO request served in 12ms\n"
+                .iter()
+                .cycle()
+                .take(3_500_000)
+                .copied()
+                .collect::<Vec<u8>>(),
+        );
+
+        let (data_map_chunk, chunks) = crate::self_encryption::encrypt_with_compression(
+            test_data.clone(),
+            Some(Compression::default()),
+        )
+        .expect("Failed to encrypt test data");
+
+        let mut chunk_storage = HashMap::new();
+        for chunk in &chunks {
+            let hash = xor_name::XorName::from_content(&chunk.value);
+            chunk_storage.insert(hash, chunk.clone());
+        }
+
+        let (restored_data_map, frames, _) = WrappedDataMap::from_bytes(&data_map_chunk.value)
+            .expect("Compressed data should have a wrapped data map")
+            .expect("Failed to deserialize data map")
+            .into_parts();
+        let frames = frames.expect("Compressed data should record its frames");
+        assert!(frames.compressed_size() < test_data.len());
+
+        let chunk_fetcher: ChunkFetcher = Box::new(move |chunk_names: &[(usize, xor_name::XorName)]| -> self_encryption::Result<Vec<(usize, crate::Bytes)>> {
+            let mut results = Vec::new();
+            for (i, hash) in chunk_names {
+                let chunk = chunk_storage.get(hash)
+                    .ok_or_else(|| self_encryption::Error::Generic("Chunk not found".to_string()))?;
+                results.push((*i, chunk.value.clone()));
+            }
+            Ok(results)
+        });
+        let streaming_decrypt =
+            self_encryption::streaming_decrypt(&restored_data_map, chunk_fetcher)
+                .expect("Failed to create streaming decrypt");
+        let data_stream = DataStream::with_decryption(streaming_decrypt, Some(frames));
+
+        assert_eq!(data_stream.data_size(), test_data.len());
+
+        // Range across a frame boundary
+        let range_data = data_stream.get_range(1_048_000, 5000).unwrap();
+        assert_eq!(range_data.as_ref(), &test_data[1_048_000..1_053_000]);
+
+        let from_data = data_stream.range_from(3_499_000).unwrap();
+        assert_eq!(from_data.as_ref(), &test_data[3_499_000..]);
+
+        let inclusive_data = data_stream.range_inclusive(1000, 1999).unwrap();
+        assert_eq!(inclusive_data.as_ref(), &test_data[1000..2000]);
+
+        let beyond_range = data_stream.get_range(4_000_000, 1000).unwrap();
+        assert!(beyond_range.is_empty());
+
+        // Iterating yields the whole original data
+        let mut data_from_stream = Vec::new();
+        for chunk_result in data_stream {
+            data_from_stream.extend_from_slice(&chunk_result.unwrap());
+        }
+        assert_eq!(data_from_stream, test_data);
+    }
 }
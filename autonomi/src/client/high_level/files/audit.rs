@@ -0,0 +1,548 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Metadata;
+use super::archive_private::PrivateArchiveDataMap;
+use super::archive_public::ArchiveAddress;
+use crate::client::data::DataAddress;
+use crate::client::data_types::chunk::{Chunk, ChunkAddress, DataMapChunk};
+use crate::client::payment::PaymentOption;
+use crate::client::{GetError, PutError};
+use crate::networking::{NetworkAddress, RecordHolders};
+use crate::self_encryption::{EncryptionOptions, WrappedDataMap};
+use crate::{AttoTokens, Client};
+use ant_protocol::CLOSE_GROUP_SIZE;
+use ant_protocol::storage::DataTypes;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What to audit.
+#[derive(Debug, Clone)]
+pub enum AuditTarget {
+    /// A private file or piece of data
+    Private(DataMapChunk),
+    /// A public file or piece of data
+    Public(DataAddress),
+    /// A private archive along with the files in it
+    PrivateArchive(PrivateArchiveDataMap),
+    /// A public archive along with the files in it
+    PublicArchive(ArchiveAddress),
+}
+
+/// Presence of a chunk at its close group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkHealth {
+    pub address: ChunkAddress,
+    /// Close group peers that confirmed holding the chunk
+    pub holders: usize,
+    /// Close group peers that couldn't be asked, e.g. unreachable ones
+    pub unanswered: usize,
+}
+
+impl ChunkHealth {
+    /// Some close group peers answered that they don't hold the chunk.
+    ///
+    /// The peers that didn't answer aren't counted, as they may well hold the chunk.
+    pub fn is_weak(&self) -> bool {
+        self.holders + self.unanswered < CLOSE_GROUP_SIZE
+    }
+
+    /// Not every close group peer answered, and none of those that did lacked the chunk.
+    pub fn is_unknown(&self) -> bool {
+        !self.is_weak() && self.holders < CLOSE_GROUP_SIZE
+    }
+
+    /// Every close group peer answered that it doesn't hold the chunk.
+    pub fn is_missing(&self) -> bool {
+        self.holders == 0 && self.unanswered == 0
+    }
+}
+
+/// Audit of the chunks of a file, or of an archive.
+#[derive(Debug, Clone)]
+pub struct FileAudit {
+    /// Path of the file in its archive, empty for an archive or data audited on its own
+    pub path: PathBuf,
+    /// Address of public data, `None` for private data
+    pub address: Option<DataAddress>,
+    /// Health of each chunk of the file
+    pub chunks: Vec<ChunkHealth>,
+    /// Whether all the chunks could be listed.
+    /// Listing them requires fetching the datamap of the file, and the list of files of an
+    /// archive, so when these can't be fetched only the chunks found so far are audited.
+    pub complete: bool,
+    /// How the file was encrypted, to get its chunks out of a local copy
+    options: EncryptionOptions,
+}
+
+impl FileAudit {
+    /// Chunks that some of their close group answered not to hold, see [`ChunkHealth::is_weak`].
+    pub fn weak_chunks(&self) -> impl Iterator<Item = &ChunkHealth> {
+        self.chunks.iter().filter(|chunk| chunk.is_weak())
+    }
+
+    /// Chunks that couldn't be checked with their whole close group, see
+    /// [`ChunkHealth::is_unknown`].
+    pub fn unknown_chunks(&self) -> impl Iterator<Item = &ChunkHealth> {
+        self.chunks.iter().filter(|chunk| chunk.is_unknown())
+    }
+
+    /// All the chunks could be listed and are held by their whole close group.
+    pub fn is_healthy(&self) -> bool {
+        self.complete
+            && self
+                .chunks
+                .iter()
+                .all(|chunk| chunk.holders >= CLOSE_GROUP_SIZE)
+    }
+}
+
+/// Result of [`Client::audit`].
+#[derive(Debug, Clone)]
+pub struct AuditReport {
+    /// The archive itself, if an archive was audited
+    pub archive: Option<FileAudit>,
+    /// The files of the archive, or the single file audited
+    pub files: Vec<FileAudit>,
+}
+
+impl AuditReport {
+    /// The archive, if any, followed by its files.
+    pub fn iter(&self) -> impl Iterator<Item = &FileAudit> {
+        self.archive.iter().chain(self.files.iter())
+    }
+
+    /// Number of chunks audited.
+    pub fn chunk_count(&self) -> usize {
+        self.iter().map(|file| file.chunks.len()).sum()
+    }
+
+    /// Number of weak chunks, see [`ChunkHealth::is_weak`].
+    pub fn weak_chunk_count(&self) -> usize {
+        self.iter().map(|file| file.weak_chunks().count()).sum()
+    }
+
+    /// Number of chunks that couldn't be checked, see [`ChunkHealth::is_unknown`].
+    pub fn unknown_chunk_count(&self) -> usize {
+        self.iter().map(|file| file.unknown_chunks().count()).sum()
+    }
+
+    /// Not every chunk could be listed or checked with its whole close group, but none were
+    /// found weak, so there is nothing to repair.
+    pub fn is_inconclusive(&self) -> bool {
+        !self.is_healthy() && self.iter().all(|file| file.weak_chunks().next().is_none())
+    }
+
+    /// Every file is healthy, see [`FileAudit::is_healthy`].
+    pub fn is_healthy(&self) -> bool {
+        self.iter().all(FileAudit::is_healthy)
+    }
+}
+
+/// Result of [`Client::audit_repair`].
+#[derive(Debug, Clone)]
+pub struct RepairSummary {
+    /// Weak chunks found in the cache or the local copy and uploaded again
+    pub reuploaded: usize,
+    /// Weak chunks that couldn't be found in the cache nor the local copy
+    pub unrecoverable: Vec<ChunkAddress>,
+    pub cost: AttoTokens,
+}
+
+/// Errors that can occur when repairing audited data.
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    #[error("Failed to read the local copy: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to encrypt the local copy: {0}")]
+    Encryption(String),
+    #[error("Failed to upload the weak chunks: {0}")]
+    PutError(#[from] PutError),
+    #[error("Failed to load chunks from the cache: {0}")]
+    GetError(#[from] GetError),
+}
+
+impl Client {
+    /// Check that every chunk of a file or archive is still held by its close group, without
+    /// downloading the data.
+    ///
+    /// Each close group peer is asked which of the chunks it should hold it actually holds.
+    /// See [`Client::audit_repair`] to upload the weak chunks again.
+    pub async fn audit(&self, target: &AuditTarget) -> AuditReport {
+        info!("Auditing {target:?}");
+        match target {
+            AuditTarget::Private(data_map) => AuditReport {
+                archive: None,
+                files: vec![self.audit_private(PathBuf::new(), data_map).await],
+            },
+            AuditTarget::Public(address) => AuditReport {
+                archive: None,
+                files: vec![self.audit_public(PathBuf::new(), address).await],
+            },
+            AuditTarget::PrivateArchive(data_map) => {
+                let mut archive = self.audit_private(PathBuf::new(), data_map).await;
+                let mut files = vec![];
+                match self.archive_get(data_map).await {
+                    Ok(private_archive) => {
+                        for (path, data_map, _meta) in private_archive.iter() {
+                            files.push(self.audit_private(path.clone(), data_map).await);
+                        }
+                    }
+                    Err(err) => {
+                        warn!("Failed to fetch private archive to audit its files: {err}");
+                        archive.complete = false;
+                    }
+                }
+                AuditReport {
+                    archive: Some(archive),
+                    files,
+                }
+            }
+            AuditTarget::PublicArchive(address) => {
+                let mut archive = self.audit_public(PathBuf::new(), address).await;
+                let mut files = vec![];
+                match self.archive_get_public(address).await {
+                    Ok(public_archive) => {
+                        for (path, address, _meta) in public_archive.iter() {
+                            files.push(self.audit_public(path.clone(), address).await);
+                        }
+                    }
+                    Err(err) => {
+                        warn!("Failed to fetch public archive to audit its files: {err}");
+                        archive.complete = false;
+                    }
+                }
+                AuditReport {
+                    archive: Some(archive),
+                    files,
+                }
+            }
+        }
+    }
+
+    /// Upload again the weak chunks found by an audit, taking them from the chunk cache or from
+    /// a local copy of the data: the file itself, or the directory an archive was uploaded from.
+    ///
+    /// Chunks that are still held by some of their close group are usually free to upload again,
+    /// so only the missing ones get paid for.
+    pub async fn audit_repair(
+        &self,
+        report: &AuditReport,
+        local_copy: Option<&Path>,
+        payment_option: PaymentOption,
+    ) -> Result<RepairSummary, AuditError> {
+        let mut found = vec![];
+        let mut unrecoverable = vec![];
+        for (file, is_archive) in report
+            .archive
+            .iter()
+            .map(|archive| (archive, true))
+            .chain(report.files.iter().map(|file| (file, false)))
+        {
+            let mut weak: HashSet<ChunkAddress> =
+                file.weak_chunks().map(|chunk| chunk.address).collect();
+            if weak.is_empty() {
+                continue;
+            }
+
+            for addr in weak.clone() {
+                if let Some(chunk) = self.try_load_chunk_from_cache(&addr)? {
+                    weak.remove(&addr);
+                    found.push(chunk);
+                }
+            }
+
+            // An archive is put together on upload, there is no local copy of it
+            if let Some(local_copy) = local_copy.filter(|_| !is_archive && !weak.is_empty()) {
+                let path = local_copy_path(local_copy, &file.path);
+                found.extend(self.chunks_from_local_copy(file, &path, &mut weak).await?);
+            }
+
+            unrecoverable.extend(weak);
+        }
+
+        if found.is_empty() {
+            return Ok(RepairSummary {
+                reuploaded: 0,
+                unrecoverable,
+                cost: AttoTokens::zero(),
+            });
+        }
+
+        info!("Uploading {} weak chunks again", found.len());
+        let (receipt, already_paid) = self
+            .pay_for_content_addrs(
+                DataTypes::Chunk,
+                found.iter().map(|chunk| (*chunk.name(), chunk.size())),
+                payment_option,
+            )
+            .await
+            .map_err(PutError::from)?;
+        self.chunk_batch_upload(found.iter().collect(), &receipt)
+            .await?;
+        let cost = self
+            .calculate_total_cost(found.len(), vec![receipt], already_paid)
+            .await;
+
+        Ok(RepairSummary {
+            reuploaded: found.len(),
+            unrecoverable,
+            cost,
+        })
+    }
+
+    async fn audit_private(&self, path: PathBuf, data_map: &DataMapChunk) -> FileAudit {
+        self.audit_data_map(path, None, data_map, vec![]).await
+    }
+
+    async fn audit_public(&self, path: PathBuf, address: &DataAddress) -> FileAudit {
+        let data_map_addr = ChunkAddress::new(*address.xorname());
+        match self.chunk_get(&data_map_addr).await {
+            Ok(chunk) => {
+                self.audit_data_map(
+                    path,
+                    Some(*address),
+                    &DataMapChunk(chunk),
+                    vec![data_map_addr],
+                )
+                .await
+            }
+            Err(err) => {
+                warn!("Failed to fetch the datamap of {address:?} to audit it: {err}");
+                FileAudit {
+                    path,
+                    address: Some(*address),
+                    chunks: self.chunks_health(&[data_map_addr]).await,
+                    complete: false,
+                    options: EncryptionOptions::default(),
+                }
+            }
+        }
+    }
+
+    async fn audit_data_map(
+        &self,
+        path: PathBuf,
+        address: Option<DataAddress>,
+        data_map: &DataMapChunk,
+        mut addrs: Vec<ChunkAddress>,
+    ) -> FileAudit {
+        let (complete, options) = match self.restore_data_map_from_chunk(data_map).await {
+            Ok(restored) => {
+                addrs.extend(restored.chunk_addresses());
+                (true, restored.encryption_options())
+            }
+            Err(err) => {
+                warn!("Failed to restore the datamap of {path:?} to audit it: {err}");
+                addrs.extend(root_level_addresses(data_map));
+                (false, EncryptionOptions::default())
+            }
+        };
+        debug!("Auditing {} chunks of {path:?}", addrs.len());
+
+        FileAudit {
+            path,
+            address,
+            chunks: self.chunks_health(&addrs).await,
+            complete,
+            options,
+        }
+    }
+
+    async fn chunks_health(&self, addrs: &[ChunkAddress]) -> Vec<ChunkHealth> {
+        let keys = addrs
+            .iter()
+            .map(|addr| NetworkAddress::from(*addr))
+            .collect();
+        let holders = self
+            .network
+            .count_record_holders(keys, &self.chunk_download_concurrency)
+            .await;
+
+        addrs
+            .iter()
+            .map(|addr| {
+                // The close group couldn't be found, so none of it could be asked
+                let RecordHolders {
+                    holders,
+                    unanswered,
+                } = holders
+                    .get(&NetworkAddress::from(*addr))
+                    .copied()
+                    .unwrap_or(RecordHolders {
+                        holders: 0,
+                        unanswered: CLOSE_GROUP_SIZE,
+                    });
+                ChunkHealth {
+                    address: *addr,
+                    holders,
+                    unanswered,
+                }
+            })
+            .collect()
+    }
+
+    /// Encrypt the local copy of a file the same way it was uploaded, to get the chunks in
+    /// `wanted` back out of it. The chunks found are removed from `wanted`.
+    async fn chunks_from_local_copy(
+        &self,
+        file: &FileAudit,
+        path: &Path,
+        wanted: &mut HashSet<ChunkAddress>,
+    ) -> Result<Vec<Chunk>, AuditError> {
+        if !path.is_file() {
+            warn!("No local copy of {:?} at {path:?}", file.path);
+            return Ok(vec![]);
+        }
+        info!("Encrypting local copy {path:?} to recover weak chunks");
+
+        let file_size = std::fs::metadata(path)?.len() as usize;
+        let mut stream = crate::self_encryption::encrypt_file(
+            path.to_path_buf(),
+            path.to_path_buf(),
+            file_size,
+            Metadata::new_with_size(file_size as u64),
+            file.address.is_some(),
+            file.options,
+        )
+        .await
+        .map_err(AuditError::Encryption)?;
+
+        let mut found = vec![];
+        while !wanted.is_empty() {
+            let Some(batch) = stream.next_batch(self.config.upload_flow_batch_size.get()) else {
+                break;
+            };
+            found.extend(
+                batch
+                    .into_iter()
+                    .filter(|chunk| wanted.remove(chunk.address())),
+            );
+        }
+
+        if found.is_empty() {
+            warn!("Local copy {path:?} doesn't hold any of the weak chunks of the data");
+        }
+        Ok(found)
+    }
+}
+
+/// Where the local copy of an audited file is: the local copy itself for a file audited on its
+/// own, otherwise the file within the uploaded directory, whose name starts the archive paths.
+fn local_copy_path(local_copy: &Path, file_path: &Path) -> PathBuf {
+    let mut components = file_path.components();
+    components.next();
+    let within_root = components.as_path();
+    if within_root.as_os_str().is_empty() {
+        local_copy.to_path_buf()
+    } else {
+        local_copy.join(within_root)
+    }
+}
+
+/// The chunks listed in the datamap chunk itself, which can be audited even when the rest of
+/// the datamap can't be restored.
+fn root_level_addresses(data_map: &DataMapChunk) -> Vec<ChunkAddress> {
+    let bytes = data_map.0.value();
+    let root = match WrappedDataMap::from_bytes(bytes) {
+        Some(wrapped) => wrapped.ok().map(|wrapped| wrapped.into_parts().0),
+        None => Client::deserialize_data_map(bytes).ok(),
+    };
+    root.map(|root| {
+        root.infos()
+            .iter()
+            .map(|info| ChunkAddress::new(info.dst_hash))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::self_encryption::{Compression, Resilience, encrypt_with_options};
+    use bytes::Bytes;
+
+    #[test]
+    fn unanswered_peers_do_not_make_chunks_weak() {
+        let health = |holders, unanswered| ChunkHealth {
+            address: ChunkAddress::new(xor_name::XorName::default()),
+            holders,
+            unanswered,
+        };
+
+        let held = health(CLOSE_GROUP_SIZE, 0);
+        assert!(!held.is_weak() && !held.is_unknown() && !held.is_missing());
+
+        let unreachable = health(1, CLOSE_GROUP_SIZE - 1);
+        assert!(!unreachable.is_weak() && unreachable.is_unknown() && !unreachable.is_missing());
+
+        let not_found = health(0, CLOSE_GROUP_SIZE);
+        assert!(!not_found.is_weak() && not_found.is_unknown() && !not_found.is_missing());
+
+        let lacking = health(1, CLOSE_GROUP_SIZE - 2);
+        assert!(lacking.is_weak() && !lacking.is_unknown() && !lacking.is_missing());
+
+        let missing = health(0, 0);
+        assert!(missing.is_weak() && missing.is_missing());
+
+        let file = FileAudit {
+            path: PathBuf::new(),
+            address: None,
+            chunks: vec![held, unreachable],
+            complete: true,
+            options: EncryptionOptions::default(),
+        };
+        assert!(!file.is_healthy());
+        assert_eq!(file.weak_chunks().count(), 0);
+        assert_eq!(file.unknown_chunks().count(), 1);
+        let report = AuditReport {
+            archive: None,
+            files: vec![file],
+        };
+        assert!(report.is_inconclusive());
+        assert_eq!(report.unknown_chunk_count(), 1);
+    }
+
+    #[test]
+    fn local_copy_is_found_for_single_files_and_archived_ones() {
+        let local_copy = Path::new("/home/user/photos");
+
+        assert_eq!(local_copy_path(local_copy, Path::new("")), local_copy);
+        assert_eq!(
+            local_copy_path(local_copy, Path::new("photos/2024/beach.jpg")),
+            local_copy.join("2024/beach.jpg")
+        );
+        assert_eq!(
+            local_copy_path(Path::new("./beach.jpg"), Path::new("beach.jpg")),
+            Path::new("./beach.jpg")
+        );
+    }
+
+    #[test]
+    fn root_level_is_listed_for_bare_and_wrapped_data_maps() {
+        let data = Bytes::from(vec![7u8; 5 * 1024 * 1024]);
+        for options in [
+            EncryptionOptions::default(),
+            EncryptionOptions {
+                compression: Some(Compression::default()),
+                resilience: Some(Resilience::HIGH),
+            },
+        ] {
+            let (data_map_chunk, chunks) = encrypt_with_options(data.clone(), options).unwrap();
+            let stored: HashSet<_> = chunks.iter().map(|chunk| *chunk.address()).collect();
+
+            let root = root_level_addresses(&DataMapChunk(data_map_chunk));
+            assert!(!root.is_empty());
+            assert!(root.iter().all(|addr| stored.contains(addr)));
+        }
+
+        let not_a_data_map = DataMapChunk(Chunk::new(Bytes::from_static(b"not a data map")));
+        assert!(root_level_addresses(&not_a_data_map).is_empty());
+    }
+}
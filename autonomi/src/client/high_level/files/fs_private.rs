@@ -18,6 +18,7 @@ use crate::self_encryption::encrypt_directory_files;
 
 impl Client {
     /// Download private file directly to filesystem. Always uses streaming.
+    #[tracing::instrument(skip_all, fields(to_dest = ?to_dest))]
     pub async fn file_download(
         &self,
         data_map: &DataMapChunk,
@@ -30,14 +31,15 @@ impl Client {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let datamap = self.restore_data_map_from_chunk(data_map).await?;
-        self.stream_download_from_datamap(datamap, &to_dest)?;
+        let restored = self.restore_data_map_from_chunk(data_map).await?;
+        self.stream_download_from_datamap(restored, &to_dest)?;
 
         debug!("Successfully downloaded private file to {to_dest:?}");
         Ok(())
     }
 
     /// Download a private directory from network to local file system
+    #[tracing::instrument(skip_all, fields(to_dest = ?to_dest))]
     pub async fn dir_download(
         &self,
         archive_access: &PrivateArchiveDataMap,
@@ -63,7 +65,8 @@ impl Client {
         info!("Uploading directory as private: {dir_path:?}");
 
         // encrypt
-        let encryption_results = encrypt_directory_files(dir_path, false).await?;
+        let encryption_results =
+            encrypt_directory_files(dir_path, false, self.config.encryption_options()).await?;
         let mut chunk_iterators = vec![];
         for encryption_result in encryption_results {
             match encryption_result {
@@ -111,6 +114,7 @@ impl Client {
     /// Same as [`Client::dir_content_upload`] but also uploads the archive (privately) to the network.
     ///
     /// Returns the [`PrivateArchiveDataMap`] allowing the private archive to be downloaded from the network.
+    #[tracing::instrument(skip_all, fields(dir_path = ?dir_path))]
     pub async fn dir_upload(
         &self,
         dir_path: PathBuf,
@@ -129,6 +133,7 @@ impl Client {
 
     /// Upload the content of a private file to the network.
     /// Reads file, splits into chunks, uploads chunks, uploads datamap, returns [`DataMapChunk`] (pointing to the datamap)
+    #[tracing::instrument(skip_all, fields(path = ?path))]
     pub async fn file_content_upload(
         &self,
         path: PathBuf,
@@ -19,6 +19,7 @@ use std::time::{Duration, SystemTime};
 
 impl Client {
     /// Download file from network to local file system
+    #[tracing::instrument(skip_all, fields(addr = ?data_addr))]
     pub async fn file_download_public(
         &self,
         data_addr: &DataAddress,
@@ -36,6 +37,7 @@ impl Client {
     }
 
     /// Download directory from network to local file system
+    #[tracing::instrument(skip_all, fields(addr = ?archive_addr))]
     pub async fn dir_download_public(
         &self,
         archive_addr: &ArchiveAddress,
@@ -68,8 +70,12 @@ impl Client {
         info!("Uploading directory: {dir_path:?}");
 
         // encrypt
-        let encryption_results =
-            crate::self_encryption::encrypt_directory_files(dir_path, true).await?;
+        let encryption_results = crate::self_encryption::encrypt_directory_files(
+            dir_path,
+            true,
+            self.config.encryption_options(),
+        )
+        .await?;
         let mut chunk_iterators = vec![];
         for encryption_result in encryption_results {
             match encryption_result {
@@ -122,6 +128,7 @@ impl Client {
     /// Same as [`Client::dir_content_upload_public`] but also uploads the archive to the network.
     ///
     /// Returns the [`ArchiveAddress`] of the uploaded archive.
+    #[tracing::instrument(skip_all, fields(dir_path = ?dir_path))]
     pub async fn dir_upload_public(
         &self,
         dir_path: PathBuf,
@@ -140,6 +147,7 @@ impl Client {
 
     /// Upload the content of a file to the network.
     /// Reads file, splits into chunks, uploads chunks, uploads datamap, returns DataAddr (pointing to the datamap)
+    #[tracing::instrument(skip_all, fields(path = ?path))]
     pub async fn file_content_upload_public(
         &self,
         path: PathBuf,
@@ -6,18 +6,21 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use self_encryption::DataMap;
 use serde::{Deserialize, Serialize};
 use std::{
+    io::Write,
     path::{Path, PathBuf},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 use tracing::info;
 
+use crate::client::data_map_restoration::RestoredDataMap;
 use crate::client::data_types::chunk::ChunkAddress;
-use crate::client::{GetError, PutError, quote::CostError};
-use crate::utils::process_tasks_with_max_concurrency;
+use crate::client::high_level::data::resilience::StripeRecovery;
+use crate::client::{GetError, PutError, concurrency::TaskOutcome, quote::CostError};
+use crate::data::DataStream;
+use crate::utils::process_tasks_with_adaptive_concurrency;
 use crate::{
     Client,
     chunk::DataMapChunk,
@@ -29,6 +32,7 @@ use xor_name::XorName;
 
 pub mod archive_private;
 pub mod archive_public;
+pub mod audit;
 pub mod fs_private;
 pub mod fs_public;
 
@@ -148,9 +152,16 @@ pub(crate) fn normalize_path(path: PathBuf) -> PathBuf {
 impl Client {
     pub(crate) fn stream_download_from_datamap(
         &self,
-        data_map: DataMap,
+        restored: RestoredDataMap,
         to_dest: &Path,
     ) -> Result<(), DownloadError> {
+        if restored.compression.is_some() {
+            return self.stream_download_decompressed(restored, to_dest);
+        }
+        let RestoredDataMap {
+            data_map, parity, ..
+        } = restored;
+
         // Verify that the destination path can be used to create a file.
         if let Err(e) = std::fs::File::create(to_dest) {
             #[cfg(feature = "loud")]
@@ -180,6 +191,7 @@ impl Client {
         }
 
         let total_chunks = data_map.infos().len();
+        let recovery = parity.map(StripeRecovery::new);
 
         #[cfg(feature = "loud")]
         println!("Streaming fetching {total_chunks} chunks to {to_dest:?} ...");
@@ -200,7 +212,7 @@ impl Client {
             tokio::task::block_in_place(|| {
                 tokio::runtime::Handle::current().block_on(async {
                     client_clone
-                        .fetch_chunks_parallel(&chunk_addresses, total_chunks)
+                        .fetch_chunks_parallel(&chunk_addresses, total_chunks, recovery.as_ref())
                         .await
                 })
             })
@@ -226,15 +238,64 @@ impl Client {
         Ok(())
     }
 
-    /// Fetch multiple chunks in parallel from the network
+    /// Same as [`Client::stream_download_from_datamap`], for data that was compressed before
+    /// being encrypted, decompressing it frame by frame on its way to the file.
+    fn stream_download_decompressed(
+        &self,
+        restored: RestoredDataMap,
+        to_dest: &Path,
+    ) -> Result<(), DownloadError> {
+        let chunk_addrs: Vec<ChunkAddress> = restored
+            .data_map
+            .infos()
+            .iter()
+            .map(|info| ChunkAddress::new(info.dst_hash))
+            .collect();
+
+        #[cfg(feature = "loud")]
+        println!(
+            "Streaming fetching {} chunks of compressed data to {to_dest:?} ...",
+            chunk_addrs.len()
+        );
+        info!(
+            "Streaming fetching {} chunks of compressed data to {to_dest:?} ...",
+            chunk_addrs.len()
+        );
+
+        let stream = DataStream::new(self.clone(), restored)?;
+        let mut file = std::io::BufWriter::new(std::fs::File::create(to_dest)?);
+        for data in stream {
+            file.write_all(&data?)?;
+        }
+        file.flush()?;
+
+        // Cleanup the chunk_cache
+        self.cleanup_cached_chunks(&chunk_addrs);
+
+        Ok(())
+    }
+
+    /// Fetch multiple chunks in parallel from the network, recovering the ones that can't be
+    /// fetched from the parity chunks of the data, if any.
     pub(super) async fn fetch_chunks_parallel(
         &self,
         chunk_addresses: &[(usize, ChunkAddress)],
         total_chunks: usize,
+        recovery: Option<&StripeRecovery>,
     ) -> Result<Vec<(usize, Bytes)>, self_encryption::Error> {
         let mut download_tasks = vec![];
 
+        // Fetch what we can in batches, the rest is fetched one by one below
+        let addrs: Vec<ChunkAddress> = chunk_addresses.iter().map(|(_, addr)| *addr).collect();
+        let prefetched = self.chunks_get_batched(&addrs).await;
+
+        let mut chunks = vec![];
         for (i, chunk_addr) in chunk_addresses {
+            if let Some(chunk) = prefetched.get(chunk_addr) {
+                info!("Fetched chunk {i}/{total_chunks}({chunk_addr:?}) in a batch");
+                chunks.push((*i, chunk.value.clone()));
+                continue;
+            }
             let client_clone = self.clone();
             let addr_clone = *chunk_addr;
 
@@ -243,14 +304,10 @@ impl Client {
                 println!("Fetching chunk {i}/{total_chunks} ...");
                 info!("Fetching chunk {i}/{total_chunks}({addr_clone:?})");
                 let result = client_clone
-                    .chunk_get(&addr_clone)
+                    .chunk_get_or_recover(&addr_clone, recovery)
                     .await
                     .map(|chunk| (*i, chunk.value))
-                    .map_err(|e| {
-                        self_encryption::Error::Generic(format!(
-                            "Failed to fetch chunk {addr_clone:?}: {e:?}"
-                        ))
-                    });
+                    .map_err(|e| (addr_clone, e));
                 #[cfg(feature = "loud")]
                 println!("Fetching chunk {i}/{total_chunks} [DONE]");
                 info!("Fetching chunk {i}/{total_chunks}({addr_clone:?}) [DONE]");
@@ -258,13 +315,21 @@ impl Client {
             });
         }
 
-        let chunks = process_tasks_with_max_concurrency(
+        let fetched = process_tasks_with_adaptive_concurrency(
             download_tasks,
-            *crate::client::config::CHUNK_DOWNLOAD_BATCH_SIZE,
+            &self.chunk_download_concurrency,
+            |res| match res {
+                Ok(_) => TaskOutcome::Success,
+                Err((_, err)) => TaskOutcome::of_get_error(err),
+            },
         )
-        .await
-        .into_iter()
-        .collect::<Result<Vec<(usize, Bytes)>, self_encryption::Error>>()?;
+        .await;
+        for res in fetched {
+            let chunk = res.map_err(|(addr, e)| {
+                self_encryption::Error::Generic(format!("Failed to fetch chunk {addr:?}: {e:?}"))
+            })?;
+            chunks.push(chunk);
+        }
 
         Ok(chunks)
     }
@@ -288,6 +353,7 @@ impl Client {
             file_size,
             meta,
             is_public,
+            self.config.encryption_options(),
         )
         .await;
         let mut encryption_stream = match encryption_result {
@@ -132,6 +132,7 @@ impl Client {
     /// Create a new register with an initial value.
     ///
     /// Note that two payments are required, one for the underlying [`GraphEntry`] and one for the [`crate::Pointer`]
+    #[tracing::instrument(skip_all)]
     pub async fn register_create(
         &self,
         owner: &SecretKey,
@@ -173,6 +174,7 @@ impl Client {
     /// Update the value of a register.
     ///
     /// The register needs to be created first with [`Client::register_create`]
+    #[tracing::instrument(skip_all)]
     pub async fn register_update(
         &self,
         owner: &SecretKey,
@@ -237,6 +239,7 @@ impl Client {
     }
 
     /// Get the current value of the register
+    #[tracing::instrument(skip_all, fields(addr = ?addr))]
     pub async fn register_get(
         &self,
         addr: &RegisterAddress,
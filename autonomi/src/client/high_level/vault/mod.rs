@@ -12,7 +12,6 @@ pub mod user_data;
 pub use key::{VaultSecretKey, vault_derive_key};
 pub use user_data::UserData;
 
-use crate::client::config::FILE_UPLOAD_BATCH_SIZE;
 use crate::client::data_types::scratchpad::ScratchpadError;
 use crate::client::key_derivation::{DerivationIndex, MainSecretKey};
 use crate::client::payment::PaymentOption;
@@ -266,7 +265,8 @@ impl Client {
             .collect();
 
         let update_results =
-            process_tasks_with_max_concurrency(update_futures, *FILE_UPLOAD_BATCH_SIZE).await;
+            process_tasks_with_max_concurrency(update_futures, self.config.file_uploads.get())
+                .await;
 
         // Process results
         for result in update_results {
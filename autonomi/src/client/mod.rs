@@ -40,6 +40,7 @@ pub mod external_signer;
 
 // private module with utility functions
 mod chunk_cache;
+pub(crate) mod concurrency;
 mod data_map_restoration;
 mod network;
 mod put_error_state;
@@ -50,10 +51,12 @@ pub use put_error_state::ChunkBatchUploadState;
 use ant_bootstrap::{InitialPeersConfig, contacts::ALPHANET_CONTACTS};
 pub use ant_evm::Amount;
 use ant_evm::EvmNetwork;
+use concurrency::ConcurrencyController;
 use config::ClientConfig;
 use payment::PayError;
 use quote::CostError;
 use std::collections::HashSet;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 /// Time before considering the connection timed out.
@@ -63,9 +66,13 @@ const CLIENT_EVENT_CHANNEL_SIZE: usize = 100;
 
 // Amount of peers to confirm into our routing table before we consider the client ready.
 use crate::client::config::ClientOperatingStrategy;
-use crate::networking::{Multiaddr, Network, NetworkAddress, NetworkError, multiaddr_is_global};
+use crate::networking::{
+    Multiaddr, Network, NetworkAddress, NetworkError, PeerId, PeerStats, RequestKind,
+    multiaddr_is_global,
+};
 pub use ant_protocol::CLOSE_GROUP_SIZE;
 use ant_protocol::storage::RecordKind;
+use ant_protocol::version::NetworkContext;
 
 /// Represents a client for the Autonomi network.
 ///
@@ -91,6 +98,10 @@ pub struct Client {
     evm_network: EvmNetwork,
     /// The configuration for operations on the client.
     config: ClientOperatingStrategy,
+    /// Adapts the number of chunks uploaded at once, shared by the clones of the client.
+    chunk_upload_concurrency: Arc<ConcurrencyController>,
+    /// Adapts the number of chunks downloaded at once, shared by the clones of the client.
+    chunk_download_concurrency: Arc<ConcurrencyController>,
     /// Max times of total chunks to carry out retry on upload failure.
     /// Default to be `0` to indicate not carry out retry.
     retry_failed: u64,
@@ -294,16 +305,25 @@ impl Client {
     /// # }
     /// ```
     pub async fn init_with_config(config: ClientConfig) -> Result<Self, ConnectError> {
-        if let Some(network_id) = config.network_id {
-            ant_protocol::version::set_network_id(network_id);
-        }
-
-        let initial_peers = match config.init_peers_config.get_bootstrap_addr(Some(50)).await {
+        let network_context = config
+            .network_id
+            .map(NetworkContext::new)
+            .unwrap_or_else(NetworkContext::current);
+        let network_id = network_context.network_id();
+
+        let initial_peers = match config
+            .init_peers_config
+            .get_bootstrap_addr_for_network(Some(50), network_id)
+            .await
+        {
             Ok(peers) => peers,
             Err(e) => return Err(e.into()),
         };
 
-        let network = Network::new(initial_peers, config.bootstrap_cache_config)?;
+        let bootstrap_cache_config = config
+            .bootstrap_cache_config
+            .map(|cache_config| cache_config.with_network_id(network_id));
+        let network = Network::new(initial_peers, bootstrap_cache_config, network_context)?;
 
         // Wait for the network to be ready with enough peers
         network.wait_for_connectivity().await?;
@@ -312,6 +332,12 @@ impl Client {
             network,
             client_event_sender: None,
             evm_network: config.evm_network,
+            chunk_upload_concurrency: Arc::new(ConcurrencyController::new(
+                config.strategy.chunk_uploads,
+            )),
+            chunk_download_concurrency: Arc::new(ConcurrencyController::new(
+                config.strategy.chunk_downloads,
+            )),
             config: config.strategy,
             retry_failed: 0,
         })
@@ -319,6 +345,10 @@ impl Client {
 
     /// Set the `ClientOperatingStrategy` for the client.
     pub fn with_strategy(mut self, strategy: ClientOperatingStrategy) -> Self {
+        self.chunk_upload_concurrency =
+            Arc::new(ConcurrencyController::new(strategy.chunk_uploads));
+        self.chunk_download_concurrency =
+            Arc::new(ConcurrencyController::new(strategy.chunk_downloads));
         self.config = strategy;
         self
     }
@@ -329,6 +359,19 @@ impl Client {
         self
     }
 
+    /// Record the metrics of the client in the registry, to be served to Prometheus.
+    ///
+    /// Covers record PUTs and GETs per strategy, retries, quotes, payments, the chunk cache and
+    /// the bytes transferred. All metrics are prefixed with `autonomi_client`.
+    #[cfg(feature = "open-metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "open-metrics")))]
+    pub fn with_metrics(mut self, registry: &mut prometheus_client::registry::Registry) -> Self {
+        self.network.metrics_recorder = Some(
+            crate::networking::metrics::ClientMetricsRecorder::new(registry),
+        );
+        self
+    }
+
     /// Receive events from the client.
     pub fn enable_client_events(&mut self) -> mpsc::Receiver<ClientEvent> {
         let (client_event_sender, client_event_receiver) =
@@ -343,6 +386,13 @@ impl Client {
     pub fn evm_network(&self) -> &EvmNetwork {
         &self.evm_network
     }
+
+    /// Get how fast and reliably the peers answered each kind of requests of the client so far,
+    /// for debugging. Gets go to the fastest holders first and chronically slow peers are avoided
+    /// for quotes and uploads based on these.
+    pub fn peer_stats(&self) -> Vec<(PeerId, RequestKind, PeerStats)> {
+        self.network.peer_stats()
+    }
 }
 
 /// Events that can be sent by the client.
@@ -376,7 +426,7 @@ mod tests {
                 .parse()
                 .unwrap(),
         ];
-        let network = Network::new(initial_peers, None).unwrap();
+        let network = Network::new(initial_peers, None, NetworkContext::default()).unwrap();
 
         match network.wait_for_connectivity().await {
             Err(ConnectError::TimedOut) => {} // This is the expected outcome
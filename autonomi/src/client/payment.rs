@@ -107,6 +107,7 @@ impl Client {
     }
 
     /// Pay for the content addrs and get the proof of payment.
+    #[tracing::instrument(skip_all)]
     pub(crate) async fn pay(
         &self,
         data_type: DataTypes,
@@ -134,10 +135,15 @@ impl Client {
             // TODO: the error might contain some succeeded quote payments as well. These should be returned on err, so that they can be skipped when retrying.
             // TODO: retry when it fails?
             // Execute payments
-            let _payments = wallet
-                .pay_for_quotes(quotes.payments())
-                .await
-                .map_err(|err| PayError::from(err.0))?;
+            #[cfg(feature = "open-metrics")]
+            let start = std::time::Instant::now();
+            let result = wallet.pay_for_quotes(quotes.payments()).await;
+            #[cfg(feature = "open-metrics")]
+            if let Some(metrics_recorder) = &self.network.metrics_recorder {
+                let records_paid = result.as_ref().ok().map(|_| quotes.len());
+                metrics_recorder.record_payment(records_paid, start.elapsed());
+            }
+            let _payments = result.map_err(|err| PayError::from(err.0))?;
 
             // payment is done, unlock the wallet for other threads
             drop(lock_guard);
@@ -7,7 +7,6 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::Client;
-use crate::client::config::CHUNK_UPLOAD_BATCH_SIZE;
 use crate::networking::Network;
 use crate::networking::common::Addresses;
 use crate::utils::process_tasks_with_max_concurrency;
@@ -119,11 +118,12 @@ impl Client {
             })
             .collect();
 
-        let parallism = std::cmp::min(*CHUNK_UPLOAD_BATCH_SIZE * 8, 128);
+        let parallism = std::cmp::min(self.chunk_upload_concurrency.limit() * 8, 128);
 
         process_tasks_with_max_concurrency(futures, parallism).await
     }
 
+    #[tracing::instrument(skip_all)]
     pub async fn get_store_quotes(
         &self,
         data_type: DataTypes,
@@ -180,7 +180,7 @@ impl Client {
         let quotes_with_prices: Vec<(XorName, PeerId, Addresses, PaymentQuote, Amount)> =
             all_quotes
                 .into_iter()
-                .zip(all_prices.into_iter())
+                .zip(all_prices)
                 .map(|((content_addr, peer_id, addrs, quote), price)| {
                     (content_addr, peer_id, addrs, quote, price)
                 })
@@ -241,16 +241,22 @@ async fn fetch_store_quote(
     data_type: u32,
     data_size: usize,
 ) -> Result<(XorName, Vec<(PeerId, Addresses, PaymentQuote)>), CostError> {
-    let maybe_quotes = network
+    #[cfg(feature = "open-metrics")]
+    let start = std::time::Instant::now();
+    let result = network
         .get_quotes_with_retries(
             NetworkAddress::from(ChunkAddress::new(content_addr)),
             data_type,
             data_size,
         )
-        .await
-        .inspect_err(|err| {
-            error!("Error while fetching store quote: {err:?}");
-        })?;
+        .await;
+    #[cfg(feature = "open-metrics")]
+    if let Some(metrics_recorder) = &network.metrics_recorder {
+        metrics_recorder.record_quote(result.is_ok(), start.elapsed());
+    }
+    let maybe_quotes = result.inspect_err(|err| {
+        error!("Error while fetching store quote: {err:?}");
+    })?;
 
     // if no quotes are returned an empty vector is returned
     let quotes = maybe_quotes.unwrap_or_default();
@@ -0,0 +1,339 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::DaemonError;
+use super::protocol::{DaemonRequest, DaemonResponse, DaemonStatus, read_message, write_message};
+use crate::client::analyze::DataMapContent;
+use crate::client::data::DataAddress;
+use crate::client::data_types::chunk::DataMapChunk;
+use crate::client::files::{PrivateArchive, PublicArchive};
+use crate::client::register::{RegisterAddress, RegisterValue};
+use crate::{AttoTokens, Bytes, PublicKey, Scratchpad, ScratchpadAddress, SecretKey};
+use std::path::{Path, PathBuf};
+use tokio::net::UnixStream;
+
+/// A connection to a [`super::DaemonServer`].
+///
+/// The methods mirror the ones of [`crate::Client`] that the daemon serves. Requests are
+/// answered in order, one at a time per connection.
+///
+/// # Example
+///
+/// ```no_run
+/// # use autonomi::daemon::DaemonClient;
+/// # use autonomi::Bytes;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut daemon = DaemonClient::connect("/tmp/autonomi.sock".as_ref()).await?;
+/// let cost = daemon.data_cost(Bytes::from("Hello, World")).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct DaemonClient {
+    stream: UnixStream,
+}
+
+macro_rules! expect_response {
+    ($response:expr, $pattern:pat => $value:expr) => {
+        match $response {
+            $pattern => Ok($value),
+            DaemonResponse::Error(err) => Err(DaemonError::Remote(err)),
+            other => Err(DaemonError::UnexpectedResponse(Box::new(other))),
+        }
+    };
+}
+
+impl DaemonClient {
+    /// Connect to the daemon listening on the socket.
+    pub async fn connect(socket_path: &Path) -> Result<Self, DaemonError> {
+        let stream = UnixStream::connect(socket_path).await?;
+        Ok(Self { stream })
+    }
+
+    /// Send a request and wait for its response.
+    pub async fn request(
+        &mut self,
+        request: &DaemonRequest,
+    ) -> Result<DaemonResponse, DaemonError> {
+        write_message(&mut self.stream, request).await?;
+        read_message(&mut self.stream)
+            .await?
+            .ok_or(DaemonError::Disconnected)
+    }
+
+    /// Get the status of the daemon.
+    pub async fn status(&mut self) -> Result<DaemonStatus, DaemonError> {
+        let response = self.request(&DaemonRequest::Status).await?;
+        expect_response!(response, DaemonResponse::Status(status) => status)
+    }
+
+    /// Ask the daemon to stop.
+    pub async fn shutdown(&mut self) -> Result<(), DaemonError> {
+        let response = self.request(&DaemonRequest::Shutdown).await?;
+        expect_response!(response, DaemonResponse::Done => ())
+    }
+
+    /// See [`crate::Client::data_get`]. Data too large to be fetched in memory is reported as
+    /// [`super::RemoteError::TooLargeForMemory`].
+    pub async fn data_get(&mut self, data_map: &DataMapChunk) -> Result<Bytes, DaemonError> {
+        let response = self
+            .request(&DaemonRequest::DataGet(data_map.clone()))
+            .await?;
+        expect_response!(response, DaemonResponse::Data(data) => data)
+    }
+
+    /// See [`crate::Client::data_get_public`]. Data too large to be fetched in memory is reported
+    /// as [`super::RemoteError::TooLargeForMemory`].
+    pub async fn data_get_public(&mut self, addr: &DataAddress) -> Result<Bytes, DaemonError> {
+        let response = self.request(&DaemonRequest::DataGetPublic(*addr)).await?;
+        expect_response!(response, DaemonResponse::Data(data) => data)
+    }
+
+    /// See [`crate::Client::data_put`], paid with the wallet of the daemon.
+    pub async fn data_put(
+        &mut self,
+        data: Bytes,
+    ) -> Result<(AttoTokens, DataMapChunk), DaemonError> {
+        let response = self.request(&DaemonRequest::DataPut(data)).await?;
+        expect_response!(response, DaemonResponse::PrivateUpload { cost, data_map } => (cost, data_map))
+    }
+
+    /// See [`crate::Client::data_put_public`], paid with the wallet of the daemon.
+    pub async fn data_put_public(
+        &mut self,
+        data: Bytes,
+    ) -> Result<(AttoTokens, DataAddress), DaemonError> {
+        let response = self.request(&DaemonRequest::DataPutPublic(data)).await?;
+        expect_response!(response, DaemonResponse::PublicUpload { cost, addr } => (cost, addr))
+    }
+
+    /// See [`crate::Client::data_cost`].
+    pub async fn data_cost(&mut self, data: Bytes) -> Result<AttoTokens, DaemonError> {
+        let response = self.request(&DaemonRequest::DataCost(data)).await?;
+        expect_response!(response, DaemonResponse::Cost(cost) => cost)
+    }
+
+    /// See [`crate::Client::file_cost`]. The path is resolved by the daemon.
+    pub async fn file_cost(&mut self, path: &Path) -> Result<AttoTokens, DaemonError> {
+        let response = self
+            .request(&DaemonRequest::FileCost(absolute(path)?))
+            .await?;
+        expect_response!(response, DaemonResponse::Cost(cost) => cost)
+    }
+
+    /// See [`crate::Client::file_content_upload`], paid with the wallet of the daemon.
+    pub async fn file_content_upload(
+        &mut self,
+        path: &Path,
+    ) -> Result<(AttoTokens, DataMapChunk), DaemonError> {
+        let response = self
+            .request(&DaemonRequest::FileUpload(absolute(path)?))
+            .await?;
+        expect_response!(response, DaemonResponse::PrivateUpload { cost, data_map } => (cost, data_map))
+    }
+
+    /// See [`crate::Client::file_content_upload_public`], paid with the wallet of the daemon.
+    pub async fn file_content_upload_public(
+        &mut self,
+        path: &Path,
+    ) -> Result<(AttoTokens, DataAddress), DaemonError> {
+        let response = self
+            .request(&DaemonRequest::FileUploadPublic(absolute(path)?))
+            .await?;
+        expect_response!(response, DaemonResponse::PublicUpload { cost, addr } => (cost, addr))
+    }
+
+    /// See [`crate::Client::dir_content_upload`], paid with the wallet of the daemon.
+    pub async fn dir_content_upload(
+        &mut self,
+        path: &Path,
+    ) -> Result<(AttoTokens, PrivateArchive), DaemonError> {
+        let response = self
+            .request(&DaemonRequest::DirContentUpload(absolute(path)?))
+            .await?;
+        expect_response!(response, DaemonResponse::PrivateArchive { cost, archive } => (cost, archive))
+    }
+
+    /// See [`crate::Client::dir_content_upload_public`], paid with the wallet of the daemon.
+    pub async fn dir_content_upload_public(
+        &mut self,
+        path: &Path,
+    ) -> Result<(AttoTokens, PublicArchive), DaemonError> {
+        let response = self
+            .request(&DaemonRequest::DirContentUploadPublic(absolute(path)?))
+            .await?;
+        expect_response!(response, DaemonResponse::PublicArchive { cost, archive } => (cost, archive))
+    }
+
+    /// See [`crate::Client::file_download`].
+    pub async fn file_download(
+        &mut self,
+        data_map: &DataMapChunk,
+        to_dest: &Path,
+    ) -> Result<(), DaemonError> {
+        let request = DaemonRequest::FileDownload {
+            data_map: data_map.clone(),
+            to_dest: absolute(to_dest)?,
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::Done => ())
+    }
+
+    /// See [`crate::Client::file_download_public`].
+    pub async fn file_download_public(
+        &mut self,
+        addr: &DataAddress,
+        to_dest: &Path,
+    ) -> Result<(), DaemonError> {
+        let request = DaemonRequest::FileDownloadPublic {
+            addr: *addr,
+            to_dest: absolute(to_dest)?,
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::Done => ())
+    }
+
+    /// See [`crate::Client::analyze_address`]. Returns the rendered analysis.
+    pub async fn analyze_address(
+        &mut self,
+        address: &str,
+        verbose: bool,
+    ) -> Result<String, DaemonError> {
+        let request = DaemonRequest::Analyze {
+            address: address.to_string(),
+            verbose,
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::Analysis(analysis) => analysis)
+    }
+
+    /// Find out what a data map points to, analyzing it like [`crate::Client::analyze_address`].
+    pub async fn data_map_content(
+        &mut self,
+        data_map: &DataMapChunk,
+    ) -> Result<DataMapContent, DaemonError> {
+        let response = self
+            .request(&DaemonRequest::DataMapContent(data_map.clone()))
+            .await?;
+        expect_response!(response, DaemonResponse::DataMapContent(content) => content)
+    }
+
+    /// See [`crate::Client::register_get`].
+    pub async fn register_get(
+        &mut self,
+        addr: &RegisterAddress,
+    ) -> Result<RegisterValue, DaemonError> {
+        let response = self.request(&DaemonRequest::RegisterGet(*addr)).await?;
+        expect_response!(response, DaemonResponse::RegisterValue(value) => value)
+    }
+
+    /// See [`crate::Client::register_create`], paid with the wallet of the daemon.
+    pub async fn register_create(
+        &mut self,
+        owner: &SecretKey,
+        value: RegisterValue,
+    ) -> Result<(AttoTokens, RegisterAddress), DaemonError> {
+        let request = DaemonRequest::RegisterCreate {
+            owner: owner.to_hex(),
+            value,
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::RegisterCreated { cost, addr } => (cost, addr))
+    }
+
+    /// See [`crate::Client::register_update`], paid with the wallet of the daemon.
+    pub async fn register_update(
+        &mut self,
+        owner: &SecretKey,
+        value: RegisterValue,
+    ) -> Result<AttoTokens, DaemonError> {
+        let request = DaemonRequest::RegisterUpdate {
+            owner: owner.to_hex(),
+            value,
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::Cost(cost) => cost)
+    }
+
+    /// See [`crate::Client::register_cost`].
+    pub async fn register_cost(&mut self, owner: &PublicKey) -> Result<AttoTokens, DaemonError> {
+        let response = self.request(&DaemonRequest::RegisterCost(*owner)).await?;
+        expect_response!(response, DaemonResponse::Cost(cost) => cost)
+    }
+
+    /// See [`crate::Client::scratchpad_get`]. A fork is reported as
+    /// [`super::RemoteError::ScratchpadFork`].
+    pub async fn scratchpad_get(
+        &mut self,
+        addr: &ScratchpadAddress,
+    ) -> Result<Scratchpad, DaemonError> {
+        let response = self.request(&DaemonRequest::ScratchpadGet(*addr)).await?;
+        expect_response!(response, DaemonResponse::Scratchpad(scratchpad) => scratchpad)
+    }
+
+    /// See [`crate::Client::scratchpad_create`], paid with the wallet of the daemon.
+    pub async fn scratchpad_create(
+        &mut self,
+        owner: &SecretKey,
+        content_type: u64,
+        initial_data: &Bytes,
+    ) -> Result<(AttoTokens, ScratchpadAddress), DaemonError> {
+        let request = DaemonRequest::ScratchpadCreate {
+            owner: owner.to_hex(),
+            content_type,
+            data: initial_data.clone(),
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::ScratchpadCreated { cost, addr } => (cost, addr))
+    }
+
+    /// See [`crate::Client::scratchpad_update`].
+    pub async fn scratchpad_update(
+        &mut self,
+        owner: &SecretKey,
+        content_type: u64,
+        data: &Bytes,
+    ) -> Result<(), DaemonError> {
+        let request = DaemonRequest::ScratchpadUpdate {
+            owner: owner.to_hex(),
+            content_type,
+            data: data.clone(),
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::Done => ())
+    }
+
+    /// See [`crate::Client::scratchpad_update_from`].
+    pub async fn scratchpad_update_from(
+        &mut self,
+        current: &Scratchpad,
+        owner: &SecretKey,
+        content_type: u64,
+        data: &Bytes,
+    ) -> Result<Scratchpad, DaemonError> {
+        let request = DaemonRequest::ScratchpadUpdateFrom {
+            current: current.clone(),
+            owner: owner.to_hex(),
+            content_type,
+            data: data.clone(),
+        };
+        let response = self.request(&request).await?;
+        expect_response!(response, DaemonResponse::Scratchpad(scratchpad) => scratchpad)
+    }
+
+    /// See [`crate::Client::scratchpad_cost`].
+    pub async fn scratchpad_cost(&mut self, owner: &PublicKey) -> Result<AttoTokens, DaemonError> {
+        let response = self.request(&DaemonRequest::ScratchpadCost(*owner)).await?;
+        expect_response!(response, DaemonResponse::Cost(cost) => cost)
+    }
+}
+
+/// Paths are resolved by the daemon, which may run in another directory.
+fn absolute(path: &Path) -> Result<PathBuf, DaemonError> {
+    Ok(std::path::absolute(path)?)
+}
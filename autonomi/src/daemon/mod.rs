@@ -0,0 +1,39 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! A long running client serving other local programs over a Unix socket.
+//!
+//! Connecting to the network takes a while, as the routing table needs to be populated before
+//! anything can be done. The [`DaemonServer`] keeps a connected [`crate::Client`] around so
+//! that short lived programs can use it through a [`DaemonClient`] instead of connecting on
+//! their own.
+
+mod client;
+mod protocol;
+mod server;
+
+pub use client::DaemonClient;
+pub use protocol::{DaemonRequest, DaemonResponse, DaemonStatus, MAX_MESSAGE_SIZE, RemoteError};
+pub use server::DaemonServer;
+
+/// Errors that can occur when talking to the daemon.
+#[derive(Debug, thiserror::Error)]
+pub enum DaemonError {
+    #[error("IO error on the daemon socket: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to encode or decode a daemon message: {0}")]
+    Encoding(String),
+    #[error("Daemon message of {0} bytes exceeds the maximum size")]
+    MessageTooLarge(usize),
+    #[error("The daemon closed the connection")]
+    Disconnected,
+    #[error("Unexpected response from the daemon: {0:?}")]
+    UnexpectedResponse(Box<DaemonResponse>),
+    #[error(transparent)]
+    Remote(#[from] RemoteError),
+}
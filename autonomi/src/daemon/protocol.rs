@@ -0,0 +1,342 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::analyze::DataMapContent;
+use crate::client::data::DataAddress;
+use crate::client::data_types::chunk::DataMapChunk;
+use crate::client::files::{PrivateArchive, PublicArchive};
+use crate::client::register::{RegisterAddress, RegisterValue};
+use crate::{AttoTokens, Bytes, PublicKey, Scratchpad, ScratchpadAddress};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::fmt;
+use std::path::PathBuf;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::DaemonError;
+
+/// Largest message accepted on the socket. Bigger payloads should go through the file operations,
+/// which only send paths.
+pub const MAX_MESSAGE_SIZE: usize = 512 * 1024 * 1024;
+
+/// A request sent to the daemon.
+///
+/// Secret keys are sent hex encoded, the socket is only reachable by the user running the daemon.
+/// They are redacted from the [`Debug`] output, as are the payloads, which are only described by
+/// their size.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum DaemonRequest {
+    /// Get the status of the daemon.
+    Status,
+    /// Stop the daemon once the request has been answered.
+    Shutdown,
+    /// Fetch private data, see [`crate::Client::data_get`].
+    DataGet(DataMapChunk),
+    /// Fetch public data, see [`crate::Client::data_get_public`].
+    DataGetPublic(DataAddress),
+    /// Upload private data, see [`crate::Client::data_put`].
+    DataPut(Bytes),
+    /// Upload public data, see [`crate::Client::data_put_public`].
+    DataPutPublic(Bytes),
+    /// Estimate the cost of uploading data, see [`crate::Client::data_cost`].
+    DataCost(Bytes),
+    /// Estimate the cost of uploading a file or directory, see [`crate::Client::file_cost`].
+    FileCost(PathBuf),
+    /// Upload the content of a file privately, see [`crate::Client::file_content_upload`].
+    FileUpload(PathBuf),
+    /// Upload the content of a file publicly, see [`crate::Client::file_content_upload_public`].
+    FileUploadPublic(PathBuf),
+    /// Upload the content of a file or directory privately, see
+    /// [`crate::Client::dir_content_upload`].
+    DirContentUpload(PathBuf),
+    /// Upload the content of a file or directory publicly, see
+    /// [`crate::Client::dir_content_upload_public`].
+    DirContentUploadPublic(PathBuf),
+    /// Download a private file, see [`crate::Client::file_download`].
+    FileDownload {
+        data_map: DataMapChunk,
+        to_dest: PathBuf,
+    },
+    /// Download a public file, see [`crate::Client::file_download_public`].
+    FileDownloadPublic { addr: DataAddress, to_dest: PathBuf },
+    /// Analyze an address, see [`crate::Client::analyze_address`].
+    Analyze { address: String, verbose: bool },
+    /// Find out what a data map points to, see [`DataMapContent`].
+    DataMapContent(DataMapChunk),
+    /// Get the value of a register, see [`crate::Client::register_get`].
+    RegisterGet(RegisterAddress),
+    /// Create a register, see [`crate::Client::register_create`].
+    RegisterCreate { owner: String, value: RegisterValue },
+    /// Update a register, see [`crate::Client::register_update`].
+    RegisterUpdate { owner: String, value: RegisterValue },
+    /// Estimate the cost of creating a register, see [`crate::Client::register_cost`].
+    RegisterCost(PublicKey),
+    /// Get a scratchpad, see [`crate::Client::scratchpad_get`].
+    ScratchpadGet(ScratchpadAddress),
+    /// Create a scratchpad, see [`crate::Client::scratchpad_create`].
+    ScratchpadCreate {
+        owner: String,
+        content_type: u64,
+        data: Bytes,
+    },
+    /// Update a scratchpad, see [`crate::Client::scratchpad_update`].
+    ScratchpadUpdate {
+        owner: String,
+        content_type: u64,
+        data: Bytes,
+    },
+    /// Update a scratchpad from a known version, see [`crate::Client::scratchpad_update_from`].
+    ScratchpadUpdateFrom {
+        current: Scratchpad,
+        owner: String,
+        content_type: u64,
+        data: Bytes,
+    },
+    /// Estimate the cost of creating a scratchpad, see [`crate::Client::scratchpad_cost`].
+    ScratchpadCost(PublicKey),
+}
+
+impl fmt::Debug for DaemonRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const REDACTED: &str = "<redacted>";
+        let size = |data: &Bytes| format!("<{} bytes>", data.len());
+        match self {
+            Self::Status => write!(f, "Status"),
+            Self::Shutdown => write!(f, "Shutdown"),
+            Self::DataGet(data_map) => f.debug_tuple("DataGet").field(data_map).finish(),
+            Self::DataGetPublic(addr) => f.debug_tuple("DataGetPublic").field(addr).finish(),
+            Self::DataPut(data) => f.debug_tuple("DataPut").field(&size(data)).finish(),
+            Self::DataPutPublic(data) => f.debug_tuple("DataPutPublic").field(&size(data)).finish(),
+            Self::DataCost(data) => f.debug_tuple("DataCost").field(&size(data)).finish(),
+            Self::FileCost(path) => f.debug_tuple("FileCost").field(path).finish(),
+            Self::FileUpload(path) => f.debug_tuple("FileUpload").field(path).finish(),
+            Self::FileUploadPublic(path) => f.debug_tuple("FileUploadPublic").field(path).finish(),
+            Self::DirContentUpload(path) => f.debug_tuple("DirContentUpload").field(path).finish(),
+            Self::DirContentUploadPublic(path) => {
+                f.debug_tuple("DirContentUploadPublic").field(path).finish()
+            }
+            Self::FileDownload { data_map, to_dest } => f
+                .debug_struct("FileDownload")
+                .field("data_map", data_map)
+                .field("to_dest", to_dest)
+                .finish(),
+            Self::FileDownloadPublic { addr, to_dest } => f
+                .debug_struct("FileDownloadPublic")
+                .field("addr", addr)
+                .field("to_dest", to_dest)
+                .finish(),
+            Self::Analyze { address, verbose } => f
+                .debug_struct("Analyze")
+                .field("address", address)
+                .field("verbose", verbose)
+                .finish(),
+            Self::DataMapContent(data_map) => {
+                f.debug_tuple("DataMapContent").field(data_map).finish()
+            }
+            Self::RegisterGet(addr) => f.debug_tuple("RegisterGet").field(addr).finish(),
+            Self::RegisterCreate { value, .. } => f
+                .debug_struct("RegisterCreate")
+                .field("owner", &REDACTED)
+                .field("value", value)
+                .finish(),
+            Self::RegisterUpdate { value, .. } => f
+                .debug_struct("RegisterUpdate")
+                .field("owner", &REDACTED)
+                .field("value", value)
+                .finish(),
+            Self::RegisterCost(owner) => f.debug_tuple("RegisterCost").field(owner).finish(),
+            Self::ScratchpadGet(addr) => f.debug_tuple("ScratchpadGet").field(addr).finish(),
+            Self::ScratchpadCreate {
+                content_type, data, ..
+            } => f
+                .debug_struct("ScratchpadCreate")
+                .field("owner", &REDACTED)
+                .field("content_type", content_type)
+                .field("data", &size(data))
+                .finish(),
+            Self::ScratchpadUpdate {
+                content_type, data, ..
+            } => f
+                .debug_struct("ScratchpadUpdate")
+                .field("owner", &REDACTED)
+                .field("content_type", content_type)
+                .field("data", &size(data))
+                .finish(),
+            Self::ScratchpadUpdateFrom {
+                current,
+                content_type,
+                data,
+                ..
+            } => f
+                .debug_struct("ScratchpadUpdateFrom")
+                .field("current", current.address())
+                .field("owner", &REDACTED)
+                .field("content_type", content_type)
+                .field("data", &size(data))
+                .finish(),
+            Self::ScratchpadCost(owner) => f.debug_tuple("ScratchpadCost").field(owner).finish(),
+        }
+    }
+}
+
+/// The answer of the daemon to a [`DaemonRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DaemonResponse {
+    Status(DaemonStatus),
+    /// The operation succeeded and has nothing to return.
+    Done,
+    Data(Bytes),
+    Cost(AttoTokens),
+    PrivateUpload {
+        cost: AttoTokens,
+        data_map: DataMapChunk,
+    },
+    PublicUpload {
+        cost: AttoTokens,
+        addr: DataAddress,
+    },
+    PrivateArchive {
+        cost: AttoTokens,
+        archive: PrivateArchive,
+    },
+    PublicArchive {
+        cost: AttoTokens,
+        archive: PublicArchive,
+    },
+    DataMapContent(DataMapContent),
+    /// The rendered [`crate::client::analyze::Analysis`].
+    Analysis(String),
+    RegisterValue(RegisterValue),
+    RegisterCreated {
+        cost: AttoTokens,
+        addr: RegisterAddress,
+    },
+    Scratchpad(Scratchpad),
+    ScratchpadCreated {
+        cost: AttoTokens,
+        addr: ScratchpadAddress,
+    },
+    Error(RemoteError),
+}
+
+/// The status of a running daemon.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    /// The version of the daemon.
+    pub version: String,
+    /// How long the daemon has been running, in seconds.
+    pub uptime_secs: u64,
+    /// The EVM network used to pay for uploads.
+    pub evm_network: String,
+    /// The address of the wallet paying for uploads, if the daemon has one.
+    pub wallet: Option<String>,
+}
+
+/// An operation that failed within the daemon.
+#[derive(Debug, Clone, Serialize, Deserialize, thiserror::Error)]
+pub enum RemoteError {
+    #[error("{0}")]
+    Failed(String),
+    #[error("The daemon was started without a wallet, it can't pay for uploads")]
+    NoWallet,
+    #[error("Invalid secret key: {0}")]
+    InvalidKey(String),
+    #[error("The address was not recognized")]
+    UnrecognizedAddress,
+    #[error("The data is too large to be fetched in memory, download it to a file instead")]
+    TooLargeForMemory,
+    #[error("Got multiple conflicting scratchpads with the latest version")]
+    ScratchpadFork(Vec<Scratchpad>),
+}
+
+/// Write a message to the socket, prefixed with its length.
+pub(crate) async fn write_message<W, T>(writer: &mut W, message: &T) -> Result<(), DaemonError>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let bytes = rmp_serde::to_vec(message).map_err(|e| DaemonError::Encoding(e.to_string()))?;
+    if bytes.len() > MAX_MESSAGE_SIZE {
+        return Err(DaemonError::MessageTooLarge(bytes.len()));
+    }
+    writer.write_u32(bytes.len() as u32).await?;
+    writer.write_all(&bytes).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Read a message written by [`write_message`], `None` if the other side closed the socket.
+pub(crate) async fn read_message<R, T>(reader: &mut R) -> Result<Option<T>, DaemonError>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    let len = match reader.read_u32().await {
+        Ok(len) => len as usize,
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    if len > MAX_MESSAGE_SIZE {
+        return Err(DaemonError::MessageTooLarge(len));
+    }
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes).await?;
+    rmp_serde::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| DaemonError::Encoding(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn messages_roundtrip_over_a_stream() {
+        let (mut a, mut b) = tokio::io::duplex(1024);
+        let request = DaemonRequest::ScratchpadCreate {
+            owner: "00".repeat(32),
+            content_type: 7,
+            data: Bytes::from_static(b"scratch"),
+        };
+        write_message(&mut a, &request).await.expect("write");
+        drop(a);
+
+        let read: DaemonRequest = read_message(&mut b)
+            .await
+            .expect("read")
+            .expect("a message");
+        let DaemonRequest::ScratchpadCreate {
+            owner,
+            content_type,
+            data,
+        } = read
+        else {
+            panic!("unexpected request: {read:?}");
+        };
+        assert_eq!(owner, "00".repeat(32));
+        assert_eq!(content_type, 7);
+        assert_eq!(data, Bytes::from_static(b"scratch"));
+
+        let closed: Option<DaemonRequest> = read_message(&mut b).await.expect("read");
+        assert!(closed.is_none());
+    }
+
+    #[test]
+    fn debug_output_should_not_contain_secrets_or_payloads() {
+        let owner = "ab".repeat(32);
+        let request = DaemonRequest::ScratchpadCreate {
+            owner: owner.clone(),
+            content_type: 7,
+            data: Bytes::from(vec![b'x'; 1024]),
+        };
+
+        let debug = format!("{request:?}");
+        assert!(!debug.contains(&owner), "{debug}");
+        assert!(!debug.contains("xxxx"), "{debug}");
+        assert!(debug.contains("<1024 bytes>"), "{debug}");
+    }
+}
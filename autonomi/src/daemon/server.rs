@@ -0,0 +1,371 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::DaemonError;
+use super::protocol::{
+    DaemonRequest, DaemonResponse, DaemonStatus, RemoteError, read_message, write_message,
+};
+use crate::client::GetError;
+use crate::client::analyze::AnalysisError;
+use crate::client::analyze::DataMapContent;
+use crate::client::payment::PaymentOption;
+use crate::client::scratchpad::ScratchpadError;
+use crate::{Client, SecretKey, Wallet};
+use std::fmt::Display;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Notify;
+
+/// Serves a connected [`Client`] to other local programs over a Unix socket.
+///
+/// # Example
+///
+/// ```no_run
+/// # use autonomi::Client;
+/// # use autonomi::daemon::DaemonServer;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::init().await?;
+/// DaemonServer::new(client, None)
+///     .serve("/tmp/autonomi.sock".as_ref())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct DaemonServer {
+    client: Client,
+    wallet: Option<Wallet>,
+    started: Instant,
+}
+
+impl DaemonServer {
+    /// Create a server for the client. Without a wallet, the daemon can't pay for uploads.
+    pub fn new(client: Client, wallet: Option<Wallet>) -> Self {
+        Self {
+            client,
+            wallet,
+            started: Instant::now(),
+        }
+    }
+
+    /// Listen on the socket until a [`DaemonRequest::Shutdown`] is received.
+    ///
+    /// A stale socket left behind by a daemon that didn't exit cleanly is replaced, but this
+    /// fails if another daemon is still listening on it.
+    pub async fn serve(self, socket_path: &Path) -> Result<(), DaemonError> {
+        if socket_path.exists() {
+            if UnixStream::connect(socket_path).await.is_ok() {
+                return Err(DaemonError::Io(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    format!("A daemon is already listening on {socket_path:?}"),
+                )));
+            }
+            warn!("Removing stale daemon socket at {socket_path:?}");
+            std::fs::remove_file(socket_path)?;
+        }
+
+        let listener = bind_private(socket_path)?;
+        info!("Daemon listening on {socket_path:?}");
+
+        let server = Arc::new(self);
+        let shutdown = Arc::new(Notify::new());
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, _) = match accepted {
+                        Ok(accepted) => accepted,
+                        Err(err) => {
+                            error!("Failed to accept a daemon connection: {err}");
+                            continue;
+                        }
+                    };
+                    let server = Arc::clone(&server);
+                    let shutdown = Arc::clone(&shutdown);
+                    let _handle = tokio::spawn(async move {
+                        if let Err(err) = server.handle_connection(stream, &shutdown).await {
+                            warn!("Daemon connection ended with an error: {err}");
+                        }
+                    });
+                }
+                _ = shutdown.notified() => break,
+            }
+        }
+
+        info!("Daemon shutting down");
+        std::fs::remove_file(socket_path)?;
+        Ok(())
+    }
+
+    /// Answer the requests of a connection one after the other until it is closed.
+    async fn handle_connection(
+        &self,
+        mut stream: UnixStream,
+        shutdown: &Notify,
+    ) -> Result<(), DaemonError> {
+        while let Some(request) = read_message::<_, DaemonRequest>(&mut stream).await? {
+            let is_shutdown = matches!(request, DaemonRequest::Shutdown);
+            let response = self
+                .handle_request(request)
+                .await
+                .unwrap_or_else(DaemonResponse::Error);
+            write_message(&mut stream, &response).await?;
+            if is_shutdown {
+                shutdown.notify_one();
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_request(&self, request: DaemonRequest) -> Result<DaemonResponse, RemoteError> {
+        debug!("Daemon request: {request:?}");
+        let client = &self.client;
+        let response = match request {
+            DaemonRequest::Status => DaemonResponse::Status(DaemonStatus {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                uptime_secs: self.started.elapsed().as_secs(),
+                evm_network: client.evm_network().to_string(),
+                wallet: self
+                    .wallet
+                    .as_ref()
+                    .map(|wallet| wallet.address().to_string()),
+            }),
+            DaemonRequest::Shutdown => DaemonResponse::Done,
+            DaemonRequest::DataGet(data_map) => {
+                DaemonResponse::Data(client.data_get(&data_map).await.map_err(get_failed)?)
+            }
+            DaemonRequest::DataGetPublic(addr) => {
+                DaemonResponse::Data(client.data_get_public(&addr).await.map_err(get_failed)?)
+            }
+            DaemonRequest::DataPut(data) => {
+                let (cost, data_map) = client
+                    .data_put(data, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::PrivateUpload { cost, data_map }
+            }
+            DaemonRequest::DataPutPublic(data) => {
+                let (cost, addr) = client
+                    .data_put_public(data, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::PublicUpload { cost, addr }
+            }
+            DaemonRequest::DataCost(data) => {
+                DaemonResponse::Cost(client.data_cost(data).await.map_err(failed)?)
+            }
+            DaemonRequest::FileCost(path) => {
+                DaemonResponse::Cost(client.file_cost(&path).await.map_err(failed)?)
+            }
+            DaemonRequest::FileUpload(path) => {
+                let (cost, data_map) = client
+                    .file_content_upload(path, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::PrivateUpload { cost, data_map }
+            }
+            DaemonRequest::FileUploadPublic(path) => {
+                let (cost, addr) = client
+                    .file_content_upload_public(path, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::PublicUpload { cost, addr }
+            }
+            DaemonRequest::DirContentUpload(path) => {
+                let (cost, archive) = client
+                    .dir_content_upload(path, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::PrivateArchive { cost, archive }
+            }
+            DaemonRequest::DirContentUploadPublic(path) => {
+                let (cost, archive) = client
+                    .dir_content_upload_public(path, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::PublicArchive { cost, archive }
+            }
+            DaemonRequest::FileDownload { data_map, to_dest } => {
+                client
+                    .file_download(&data_map, to_dest)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::Done
+            }
+            DaemonRequest::FileDownloadPublic { addr, to_dest } => {
+                client
+                    .file_download_public(&addr, to_dest)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::Done
+            }
+            DaemonRequest::Analyze { address, verbose } => {
+                match client.analyze_address(&address, verbose).await {
+                    Ok(analysis) => DaemonResponse::Analysis(analysis.to_string()),
+                    Err(AnalysisError::UnrecognizedInput) => {
+                        return Err(RemoteError::UnrecognizedAddress);
+                    }
+                    Err(err) => return Err(failed(err)),
+                }
+            }
+            DaemonRequest::DataMapContent(data_map) => {
+                match client.analyze_address(&data_map.to_hex(), false).await {
+                    Ok(analysis) => DaemonResponse::DataMapContent(
+                        DataMapContent::try_from(analysis).map_err(|other| {
+                            RemoteError::Failed(format!("Unexpected data type found: {other}"))
+                        })?,
+                    ),
+                    Err(AnalysisError::UnrecognizedInput) => {
+                        return Err(RemoteError::UnrecognizedAddress);
+                    }
+                    Err(err) => return Err(failed(err)),
+                }
+            }
+            DaemonRequest::RegisterGet(addr) => {
+                DaemonResponse::RegisterValue(client.register_get(&addr).await.map_err(failed)?)
+            }
+            DaemonRequest::RegisterCreate { owner, value } => {
+                let (cost, addr) = client
+                    .register_create(&parse_key(&owner)?, value, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::RegisterCreated { cost, addr }
+            }
+            DaemonRequest::RegisterUpdate { owner, value } => {
+                let cost = client
+                    .register_update(&parse_key(&owner)?, value, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::Cost(cost)
+            }
+            DaemonRequest::RegisterCost(owner) => {
+                DaemonResponse::Cost(client.register_cost(&owner).await.map_err(failed)?)
+            }
+            DaemonRequest::ScratchpadGet(addr) => match client.scratchpad_get(&addr).await {
+                Ok(scratchpad) => DaemonResponse::Scratchpad(scratchpad),
+                Err(ScratchpadError::Fork(conflicting)) => {
+                    return Err(RemoteError::ScratchpadFork(conflicting));
+                }
+                Err(err) => return Err(failed(err)),
+            },
+            DaemonRequest::ScratchpadCreate {
+                owner,
+                content_type,
+                data,
+            } => {
+                let (cost, addr) = client
+                    .scratchpad_create(&parse_key(&owner)?, content_type, &data, self.payment()?)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::ScratchpadCreated { cost, addr }
+            }
+            DaemonRequest::ScratchpadUpdate {
+                owner,
+                content_type,
+                data,
+            } => {
+                client
+                    .scratchpad_update(&parse_key(&owner)?, content_type, &data)
+                    .await
+                    .map_err(failed)?;
+                DaemonResponse::Done
+            }
+            DaemonRequest::ScratchpadUpdateFrom {
+                current,
+                owner,
+                content_type,
+                data,
+            } => DaemonResponse::Scratchpad(
+                client
+                    .scratchpad_update_from(&current, &parse_key(&owner)?, content_type, &data)
+                    .await
+                    .map_err(failed)?,
+            ),
+            DaemonRequest::ScratchpadCost(owner) => {
+                DaemonResponse::Cost(client.scratchpad_cost(&owner).await.map_err(failed)?)
+            }
+        };
+        Ok(response)
+    }
+
+    fn payment(&self) -> Result<PaymentOption, RemoteError> {
+        self.wallet
+            .clone()
+            .map(PaymentOption::from)
+            .ok_or(RemoteError::NoWallet)
+    }
+}
+
+/// Bind the socket so that only the current user can ever connect to it.
+///
+/// Binding gives the socket the permissions of the umask, so it is bound inside a private
+/// directory, restricted and only then moved to its path.
+fn bind_private(socket_path: &Path) -> Result<UnixListener, DaemonError> {
+    let parent = match socket_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let private_dir = parent.join(format!(".autonomi-daemon-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&private_dir);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+
+    let bound_path = private_dir.join("socket");
+    let bound = UnixListener::bind(&bound_path)
+        .and_then(|listener| {
+            std::fs::set_permissions(&bound_path, std::fs::Permissions::from_mode(0o600))?;
+            std::fs::rename(&bound_path, socket_path)?;
+            Ok(listener)
+        })
+        .map_err(DaemonError::from);
+    if let Err(err) = std::fs::remove_dir_all(&private_dir) {
+        warn!("Failed to remove {private_dir:?}: {err}");
+    }
+    bound
+}
+
+fn parse_key(hex: &str) -> Result<SecretKey, RemoteError> {
+    SecretKey::from_hex(hex).map_err(|err| RemoteError::InvalidKey(err.to_string()))
+}
+
+fn failed(err: impl Display) -> RemoteError {
+    RemoteError::Failed(err.to_string())
+}
+
+/// Data too large for memory is reported apart, so that it can be downloaded to a file instead.
+fn get_failed(err: GetError) -> RemoteError {
+    match err {
+        GetError::TooLargeForMemory => RemoteError::TooLargeForMemory,
+        err => failed(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn socket_should_only_be_accessible_to_the_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("daemon.sock");
+
+        let _listener = bind_private(&socket_path).unwrap();
+
+        let mode = std::fs::metadata(&socket_path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert!(UnixStream::connect(&socket_path).await.is_ok());
+    }
+}
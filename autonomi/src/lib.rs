@@ -47,6 +47,8 @@
 //! # Features
 //!
 //! - `loud`: Print debug information to stdout
+//! - `daemon`: Serve a connected client to other local programs over a Unix socket
+//! - `open-metrics`: Record Prometheus metrics of the client, see [`Client::with_metrics`]
 
 // docs.rs generation will enable unstable `doc_cfg` feature
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -61,6 +63,9 @@
 extern crate tracing;
 
 pub mod client;
+#[cfg(all(feature = "daemon", unix))]
+#[cfg_attr(docsrs, doc(cfg(feature = "daemon")))]
+pub mod daemon;
 pub mod networking;
 pub mod self_encryption;
 
@@ -99,6 +104,9 @@ pub use bls::{PublicKey, SecretKey, Signature};
 pub use bytes::Bytes;
 #[doc(no_inline)] // Place this under 'Re-exports' in the docs.
 pub use libp2p::Multiaddr;
+#[cfg(feature = "open-metrics")]
+#[doc(no_inline)] // Place this under 'Re-exports' in the docs.
+pub use prometheus_client;
 
 // private helper modules
 pub(crate) mod utils;
@@ -112,6 +120,7 @@ pub use client::{
     config::BootstrapError,
     config::ClientConfig,
     config::ClientOperatingStrategy,
+    config::Concurrency,
     config::InitialPeersConfig,
 
     // Native data types
@@ -0,0 +1,775 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_protocol::{CLOSE_GROUP_SIZE, NetworkAddress, constants::MAX_KEYS_PER_BATCH_QUERY};
+use futures::stream::{self, FuturesUnordered, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+use super::interface::{NetworkTask, RecordsAndRemaining};
+use super::peer_stats::RequestKind;
+use super::{Network, NetworkError, PeerId, PeerInfo, Record, Strategy};
+use crate::client::concurrency::{ConcurrencyController, TaskOutcome};
+use crate::utils::process_tasks_with_adaptive_concurrency;
+
+impl Network {
+    /// Get several records from a single peer, in as few requests as the packet size allows.
+    /// Returns the records the peer handed back, the ones it doesn't hold are left out.
+    ///
+    /// The records come from a single peer without any quorum, so this should only be used for
+    /// self-verifying content such as chunks.
+    /// Nodes that don't support batched queries yield [`NetworkError::IncompatibleNetworkProtocol`].
+    pub async fn get_records_from_peer(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        peer: PeerInfo,
+    ) -> Result<HashMap<NetworkAddress, Record>, NetworkError> {
+        let mut found = HashMap::new();
+        let mut pending = addrs;
+        while !pending.is_empty() {
+            let requested: HashSet<_> = pending.iter().cloned().collect();
+            let (records, remaining) = self.get_records_req(pending, peer.clone()).await?;
+
+            // Ignore anything the peer sent that we didn't ask for
+            found.extend(records.into_iter().filter_map(|(addr, record)| {
+                record
+                    .filter(|_| requested.contains(&addr))
+                    .map(|record| (addr, record))
+            }));
+
+            // A peer that doesn't make progress would keep us asking forever
+            if remaining.len() >= requested.len() {
+                warn!(
+                    "Peer {:?} made no progress on a batched get, {} keys left",
+                    peer.peer_id,
+                    remaining.len()
+                );
+                break;
+            }
+            pending = remaining
+                .into_iter()
+                .filter(|addr| requested.contains(addr))
+                .collect();
+        }
+        #[cfg(feature = "open-metrics")]
+        if let Some(metrics_recorder) = &self.metrics_recorder {
+            let bytes = found.values().map(|record| record.value.len()).sum();
+            metrics_recorder.record_downloaded_bytes(bytes);
+        }
+        Ok(found)
+    }
+
+    /// Get a record from a single peer, with a batched get if the peer supports it or else the
+    /// single record query that old nodes answer as well.
+    /// Returns `None` if the peer doesn't hold the record.
+    ///
+    /// As with [`Network::get_records_from_peer`], only use this for self-verifying content.
+    pub async fn get_record_from_peer(
+        &self,
+        addr: NetworkAddress,
+        peer: PeerInfo,
+    ) -> Result<Option<Record>, NetworkError> {
+        if self.peer_stats.supports_direct_gets(&peer.peer_id) {
+            match self
+                .get_records_from_peer(vec![addr.clone()], peer.clone())
+                .await
+            {
+                Ok(mut records) => return Ok(records.remove(&addr)),
+                Err(NetworkError::IncompatibleNetworkProtocol) => {
+                    debug!("Peer {:?} doesn't support direct gets", peer.peer_id);
+                    self.peer_stats.record_direct_gets_unsupported(peer.peer_id);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let record = self.get_record_req(addr, peer).await?;
+        #[cfg(feature = "open-metrics")]
+        if let Some(metrics_recorder) = &self.metrics_recorder
+            && let Some(record) = &record
+        {
+            metrics_recorder.record_downloaded_bytes(record.value.len());
+        }
+        Ok(record)
+    }
+
+    /// Check which of the given records a single peer holds.
+    /// Only the keys the peer answered for are in the result.
+    pub async fn check_records_exist_at_peer(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        peer: PeerInfo,
+    ) -> Result<HashMap<NetworkAddress, bool>, NetworkError> {
+        let mut results = HashMap::new();
+        for batch in addrs.chunks(MAX_KEYS_PER_BATCH_QUERY) {
+            let requested: HashSet<_> = batch.iter().collect();
+            let answers = self
+                .check_records_exist_req(batch.to_vec(), peer.clone())
+                .await?;
+            results.extend(
+                answers
+                    .into_iter()
+                    .filter(|(addr, _)| requested.contains(addr)),
+            );
+        }
+        Ok(results)
+    }
+
+    /// Get several records, asking each close group peer for all the records it holds at once.
+    /// Returns the records that could be fetched this way, callers are expected to fetch the
+    /// missing ones individually, as peers may be old nodes that don't support batched queries.
+    ///
+    /// The records go to the fastest of the peers, and when a peer takes longer than most
+    /// batches do, its records are asked from their other holders as well.
+    ///
+    /// As with [`Network::get_records_from_peer`], only use this for self-verifying content.
+    /// Each record is timed as a get with the strategy, and the controller is told how the
+    /// request to each peer went.
+    pub(crate) async fn get_records_batched(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        strategy: &Strategy,
+        concurrency: &ConcurrencyController,
+    ) -> HashMap<NetworkAddress, Record> {
+        let started = Instant::now();
+        let holders = self
+            .close_group_holders(addrs.clone(), concurrency.limit(), RequestKind::Get)
+            .await;
+        let groups = assign_addresses_to_peers(holders.clone());
+        let holders: HashMap<_, _> = holders.into_iter().collect();
+        let hedge_delay = self.peer_stats.batch_hedge_delay();
+
+        let tasks = groups.into_iter().map(|(peer, addrs)| {
+            let holders = &holders;
+            async move {
+                let res = self
+                    .get_records_hedged(peer, addrs, holders, hedge_delay)
+                    .await;
+                (started.elapsed(), res)
+            }
+        });
+        let results =
+            process_tasks_with_adaptive_concurrency(tasks, concurrency, |(_, res)| outcome(res))
+                .await;
+
+        let mut found = HashMap::new();
+        for (elapsed, records) in results {
+            for (addr, record) in records.into_iter().flatten() {
+                self.record_direct_get(strategy, elapsed, true);
+                found.insert(addr, record);
+            }
+        }
+        for _ in addrs.iter().filter(|addr| !found.contains_key(*addr)) {
+            self.record_direct_get(strategy, started.elapsed(), false);
+        }
+        found
+    }
+
+    /// Get the records from the peer, and from their other holders as well if the peer doesn't
+    /// answer within the hedging delay. Returns once the peer answered or the other holders
+    /// handed over every record, with the error of the peer if nothing could be fetched.
+    async fn get_records_hedged(
+        &self,
+        peer: PeerInfo,
+        addrs: Vec<NetworkAddress>,
+        holders: &HashMap<NetworkAddress, Vec<PeerInfo>>,
+        hedge_delay: Duration,
+    ) -> Result<HashMap<NetworkAddress, Record>, NetworkError> {
+        let peer_id = peer.peer_id;
+        let start = Instant::now();
+        let first = self.get_records_from_peer(addrs.clone(), peer);
+        tokio::pin!(first);
+        tokio::select! {
+            res = &mut first => {
+                match &res {
+                    Ok(_) => self.peer_stats.record_batch_get_latency(start.elapsed()),
+                    Err(err) => self.batch_query_failed(peer_id, "get", err),
+                }
+                return res;
+            }
+            _ = tokio::time::sleep(hedge_delay) => {}
+        }
+
+        let others = addrs
+            .iter()
+            .map(|addr| {
+                let peers = holders.get(addr).into_iter().flatten();
+                let peers = peers.filter(|other| other.peer_id != peer_id).cloned();
+                (addr.clone(), peers.collect())
+            })
+            .collect();
+        let mut hedges: FuturesUnordered<_> = assign_addresses_to_peers(others)
+            .into_iter()
+            .map(|(other, addrs)| async move {
+                let other_id = other.peer_id;
+                (other_id, self.get_records_from_peer(addrs, other).await)
+            })
+            .collect();
+        debug!(
+            "Peer {peer_id:?} didn't hand over {} records within {hedge_delay:?}, asking {} other holders",
+            addrs.len(),
+            hedges.len()
+        );
+
+        let mut found = HashMap::new();
+        let mut first_err = None;
+        let mut first_done = false;
+        loop {
+            tokio::select! {
+                res = &mut first, if !first_done => {
+                    first_done = true;
+                    match res {
+                        Ok(records) => {
+                            found.extend(records);
+                            return Ok(found);
+                        }
+                        Err(err) => {
+                            self.batch_query_failed(peer_id, "get", &err);
+                            first_err = Some(err);
+                        }
+                    }
+                }
+                Some((other_id, res)) = hedges.next() => {
+                    match res {
+                        Ok(records) => found.extend(records),
+                        Err(err) => self.batch_query_failed(other_id, "hedged get", &err),
+                    }
+                    if addrs.iter().all(|addr| found.contains_key(addr)) {
+                        return Ok(found);
+                    }
+                }
+                else => break,
+            }
+        }
+        match first_err {
+            Some(err) if found.is_empty() => Err(err),
+            _ => Ok(found),
+        }
+    }
+
+    /// Check the existence of several records, asking each close group peer about all the
+    /// records it should hold at once.
+    /// Only records that a peer confirmed holding are reported as existing, the others may still
+    /// exist and are left for the caller to check individually.
+    /// The controller is told how the request to each peer went.
+    pub(crate) async fn check_records_existence_batched(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        concurrency: &ConcurrencyController,
+    ) -> HashSet<NetworkAddress> {
+        let holders = self
+            .close_group_holders(addrs, concurrency.limit(), RequestKind::ExistenceCheck)
+            .await;
+        let groups = assign_addresses_to_peers(holders);
+
+        let tasks = groups.into_iter().map(|(peer, addrs)| async move {
+            let peer_id = peer.peer_id;
+            let res = self.check_records_exist_at_peer(addrs, peer).await;
+            (peer_id, res)
+        });
+        let results =
+            process_tasks_with_adaptive_concurrency(tasks, concurrency, |(_, res)| outcome(res))
+                .await;
+
+        let mut existing = HashSet::new();
+        for (peer_id, res) in results {
+            match res {
+                Ok(answers) => existing.extend(
+                    answers
+                        .into_iter()
+                        .filter_map(|(addr, exists)| exists.then_some(addr)),
+                ),
+                Err(err) => self.batch_query_failed(peer_id, "existence check", &err),
+            }
+        }
+        existing
+    }
+
+    /// Count the peers holding each record, asking every close group peer about all the records
+    /// it should hold at once.
+    /// Old nodes that don't support batched queries are asked for a proof of each chunk instead,
+    /// so this should only be used for chunks.
+    /// Addresses whose close group couldn't be found are left out.
+    /// The controller is told how the requests to each peer went.
+    pub(crate) async fn count_record_holders(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        concurrency: &ConcurrencyController,
+    ) -> HashMap<NetworkAddress, RecordHolders> {
+        let closest = self.closest_peers_of(addrs, concurrency.limit()).await;
+
+        let mut by_peer: HashMap<PeerId, (PeerInfo, Vec<NetworkAddress>)> = HashMap::new();
+        for (addr, peers) in &closest {
+            for peer in peers.iter().take(CLOSE_GROUP_SIZE) {
+                by_peer
+                    .entry(peer.peer_id)
+                    .or_insert_with(|| (peer.clone(), vec![]))
+                    .1
+                    .push(addr.clone());
+            }
+        }
+
+        let tasks = by_peer.into_values().map(|(peer, addrs)| async move {
+            let peer_id = peer.peer_id;
+            let res = if self.peer_stats.supports_direct_gets(&peer_id) {
+                self.check_records_exist_at_peer(addrs.clone(), peer.clone())
+                    .await
+            } else {
+                Err(NetworkError::IncompatibleNetworkProtocol)
+            };
+            let res = match res {
+                Err(NetworkError::IncompatibleNetworkProtocol) => {
+                    self.peer_stats.record_direct_gets_unsupported(peer_id);
+                    Ok(self.check_chunks_exist_one_by_one(&addrs, peer).await)
+                }
+                res => res,
+            };
+            if let Err(err) = &res {
+                debug!("Existence check at peer {peer_id:?} failed: {err}");
+            }
+            (addrs, res)
+        });
+        let answers =
+            process_tasks_with_adaptive_concurrency(tasks, concurrency, |(_, res)| outcome(res))
+                .await
+                .into_iter()
+                .map(|(addrs, res)| (addrs, res.ok()))
+                .collect();
+
+        tally_record_holders(closest.into_iter().map(|(addr, _)| addr), answers)
+    }
+
+    /// Log a failed batched query, remembering the peers that don't support them so that they
+    /// aren't asked again for a while.
+    fn batch_query_failed(&self, peer_id: PeerId, query: &str, err: &NetworkError) {
+        if matches!(err, NetworkError::IncompatibleNetworkProtocol) {
+            self.peer_stats.record_direct_gets_unsupported(peer_id);
+        }
+        debug!("Batched {query} at peer {peer_id:?} failed: {err}");
+    }
+
+    /// Check which of the chunks a peer holds by asking it for the proof of each chunk, which old
+    /// nodes that don't support batched queries answer.
+    /// Only the chunks the peer answered for are in the result.
+    async fn check_chunks_exist_one_by_one(
+        &self,
+        addrs: &[NetworkAddress],
+        peer: PeerInfo,
+    ) -> HashMap<NetworkAddress, bool> {
+        let mut results = HashMap::new();
+        for addr in addrs {
+            match self.chunk_existence_req(addr.clone(), peer.clone()).await {
+                Ok(exists) => {
+                    results.insert(addr.clone(), exists);
+                }
+                Err(err) => {
+                    debug!(
+                        "Existence proof of {addr:?} at peer {:?} failed: {err}",
+                        peer.peer_id
+                    );
+                }
+            }
+        }
+        results
+    }
+
+    /// Look up the close group of each address, which holds its record, and leave out the
+    /// holders that are slow at the kind of requests and the old nodes that don't support
+    /// batched queries. The other holders are sorted from the fastest.
+    /// Addresses whose close group couldn't be found are left out.
+    async fn close_group_holders(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        concurrency: usize,
+        kind: RequestKind,
+    ) -> Vec<(NetworkAddress, Vec<PeerInfo>)> {
+        self.closest_peers_of(addrs, concurrency)
+            .await
+            .into_iter()
+            .map(|(addr, mut peers)| {
+                peers.truncate(CLOSE_GROUP_SIZE);
+                let peers = self.peer_stats.with_direct_gets(peers);
+                let mut peers = self.peer_stats.without_slow_peers(kind, peers, 1);
+                self.peer_stats.sort_by_speed(kind, &mut peers);
+                (addr, peers)
+            })
+            .collect()
+    }
+
+    /// Find the closest peers of each address.
+    /// A first few addresses are looked up on the network, which tells how close a close group
+    /// is. The routing table is used for the other addresses when it knows enough peers that
+    /// close to them, the rest are looked up as well.
+    /// Addresses whose close group couldn't be found are left out.
+    async fn closest_peers_of(
+        &self,
+        mut addrs: Vec<NetworkAddress>,
+        concurrency: usize,
+    ) -> Vec<(NetworkAddress, Vec<PeerInfo>)> {
+        let others = addrs.split_off(addrs.len().min(concurrency.max(1)));
+        let mut closest = self.look_up_closest_peers(addrs, concurrency).await;
+        let Some(close_group_distance) = closest
+            .iter()
+            .filter_map(|(addr, peers)| close_group_ilog2(addr, peers))
+            .min()
+        else {
+            closest.extend(self.look_up_closest_peers(others, concurrency).await);
+            return closest;
+        };
+
+        let local = match self.get_local_closest_peers(others.clone()).await {
+            Ok(local) => local,
+            Err(err) => {
+                debug!("Failed to get the closest peers from the routing table: {err}");
+                closest.extend(self.look_up_closest_peers(others, concurrency).await);
+                return closest;
+            }
+        };
+        let (covered, not_covered): (Vec<_>, Vec<_>) =
+            local.into_iter().partition(|(addr, peers)| {
+                close_group_ilog2(addr, peers)
+                    .is_some_and(|distance| distance <= close_group_distance)
+            });
+        debug!(
+            "The routing table covers {} of {} addresses for batching",
+            covered.len(),
+            others.len()
+        );
+        closest.extend(covered);
+        let not_covered = not_covered.into_iter().map(|(addr, _)| addr).collect();
+        closest.extend(self.look_up_closest_peers(not_covered, concurrency).await);
+        closest
+    }
+
+    /// Look up the closest peers of each address on the network.
+    /// Addresses whose close group couldn't be found are left out.
+    async fn look_up_closest_peers(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        concurrency: usize,
+    ) -> Vec<(NetworkAddress, Vec<PeerInfo>)> {
+        stream::iter(addrs)
+            .map(|addr| async move {
+                match self.get_closest_peers(addr.clone()).await {
+                    Ok(peers) => Some((addr, peers)),
+                    Err(err) => {
+                        debug!("Failed to get closest peers of {addr:?} for batching: {err}");
+                        None
+                    }
+                }
+            })
+            .buffer_unordered(concurrency.max(1))
+            .filter_map(|res| async move { res })
+            .collect()
+            .await
+    }
+
+    async fn get_local_closest_peers(
+        &self,
+        addrs: Vec<NetworkAddress>,
+    ) -> Result<Vec<(NetworkAddress, Vec<PeerInfo>)>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let task = NetworkTask::GetLocalClosestPeers {
+            addrs,
+            n: CLOSE_GROUP_SIZE,
+            resp: tx,
+        };
+        self.task_sender
+            .send(task)
+            .await
+            .map_err(|_| NetworkError::NetworkDriverOffline)?;
+        rx.await?
+    }
+
+    async fn get_record_req(
+        &self,
+        addr: NetworkAddress,
+        peer: PeerInfo,
+    ) -> Result<Option<Record>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let task = NetworkTask::GetRecordReq {
+            addr,
+            peer,
+            resp: tx,
+        };
+        self.task_sender
+            .send(task)
+            .await
+            .map_err(|_| NetworkError::NetworkDriverOffline)?;
+        rx.await?
+    }
+
+    async fn get_records_req(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        peer: PeerInfo,
+    ) -> Result<RecordsAndRemaining, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let task = NetworkTask::GetRecordsReq {
+            addrs,
+            peer,
+            resp: tx,
+        };
+        self.task_sender
+            .send(task)
+            .await
+            .map_err(|_| NetworkError::NetworkDriverOffline)?;
+        rx.await?
+    }
+
+    async fn check_records_exist_req(
+        &self,
+        addrs: Vec<NetworkAddress>,
+        peer: PeerInfo,
+    ) -> Result<Vec<(NetworkAddress, bool)>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let task = NetworkTask::CheckRecordsExistReq {
+            addrs,
+            peer,
+            resp: tx,
+        };
+        self.task_sender
+            .send(task)
+            .await
+            .map_err(|_| NetworkError::NetworkDriverOffline)?;
+        rx.await?
+    }
+
+    async fn chunk_existence_req(
+        &self,
+        addr: NetworkAddress,
+        peer: PeerInfo,
+    ) -> Result<bool, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let task = NetworkTask::ChunkExistenceReq {
+            addr,
+            peer,
+            resp: tx,
+        };
+        self.task_sender
+            .send(task)
+            .await
+            .map_err(|_| NetworkError::NetworkDriverOffline)?;
+        rx.await?
+    }
+}
+
+/// How a request to a peer went, for the concurrency controller.
+fn outcome<T>(res: &Result<T, NetworkError>) -> TaskOutcome {
+    match res {
+        Ok(_) => TaskOutcome::Success,
+        Err(err) => TaskOutcome::of_network_error(err),
+    }
+}
+
+/// Which of the records it was asked about a peer holds.
+type ExistenceAnswers = HashMap<NetworkAddress, bool>;
+
+/// How many close group peers of a record hold it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecordHolders {
+    /// Peers that confirmed holding the record
+    pub holders: usize,
+    /// Peers that couldn't be asked, e.g. unreachable ones
+    pub unanswered: usize,
+}
+
+/// Count the holders of each address out of the answers of its close group peers, given as the
+/// addresses each peer was asked about and its answers, if it could be asked.
+fn tally_record_holders(
+    addrs: impl Iterator<Item = NetworkAddress>,
+    answers: Vec<(Vec<NetworkAddress>, Option<ExistenceAnswers>)>,
+) -> HashMap<NetworkAddress, RecordHolders> {
+    let mut counts: HashMap<_, RecordHolders> =
+        addrs.map(|addr| (addr, Default::default())).collect();
+    for (asked, answer) in answers {
+        for addr in asked {
+            let Some(count) = counts.get_mut(&addr) else {
+                continue;
+            };
+            match answer.as_ref().and_then(|answer| answer.get(&addr)) {
+                Some(true) => count.holders += 1,
+                Some(false) => {}
+                None => count.unanswered += 1,
+            }
+        }
+    }
+    counts
+}
+
+/// How far the farthest close group peer of an address is, as the ilog2 of the distance.
+/// `None` if there are not enough peers to make up a close group.
+fn close_group_ilog2(addr: &NetworkAddress, peers: &[PeerInfo]) -> Option<u32> {
+    if peers.len() < CLOSE_GROUP_SIZE {
+        return None;
+    }
+    peers
+        .iter()
+        .take(CLOSE_GROUP_SIZE)
+        .filter_map(|peer| addr.distance(&NetworkAddress::from(peer.peer_id)).ilog2())
+        .max()
+}
+
+/// Assign each address to one of its close group peers, preferring the peers that are in the
+/// close group of the most addresses, so that the addresses end up in as few groups as possible.
+/// Ties go to the peer listed first for the address, such as the fastest or the closest one.
+fn assign_addresses_to_peers(
+    closest: Vec<(NetworkAddress, Vec<PeerInfo>)>,
+) -> Vec<(PeerInfo, Vec<NetworkAddress>)> {
+    let mut peer_counts: HashMap<PeerId, usize> = HashMap::new();
+    for (_, peers) in &closest {
+        for peer in peers.iter().take(CLOSE_GROUP_SIZE) {
+            *peer_counts.entry(peer.peer_id).or_default() += 1;
+        }
+    }
+
+    let mut groups: Vec<(PeerInfo, Vec<NetworkAddress>)> = vec![];
+    let mut group_index: HashMap<PeerId, usize> = HashMap::new();
+    for (addr, peers) in closest {
+        let mut best: Option<(&PeerInfo, usize)> = None;
+        for peer in peers.iter().take(CLOSE_GROUP_SIZE) {
+            let count = peer_counts.get(&peer.peer_id).copied().unwrap_or_default();
+            if best.is_none_or(|(_, best_count)| count > best_count) {
+                best = Some((peer, count));
+            }
+        }
+        let Some((peer, _)) = best else {
+            continue;
+        };
+
+        match group_index.get(&peer.peer_id) {
+            Some(index) => groups[*index].1.push(addr),
+            None => {
+                group_index.insert(peer.peer_id, groups.len());
+                groups.push((peer.clone(), vec![addr]));
+            }
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xor_name::XorName;
+
+    fn peer() -> PeerInfo {
+        PeerInfo {
+            peer_id: PeerId::random(),
+            addrs: vec![],
+        }
+    }
+
+    fn addr() -> NetworkAddress {
+        NetworkAddress::from(ant_protocol::storage::ChunkAddress::new(XorName::random(
+            &mut rand::thread_rng(),
+        )))
+    }
+
+    #[test]
+    fn addresses_sharing_a_peer_end_up_in_one_group() {
+        let shared = peer();
+        let (a, b, c) = (addr(), addr(), addr());
+        let closest = vec![
+            (a.clone(), vec![peer(), shared.clone(), peer()]),
+            (b.clone(), vec![shared.clone(), peer()]),
+            (c.clone(), vec![peer(), peer(), shared.clone()]),
+        ];
+
+        let groups = assign_addresses_to_peers(closest);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0.peer_id, shared.peer_id);
+        assert_eq!(groups[0].1, vec![a, b, c]);
+    }
+
+    #[test]
+    fn ties_go_to_the_peer_listed_first() {
+        let (closest_peer, other_peer) = (peer(), peer());
+        let a = addr();
+
+        let groups =
+            assign_addresses_to_peers(vec![(a.clone(), vec![closest_peer.clone(), other_peer])]);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0.peer_id, closest_peer.peer_id);
+        assert_eq!(groups[0].1, vec![a]);
+    }
+
+    #[test]
+    fn holders_are_tallied_per_address() {
+        let (a, b) = (addr(), addr());
+        let answers = vec![
+            (
+                vec![a.clone(), b.clone()],
+                Some(HashMap::from([(a.clone(), true), (b.clone(), false)])),
+            ),
+            (vec![a.clone()], Some(HashMap::from([(a.clone(), true)]))),
+            // A peer that left one address unanswered, and one that couldn't be asked
+            (vec![b.clone()], Some(HashMap::new())),
+            (vec![a.clone(), b.clone()], None),
+        ];
+
+        let counts = tally_record_holders([a.clone(), b.clone()].into_iter(), answers);
+
+        assert_eq!(
+            counts[&a],
+            RecordHolders {
+                holders: 2,
+                unanswered: 1
+            }
+        );
+        assert_eq!(
+            counts[&b],
+            RecordHolders {
+                holders: 0,
+                unanswered: 2
+            }
+        );
+    }
+
+    #[test]
+    fn close_group_distance_is_the_farthest_close_group_peer() {
+        let a = addr();
+        let mut peers: Vec<_> = (0..CLOSE_GROUP_SIZE).map(|_| peer()).collect();
+        let expected = peers
+            .iter()
+            .filter_map(|peer| a.distance(&NetworkAddress::from(peer.peer_id)).ilog2())
+            .max();
+
+        assert_eq!(close_group_ilog2(&a, &peers), expected);
+        peers.pop();
+        assert_eq!(close_group_ilog2(&a, &peers), None);
+    }
+
+    #[test]
+    fn addresses_without_peers_are_left_out() {
+        let groups = assign_addresses_to_peers(vec![(addr(), vec![])]);
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn only_close_group_peers_are_considered() {
+        let far_peer = peer();
+        let mut peers: Vec<_> = (0..CLOSE_GROUP_SIZE).map(|_| peer()).collect();
+        peers.push(far_peer.clone());
+        let closest = vec![
+            (addr(), peers.clone()),
+            (addr(), vec![far_peer.clone()]),
+            (addr(), vec![far_peer.clone()]),
+        ];
+
+        let groups = assign_addresses_to_peers(closest);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0.peer_id, peers[0].peer_id);
+        assert_eq!(groups[1].0.peer_id, far_peer.peer_id);
+        assert_eq!(groups[1].1.len(), 2);
+    }
+}
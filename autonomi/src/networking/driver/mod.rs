@@ -15,12 +15,12 @@ use std::{num::NonZeroUsize, time::Duration};
 
 use crate::networking::NetworkError;
 use crate::networking::interface::NetworkTask;
+use crate::networking::peer_stats::PeerStatsTracker;
 use ant_bootstrap::BootstrapCacheStore;
 use ant_protocol::NetworkAddress;
-use ant_protocol::version::IDENTIFY_PROTOCOL_STR;
 use ant_protocol::{
-    messages::{Query, Request, Response},
-    version::REQ_RESPONSE_VERSION_STR,
+    messages::{MessageCodec, Query, Request},
+    version::NetworkContext,
 };
 use futures::future::Either;
 use libp2p::kad::NoKnownPeers;
@@ -31,10 +31,10 @@ use libp2p::{
     core::muxing::StreamMuxerBox,
     futures::StreamExt,
     identity::Keypair,
-    kad::{self, store::MemoryStore},
+    kad::{self, PeerInfo, store::MemoryStore},
     multiaddr::Protocol,
     quic::tokio::Transport as QuicTransport,
-    request_response::{self, ProtocolSupport, cbor::codec::Codec as CborCodec},
+    request_response::{self, ProtocolSupport},
     swarm::NetworkBehaviour,
 };
 use task_handler::TaskHandler;
@@ -88,7 +88,7 @@ pub(crate) struct AutonomiClientBehaviour {
     pub kademlia: kad::Behaviour<MemoryStore>,
     pub identify: libp2p::identify::Behaviour,
     pub relay_client: libp2p::relay::client::Behaviour,
-    pub request_response: request_response::cbor::Behaviour<Request, Response>,
+    pub request_response: request_response::Behaviour<MessageCodec>,
     pub blocklist: libp2p::allow_block_list::Behaviour<libp2p::allow_block_list::BlockedPeers>,
 }
 
@@ -97,6 +97,8 @@ impl NetworkDriver {
     pub fn new(
         bootstrap_cache: Option<BootstrapCacheStore>,
         task_receiver: mpsc::Receiver<NetworkTask>,
+        network_context: &NetworkContext,
+        peer_stats: PeerStatsTracker,
     ) -> Self {
         // random new client id
         let keypair = Keypair::generate_ed25519();
@@ -129,13 +131,9 @@ impl NetworkDriver {
 
         // identify behaviour
         let identify = {
-            let identify_protocol_str = IDENTIFY_PROTOCOL_STR
-                .read()
-                .expect("Could not get IDENTIFY_PROTOCOL_STR")
-                .clone();
-            let agent_version = ant_protocol::version::construct_client_user_agent(
-                env!("CARGO_PKG_VERSION").to_string(),
-            );
+            let identify_protocol_str = network_context.identify_protocol();
+            let agent_version =
+                network_context.client_user_agent(env!("CARGO_PKG_VERSION").to_string());
             info!("Client user agent: {agent_version}");
             let cfg = libp2p::identify::Config::new(identify_protocol_str, keypair.public())
                 .with_agent_version(agent_version)
@@ -149,16 +147,19 @@ impl NetworkDriver {
         let request_response = {
             let cfg = request_response::Config::default().with_request_timeout(REQ_TIMEOUT);
 
-            let req_res_version_str = REQ_RESPONSE_VERSION_STR
-                .read()
-                .expect("no protocol version")
-                .clone();
-            let stream = StreamProtocol::try_from_owned(req_res_version_str)
-                .expect("StreamProtocol should start with a /");
-            let proto = [(stream, ProtocolSupport::Outbound)];
+            // The compressed protocol comes first so that it is preferred by nodes supporting it
+            let proto = [
+                network_context.compressed_req_response_protocol(),
+                network_context.req_response_protocol(),
+            ]
+            .map(|version_str| {
+                let stream = StreamProtocol::try_from_owned(version_str)
+                    .expect("StreamProtocol should start with a /");
+                (stream, ProtocolSupport::Outbound)
+            });
 
-            let codec = CborCodec::<Request, Response>::default()
-                .set_request_size_maximum(2 * MAX_PACKET_SIZE as u64);
+            let codec =
+                MessageCodec::default().set_request_size_maximum(2 * MAX_PACKET_SIZE as u64);
 
             request_response::Behaviour::with_codec(codec, proto, cfg)
         };
@@ -194,7 +195,7 @@ impl NetworkDriver {
         let swarm_config = libp2p::swarm::Config::with_tokio_executor();
         let swarm = Swarm::new(transport, behaviour, peer_id, swarm_config);
 
-        let task_handler = TaskHandler::new();
+        let task_handler = TaskHandler::new(peer_stats);
 
         Self {
             bootstrap_cache,
@@ -241,7 +242,7 @@ impl NetworkDriver {
     }
 
     /// Shorthand for request response behaviour mut
-    fn req(&mut self) -> &mut request_response::cbor::Behaviour<Request, Response> {
+    fn req(&mut self) -> &mut request_response::Behaviour<MessageCodec> {
         &mut self.swarm.behaviour_mut().request_response
     }
 
@@ -358,6 +359,87 @@ impl NetworkDriver {
                     },
                 );
             }
+            NetworkTask::GetRecordReq { addr, peer, resp } => {
+                let req = Request::Query(Query::GetReplicatedRecord {
+                    requester: NetworkAddress::from(*self.swarm.local_peer_id()),
+                    key: addr.clone(),
+                });
+
+                let req_id =
+                    self.req()
+                        .send_request_with_addresses(&peer.peer_id, req, peer.addrs.clone());
+
+                self.pending_tasks
+                    .insert_query(req_id, NetworkTask::GetRecordReq { addr, peer, resp });
+            }
+            NetworkTask::GetRecordsReq { addrs, peer, resp } => {
+                let req = Request::Query(Query::GetReplicatedRecords {
+                    holder: NetworkAddress::from(peer.peer_id),
+                    keys: addrs.clone(),
+                });
+
+                let req_id =
+                    self.req()
+                        .send_request_with_addresses(&peer.peer_id, req, peer.addrs.clone());
+
+                self.pending_tasks
+                    .insert_query(req_id, NetworkTask::GetRecordsReq { addrs, peer, resp });
+            }
+            NetworkTask::CheckRecordsExistReq { addrs, peer, resp } => {
+                let req = Request::Query(Query::CheckRecordsExist {
+                    holder: NetworkAddress::from(peer.peer_id),
+                    keys: addrs.clone(),
+                });
+
+                let req_id =
+                    self.req()
+                        .send_request_with_addresses(&peer.peer_id, req, peer.addrs.clone());
+
+                self.pending_tasks.insert_query(
+                    req_id,
+                    NetworkTask::CheckRecordsExistReq { addrs, peer, resp },
+                );
+            }
+            NetworkTask::ChunkExistenceReq { addr, peer, resp } => {
+                // The proof can't be verified without the chunk, only whether the peer has one
+                // matters here, so any nonce does
+                let req = Request::Query(Query::GetChunkExistenceProof {
+                    key: addr.clone(),
+                    nonce: rand::random(),
+                    difficulty: 1,
+                });
+
+                let req_id =
+                    self.req()
+                        .send_request_with_addresses(&peer.peer_id, req, peer.addrs.clone());
+
+                self.pending_tasks
+                    .insert_query(req_id, NetworkTask::ChunkExistenceReq { addr, peer, resp });
+            }
+            NetworkTask::GetLocalClosestPeers { addrs, n, resp } => {
+                let mut known_peers = vec![];
+                for kbucket in self.kad().kbuckets() {
+                    for entry in kbucket.iter() {
+                        known_peers.push(PeerInfo {
+                            peer_id: *entry.node.key.preimage(),
+                            addrs: entry.node.value.clone().into_vec(),
+                        });
+                    }
+                }
+                let closest = addrs
+                    .into_iter()
+                    .map(|addr| {
+                        let mut peers = known_peers.clone();
+                        peers
+                            .sort_by_key(|peer| addr.distance(&NetworkAddress::from(peer.peer_id)));
+                        peers.truncate(n);
+                        (addr, peers)
+                    })
+                    .collect();
+                if let Err(e) = resp.send(Ok(closest)) {
+                    error!("Error sending local closest peers response: {e:?}");
+                }
+            }
             NetworkTask::ConnectionsMade { resp } => {
                 // Send the current count of connections made
                 if let Err(e) = resp.send(Ok(self.connections_made)) {
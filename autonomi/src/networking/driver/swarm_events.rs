@@ -165,6 +165,7 @@ impl NetworkDriver {
             trace!("Ignore result for unknown query (possibly already completed): {request_id:?}");
             return Ok(());
         }
+        self.pending_tasks.record_response(&request_id);
 
         match response {
             Response::Query(QueryResponse::GetStoreQuote {
@@ -183,6 +184,26 @@ impl NetworkDriver {
                 self.pending_tasks
                     .update_put_record_req(request_id, result)?;
             }
+            Response::Query(QueryResponse::GetReplicatedRecord(result)) => {
+                self.pending_tasks
+                    .update_get_record_req(request_id, result)?;
+            }
+            Response::Query(QueryResponse::GetReplicatedRecords {
+                holder: _,
+                records,
+                remaining,
+            }) => {
+                self.pending_tasks
+                    .update_get_records_req(request_id, records, remaining)?;
+            }
+            Response::Query(QueryResponse::CheckRecordsExist { holder: _, results }) => {
+                self.pending_tasks
+                    .update_check_records_exist_req(request_id, results)?;
+            }
+            Response::Query(QueryResponse::GetChunkExistenceProof(proofs)) => {
+                self.pending_tasks
+                    .update_chunk_existence_req(request_id, proofs)?;
+            }
 
             _ => {
                 trace!("Other request response event: {response:?}");
@@ -263,6 +284,7 @@ impl NetworkDriver {
             return Ok(());
         }
 
+        self.pending_tasks.record_failure(&request_id);
         self.pending_tasks
             .terminate_query(request_id, peer, error)?;
 
@@ -6,16 +6,20 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::networking::interface::NetworkTask;
+use crate::networking::interface::{NetworkTask, RecordsAndRemaining};
+use crate::networking::peer_stats::{PeerStatsTracker, RequestKind};
 use crate::networking::utils::get_quorum_amount;
 use crate::networking::NetworkError;
 use crate::networking::OneShotTaskResult;
 use ant_evm::PaymentQuote;
+use ant_protocol::messages::ChunkProof;
 use ant_protocol::{NetworkAddress, PrettyPrintRecordKey};
+use bytes::Bytes;
 use libp2p::kad::{self, PeerInfo, QueryId, Quorum, Record};
 use libp2p::request_response::OutboundRequestId;
 use libp2p::PeerId;
 use std::collections::HashMap;
+use std::time::Instant;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -48,10 +52,18 @@ pub(crate) struct TaskHandler {
     >,
     get_record: HashMap<QueryId, (OneShotTaskResult<RecordAndHolders>, Quorum)>,
     get_record_accumulator: HashMap<QueryId, HashMap<PeerId, Record>>,
+    get_record_req: HashMap<OutboundRequestId, (OneShotTaskResult<Option<Record>>, NetworkAddress)>,
+    get_records_req: HashMap<OutboundRequestId, OneShotTaskResult<RecordsAndRemaining>>,
+    check_records_exist_req:
+        HashMap<OutboundRequestId, OneShotTaskResult<Vec<(NetworkAddress, bool)>>>,
+    chunk_existence_req: HashMap<OutboundRequestId, (OneShotTaskResult<bool>, NetworkAddress)>,
+    /// Peer, kind and start time of the pending requests, to time the peers with
+    request_starts: HashMap<OutboundRequestId, (PeerId, RequestKind, Instant)>,
+    peer_stats: PeerStatsTracker,
 }
 
 impl TaskHandler {
-    pub fn new() -> Self {
+    pub fn new(peer_stats: PeerStatsTracker) -> Self {
         Self {
             closest_peers: Default::default(),
             put_record_kad: Default::default(),
@@ -59,6 +71,12 @@ impl TaskHandler {
             get_cost: Default::default(),
             get_record: Default::default(),
             get_record_accumulator: Default::default(),
+            get_record_req: Default::default(),
+            get_records_req: Default::default(),
+            check_records_exist_req: Default::default(),
+            chunk_existence_req: Default::default(),
+            request_starts: Default::default(),
+            peer_stats,
         }
     }
 
@@ -69,7 +87,12 @@ impl TaskHandler {
     }
 
     pub fn contains_query(&self, id: &OutboundRequestId) -> bool {
-        self.get_cost.contains_key(id) || self.put_record_req.contains_key(id)
+        self.get_cost.contains_key(id)
+            || self.put_record_req.contains_key(id)
+            || self.get_record_req.contains_key(id)
+            || self.get_records_req.contains_key(id)
+            || self.check_records_exist_req.contains_key(id)
+            || self.chunk_existence_req.contains_key(id)
     }
 
     pub fn insert_task(&mut self, id: QueryId, task: NetworkTask) {
@@ -90,6 +113,18 @@ impl TaskHandler {
 
     pub fn insert_query(&mut self, id: OutboundRequestId, task: NetworkTask) {
         info!("New query: with OutboundRequestId({id}): {task:?}");
+        let timed = match &task {
+            NetworkTask::GetQuote { peer, .. } => Some((peer, RequestKind::Quote)),
+            NetworkTask::GetRecordReq { peer, .. }
+            | NetworkTask::GetRecordsReq { peer, .. } => Some((peer, RequestKind::Get)),
+            NetworkTask::CheckRecordsExistReq { peer, .. }
+            | NetworkTask::ChunkExistenceReq { peer, .. } => Some((peer, RequestKind::ExistenceCheck)),
+            NetworkTask::PutRecordReq { to, .. } => Some((to, RequestKind::Put)),
+            _ => None,
+        };
+        if let Some((peer, kind)) = timed {
+            self.request_starts.insert(id, (peer.peer_id, kind, Instant::now()));
+        }
         match task {
             NetworkTask::GetQuote {
                 resp,
@@ -102,10 +137,36 @@ impl TaskHandler {
             NetworkTask::PutRecordReq { resp, .. } => {
                 self.put_record_req.insert(id, resp);
             }
+            NetworkTask::GetRecordReq { resp, addr, .. } => {
+                self.get_record_req.insert(id, (resp, addr));
+            }
+            NetworkTask::GetRecordsReq { resp, .. } => {
+                self.get_records_req.insert(id, resp);
+            }
+            NetworkTask::CheckRecordsExistReq { resp, .. } => {
+                self.check_records_exist_req.insert(id, resp);
+            }
+            NetworkTask::ChunkExistenceReq { resp, addr, .. } => {
+                self.chunk_existence_req.insert(id, (resp, addr));
+            }
             _ => {}
         }
     }
 
+    /// Time the peer that answered the request, whatever the answer
+    pub fn record_response(&mut self, id: &OutboundRequestId) {
+        if let Some((peer, kind, start)) = self.request_starts.remove(id) {
+            self.peer_stats.record_success(peer, kind, start.elapsed());
+        }
+    }
+
+    /// Time the peer the request failed with, a timeout or a dropped connection
+    pub fn record_failure(&mut self, id: &OutboundRequestId) {
+        if let Some((peer, kind, start)) = self.request_starts.remove(id) {
+            self.peer_stats.record_failure(peer, kind, start.elapsed());
+        }
+    }
+
     pub fn update_closest_peers(
         &mut self,
         id: QueryId,
@@ -337,6 +398,118 @@ impl TaskHandler {
         Ok(())
     }
 
+    /// The peer doesn't hold the record if it answered that it couldn't find it
+    pub fn update_get_record_req(
+        &mut self,
+        id: OutboundRequestId,
+        result: Result<(NetworkAddress, Bytes), ant_protocol::error::Error>,
+    ) -> Result<(), TaskHandlerError> {
+        let (responder, addr) = self
+            .get_record_req
+            .remove(&id)
+            .ok_or(TaskHandlerError::UnknownQuery(format!(
+                "OutboundRequestId {id:?}"
+            )))?;
+
+        let res = match result {
+            Ok((_holder, value)) => Ok(Some(Record {
+                key: addr.to_record_key(),
+                value: value.to_vec(),
+                publisher: None,
+                expires: None,
+            })),
+            Err(ant_protocol::error::Error::ReplicatedRecordNotFound { .. }) => Ok(None),
+            Err(e) => Err(NetworkError::GetRecordError(e.to_string())),
+        };
+        trace!("OutboundRequestId({id}): got record {addr:?}: {:?}", res.as_ref().map(Option::is_some));
+        responder
+            .send(res)
+            .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
+        Ok(())
+    }
+
+    pub fn update_get_records_req(
+        &mut self,
+        id: OutboundRequestId,
+        records: Vec<(NetworkAddress, Option<Bytes>)>,
+        remaining: Vec<NetworkAddress>,
+    ) -> Result<(), TaskHandlerError> {
+        let responder = self
+            .get_records_req
+            .remove(&id)
+            .ok_or(TaskHandlerError::UnknownQuery(format!(
+                "OutboundRequestId {id:?}"
+            )))?;
+
+        trace!(
+            "OutboundRequestId({id}): got {} records with {} remaining",
+            records.len(),
+            remaining.len()
+        );
+        let records = records
+            .into_iter()
+            .map(|(addr, value)| {
+                let record = value.map(|value| Record {
+                    key: addr.to_record_key(),
+                    value: value.to_vec(),
+                    publisher: None,
+                    expires: None,
+                });
+                (addr, record)
+            })
+            .collect();
+
+        responder
+            .send(Ok((records, remaining)))
+            .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
+        Ok(())
+    }
+
+    pub fn update_check_records_exist_req(
+        &mut self,
+        id: OutboundRequestId,
+        results: Vec<(NetworkAddress, bool)>,
+    ) -> Result<(), TaskHandlerError> {
+        let responder = self
+            .check_records_exist_req
+            .remove(&id)
+            .ok_or(TaskHandlerError::UnknownQuery(format!(
+                "OutboundRequestId {id:?}"
+            )))?;
+
+        trace!(
+            "OutboundRequestId({id}): got existence of {} records",
+            results.len()
+        );
+        responder
+            .send(Ok(results))
+            .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
+        Ok(())
+    }
+
+    /// The peer holds the chunk if it answered with a proof for it
+    pub fn update_chunk_existence_req(
+        &mut self,
+        id: OutboundRequestId,
+        proofs: Vec<(NetworkAddress, Result<ChunkProof, ant_protocol::error::Error>)>,
+    ) -> Result<(), TaskHandlerError> {
+        let (responder, addr) = self
+            .chunk_existence_req
+            .remove(&id)
+            .ok_or(TaskHandlerError::UnknownQuery(format!(
+                "OutboundRequestId {id:?}"
+            )))?;
+
+        let exists = proofs
+            .iter()
+            .any(|(proof_addr, proof)| *proof_addr == addr && proof.is_ok());
+        trace!("OutboundRequestId({id}): chunk {addr:?} exists: {exists}");
+        responder
+            .send(Ok(exists))
+            .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
+        Ok(())
+    }
+
     pub fn update_get_quote(
         &mut self,
         id: OutboundRequestId,
@@ -411,6 +584,36 @@ impl TaskHandler {
                     .send(Err(NetworkError::PutRecordRejected(error.to_string())))
                     .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
             }
+        // Single record get case
+        } else if let Some((responder, _)) = self.get_record_req.remove(&id) {
+            trace!(
+                "OutboundRequestId({id}): get record got fatal error from peer {peer:?}: {error:?}"
+            );
+            responder
+                .send(Err(NetworkError::GetRecordError(error.to_string())))
+                .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
+        // Batched get or existence check case
+        } else if let Some(responder) = self.get_records_req.remove(&id) {
+            trace!(
+                "OutboundRequestId({id}): get records got fatal error from peer {peer:?}: {error:?}"
+            );
+            responder
+                .send(Err(batch_query_error(&error)))
+                .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
+        } else if let Some(responder) = self.check_records_exist_req.remove(&id) {
+            trace!(
+                "OutboundRequestId({id}): check records exist got fatal error from peer {peer:?}: {error:?}"
+            );
+            responder
+                .send(Err(batch_query_error(&error)))
+                .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
+        } else if let Some((responder, _)) = self.chunk_existence_req.remove(&id) {
+            trace!(
+                "OutboundRequestId({id}): chunk existence proof got fatal error from peer {peer:?}: {error:?}"
+            );
+            responder
+                .send(Err(NetworkError::GetRecordError(error.to_string())))
+                .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
         } else {
             trace!(
                 "OutboundRequestId({id}): trying to terminate unknown query, maybe it was already removed"
@@ -458,6 +661,18 @@ fn get_max_content_holders_count(holders: &HashMap<PeerId, Record>) -> Option<us
     content_counts.values().max().copied()
 }
 
+/// Old nodes can't decode batched queries and drop the stream, which we see as an EOF
+fn batch_query_error(error: &libp2p::autonat::OutboundFailure) -> NetworkError {
+    match error {
+        libp2p::autonat::OutboundFailure::Io(err)
+            if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+        {
+            NetworkError::IncompatibleNetworkProtocol
+        }
+        _ => NetworkError::GetRecordError(error.to_string()),
+    }
+}
+
 fn verify_quote(
     quote_res: Result<PaymentQuote, ant_protocol::error::Error>,
     peer_address: NetworkAddress,
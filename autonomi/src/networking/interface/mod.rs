@@ -15,6 +15,9 @@ use libp2p::{
 };
 use std::num::NonZeroUsize;
 
+/// Records handed back by a peer for a batched get, along with the keys it didn't get to
+pub(super) type RecordsAndRemaining = (Vec<(NetworkAddress, Option<Record>)>, Vec<NetworkAddress>);
+
 /// Task for the underlying network driver
 /// Sent by the [`crate::Network`], handled by the [`crate::driver::NetworkDriver`]
 ///
@@ -33,6 +36,14 @@ pub(super) enum NetworkTask {
         resp: OneShotTaskResult<Vec<PeerInfo>>,
         n: NonZeroUsize,
     },
+    /// Get the closest peers of each address that are in the routing table, without querying
+    /// the network
+    GetLocalClosestPeers {
+        addrs: Vec<NetworkAddress>,
+        n: usize,
+        #[debug(skip)]
+        resp: OneShotTaskResult<Vec<(NetworkAddress, Vec<PeerInfo>)>>,
+    },
     /// cf [`crate::driver::task_handler::TaskHandler::update_get_record`]
     GetRecord {
         addr: NetworkAddress,
@@ -67,6 +78,34 @@ pub(super) enum NetworkTask {
         #[debug(skip)]
         resp: OneShotTaskResult<Option<(PeerInfo, PaymentQuote)>>,
     },
+    /// cf [`crate::driver::task_handler::TaskHandler::update_get_record_req`]
+    GetRecordReq {
+        addr: NetworkAddress,
+        peer: PeerInfo,
+        #[debug(skip)]
+        resp: OneShotTaskResult<Option<Record>>,
+    },
+    /// cf [`crate::driver::task_handler::TaskHandler::update_get_records_req`]
+    GetRecordsReq {
+        addrs: Vec<NetworkAddress>,
+        peer: PeerInfo,
+        #[debug(skip)]
+        resp: OneShotTaskResult<RecordsAndRemaining>,
+    },
+    /// cf [`crate::driver::task_handler::TaskHandler::update_check_records_exist_req`]
+    CheckRecordsExistReq {
+        addrs: Vec<NetworkAddress>,
+        peer: PeerInfo,
+        #[debug(skip)]
+        resp: OneShotTaskResult<Vec<(NetworkAddress, bool)>>,
+    },
+    /// cf [`crate::driver::task_handler::TaskHandler::update_chunk_existence_req`]
+    ChunkExistenceReq {
+        addr: NetworkAddress,
+        peer: PeerInfo,
+        #[debug(skip)]
+        resp: OneShotTaskResult<bool>,
+    },
     /// Get information about the amount of connections made
     ConnectionsMade {
         #[debug(skip)]
@@ -0,0 +1,352 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::retries::RetriedOperation;
+use super::{Quorum, RetryStrategy};
+use prometheus_client::{
+    encoding::{EncodeLabelSet, EncodeLabelValue},
+    metrics::{
+        counter::Counter,
+        family::Family,
+        histogram::{Histogram, exponential_buckets},
+    },
+    registry::Registry,
+};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct StrategyLabels {
+    quorum: String,
+    retry: String,
+}
+
+impl StrategyLabels {
+    fn new(quorum: Quorum, retry: RetryStrategy) -> Self {
+        Self {
+            quorum: format!("{quorum:?}"),
+            retry: format!("{retry:?}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RetryLabels {
+    operation: RetriedOperation,
+    retry: String,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+enum Outcome {
+    Ok,
+    Err,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct OutcomeLabels {
+    outcome: Outcome,
+}
+
+impl From<bool> for OutcomeLabels {
+    fn from(ok: bool) -> Self {
+        Self {
+            outcome: if ok { Outcome::Ok } else { Outcome::Err },
+        }
+    }
+}
+
+/// The shared recorders of the client metrics, registered with
+/// [`crate::Client::with_metrics`].
+#[derive(Clone, Debug)]
+pub(crate) struct ClientMetricsRecorder {
+    // records
+    record_put_duration: Family<StrategyLabels, Histogram>,
+    record_put_err: Family<StrategyLabels, Counter>,
+    record_get_duration: Family<StrategyLabels, Histogram>,
+    record_get_err: Family<StrategyLabels, Counter>,
+    retries: Family<RetryLabels, Counter>,
+    split_records: Counter,
+    bytes_uploaded: Counter,
+    bytes_downloaded: Counter,
+
+    // quotes and payments
+    quotes: Family<OutcomeLabels, Counter>,
+    quote_duration: Histogram,
+    payments: Family<OutcomeLabels, Counter>,
+    payment_duration: Histogram,
+    records_paid: Counter,
+
+    // chunk cache
+    chunk_cache_hits: Counter,
+    chunk_cache_misses: Counter,
+}
+
+/// From 10ms to ~3 minutes.
+fn duration_histogram() -> Histogram {
+    Histogram::new(exponential_buckets(0.01, 2.0, 15))
+}
+
+impl ClientMetricsRecorder {
+    pub(crate) fn new(registry: &mut Registry) -> Self {
+        let sub_registry = registry.sub_registry_with_prefix("autonomi_client");
+
+        let record_put_duration: Family<StrategyLabels, Histogram> =
+            Family::new_with_constructor(duration_histogram);
+        sub_registry.register(
+            "record_put_duration_seconds",
+            "Time taken to put a record, retries included, per put strategy",
+            record_put_duration.clone(),
+        );
+        let record_put_err = Family::default();
+        sub_registry.register(
+            "record_put_err",
+            "Number of record PUTs that failed after all retries, per put strategy",
+            record_put_err.clone(),
+        );
+
+        let record_get_duration: Family<StrategyLabels, Histogram> =
+            Family::new_with_constructor(duration_histogram);
+        sub_registry.register(
+            "record_get_duration_seconds",
+            "Time taken to get a record, retries included, per get strategy",
+            record_get_duration.clone(),
+        );
+        let record_get_err = Family::default();
+        sub_registry.register(
+            "record_get_err",
+            "Number of record GETs that failed or found nothing after all retries, per get strategy",
+            record_get_err.clone(),
+        );
+
+        let retries = Family::default();
+        sub_registry.register(
+            "retries",
+            "Number of retried attempts, per operation and retry strategy",
+            retries.clone(),
+        );
+
+        let split_records = Counter::default();
+        sub_registry.register(
+            "split_records",
+            "Number of GETs that found conflicting versions of a record",
+            split_records.clone(),
+        );
+
+        let bytes_uploaded = Counter::default();
+        sub_registry.register(
+            "bytes_uploaded",
+            "Bytes of records stored on the network",
+            bytes_uploaded.clone(),
+        );
+        let bytes_downloaded = Counter::default();
+        sub_registry.register(
+            "bytes_downloaded",
+            "Bytes of records fetched from the network",
+            bytes_downloaded.clone(),
+        );
+
+        let quotes = Family::default();
+        sub_registry.register(
+            "quotes",
+            "Number of store quote requests for an address",
+            quotes.clone(),
+        );
+        let quote_duration = duration_histogram();
+        sub_registry.register(
+            "quote_duration_seconds",
+            "Time taken to get the store quotes of an address",
+            quote_duration.clone(),
+        );
+
+        let payments = Family::default();
+        sub_registry.register(
+            "payments",
+            "Number of payments made for a batch of quotes",
+            payments.clone(),
+        );
+        let payment_duration = duration_histogram();
+        sub_registry.register(
+            "payment_duration_seconds",
+            "Time taken to pay for a batch of quotes",
+            payment_duration.clone(),
+        );
+        let records_paid = Counter::default();
+        sub_registry.register(
+            "records_paid",
+            "Number of records paid for",
+            records_paid.clone(),
+        );
+
+        let chunk_cache_hits = Counter::default();
+        sub_registry.register(
+            "chunk_cache_hits",
+            "Number of chunks loaded from the local chunk cache",
+            chunk_cache_hits.clone(),
+        );
+        let chunk_cache_misses = Counter::default();
+        sub_registry.register(
+            "chunk_cache_misses",
+            "Number of chunks looked up in the local chunk cache without being found",
+            chunk_cache_misses.clone(),
+        );
+
+        Self {
+            record_put_duration,
+            record_put_err,
+            record_get_duration,
+            record_get_err,
+            retries,
+            split_records,
+            bytes_uploaded,
+            bytes_downloaded,
+            quotes,
+            quote_duration,
+            payments,
+            payment_duration,
+            records_paid,
+            chunk_cache_hits,
+            chunk_cache_misses,
+        }
+    }
+
+    pub(crate) fn record_put(
+        &self,
+        quorum: Quorum,
+        retry: RetryStrategy,
+        elapsed: Duration,
+        stored_bytes: Option<usize>,
+    ) {
+        let labels = StrategyLabels::new(quorum, retry);
+        self.record_put_duration
+            .get_or_create(&labels)
+            .observe(elapsed.as_secs_f64());
+        match stored_bytes {
+            Some(bytes) => {
+                let _ = self.bytes_uploaded.inc_by(bytes as u64);
+            }
+            None => {
+                let _ = self.record_put_err.get_or_create(&labels).inc();
+            }
+        }
+    }
+
+    pub(crate) fn record_get(
+        &self,
+        quorum: Quorum,
+        retry: RetryStrategy,
+        elapsed: Duration,
+        fetched_bytes: Option<usize>,
+    ) {
+        self.record_direct_get(quorum, retry, elapsed, fetched_bytes.is_some());
+        if let Some(bytes) = fetched_bytes {
+            self.record_downloaded_bytes(bytes);
+        }
+    }
+
+    /// Time a get sent to the holders directly, whose bytes are counted per request to a peer.
+    pub(crate) fn record_direct_get(
+        &self,
+        quorum: Quorum,
+        retry: RetryStrategy,
+        elapsed: Duration,
+        found: bool,
+    ) {
+        let labels = StrategyLabels::new(quorum, retry);
+        self.record_get_duration
+            .get_or_create(&labels)
+            .observe(elapsed.as_secs_f64());
+        if !found {
+            let _ = self.record_get_err.get_or_create(&labels).inc();
+        }
+    }
+
+    pub(crate) fn record_downloaded_bytes(&self, bytes: usize) {
+        let _ = self.bytes_downloaded.inc_by(bytes as u64);
+    }
+
+    pub(crate) fn record_retry(&self, operation: RetriedOperation, retry: RetryStrategy) {
+        let _ = self
+            .retries
+            .get_or_create(&RetryLabels {
+                operation,
+                retry: format!("{retry:?}"),
+            })
+            .inc();
+    }
+
+    pub(crate) fn record_split_record(&self) {
+        let _ = self.split_records.inc();
+    }
+
+    pub(crate) fn record_quote(&self, ok: bool, elapsed: Duration) {
+        let _ = self.quotes.get_or_create(&ok.into()).inc();
+        self.quote_duration.observe(elapsed.as_secs_f64());
+    }
+
+    pub(crate) fn record_payment(&self, records: Option<usize>, elapsed: Duration) {
+        let _ = self.payments.get_or_create(&records.is_some().into()).inc();
+        self.payment_duration.observe(elapsed.as_secs_f64());
+        if let Some(records) = records {
+            let _ = self.records_paid.inc_by(records as u64);
+        }
+    }
+
+    pub(crate) fn record_chunk_cache_lookup(&self, hit: bool) {
+        let counter = if hit {
+            &self.chunk_cache_hits
+        } else {
+            &self.chunk_cache_misses
+        };
+        let _ = counter.inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus_client::encoding::text::encode;
+
+    #[test]
+    fn metrics_are_encoded_with_their_labels() {
+        let mut registry = Registry::default();
+        let recorder = ClientMetricsRecorder::new(&mut registry);
+        recorder.record_put(
+            Quorum::Majority,
+            RetryStrategy::Balanced,
+            Duration::from_millis(300),
+            Some(1024),
+        );
+        recorder.record_retry(RetriedOperation::GetRecord, RetryStrategy::Quick);
+        recorder.record_direct_get(
+            Quorum::One,
+            RetryStrategy::Quick,
+            Duration::from_millis(50),
+            false,
+        );
+        recorder.record_chunk_cache_lookup(true);
+
+        let mut encoded = String::new();
+        encode(&mut encoded, &registry).expect("encoding never fails");
+        assert!(encoded.contains(
+            "autonomi_client_record_put_duration_seconds_count{quorum=\"Majority\",retry=\"Balanced\"} 1"
+        ));
+        assert!(encoded.contains("autonomi_client_bytes_uploaded_total 1024"));
+        assert!(
+            encoded.contains(
+                "autonomi_client_retries_total{operation=\"GetRecord\",retry=\"Quick\"} 1"
+            )
+        );
+        assert!(encoded.contains(
+            "autonomi_client_record_get_duration_seconds_count{quorum=\"One\",retry=\"Quick\"} 1"
+        ));
+        assert!(
+            encoded
+                .contains("autonomi_client_record_get_err_total{quorum=\"One\",retry=\"Quick\"} 1")
+        );
+        assert!(encoded.contains("autonomi_client_chunk_cache_hits_total 1"));
+    }
+}
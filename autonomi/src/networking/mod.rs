@@ -7,10 +7,14 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 // all modules are private to this networking module
+mod batch;
 pub(crate) mod common;
 mod config;
 mod driver;
 mod interface;
+#[cfg(feature = "open-metrics")]
+pub(crate) mod metrics;
+mod peer_stats;
 mod retries;
 mod utils;
 
@@ -22,15 +26,17 @@ pub(crate) use utils::multiaddr_is_global;
 // re-export the types our API exposes to avoid dependency version conflicts
 pub use ant_evm::PaymentQuote;
 pub use ant_protocol::NetworkAddress;
+pub use batch::RecordHolders;
 pub use config::{RetryStrategy, Strategy};
 pub use libp2p::kad::PeerInfo;
 pub use libp2p::{
     Multiaddr, PeerId,
     kad::{Quorum, Record},
 };
+pub use peer_stats::{PeerStats, RequestKind};
 
 // internal needs
-use ant_protocol::{CLOSE_GROUP_SIZE, PrettyPrintRecordKey};
+use ant_protocol::{CLOSE_GROUP_SIZE, PrettyPrintRecordKey, version::NetworkContext};
 use driver::NetworkDriver;
 use futures::stream::{FuturesUnordered, StreamExt};
 use interface::NetworkTask;
@@ -140,6 +146,16 @@ impl NetworkError {
         )
     }
 
+    /// Whether the peers failed to answer in time, a sign of an overloaded network or client
+    pub fn is_timeout(&self) -> bool {
+        matches!(
+            self,
+            NetworkError::GetClosestPeersTimeout
+                | NetworkError::PutRecordTimeout(_)
+                | NetworkError::GetRecordTimeout(_)
+        )
+    }
+
     /// When encountering these, the request should not be retried
     pub fn cannot_retry(&self) -> bool {
         matches!(self, NetworkError::OutdatedRecordRejected { .. }) || self.is_fatal()
@@ -150,15 +166,22 @@ impl NetworkError {
 #[derive(Debug, Clone)]
 pub struct Network {
     task_sender: Arc<tokio::sync::mpsc::Sender<NetworkTask>>,
+    peer_stats: peer_stats::PeerStatsTracker,
+    #[cfg(feature = "open-metrics")]
+    pub(crate) metrics_recorder: Option<metrics::ClientMetricsRecorder>,
 }
 
 impl Network {
     /// Create a new network client
     /// This will start the network driver in a background thread, which is a long-running task that runs until the [`Network`] is dropped
     /// The [`Network`] is cheaply cloneable, prefer cloning over creating new instances to avoid creating multiple network drivers
+    ///
+    /// The `network_context` selects the network to talk to. Networks created with different contexts
+    /// are independent, so one process can be connected to several networks at once.
     pub fn new(
         initial_contacts: Vec<Multiaddr>,
         bootstrap_cache_config: Option<BootstrapCacheConfig>,
+        network_context: NetworkContext,
     ) -> Result<Self, NoKnownPeers> {
         let (task_sender, task_receiver) = mpsc::channel(100);
         let bootstrap_cache_store = if let Some(config) = bootstrap_cache_config {
@@ -186,7 +209,17 @@ impl Network {
             None
         };
 
-        let mut driver = NetworkDriver::new(bootstrap_cache_store, task_receiver);
+        info!(
+            "Creating network for network id {}",
+            network_context.network_id()
+        );
+        let peer_stats = peer_stats::PeerStatsTracker::default();
+        let mut driver = NetworkDriver::new(
+            bootstrap_cache_store,
+            task_receiver,
+            &network_context,
+            peer_stats.clone(),
+        );
 
         // Bootstrap here so we can early detect a failure
         driver.connect_to_peers(initial_contacts)?;
@@ -198,6 +231,9 @@ impl Network {
 
         let network = Self {
             task_sender: Arc::new(task_sender),
+            peer_stats,
+            #[cfg(feature = "open-metrics")]
+            metrics_recorder: None,
         };
 
         Ok(network)
@@ -492,28 +528,53 @@ impl Network {
         let closest_peers = self.get_closest_peers_with_retries(addr.clone()).await?;
         let closest_peers_id = closest_peers.iter().map(|p| p.peer_id).collect::<Vec<_>>();
         debug!("Get quotes for {addr}: got closest peers: {closest_peers_id:?}");
+        // chronically slow peers are only asked for a quote, and so paid and uploaded to, when
+        // one of the others doesn't quote
+        let fast_peers = self.peer_stats.without_slow_peers(
+            RequestKind::Quote,
+            closest_peers.clone(),
+            minimum_quotes + 1,
+        );
+        let mut spare_peers = closest_peers
+            .into_iter()
+            .filter(|peer| !fast_peers.contains(peer))
+            .collect::<Vec<_>>()
+            .into_iter();
 
         // get all quotes
-        let mut tasks = FuturesUnordered::new();
-        for peer in closest_peers {
+        let ask = |peer: PeerInfo| {
             let addr_clone = addr.clone();
-            tasks.push(async move {
+            async move {
                 let res = self
                     .get_quote(addr_clone, peer.clone(), data_type, data_size)
                     .await;
                 (res, peer)
-            });
-        }
+            }
+        };
+        let mut tasks: FuturesUnordered<_> = fast_peers.into_iter().map(ask).collect();
 
         // count quotes and peers that claim there is no need to pay
         let mut quotes = vec![];
         let mut no_need_to_pay = vec![];
         let mut errors = vec![];
         while let Some((result, peer)) = tasks.next().await {
-            match result {
-                Ok(Some(quote)) => quotes.push(quote),
-                Ok(None) => no_need_to_pay.push(peer),
-                Err(e) => errors.push(e),
+            let quoted = match result {
+                Ok(Some(quote)) => {
+                    quotes.push(quote);
+                    true
+                }
+                Ok(None) => {
+                    no_need_to_pay.push(peer);
+                    false
+                }
+                Err(e) => {
+                    errors.push(e);
+                    false
+                }
+            };
+            // a peer that didn't quote is made up for by a spare one
+            if !quoted {
+                tasks.extend(spare_peers.next().map(ask));
             }
 
             // if we have enough quotes, return them
@@ -0,0 +1,550 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_protocol::{CLOSE_GROUP_SIZE, NetworkAddress};
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{Network, PeerId, PeerInfo, Record, Strategy};
+
+/// Weight of the latest request in the averages of a peer.
+const EWMA_ALPHA: f64 = 0.2;
+/// Peers beyond this are forgotten, starting with the least asked ones.
+const MAX_TRACKED_PEERS: usize = 2_000;
+/// Number of recent direct get latencies the hedging delay is taken from.
+const MAX_LATENCY_SAMPLES: usize = 256;
+/// A hedged request is sent once the first one has taken longer than this share of gets.
+const HEDGE_PERCENTILE: f64 = 0.9;
+/// Hedging delay until enough gets were timed.
+const DEFAULT_HEDGE_DELAY: Duration = Duration::from_secs(2);
+const MIN_HEDGE_DELAY: Duration = Duration::from_millis(100);
+const MAX_HEDGE_DELAY: Duration = Duration::from_secs(10);
+/// Same as above for batched gets, which carry many records and take longer.
+const DEFAULT_BATCH_HEDGE_DELAY: Duration = Duration::from_secs(10);
+const MAX_BATCH_HEDGE_DELAY: Duration = Duration::from_secs(30);
+/// A peer is only judged slow after this many requests.
+const MIN_REQUESTS_TO_JUDGE: u64 = 5;
+/// A peer is slow when it takes this many times as long as the typical peer.
+const SLOW_PEER_FACTOR: f64 = 4.0;
+/// A peer is slow when it answers less often than this.
+const MIN_HEALTHY_SUCCESS_RATE: f64 = 0.5;
+/// Peers found not to support direct gets are asked again after this, as they may have upgraded.
+const DIRECT_GETS_RECHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The kinds of requests peers are timed on. Each kind is timed on its own, as a chunk upload
+/// takes far longer than a quote, and peers are only compared on the same kind of requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    /// Store quote requests
+    Quote,
+    /// Direct record gets, single or batched
+    Get,
+    /// Record existence checks, batched or with chunk proofs
+    ExistenceCheck,
+    /// Record uploads
+    Put,
+}
+
+/// How fast and reliably a peer answered the requests of a kind sent to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeerStats {
+    /// Average time taken by the peer to answer, recent requests weigh more.
+    pub latency: Duration,
+    /// Average share of requests the peer answered, from 0 to 1, recent requests weigh more.
+    pub success_rate: f64,
+    /// Number of requests of the kind sent to the peer.
+    pub requests: u64,
+}
+
+impl PeerStats {
+    /// Expected time to get an answer from the peer, failures included, in seconds.
+    fn score(&self) -> f64 {
+        self.latency.as_secs_f64() / self.success_rate.max(0.05)
+    }
+}
+
+/// Keeps the [`PeerStats`] of the peers, shared by the network driver that times the requests
+/// and the [`Network`] that picks the peers to send them to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PeerStatsTracker {
+    inner: Arc<Mutex<TrackerState>>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    peers: HashMap<(PeerId, RequestKind), PeerStats>,
+    /// Recent direct get latencies, to derive the hedging delay from.
+    get_latencies: VecDeque<Duration>,
+    /// Recent batched get latencies, to derive the hedging delay of batches from.
+    batch_get_latencies: VecDeque<Duration>,
+    /// Peers that don't support direct gets, such as old nodes, with when they were found out.
+    without_direct_gets: HashMap<PeerId, Instant>,
+}
+
+impl PeerStatsTracker {
+    pub(crate) fn record_success(&self, peer: PeerId, kind: RequestKind, elapsed: Duration) {
+        self.record((peer, kind), elapsed, true);
+    }
+
+    pub(crate) fn record_failure(&self, peer: PeerId, kind: RequestKind, elapsed: Duration) {
+        self.record((peer, kind), elapsed, false);
+    }
+
+    fn record(&self, peer: (PeerId, RequestKind), elapsed: Duration, success: bool) {
+        let mut state = self.lock();
+        if !state.peers.contains_key(&peer) && state.peers.len() >= MAX_TRACKED_PEERS {
+            let least_asked = state
+                .peers
+                .iter()
+                .min_by_key(|(_, stats)| stats.requests)
+                .map(|(peer, _)| *peer);
+            if let Some(least_asked) = least_asked {
+                state.peers.remove(&least_asked);
+            }
+        }
+
+        let success_value = if success { 1.0 } else { 0.0 };
+        match state.peers.get_mut(&peer) {
+            Some(stats) => {
+                // A failure, such as a refused connection, never makes a peer look faster
+                if success || elapsed > stats.latency {
+                    stats.latency = ewma_duration(stats.latency, elapsed);
+                }
+                stats.success_rate = ewma(stats.success_rate, success_value);
+                stats.requests += 1;
+            }
+            None => {
+                let stats = PeerStats {
+                    latency: elapsed,
+                    success_rate: success_value,
+                    requests: 1,
+                };
+                state.peers.insert(peer, stats);
+            }
+        }
+    }
+
+    pub(crate) fn record_get_latency(&self, elapsed: Duration) {
+        push_sample(&mut self.lock().get_latencies, elapsed);
+    }
+
+    pub(crate) fn record_batch_get_latency(&self, elapsed: Duration) {
+        push_sample(&mut self.lock().batch_get_latencies, elapsed);
+    }
+
+    /// How long to wait for a holder before asking another one for the same record.
+    pub(crate) fn hedge_delay(&self) -> Duration {
+        hedge_delay_of(
+            &self.lock().get_latencies,
+            DEFAULT_HEDGE_DELAY,
+            MAX_HEDGE_DELAY,
+        )
+    }
+
+    /// How long to wait for a peer to hand over a batch of records before asking other holders
+    /// for them.
+    pub(crate) fn batch_hedge_delay(&self) -> Duration {
+        hedge_delay_of(
+            &self.lock().batch_get_latencies,
+            DEFAULT_BATCH_HEDGE_DELAY,
+            MAX_BATCH_HEDGE_DELAY,
+        )
+    }
+
+    pub(crate) fn all(&self) -> Vec<(PeerId, RequestKind, PeerStats)> {
+        self.lock()
+            .peers
+            .iter()
+            .map(|((peer, kind), stats)| (*peer, *kind, *stats))
+            .collect()
+    }
+
+    /// Sort the peers from the fastest to the slowest at the kind of requests. Peers we know
+    /// nothing about are assumed to be typical, and peers that are alike keep their order.
+    pub(crate) fn sort_by_speed(&self, kind: RequestKind, peers: &mut [PeerInfo]) {
+        let state = self.lock();
+        let typical = typical_score(&state.peers, kind);
+        let score = |peer: &PeerInfo| {
+            state
+                .peers
+                .get(&(peer.peer_id, kind))
+                .map_or(typical, PeerStats::score)
+        };
+        peers.sort_by(|a, b| score(a).total_cmp(&score(b)));
+    }
+
+    /// Leave out the peers that are chronically slow or unreliable at the kind of requests, as
+    /// long as at least `keep` peers remain. The order of the peers is kept.
+    pub(crate) fn without_slow_peers(
+        &self,
+        kind: RequestKind,
+        peers: Vec<PeerInfo>,
+        keep: usize,
+    ) -> Vec<PeerInfo> {
+        let state = self.lock();
+        let typical = typical_score(&state.peers, kind);
+        let (fast, slow): (Vec<_>, Vec<_>) = peers.into_iter().partition(|peer| {
+            state
+                .peers
+                .get(&(peer.peer_id, kind))
+                .is_none_or(|stats| !is_slow(stats, typical))
+        });
+        if !slow.is_empty() {
+            let slow_ids: Vec<_> = slow.iter().map(|peer| peer.peer_id).collect();
+            debug!("Deprioritising slow peers {slow_ids:?}");
+        }
+
+        let missing = keep.saturating_sub(fast.len());
+        let kept_slow: Vec<_> = slow.into_iter().take(missing).collect();
+        if kept_slow.is_empty() {
+            return fast;
+        }
+        // Slow peers that are needed after all keep their place
+        let kept_slow_ids: Vec<_> = kept_slow.iter().map(|peer| peer.peer_id).collect();
+        let mut kept: Vec<_> = fast.into_iter().chain(kept_slow).collect();
+        kept.sort_by_key(|peer| kept_slow_ids.contains(&peer.peer_id));
+        kept
+    }
+
+    /// Remember that the peer doesn't support direct gets, so it isn't asked for a while.
+    pub(crate) fn record_direct_gets_unsupported(&self, peer: PeerId) {
+        let mut state = self.lock();
+        if state.without_direct_gets.len() >= MAX_TRACKED_PEERS {
+            state
+                .without_direct_gets
+                .retain(|_, since| since.elapsed() < DIRECT_GETS_RECHECK_INTERVAL);
+        }
+        if state.without_direct_gets.len() < MAX_TRACKED_PEERS {
+            state.without_direct_gets.insert(peer, Instant::now());
+        }
+    }
+
+    /// Whether the peer isn't known not to support direct gets.
+    pub(crate) fn supports_direct_gets(&self, peer: &PeerId) -> bool {
+        self.lock()
+            .without_direct_gets
+            .get(peer)
+            .is_none_or(|since| since.elapsed() >= DIRECT_GETS_RECHECK_INTERVAL)
+    }
+
+    /// Leave out the peers known not to support direct gets. The order of the peers is kept.
+    pub(crate) fn with_direct_gets(&self, peers: Vec<PeerInfo>) -> Vec<PeerInfo> {
+        self.with_direct_gets_at(peers, Instant::now())
+    }
+
+    fn with_direct_gets_at(&self, peers: Vec<PeerInfo>, now: Instant) -> Vec<PeerInfo> {
+        let state = self.lock();
+        peers
+            .into_iter()
+            .filter(|peer| {
+                state
+                    .without_direct_gets
+                    .get(&peer.peer_id)
+                    .is_none_or(|since| now.duration_since(*since) >= DIRECT_GETS_RECHECK_INTERVAL)
+            })
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TrackerState> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn is_slow(stats: &PeerStats, typical: f64) -> bool {
+    stats.requests >= MIN_REQUESTS_TO_JUDGE
+        && (stats.success_rate < MIN_HEALTHY_SUCCESS_RATE
+            || stats.score() > typical * SLOW_PEER_FACTOR)
+}
+
+/// Median score of the peers at the kind of requests, 0 when there are none.
+fn typical_score(peers: &HashMap<(PeerId, RequestKind), PeerStats>, kind: RequestKind) -> f64 {
+    let mut scores: Vec<_> = peers
+        .iter()
+        .filter(|((_, peer_kind), _)| *peer_kind == kind)
+        .map(|(_, stats)| stats.score())
+        .collect();
+    if scores.is_empty() {
+        return 0.0;
+    }
+    scores.sort_by(f64::total_cmp);
+    scores[scores.len() / 2]
+}
+
+fn push_sample(samples: &mut VecDeque<Duration>, elapsed: Duration) {
+    if samples.len() >= MAX_LATENCY_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(elapsed);
+}
+
+/// A high percentile of the latencies, or the default until enough requests were timed.
+fn hedge_delay_of(samples: &VecDeque<Duration>, default: Duration, max: Duration) -> Duration {
+    if samples.len() < MIN_REQUESTS_TO_JUDGE as usize {
+        return default;
+    }
+    let mut latencies: Vec<_> = samples.iter().copied().collect();
+    latencies.sort();
+    let index = ((latencies.len() - 1) as f64 * HEDGE_PERCENTILE).round() as usize;
+    latencies[index].clamp(MIN_HEDGE_DELAY, max)
+}
+
+fn ewma(average: f64, value: f64) -> f64 {
+    average + EWMA_ALPHA * (value - average)
+}
+
+fn ewma_duration(average: Duration, value: Duration) -> Duration {
+    Duration::from_secs_f64(ewma(average.as_secs_f64(), value.as_secs_f64()))
+}
+
+impl Network {
+    /// The stats of the peers the client sent requests to, for debugging.
+    pub fn peer_stats(&self) -> Vec<(PeerId, RequestKind, PeerStats)> {
+        self.peer_stats.all()
+    }
+
+    /// Get a record from the fastest known holders in its close group, asking another holder
+    /// when the first one takes longer than most gets do, until the whole close group was asked.
+    /// Holders known not to support direct gets, such as old nodes, are asked for the single
+    /// record instead, so that the peers of the one lookup serve every holder.
+    /// Returns `None` when none of the close group hands the record over, callers are expected to
+    /// fall back to a regular get, which also reaches peers beyond the close group.
+    ///
+    /// The record comes from a single peer without any quorum, so this should only be used for
+    /// self-verifying content such as chunks. The get is timed with the strategy.
+    pub async fn get_record_from_fastest_holders(
+        &self,
+        addr: NetworkAddress,
+        strategy: &Strategy,
+    ) -> Option<Record> {
+        let started = Instant::now();
+        let record = self.get_record_from_close_group(addr).await;
+        self.record_direct_get(strategy, started.elapsed(), record.is_some());
+        record
+    }
+
+    async fn get_record_from_close_group(&self, addr: NetworkAddress) -> Option<Record> {
+        let mut holders = match self.get_closest_peers(addr.clone()).await {
+            Ok(peers) => peers,
+            Err(err) => {
+                debug!("Failed to get the closest peers of {addr:?} for a direct get: {err}");
+                return None;
+            }
+        };
+        holders.truncate(CLOSE_GROUP_SIZE);
+        self.peer_stats
+            .sort_by_speed(RequestKind::Get, &mut holders);
+        let hedge_delay = self.peer_stats.hedge_delay();
+
+        let mut holders = holders.into_iter();
+        let mut requests = FuturesUnordered::new();
+        let ask = |peer: PeerInfo| {
+            let addr = addr.clone();
+            async move {
+                let start = Instant::now();
+                let res = self.get_record_from_peer(addr, peer.clone()).await;
+                (peer, start.elapsed(), res)
+            }
+        };
+        requests.extend(holders.next().map(ask));
+
+        loop {
+            tokio::select! {
+                Some((peer, elapsed, res)) = requests.next() => {
+                    match res {
+                        Ok(Some(record)) => {
+                            self.peer_stats.record_get_latency(elapsed);
+                            return Some(record);
+                        }
+                        Ok(None) => debug!("Peer {:?} doesn't hold {addr:?}", peer.peer_id),
+                        Err(err) => debug!("Direct get of {addr:?} from peer {:?} failed: {err}", peer.peer_id),
+                    }
+                    // Ask the next holder straight away
+                    match holders.next() {
+                        Some(next) => requests.push(ask(next)),
+                        None if requests.is_empty() => return None,
+                        None => {}
+                    }
+                }
+                _ = tokio::time::sleep(hedge_delay), if holders.len() > 0 => {
+                    if let Some(next) = holders.next() {
+                        debug!("No answer for {addr:?} within {hedge_delay:?}, asking another holder");
+                        requests.push(ask(next));
+                    }
+                }
+                else => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer() -> PeerInfo {
+        PeerInfo {
+            peer_id: PeerId::random(),
+            addrs: vec![],
+        }
+    }
+
+    fn record_requests(
+        tracker: &PeerStatsTracker,
+        peer: &PeerInfo,
+        kind: RequestKind,
+        latency: Duration,
+    ) {
+        for _ in 0..MIN_REQUESTS_TO_JUDGE {
+            tracker.record_success(peer.peer_id, kind, latency);
+        }
+    }
+
+    #[test]
+    fn averages_follow_recent_requests() {
+        let tracker = PeerStatsTracker::default();
+        let peer = PeerId::random();
+        tracker.record_success(peer, RequestKind::Get, Duration::from_secs(1));
+        assert_eq!(
+            tracker.all(),
+            vec![(
+                peer,
+                RequestKind::Get,
+                PeerStats {
+                    latency: Duration::from_secs(1),
+                    success_rate: 1.0,
+                    requests: 1,
+                }
+            )]
+        );
+
+        tracker.record_failure(peer, RequestKind::Get, Duration::from_millis(10));
+        let (_, _, stats) = tracker.all()[0];
+        assert_eq!(stats.latency, Duration::from_secs(1));
+        assert!(stats.success_rate < 1.0);
+        assert_eq!(stats.requests, 2);
+    }
+
+    #[test]
+    fn peers_are_sorted_from_the_fastest() {
+        let tracker = PeerStatsTracker::default();
+        let (slow, fast, failing, unknown) = (peer(), peer(), peer(), peer());
+        record_requests(&tracker, &slow, RequestKind::Get, Duration::from_secs(2));
+        record_requests(
+            &tracker,
+            &fast,
+            RequestKind::Get,
+            Duration::from_millis(100),
+        );
+        for _ in 0..MIN_REQUESTS_TO_JUDGE {
+            tracker.record_failure(failing.peer_id, RequestKind::Get, Duration::from_secs(1));
+        }
+
+        let mut peers = vec![slow.clone(), failing.clone(), unknown.clone(), fast.clone()];
+        tracker.sort_by_speed(RequestKind::Get, &mut peers);
+
+        let order: Vec<_> = peers.iter().map(|peer| peer.peer_id).collect();
+        assert_eq!(
+            order,
+            vec![fast.peer_id, slow.peer_id, unknown.peer_id, failing.peer_id]
+        );
+    }
+
+    #[test]
+    fn slow_peers_are_left_out_while_enough_remain() {
+        let tracker = PeerStatsTracker::default();
+        let peers: Vec<_> = (0..4).map(|_| peer()).collect();
+        for peer in &peers[..3] {
+            record_requests(
+                &tracker,
+                peer,
+                RequestKind::Quote,
+                Duration::from_millis(100),
+            );
+        }
+        record_requests(
+            &tracker,
+            &peers[3],
+            RequestKind::Quote,
+            Duration::from_secs(5),
+        );
+        let slow = peers[3].clone();
+        let mut with_slow_first = vec![slow.clone()];
+        with_slow_first.extend_from_slice(&peers[..3]);
+
+        let kept = tracker.without_slow_peers(RequestKind::Quote, with_slow_first.clone(), 2);
+        assert_eq!(kept, peers[..3].to_vec());
+
+        let kept = tracker.without_slow_peers(RequestKind::Quote, with_slow_first, 4);
+        assert_eq!(kept.len(), 4);
+        assert_eq!(kept[3], slow);
+    }
+
+    #[test]
+    fn peers_are_only_compared_on_the_same_kind_of_requests() {
+        let tracker = PeerStatsTracker::default();
+        let peers: Vec<_> = (0..4).map(|_| peer()).collect();
+        for peer in &peers[..3] {
+            record_requests(
+                &tracker,
+                peer,
+                RequestKind::Quote,
+                Duration::from_millis(100),
+            );
+        }
+        // Uploading a large record takes far longer than any quote
+        record_requests(
+            &tracker,
+            &peers[0],
+            RequestKind::Put,
+            Duration::from_secs(5),
+        );
+        record_requests(
+            &tracker,
+            &peers[3],
+            RequestKind::Put,
+            Duration::from_secs(5),
+        );
+
+        let kept = tracker.without_slow_peers(RequestKind::Quote, peers.clone(), 1);
+        assert_eq!(kept, peers);
+        let kept = tracker.without_slow_peers(RequestKind::Put, peers.clone(), 1);
+        assert_eq!(kept, peers);
+    }
+
+    #[test]
+    fn peers_without_direct_gets_are_left_out_until_rechecked() {
+        let tracker = PeerStatsTracker::default();
+        let (old, new) = (peer(), peer());
+        tracker.record_direct_gets_unsupported(old.peer_id);
+
+        let peers = vec![old.clone(), new.clone()];
+        assert_eq!(tracker.with_direct_gets(peers.clone()), vec![new.clone()]);
+        // Only old nodes are left, so the caller falls back to a regular get straight away
+        assert!(tracker.with_direct_gets(vec![old.clone()]).is_empty());
+
+        let later = Instant::now() + DIRECT_GETS_RECHECK_INTERVAL;
+        assert_eq!(tracker.with_direct_gets_at(peers.clone(), later), peers);
+    }
+
+    #[test]
+    fn hedge_delay_is_a_high_percentile_of_gets() {
+        let tracker = PeerStatsTracker::default();
+        assert_eq!(tracker.hedge_delay(), DEFAULT_HEDGE_DELAY);
+
+        for millis in 1..=100 {
+            tracker.record_get_latency(Duration::from_millis(millis * 10));
+        }
+        assert_eq!(tracker.hedge_delay(), Duration::from_millis(900));
+        // Batched gets are timed apart
+        assert_eq!(tracker.batch_hedge_delay(), DEFAULT_BATCH_HEDGE_DELAY);
+    }
+}
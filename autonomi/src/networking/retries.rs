@@ -11,8 +11,24 @@ use ant_protocol::{NetworkAddress, PrettyPrintRecordKey};
 
 use super::{Network, RetryStrategy};
 use super::{NetworkError, PeerInfo, Record, Strategy};
+use std::time::Duration;
+#[cfg(feature = "open-metrics")]
+use std::time::Instant;
 use tokio::time::sleep;
 
+/// Operations done with a [`RetryStrategy`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "open-metrics",
+    derive(prometheus_client::encoding::EncodeLabelValue)
+)]
+pub(crate) enum RetriedOperation {
+    PutRecord,
+    GetRecord,
+    GetQuotes,
+    GetClosestPeers,
+}
+
 impl Network {
     /// Put a record to the network with retries
     ///
@@ -22,6 +38,27 @@ impl Network {
         record: Record,
         to: Vec<PeerInfo>,
         strategy: &Strategy,
+    ) -> Result<(), NetworkError> {
+        #[cfg(feature = "open-metrics")]
+        let (started, size) = (Instant::now(), record.value.len());
+        let res = self.put_record_retrying(record, to, strategy).await;
+        #[cfg(feature = "open-metrics")]
+        if let Some(recorder) = &self.metrics_recorder {
+            recorder.record_put(
+                strategy.put_quorum,
+                strategy.put_retry,
+                started.elapsed(),
+                res.is_ok().then_some(size),
+            );
+        }
+        res
+    }
+
+    async fn put_record_retrying(
+        &self,
+        record: Record,
+        to: Vec<PeerInfo>,
+        strategy: &Strategy,
     ) -> Result<(), NetworkError> {
         let addr = PrettyPrintRecordKey::from(&record.key).into_owned();
         let mut errors = vec![];
@@ -42,7 +79,10 @@ impl Network {
                     warn!("Put record failed at {addr}: {err:?}, retrying in {duration:?}");
                     errors.push(err.clone());
                     match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
+                        Some(retry_delay) => {
+                            self.record_retry(RetriedOperation::PutRecord, strategy.put_retry);
+                            sleep(retry_delay).await
+                        }
                         None => return Err(err),
                     }
                 }
@@ -56,6 +96,33 @@ impl Network {
         &self,
         addr: NetworkAddress,
         strategy: &Strategy,
+    ) -> Result<Option<Record>, NetworkError> {
+        #[cfg(feature = "open-metrics")]
+        let started = Instant::now();
+        let res = self.get_record_retrying(addr, strategy).await;
+        #[cfg(feature = "open-metrics")]
+        if let Some(recorder) = &self.metrics_recorder {
+            let fetched = match &res {
+                Ok(Some(record)) => Some(record.value.len()),
+                _ => None,
+            };
+            recorder.record_get(
+                strategy.get_quorum,
+                strategy.get_retry,
+                started.elapsed(),
+                fetched,
+            );
+            if matches!(res, Err(NetworkError::SplitRecord(_))) {
+                recorder.record_split_record();
+            }
+        }
+        res
+    }
+
+    async fn get_record_retrying(
+        &self,
+        addr: NetworkAddress,
+        strategy: &Strategy,
     ) -> Result<Option<Record>, NetworkError> {
         let mut errors = vec![];
         let quorum = strategy.get_quorum;
@@ -75,7 +142,10 @@ impl Network {
                 Ok(None) => {
                     warn!("Record not found at {addr}, retrying in {duration:?}");
                     match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
+                        Some(retry_delay) => {
+                            self.record_retry(RetriedOperation::GetRecord, strategy.get_retry);
+                            sleep(retry_delay).await
+                        }
                         None => return Ok(None),
                     }
                 }
@@ -84,7 +154,10 @@ impl Network {
                     warn!("Get record failed at {addr}: {err:?}, retrying in {duration:?}");
                     errors.push(err.clone());
                     match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
+                        Some(retry_delay) => {
+                            self.record_retry(RetriedOperation::GetRecord, strategy.get_retry);
+                            sleep(retry_delay).await
+                        }
                         None => return Err(err),
                     }
                 }
@@ -114,7 +187,10 @@ impl Network {
                     warn!("Get quotes failed at {addr}: {err:?}, retrying in {duration:?}");
                     errors.push(err.clone());
                     match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
+                        Some(retry_delay) => {
+                            self.record_retry(RetriedOperation::GetQuotes, RetryStrategy::Once);
+                            sleep(retry_delay).await
+                        }
                         None => return Err(err),
                     }
                 }
@@ -142,7 +218,13 @@ impl Network {
                     warn!("Get closest peers failed at {addr}: {err:?}, retrying in {duration:?}");
                     errors.push(err.clone());
                     match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
+                        Some(retry_delay) => {
+                            self.record_retry(
+                                RetriedOperation::GetClosestPeers,
+                                RetryStrategy::Once,
+                            );
+                            sleep(retry_delay).await
+                        }
                         None => return Err(err),
                     }
                 }
@@ -150,4 +232,23 @@ impl Network {
         }
         Err(NetworkError::InvalidRetryStrategy)
     }
+
+    /// Also records the retry if metrics are enabled, see [`crate::Client::with_metrics`].
+    #[cfg_attr(not(feature = "open-metrics"), allow(unused_variables))]
+    fn record_retry(&self, operation: RetriedOperation, retry: RetryStrategy) {
+        #[cfg(feature = "open-metrics")]
+        if let Some(recorder) = &self.metrics_recorder {
+            recorder.record_retry(operation, retry);
+        }
+    }
+
+    /// Records how long a get sent to the holders directly took for the strategy, and whether
+    /// it found the record, if metrics are enabled, see [`crate::Client::with_metrics`].
+    #[cfg_attr(not(feature = "open-metrics"), allow(unused_variables))]
+    pub(super) fn record_direct_get(&self, strategy: &Strategy, elapsed: Duration, found: bool) {
+        #[cfg(feature = "open-metrics")]
+        if let Some(recorder) = &self.metrics_recorder {
+            recorder.record_direct_get(strategy.get_quorum, strategy.get_retry, elapsed, found);
+        }
+    }
 }
@@ -0,0 +1,386 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Error;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::ops::Range;
+use std::path::Path;
+
+/// Size of the original data in each compressed frame.
+/// Frames are compressed independently, so that a range of the data can be read by fetching and
+/// decompressing only the frames it overlaps.
+pub(crate) const COMPRESSION_FRAME_SIZE: usize = 1024 * 1024;
+/// Largest frame accepted in a data map, a frame is decompressed in memory at once.
+const MAX_COMPRESSION_FRAME_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Codec used to compress data before self-encrypting it.
+///
+/// Self-encryption compresses each chunk on its own, but chunks are cut from the original data,
+/// so that doesn't reduce the number of chunks to pay for. Compressing the data first does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// zstd with the given compression level.
+    Zstd { level: i32 },
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self::Zstd { level: 3 }
+    }
+}
+
+/// How data was compressed before being self-encrypted, recorded next to its data map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressedFrames {
+    compression: Compression,
+    /// Size of the original data in each frame, the last one may be shorter
+    frame_size: u64,
+    /// Size of the original data
+    original_size: u64,
+    /// Compressed size of each frame
+    frame_sizes: Vec<u32>,
+}
+
+impl CompressedFrames {
+    /// Check that the frames are consistent, as they come from data maps fetched from the network
+    /// and the other methods rely on it.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.frame_size == 0 || self.frame_size > MAX_COMPRESSION_FRAME_SIZE {
+            return Err(invalid_data(format!(
+                "Invalid compression frame size {}",
+                self.frame_size
+            )));
+        }
+        let expected_frames = self.original_size.div_ceil(self.frame_size);
+        if self.frame_sizes.len() as u64 != expected_frames {
+            return Err(invalid_data(format!(
+                "{} compressed frames for {} bytes in frames of {}, expected {expected_frames}",
+                self.frame_sizes.len(),
+                self.original_size,
+                self.frame_size
+            )));
+        }
+        if usize::try_from(self.original_size).is_err() {
+            return Err(invalid_data(format!(
+                "Original size {} does not fit in memory addresses",
+                self.original_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// The codec the frames were compressed with.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Size of the data before compression.
+    pub fn original_size(&self) -> usize {
+        self.original_size as usize
+    }
+
+    /// Size of the data after compression, which is what got self-encrypted.
+    pub fn compressed_size(&self) -> usize {
+        self.frame_sizes.iter().map(|size| *size as usize).sum()
+    }
+
+    pub(crate) fn frame_count(&self) -> usize {
+        self.frame_sizes.len()
+    }
+
+    /// Position of the first byte of a frame in the original data.
+    pub(crate) fn frame_start(&self, frame: usize) -> usize {
+        frame * self.frame_size as usize
+    }
+
+    /// The frames overlapping `len` bytes of the original data from `start`.
+    pub(crate) fn frames_in_range(&self, start: usize, len: usize) -> Range<usize> {
+        let end = start.saturating_add(len).min(self.original_size());
+        if start >= end {
+            return 0..0;
+        }
+        let frame_size = self.frame_size as usize;
+        start / frame_size..end.div_ceil(frame_size)
+    }
+
+    /// The frames from `first` on whose compressed size adds up to at most `max_size`.
+    /// Holds at least one frame, unless there are no frames left.
+    pub(crate) fn frames_up_to_size(&self, first: usize, max_size: usize) -> Range<usize> {
+        let mut end = first;
+        let mut size = 0;
+        while let Some(frame_size) = self.frame_sizes.get(end) {
+            size += *frame_size as usize;
+            if size > max_size && end > first {
+                break;
+            }
+            end += 1;
+        }
+        first..end
+    }
+
+    /// Where the given frames are in the compressed data.
+    pub(crate) fn compressed_range(&self, frames: Range<usize>) -> Range<usize> {
+        let sizes = |frames: Range<usize>| -> usize {
+            self.frame_sizes[frames]
+                .iter()
+                .map(|size| *size as usize)
+                .sum()
+        };
+        let start = sizes(0..frames.start);
+        start..start + sizes(frames)
+    }
+
+    /// Decompress the given frames out of their compressed bytes.
+    pub(crate) fn decompress(
+        &self,
+        compressed: &[u8],
+        frames: Range<usize>,
+    ) -> Result<Bytes, Error> {
+        let mut data = Vec::with_capacity(frames.len() * self.frame_size as usize);
+        let mut offset = 0;
+        for frame in frames {
+            let size = self.frame_sizes[frame] as usize;
+            let Some(frame_bytes) = compressed.get(offset..offset + size) else {
+                return Err(invalid_data(format!(
+                    "Compressed frame {frame} is out of the {} bytes provided",
+                    compressed.len()
+                )));
+            };
+            offset += size;
+
+            let expected =
+                (self.original_size() - self.frame_start(frame)).min(self.frame_size as usize);
+            let decompressed = match self.compression {
+                // The capacity bounds how much a corrupted frame can expand
+                Compression::Zstd { .. } => {
+                    zstd::bulk::decompress(frame_bytes, expected).map_err(Error::Compression)?
+                }
+            };
+            if decompressed.len() != expected {
+                return Err(invalid_data(format!(
+                    "Frame {frame} decompressed to {} bytes instead of {expected}",
+                    decompressed.len()
+                )));
+            }
+            data.extend_from_slice(&decompressed);
+        }
+        Ok(Bytes::from(data))
+    }
+
+    /// Decompress all the data.
+    pub(crate) fn decompress_all(&self, compressed: &[u8]) -> Result<Bytes, Error> {
+        self.decompress(compressed, 0..self.frame_count())
+    }
+}
+
+/// Compress the data frame by frame.
+/// Returns `None` if that doesn't make the data smaller, it's then better stored as is.
+pub(crate) fn compress(
+    data: &[u8],
+    compression: Compression,
+) -> Result<Option<(Bytes, CompressedFrames)>, Error> {
+    let mut compressed = Vec::new();
+    let mut frame_sizes = vec![];
+    for frame in data.chunks(COMPRESSION_FRAME_SIZE) {
+        let compressed_frame = compress_frame(frame, compression)?;
+        frame_sizes.push(compressed_frame.len() as u32);
+        compressed.extend_from_slice(&compressed_frame);
+        if compressed.len() >= data.len() {
+            return Ok(None);
+        }
+    }
+
+    let frames = CompressedFrames {
+        compression,
+        frame_size: COMPRESSION_FRAME_SIZE as u64,
+        original_size: data.len() as u64,
+        frame_sizes,
+    };
+    Ok(Some((Bytes::from(compressed), frames)))
+}
+
+/// Compress a file frame by frame without holding more than a frame in memory, only to learn
+/// the size of the frames.
+/// Returns `None` if compression doesn't make the file smaller.
+pub(crate) fn scan_compressed_file(
+    path: &Path,
+    compression: Compression,
+) -> Result<Option<CompressedFrames>, Error> {
+    let mut original_size = 0;
+    let mut compressed_size = 0;
+    let mut frame_sizes = vec![];
+    for frame in file_frames(path)? {
+        let frame = frame.map_err(Error::Compression)?;
+        let compressed_frame = compress_frame(&frame, compression)?;
+        original_size += frame.len();
+        compressed_size += compressed_frame.len();
+        frame_sizes.push(compressed_frame.len() as u32);
+    }
+    if compressed_size >= original_size {
+        return Ok(None);
+    }
+
+    Ok(Some(CompressedFrames {
+        compression,
+        frame_size: COMPRESSION_FRAME_SIZE as u64,
+        original_size: original_size as u64,
+        frame_sizes,
+    }))
+}
+
+/// Compress a file frame by frame, yielding the compressed frames as they are ready.
+pub(crate) fn compressed_file_frames(
+    path: &Path,
+    compression: Compression,
+) -> Result<impl Iterator<Item = Result<Vec<u8>, Error>>, Error> {
+    Ok(file_frames(path)?.map(move |frame| {
+        let frame = frame.map_err(Error::Compression)?;
+        compress_frame(&frame, compression)
+    }))
+}
+
+fn file_frames(path: &Path) -> Result<impl Iterator<Item = std::io::Result<Vec<u8>>>, Error> {
+    let mut file = File::open(path).map_err(Error::Compression)?;
+    let mut failed = false;
+    Ok(std::iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        let mut frame = Vec::with_capacity(COMPRESSION_FRAME_SIZE);
+        match (&mut file)
+            .take(COMPRESSION_FRAME_SIZE as u64)
+            .read_to_end(&mut frame)
+        {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(frame)),
+            Err(err) => {
+                failed = true;
+                Some(Err(err))
+            }
+        }
+    }))
+}
+
+fn compress_frame(frame: &[u8], compression: Compression) -> Result<Vec<u8>, Error> {
+    match compression {
+        Compression::Zstd { level } => {
+            zstd::bulk::compress(frame, level).map_err(Error::Compression)
+        }
+    }
+}
+
+fn invalid_data(msg: String) -> Error {
+    Error::Compression(std::io::Error::new(std::io::ErrorKind::InvalidData, msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(len: usize) -> Vec<u8> {
+        b"timestamp=2025-01-01 level=INFO msg=\"request served\"\n"
+            .iter()
+            .cycle()
+            .take(len)
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn compressed_frames_roundtrip() {
+        let data = text(3 * COMPRESSION_FRAME_SIZE + 100);
+        let (compressed, frames) = compress(&data, Compression::default())
+            .unwrap()
+            .expect("text should compress");
+
+        assert_eq!(frames.frame_count(), 4);
+        assert_eq!(frames.original_size(), data.len());
+        assert_eq!(frames.compressed_size(), compressed.len());
+        assert_eq!(frames.decompress_all(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn frames_can_be_decompressed_on_their_own() {
+        let data = text(3 * COMPRESSION_FRAME_SIZE + 100);
+        let (compressed, frames) = compress(&data, Compression::default()).unwrap().unwrap();
+
+        let start = COMPRESSION_FRAME_SIZE + 10;
+        let range = frames.frames_in_range(start, COMPRESSION_FRAME_SIZE);
+        assert_eq!(range, 1..3);
+
+        let compressed_range = frames.compressed_range(range.clone());
+        let decompressed = frames
+            .decompress(&compressed[compressed_range], range.clone())
+            .unwrap();
+        let offset = start - frames.frame_start(range.start);
+        assert_eq!(
+            decompressed[offset..offset + COMPRESSION_FRAME_SIZE],
+            data[start..start + COMPRESSION_FRAME_SIZE]
+        );
+    }
+
+    #[test]
+    fn frames_in_range_stay_within_the_data() {
+        let data = text(COMPRESSION_FRAME_SIZE + 100);
+        let (_, frames) = compress(&data, Compression::default()).unwrap().unwrap();
+
+        assert_eq!(frames.frames_in_range(0, 0), 0..0);
+        assert_eq!(frames.frames_in_range(data.len(), 10), 0..0);
+        assert_eq!(frames.frames_in_range(10, usize::MAX), 0..2);
+        assert_eq!(frames.frames_up_to_size(0, 0), 0..1);
+        assert_eq!(frames.frames_up_to_size(0, usize::MAX), 0..2);
+        assert_eq!(frames.frames_up_to_size(2, usize::MAX), 2..2);
+    }
+
+    #[test]
+    fn malformed_frames_are_rejected() {
+        let data = text(2 * COMPRESSION_FRAME_SIZE + 100);
+        let (_, frames) = compress(&data, Compression::default()).unwrap().unwrap();
+        assert!(frames.validate().is_ok());
+
+        let zero_frame_size = CompressedFrames {
+            frame_size: 0,
+            ..frames.clone()
+        };
+        assert!(zero_frame_size.validate().is_err());
+
+        let huge_frame_size = CompressedFrames {
+            frame_size: u64::MAX,
+            ..frames.clone()
+        };
+        assert!(huge_frame_size.validate().is_err());
+
+        let missing_frame = CompressedFrames {
+            frame_sizes: frames.frame_sizes[..2].to_vec(),
+            ..frames.clone()
+        };
+        assert!(missing_frame.validate().is_err());
+
+        let larger_original = CompressedFrames {
+            original_size: 10 * COMPRESSION_FRAME_SIZE as u64,
+            ..frames.clone()
+        };
+        assert!(larger_original.validate().is_err());
+
+        let empty = CompressedFrames {
+            original_size: 0,
+            frame_sizes: vec![],
+            ..frames
+        };
+        assert!(empty.validate().is_ok());
+    }
+
+    #[test]
+    fn incompressible_data_is_left_alone() {
+        let data: Vec<u8> = (0..100_000).map(|_| rand::random()).collect();
+        assert!(compress(&data, Compression::default()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{CompressedFrames, Error, ParityShards};
+use bytes::Bytes;
+use self_encryption::DataMap;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of data map chunks holding a [`WrappedDataMap`] instead of a bare data map.
+const WRAPPED_DATA_MAP_MAGIC: &[u8] = b"ANT-WDM";
+
+/// Largest serialised parity kept in the data map chunk, leaving room for the data map and the
+/// compressed frames.
+const MAX_INLINE_PARITY_SIZE: usize = 1024 * 1024;
+
+/// The parity chunks of some data, as recorded in its data map chunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ParityRecord {
+    /// Kept next to the data map when small enough
+    Inline(ParityShards),
+    /// Self-encrypted in chunks of its own otherwise, which the data map of is kept instead
+    Chunked(DataMap),
+}
+
+/// A data map along with how the data was encoded around its self-encryption.
+///
+/// Data map chunks of such data hold the serialised wrapper after [`WRAPPED_DATA_MAP_MAGIC`], so
+/// that they can't be mistaken for the bare data maps that plain data still uses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum WrappedDataMap {
+    V1 {
+        data_map: DataMap,
+        frames: CompressedFrames,
+    },
+    V2 {
+        data_map: DataMap,
+        frames: Option<CompressedFrames>,
+        parity: Option<ParityRecord>,
+    },
+}
+
+impl WrappedDataMap {
+    pub(crate) fn to_bytes(&self) -> Result<Bytes, rmp_serde::encode::Error> {
+        let mut bytes = WRAPPED_DATA_MAP_MAGIC.to_vec();
+        rmp_serde::encode::write(&mut bytes, self)?;
+        Ok(Bytes::from(bytes))
+    }
+
+    /// Returns `None` if the bytes don't hold a wrapped data map.
+    /// The compressed frames are validated, as the bytes usually come from the network.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<Result<Self, rmp_serde::decode::Error>> {
+        let wrapped = bytes.strip_prefix(WRAPPED_DATA_MAP_MAGIC)?;
+        let wrapped: Self = match rmp_serde::from_slice(wrapped) {
+            Ok(wrapped) => wrapped,
+            Err(err) => return Some(Err(err)),
+        };
+        let frames = match &wrapped {
+            Self::V1 { frames, .. } => Some(frames),
+            Self::V2 { frames, .. } => frames.as_ref(),
+        };
+        if let Some(Err(err)) = frames.map(CompressedFrames::validate) {
+            return Some(Err(rmp_serde::decode::Error::Uncategorized(
+                err.to_string(),
+            )));
+        }
+        Some(Ok(wrapped))
+    }
+
+    pub(crate) fn into_parts(self) -> (DataMap, Option<CompressedFrames>, Option<ParityRecord>) {
+        match self {
+            Self::V1 { data_map, frames } => (data_map, Some(frames), None),
+            Self::V2 {
+                data_map,
+                frames,
+                parity,
+            } => (data_map, frames, parity),
+        }
+    }
+}
+
+/// The content of the data map chunk of some data, along with the chunks holding its parity
+/// record if it's too large to be kept in the data map chunk.
+/// Plain data gets a bare data map, so that its data map chunk stays readable by older clients.
+pub(crate) fn data_map_chunk_content(
+    data_map: DataMap,
+    frames: Option<CompressedFrames>,
+    parity: Option<ParityShards>,
+) -> Result<(Bytes, Vec<Bytes>), Error> {
+    pack_data_map_chunk(data_map, frames, parity, MAX_INLINE_PARITY_SIZE)
+}
+
+fn pack_data_map_chunk(
+    data_map: DataMap,
+    frames: Option<CompressedFrames>,
+    parity: Option<ParityShards>,
+    max_inline_parity_size: usize,
+) -> Result<(Bytes, Vec<Bytes>), Error> {
+    if frames.is_none() && parity.is_none() {
+        return Ok((Bytes::from(rmp_serde::to_vec(&data_map)?), vec![]));
+    }
+
+    let mut parity_chunks = vec![];
+    let parity = match parity {
+        Some(parity) => {
+            let bytes = Bytes::from(rmp_serde::to_vec(&parity)?);
+            if bytes.len() <= max_inline_parity_size {
+                Some(ParityRecord::Inline(parity))
+            } else {
+                debug!(
+                    "Parity record of {} bytes is too large for the data map chunk, storing it in chunks",
+                    bytes.len()
+                );
+                let (parity_map, chunks) = self_encryption::encrypt(bytes)?;
+                parity_chunks.extend(chunks.into_iter().map(|chunk| chunk.content));
+                Some(ParityRecord::Chunked(parity_map))
+            }
+        }
+        None => None,
+    };
+    let wrapped = WrappedDataMap::V2 {
+        data_map,
+        frames,
+        parity,
+    };
+    Ok((wrapped.to_bytes()?, parity_chunks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::self_encryption::resilience::{ParityEncoder, Resilience};
+    use crate::self_encryption::{Compression, compression::compress};
+    use xor_name::XorName;
+
+    #[test]
+    fn wrapped_data_map_is_told_apart_from_bare_ones() {
+        let data: Vec<u8> = b"level=INFO msg=\"request served\"\n"
+            .iter()
+            .cycle()
+            .take(100_000)
+            .copied()
+            .collect();
+        let (compressed, frames) = compress(&data, Compression::default()).unwrap().unwrap();
+        let (data_map, _) = self_encryption::encrypt(compressed).unwrap();
+
+        let (wrapped, parity_chunks) =
+            data_map_chunk_content(data_map.clone(), Some(frames.clone()), None).unwrap();
+        assert!(parity_chunks.is_empty());
+        let (unwrapped_map, unwrapped_frames, parity) = WrappedDataMap::from_bytes(&wrapped)
+            .expect("should be recognised as wrapped")
+            .unwrap()
+            .into_parts();
+        assert_eq!(unwrapped_map, data_map);
+        assert_eq!(unwrapped_frames, Some(frames));
+        assert_eq!(parity, None);
+
+        let (bare, _) = data_map_chunk_content(data_map.clone(), None, None).unwrap();
+        assert!(WrappedDataMap::from_bytes(&bare).is_none());
+        assert_eq!(rmp_serde::from_slice::<DataMap>(&bare).unwrap(), data_map);
+        assert!(rmp_serde::from_slice::<DataMap>(&wrapped).is_err());
+    }
+
+    #[test]
+    fn large_parity_is_stored_in_chunks() {
+        let mut encoder = ParityEncoder::new(Resilience::HIGH).unwrap();
+        for i in 0..40u8 {
+            let _ = encoder.push(Bytes::from(vec![i; 100])).unwrap();
+        }
+        let (_, parity) = encoder.finish().unwrap();
+        let (data_map, _) = self_encryption::encrypt(Bytes::from(vec![7u8; 10_000])).unwrap();
+
+        let (inline, parity_chunks) =
+            pack_data_map_chunk(data_map.clone(), None, Some(parity.clone()), 10_000).unwrap();
+        assert!(parity_chunks.is_empty());
+        let (_, _, record) = WrappedDataMap::from_bytes(&inline)
+            .unwrap()
+            .unwrap()
+            .into_parts();
+        assert_eq!(record, Some(ParityRecord::Inline(parity.clone())));
+
+        let (chunked, parity_chunks) =
+            pack_data_map_chunk(data_map, None, Some(parity.clone()), 100).unwrap();
+        assert!(!parity_chunks.is_empty());
+        let (_, _, record) = WrappedDataMap::from_bytes(&chunked)
+            .unwrap()
+            .unwrap()
+            .into_parts();
+        let Some(ParityRecord::Chunked(parity_map)) = record else {
+            panic!("parity should be stored in chunks, got {record:?}");
+        };
+
+        let encrypted_chunks: Vec<_> = parity_chunks
+            .into_iter()
+            .map(|content| self_encryption::EncryptedChunk { content })
+            .collect();
+        let mut get_chunk = |name| {
+            encrypted_chunks
+                .iter()
+                .find(|chunk| XorName::from_content(&chunk.content) == name)
+                .map(|chunk| chunk.content.clone())
+                .ok_or_else(|| self_encryption::Error::Generic("Chunk not found".to_string()))
+        };
+        let root_map = self_encryption::get_root_data_map(parity_map, &mut get_chunk).unwrap();
+        let bytes = self_encryption::decrypt(&root_map, &encrypted_chunks).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<ParityShards>(&bytes).unwrap(),
+            parity
+        );
+    }
+
+    #[test]
+    fn wrapped_data_map_with_malformed_frames_is_rejected() {
+        #[derive(Serialize)]
+        struct MalformedFrames {
+            compression: Compression,
+            frame_size: u64,
+            original_size: u64,
+            frame_sizes: Vec<u32>,
+        }
+        #[derive(Serialize)]
+        enum MalformedDataMap {
+            V1 {
+                data_map: DataMap,
+                frames: MalformedFrames,
+            },
+        }
+
+        let (data_map, _) = self_encryption::encrypt(Bytes::from(vec![7u8; 10_000])).unwrap();
+        let malformed = MalformedDataMap::V1 {
+            data_map,
+            frames: MalformedFrames {
+                compression: Compression::default(),
+                frame_size: 0,
+                original_size: 10_000,
+                frame_sizes: vec![100],
+            },
+        };
+        let mut bytes = WRAPPED_DATA_MAP_MAGIC.to_vec();
+        rmp_serde::encode::write(&mut bytes, &malformed).unwrap();
+
+        assert!(matches!(
+            WrappedDataMap::from_bytes(&bytes),
+            Some(Err(err)) if err.to_string().contains("frame size")
+        ));
+    }
+
+    #[test]
+    fn v1_data_maps_are_still_read() {
+        let data: Vec<u8> = b"0123456789".iter().cycle().take(10_000).copied().collect();
+        let (compressed, frames) = compress(&data, Compression::default()).unwrap().unwrap();
+        let (data_map, _) = self_encryption::encrypt(compressed).unwrap();
+
+        let v1 = WrappedDataMap::V1 {
+            data_map: data_map.clone(),
+            frames: frames.clone(),
+        }
+        .to_bytes()
+        .unwrap();
+        let (unwrapped_map, unwrapped_frames, parity) = WrappedDataMap::from_bytes(&v1)
+            .unwrap()
+            .unwrap()
+            .into_parts();
+        assert_eq!(unwrapped_map, data_map);
+        assert_eq!(unwrapped_frames, Some(frames));
+        assert_eq!(parity, None);
+    }
+}
@@ -6,6 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::compression::{Compression, compress};
+use super::data_map::data_map_chunk_content;
+use super::resilience::{ParityEncoder, Resilience};
 use ant_protocol::storage::Chunk;
 use bytes::{BufMut, Bytes, BytesMut};
 use rayon::prelude::*;
@@ -19,6 +22,19 @@ pub enum Error {
     Encoding(#[from] rmp_serde::encode::Error),
     #[error(transparent)]
     SelfEncryption(#[from] self_encryption::Error),
+    #[error("Compression failed: {0}")]
+    Compression(std::io::Error),
+    #[error("Erasure coding failed: {0}")]
+    ErasureCoding(String),
+}
+
+/// How data is encoded around its self-encryption, plain by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncryptionOptions {
+    /// Compress the data before encrypting it.
+    pub compression: Option<Compression>,
+    /// Add parity chunks over the encrypted chunks.
+    pub resilience: Option<Resilience>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -34,14 +50,77 @@ pub enum DataMapLevel {
 pub fn encrypt(data: Bytes) -> Result<(Chunk, Vec<Chunk>), Error> {
     let (data_map, chunks) = self_encryption::encrypt(data)?;
     let data_map_chunk = pack_data_map(data_map)?;
+    Ok((data_map_chunk, into_chunks(chunks)))
+}
+
+/// Same as [`encrypt`], compressing the data first if a [`Compression`] is given.
+/// The data is encrypted as is if compressing it doesn't make it smaller.
+pub fn encrypt_with_compression(
+    data: Bytes,
+    compression: Option<Compression>,
+) -> Result<(Chunk, Vec<Chunk>), Error> {
+    encrypt_with_options(
+        data,
+        EncryptionOptions {
+            compression,
+            resilience: None,
+        },
+    )
+}
+
+/// Same as [`encrypt`], encoding the data as set in the [`EncryptionOptions`].
+/// The parity chunks, if any, are returned along with the encrypted chunks.
+pub fn encrypt_with_options(
+    data: Bytes,
+    options: EncryptionOptions,
+) -> Result<(Chunk, Vec<Chunk>), Error> {
+    let (data, frames) = match options.compression {
+        Some(compression) => match compress(&data, compression)? {
+            Some((compressed, frames)) => {
+                debug!(
+                    "Compressed data from {} to {} bytes",
+                    data.len(),
+                    compressed.len()
+                );
+                (compressed, Some(frames))
+            }
+            None => {
+                debug!("Compression doesn't shrink the data, encrypting it as is");
+                (data, None)
+            }
+        },
+        None => (data, None),
+    };
 
-    // Transform `EncryptedChunk` into `Chunk`
-    let chunks: Vec<Chunk> = chunks
+    let (data_map, chunks) = self_encryption::encrypt(data)?;
+    let mut chunks = into_chunks(chunks);
+
+    let parity = match options.resilience {
+        Some(resilience) => {
+            let mut encoder = ParityEncoder::new(resilience)?;
+            let mut parity_chunks = vec![];
+            for chunk in &chunks {
+                parity_chunks.extend(encoder.push(chunk.value.clone())?);
+            }
+            let (last_parity_chunks, parity) = encoder.finish()?;
+            parity_chunks.extend(last_parity_chunks);
+            chunks.extend(parity_chunks.into_iter().map(Chunk::new));
+            Some(parity)
+        }
+        None => None,
+    };
+
+    let (data_map_chunk, parity_chunks) = data_map_chunk_content(data_map, frames, parity)?;
+    chunks.extend(parity_chunks.into_iter().map(Chunk::new));
+    Ok((Chunk::new(data_map_chunk), chunks))
+}
+
+// Transform `EncryptedChunk` into `Chunk`
+fn into_chunks(chunks: Vec<self_encryption::EncryptedChunk>) -> Vec<Chunk> {
+    chunks
         .into_par_iter()
         .map(|c| Chunk::new(c.content.clone()))
-        .collect();
-
-    Ok((data_map_chunk, chunks))
+        .collect()
 }
 
 // Produces a chunk out of the first `DataMap`, which is validated for its size.
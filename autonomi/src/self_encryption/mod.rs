@@ -6,9 +6,17 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod compression;
+mod data_map;
 mod memory_encryption;
+mod resilience;
 mod stream_encryption;
 
-pub use memory_encryption::{DataMapLevel, Error, encrypt};
+pub use compression::{CompressedFrames, Compression};
+pub(crate) use data_map::{ParityRecord, WrappedDataMap};
+pub use memory_encryption::{
+    DataMapLevel, EncryptionOptions, Error, encrypt, encrypt_with_compression, encrypt_with_options,
+};
+pub use resilience::{ParityShards, Resilience};
 pub use self_encryption::MAX_CHUNK_SIZE;
 pub(crate) use stream_encryption::{EncryptionStream, encrypt_directory_files, encrypt_file};
@@ -0,0 +1,374 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Error;
+use bytes::Bytes;
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use serde::{Deserialize, Serialize};
+use xor_name::XorName;
+
+/// Maximum number of shards in a stripe, as the codec works over GF(2^8).
+const MAX_STRIPE_SHARDS: usize = 256;
+
+/// Client side redundancy on top of the replication done by the network.
+///
+/// The encrypted chunks are grouped in stripes of `data_shards` chunks, each getting
+/// `parity_shards` Reed-Solomon parity chunks, so that any `parity_shards` chunks of a stripe can
+/// be lost and reconstructed from the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resilience {
+    pub data_shards: usize,
+    pub parity_shards: usize,
+}
+
+impl Resilience {
+    /// Survives the loss of any 2 chunks out of 10, for 25% more chunks.
+    pub const STANDARD: Self = Self {
+        data_shards: 8,
+        parity_shards: 2,
+    };
+    /// Survives the loss of any 2 chunks out of 6, for 50% more chunks.
+    pub const HIGH: Self = Self {
+        data_shards: 4,
+        parity_shards: 2,
+    };
+    /// Survives the loss of any 3 chunks out of 6, for twice as many chunks.
+    pub const MAXIMUM: Self = Self {
+        data_shards: 3,
+        parity_shards: 3,
+    };
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.data_shards == 0 || self.parity_shards == 0 {
+            return Err(Error::ErasureCoding(
+                "Both data and parity shards are needed".to_string(),
+            ));
+        }
+        if self.data_shards + self.parity_shards > MAX_STRIPE_SHARDS {
+            return Err(Error::ErasureCoding(format!(
+                "At most {MAX_STRIPE_SHARDS} shards are supported in a stripe"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The parity chunks added to some data, recorded next to its data map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParityShards {
+    stripes: Vec<Stripe>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Stripe {
+    /// Name and size of the chunks covered by the parity
+    chunks: Vec<(XorName, u32)>,
+    /// Names of the parity chunks, each as large as the largest chunk of the stripe
+    parity: Vec<XorName>,
+}
+
+impl Stripe {
+    fn shard_len(&self) -> usize {
+        self.chunks
+            .iter()
+            .map(|(_, size)| *size as usize)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl ParityShards {
+    pub fn stripe_count(&self) -> usize {
+        self.stripes.len()
+    }
+
+    /// Names of all the chunks covered by the parity, followed by their parity chunks, stripe by
+    /// stripe.
+    pub fn shard_names(&self) -> impl Iterator<Item = XorName> + '_ {
+        (0..self.stripes.len()).flat_map(|stripe| self.stripe_shards(stripe))
+    }
+
+    /// The resilience the parity was computed with, read from the first stripe as only the last
+    /// one can be partial.
+    pub(crate) fn resilience(&self) -> Option<Resilience> {
+        self.stripes.first().map(|stripe| Resilience {
+            data_shards: stripe.chunks.len(),
+            parity_shards: stripe.parity.len(),
+        })
+    }
+
+    /// The stripe a chunk or parity chunk belongs to.
+    pub(crate) fn stripe_of(&self, name: &XorName) -> Option<usize> {
+        self.stripes.iter().position(|stripe| {
+            stripe.chunks.iter().any(|(chunk, _)| chunk == name) || stripe.parity.contains(name)
+        })
+    }
+
+    /// Names of the chunks of a stripe, followed by its parity chunks.
+    pub(crate) fn stripe_shards(&self, stripe: usize) -> Vec<XorName> {
+        let stripe = &self.stripes[stripe];
+        stripe
+            .chunks
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(stripe.parity.iter().copied())
+            .collect()
+    }
+
+    /// Rebuild the missing shards of a stripe out of the others.
+    /// Shards are given and returned in the order of [`ParityShards::stripe_shards`].
+    pub(crate) fn reconstruct(
+        &self,
+        stripe: usize,
+        shards: Vec<Option<Bytes>>,
+    ) -> Result<Vec<Bytes>, Error> {
+        let names = self.stripe_shards(stripe);
+        let stripe = &self.stripes[stripe];
+        if shards.len() != names.len() {
+            return Err(Error::ErasureCoding(format!(
+                "Expected {} shards, got {}",
+                names.len(),
+                shards.len()
+            )));
+        }
+        let available = shards.iter().filter(|shard| shard.is_some()).count();
+        if available < stripe.chunks.len() {
+            return Err(Error::ErasureCoding(format!(
+                "Only {available} of the {} shards are available, {} are needed",
+                names.len(),
+                stripe.chunks.len()
+            )));
+        }
+
+        let shard_len = stripe.shard_len();
+        let mut padded: Vec<Option<Vec<u8>>> = shards
+            .into_iter()
+            .map(|shard| shard.map(|shard| pad(&shard, shard_len)))
+            .collect();
+        codec(stripe.chunks.len(), stripe.parity.len())?
+            .reconstruct(&mut padded)
+            .map_err(|err| Error::ErasureCoding(format!("{err:?}")))?;
+
+        let sizes = stripe
+            .chunks
+            .iter()
+            .map(|(_, size)| *size as usize)
+            .chain(std::iter::repeat(shard_len));
+        let mut rebuilt = Vec::with_capacity(names.len());
+        for ((shard, size), name) in padded.into_iter().zip(sizes).zip(names) {
+            let mut shard = shard.unwrap_or_default();
+            shard.truncate(size);
+            // Catch shards that were given for the wrong stripe or got corrupted
+            if XorName::from_content(&shard) != name {
+                return Err(Error::ErasureCoding(format!(
+                    "Reconstructed shard doesn't match {name:?}"
+                )));
+            }
+            rebuilt.push(Bytes::from(shard));
+        }
+        Ok(rebuilt)
+    }
+}
+
+/// Computes the parity chunks of the encrypted chunks as they go by.
+pub(crate) struct ParityEncoder {
+    resilience: Resilience,
+    pending: Vec<Bytes>,
+    stripes: Vec<Stripe>,
+}
+
+impl ParityEncoder {
+    pub(crate) fn new(resilience: Resilience) -> Result<Self, Error> {
+        resilience.validate()?;
+        Ok(Self {
+            resilience,
+            pending: Vec::with_capacity(resilience.data_shards),
+            stripes: vec![],
+        })
+    }
+
+    /// Add a chunk, returning the parity chunks of its stripe if it completes it.
+    pub(crate) fn push(&mut self, chunk: Bytes) -> Result<Vec<Bytes>, Error> {
+        self.pending.push(chunk);
+        if self.pending.len() < self.resilience.data_shards {
+            return Ok(vec![]);
+        }
+        self.encode_pending()
+    }
+
+    /// Complete the last stripe, returning its parity chunks along with the record of all the
+    /// parity chunks.
+    pub(crate) fn finish(mut self) -> Result<(Vec<Bytes>, ParityShards), Error> {
+        let parity = if self.pending.is_empty() {
+            vec![]
+        } else {
+            self.encode_pending()?
+        };
+        Ok((
+            parity,
+            ParityShards {
+                stripes: self.stripes,
+            },
+        ))
+    }
+
+    fn encode_pending(&mut self) -> Result<Vec<Bytes>, Error> {
+        let chunks = std::mem::take(&mut self.pending);
+        let shard_len = chunks.iter().map(|chunk| chunk.len()).max().unwrap_or(0);
+        let parity_shards = self.resilience.parity_shards;
+
+        let mut shards: Vec<Vec<u8>> = chunks
+            .iter()
+            .map(|chunk| pad(chunk, shard_len))
+            .chain((0..parity_shards).map(|_| vec![0; shard_len]))
+            .collect();
+        codec(chunks.len(), parity_shards)?
+            .encode(&mut shards)
+            .map_err(|err| Error::ErasureCoding(format!("{err:?}")))?;
+
+        let parity: Vec<Bytes> = shards
+            .into_iter()
+            .skip(chunks.len())
+            .map(Bytes::from)
+            .collect();
+        self.stripes.push(Stripe {
+            chunks: chunks
+                .iter()
+                .map(|chunk| (XorName::from_content(chunk), chunk.len() as u32))
+                .collect(),
+            parity: parity
+                .iter()
+                .map(|shard| XorName::from_content(shard))
+                .collect(),
+        });
+        Ok(parity)
+    }
+}
+
+fn codec(data_shards: usize, parity_shards: usize) -> Result<ReedSolomon, Error> {
+    ReedSolomon::new(data_shards, parity_shards)
+        .map_err(|err| Error::ErasureCoding(format!("{err:?}")))
+}
+
+fn pad(shard: &[u8], len: usize) -> Vec<u8> {
+    let mut padded = Vec::with_capacity(len);
+    padded.extend_from_slice(shard);
+    padded.resize(len, 0);
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks(count: usize) -> Vec<Bytes> {
+        (0..count)
+            .map(|i| {
+                let len = 1000 + i * 10;
+                Bytes::from((0..len).map(|_| rand::random::<u8>()).collect::<Vec<_>>())
+            })
+            .collect()
+    }
+
+    fn encode(chunks: &[Bytes], resilience: Resilience) -> (Vec<Bytes>, ParityShards) {
+        let mut encoder = ParityEncoder::new(resilience).unwrap();
+        let mut parity = vec![];
+        for chunk in chunks {
+            parity.extend(encoder.push(chunk.clone()).unwrap());
+        }
+        let (last, shards) = encoder.finish().unwrap();
+        parity.extend(last);
+        (parity, shards)
+    }
+
+    #[test]
+    fn lost_shards_are_reconstructed() {
+        let chunks = chunks(4);
+        let (parity, shards) = encode(&chunks, Resilience::HIGH);
+        assert_eq!(parity.len(), 2);
+        assert_eq!(shards.stripe_count(), 1);
+
+        let all: Vec<Bytes> = chunks.iter().chain(parity.iter()).cloned().collect();
+        let mut damaged: Vec<Option<Bytes>> = all.iter().cloned().map(Some).collect();
+        damaged[1] = None;
+        damaged[4] = None;
+
+        assert_eq!(shards.reconstruct(0, damaged).unwrap(), all);
+    }
+
+    #[test]
+    fn last_stripe_can_be_partial() {
+        let chunks = chunks(10);
+        let (parity, shards) = encode(&chunks, Resilience::HIGH);
+        assert_eq!(shards.stripe_count(), 3);
+        assert_eq!(parity.len(), 6);
+        assert_eq!(shards.shard_names().count(), 16);
+
+        let last_stripe = shards
+            .stripe_of(&XorName::from_content(&chunks[9]))
+            .unwrap();
+        assert_eq!(last_stripe, 2);
+        let names = shards.stripe_shards(last_stripe);
+        assert_eq!(names.len(), 4);
+
+        let mut damaged: Vec<Option<Bytes>> = vec![None, None];
+        damaged.extend(parity[4..].iter().cloned().map(Some));
+        let rebuilt = shards.reconstruct(last_stripe, damaged).unwrap();
+        assert_eq!(rebuilt[..2], chunks[8..]);
+    }
+
+    #[test]
+    fn too_many_lost_shards_fail() {
+        let chunks = chunks(4);
+        let (parity, shards) = encode(&chunks, Resilience::HIGH);
+
+        let mut damaged: Vec<Option<Bytes>> = chunks
+            .iter()
+            .chain(parity.iter())
+            .cloned()
+            .map(Some)
+            .collect();
+        damaged[0] = None;
+        damaged[1] = None;
+        damaged[2] = None;
+
+        assert!(shards.reconstruct(0, damaged).is_err());
+    }
+
+    #[test]
+    fn corrupt_shards_are_detected() {
+        let chunks = chunks(3);
+        let (parity, shards) = encode(&chunks, Resilience::MAXIMUM);
+
+        let mut damaged: Vec<Option<Bytes>> = chunks
+            .iter()
+            .chain(parity.iter())
+            .cloned()
+            .map(Some)
+            .collect();
+        damaged[0] = None;
+        damaged[3] = Some(Bytes::from(vec![0; parity[0].len()]));
+
+        assert!(shards.reconstruct(0, damaged).is_err());
+    }
+
+    #[test]
+    fn invalid_resilience_is_rejected() {
+        let no_parity = Resilience {
+            data_shards: 4,
+            parity_shards: 0,
+        };
+        assert!(ParityEncoder::new(no_parity).is_err());
+
+        let too_wide = Resilience {
+            data_shards: 200,
+            parity_shards: 100,
+        };
+        assert!(ParityEncoder::new(too_wide).is_err());
+    }
+}
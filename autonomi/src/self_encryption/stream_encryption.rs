@@ -8,11 +8,16 @@
 
 use crate::client::data_types::chunk::DataMapChunk;
 use crate::files::{Metadata, get_relative_file_path_from_abs_file_and_folder_path};
-use crate::self_encryption::encrypt;
+use crate::self_encryption::compression::{
+    Compression, compressed_file_frames, scan_compressed_file,
+};
+use crate::self_encryption::data_map::data_map_chunk_content;
+use crate::self_encryption::resilience::ParityEncoder;
+use crate::self_encryption::{CompressedFrames, EncryptionOptions, Error, encrypt_with_options};
 use ant_protocol::storage::Chunk;
 use bytes::Bytes;
-use self_encryption::MAX_CHUNK_SIZE;
-use std::path::PathBuf;
+use self_encryption::{DataMap, MAX_CHUNK_SIZE};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tokio::sync::oneshot;
 
@@ -144,15 +149,27 @@ impl EncryptionStream {
         }
     }
 
+    /// A stream over chunks that are already encrypted, such as lost chunks to upload again.
+    pub fn new_from_chunks(chunks: Vec<Chunk>, data_map_chunk: DataMapChunk) -> Self {
+        EncryptionStream {
+            file_path: "".to_string(),
+            relative_path: Default::default(),
+            metadata: Metadata::default(),
+            is_public: false,
+            state: EncryptionState::InMemory(chunks, data_map_chunk),
+        }
+    }
+
     pub fn new_in_memory_with(
         file_path: String,
         relative_path: PathBuf,
         metadata: Metadata,
         is_public: bool,
         bytes: Bytes,
+        options: EncryptionOptions,
     ) -> Result<(Self, DataMapChunk), crate::self_encryption::Error> {
         let start = Instant::now();
-        let (data_map_chunk, mut chunks) = encrypt(bytes)?;
+        let (data_map_chunk, mut chunks) = encrypt_with_options(bytes, options)?;
 
         if is_public {
             chunks.push(data_map_chunk.clone());
@@ -173,6 +190,7 @@ impl EncryptionStream {
     pub fn new_in_memory(
         bytes: Bytes,
         is_public: bool,
+        options: EncryptionOptions,
     ) -> Result<(Self, DataMapChunk), crate::self_encryption::Error> {
         Self::new_in_memory_with(
             "".to_string(),
@@ -180,6 +198,7 @@ impl EncryptionStream {
             Metadata::default(),
             is_public,
             bytes,
+            options,
         )
     }
 
@@ -189,6 +208,7 @@ impl EncryptionStream {
         metadata: Metadata,
         is_public: bool,
         file_size: usize,
+        options: EncryptionOptions,
     ) -> Result<Self, String> {
         let start = Instant::now();
         let (chunk_sender, chunk_receiver) =
@@ -200,26 +220,22 @@ impl EncryptionStream {
         tokio::spawn(async move {
             // encrypt the file and send chunks in a chunk channel
             let path = PathBuf::from(&file_path_clone);
-            let result = self_encryption::streaming_encrypt_from_file(&path, |_xorname, bytes| {
+            let mut send_chunk = |bytes: Bytes| {
                 let chunk = Chunk::new(bytes);
                 chunk_sender.send(chunk).map_err(|err| {
                     error!("Error sending chunk: {err:?}");
                     self_encryption::Error::Io(std::io::Error::other(format!(
                         "Channel send error in encryption stream for {file_path_clone:?}: {err}"
                     )))
-                })?;
-                Ok(())
-            });
+                })
+            };
+            let result = stream_encrypt_with_options(&path, options, &mut send_chunk);
 
             // once we're done, send the datamap to the datamap channel
             match result {
-                Ok(datamap) => {
+                Ok(datamap_bytes) => {
                     // Send the DataMap result
-                    // Convert DataMap to bytes and create a chunk
-                    let datamap_bytes = rmp_serde::to_vec(&datamap)
-                        .map_err(|e| error!("Streaming encryption error serializing datamap for {file_path_clone}: {e}"))
-                        .unwrap_or_else(|_| vec![]);
-                    let datamap_chunk = DataMapChunk(Chunk::new(Bytes::from(datamap_bytes)));
+                    let datamap_chunk = DataMapChunk(Chunk::new(datamap_bytes));
                     if let Err(err) = datamap_sender.send(datamap_chunk) {
                         error!(
                             "Streaming encryption error sending datamap for {file_path_clone}: {err}"
@@ -261,10 +277,125 @@ impl EncryptionStream {
     }
 }
 
+/// Encrypts a file in a stream as set in the [`EncryptionOptions`], returning the content of its
+/// datamap chunk.
+fn stream_encrypt_with_options(
+    path: &Path,
+    options: EncryptionOptions,
+    send_chunk: &mut impl FnMut(Bytes) -> Result<(), self_encryption::Error>,
+) -> Result<Bytes, Error> {
+    let mut parity_encoder = options.resilience.map(ParityEncoder::new).transpose()?;
+
+    // Parity chunks are sent as soon as their stripe is complete
+    let mut store_chunk = |bytes: Bytes| {
+        if let Some(encoder) = parity_encoder.as_mut() {
+            let parity_chunks = encoder
+                .push(bytes.clone())
+                .map_err(|err| self_encryption::Error::Generic(err.to_string()))?;
+            for parity_chunk in parity_chunks {
+                send_chunk(parity_chunk)?;
+            }
+        }
+        send_chunk(bytes)
+    };
+    let (data_map, frames) = match options.compression {
+        Some(compression) => stream_encrypt_compressed(path, compression, &mut store_chunk)?,
+        None => (stream_encrypt(path, &mut store_chunk)?, None),
+    };
+
+    let parity = match parity_encoder {
+        Some(encoder) => {
+            let (parity_chunks, parity) = encoder.finish()?;
+            for parity_chunk in parity_chunks {
+                send_chunk(parity_chunk)?;
+            }
+            Some(parity)
+        }
+        None => None,
+    };
+    let (data_map_chunk, parity_chunks) = data_map_chunk_content(data_map, frames, parity)?;
+    for parity_chunk in parity_chunks {
+        send_chunk(parity_chunk)?;
+    }
+    Ok(data_map_chunk)
+}
+
+/// Encrypts a file in a stream.
+fn stream_encrypt(
+    path: &Path,
+    store_chunk: &mut impl FnMut(Bytes) -> Result<(), self_encryption::Error>,
+) -> Result<DataMap, Error> {
+    Ok(self_encryption::streaming_encrypt_from_file(
+        path,
+        |_xorname, bytes| store_chunk(bytes),
+    )?)
+}
+
+/// Compresses then encrypts a file in a stream, returning how it was compressed.
+/// The file is encrypted as is if compressing it doesn't make it smaller.
+fn stream_encrypt_compressed(
+    path: &Path,
+    compression: Compression,
+    store_chunk: &mut impl FnMut(Bytes) -> Result<(), self_encryption::Error>,
+) -> Result<(DataMap, Option<CompressedFrames>), Error> {
+    // Encryption needs the size of the data up front, so the file is compressed twice: once to
+    // learn the compressed size, then again while encrypting
+    let Some(frames) = scan_compressed_file(path, compression)? else {
+        debug!("Compression doesn't shrink {path:?}, encrypting it as is");
+        return Ok((stream_encrypt(path, store_chunk)?, None));
+    };
+    debug!(
+        "Compressing {path:?} from {} to {} bytes",
+        frames.original_size(),
+        frames.compressed_size()
+    );
+
+    let mut compression_error = None;
+    let mut compressed_size = 0;
+    let compressed_frames =
+        compressed_file_frames(path, compression)?.map_while(|frame| match frame {
+            Ok(frame) => {
+                compressed_size += frame.len();
+                Some(Bytes::from(frame))
+            }
+            Err(err) => {
+                compression_error = Some(err);
+                None
+            }
+        });
+
+    let mut stream = self_encryption::stream_encrypt(frames.compressed_size(), compressed_frames)?;
+    for chunk in stream.chunks() {
+        let (_xorname, content) = chunk?;
+        store_chunk(content)?;
+    }
+    let data_map = stream.datamap().cloned();
+    drop(stream);
+
+    if let Some(err) = compression_error {
+        return Err(err);
+    }
+    // The file changing between both passes would leave us with a broken datamap
+    if compressed_size != frames.compressed_size() {
+        return Err(Error::Compression(std::io::Error::other(format!(
+            "{path:?} compressed to {compressed_size} bytes instead of {}, was it modified?",
+            frames.compressed_size()
+        ))));
+    }
+    let Some(data_map) = data_map else {
+        return Err(Error::SelfEncryption(self_encryption::Error::Generic(
+            "Streaming encryption completed without a datamap".to_string(),
+        )));
+    };
+
+    Ok((data_map, Some(frames)))
+}
+
 /// Encrypts all files in a directory and returns the encryption results (common logic)
 pub(crate) async fn encrypt_directory_files(
     dir_path: PathBuf,
     is_public: bool,
+    options: EncryptionOptions,
 ) -> Result<Vec<Result<EncryptionStream, String>>, walkdir::Error> {
     let mut encryption_tasks = vec![];
 
@@ -286,7 +417,15 @@ pub(crate) async fn encrypt_directory_files(
                 .metadata()
                 .map_err(|err| format!("Error getting file size {file_path:?}: {err:?}"))?
                 .len() as usize;
-            encrypt_file(relative_path, file_path, file_size, metadata, is_public).await
+            encrypt_file(
+                relative_path,
+                file_path,
+                file_size,
+                metadata,
+                is_public,
+                options,
+            )
+            .await
         });
     }
 
@@ -302,6 +441,7 @@ pub(crate) async fn encrypt_file(
     file_size: usize,
     metadata: Metadata,
     is_public: bool,
+    options: EncryptionOptions,
 ) -> Result<EncryptionStream, String> {
     info!("Encrypting file: {file_path:?}..");
     #[cfg(feature = "loud")]
@@ -309,9 +449,16 @@ pub(crate) async fn encrypt_file(
 
     // choose encryption method
     if file_size > *IN_MEMORY_ENCRYPTION_MAX_SIZE {
-        encrypt_file_in_stream(file_path, is_public, metadata, relative_path, file_size)
+        encrypt_file_in_stream(
+            file_path,
+            is_public,
+            metadata,
+            relative_path,
+            file_size,
+            options,
+        )
     } else {
-        encrypt_file_in_memory(file_path, is_public, metadata, relative_path).await
+        encrypt_file_in_memory(file_path, is_public, metadata, relative_path, options).await
     }
 }
 
@@ -321,6 +468,7 @@ fn encrypt_file_in_stream(
     metadata: Metadata,
     relative_path: PathBuf,
     file_size: usize,
+    options: EncryptionOptions,
 ) -> Result<EncryptionStream, String> {
     info!("Encrypting file in stream: {file_path:?}..");
     EncryptionStream::new_stream_from_file(
@@ -329,6 +477,7 @@ fn encrypt_file_in_stream(
         metadata,
         is_public,
         file_size,
+        options,
     )
 }
 
@@ -337,6 +486,7 @@ async fn encrypt_file_in_memory(
     is_public: bool,
     metadata: Metadata,
     relative_path: PathBuf,
+    options: EncryptionOptions,
 ) -> Result<EncryptionStream, String> {
     info!("Encrypting file in memory: {file_path:?}..");
     let data = tokio::fs::read(&file_path)
@@ -356,6 +506,7 @@ async fn encrypt_file_in_memory(
         metadata,
         is_public,
         data,
+        options,
     )
     .map_err(|err| format!("Error encrypting file {file_path:?}: {err:?}"))?;
 
@@ -391,6 +542,7 @@ mod tests {
             metadata,
             is_public,
             file_size,
+            EncryptionOptions::default(),
         )
         .unwrap();
 
@@ -428,4 +580,61 @@ mod tests {
         let data_map_chunk = stream.data_map_chunk();
         assert!(data_map_chunk.is_some());
     }
+
+    #[test]
+    fn test_stream_encrypt_with_options_roundtrip() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let test_data: Vec<u8> = b"{\"id\":42,\"status\":\"ok\"}\n"
+            .iter()
+            .cycle()
+            .take(2_500_000)
+            .copied()
+            .collect();
+        temp_file.write_all(&test_data).unwrap();
+        temp_file.flush().unwrap();
+
+        let mut chunks = std::collections::HashMap::new();
+        let mut store_chunk = |bytes: Bytes| {
+            let _ = chunks.insert(xor_name::XorName::from_content(&bytes), bytes);
+            Ok(())
+        };
+        let options = EncryptionOptions {
+            compression: Some(Compression::default()),
+            resilience: Some(crate::self_encryption::Resilience::HIGH),
+        };
+        let datamap_bytes =
+            stream_encrypt_with_options(temp_file.path(), options, &mut store_chunk).unwrap();
+
+        let (data_map, frames, parity) =
+            crate::self_encryption::WrappedDataMap::from_bytes(&datamap_bytes)
+                .expect("compressible file should have a wrapped datamap")
+                .unwrap()
+                .into_parts();
+        let frames = frames.expect("compressible file should be compressed");
+        assert_eq!(frames.original_size(), test_data.len());
+
+        // Every chunk and parity chunk was stored
+        let Some(crate::self_encryption::ParityRecord::Inline(parity)) = parity else {
+            panic!("parity should be recorded in the datamap chunk, got {parity:?}");
+        };
+        assert_eq!(parity.shard_names().count(), chunks.len());
+        assert!(parity.shard_names().all(|name| chunks.contains_key(&name)));
+
+        let mut get_chunk = |name| {
+            chunks
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| self_encryption::Error::Generic("Chunk not found".to_string()))
+        };
+        let root_map = self_encryption::get_root_data_map(data_map, &mut get_chunk).unwrap();
+        let encrypted_chunks: Vec<_> = root_map
+            .infos()
+            .iter()
+            .map(|info| self_encryption::EncryptedChunk {
+                content: get_chunk(info.dst_hash).unwrap(),
+            })
+            .collect();
+        let compressed = self_encryption::decrypt(&root_map, &encrypted_chunks).unwrap();
+        assert_eq!(frames.decompress_all(&compressed).unwrap(), test_data);
+    }
 }
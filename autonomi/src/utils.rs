@@ -7,8 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::client::PutError;
+use crate::client::concurrency::{ConcurrencyController, TaskOutcome};
 use futures::stream::{FuturesUnordered, StreamExt};
 use std::future::Future;
+use std::time::Instant;
 
 pub(crate) async fn process_tasks_with_max_concurrency<I, R>(tasks: I, batch_size: usize) -> Vec<R>
 where
@@ -37,6 +39,44 @@ where
     results
 }
 
+/// Like [`process_tasks_with_max_concurrency`], but the number of tasks run at once follows the
+/// controller, which is told how each task went.
+pub(crate) async fn process_tasks_with_adaptive_concurrency<I, R>(
+    tasks: I,
+    controller: &ConcurrencyController,
+    outcome: impl Fn(&R) -> TaskOutcome,
+) -> Vec<R>
+where
+    I: IntoIterator,
+    I::Item: Future<Output = R> + Send,
+    R: Send,
+{
+    let mut futures = FuturesUnordered::new();
+    let mut results = Vec::new();
+
+    for task in tasks.into_iter() {
+        while futures.len() >= controller.limit()
+            && let Some((elapsed, result)) = futures.next().await
+        {
+            controller.record(outcome(&result), elapsed);
+            results.push(result);
+        }
+        futures.push(async move {
+            let start = Instant::now();
+            let result = task.await;
+            (start.elapsed(), result)
+        });
+    }
+
+    // Process remaining tasks
+    while let Some((elapsed, result)) = futures.next().await {
+        controller.record(outcome(&result), elapsed);
+        results.push(result);
+    }
+
+    results
+}
+
 /// Extracts gas fee values from an error message string.
 ///
 /// Looks for patterns like "maxFeePerGas: <value>, baseFee: <value>" in the error string
@@ -12,6 +12,7 @@ use crate::{
     mode::{InputMode, Scene},
     node_stats::NodeStats,
 };
+use ant_node_manager::resources::ExistingNodes;
 use ant_service_management::NodeServiceData;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -30,6 +31,10 @@ pub enum Action {
     StorePortRange(u32, u32),
     StoreRewardsAddress(String),
     StoreNodesToStart(usize),
+    /// Whether the nodes to start were confirmed above the number recommended for the host.
+    StoreIgnoreResourceLimit(bool),
+    /// The nodes already on the host, measured in the background for the recommendation.
+    StoreExistingNodes(ExistingNodes),
 
     SetUpnpSupport(UpnpSupport),
 
@@ -121,7 +126,9 @@ pub enum StatusActions {
     TriggerManageNodes,
     TriggerRewardsAddress,
     TriggerNodeLogs,
+    TriggerNodeDetails,
     TriggerRemoveNode,
+    CycleHistoryWindow,
 
     PreviousTableItem,
     NextTableItem,
@@ -131,6 +138,7 @@ pub enum StatusActions {
 pub enum OptionsActions {
     ResetNodes,
     UpdateNodes,
+    RollingUpdateNodes,
 
     TriggerChangeDrive,
     TriggerChangeConnectionMode,
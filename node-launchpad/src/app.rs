@@ -22,12 +22,12 @@ use crate::{
         },
         status::{Status, StatusConfig},
     },
-    config::{AppData, Config, get_launchpad_nodes_data_dir_path},
+    config::{AppData, Config},
     connection_mode::ConnectionMode,
     mode::{InputMode, Scene},
     node_mgmt::{PORT_MAX, PORT_MIN},
     style::SPACE_CADET,
-    system::{get_default_mount_point, get_primary_mount_point, get_primary_mount_point_name},
+    system::{get_primary_mount_point, get_primary_mount_point_name},
     tui,
 };
 use ant_bootstrap::InitialPeersConfig;
@@ -63,17 +63,7 @@ impl App {
         let app_data = AppData::load(app_data_path)?;
         let config = Config::new()?;
 
-        // Tries to set the data dir path based on the storage mountpoint set by the user,
-        // if not set, it tries to get the default mount point (where the executable is) and
-        // create the nodes data dir there.
-        // If even that fails, it will create the nodes data dir in the primary mount point.
-        let data_dir_path = match &app_data.storage_mountpoint {
-            Some(path) => get_launchpad_nodes_data_dir_path(&PathBuf::from(path), true)?,
-            None => match get_default_mount_point() {
-                Ok((_, path)) => get_launchpad_nodes_data_dir_path(&path, true)?,
-                Err(_) => get_launchpad_nodes_data_dir_path(&get_primary_mount_point(), true)?,
-            },
-        };
+        let data_dir_path = app_data.nodes_data_dir_path()?;
         debug!("Data dir path for nodes: {data_dir_path:?}");
 
         // App data default values
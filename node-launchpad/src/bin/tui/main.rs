@@ -15,13 +15,16 @@ mod terminal;
 extern crate tracing;
 
 use ant_bootstrap::InitialPeersConfig;
+use ant_node_manager::add_services::config::PortRange;
 #[cfg(target_os = "windows")]
 use ant_node_manager::config::is_running_as_root;
-use clap::Parser;
-use color_eyre::eyre::Result;
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{Result, eyre};
 use node_launchpad::{
     app::App,
     config::configure_winsw,
+    connection_mode::ConnectionMode,
+    headless::{self, ApplyOptions},
     utils::{initialize_logging, initialize_panic_handler},
 };
 use std::{env, path::PathBuf, time::Duration};
@@ -58,6 +61,9 @@ pub struct Cli {
     #[command(flatten)]
     peers: InitialPeersConfig,
 
+    #[clap(subcommand)]
+    cmd: Option<SubCmd>,
+
     /// Print the package version.
     #[clap(long)]
     #[cfg(not(feature = "nightly"))]
@@ -72,6 +78,44 @@ pub struct Cli {
     version: bool,
 }
 
+/// Commands that run without the user interface. They use the same saved settings as the TUI.
+#[derive(Subcommand, Debug)]
+enum SubCmd {
+    /// Change the node settings and bring the nodes in line with them.
+    ///
+    /// Settings which are not provided keep their saved value. The nodes are reset if the drive,
+    /// connection mode, port range or rewards address changes, then the number of running nodes is
+    /// brought to the node count.
+    #[clap(name = "apply")]
+    Apply {
+        /// The connection mode for the nodes: automatic, upnp or custom-ports.
+        #[clap(long)]
+        connection_mode: Option<ConnectionMode>,
+        /// The mount point of the drive to store the node data on.
+        #[clap(long)]
+        drive: Option<PathBuf>,
+        /// Print the resulting status as JSON.
+        #[clap(long)]
+        json: bool,
+        /// The number of nodes to run. Use 0 to stop all the nodes.
+        #[clap(long)]
+        nodes: Option<usize>,
+        /// The port range for the nodes, e.g. 12000-12049. Requires the custom-ports connection mode.
+        #[clap(long, value_parser = PortRange::parse)]
+        port_range: Option<PortRange>,
+        /// The address the node rewards are paid to.
+        #[clap(long)]
+        rewards_address: Option<String>,
+    },
+    /// Print the node settings and the state of every node.
+    #[clap(name = "status")]
+    Status {
+        /// Print the status as JSON.
+        #[clap(long)]
+        json: bool,
+    },
+}
+
 fn is_running_in_terminal() -> bool {
     atty::is(atty::Stream::Stdout)
 }
@@ -82,6 +126,19 @@ fn main() -> Result<()> {
         initialize_logging()?;
         configure_winsw().await?;
 
+        let args = Cli::parse();
+        if let Some(cmd) = args.cmd {
+            // Headless commands never need a terminal, so they run before one would be launched.
+            return run_headless(
+                cmd,
+                args.path,
+                args.antnode_path,
+                args.peers,
+                args.network_id,
+            )
+            .await;
+        }
+
         if !is_running_in_terminal() {
             info!("Running in non-terminal mode. Launching terminal.");
             // If we weren't already running in a terminal, this process returns early, having spawned
@@ -106,7 +163,6 @@ fn main() -> Result<()> {
         }
 
         initialize_panic_handler()?;
-        let args = Cli::parse();
 
         if args.version {
             println!(
@@ -152,3 +208,51 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+async fn run_headless(
+    cmd: SubCmd,
+    app_data_path: Option<PathBuf>,
+    antnode_path: Option<PathBuf>,
+    init_peers_config: InitialPeersConfig,
+    network_id: Option<u8>,
+) -> Result<()> {
+    match cmd {
+        SubCmd::Apply {
+            connection_mode,
+            drive,
+            json,
+            nodes,
+            port_range,
+            rewards_address,
+        } => {
+            let port_range = match port_range {
+                Some(PortRange::Range(port_from, port_to)) => {
+                    Some((port_from as u32, port_to as u32))
+                }
+                Some(PortRange::Single(port)) => {
+                    return Err(eyre!(
+                        "The port range must be given as FROM-TO, not a single port {port}"
+                    ));
+                }
+                None => None,
+            };
+            let options = ApplyOptions {
+                connection_mode,
+                drive,
+                nodes,
+                port_range,
+                rewards_address,
+            };
+            headless::apply(
+                options,
+                app_data_path,
+                antnode_path,
+                init_peers_config,
+                network_id,
+                json,
+            )
+            .await
+        }
+        SubCmd::Status { json } => headless::status(app_data_path, json).await,
+    }
+}
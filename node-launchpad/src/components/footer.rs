@@ -82,6 +82,9 @@ impl StatefulWidget for Footer {
             Span::styled(" ", Style::default()),
             Span::styled("[L] ", open_logs_command_style),
             Span::styled("Open Logs", open_logs_text_style),
+            Span::styled(" ", Style::default()),
+            Span::styled("[D] ", open_logs_command_style),
+            Span::styled("Details", open_logs_text_style),
         ];
 
         let stop_all = vec![
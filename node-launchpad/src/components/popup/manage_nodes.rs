@@ -10,15 +10,21 @@ use std::path::PathBuf;
 
 use crate::action::OptionsActions;
 use crate::system::get_available_space_b;
+use ant_node_manager::{
+    config::get_node_registry_path,
+    resources::{ExistingNodes, HostResources, ProjectedUsage},
+};
+use ant_service_management::{NodeRegistryManager, ServiceStatus};
 use color_eyre::Result;
 use crossterm::event::{Event, KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
 use tui_input::{Input, backend::crossterm::EventHandler};
 
 use crate::{
     action::Action,
     mode::{InputMode, Scene},
-    style::{EUCALYPTUS, GHOST_WHITE, LIGHT_PERIWINKLE, VIVID_SKY_BLUE, clear_area},
+    style::{EUCALYPTUS, GHOST_WHITE, LIGHT_PERIWINKLE, SIZZLING_RED, VIVID_SKY_BLUE, clear_area},
 };
 
 use super::super::{Component, utils::centered_rect_fixed};
@@ -32,10 +38,18 @@ pub struct ManageNodes {
     /// Whether the component is active right now, capturing keystrokes + drawing things.
     active: bool,
     available_disk_space_gb: usize,
+    action_sender: Option<UnboundedSender<Action>>,
+    /// The resources of the host, used to recommend a maximum number of nodes.
+    resources: HostResources,
+    /// The nodes already on the host, which the recommendation includes.
+    existing_nodes: ExistingNodes,
     storage_mountpoint: PathBuf,
     nodes_to_start_input: Input,
     // cache the old value incase user presses Esc.
     old_value: String,
+    /// Whether Enter was pressed once with more nodes than recommended, which has to be confirmed
+    /// by pressing it again.
+    above_recommended_warned: bool,
 }
 
 impl ManageNodes {
@@ -44,9 +58,13 @@ impl ManageNodes {
         let new = Self {
             active: false,
             available_disk_space_gb: (get_available_space_b(&storage_mountpoint)? / GB) as usize,
+            action_sender: None,
+            resources: HostResources::measure(&storage_mountpoint),
+            existing_nodes: ExistingNodes::default(),
             nodes_to_start_input: Input::default().with_value(nodes_to_start.to_string()),
             old_value: Default::default(),
             storage_mountpoint: storage_mountpoint.clone(),
+            above_recommended_warned: false,
         };
         Ok(new)
     }
@@ -63,31 +81,90 @@ impl ManageNodes {
             MAX_NODE_COUNT,
         )
     }
+
+    // Returns the number of nodes recommended for the resources of the host. Going above it
+    // requires a confirmation.
+    fn recommended_nodes_to_start(&self) -> usize {
+        self.resources.recommend(&self.existing_nodes).max_nodes
+    }
+
+    // Measures the nodes already on the host in the background, as walking their data can take a
+    // while. The result comes back as an action.
+    fn spawn_existing_nodes_measure(&self) {
+        let Some(action_sender) = self.action_sender.clone() else {
+            return;
+        };
+        let storage_mountpoint = self.storage_mountpoint.clone();
+        tokio::spawn(async move {
+            match measure_existing_nodes(storage_mountpoint).await {
+                Ok(existing_nodes) => {
+                    if let Err(err) = action_sender.send(Action::StoreExistingNodes(existing_nodes))
+                    {
+                        error!("Error sending the StoreExistingNodes action: {err}");
+                    }
+                }
+                Err(err) => error!("Failed to measure the existing nodes: {err}"),
+            }
+        });
+    }
+}
+
+async fn measure_existing_nodes(storage_mountpoint: PathBuf) -> Result<ExistingNodes> {
+    let node_registry = NodeRegistryManager::load(&get_node_registry_path()?).await?;
+    let data_dirs: Vec<PathBuf> = node_registry
+        .get_node_service_data()
+        .await
+        .into_iter()
+        .filter(|node| node.status != ServiceStatus::Removed)
+        .map(|node| node.data_dir_path)
+        .collect();
+    Ok(
+        tokio::task::spawn_blocking(move || {
+            ExistingNodes::measure(&data_dirs, &storage_mountpoint)
+        })
+        .await?,
+    )
 }
 
 impl Component for ManageNodes {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_sender = Some(tx);
+        Ok(())
+    }
+
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Vec<Action>> {
         if !self.active {
             return Ok(vec![]);
         }
 
+        let above_recommended_warned = std::mem::take(&mut self.above_recommended_warned);
         // while in entry mode, key bindings are not captured, so gotta exit entry mode from here
         let send_back = match key.code {
             KeyCode::Enter => {
                 let nodes_to_start_str = self.nodes_to_start_input.value().to_string();
                 let nodes_to_start =
                     std::cmp::min(self.get_nodes_to_start_val(), self.max_nodes_to_start());
+                if nodes_to_start > self.recommended_nodes_to_start() && !above_recommended_warned {
+                    debug!(
+                        "Got Enter with {nodes_to_start} nodes, above the {} recommended, waiting for a confirmation",
+                        self.recommended_nodes_to_start()
+                    );
+                    self.above_recommended_warned = true;
+                    return Ok(vec![]);
+                }
 
                 // set the new value
                 self.nodes_to_start_input = self
                     .nodes_to_start_input
                     .clone()
                     .with_value(nodes_to_start.to_string());
+                let ignore_resource_limit = nodes_to_start > self.recommended_nodes_to_start();
 
                 debug!(
                     "Got Enter, value found to be {nodes_to_start} derived from input: {nodes_to_start_str:?} and switching scene",
                 );
                 vec![
+                    Action::StoreIgnoreResourceLimit(ignore_resource_limit),
                     Action::StoreNodesToStart(nodes_to_start),
                     Action::SwitchScene(Scene::Status),
                 ]
@@ -173,6 +250,7 @@ impl Component for ManageNodes {
                         .with_value(amount_of_nodes.to_string());
                     self.active = true;
                     self.old_value = self.nodes_to_start_input.value().to_string();
+                    self.spawn_existing_nodes_measure();
                     // set to entry input mode as we want to handle everything within our handle_key_events
                     // so by default if this scene is active, we capture inputs.
                     Some(Action::SwitchInputMode(InputMode::Entry))
@@ -185,6 +263,12 @@ impl Component for ManageNodes {
             Action::OptionsActions(OptionsActions::UpdateStorageDrive(mountpoint, _drive_name)) => {
                 self.storage_mountpoint.clone_from(&mountpoint);
                 self.available_disk_space_gb = (get_available_space_b(&mountpoint)? / GB) as usize;
+                self.resources = HostResources::measure(&mountpoint);
+                self.spawn_existing_nodes_measure();
+                None
+            }
+            Action::StoreExistingNodes(existing_nodes) => {
+                self.existing_nodes = existing_nodes;
                 None
             }
             _ => None,
@@ -197,7 +281,7 @@ impl Component for ManageNodes {
             return Ok(());
         }
 
-        let layer_zero = centered_rect_fixed(52, 15, area);
+        let layer_zero = centered_rect_fixed(52, 17, area);
         let layer_one = Layout::new(
             Direction::Vertical,
             [
@@ -207,6 +291,10 @@ impl Component for ManageNodes {
                 Constraint::Length(1),
                 // for the info field telling how much gb used
                 Constraint::Length(1),
+                // for the projected memory and cpu usage
+                Constraint::Length(1),
+                // for the recommended number of nodes
+                Constraint::Length(1),
                 // gap before help
                 Constraint::Length(1),
                 // for the help
@@ -285,6 +373,59 @@ impl Component for ManageNodes {
         let info = Paragraph::new(info).alignment(Alignment::Center);
         f.render_widget(info, layer_one[2]);
 
+        // ==== projected usage ====
+        let usage = ProjectedUsage::for_nodes(self.get_nodes_to_start_val());
+        let usage = Line::from(vec![
+            Span::styled(
+                format!(
+                    "{:.1}GB of {:.1}GB RAM",
+                    usage.memory_b as f64 / GB as f64,
+                    self.resources.total_memory_b as f64 / GB as f64
+                ),
+                info_style,
+            ),
+            Span::styled(
+                format!(
+                    ", {:.1} of {} CPU cores",
+                    usage.cpu_cores, self.resources.cpu_cores
+                ),
+                info_style,
+            ),
+        ]);
+        f.render_widget(
+            Paragraph::new(usage).alignment(Alignment::Center),
+            layer_one[3],
+        );
+
+        // ==== recommendation ====
+        let recommendation = self.resources.recommend(&self.existing_nodes);
+        let recommendation = if self.above_recommended_warned {
+            Line::from(Span::styled(
+                format!(
+                    "Above the {} recommended. [Enter] to confirm",
+                    recommendation.max_nodes
+                ),
+                Style::default().fg(SIZZLING_RED),
+            ))
+        } else {
+            let style = if self.get_nodes_to_start_val() > recommendation.max_nodes {
+                Style::default().fg(SIZZLING_RED)
+            } else {
+                Style::default().fg(LIGHT_PERIWINKLE)
+            };
+            Line::from(Span::styled(
+                format!(
+                    "Recommended: up to {} nodes ({})",
+                    recommendation.max_nodes, recommendation.limited_by
+                ),
+                style,
+            ))
+        };
+        f.render_widget(
+            Paragraph::new(recommendation).alignment(Alignment::Center),
+            layer_one[4],
+        );
+
         // ==== help ====
         let help = Paragraph::new(vec![
             Line::raw(format!(
@@ -298,18 +439,18 @@ impl Component for ManageNodes {
         .block(Block::default().padding(Padding::horizontal(4)))
         .alignment(Alignment::Center)
         .fg(GHOST_WHITE);
-        f.render_widget(help, layer_one[4]);
+        f.render_widget(help, layer_one[6]);
 
         // ==== dash ====
         let dash = Block::new()
             .borders(Borders::BOTTOM)
             .border_style(Style::new().fg(GHOST_WHITE));
-        f.render_widget(dash, layer_one[5]);
+        f.render_widget(dash, layer_one[7]);
 
         // ==== buttons ====
         let buttons_layer =
             Layout::horizontal(vec![Constraint::Percentage(45), Constraint::Percentage(55)])
-                .split(layer_one[6]);
+                .split(layer_one[8]);
 
         let button_no = Line::from(vec![Span::styled(
             "  Close [Esc]",
@@ -49,6 +49,13 @@ impl Component for UpgradeNodesPopUp {
                     Action::SwitchScene(Scene::Status),
                 ]
             }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                debug!("Got R, Upgrading nodes in health-gated stages...");
+                vec![
+                    Action::OptionsActions(OptionsActions::RollingUpdateNodes),
+                    Action::SwitchScene(Scene::Status),
+                ]
+            }
             KeyCode::Esc => {
                 debug!("Got Esc, Not upgrading nodes.");
                 vec![Action::SwitchScene(Scene::Options)]
@@ -80,7 +87,7 @@ impl Component for UpgradeNodesPopUp {
             return Ok(());
         }
 
-        let layer_zero = centered_rect_fixed(52, 15, area);
+        let layer_zero = centered_rect_fixed(52, 18, area);
 
         let layer_one = Layout::new(
             Direction::Vertical,
@@ -112,7 +119,7 @@ impl Component for UpgradeNodesPopUp {
             Direction::Vertical,
             [
                 // for the text
-                Constraint::Length(10),
+                Constraint::Length(13),
                 // gap
                 Constraint::Length(3),
                 // for the buttons
@@ -158,6 +165,19 @@ impl Component for UpgradeNodesPopUp {
                 "Are you sure you want to continue?",
                 Style::default(),
             )),
+            Line::from(Span::styled("\n\n", Style::default())),
+            Line::from(vec![
+                Span::styled("Or press ", Style::default().fg(LIGHT_PERIWINKLE)),
+                Span::styled("[R] ", Style::default().fg(GHOST_WHITE)),
+                Span::styled(
+                    "to upgrade in stages, keeping",
+                    Style::default().fg(LIGHT_PERIWINKLE),
+                ),
+            ]),
+            Line::from(Span::styled(
+                "nodes running and rolling back unhealthy ones.",
+                Style::default().fg(LIGHT_PERIWINKLE),
+            )),
         ])
         .block(Block::default().padding(Padding::horizontal(2)))
         .alignment(Alignment::Center)
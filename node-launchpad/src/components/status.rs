@@ -14,7 +14,7 @@ use super::{Component, Frame, footer::Footer, header::Header, popup::manage_node
 use crate::action::OptionsActions;
 use crate::components::popup::manage_nodes::MAX_NODE_COUNT;
 use crate::components::popup::port_range::PORT_ALLOCATION;
-use crate::components::utils::open_logs;
+use crate::components::utils::{centered_rect, open_logs, read_recent_log_lines};
 use crate::config::get_launchpad_nodes_data_dir_path;
 use crate::connection_mode::{ConnectionMode, NodeConnectionMode};
 use crate::error::ErrorPopup;
@@ -32,16 +32,18 @@ use crate::{
     config::Config,
     mode::{InputMode, Scene},
     node_stats::NodeStats,
+    stats_history::{HistoryMetric, HistoryWindow, StatsHistory, sparkline_text},
     style::{EUCALYPTUS, GHOST_WHITE, LIGHT_PERIWINKLE, VERY_LIGHT_AZURE, VIVID_SKY_BLUE},
 };
 use ant_bootstrap::InitialPeersConfig;
 use ant_node_manager::add_services::config::PortRange;
 use ant_node_manager::config::get_node_registry_path;
+use ant_node_manager::rolling_upgrade::{HealthGate, RollingUpgradeStrategy};
 use ant_service_management::{
     NodeRegistryManager, NodeServiceData, ServiceStatus, control::ServiceController,
 };
 use color_eyre::eyre::{Ok, OptionExt, Result};
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::text::Span;
 use ratatui::{prelude::*, widgets::*};
 use std::fmt;
@@ -54,6 +56,10 @@ use throbber_widgets_tui::{self, Throbber, ThrobberState};
 use tokio::sync::mpsc::UnboundedSender;
 
 pub const NODE_STAT_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+/// How often the stats history is written to disk.
+const STATS_HISTORY_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// How many of the most recent log lines are shown in the details of a node.
+const NODE_DETAILS_LOG_LINES: usize = 50;
 /// If nat detection fails for more than 3 times, we don't want to waste time running during every node start.
 const MAX_ERRORS_WHILE_RUNNING_NAT_DETECTION: usize = 3;
 
@@ -68,6 +74,7 @@ const PEERS_WIDTH: usize = 5;
 const CONNS_WIDTH: usize = 5;
 const MODE_WIDTH: usize = 7;
 const STATUS_WIDTH: usize = 8;
+const TREND_WIDTH: usize = 10;
 const FAILURE_WIDTH: usize = 64;
 const SPINNER_WIDTH: usize = 1;
 
@@ -85,6 +92,14 @@ pub struct Status<'a> {
     // Device Stats Section
     node_stats: NodeStats,
     node_stats_last_update: Instant,
+    // Stats History
+    stats_history: StatsHistory,
+    stats_history_path: PathBuf,
+    stats_history_last_saved: Instant,
+    history_window: HistoryWindow,
+    // The node whose details are shown, with its recent log lines
+    node_details: Option<String>,
+    node_details_logs: Vec<String>,
     // Nodes
     node_services: Vec<NodeServiceData>,
     items: Option<StatefulTable<NodeItem<'a>>>,
@@ -94,6 +109,8 @@ pub struct Status<'a> {
     node_management: NodeManagement,
     // Amount of nodes
     nodes_to_start: usize,
+    // Whether the amount of nodes was confirmed above the recommendation
+    ignore_resource_limit: bool,
     // Rewards address
     rewards_address: String,
     // Peers to pass into nodes for startup
@@ -132,6 +149,7 @@ pub struct StatusConfig {
 impl Status<'_> {
     pub async fn new(config: StatusConfig) -> Result<Self> {
         let node_registry = NodeRegistryManager::load(&get_node_registry_path()?).await?;
+        let stats_history_path = StatsHistory::get_history_path()?;
         let mut status = Self {
             init_peers_config: config.init_peers_config,
             action_sender: Default::default(),
@@ -143,10 +161,17 @@ impl Status<'_> {
             network_id: config.network_id,
             node_stats: NodeStats::default(),
             node_stats_last_update: Instant::now(),
+            stats_history: StatsHistory::load(&stats_history_path),
+            stats_history_path,
+            stats_history_last_saved: Instant::now(),
+            history_window: HistoryWindow::default(),
+            node_details: None,
+            node_details_logs: Vec::new(),
             node_services: Default::default(),
             node_management: NodeManagement::new(node_registry.clone())?,
             items: None,
             nodes_to_start: config.allocated_disk_space,
+            ignore_resource_limit: false,
             rewards_address: config.rewards_address,
             antnode_path: config.antnode_path,
             data_dir_path: config.data_dir_path,
@@ -270,6 +295,20 @@ impl Status<'_> {
                         item.records = stats.max_records;
                         item.connections = stats.connections;
                     }
+
+                    item.trend = self
+                        .stats_history
+                        .nodes
+                        .get(&node_item.service_name)
+                        .map_or_else(
+                            || " ".repeat(TREND_WIDTH),
+                            |series| {
+                                sparkline_text(
+                                    &series.values(self.history_window, HistoryMetric::Records),
+                                    TREND_WIDTH,
+                                )
+                            },
+                        );
                 } else {
                     // If not found, create a new NodeItem and add it to items
                     let new_item = NodeItem {
@@ -281,6 +320,7 @@ impl Status<'_> {
                         records: 0,
                         peers: 0,
                         connections: 0,
+                        trend: " ".repeat(TREND_WIDTH),
                         mode: NodeConnectionMode::from(node_item),
                         locked: false,
                         status: NodeStatus::Added, // Set initial status as Added
@@ -318,6 +358,7 @@ impl Status<'_> {
                         records: 0,
                         peers: 0,
                         connections: 0,
+                        trend: " ".repeat(TREND_WIDTH),
                         locked: false,
                         mode: NodeConnectionMode::from(node_item),
                         status,
@@ -421,6 +462,199 @@ impl Status<'_> {
 
         (service_names, peers_ids)
     }
+
+    /// Records the stats in the history, writing it to disk every `STATS_HISTORY_SAVE_INTERVAL`.
+    fn record_stats_history(&mut self, stats: &NodeStats) {
+        self.stats_history
+            .record(stats, chrono::Utc::now().timestamp() as u64);
+        if self.stats_history_last_saved.elapsed() > STATS_HISTORY_SAVE_INTERVAL {
+            self.stats_history_last_saved = Instant::now();
+            if let Err(err) = self.stats_history.save(&self.stats_history_path) {
+                error!("Failed to save the stats history: {err:?}");
+            }
+        }
+    }
+
+    fn refresh_node_details_logs(&mut self) {
+        let Some(service_name) = &self.node_details else {
+            return;
+        };
+        self.node_details_logs = self
+            .node_services
+            .iter()
+            .find(|node| node.service_name == *service_name)
+            .map(|node| read_recent_log_lines(&node.log_dir_path, NODE_DETAILS_LOG_LINES))
+            .unwrap_or_default();
+    }
+
+    /// Draws the history of the whole fleet, as a sparkline for each metric.
+    fn draw_fleet_history(&self, f: &mut Frame<'_>, area: Rect) {
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::styled(" Fleet History", Style::default().fg(GHOST_WHITE).bold()),
+                Span::styled(
+                    format!(" ({}) ", self.history_window),
+                    Style::default().fg(LIGHT_PERIWINKLE),
+                ),
+            ]))
+            .title_bottom(Line::from(vec![
+                Span::styled(" [W] ", Style::default().fg(GHOST_WHITE)),
+                Span::styled("Change Period ", Style::default().fg(LIGHT_PERIWINKLE)),
+            ]))
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .border_style(Style::default().fg(VERY_LIGHT_AZURE));
+        let inner_area = block.inner(area);
+        f.render_widget(block, area);
+
+        let columns = Layout::horizontal(
+            HistoryMetric::ALL.map(|_| Constraint::Ratio(1, HistoryMetric::ALL.len() as u32)),
+        )
+        .spacing(2)
+        .split(inner_area);
+        for (metric, column) in HistoryMetric::ALL.iter().zip(columns.iter()) {
+            let values = self
+                .stats_history
+                .fleet
+                .values(self.history_window, *metric);
+            let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).split(*column);
+            let current = values
+                .last()
+                .map_or("-".to_string(), |value| metric.format_value(*value));
+            f.render_widget(
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", metric.label()),
+                        Style::default().fg(COOL_GREY),
+                    ),
+                    Span::styled(current, Style::default().fg(GHOST_WHITE)),
+                ]),
+                rows[0],
+            );
+            // Only the most recent values fit the width of the column.
+            let shown = &values[values.len().saturating_sub(rows[1].width as usize)..];
+            f.render_widget(
+                Sparkline::default()
+                    .data(shown)
+                    .style(Style::default().fg(EUCALYPTUS)),
+                rows[1],
+            );
+        }
+    }
+
+    /// Draws the history of a node as charts, with its most recent log lines.
+    fn draw_node_details(&self, f: &mut Frame<'_>, area: Rect, service_name: &str) {
+        let popup_area = centered_rect(90, 90, area);
+        clear_area(f, popup_area);
+
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::styled(
+                    format!(" {service_name}"),
+                    Style::default().fg(GHOST_WHITE).bold(),
+                ),
+                Span::styled(
+                    format!(" ({}) ", self.history_window),
+                    Style::default().fg(LIGHT_PERIWINKLE),
+                ),
+            ]))
+            .title_bottom(Line::from(vec![
+                Span::styled(" [W] ", Style::default().fg(GHOST_WHITE)),
+                Span::styled("Change Period ", Style::default().fg(LIGHT_PERIWINKLE)),
+                Span::styled(" [Esc] ", Style::default().fg(GHOST_WHITE)),
+                Span::styled("Close ", Style::default().fg(LIGHT_PERIWINKLE)),
+            ]))
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .border_style(Style::default().fg(VIVID_SKY_BLUE));
+        let inner_area = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let sections = Layout::vertical([
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Min(5),
+        ])
+        .split(inner_area);
+        let series = self.stats_history.nodes.get(service_name);
+        for (row, metrics) in HistoryMetric::ALL.chunks(3).enumerate() {
+            let columns = Layout::horizontal([Constraint::Ratio(1, 3); 3]).split(sections[row]);
+            for (metric, column) in metrics.iter().zip(columns.iter()) {
+                let values = series
+                    .map(|series| series.values(self.history_window, *metric))
+                    .unwrap_or_default();
+                self.draw_history_chart(f, *column, *metric, &values);
+            }
+        }
+
+        let logs_block = Block::default()
+            .title(Span::styled(
+                " Recent Logs ",
+                Style::default().fg(GHOST_WHITE).bold(),
+            ))
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(COOL_GREY));
+        let logs_area = logs_block.inner(sections[2]);
+        let lines: Vec<Line> = if self.node_details_logs.is_empty() {
+            vec![Line::styled(
+                "No logs found for this node",
+                Style::default().fg(COOL_GREY),
+            )]
+        } else {
+            let shown = self
+                .node_details_logs
+                .len()
+                .saturating_sub(logs_area.height as usize);
+            self.node_details_logs[shown..]
+                .iter()
+                .map(|line| Line::styled(line.as_str(), Style::default().fg(LIGHT_PERIWINKLE)))
+                .collect()
+        };
+        f.render_widget(Paragraph::new(lines).block(logs_block), sections[2]);
+    }
+
+    fn draw_history_chart(
+        &self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        metric: HistoryMetric,
+        values: &[u64],
+    ) {
+        let data: Vec<(f64, f64)> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (i as f64, *value as f64))
+            .collect();
+        let max = values.iter().copied().max().unwrap_or(0);
+        let current = values
+            .last()
+            .map_or("-".to_string(), |value| metric.format_value(*value));
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(EUCALYPTUS))
+            .data(&data);
+        let chart = Chart::new(vec![dataset])
+            .block(Block::default().title(Line::from(vec![
+                Span::styled(
+                    format!("{} ", metric.label()),
+                    Style::default().fg(COOL_GREY),
+                ),
+                Span::styled(current, Style::default().fg(GHOST_WHITE)),
+            ])))
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(COOL_GREY))
+                    .bounds([0.0, (data.len().max(2) - 1) as f64]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(COOL_GREY))
+                    .bounds([0.0, max.max(1) as f64])
+                    .labels([Span::raw("0"), Span::raw(metric.format_value(max))]),
+            );
+        f.render_widget(chart, area);
+    }
 }
 
 impl Component for Status<'_> {
@@ -458,12 +692,21 @@ impl Component for Status<'_> {
                 | Scene::RemoveNodePopUp
                 | Scene::UpgradeLaunchpadPopUp => {
                     self.active = true;
+                    self.node_details = None;
                     // make sure we're in navigation mode
                     return Ok(Some(Action::SwitchInputMode(InputMode::Navigation)));
                 }
                 Scene::ManageNodesPopUp { .. } => self.active = true,
+                Scene::NodeDetailsPopUp => {
+                    self.active = true;
+                    // the details capture their own keys
+                    return Ok(Some(Action::SwitchInputMode(InputMode::Entry)));
+                }
                 _ => self.active = false,
             },
+            Action::StoreIgnoreResourceLimit(ignore_resource_limit) => {
+                self.ignore_resource_limit = ignore_resource_limit;
+            }
             Action::StoreNodesToStart(count) => {
                 self.nodes_to_start = count;
                 if self.nodes_to_start == 0 {
@@ -529,6 +772,8 @@ impl Component for Status<'_> {
             }
             Action::StatusActions(status_action) => match status_action {
                 StatusActions::NodesStatsObtained(stats) => {
+                    self.record_stats_history(&stats);
+                    self.refresh_node_details_logs();
                     self.node_stats = stats;
                 }
                 StatusActions::StartNodesCompleted {
@@ -888,6 +1133,7 @@ impl Component for Status<'_> {
                         connection_mode: self.connection_mode,
                         count: self.nodes_to_start as u16,
                         data_dir_path: Some(self.data_dir_path.clone()),
+                        ignore_resource_limit: self.ignore_resource_limit,
                         network_id: self.network_id,
                         owner: self.rewards_address.clone(),
                         init_peers_config: self.init_peers_config.clone(),
@@ -991,6 +1237,7 @@ impl Component for Status<'_> {
                         connection_mode: self.connection_mode,
                         count: 1,
                         data_dir_path: Some(self.data_dir_path.clone()),
+                        ignore_resource_limit: self.ignore_resource_limit,
                         network_id: self.network_id,
                         owner: self.rewards_address.clone(),
                         init_peers_config: self.init_peers_config.clone(),
@@ -1060,6 +1307,21 @@ impl Component for Status<'_> {
                         return Ok(None);
                     }
                 }
+                StatusActions::TriggerNodeDetails => {
+                    if let Some(node) = self.items.as_ref().and_then(|items| items.selected_item())
+                    {
+                        debug!("Got action to show the details of {:?}", node.name);
+                        self.node_details = Some(node.name.clone());
+                        self.refresh_node_details_logs();
+                        return Ok(Some(Action::SwitchScene(Scene::NodeDetailsPopUp)));
+                    } else {
+                        debug!("Got action to show node details but no node was selected.");
+                    }
+                }
+                StatusActions::CycleHistoryWindow => {
+                    self.history_window = self.history_window.next();
+                    debug!("Showing the stats history of the {}", self.history_window);
+                }
                 StatusActions::TriggerNodeLogs => {
                     if let Some(node) = self.items.as_ref().and_then(|items| items.selected_item())
                     {
@@ -1070,8 +1332,11 @@ impl Component for Status<'_> {
                     }
                 }
             },
-            Action::OptionsActions(OptionsActions::UpdateNodes) => {
-                debug!("Got action to Update Nodes");
+            Action::OptionsActions(
+                action @ (OptionsActions::UpdateNodes | OptionsActions::RollingUpdateNodes),
+            ) => {
+                let rolling = action == OptionsActions::RollingUpdateNodes;
+                debug!("Got action to Update Nodes, rolling: {rolling}");
                 let action_sender = self.get_actions_sender()?;
                 info!("Got action to update nodes");
                 let _ = self.update_node_items(Some(NodeStatus::Updating));
@@ -1080,13 +1345,18 @@ impl Component for Status<'_> {
                 let upgrade_nodes_args = UpgradeNodesArgs {
                     action_sender,
                     connection_timeout_s: 5,
-                    do_not_start: true,
+                    do_not_start: !rolling,
                     custom_bin_path: self.antnode_path.clone(),
                     force: false,
                     fixed_interval: Some(FIXED_INTERVAL),
                     peer_ids,
                     provided_env_variables: None,
                     service_names,
+                    strategy: rolling.then(|| RollingUpgradeStrategy {
+                        canary_percentage: 10,
+                        batch_size: 5,
+                        health_gate: Some(HealthGate::default()),
+                    }),
                     url: None,
                     version: None,
                 };
@@ -1126,6 +1396,8 @@ impl Component for Status<'_> {
                 Constraint::Length(1),
                 // Device status
                 Constraint::Max(6),
+                // Fleet history
+                Constraint::Length(5),
                 // Node status
                 Constraint::Min(3),
                 // Footer
@@ -1281,6 +1553,10 @@ impl Component for Status<'_> {
         f.render_widget(stats_table, device_layout[0]);
         f.render_widget(attos_wallet_table, device_layout[1]);
 
+        // ==== Fleet History =====
+
+        self.draw_fleet_history(f, layout[2]);
+
         // ==== Node Status =====
 
         // No nodes. Empty Table.
@@ -1321,7 +1597,7 @@ impl Component for Status<'_> {
                                 .border_style(style::Style::default().fg(EUCALYPTUS))
                                 .padding(Padding::horizontal(1)),
                         ),
-                    layout[2],
+                    layout[3],
                 );
             } else {
                 // Node/s block
@@ -1346,7 +1622,7 @@ impl Component for Status<'_> {
                     .border_style(Style::default().fg(EUCALYPTUS));
 
                 // Split the inner area of the combined block
-                let inner_area = block_nodes.inner(layout[2]);
+                let inner_area = block_nodes.inner(layout[3]);
 
                 // Column Widths
                 let node_widths = [
@@ -1358,6 +1634,7 @@ impl Component for Status<'_> {
                     Constraint::Min(RECORDS_WIDTH as u16),
                     Constraint::Min(PEERS_WIDTH as u16),
                     Constraint::Min(CONNS_WIDTH as u16),
+                    Constraint::Min(TREND_WIDTH as u16),
                     Constraint::Min(MODE_WIDTH as u16),
                     Constraint::Min(STATUS_WIDTH as u16),
                     Constraint::Fill(FAILURE_WIDTH as u16),
@@ -1377,6 +1654,7 @@ impl Component for Status<'_> {
                     Cell::new("Recs").fg(COOL_GREY),
                     Cell::new("Peers").fg(COOL_GREY),
                     Cell::new("Conns").fg(COOL_GREY),
+                    Cell::new("Trend").fg(COOL_GREY),
                     Cell::new("Mode").fg(COOL_GREY),
                     Cell::new("Status").fg(COOL_GREY),
                     Cell::new("Failure").fg(COOL_GREY),
@@ -1388,7 +1666,7 @@ impl Component for Status<'_> {
                 if let Some(ref mut items_table) = self.items {
                     for (i, node_item) in items_table.items.iter_mut().enumerate() {
                         let is_selected = items_table.state.selected() == Some(i);
-                        items.push(node_item.render_as_row(i, layout[2], f, is_selected));
+                        items.push(node_item.render_as_row(i, layout[3], f, is_selected));
                     }
                 }
 
@@ -1401,7 +1679,7 @@ impl Component for Status<'_> {
 
                 f.render_widget(table, inner_area);
 
-                f.render_widget(block_nodes, layout[2]);
+                f.render_widget(block_nodes, layout[3]);
             }
         }
 
@@ -1433,10 +1711,14 @@ impl Component for Status<'_> {
         } else {
             &mut NodesToStart::NotRunning
         };
-        f.render_stateful_widget(footer, layout[3], footer_state);
+        f.render_stateful_widget(footer, layout[4], footer_state);
 
         // ===== Popups =====
 
+        if let Some(service_name) = &self.node_details {
+            self.draw_node_details(f, area, service_name);
+        }
+
         // Error Popup
         if let Some(error_popup) = &self.error_popup
             && error_popup.is_visible()
@@ -1501,6 +1783,16 @@ impl Component for Status<'_> {
             error_popup.handle_input(key);
             return Ok(vec![Action::SwitchInputMode(InputMode::Navigation)]);
         }
+        if self.node_details.is_some() {
+            let send_back = match key.code {
+                KeyCode::Esc => vec![Action::SwitchScene(Scene::Status)],
+                KeyCode::Char('w') | KeyCode::Char('W') => {
+                    vec![Action::StatusActions(StatusActions::CycleHistoryWindow)]
+                }
+                _ => vec![],
+            };
+            return Ok(send_back);
+        }
         Ok(vec![])
     }
 }
@@ -1594,7 +1886,8 @@ pub struct NodeItem<'a> {
     records: usize,
     peers: usize,
     connections: usize,
-    locked: bool, // Semaphore for being able to change status
+    trend: String, // Sparkline of the records stored over the history window
+    locked: bool,  // Semaphore for being able to change status
     mode: NodeConnectionMode,
     status: NodeStatus,
     failure: Option<(chrono::DateTime<chrono::Utc>, String)>,
@@ -1709,6 +2002,7 @@ impl NodeItem<'_> {
                 " ".repeat(CONNS_WIDTH.saturating_sub(self.connections.to_string().len())),
                 self.connections.to_string()
             ),
+            self.trend.clone(),
             self.mode.to_string(),
             self.status.to_string(),
             failure,
@@ -11,6 +11,13 @@ use ant_node_manager::config::get_service_log_dir_path;
 use ant_releases::ReleaseType;
 use color_eyre::eyre::{self};
 use ratatui::prelude::*;
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// How much of the end of a log file is read to find its most recent lines.
+const LOG_TAIL_BYTES: u64 = 64 * 1024;
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -70,3 +77,36 @@ pub fn open_logs(node_name: Option<String>) -> Result<(), eyre::Report> {
     }
     Ok(())
 }
+
+/// Reads the most recent lines of the log of a node, without their colour codes.
+///
+/// Only the end of the log is read, so fewer lines than requested are returned if they are very
+/// long. An empty list is returned if the log cannot be read.
+pub fn read_recent_log_lines(log_dir_path: &Path, count: usize) -> Vec<String> {
+    let read_tail = || -> std::io::Result<(Vec<u8>, bool)> {
+        let mut file = std::fs::File::open(log_dir_path.join("antnode.log"))?;
+        let start = file.metadata()?.len().saturating_sub(LOG_TAIL_BYTES);
+        file.seek(SeekFrom::Start(start))?;
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail)?;
+        Ok((tail, start > 0))
+    };
+    let (tail, truncated) = match read_tail() {
+        Ok(tail) => tail,
+        Err(err) => {
+            debug!("Could not read the log in {log_dir_path:?}: {err:?}");
+            return Vec::new();
+        }
+    };
+    let tail = String::from_utf8_lossy(&strip_ansi_escapes::strip(tail)).to_string();
+    let lines: Vec<&str> = tail.lines().collect();
+    // The first line was likely cut by where the read started.
+    let skip = usize::from(truncated && !lines.is_empty());
+    lines[skip..]
+        .iter()
+        .rev()
+        .take(count)
+        .rev()
+        .map(|line| line.to_string())
+        .collect()
+}
@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::connection_mode::ConnectionMode;
-use crate::system::get_primary_mount_point;
+use crate::system::{get_default_mount_point, get_primary_mount_point};
 use crate::{action::Action, mode::Scene};
 use ant_node_manager::config::is_running_as_root;
 use color_eyre::eyre::{Result, eyre};
@@ -186,6 +186,21 @@ impl AppData {
 
         Ok(())
     }
+
+    /// The directory the nodes store their data in, creating it if needed.
+    ///
+    /// Uses the storage mountpoint set by the user. If it is not set, the default mount point (where
+    /// the executable is) is used, falling back to the primary mount point.
+    pub fn nodes_data_dir_path(&self) -> Result<PathBuf> {
+        let data_dir_path = match &self.storage_mountpoint {
+            Some(path) => get_launchpad_nodes_data_dir_path(path, true)?,
+            None => match get_default_mount_point() {
+                Ok((_, path)) => get_launchpad_nodes_data_dir_path(&path, true)?,
+                Err(_) => get_launchpad_nodes_data_dir_path(&get_primary_mount_point(), true)?,
+            },
+        };
+        Ok(data_dir_path)
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
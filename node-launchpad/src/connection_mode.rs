@@ -9,6 +9,7 @@
 use ant_service_management::NodeServiceData;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result};
+use std::str::FromStr;
 use strum::{Display, EnumIter};
 
 #[derive(Clone, Copy, Debug, Default, EnumIter, Eq, Hash, PartialEq)]
@@ -31,6 +32,23 @@ impl Display for ConnectionMode {
     }
 }
 
+/// Parses the connection modes that can be chosen from the command line. The home network mode
+/// can no longer be set manually, so it is not accepted.
+impl FromStr for ConnectionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "automatic" => Ok(ConnectionMode::Automatic),
+            "upnp" => Ok(ConnectionMode::UPnP),
+            "custom-ports" => Ok(ConnectionMode::CustomPorts),
+            _ => Err(format!(
+                "Invalid connection mode {s:?}: expected automatic, upnp or custom-ports"
+            )),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for ConnectionMode {
     fn deserialize<D>(deserializer: D) -> std::result::Result<ConnectionMode, D::Error>
     where
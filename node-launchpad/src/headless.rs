@@ -0,0 +1,536 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Non-interactive access to the launchpad, for scripts and remote hosts without a terminal.
+//!
+//! The commands read and write the same app data and node registry as the TUI, so the settings
+//! applied here are picked up the next time the TUI is opened, and vice versa.
+
+use crate::{
+    action::{Action, StatusActions},
+    components::popup::{
+        manage_nodes::{GB, GB_PER_NODE, MAX_NODE_COUNT},
+        port_range::PORT_ALLOCATION,
+    },
+    config::AppData,
+    connection_mode::{ConnectionMode, NodeConnectionMode},
+    node_mgmt::{
+        MaintainNodesArgs, PORT_MAX, PORT_MIN, maintain_n_running_nodes, reset_nodes, stop_nodes,
+    },
+    node_stats::{IndividualNodeStats, NodeStats},
+    system::{
+        get_available_space_b, get_list_of_available_drives_and_available_space,
+        get_primary_mount_point, get_primary_mount_point_name,
+    },
+};
+use ant_bootstrap::InitialPeersConfig;
+use ant_node_manager::{
+    VerbosityLevel, add_services::config::PortRange, config::get_node_registry_path,
+};
+use ant_service_management::{
+    NatDetectionStatus, NodeRegistryManager, NodeServiceData, ServiceStatus,
+    control::ServiceController,
+};
+use color_eyre::eyre::{Result, bail, eyre};
+use regex::Regex;
+use serde::Serialize;
+use std::{path::PathBuf, time::Duration};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+/// How long to wait for the running nodes to report their metrics.
+const STATS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The settings to change with `apply`. Anything left unset keeps its saved value.
+#[derive(Debug, Default)]
+pub struct ApplyOptions {
+    pub connection_mode: Option<ConnectionMode>,
+    /// The mount point of the drive to store the node data on.
+    pub drive: Option<PathBuf>,
+    pub nodes: Option<usize>,
+    pub port_range: Option<(u32, u32)>,
+    pub rewards_address: Option<String>,
+}
+
+/// The node settings around which the launchpad is run, that both commands report.
+#[derive(Debug, Serialize)]
+pub struct LaunchpadStatus {
+    pub rewards_address: String,
+    pub nodes_to_start: usize,
+    pub storage_drive: String,
+    pub storage_mountpoint: PathBuf,
+    pub available_disk_space_gb: u64,
+    pub connection_mode: ConnectionMode,
+    pub port_from: Option<u32>,
+    pub port_to: Option<u32>,
+    pub nat_status: Option<NatDetectionStatus>,
+    pub nodes: Vec<NodeReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeReport {
+    pub service_name: String,
+    pub version: String,
+    pub status: ServiceStatus,
+    pub peer_id: Option<String>,
+    pub connection_mode: NodeConnectionMode,
+    pub node_port: Option<u16>,
+    pub metrics_port: Option<u16>,
+    pub connected_peers: Option<usize>,
+    pub data_dir_path: PathBuf,
+    pub log_dir_path: PathBuf,
+    /// Only available for running nodes which answered within the timeout.
+    pub stats: Option<IndividualNodeStats>,
+}
+
+/// Print the saved settings and the state of every node.
+pub async fn status(app_data_path: Option<PathBuf>, json: bool) -> Result<()> {
+    let app_data = AppData::load(app_data_path)?;
+    let node_registry = load_node_registry().await?;
+    let status = get_status(&app_data, &node_registry).await?;
+    print_status(&status, json)
+}
+
+/// Validate and save the given settings, like the TUI's option popups do, then bring the nodes in
+/// line with them: the nodes are reset if a setting they were created with has changed, and then
+/// the number of running nodes is converged on the saved node count.
+pub async fn apply(
+    options: ApplyOptions,
+    app_data_path: Option<PathBuf>,
+    antnode_path: Option<PathBuf>,
+    init_peers_config: InitialPeersConfig,
+    network_id: Option<u8>,
+    json: bool,
+) -> Result<()> {
+    let mut app_data = AppData::load(app_data_path.clone())?;
+    let node_registry = load_node_registry().await?;
+    let reset_required = update_app_data(&mut app_data, options)?;
+    let data_dir_path = app_data.nodes_data_dir_path()?;
+    app_data.save(app_data_path)?;
+    info!("Saved the app data after applying the new settings: {app_data:?}");
+
+    let (action_sender, action_receiver) = mpsc::unbounded_channel();
+    let has_nodes = node_registry
+        .get_node_service_data()
+        .await
+        .iter()
+        .any(|node| node.status != ServiceStatus::Removed);
+    if reset_required && has_nodes {
+        info!("The node settings have changed, resetting the nodes");
+        reset_nodes(action_sender.clone(), node_registry.clone(), false).await;
+    }
+
+    if app_data.nodes_to_start == 0 {
+        let running = node_registry
+            .get_node_service_data()
+            .await
+            .into_iter()
+            .filter(|node| node.status == ServiceStatus::Running)
+            .map(|node| node.service_name)
+            .collect::<Vec<_>>();
+        if !running.is_empty() {
+            stop_nodes(running, action_sender.clone(), node_registry.clone()).await;
+        }
+    } else {
+        let connection_mode = app_data
+            .connection_mode
+            .unwrap_or(ConnectionMode::Automatic);
+        let run_nat_detection = connection_mode == ConnectionMode::Automatic
+            && node_registry.nat_status.read().await.is_none();
+        let args = MaintainNodesArgs {
+            action_sender: action_sender.clone(),
+            antnode_path,
+            connection_mode,
+            count: app_data.nodes_to_start as u16,
+            data_dir_path: Some(data_dir_path),
+            ignore_resource_limit: false,
+            network_id,
+            owner: app_data.discord_username.clone(),
+            init_peers_config,
+            port_range: Some(PortRange::Range(
+                app_data.port_from.unwrap_or(PORT_MIN) as u16,
+                app_data.port_to.unwrap_or(PORT_MAX) as u16,
+            )),
+            rewards_address: app_data.discord_username.clone(),
+            run_nat_detection,
+        };
+        maintain_n_running_nodes(args, node_registry.clone()).await;
+    }
+    drop(action_sender);
+
+    let errors = collect_errors(action_receiver);
+    let status = get_status(&app_data, &node_registry).await?;
+    print_status(&status, json)?;
+
+    if !errors.is_empty() {
+        bail!("Failed to apply the settings:\n{}", errors.join("\n"));
+    }
+    Ok(())
+}
+
+/// Apply the options to the app data, validating them like the TUI's popups do.
+///
+/// Returns whether the existing nodes have to be reset for the new settings to take effect.
+fn update_app_data(app_data: &mut AppData, options: ApplyOptions) -> Result<bool> {
+    let mut reset_required = false;
+
+    if let Some(rewards_address) = options.rewards_address {
+        let re = Regex::new(r"^0x[a-fA-F0-9]{40}$").expect("Failed to compile regex");
+        if !re.is_match(&rewards_address) {
+            bail!(
+                "Invalid rewards address {rewards_address:?}: it must be 0x followed by 40 hexadecimal characters"
+            );
+        }
+        reset_required |= app_data.discord_username != rewards_address;
+        app_data.discord_username = rewards_address;
+    }
+
+    if let Some(drive) = options.drive {
+        let (name, mountpoint, _, accessible) = get_list_of_available_drives_and_available_space()?
+            .into_iter()
+            .find(|(_, mountpoint, _, _)| *mountpoint == drive)
+            .ok_or_else(|| eyre!("{drive:?} is not the mount point of an available drive"))?;
+        if !accessible {
+            bail!("The drive mounted at {mountpoint:?} is not readable and writable");
+        }
+        let current = app_data
+            .storage_mountpoint
+            .clone()
+            .unwrap_or(get_primary_mount_point());
+        reset_required |= current != mountpoint;
+        app_data.storage_mountpoint = Some(mountpoint);
+        app_data.storage_drive = Some(name);
+    }
+
+    if let Some(connection_mode) = options.connection_mode {
+        reset_required |= app_data.connection_mode.unwrap_or_default() != connection_mode;
+        app_data.connection_mode = Some(connection_mode);
+    }
+
+    let connection_mode = app_data.connection_mode.unwrap_or_default();
+    if let Some((port_from, port_to)) = options.port_range {
+        if connection_mode != ConnectionMode::CustomPorts {
+            bail!("A port range can only be set with the custom-ports connection mode");
+        }
+        validate_port_range(port_from, port_to)?;
+        reset_required |=
+            app_data.port_from != Some(port_from) || app_data.port_to != Some(port_to);
+        app_data.port_from = Some(port_from);
+        app_data.port_to = Some(port_to);
+    } else if options.connection_mode == Some(ConnectionMode::CustomPorts)
+        && app_data.port_from.is_none()
+    {
+        // Same default as the port range popup.
+        app_data.port_from = Some(PORT_MIN);
+        app_data.port_to = Some(PORT_MIN + PORT_ALLOCATION);
+    }
+
+    if let Some(nodes) = options.nodes {
+        let storage_mountpoint = app_data
+            .storage_mountpoint
+            .clone()
+            .unwrap_or(get_primary_mount_point());
+        let available_disk_space_gb = get_available_space_b(&storage_mountpoint)? / GB;
+        let max_nodes = max_nodes_to_start(available_disk_space_gb);
+        if nodes > max_nodes {
+            bail!(
+                "Cannot start {nodes} nodes: at most {max_nodes} fit on {storage_mountpoint:?} \
+                 ({GB_PER_NODE}GB per node, up to {MAX_NODE_COUNT} nodes)"
+            );
+        }
+        if connection_mode == ConnectionMode::CustomPorts {
+            let ports = (app_data.port_to.unwrap_or(PORT_MAX) + 1)
+                .saturating_sub(app_data.port_from.unwrap_or(PORT_MIN));
+            if nodes as u32 > ports {
+                bail!("Cannot start {nodes} nodes with only {ports} ports in the port range");
+            }
+        }
+        app_data.nodes_to_start = nodes;
+    }
+
+    if app_data.nodes_to_start > 0 && app_data.discord_username.is_empty() {
+        bail!("A rewards address is required to run nodes, set one with --rewards-address");
+    }
+
+    Ok(reset_required)
+}
+
+fn validate_port_range(port_from: u32, port_to: u32) -> Result<()> {
+    if !(PORT_MIN..=PORT_MAX).contains(&port_from) || !(PORT_MIN..=PORT_MAX).contains(&port_to) {
+        bail!("The ports must be between {PORT_MIN} and {PORT_MAX}");
+    }
+    if port_from > port_to {
+        bail!("The port range {port_from}-{port_to} is empty");
+    }
+    Ok(())
+}
+
+fn max_nodes_to_start(available_disk_space_gb: u64) -> usize {
+    std::cmp::min(
+        (available_disk_space_gb / GB_PER_NODE) as usize,
+        MAX_NODE_COUNT,
+    )
+}
+
+async fn load_node_registry() -> Result<NodeRegistryManager> {
+    let node_registry = NodeRegistryManager::load(&get_node_registry_path()?).await?;
+    ant_node_manager::refresh_node_registry(
+        node_registry.clone(),
+        &ServiceController {},
+        false,
+        true,
+        VerbosityLevel::Minimal,
+    )
+    .await?;
+    node_registry.save().await?;
+    Ok(node_registry)
+}
+
+/// Drain the actions sent while managing the nodes, keeping the errors to report.
+fn collect_errors(mut action_receiver: UnboundedReceiver<Action>) -> Vec<String> {
+    let mut errors = Vec::new();
+    while let Ok(action) = action_receiver.try_recv() {
+        let Action::StatusActions(action) = action else {
+            continue;
+        };
+        match action {
+            StatusActions::ErrorWhileRunningNatDetection => {
+                errors.push("NAT detection failed".to_string())
+            }
+            StatusActions::ErrorLoadingNodeRegistry { raw_error }
+            | StatusActions::ErrorGettingNodeRegistryPath { raw_error }
+            | StatusActions::ErrorScalingUpNodes { raw_error }
+            | StatusActions::ErrorResettingNodes { raw_error }
+            | StatusActions::ErrorUpdatingNodes { raw_error }
+            | StatusActions::ErrorAddingNodes { raw_error }
+            | StatusActions::ErrorStartingNodes { raw_error, .. }
+            | StatusActions::ErrorStoppingNodes { raw_error, .. }
+            | StatusActions::ErrorRemovingNodes { raw_error, .. } => errors.push(raw_error),
+            _ => {}
+        }
+    }
+    errors
+}
+
+async fn get_status(
+    app_data: &AppData,
+    node_registry: &NodeRegistryManager,
+) -> Result<LaunchpadStatus> {
+    let nodes = node_registry
+        .get_node_service_data()
+        .await
+        .into_iter()
+        .filter(|node| node.status != ServiceStatus::Removed)
+        .collect::<Vec<_>>();
+    let stats = fetch_node_stats(&nodes).await;
+
+    let storage_mountpoint = app_data
+        .storage_mountpoint
+        .clone()
+        .unwrap_or(get_primary_mount_point());
+    let storage_drive = match &app_data.storage_drive {
+        Some(drive) => drive.clone(),
+        None => get_primary_mount_point_name()?,
+    };
+    let connection_mode = app_data.connection_mode.unwrap_or_default();
+    let (port_from, port_to) = if connection_mode == ConnectionMode::CustomPorts {
+        (app_data.port_from, app_data.port_to)
+    } else {
+        (None, None)
+    };
+
+    Ok(LaunchpadStatus {
+        rewards_address: app_data.discord_username.clone(),
+        nodes_to_start: app_data.nodes_to_start,
+        storage_drive,
+        available_disk_space_gb: get_available_space_b(&storage_mountpoint)? / GB,
+        storage_mountpoint,
+        connection_mode,
+        port_from,
+        port_to,
+        nat_status: node_registry.nat_status.read().await.clone(),
+        nodes: nodes
+            .iter()
+            .map(|node| NodeReport {
+                service_name: node.service_name.clone(),
+                version: node.version.clone(),
+                status: node.status.clone(),
+                peer_id: node.peer_id.map(|peer_id| peer_id.to_string()),
+                connection_mode: NodeConnectionMode::from(node),
+                node_port: node.node_port,
+                metrics_port: node.metrics_port,
+                connected_peers: node.connected_peers.as_ref().map(|peers| peers.len()),
+                data_dir_path: node.data_dir_path.clone(),
+                log_dir_path: node.log_dir_path.clone(),
+                stats: stats
+                    .iter()
+                    .find(|stats| stats.service_name == node.service_name)
+                    .cloned(),
+            })
+            .collect(),
+    })
+}
+
+/// Fetch the metrics of the running nodes, giving up after `STATS_TIMEOUT`.
+async fn fetch_node_stats(nodes: &[NodeServiceData]) -> Vec<IndividualNodeStats> {
+    if !nodes
+        .iter()
+        .any(|node| node.status == ServiceStatus::Running)
+    {
+        return Vec::new();
+    }
+
+    let (action_sender, mut action_receiver) = mpsc::unbounded_channel();
+    NodeStats::fetch_all_node_stats(nodes, action_sender);
+    let fetch = async {
+        while let Some(action) = action_receiver.recv().await {
+            if let Action::StatusActions(StatusActions::NodesStatsObtained(stats)) = action {
+                return stats.individual_stats;
+            }
+        }
+        Vec::new()
+    };
+    match tokio::time::timeout(STATS_TIMEOUT, fetch).await {
+        Ok(stats) => stats,
+        Err(_) => {
+            warn!("Timed out while fetching the node stats");
+            Vec::new()
+        }
+    }
+}
+
+fn print_status(status: &LaunchpadStatus, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(status)?);
+        return Ok(());
+    }
+
+    println!("Rewards address: {}", status.rewards_address);
+    println!("Nodes to start: {}", status.nodes_to_start);
+    println!(
+        "Storage drive: {} ({}, {}GB available)",
+        status.storage_drive,
+        status.storage_mountpoint.display(),
+        status.available_disk_space_gb
+    );
+    match (status.port_from, status.port_to) {
+        (Some(port_from), Some(port_to)) => println!(
+            "Connection mode: {} ({port_from}-{port_to})",
+            status.connection_mode
+        ),
+        _ => println!("Connection mode: {}", status.connection_mode),
+    }
+    match &status.nat_status {
+        Some(nat_status) => println!("NAT status: {nat_status:?}"),
+        None => println!("NAT status: Undetermined"),
+    }
+
+    if status.nodes.is_empty() {
+        println!("No nodes have been added");
+        return Ok(());
+    }
+    println!();
+    println!(
+        "{:<12} {:<10} {:<10} {:<8} {:<8} {:<8} {:<10}",
+        "Node", "Version", "Status", "Mode", "Peers", "Records", "Memory"
+    );
+    for node in &status.nodes {
+        let (peers, records, memory) = match &node.stats {
+            Some(stats) => (
+                stats.peers.to_string(),
+                stats.max_records.to_string(),
+                format!("{}MB", stats.memory_usage_mb),
+            ),
+            None => ("-".to_string(), "-".to_string(), "-".to_string()),
+        };
+        println!(
+            "{:<12} {:<10} {:<10} {:<8} {:<8} {:<8} {:<10}",
+            node.service_name,
+            node.version,
+            format!("{:?}", node.status),
+            node.connection_mode.to_string(),
+            peers,
+            records,
+            memory
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn port_range_must_be_within_bounds_and_ordered() {
+        assert!(validate_port_range(PORT_MIN, PORT_MIN + PORT_ALLOCATION).is_ok());
+        assert!(validate_port_range(12000, 12000).is_ok());
+        assert!(validate_port_range(PORT_MIN - 1, 12000).is_err());
+        assert!(validate_port_range(12000, PORT_MAX + 1).is_err());
+        assert!(validate_port_range(12010, 12000).is_err());
+    }
+
+    #[test]
+    fn max_nodes_is_limited_by_disk_space_and_node_count() {
+        assert_eq!(max_nodes_to_start(0), 0);
+        assert_eq!(max_nodes_to_start(GB_PER_NODE * 3 + 1), 3);
+        assert_eq!(max_nodes_to_start(GB_PER_NODE * 1000), MAX_NODE_COUNT);
+    }
+
+    #[test]
+    fn invalid_rewards_address_is_rejected() {
+        let mut app_data = AppData::default();
+        let result = update_app_data(
+            &mut app_data,
+            ApplyOptions {
+                rewards_address: Some("0x1234".to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+        assert!(app_data.discord_username.is_empty());
+    }
+
+    #[test]
+    fn port_range_requires_custom_ports_mode() {
+        let mut app_data = AppData {
+            discord_username: format!("0x{}", "a".repeat(40)),
+            ..Default::default()
+        };
+        let result = update_app_data(
+            &mut app_data,
+            ApplyOptions {
+                port_range: Some((12000, 12049)),
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+
+        let reset_required = update_app_data(
+            &mut app_data,
+            ApplyOptions {
+                connection_mode: Some(ConnectionMode::CustomPorts),
+                port_range: Some((12000, 12049)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(reset_required);
+        assert_eq!(app_data.port_from, Some(12000));
+        assert_eq!(app_data.port_to, Some(12049));
+    }
+
+    #[test]
+    fn connection_mode_is_parsed_from_cli_names() {
+        assert_eq!(
+            "custom-ports".parse::<ConnectionMode>(),
+            Ok(ConnectionMode::CustomPorts)
+        );
+        assert_eq!("UPnP".parse::<ConnectionMode>(), Ok(ConnectionMode::UPnP));
+        assert!("home-network".parse::<ConnectionMode>().is_err());
+    }
+}
@@ -18,9 +18,11 @@ pub mod components;
 pub mod config;
 pub mod connection_mode;
 pub mod error;
+pub mod headless;
 pub mod mode;
 pub mod node_mgmt;
 pub mod node_stats;
+pub mod stats_history;
 pub mod style;
 pub mod system;
 pub mod tui;
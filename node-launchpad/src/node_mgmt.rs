@@ -10,7 +10,9 @@ use crate::action::{Action, StatusActions};
 use crate::connection_mode::ConnectionMode;
 use ant_bootstrap::InitialPeersConfig;
 use ant_evm::{EvmNetwork, RewardsAddress};
-use ant_node_manager::{VerbosityLevel, add_services::config::PortRange};
+use ant_node_manager::{
+    VerbosityLevel, add_services::config::PortRange, rolling_upgrade::RollingUpgradeStrategy,
+};
 use ant_releases::{self, AntReleaseRepoActions, ReleaseType};
 use ant_service_management::NodeRegistryManager;
 use color_eyre::Result;
@@ -140,7 +142,7 @@ impl NodeManagement {
 }
 
 /// Stop the specified services
-async fn stop_nodes(
+pub(crate) async fn stop_nodes(
     services: Vec<String>,
     action_sender: UnboundedSender<Action>,
     node_registry: NodeRegistryManager,
@@ -184,6 +186,8 @@ pub struct MaintainNodesArgs {
     pub connection_mode: ConnectionMode,
     pub count: u16,
     pub data_dir_path: Option<PathBuf>,
+    /// Add nodes even above the number recommended for the host.
+    pub ignore_resource_limit: bool,
     pub network_id: Option<u8>,
     pub owner: String,
     pub init_peers_config: InitialPeersConfig,
@@ -193,7 +197,10 @@ pub struct MaintainNodesArgs {
 }
 
 /// Maintain the specified number of nodes
-async fn maintain_n_running_nodes(args: MaintainNodesArgs, node_registry: NodeRegistryManager) {
+pub(crate) async fn maintain_n_running_nodes(
+    args: MaintainNodesArgs,
+    node_registry: NodeRegistryManager,
+) {
     debug!("Maintaining {} nodes", args.count);
     if args.run_nat_detection {
         run_nat_detection(&args.action_sender).await;
@@ -236,7 +243,7 @@ async fn maintain_n_running_nodes(args: MaintainNodesArgs, node_registry: NodeRe
 }
 
 /// Reset all the nodes
-async fn reset_nodes(
+pub(crate) async fn reset_nodes(
     action_sender: UnboundedSender<Action>,
     node_registry: NodeRegistryManager,
     start_nodes_after_reset: bool,
@@ -276,13 +283,18 @@ pub struct UpgradeNodesArgs {
     pub peer_ids: Vec<String>,
     pub provided_env_variables: Option<Vec<(String, String)>>,
     pub service_names: Vec<String>,
+    /// If set, the nodes are upgraded in health-gated stages while the others keep running,
+    /// rather than all being stopped first.
+    pub strategy: Option<RollingUpgradeStrategy>,
     pub url: Option<String>,
     pub version: Option<String>,
 }
 
 async fn upgrade_nodes(args: UpgradeNodesArgs, node_registry: NodeRegistryManager) {
-    // First we stop the Nodes
-    if let Err(err) = ant_node_manager::cmd::node::stop(
+    // First we stop the Nodes, unless they are upgraded in stages
+    if args.strategy.is_some() {
+        debug!("Upgrading nodes in stages, they are not stopped beforehand");
+    } else if let Err(err) = ant_node_manager::cmd::node::stop(
         None,
         node_registry.clone(),
         vec![],
@@ -306,10 +318,12 @@ async fn upgrade_nodes(args: UpgradeNodesArgs, node_registry: NodeRegistryManage
         args.custom_bin_path,
         args.force,
         Some(FIXED_INTERVAL),
+        false,
         node_registry.clone(),
         args.peer_ids,
         args.provided_env_variables,
         args.service_names,
+        args.strategy.unwrap_or_default(),
         args.url,
         args.version,
         VerbosityLevel::Minimal,
@@ -425,15 +439,19 @@ async fn add_node(args: MaintainNodesArgs, node_registry: NodeRegistryManager) {
         false, // alpha,
         false, // auto_restart,
         config.auto_set_nat_flags,
+        node_registry.service_backend().await,
         Some(config.count),
         config.data_dir_path,
-        true,       // enable_metrics_server,
-        None,       // env_variables,
-        None,       // evm_network
+        true, // enable_metrics_server,
+        None, // env_variables,
+        None, // evm_network
+        config.ignore_resource_limit,
         None,       // log_dir_path,
         None,       // log_format,
         None,       // max_archived_log_files,
+        None,       // max_download_rate,
         None,       // max_log_files,
+        None,       // max_upload_rate,
         None,       // metrics_port,
         None,       // network_id
         None,       // node_ip,
@@ -488,6 +506,7 @@ async fn start_nodes(
     if let Err(err) = ant_node_manager::cmd::node::start(
         CONNECTION_TIMEOUT_START,
         None,
+        false,
         node_registry.clone(),
         vec![],
         services.clone(),
@@ -532,6 +551,7 @@ struct NodeConfig {
     count: u16,
     custom_ports: Option<PortRange>,
     data_dir_path: Option<PathBuf>,
+    ignore_resource_limit: bool,
     relay: bool,
     network_id: Option<u8>,
     owner: Option<String>,
@@ -597,6 +617,7 @@ fn prepare_node_config(args: &MaintainNodesArgs) -> NodeConfig {
         antnode_path: args.antnode_path.clone(),
         auto_set_nat_flags: args.connection_mode == ConnectionMode::Automatic,
         data_dir_path: args.data_dir_path.clone(),
+        ignore_resource_limit: args.ignore_resource_limit,
         count: args.count,
         custom_ports: if args.connection_mode == ConnectionMode::CustomPorts {
             args.port_range.clone()
@@ -671,10 +692,13 @@ async fn scale_down_nodes(config: &NodeConfig, count: u16, node_registry: NodeRe
         true,
         None,
         Some(EvmNetwork::default()),
+        config.ignore_resource_limit,
         None,
         None,
         None,
+        None, // max_download_rate
         None,
+        None, // max_upload_rate
         None,
         config.network_id,
         None,
@@ -747,10 +771,13 @@ async fn add_nodes(
             true,
             None,
             Some(EvmNetwork::default()),
+            config.ignore_resource_limit,
             None,
             None,
             None,
+            None, // max_download_rate
             None,
+            None, // max_upload_rate
             None,
             config.network_id,
             None,
@@ -23,6 +23,7 @@ pub struct IndividualNodeStats {
     pub forwarded_rewards: usize,
     pub rewards_wallet_balance: usize,
     pub memory_usage_mb: usize,
+    pub cpu_usage_percentage: usize,
     pub bandwidth_inbound: usize,
     pub bandwidth_outbound: usize,
     pub bandwidth_inbound_rate: usize,
@@ -140,6 +141,7 @@ impl NodeStats {
                         forwarded_rewards: stats.forwarded_rewards,
                         rewards_wallet_balance: stats.rewards_wallet_balance,
                         memory_usage_mb: stats.memory_usage_mb,
+                        cpu_usage_percentage: stats.cpu_usage_percentage,
                         bandwidth_inbound: stats.bandwidth_inbound,
                         bandwidth_outbound: stats.bandwidth_outbound,
                         max_records: stats.max_records,
@@ -209,6 +211,16 @@ impl NodeStats {
                     }
                     _ => {}
                 }
+            } else if sample.metric == "ant_networking_process_cpu_usage_percentage" {
+                // CPU
+                match sample.value {
+                    prometheus_parse::Value::Counter(val)
+                    | prometheus_parse::Value::Gauge(val)
+                    | prometheus_parse::Value::Untyped(val) => {
+                        stats.cpu_usage_percentage = val.round() as usize;
+                    }
+                    _ => {}
+                }
             } else if sample.metric == "libp2p_bandwidth_bytes_total" {
                 // Mbps
                 match sample.value {
@@ -0,0 +1,440 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{config::get_launchpad_data_dir_path, node_stats::NodeStats};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+const STATS_HISTORY_FILE_NAME: &str = "stats_history.json";
+
+/// The period of time the history is shown for.
+///
+/// Each window keeps its own series, with a resolution coarse enough to hold the whole window in
+/// a few hundred samples.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HistoryWindow {
+    #[default]
+    Hour,
+    Day,
+    Week,
+}
+
+impl HistoryWindow {
+    pub const ALL: [HistoryWindow; 3] =
+        [HistoryWindow::Hour, HistoryWindow::Day, HistoryWindow::Week];
+
+    /// The length of the window, in seconds.
+    pub fn span_secs(&self) -> u64 {
+        match self {
+            HistoryWindow::Hour => 60 * 60,
+            HistoryWindow::Day => 24 * 60 * 60,
+            HistoryWindow::Week => 7 * 24 * 60 * 60,
+        }
+    }
+
+    /// The period covered by each sample, in seconds.
+    pub fn resolution_secs(&self) -> u64 {
+        match self {
+            HistoryWindow::Hour => 30,
+            HistoryWindow::Day => 10 * 60,
+            HistoryWindow::Week => 60 * 60,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            HistoryWindow::Hour => HistoryWindow::Day,
+            HistoryWindow::Day => HistoryWindow::Week,
+            HistoryWindow::Week => HistoryWindow::Hour,
+        }
+    }
+}
+
+impl fmt::Display for HistoryWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryWindow::Hour => write!(f, "last hour"),
+            HistoryWindow::Day => write!(f, "last day"),
+            HistoryWindow::Week => write!(f, "last week"),
+        }
+    }
+}
+
+/// A quantity recorded in the history.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HistoryMetric {
+    Records,
+    Peers,
+    Rewards,
+    /// Bytes per second received and sent, derived from the totals of consecutive samples.
+    Bandwidth,
+    MemoryMb,
+    CpuPercentage,
+}
+
+impl HistoryMetric {
+    pub const ALL: [HistoryMetric; 6] = [
+        HistoryMetric::Records,
+        HistoryMetric::Peers,
+        HistoryMetric::Rewards,
+        HistoryMetric::Bandwidth,
+        HistoryMetric::MemoryMb,
+        HistoryMetric::CpuPercentage,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistoryMetric::Records => "Records",
+            HistoryMetric::Peers => "Peers",
+            HistoryMetric::Rewards => "Attos",
+            HistoryMetric::Bandwidth => "Bandwidth",
+            HistoryMetric::MemoryMb => "Memory",
+            HistoryMetric::CpuPercentage => "CPU",
+        }
+    }
+
+    /// Format a value of the metric for display.
+    pub fn format_value(&self, value: u64) -> String {
+        match self {
+            HistoryMetric::Bandwidth => {
+                format!("{:.2} Mbps", value.saturating_mul(8) as f64 / 1_000_000.0)
+            }
+            HistoryMetric::MemoryMb => {
+                if value > 1024 {
+                    format!("{:.2} GB", value as f64 / 1024.0)
+                } else {
+                    format!("{value} MB")
+                }
+            }
+            HistoryMetric::CpuPercentage => format!("{value}%"),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// The stats of a node, or the total of the fleet, at some point in time.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsSample {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub records: u64,
+    pub peers: u64,
+    pub rewards: u64,
+    pub bandwidth_inbound: u64,
+    pub bandwidth_outbound: u64,
+    pub memory_mb: u64,
+    pub cpu_percentage: u64,
+}
+
+impl StatsSample {
+    fn add(&mut self, other: &StatsSample) {
+        // rewards are in attos, a fleet total may not fit
+        self.records = self.records.saturating_add(other.records);
+        self.peers = self.peers.saturating_add(other.peers);
+        self.rewards = self.rewards.saturating_add(other.rewards);
+        self.bandwidth_inbound = self
+            .bandwidth_inbound
+            .saturating_add(other.bandwidth_inbound);
+        self.bandwidth_outbound = self
+            .bandwidth_outbound
+            .saturating_add(other.bandwidth_outbound);
+        self.memory_mb = self.memory_mb.saturating_add(other.memory_mb);
+        self.cpu_percentage = self.cpu_percentage.saturating_add(other.cpu_percentage);
+    }
+}
+
+/// The series of samples of a node, or of the fleet, for each window.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsSeries {
+    hour: Vec<StatsSample>,
+    day: Vec<StatsSample>,
+    week: Vec<StatsSample>,
+}
+
+impl StatsSeries {
+    pub fn samples(&self, window: HistoryWindow) -> &[StatsSample] {
+        match window {
+            HistoryWindow::Hour => &self.hour,
+            HistoryWindow::Day => &self.day,
+            HistoryWindow::Week => &self.week,
+        }
+    }
+
+    /// The values of a metric over the window, oldest first.
+    pub fn values(&self, window: HistoryWindow, metric: HistoryMetric) -> Vec<u64> {
+        let samples = self.samples(window);
+        match metric {
+            HistoryMetric::Records => samples.iter().map(|sample| sample.records).collect(),
+            HistoryMetric::Peers => samples.iter().map(|sample| sample.peers).collect(),
+            HistoryMetric::Rewards => samples.iter().map(|sample| sample.rewards).collect(),
+            HistoryMetric::MemoryMb => samples.iter().map(|sample| sample.memory_mb).collect(),
+            HistoryMetric::CpuPercentage => {
+                samples.iter().map(|sample| sample.cpu_percentage).collect()
+            }
+            HistoryMetric::Bandwidth => samples
+                .windows(2)
+                .map(|pair| {
+                    let elapsed = pair[1].timestamp.saturating_sub(pair[0].timestamp).max(1);
+                    // The totals start again from zero when a node restarts.
+                    let transferred = pair[1]
+                        .bandwidth_inbound
+                        .saturating_sub(pair[0].bandwidth_inbound)
+                        + pair[1]
+                            .bandwidth_outbound
+                            .saturating_sub(pair[0].bandwidth_outbound);
+                    transferred / elapsed
+                })
+                .collect(),
+        }
+    }
+
+    pub fn latest(&self) -> Option<&StatsSample> {
+        self.hour.last()
+    }
+
+    fn record(&mut self, sample: &StatsSample) {
+        for window in HistoryWindow::ALL {
+            let samples = match window {
+                HistoryWindow::Hour => &mut self.hour,
+                HistoryWindow::Day => &mut self.day,
+                HistoryWindow::Week => &mut self.week,
+            };
+            let bucket = |timestamp: u64| timestamp / window.resolution_secs();
+            // Within a bucket, the most recent sample stands for the whole period.
+            match samples.last_mut() {
+                Some(last) if bucket(last.timestamp) == bucket(sample.timestamp) => {
+                    *last = sample.clone();
+                }
+                _ => samples.push(sample.clone()),
+            }
+            let oldest = sample.timestamp.saturating_sub(window.span_secs());
+            samples.retain(|sample| sample.timestamp > oldest);
+        }
+    }
+}
+
+/// The history of the stats of each node and of the whole fleet, kept on disk so it outlives the
+/// launchpad.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsHistory {
+    pub fleet: StatsSeries,
+    pub nodes: BTreeMap<String, StatsSeries>,
+}
+
+impl StatsHistory {
+    pub fn get_history_path() -> Result<PathBuf> {
+        Ok(get_launchpad_data_dir_path()?.join(STATS_HISTORY_FILE_NAME))
+    }
+
+    /// Load the history, starting afresh if it is missing or cannot be read.
+    pub fn load(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        match std::fs::read_to_string(path)
+            .map_err(color_eyre::Report::from)
+            .and_then(|contents| Ok(serde_json::from_str(&contents)?))
+        {
+            Ok(history) => history,
+            Err(err) => {
+                error!("Failed to load the stats history from {path:?}, starting afresh: {err:?}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string(self)?)?;
+        std::fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Record the stats obtained from the running nodes at the given time.
+    ///
+    /// Nodes with no stats in the last week, such as removed ones, are dropped.
+    pub fn record(&mut self, stats: &NodeStats, timestamp: u64) {
+        let mut fleet_sample = StatsSample {
+            timestamp,
+            ..Default::default()
+        };
+        for node_stats in &stats.individual_stats {
+            let sample = StatsSample {
+                timestamp,
+                records: node_stats.max_records as u64,
+                peers: node_stats.peers as u64,
+                rewards: node_stats.rewards_wallet_balance as u64,
+                bandwidth_inbound: node_stats.bandwidth_inbound as u64,
+                bandwidth_outbound: node_stats.bandwidth_outbound as u64,
+                memory_mb: node_stats.memory_usage_mb as u64,
+                cpu_percentage: node_stats.cpu_usage_percentage as u64,
+            };
+            fleet_sample.add(&sample);
+            self.nodes
+                .entry(node_stats.service_name.clone())
+                .or_default()
+                .record(&sample);
+        }
+        self.fleet.record(&fleet_sample);
+
+        let oldest = timestamp.saturating_sub(HistoryWindow::Week.span_secs());
+        self.nodes.retain(|_, series| {
+            series
+                .samples(HistoryWindow::Week)
+                .last()
+                .is_some_and(|sample| sample.timestamp > oldest)
+        });
+    }
+}
+
+/// Render values as a line of block characters, scaled to the largest of them.
+pub fn sparkline_text(values: &[u64], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let values = &values[values.len().saturating_sub(width)..];
+    let max = values.iter().copied().max().unwrap_or(0);
+    let line: String = values
+        .iter()
+        .map(|value| {
+            // Widened, as the values can be as large as the rewards in attos.
+            let level = (u128::from(*value) * (BARS.len() as u128 - 1))
+                .checked_div(u128::from(max))
+                .unwrap_or(0);
+            BARS[level as usize]
+        })
+        .collect();
+    format!("{}{line}", " ".repeat(width.saturating_sub(values.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_stats::IndividualNodeStats;
+
+    fn stats(records: &[(&str, usize, usize)]) -> NodeStats {
+        NodeStats {
+            individual_stats: records
+                .iter()
+                .map(|(name, records, inbound)| IndividualNodeStats {
+                    service_name: name.to_string(),
+                    max_records: *records,
+                    bandwidth_inbound: *inbound,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn record_should_keep_one_sample_per_bucket_and_total_the_fleet() {
+        let mut history = StatsHistory::default();
+        history.record(&stats(&[("antnode1", 10, 0), ("antnode2", 5, 0)]), 3600);
+        history.record(&stats(&[("antnode1", 12, 0), ("antnode2", 6, 0)]), 3605);
+        history.record(&stats(&[("antnode1", 20, 0), ("antnode2", 7, 0)]), 3630);
+
+        let node = &history.nodes["antnode1"];
+        assert_eq!(
+            node.values(HistoryWindow::Hour, HistoryMetric::Records),
+            vec![12, 20]
+        );
+        assert_eq!(
+            node.values(HistoryWindow::Day, HistoryMetric::Records),
+            vec![20]
+        );
+        assert_eq!(
+            history
+                .fleet
+                .values(HistoryWindow::Hour, HistoryMetric::Records),
+            vec![18, 27]
+        );
+    }
+
+    #[test]
+    fn record_should_drop_samples_outside_the_window_and_stale_nodes() {
+        let start = 1_700_000_000;
+        let mut history = StatsHistory::default();
+        history.record(&stats(&[("antnode1", 1, 0), ("antnode2", 1, 0)]), start);
+        history.record(&stats(&[("antnode1", 2, 0)]), start + 2 * 60 * 60);
+
+        let node = &history.nodes["antnode1"];
+        assert_eq!(
+            node.values(HistoryWindow::Hour, HistoryMetric::Records),
+            vec![2]
+        );
+        assert_eq!(
+            node.values(HistoryWindow::Week, HistoryMetric::Records),
+            vec![1, 2]
+        );
+        assert!(history.nodes.contains_key("antnode2"));
+
+        history.record(&stats(&[("antnode1", 3, 0)]), start + 8 * 24 * 60 * 60);
+        assert!(!history.nodes.contains_key("antnode2"));
+    }
+
+    #[test]
+    fn bandwidth_should_be_the_rate_between_samples() {
+        let start = 1_700_000_000;
+        let mut history = StatsHistory::default();
+        history.record(&stats(&[("antnode1", 0, 1_000)]), start);
+        history.record(&stats(&[("antnode1", 0, 4_000)]), start + 30);
+        // The node restarted, so its total went back down.
+        history.record(&stats(&[("antnode1", 0, 100)]), start + 60);
+
+        assert_eq!(
+            history.nodes["antnode1"].values(HistoryWindow::Hour, HistoryMetric::Bandwidth),
+            vec![100, 0]
+        );
+    }
+
+    #[test]
+    fn fleet_total_should_saturate_instead_of_overflowing() {
+        let mut total = StatsSample {
+            rewards: u64::MAX - 1,
+            ..Default::default()
+        };
+        total.add(&StatsSample {
+            rewards: 10,
+            records: 3,
+            ..Default::default()
+        });
+        assert_eq!(total.rewards, u64::MAX);
+        assert_eq!(total.records, 3);
+        assert_eq!(
+            HistoryMetric::Bandwidth.format_value(u64::MAX),
+            HistoryMetric::Bandwidth.format_value(u64::MAX / 8 + 1)
+        );
+    }
+
+    #[test]
+    fn sparkline_text_should_scale_to_the_largest_value() {
+        assert_eq!(sparkline_text(&[0, 7, 14], 5), "  ▁▄█");
+        assert_eq!(sparkline_text(&[1, 2, 3, 4], 2), "▆█");
+        assert_eq!(sparkline_text(&[], 2), "  ");
+        assert_eq!(sparkline_text(&[u64::MAX / 2, u64::MAX], 2), "▄█");
+    }
+
+    #[test]
+    fn save_and_load_should_round_trip() -> Result<()> {
+        let tmp_dir = tempfile::tempdir()?;
+        let path = tmp_dir.path().join(STATS_HISTORY_FILE_NAME);
+        let mut history = StatsHistory::default();
+        history.record(&stats(&[("antnode1", 10, 0)]), 100);
+
+        history.save(&path)?;
+
+        assert_eq!(StatsHistory::load(&path), history);
+        std::fs::write(&path, "not json")?;
+        assert_eq!(StatsHistory::load(&path), StatsHistory::default());
+        Ok(())
+    }
+}